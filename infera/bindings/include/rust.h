@@ -18,6 +18,93 @@
 namespace infera {
 #endif // __cplusplus
 
+/**
+ * Status codes reported in the `status` field of `InferaInferenceResult`.
+ *
+ * The values are part of the stable ABI. New codes may be added, but existing codes keep
+ * their values and meanings. Call `infera_last_error` for the detailed message.
+ */
+enum InferaStatus {
+  /**
+   * The operation succeeded.
+   */
+  InferaStatus_Ok = 0,
+  /**
+   * An error without a more specific status code.
+   */
+  InferaStatus_Error = -1,
+  /**
+   * The named model is not loaded.
+   */
+  InferaStatus_ModelNotFound = -2,
+  /**
+   * The input's size or shape does not match what the model expects.
+   */
+  InferaStatus_InvalidShape = -3,
+  /**
+   * The inference backend failed to run the model.
+   */
+  InferaStatus_BackendError = -4,
+  /**
+   * The operation did not finish in time, such as a prediction past its timeout.
+   */
+  InferaStatus_Timeout = -5,
+  /**
+   * The request was rejected because Infera is at capacity.
+   */
+  InferaStatus_Busy = -6,
+};
+#ifndef __cplusplus
+typedef int32_t InferaStatus;
+#endif // __cplusplus
+
+/**
+ * Where `infera_last_error` and `infera_take_last_error` read the last error from, set
+ * with `infera_set_error_mode`.
+ */
+enum InferaErrorMode {
+  /**
+   * The last error of the calling thread (the default).
+   */
+  InferaErrorMode_ThreadLocal = 0,
+  /**
+   * The most recent error of any thread in the process.
+   */
+  InferaErrorMode_Global = 1,
+};
+#ifndef __cplusplus
+typedef int32_t InferaErrorMode;
+#endif // __cplusplus
+
+/**
+ * How a model was obtained when it was loaded, returned by `infera_load_model_ex`.
+ *
+ * The values are part of the stable ABI.
+ */
+enum InferaLoadOutcome {
+  /**
+   * The model was loaded from a local file.
+   */
+  InferaLoadOutcome_Local = 0,
+  /**
+   * The model was loaded from the cache without downloading it, either within the
+   * cache TTL, while offline, or because the server sent no ETag to revalidate with.
+   */
+  InferaLoadOutcome_CacheHit = 1,
+  /**
+   * The model was downloaded from its URL.
+   */
+  InferaLoadOutcome_Downloaded = 2,
+  /**
+   * The server confirmed that the cached copy is current (`304 Not Modified`), and it
+   * was loaded from the cache.
+   */
+  InferaLoadOutcome_Revalidated = 3,
+};
+#ifndef __cplusplus
+typedef int32_t InferaLoadOutcome;
+#endif // __cplusplus
+
 /**
  * A C-compatible struct that holds the result of an inference operation.
  *
@@ -43,11 +130,77 @@ typedef struct InferaInferenceResult {
    */
   uintptr_t cols;
   /**
-   * The status of the inference operation. `0` for success, `-1` for failure.
+   * The status of the inference operation. `0` for success, or a negative
+   * `InferaStatus` code classifying the failure.
    */
   int32_t status;
 } InferaInferenceResult;
 
+/**
+ * A C-compatible byte buffer that holds model outputs serialized as `f32` bytes.
+ *
+ * This struct is filled in by `infera_predict_to_blob`. The caller is responsible for
+ * freeing the bytes by passing the entire struct to `infera_free_blob`.
+ */
+typedef struct InferaBlob {
+  /**
+   * A pointer to the bytes, or null if there are none.
+   */
+  uint8_t *ptr;
+  /**
+   * The number of bytes at `ptr`.
+   */
+  uintptr_t len;
+} InferaBlob;
+
+/**
+ * A C-compatible struct that holds the result of an inference operation along with
+ * how long the model ran and how many values went in and out.
+ *
+ * This struct is returned by `infera_predict_profiled`. The caller is responsible for
+ * freeing the output by passing the inner `result` to `infera_free_result`.
+ */
+typedef struct InferaProfiledResult {
+  /**
+   * The output of the inference, as returned by `infera_predict`.
+   */
+  struct InferaInferenceResult result;
+  /**
+   * The total wall-clock duration in nanoseconds of the model runs, summed over the
+   * chunks of a batch that was split. Input conversion and output copying are not
+   * included.
+   */
+  uint64_t inference_ns;
+  /**
+   * The number of input values (`rows * cols`).
+   */
+  uintptr_t input_elements;
+  /**
+   * The number of output values (`result.len`).
+   */
+  uintptr_t output_elements;
+} InferaProfiledResult;
+
+/**
+ * The callback invoked on a worker thread when a submitted prediction finishes.
+ *
+ * The callback owns `result` and must free it with `infera_free_result`. On failure,
+ * `result.status` is a negative `InferaStatus` code and `infera_last_error()` (called
+ * from within the callback) describes the error.
+ */
+typedef void (*InferaPredictCallback)(uint64_t request_id,
+                                      struct InferaInferenceResult result,
+                                      void *user_data);
+
+/**
+ * The callback that receives log records when set with `infera_set_log_callback`.
+ *
+ * `level` is `0` (error), `1` (warn), `2` (info), or `3` (debug). `target` is the Rust
+ * module the record comes from (e.g., `infera::http`) and `message` is the formatted
+ * message. Both strings are only valid for the duration of the call.
+ */
+typedef void (*InferaLogCallback)(int32_t level, const char *target, const char *message);
+
 #ifdef __cplusplus
 extern "C" {
 #endif // __cplusplus
@@ -58,6 +211,13 @@ extern "C" {
  * If the `path` starts with "http://" or "https://", the model will be downloaded
  * and cached locally. Otherwise, it will be treated as a local file path.
  *
+ * A path or URL ending in `.infera.zip` is a model bundle: a zip archive with
+ * `model.onnx` and a `manifest.json` holding the model's `"name"` and optional
+ * `"version"`, and optionally `labels.json`, `preprocess.json`, and `features.json`. The
+ * bundle is unpacked into the cache, its feature names are attached to the model, and its
+ * manifest, labels, and preprocessing are reported by `infera_get_model_info` under
+ * `"bundle"`. An empty `name` loads the model under the manifest's name.
+ *
  * # Arguments
  *
  * * `name` - A pointer to a null-terminated C string representing the unique name for the model.
@@ -78,242 +238,2354 @@ int32_t infera_load_model(const char *name,
                           const char *path);
 
 /**
- * Unloads a model, freeing its associated resources.
+ * Loads an ONNX model like `infera_load_model` and reports how it was obtained.
+ *
+ * For a URL, this tells a cache hit apart from a download, for example to log slow loads.
+ * The outcome is also reported as `"load_outcome"` by `infera_get_model_info`.
  *
  * # Arguments
  *
- * * `name` - A pointer to a null-terminated C string representing the name of the model to unload.
+ * * `name` - A pointer to a null-terminated C string representing the unique name for the model.
+ * * `path` - A pointer to a null-terminated C string representing the file path or URL of the model.
+ *
+ * # Returns
+ *
+ * * An `InferaLoadOutcome` value on success: `0` for a local file, `1` for a cache hit
+ *   without contacting the server, `2` for a download, or `3` for a cached copy the
+ *   server confirmed with `304 Not Modified`.
+ * * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
+ *
+ * # Safety
+ *
+ * * The `name` and `path` pointers must not be null.
+ * * The memory pointed to by `name` and `path` must be valid, null-terminated C strings.
+ */
+
+int32_t infera_load_model_ex(const char *name,
+                             const char *path);
+
+/**
+ * Loads an ONNX model like `infera_load_model`, with additional load options.
+ *
+ * The options are given as a JSON object. Supported keys:
+ *
+ * * `"pad_batch"` (bool): For models with a fixed batch dimension, pad inputs with
+ *   fewer rows up to the fixed batch and split inputs with more rows into several runs.
+ *   The output is truncated back to the caller's row count. Defaults to `false`.
+ * * `"pad_mode"` (string): `"zeros"` (default) or `"repeat_last"`, selecting how the
+ *   padding rows are filled.
+ * * `"extra_outputs"` (array of strings): Names of internal nodes to expose as additional
+ *   outputs, returned by `infera_predict_all`. Unknown names fail the load.
+ * * `"cache_ttl_secs"` (integer or `"infinite"`): For models loaded from a URL, overrides
+ *   `INFERA_CACHE_TTL_SECS` for this load.
+ * * `"versioning"` (bool): Overrides `INFERA_MODEL_VERSIONING` for this load. When set,
+ *   a model already loaded under the name is kept as a previous version (see
+ *   `infera_rollback_model`) instead of being dropped.
+ * * `"initializers"` (object): Constant values for graph inputs that have no initializer,
+ *   such as weights a model takes as inputs, keyed by input name. Each value is an array
+ *   of numbers in the input's declared shape, or an object with a `"shape"` and
+ *   `"values"` for inputs with dynamic dimensions. Bound inputs are no longer inputs of
+ *   the loaded model and are listed under `defaulted_inputs` by `infera_get_model_info`.
+ * * `"load_retries"` (integer): The number of times loading a local model file is retried
+ *   after a transient I/O error or while the file is still being written. Overrides
+ *   `INFERA_LOAD_RETRY_ATTEMPTS`, which counts the first attempt too.
+ *
+ * # Arguments
+ *
+ * * `name` - A pointer to a null-terminated C string representing the unique name for the model.
+ * * `path` - A pointer to a null-terminated C string representing the file path or URL of the model.
+ * * `options_json` - A pointer to a null-terminated C string containing the JSON options,
+ *   or null to use the defaults.
  *
  * # Returns
  *
  * * `0` on success.
- * * `-1` if the model was not found or an error occurred.
+ * * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
  *
  * # Safety
  *
- * * The `name` pointer must not be null.
- * * The memory pointed to by `name` must be a valid, null-terminated C string.
+ * * The `name` and `path` pointers must not be null.
+ * * All non-null pointers must point to valid, null-terminated C strings.
  */
- int32_t infera_unload_model(const char *name);
+int32_t infera_load_model_with_options(const char *name,
+                                       const char *path,
+                                       const char *options_json);
 
 /**
- * Runs inference on a loaded model with the given input data.
+ * Loads an ONNX model like `infera_load_model`, after checking that the model file has
+ * the expected SHA-256 checksum.
  *
- * The input data is provided as a raw pointer to a flat array of `f32` values.
- * The result of the inference is returned in an `InferaInferenceResult` struct.
- * The caller is responsible for freeing the result using `infera_free_result`.
+ * For a URL, a download whose checksum does not match is deleted instead of being
+ * cached, and a cached copy whose checksum does not match is downloaded again. Local
+ * files are checked before they are loaded.
  *
  * # Arguments
  *
- * * `model_name` - A pointer to a null-terminated C string for the model's name.
- * * `data` - A pointer to the input tensor data, organized as a flat array of `f32`.
- * * `rows` - The number of rows in the input tensor.
- * * `cols` - The number of columns in the input tensor.
+ * * `name` - A pointer to a null-terminated C string representing the unique name for the model.
+ * * `path` - A pointer to a null-terminated C string representing the file path or URL of the model.
+ * * `expected_sha256` - A pointer to a null-terminated C string containing the expected
+ *   SHA-256 of the model file as 64 hexadecimal characters (case-insensitive).
  *
  * # Returns
  *
- * An `InferaInferenceResult` struct containing the output tensor data and metadata.
- * If an error occurs, the `status` field of the struct will be `-1`.
+ * * `0` on success.
+ * * `-1` on failure, including a checksum mismatch. Call `infera_last_error()` to get a
+ *   descriptive error message.
  *
  * # Safety
  *
- * * `model_name` and `data` must not be null.
- * * `model_name` must point to a valid, null-terminated C string.
- * * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+ * * The `name`, `path`, and `expected_sha256` pointers must not be null.
+ * * The memory pointed to by them must be valid, null-terminated C strings.
  */
-
-struct InferaInferenceResult infera_predict(const char *model_name,
-                                            const float *data,
-                                            uintptr_t rows,
-                                            uintptr_t cols);
+int32_t infera_load_model_verified(const char *name,
+                                   const char *path,
+                                   const char *expected_sha256);
 
 /**
- * Runs inference on a loaded model with input data from a raw byte `BLOB`.
+ * Loads an ONNX model like `infera_load_model`, into the given namespace.
  *
- * This function is useful when the input tensor is stored as a `BLOB`. The byte data
- * is interpreted as a flat array of `f32` values (native-endian). The function
- * will attempt to infer the batch size based on the model's expected input shape.
+ * The model is stored as `ns/name`. It is only listed by
+ * `infera_get_loaded_models_ns` for its namespace, and other functions find it either by
+ * its qualified name or by `name` on a thread whose default namespace is `ns`.
  *
  * # Arguments
  *
- * * `model_name` - A pointer to a null-terminated C string for the model's name.
- * * `blob_data` - A pointer to the input data as a raw byte array.
- * * `blob_len` - The total length of the byte array in `blob_data`.
+ * * `ns` - A pointer to a null-terminated C string naming the namespace. It must
+ *   be non-empty and must not contain `/`.
+ * * `name` - A pointer to a null-terminated C string representing the name of the model
+ *   within the namespace. It must be non-empty and must not contain `/`.
+ * * `path` - A pointer to a null-terminated C string representing the file path or URL of the model.
  *
  * # Returns
  *
- * An `InferaInferenceResult` struct containing the output. The caller is responsible
- * for freeing this result using `infera_free_result`.
+ * * `0` on success.
+ * * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
  *
  * # Safety
  *
- * * `model_name` and `blob_data` must not be null.
- * * `model_name` must point to a valid, null-terminated C string.
- * * `blob_data` must point to a contiguous block of memory of size `blob_len`.
- * * `blob_len` must be a multiple of `std::mem::size_of::<f32>()`.
+ * * The `ns`, `name`, and `path` pointers must not be null.
+ * * The memory they point to must be valid, null-terminated C strings.
  */
 
-struct InferaInferenceResult infera_predict_from_blob(const char *model_name,
-                                                      const uint8_t *blob_data,
-                                                      uintptr_t blob_len);
+int32_t infera_load_model_ns(const char *ns,
+                             const char *name,
+                             const char *path);
 
+#if defined(INFERA_UNIX)
 /**
- * Retrieves metadata about a specific loaded model as a JSON string.
+ * Loads an ONNX model from an open file descriptor and assigns it a unique name.
  *
- * The returned JSON string includes the model's name, and its input and output shapes.
+ * This is for sandboxed hosts that hand over pre-opened file descriptors instead of
+ * paths. The descriptor is borrowed: it is read through a duplicate and is neither
+ * closed nor owned by Infera. A seekable descriptor (such as a regular file) is read
+ * from its start; one that cannot seek (such as a pipe) is read from its current
+ * position, so it must deliver the whole model and then reach end-of-file (for
+ * example, when the writer closes its end). The model is reported with the path `fd:<fd>`, and no detached
+ * signature or `<model>.features.json` file is looked for.
  *
  * # Arguments
  *
- * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `name` - A pointer to a null-terminated C string representing the unique name for the model.
+ * * `fd` - An open file descriptor to read the ONNX model bytes from.
  *
  * # Returns
  *
- * A pointer to a heap-allocated, null-terminated C string containing JSON.
- * The caller is responsible for freeing this string using `infera_free`.
- * On error (e.g., model not found), the JSON will contain an "error" key.
+ * * `0` on success.
+ * * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
  *
  * # Safety
  *
- * * The `model_name` pointer must not be null and must point to a valid C string.
- * * The returned pointer must be freed with `infera_free` to avoid memory leaks.
+ * * The `name` pointer must not be null.
+ * * The memory pointed to by `name` must be a valid, null-terminated C string.
+ * * `fd` must be an open file descriptor for the duration of the call.
  */
- char *infera_get_model_info(const char *model_name);
+int32_t infera_load_model_from_fd(const char *name, int32_t fd);
+#endif
 
 /**
- * Returns a JSON array of the names of all currently loaded models.
+ * Downloads a model from a URL to a file, without caching or loading it.
+ *
+ * The file is written next to `dest_path` and renamed over it once the download is
+ * complete, so an existing file at `dest_path` is only replaced by a complete download.
+ * Failed attempts are retried like cached downloads, and `INFERA_ALLOWED_HOSTS`,
+ * `INFERA_HOST_OVERRIDES`, and the HTTP timeouts apply, but the managed cache is not
+ * touched.
+ *
+ * # Arguments
+ *
+ * * `url` - A pointer to a null-terminated C string for the model's URL.
+ * * `dest_path` - A pointer to a null-terminated C string for the file to write.
  *
  * # Returns
  *
- * A pointer to a heap-allocated, null-terminated C string containing a JSON array of strings.
- * The caller is responsible for freeing this string using `infera_free`.
+ * * `0` on success.
+ * * `-1` on failure (e.g., Infera is offline, the download fails, or `dest_path` cannot
+ *   be written). Call `infera_last_error()` to get a descriptive error message.
  *
  * # Safety
  *
- * The returned pointer must be freed with `infera_free` to avoid memory leaks.
+ * * The `url` and `dest_path` pointers must not be null.
+ * * The memory pointed to by `url` and `dest_path` must be valid, null-terminated C strings.
  */
- char *infera_get_loaded_models(void);
+
+int32_t infera_download_model(const char *url, const char *dest_path);
 
 /**
- * Returns a JSON string with version and build information about the Infera library.
+ * Checks whether the remote copy of a model loaded from a URL has changed.
  *
- * The JSON object includes the library version, the enabled ONNX backend (e.g., "tract"),
- * and the directory used for caching remote models.
+ * A conditional request is sent with the ETag of the downloaded copy. Nothing is
+ * downloaded and the loaded model is not reloaded.
+ *
+ * # Arguments
+ *
+ * * `name` - A pointer to a null-terminated C string for the model's name.
  *
  * # Returns
  *
- * A pointer to a heap-allocated, null-terminated C string containing the version info.
- * The caller is responsible for freeing this string using `infera_free`.
+ * * `1` if the remote model has changed since it was downloaded.
+ * * `0` if it has not changed.
+ * * `-1` on failure (e.g., the model was loaded from a local file, the server sent no
+ *   ETag, or the request failed). Call `infera_last_error()` to get a descriptive error
+ *   message.
  *
  * # Safety
  *
- * The returned pointer must be freed with `infera_free` to avoid memory leaks.
+ * * The `name` pointer must not be null.
+ * * The memory pointed to by `name` must be a valid, null-terminated C string.
  */
- char *infera_get_version(void);
+
+int32_t infera_check_model_updated(const char *name);
 
 /**
- * Clears the entire model cache directory.
+ * Unloads a model, freeing its associated resources.
  *
- * This removes all cached remote models, freeing up disk space.
+ * # Arguments
+ *
+ * * `name` - A pointer to a null-terminated C string representing the name of the model to unload.
  *
  * # Returns
  *
  * * `0` on success.
- * * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
+ * * `-1` if the model was not found or an error occurred.
  *
  * # Safety
  *
- * This function is safe to call at any time.
+ * * The `name` pointer must not be null.
+ * * The memory pointed to by `name` must be a valid, null-terminated C string.
  */
- int32_t infera_clear_cache(void);
+ int32_t infera_unload_model(const char *name);
 
 /**
- * Returns cache statistics as a JSON string.
+ * Renames a loaded model without recompiling it.
  *
- * The JSON object includes:
- * * `"cache_dir"`: The path to the cache directory.
- * * `"total_size_bytes"`: Total size of cached models in bytes.
- * * `"file_count"`: Number of cached model files.
- * * `"size_limit_bytes"`: The configured cache size limit.
+ * # Arguments
+ *
+ * * `old_name` - A pointer to a null-terminated C string with the model's current name.
+ * * `new_name` - A pointer to a null-terminated C string with the model's new name.
+ * * `replace` - If `true`, a model already loaded under `new_name` is unloaded and
+ *   replaced. If `false`, the rename fails when `new_name` is taken.
  *
  * # Returns
  *
- * A pointer to a heap-allocated, null-terminated C string containing the cache info.
- * The caller is responsible for freeing this string using `infera_free`.
+ * * `0` on success.
+ * * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
  *
  * # Safety
  *
- * The returned pointer must be freed with `infera_free` to avoid memory leaks.
+ * * The `old_name` and `new_name` pointers must not be null.
+ * * The memory pointed to by `old_name` and `new_name` must be valid, null-terminated C strings.
  */
- char *infera_get_cache_info(void);
+
+int32_t infera_rename_model(const char *old_name, const char *new_name, bool replace);
 
 /**
- * Scans a directory for `.onnx` files and loads them into Infera automatically.
+ * Returns a JSON array of the current and previous versions of a model, newest first.
  *
- * The name for each model is derived from its filename (without the extension).
+ * Previous versions are kept when a model is loaded under a taken name with versioning
+ * enabled (`INFERA_MODEL_VERSIONING` or the `"versioning"` load option), up to
+ * `INFERA_MODEL_HISTORY_DEPTH` versions per name. Each entry has its `index` (0 for the
+ * current version, 1 for the version `infera_rollback_model` would restore, and so on),
+ * whether it is `current`, the `source` it was loaded from (the URL for remote models,
+ * otherwise the file path), and `loaded_at` (seconds since the Unix epoch).
  *
  * # Arguments
  *
- * * `path` - A pointer to a null-terminated C string representing the directory path.
+ * * `name` - A pointer to a null-terminated C string for the model's name.
  *
  * # Returns
  *
- * A pointer to a heap-allocated C string containing a JSON object with two fields:
- * * `"loaded"`: A list of model names that were successfully loaded.
- * * `"errors"`: A list of objects, each detailing a file that failed to load and the reason.
- *
- * The caller is responsible for freeing this string using `infera_free`.
+ * A pointer to a heap-allocated, null-terminated C string containing JSON.
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ * If the model is not loaded, the JSON will be an object with an "error" key.
  *
  * # Safety
  *
- * * The `path` pointer must not be null and must point to a valid C string.
- * * The returned pointer must be freed with `infera_free` to avoid memory leaks.
+ * * The `name` pointer must not be null and must point to a valid C string.
+ * * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
  */
- char *infera_set_autoload_dir(const char *path);
+char *infera_list_model_versions(const char *name);
 
 /**
- * Retrieves the last error message set in the current thread.
+ * Makes the previous version of a model current again.
  *
- * After an FFI function returns an error code, this function can be called
- * to get a more descriptive, human-readable error message.
+ * The current version is dropped, and predictions that start after the call run against
+ * the restored version. The swap is atomic, so no prediction ever sees the model missing.
+ *
+ * # Arguments
+ *
+ * * `name` - A pointer to a null-terminated C string for the model's name.
  *
  * # Returns
  *
- * A pointer to a null-terminated C string containing the last error message.
- * Returns a null pointer if no error has occurred since the last call.
- * The caller **must not** free this pointer, as it is managed by a thread-local static variable.
+ * * `0` on success.
+ * * `-1` if the model has no previous version. Call `infera_last_error()` to get a
+ *   descriptive error message.
+ *
+ * # Safety
+ *
+ * The `name` pointer must not be null and must point to a valid C string.
  */
- const char *infera_last_error(void);
+int32_t infera_rollback_model(const char *name);
 
 /**
- * Frees a heap-allocated C string that was returned by an Infera FFI function.
+ * Drops all but the `keep` newest previous versions of a model to free their memory.
  *
- * This function should be used to free the memory for strings returned by functions
- * like `infera_get_model_info`, `infera_get_loaded_models`, and `infera_get_version`.
+ * The current version is never dropped. Pass `0` to drop every previous version.
+ *
+ * # Arguments
+ *
+ * * `name` - A pointer to a null-terminated C string for the model's name.
+ * * `keep` - The number of previous versions to keep.
+ *
+ * # Returns
+ *
+ * * The number of versions dropped on success.
+ * * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
  *
  * # Safety
  *
- * The `ptr` must be a non-null pointer to a C string that was previously allocated
- * by Rust's `CString::into_raw`. Passing any other pointer (e.g., a string literal,
- * a pointer from a different allocator, or a null pointer) will result in undefined behavior.
+ * The `name` pointer must not be null and must point to a valid C string.
  */
- void infera_free(char *ptr);
+int32_t infera_prune_model_versions(const char *name, uintptr_t keep);
 
 /**
- * Frees the data buffer within an `InferaInferenceResult`.
+ * Points an alias at a candidate model, falling back to a known-good model if the
+ * candidate starts failing.
+ *
+ * Predictions through the alias (with `infera_predict` and the other prediction
+ * functions, including `infera_submit`) go to the candidate. Each one is recorded in a
+ * sliding window of the last `window` candidate predictions, counting inference errors
+ * and, with `INFERA_CANARY_COUNT_NON_FINITE`, outputs containing NaN or infinite values
+ * as failures. Once the window is full and more than `max_error_rate` of it failed, the
+ * alias is atomically re-pointed to the fallback, an `ERROR` is logged, and the trip is
+ * recorded for `infera_get_canary_status`. Setting an alias that is already a canary
+ * replaces it and resets its statistics.
  *
- * This function must be called on every `InferaInferenceResult` returned from
- * `infera_predict` or `infera_predict_from_blob` to prevent memory leaks.
+ * # Arguments
+ *
+ * * `alias` - A pointer to a null-terminated C string for the alias. It must not be the
+ *   name of a loaded model.
+ * * `candidate` - A pointer to a null-terminated C string naming the loaded model under test.
+ * * `fallback` - A pointer to a null-terminated C string naming the loaded model to fall
+ *   back to.
+ * * `max_error_rate` - The highest tolerated fraction of failed predictions, from 0 to 1.
+ * * `window` - The number of most recent candidate predictions the error rate is computed
+ *   over. Must be greater than zero.
+ *
+ * # Returns
+ *
+ * * `0` on success.
+ * * `-1` on failure (e.g., a model is not loaded or the policy is invalid). Call
+ *   `infera_last_error()` to get a descriptive error message.
  *
  * # Safety
  *
- * * The `res` struct must be a value that was returned by an Infera prediction function.
- * * The `res.data` pointer must have been allocated by Rust's `Vec<f32>` and not yet freed.
- * * The `res.len` field must accurately represent the capacity of the allocated buffer.
+ * * The `alias`, `candidate`, and `fallback` pointers must not be null.
+ * * The memory they point to must be valid, null-terminated C strings.
+ */
+int32_t infera_set_canary(const char *alias,
+                          const char *candidate,
+                          const char *fallback,
+                          double max_error_rate,
+                          uintptr_t window);
+
+/**
+ * Finalizes a canary by making its candidate the permanent target of the alias.
  *
- * Calling this function on a manually-created struct or calling it more than once
- * on the same result will lead to undefined behavior.
+ * Errors of the candidate are no longer tracked, so the alias never falls back
+ * afterwards. Promoting a canary that has tripped points the alias back at the candidate.
+ *
+ * # Arguments
+ *
+ * * `alias` - A pointer to a null-terminated C string for the alias.
+ *
+ * # Returns
+ *
+ * * `0` on success.
+ * * `-1` if the alias is not a canary. Call `infera_last_error()` to get a descriptive
+ *   error message.
+ *
+ * # Safety
+ *
+ * * The `alias` pointer must not be null.
+ * * The memory pointed to by `alias` must be a valid, null-terminated C string.
  */
- void infera_free_result(struct InferaInferenceResult res);
+int32_t infera_promote_canary(const char *alias);
+
+/**
+ * Returns the state and statistics of a canary alias as a JSON object.
+ *
+ * The object has the `alias`, `candidate`, and `fallback` names, the `target`
+ * predictions currently go to, the `state` (`"canary"`, `"tripped"`, or `"promoted"`),
+ * the policy (`max_error_rate` and `window`), the current window (`window_requests` and
+ * `window_error_rate`), the totals since the canary was set (`requests` and `errors`),
+ * and, once it has tripped, `tripped_at` (seconds since the Unix epoch) and
+ * `trip_error_rate`.
+ *
+ * # Arguments
+ *
+ * * `alias` - A pointer to a null-terminated C string for the alias.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated, null-terminated C string containing JSON.
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ * If the alias is not a canary, the JSON will be an object with an "error" key.
+ *
+ * # Safety
+ *
+ * * The `alias` pointer must not be null and must point to a valid C string.
+ * * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+char *infera_get_canary_status(const char *alias);
+
+/**
+ * Limits how many predictions can run on a model at once.
+ *
+ * Predictions on the model (with `infera_predict` and the other prediction functions,
+ * including those through a canary alias and `infera_submit`) each hold one of `max`
+ * permits while they run. When all permits are taken, a prediction waits for one to be
+ * released, or fails with status `Busy` if `INFERA_CONCURRENCY_BLOCK` is disabled. This
+ * keeps a heavy model from running on more threads than the CPU caches can serve.
+ * Changing the limit applies to predictions that have not started yet, and the limit
+ * stays in place if the model is reloaded under the same name. `infera_get_model_info`
+ * reports it as `max_concurrency`.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the loaded model's name.
+ * * `max` - The maximum number of concurrent predictions, or `0` to remove the limit.
+ *
+ * # Returns
+ *
+ * * `0` on success.
+ * * `-1` on failure (e.g., the model is not loaded). Call `infera_last_error()` to get a
+ *   descriptive error message.
+ *
+ * # Safety
+ *
+ * * The `model_name` pointer must not be null.
+ * * The memory pointed to by `model_name` must be a valid, null-terminated C string.
+ */
+int32_t infera_set_model_concurrency(const char *model_name, uintptr_t max);
+
+/**
+ * Attaches postprocessing to a model's output, such as clamping scores to `[0, 1]` and
+ * rounding them, so it does not have to be done per row in the caller.
+ *
+ * `spec_json` is a JSON object with any of these steps, which run in this order
+ * regardless of their order in the object:
+ *
+ * * `scale` and `offset` - Multiply every value by `scale`, then add `offset`.
+ * * `activation` - Apply `"sigmoid"` to every value, or `"softmax"` to each output row.
+ * * `clip_min` and `clip_max` - Clamp every value to the range.
+ * * `round` - Round every value to the given number of decimals (0 to 10).
+ *
+ * The steps run on the model's first output, before it is returned, in `infera_predict`
+ * and the other prediction functions that return it as floats or bytes, including
+ * streams. Outputs returned as JSON are not postprocessed. An empty string or object
+ * detaches the postprocessing. Predictions that have already started finish with the
+ * postprocessing they started with. Loading a model under the same name drops it, and
+ * `infera_get_model_info` reports it as `postprocessor`.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the loaded model's name.
+ * * `spec_json` - A pointer to a null-terminated C string with the postprocessing spec.
+ *
+ * # Returns
+ *
+ * * `0` on success.
+ * * `-1` on failure (e.g., the model is not loaded or the spec is invalid). Call
+ *   `infera_last_error()` to get a descriptive error message.
+ *
+ * # Safety
+ *
+ * * The `model_name` and `spec_json` pointers must not be null.
+ * * The memory they point to must be valid, null-terminated C strings.
+ */
+int32_t infera_set_postprocessor(const char *model_name, const char *spec_json);
+
+/**
+ * Runs inference on a loaded model with the given input data.
+ *
+ * The input data is provided as a raw pointer to a flat array of `f32` values.
+ * The result of the inference is returned in an `InferaInferenceResult` struct.
+ * The caller is responsible for freeing the result using `infera_free_result`.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `data` - A pointer to the input tensor data, organized as a flat array of `f32`.
+ * * `rows` - The number of rows in the input tensor.
+ * * `cols` - The number of columns in the input tensor.
+ *
+ * A model that takes no inputs (a generator graph) is run by passing `rows = cols = 0`,
+ * in which case `data` may be null.
+ *
+ * # Returns
+ *
+ * An `InferaInferenceResult` struct containing the output tensor data and metadata.
+ * If an error occurs, the `status` field of the struct is a negative `InferaStatus` code
+ * (e.g., `-2` if the model is not loaded or `-3` for an input shape mismatch).
+ *
+ * # Safety
+ *
+ * * `model_name` must not be null, and `data` must not be null unless `rows` and `cols` are 0.
+ * * `model_name` must point to a valid, null-terminated C string.
+ * * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+ */
+
+struct InferaInferenceResult infera_predict(const char *model_name,
+                                            const float *data,
+                                            uintptr_t rows,
+                                            uintptr_t cols);
+
+/**
+ * Runs inference like `infera_predict`, with the model's random ops seeded so that
+ * repeated calls with the same `seed` produce identical output.
+ *
+ * This makes models with `RandomNormal`, `RandomUniform`, `RandomNormalLike`, or
+ * `RandomUniformLike` nodes reproducible, for example in golden tests. `Dropout` is a
+ * no-op at inference time and needs no seed. Other models give the same output as with
+ * `infera_predict`. Models with random ops are recompiled on each call, so seeded
+ * predictions are slower for them. The caller is responsible for freeing the result
+ * using `infera_free_result`.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `data` - A pointer to the input tensor data, organized as a flat array of `f32`.
+ * * `rows` - The number of rows in the input tensor.
+ * * `cols` - The number of columns in the input tensor.
+ * * `seed` - The seed for the model's random number generators.
+ *
+ * # Returns
+ *
+ * An `InferaInferenceResult` struct containing the output tensor data and metadata.
+ * If an error occurs, the `status` field of the struct is a negative `InferaStatus` code.
+ *
+ * # Safety
+ *
+ * * `model_name` and `data` must not be null.
+ * * `model_name` must point to a valid, null-terminated C string.
+ * * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+ */
+struct InferaInferenceResult infera_predict_seeded(const char *model_name,
+                                                   const float *data,
+                                                   uintptr_t rows,
+                                                   uintptr_t cols,
+                                                   uint64_t seed);
+
+/**
+ * Runs inference like `infera_predict`, also returning how long the model ran and how
+ * many values went in and out, for observability.
+ *
+ * `inference_ns` is the wall-clock duration of the model runs, summed over the chunks of
+ * a batch split by `INFERA_BATCH_CHUNK_ROWS`. It does not include converting the input or
+ * copying the output. The caller is responsible for freeing the output by passing the
+ * inner `result` to `infera_free_result`.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `data` - A pointer to the input tensor data, organized as a flat array of `f32`.
+ * * `rows` - The number of rows in the input tensor.
+ * * `cols` - The number of columns in the input tensor.
+ *
+ * # Returns
+ *
+ * An `InferaProfiledResult` struct with the output in `result`, `inference_ns`,
+ * `input_elements` (`rows * cols`), and `output_elements` (`result.len`). If an error
+ * occurs, `result.status` is a negative `InferaStatus` code and the other fields are 0.
+ *
+ * # Safety
+ *
+ * * `model_name` must not be null, and `data` must not be null unless `rows` and `cols` are 0.
+ * * `model_name` must point to a valid, null-terminated C string.
+ * * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+ */
+struct InferaProfiledResult infera_predict_profiled(const char *model_name,
+                                                    const float *data,
+                                                    uintptr_t rows,
+                                                    uintptr_t cols);
+
+/**
+ * Queues a prediction to run on a background worker thread.
+ *
+ * The input is copied before this function returns, so the caller may free `data`
+ * immediately. When the prediction finishes, `callback` is invoked on a worker thread
+ * with the request ID, the result, and `user_data`. The callback owns the result and
+ * must free it with `infera_free_result`. If the prediction fails, `result.status` is a
+ * negative `InferaStatus` code and calling `infera_last_error()` from within the callback
+ * returns the error.
+ *
+ * Requests are served by `INFERA_WORKER_THREADS` workers from priority queues holding
+ * at most `INFERA_MAX_PENDING` requests in total. When the queues are full, submission
+ * fails immediately. Higher priority requests run first, but a normal or low priority
+ * request that has waited `INFERA_QUEUE_AGING_MS` runs ahead of them.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `data` - A pointer to the input tensor data, organized as a flat array of `f32`.
+ * * `rows` - The number of rows in the input tensor.
+ * * `cols` - The number of columns in the input tensor.
+ * * `priority` - `0` (high), `1` (normal), or `2` (low).
+ * * `callback` - The function to call with the result.
+ * * `user_data` - An opaque pointer passed back to `callback` as is.
+ *
+ * # Returns
+ *
+ * * A non-zero request ID on success.
+ * * `0` on failure (for example, when the queue is full). Call `infera_last_error()`
+ *   to get a descriptive error message.
+ *
+ * # Safety
+ *
+ * * `model_name`, `data`, and `callback` must not be null.
+ * * `model_name` must point to a valid, null-terminated C string.
+ * * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+ * * `user_data` must remain valid, and be safe to use from another thread, until the
+ *   callback has run.
+ */
+
+uint64_t infera_submit(const char *model_name,
+                       const float *data,
+                       uintptr_t rows,
+                       uintptr_t cols,
+                       int32_t priority,
+                       InferaPredictCallback callback,
+                       void *user_data);
+
+/**
+ * Cancels a prediction queued with `infera_submit` that has not started running yet.
+ *
+ * A cancelled request's callback is never called, so the caller can release any
+ * resources tied to its `user_data` once this function succeeds.
+ *
+ * # Arguments
+ *
+ * * `request_id` - The ID returned by `infera_submit`.
+ *
+ * # Returns
+ *
+ * * `0` on success.
+ * * `-1` if the request is not queued (it may be running, finished, or already
+ *   cancelled). Call `infera_last_error()` to get a descriptive error message.
+ *
+ * # Safety
+ *
+ * This function is safe to call at any time.
+ */
+
+ int32_t infera_cancel(uint64_t request_id);
+
+/**
+ * Changes the priority of a prediction queued with `infera_submit`.
+ *
+ * The request keeps its original submission time, so it is ordered among requests of
+ * the new priority by when it was submitted.
+ *
+ * # Arguments
+ *
+ * * `request_id` - The ID returned by `infera_submit`.
+ * * `priority` - `0` (high), `1` (normal), or `2` (low).
+ *
+ * # Returns
+ *
+ * * `0` on success.
+ * * `-1` if the priority is invalid or the request is not queued. Call
+ *   `infera_last_error()` to get a descriptive error message.
+ *
+ * # Safety
+ *
+ * This function is safe to call at any time.
+ */
+
+ int32_t infera_set_priority(uint64_t request_id, int32_t priority);
+
+/**
+ * Returns runtime metrics as a JSON string.
+ *
+ * The JSON object has a `"queue"` key with one entry per priority (`"high"`,
+ * `"normal"`, and `"low"`) for requests submitted with `infera_submit`. Each entry holds
+ * the current queue `depth` and a `wait_ms` histogram of how long requests waited before
+ * running, with cumulative bucket `count`s for each upper bound `le` (in milliseconds).
+ * The `"cache"` key holds the cache activity counters described in `infera_get_cache_info`.
+ * The `"autoload"` key lists the `watched_dirs` of `infera_watch_autoload_dir` and counts
+ * the `scans` of watched directories and the models they `loaded`, `reloaded`, and
+ * `unloaded`, and the files that `failed` to load.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated, null-terminated C string containing the metrics.
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ *
+ * # Safety
+ *
+ * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+char *infera_get_metrics(void);
+
+/**
+ * Runs inference on a loaded model with half-precision (f16) input data.
+ *
+ * The input is a flat array of raw IEEE 754 binary16 values, one `u16` per value. It
+ * is fed natively to models with f16 inputs and widened to f32 for other models. The
+ * output is always returned as f32 in the `InferaInferenceResult`.
+ * The caller is responsible for freeing the result using `infera_free_result`.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `data` - A pointer to the input tensor data, organized as a flat array of f16 bit patterns.
+ * * `rows` - The number of rows in the input tensor.
+ * * `cols` - The number of columns in the input tensor.
+ *
+ * # Returns
+ *
+ * An `InferaInferenceResult` struct containing the output tensor data and metadata.
+ * If an error occurs, the `status` field of the struct is a negative `InferaStatus` code
+ * (e.g., `-2` if the model is not loaded or `-3` for an input shape mismatch).
+ *
+ * # Safety
+ *
+ * * `model_name` and `data` must not be null.
+ * * `model_name` must point to a valid, null-terminated C string.
+ * * `data` must point to a contiguous block of memory of size `rows * cols * size_of<u16>()`.
+ */
+
+struct InferaInferenceResult infera_predict_f16(const char *model_name,
+                                                const uint16_t *data,
+                                                uintptr_t rows,
+                                                uintptr_t cols);
+
+/**
+ * Runs inference on a loaded model with `u8` input data.
+ *
+ * This is the entry point for quantized models whose input tensor is `u8` (or `i8`,
+ * in which case each byte is reinterpreted as a two's complement value). For models
+ * with other input types, the bytes are converted to the model's input type.
+ * The caller is responsible for freeing the result using `infera_free_result`.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `data` - A pointer to the input tensor data, organized as a flat array of `u8`.
+ * * `rows` - The number of rows in the input tensor.
+ * * `cols` - The number of columns in the input tensor.
+ *
+ * # Returns
+ *
+ * An `InferaInferenceResult` struct containing the output tensor data and metadata.
+ * If an error occurs, the `status` field of the struct is a negative `InferaStatus` code
+ * (e.g., `-2` if the model is not loaded or `-3` for an input shape mismatch).
+ *
+ * # Safety
+ *
+ * * `model_name` and `data` must not be null.
+ * * `model_name` must point to a valid, null-terminated C string.
+ * * `data` must point to a contiguous block of memory of size `rows * cols`.
+ */
+
+struct InferaInferenceResult infera_predict_u8(const char *model_name,
+                                               const uint8_t *data,
+                                               uintptr_t rows,
+                                               uintptr_t cols);
+
+/**
+ * Runs inference on a loaded model whose input has named symbolic dimensions.
+ *
+ * Models with symbolic dimensions other than the batch (e.g., a sequence length `S` in
+ * an `[N, S, F]` input) need those dimensions bound before the flat `rows x cols` input
+ * can be given its shape. `symbols_json` maps symbol names to values (for example,
+ * `{"S": 128}`), and one remaining dynamic dimension is inferred from the input size.
+ * The symbol names of a model's input are listed under `input_symbols` by
+ * `infera_get_model_info`. The caller is responsible for freeing the result using
+ * `infera_free_result`.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `data` - A pointer to the input tensor data, organized as a flat array of `f32`.
+ * * `rows` - The number of rows in the input tensor (the first input dimension).
+ * * `cols` - The number of columns in the input tensor (all other dimensions flattened).
+ * * `symbols_json` - A pointer to a null-terminated C string containing a JSON object
+ *   that maps symbol names to positive integers, or null to bind no symbols.
+ *
+ * # Returns
+ *
+ * An `InferaInferenceResult` struct containing the output tensor data and metadata.
+ * If an error occurs, the `status` field of the struct is a negative `InferaStatus` code
+ * (e.g., `-3` if the bound shape does not match `rows x cols`).
+ *
+ * # Safety
+ *
+ * * `model_name` and `data` must not be null.
+ * * `model_name` and `symbols_json` (if not null) must point to valid, null-terminated
+ *   C strings.
+ * * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+ */
+
+struct InferaInferenceResult infera_predict_with_symbols(const char *model_name,
+                                                         const float *data,
+                                                         uintptr_t rows,
+                                                         uintptr_t cols,
+                                                         const char *symbols_json);
+
+/**
+ * Runs inference on a loaded model with input data from a raw byte `BLOB`.
+ *
+ * This function is useful when the input tensor is stored as a `BLOB`. The byte data
+ * is interpreted as a flat array of `f32` values (native-endian), or as one element
+ * per byte for models with `u8` or `i8` inputs. The function will attempt to infer
+ * the batch size based on the model's expected input shape.
+ * With `INFERA_STRICT_BLOB` set, the blob must hold exactly one sample instead; use
+ * `infera_predict_from_blob_batch` to pass more.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `blob_data` - A pointer to the input data as a raw byte array.
+ * * `blob_len` - The total length of the byte array in `blob_data`.
+ *
+ * # Returns
+ *
+ * An `InferaInferenceResult` struct containing the output. The caller is responsible
+ * for freeing this result using `infera_free_result`. If an error occurs, the `status`
+ * field of the struct is a negative `InferaStatus` code.
+ *
+ * # Safety
+ *
+ * * `model_name` and `blob_data` must not be null.
+ * * `model_name` must point to a valid, null-terminated C string.
+ * * `blob_data` must point to a contiguous block of memory of size `blob_len`.
+ * * `blob_len` must be a multiple of `std::mem::size_of::<f32>()` for `f32` models.
+ */
+
+struct InferaInferenceResult infera_predict_from_blob(const char *model_name,
+                                                      const uint8_t *blob_data,
+                                                      uintptr_t blob_len);
+
+/**
+ * Runs inference on a loaded model with a raw byte `BLOB` holding exactly `batch` samples.
+ *
+ * This works like `infera_predict_from_blob`, but instead of inferring the batch size
+ * from the blob length, the blob must hold exactly `batch` samples of the model input.
+ * A blob with trailing data that happens to fill more samples is rejected.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `blob_data` - A pointer to the input data as a raw byte array.
+ * * `blob_len` - The total length of the byte array in `blob_data`.
+ * * `batch` - The number of samples in the blob.
+ *
+ * # Returns
+ *
+ * An `InferaInferenceResult` struct containing the output. The caller is responsible
+ * for freeing this result using `infera_free_result`. If an error occurs, the `status`
+ * field of the struct is a negative `InferaStatus` code.
+ *
+ * # Safety
+ *
+ * * `model_name` and `blob_data` must not be null.
+ * * `model_name` must point to a valid, null-terminated C string.
+ * * `blob_data` must point to a contiguous block of memory of size `blob_len`.
+ */
+struct InferaInferenceResult infera_predict_from_blob_batch(const char *model_name,
+                                                            const uint8_t *blob_data,
+                                                            uintptr_t blob_len,
+                                                            uintptr_t batch);
+
+/**
+ * Runs inference like `infera_predict_from_blob`, giving up once `timeout_ms`
+ * milliseconds have passed.
+ *
+ * Blobs with more samples than `INFERA_BATCH_CHUNK_ROWS` are run in chunks (for models
+ * whose input and output batch dimensions are dynamic), and the timeout is checked
+ * before each chunk starts, so a call can take longer than `timeout_ms` by up to one
+ * chunk. A blob that is not split is checked once, before it runs. A `timeout_ms` of 0
+ * means no timeout.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `blob_data` - A pointer to the input data as a raw byte array.
+ * * `blob_len` - The total length of the byte array in `blob_data`.
+ * * `timeout_ms` - The timeout in milliseconds, or 0 for none.
+ *
+ * # Returns
+ *
+ * An `InferaInferenceResult` struct containing the output. The caller is responsible
+ * for freeing this result using `infera_free_result`. If an error occurs, the `status`
+ * field of the struct is a negative `InferaStatus` code, such as `-5` (timeout) when the
+ * deadline passes, and no output is returned.
+ *
+ * # Safety
+ *
+ * * `model_name` and `blob_data` must not be null.
+ * * `model_name` must point to a valid, null-terminated C string.
+ * * `blob_data` must point to a contiguous block of memory of size `blob_len`.
+ */
+struct InferaInferenceResult infera_predict_from_blob_timeout(const char *model_name,
+                                                             const uint8_t *blob_data,
+                                                             uintptr_t blob_len,
+                                                             uint64_t timeout_ms);
+
+/**
+ * Runs inference like `infera_predict` and returns the output as a byte `BLOB`.
+ *
+ * The output values are flattened row by row and written as little-endian `f32` bytes
+ * regardless of the host's byte order (or in the host's byte order with
+ * `native_endian`), so hosts can store them in a `BLOB` column without converting
+ * them. This mirrors `infera_predict_from_blob` on the output side. The bytes are
+ * written while the output is copied out of the model, without a separate pass. On
+ * success, the caller is responsible for freeing `*out` using `infera_free_blob`.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `data` - A pointer to the input tensor data, organized as a flat array of `f32`.
+ * * `rows` - The number of rows in the input tensor.
+ * * `cols` - The number of columns in the input tensor.
+ * * `native_endian` - Whether to write the values in the host's byte order instead of
+ *   little-endian.
+ * * `out` - Where to store the `InferaBlob` with the output bytes.
+ *
+ * # Returns
+ *
+ * * `0` on success.
+ * * A negative `InferaStatus` code on failure, with `*out` set to an empty blob (if
+ *   `out` is not null).
+ *
+ * # Safety
+ *
+ * * `model_name`, `data`, and `out` must not be null.
+ * * `model_name` must point to a valid, null-terminated C string.
+ * * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+ */
+int32_t infera_predict_to_blob(const char *model_name,
+                               const float *data,
+                               uintptr_t rows,
+                               uintptr_t cols,
+                               bool native_endian,
+                               struct InferaBlob *out);
+
+/**
+ * Begins a streaming session for running inference on a dataset one chunk at a time.
+ *
+ * Push chunks with `infera_stream_push` and end the session with `infera_stream_end`.
+ * The session holds on to the model it began with, so unloading or replacing the model
+ * does not affect it, and the model's memory is only released once the session ends.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `options_json` - A pointer to a null-terminated C string containing a JSON object of
+ *   options, or null to use the defaults. The only option is `cols`, the number of
+ *   columns every chunk must have; without it, the first chunk sets the number.
+ *
+ * # Returns
+ *
+ * * A positive handle for the session on success.
+ * * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
+ *
+ * # Safety
+ *
+ * * The `model_name` pointer must not be null.
+ * * All non-null pointers must point to valid, null-terminated C strings.
+ */
+int64_t infera_stream_begin(const char *model_name, const char *options_json);
+
+/**
+ * Runs inference on the next chunk of a streaming session and returns its outputs.
+ *
+ * Each chunk is run like `infera_predict`, and its outputs are returned right away. The
+ * session reuses its input buffer between chunks of the same size. Every chunk must have
+ * the same number of columns; a chunk that fails is not counted, and the session stays
+ * open. The caller is responsible for freeing the result using `infera_free_result`.
+ *
+ * # Arguments
+ *
+ * * `handle` - The handle returned by `infera_stream_begin`.
+ * * `data` - A pointer to the chunk's input data, organized as a flat array of `f32`.
+ * * `rows` - The number of rows in the chunk.
+ * * `cols` - The number of columns in the chunk.
+ *
+ * # Returns
+ *
+ * An `InferaInferenceResult` struct containing the chunk's output tensor data and metadata.
+ * If an error occurs, the `status` field of the struct is a negative `InferaStatus` code
+ * (e.g., `-3` if the chunk has a different number of columns than the others).
+ *
+ * # Safety
+ *
+ * * `data` must not be null.
+ * * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+ */
+struct InferaInferenceResult infera_stream_push(int64_t handle,
+                                                const float *data,
+                                                uintptr_t rows,
+                                                uintptr_t cols);
+
+/**
+ * Ends a streaming session and returns its statistics as a JSON string.
+ *
+ * The object has the session's `model` and `cols`, the number of `chunks` and
+ * `total_rows` run successfully, the time spent running them (`inference_ns`), and the
+ * time since the session began (`elapsed_ns`).
+ *
+ * # Arguments
+ *
+ * * `handle` - The handle returned by `infera_stream_begin`.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated, null-terminated C string containing JSON.
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ * If the session is not open, the JSON will be an object with an "error" key.
+ *
+ * # Safety
+ *
+ * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+char *infera_stream_end(int64_t handle);
+
+/**
+ * Runs inference on a loaded vision model with an encoded image as input.
+ *
+ * The image (PNG, JPEG, or WebP) is decoded, converted to RGB, resized to `target_w x target_h`,
+ * and passed to the model as a batch of one `f32` image. Pixel values are scaled to
+ * `[0, 1]` and can then be normalized per channel as `(value - mean) / std`.
+ * Requires the `image` feature. The caller is responsible for freeing the result using
+ * `infera_free_result`.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `image_bytes` - A pointer to the encoded image bytes.
+ * * `len` - The number of bytes in `image_bytes`.
+ * * `target_w` - The width the image is resized to.
+ * * `target_h` - The height the image is resized to.
+ * * `layout` - A null-terminated C string, `"chw"` for a `[1, 3, H, W]` input or `"hwc"`
+ *   for a `[1, H, W, 3]` input.
+ * * `normalization_json` - An optional null-terminated C string with a JSON object like
+ *   `{"mean": [0.485, 0.456, 0.406], "std": [0.229, 0.224, 0.225]}`. Pass null or an
+ *   empty string to skip normalization.
+ *
+ * # Returns
+ *
+ * An `InferaInferenceResult` struct containing the output tensor data and metadata.
+ * If an error occurs, the `status` field of the struct is a negative `InferaStatus` code
+ * (e.g., `-3` if the resized image does not match the model's input shape).
+ *
+ * # Safety
+ *
+ * * `model_name`, `image_bytes`, and `layout` must not be null.
+ * * `model_name`, `layout`, and `normalization_json` (if not null) must point to valid,
+ *   null-terminated C strings.
+ * * `image_bytes` must point to a contiguous block of memory of size `len`.
+ */
+
+struct InferaInferenceResult infera_predict_image(const char *model_name,
+                                                  const uint8_t *image_bytes,
+                                                  uintptr_t len,
+                                                  uint32_t target_w,
+                                                  uint32_t target_h,
+                                                  const char *layout,
+                                                  const char *normalization_json);
+
+/**
+ * Runs inference on a loaded vision model with an image in an explicit format.
+ *
+ * Works like `infera_predict_image`, but `format` names the format of `image_bytes`
+ * instead of it being detected. Besides encoded `"png"`, `"jpeg"`, and `"webp"` images,
+ * it accepts already decoded `"rgb"` (3 bytes per pixel) and `"rgba"` (4 bytes per pixel)
+ * pixels in row-major order, so callers that decoded an image elsewhere skip a second
+ * decode. Requires the `image` feature. The caller is responsible for freeing the result
+ * using `infera_free_result`.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `image_bytes` - A pointer to the image bytes.
+ * * `len` - The number of bytes in `image_bytes`.
+ * * `format` - A null-terminated C string, one of `"png"`, `"jpeg"`, `"webp"`, `"rgb"`,
+ *   or `"rgba"`.
+ * * `source_w` - The width of raw `"rgb"` or `"rgba"` pixels (ignored otherwise).
+ * * `source_h` - The height of raw `"rgb"` or `"rgba"` pixels (ignored otherwise).
+ * * `target_w` - The width the image is resized to.
+ * * `target_h` - The height the image is resized to.
+ * * `layout` - A null-terminated C string, `"chw"` for a `[1, 3, H, W]` input or `"hwc"`
+ *   for a `[1, H, W, 3]` input.
+ * * `normalization_json` - An optional null-terminated C string with normalization
+ *   parameters, as for `infera_predict_image`.
+ *
+ * # Returns
+ *
+ * An `InferaInferenceResult` struct containing the output tensor data and metadata.
+ * If an error occurs, the `status` field of the struct is a negative `InferaStatus` code
+ * (e.g., `-1` if raw pixels do not match `source_w x source_h`).
+ *
+ * # Safety
+ *
+ * * `model_name`, `image_bytes`, `format`, and `layout` must not be null.
+ * * `model_name`, `format`, `layout`, and `normalization_json` (if not null) must point
+ *   to valid, null-terminated C strings.
+ * * `image_bytes` must point to a contiguous block of memory of size `len`.
+ */
+struct InferaInferenceResult infera_predict_image_with_format(const char *model_name,
+                                                              const uint8_t *image_bytes,
+                                                              uintptr_t len,
+                                                              const char *format,
+                                                              uint32_t source_w,
+                                                              uint32_t source_h,
+                                                              uint32_t target_w,
+                                                              uint32_t target_h,
+                                                              const char *layout,
+                                                              const char *normalization_json);
+
+/**
+ * Runs inference and returns all model outputs, labelled by name, as a JSON string.
+ *
+ * The JSON is an array with one object per output, each holding the output's `name`,
+ * `shape`, and flattened `data`. Internal nodes exposed with the `extra_outputs` load
+ * option (see `infera_load_model_with_options`) are included after the model's own outputs.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `data` - A pointer to the input tensor data, organized as a flat array of `f32`.
+ * * `rows` - The number of rows in the input tensor.
+ * * `cols` - The number of columns in the input tensor.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated, null-terminated C string containing JSON.
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ * On error, the JSON will contain an "error" key.
+ *
+ * # Safety
+ *
+ * * `model_name` and `data` must not be null.
+ * * `model_name` must point to a valid, null-terminated C string.
+ * * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+ * * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+char *infera_predict_all(const char *model_name,
+                         const float *data,
+                         uintptr_t rows,
+                         uintptr_t cols);
+
+/**
+ * Runs inference and returns a single model output, selected by name or index.
+ *
+ * Models with several outputs (for example detection models with boxes and class
+ * logits) otherwise return their first output from `infera_predict`. The whole model
+ * still runs, but only the selected output is converted and copied out. The model's
+ * postprocessor, if any, is only applied when the first output is selected.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `data` - A pointer to the input tensor data, organized as a flat array of `f32`.
+ * * `rows` - The number of rows in the input tensor.
+ * * `cols` - The number of columns in the input tensor.
+ * * `output_selector` - A pointer to a null-terminated C string with an output name (see
+ *   `infera_get_output_names`) or, if no output has that name, a zero-based output index
+ *   such as `"1"`.
+ *
+ * # Returns
+ *
+ * An `InferaInferenceResult` struct containing the selected output and its shape. If
+ * an error occurs, the `status` field of the struct is a negative `InferaStatus` code,
+ * and an unknown selector gives an error listing the model's outputs.
+ *
+ * # Safety
+ *
+ * * `model_name`, `data`, and `output_selector` must not be null.
+ * * `model_name` and `output_selector` must point to valid, null-terminated C strings.
+ * * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+ */
+
+struct InferaInferenceResult infera_predict_output(const char *model_name,
+                                                   const float *data,
+                                                   uintptr_t rows,
+                                                   uintptr_t cols,
+                                                   const char *output_selector);
+
+/**
+ * Runs inference and returns the model output at a zero-based index.
+ *
+ * This is `infera_predict_output` with the output given by its position in
+ * `infera_get_output_names` instead of a selector string, so an index never matches an
+ * output whose name is a number. The whole model still runs once, but only the selected
+ * output is converted and copied out.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `data` - A pointer to the input tensor data, organized as a flat array of `f32`.
+ * * `rows` - The number of rows in the input tensor.
+ * * `cols` - The number of columns in the input tensor.
+ * * `output_index` - The zero-based index of the output to return.
+ *
+ * # Returns
+ *
+ * An `InferaInferenceResult` struct containing the selected output and its shape. If
+ * an error occurs, the `status` field of the struct is a negative `InferaStatus` code,
+ * and an index out of range gives an error listing the model's outputs.
+ *
+ * # Safety
+ *
+ * * `model_name` and `data` must not be null.
+ * * `model_name` must point to a valid, null-terminated C string.
+ * * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+ */
+
+struct InferaInferenceResult infera_predict_output_index(const char *model_name,
+                                                         const float *data,
+                                                         uintptr_t rows,
+                                                         uintptr_t cols,
+                                                         uintptr_t output_index);
+
+/**
+ * Profiles the time a loaded model spends in each of its nodes and returns a JSON report.
+ *
+ * The model is run `iterations` times on `rows` rows of seeded random input, with each
+ * node timed as it is evaluated. Profiling uses its own run state, so it does not slow
+ * down predictions. The report holds `wall_time_ns` (the total time of the runs),
+ * `profiled_time_ns` (the part of it spent evaluating nodes), `nodes` (the slowest
+ * nodes, each with its `name`, `op` type, `output_shapes`, `total_ns`, `mean_ns`, and
+ * `percent` of the wall time), and `ops` (the total time per op type).
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `rows` - The number of input rows per run. It must fit the model's batch dimension.
+ * * `iterations` - The number of runs to time. Must be greater than zero.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated, null-terminated C string containing JSON.
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ * On error, the JSON will contain an "error" key.
+ *
+ * # Safety
+ *
+ * * The `model_name` pointer must not be null and must point to a valid C string.
+ * * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+char *infera_profile_model(const char *model_name, uintptr_t rows, uintptr_t iterations);
+
+/**
+ * Runs inference with inputs and outputs addressed by their names in the ONNX graph.
+ *
+ * `inputs_json` is a JSON object keyed by input name, as the model's export tool named
+ * it (see `input_names` in `infera_get_model_info`), with a `shape` array and a flat
+ * `data` array for each input, for example
+ * `{"float_input": {"shape": [1, 3], "data": [1, 2, 3]}}`. The result is a JSON object
+ * keyed by output name with the `shape` and `data` of every model output.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `inputs_json` - A pointer to a null-terminated C string containing the JSON inputs.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated, null-terminated C string containing JSON.
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ * On error (e.g., an unknown or missing input name), the JSON will be an object with an
+ * "error" key instead.
+ *
+ * # Safety
+ *
+ * * `model_name` and `inputs_json` must be valid, null-terminated C strings.
+ * * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+char *infera_predict_named(const char *model_name, const char *inputs_json);
+
+/**
+ * Runs inference on feature maps, JSON objects keyed by feature name, so callers do not
+ * have to put their values in the model's column order themselves.
+ *
+ * `features_json` is a single object for one row, for example
+ * `{"age": 41, "income": 52000}`, or an array of such objects for a batch. The model's
+ * feature names, which give the column order, come from the `feature_names` load option,
+ * a `<model>.features.json` file next to the model holding a JSON array of names, or the
+ * `feature_names` ONNX metadata, in that order of precedence, and are listed in
+ * `infera_get_model_info`. A missing (or null) feature takes the `feature_default` load
+ * option, or fails if it is not set. A feature that is not in the list fails unless the
+ * model was loaded with `"ignore_unknown": true`. The caller is responsible for freeing
+ * the result using `infera_free_result`.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `features_json` - A pointer to a null-terminated C string containing the JSON
+ *   feature map or array of feature maps.
+ *
+ * # Returns
+ *
+ * An `InferaInferenceResult` struct with one output row per feature map. On failure,
+ * `status` is a negative `InferaStatus` code and `data` is null. Call
+ * `infera_last_error()` for details.
+ *
+ * # Safety
+ *
+ * * `model_name` and `features_json` must be valid, null-terminated C strings.
+ */
+struct InferaInferenceResult infera_predict_features(const char *model_name,
+                                                     const char *features_json);
+
+/**
+ * Runs inference on many differently shaped inputs in one call.
+ *
+ * Each input is a JSON object with a `shape` array and a flat `data` array, for example
+ * `[{"shape": [1, 3], "data": [1, 2, 3]}, {"shape": [2, 3], "data": [1, 2, 3, 4, 5, 6]}]`.
+ * The inputs are run in order, and the result is a JSON array with one entry per input:
+ * `{"shape": [...], "data": [...]}` for the model's first output, or `{"error": "..."}`
+ * if that input was malformed or failed. This amortizes the call overhead for many
+ * small inputs.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `inputs_json` - A pointer to a null-terminated C string containing a JSON array of
+ *   `{"shape", "data"}` objects.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated, null-terminated C string containing JSON.
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ * If the model is not loaded or `inputs_json` is not a JSON array, the JSON is an
+ * object with an "error" key instead.
+ *
+ * # Safety
+ *
+ * * `model_name` and `inputs_json` must be valid, null-terminated C strings.
+ * * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+char *infera_predict_many_json(const char *model_name, const char *inputs_json);
+
+/**
+ * Runs several loaded models on the same input and averages their outputs.
+ *
+ * Every model in the ensemble is run on the same `rows x cols` input, and the result is
+ * the element-wise weighted average of their outputs. All models must produce outputs of
+ * the same shape.
+ *
+ * # Arguments
+ *
+ * * `names_json` - A pointer to a null-terminated C string containing a JSON array of
+ *   model names (e.g., `["model_a", "model_b"]`).
+ * * `data` - A pointer to the input tensor data, organized as a flat array of `f32`.
+ * * `rows` - The number of rows in the input tensor.
+ * * `cols` - The number of columns in the input tensor.
+ * * `weights_json` - A pointer to a null-terminated C string containing a JSON array with
+ *   one non-negative weight per model, or null to weight all models equally. Weights
+ *   are normalized to sum to 1.
+ *
+ * # Returns
+ *
+ * An `InferaInferenceResult` struct containing the averaged output tensor.
+ * If an error occurs (e.g., a model is not loaded or the output shapes differ), the
+ * `status` field of the struct is a negative `InferaStatus` code.
+ *
+ * # Safety
+ *
+ * * `names_json` and `data` must not be null.
+ * * All non-null string pointers must point to valid, null-terminated C strings.
+ * * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+ */
+struct InferaInferenceResult infera_predict_ensemble(const char *names_json,
+                                                     const float *data,
+                                                     uintptr_t rows,
+                                                     uintptr_t cols,
+                                                     const char *weights_json);
+
+/**
+ * Compares two loaded models on the same inputs and returns a JSON report.
+ *
+ * Both models are run on either a user-provided input matrix or seeded random inputs
+ * matching their shared input shape. The report contains the maximum and mean absolute
+ * and relative differences, the fraction of rows whose argmax differs, and a `pass`
+ * verdict against the given tolerances.
+ *
+ * The options are given as a JSON object. Supported keys:
+ *
+ * * `"samples"` (integer): Number of random input rows to generate. Defaults to `100`.
+ * * `"seed"` (integer): Seed for the random inputs. Defaults to `42`.
+ * * `"inputs"` (array of number arrays): Input rows to use instead of random inputs.
+ * * `"atol"` (number): Absolute tolerance. Defaults to `1e-5`.
+ * * `"rtol"` (number): Relative tolerance. Defaults to `1e-4`.
+ *
+ * The comparison passes if every output element satisfies `|a - b| <= atol + rtol * |b|`.
+ *
+ * # Arguments
+ *
+ * * `name_a` - A pointer to a null-terminated C string for the reference model's name.
+ * * `name_b` - A pointer to a null-terminated C string for the compared model's name.
+ * * `options_json` - A pointer to a null-terminated C string containing the JSON options,
+ *   or null to use the defaults.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated, null-terminated C string containing JSON.
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ * On error (e.g., incompatible input shapes), the JSON will contain an "error" key.
+ *
+ * # Safety
+ *
+ * * The `name_a` and `name_b` pointers must not be null.
+ * * All non-null pointers must point to valid, null-terminated C strings.
+ * * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+char *infera_compare_models(const char *name_a, const char *name_b, const char *options_json);
+
+/**
+ * Retrieves metadata about a specific loaded model as a JSON string.
+ *
+ * The returned JSON string includes the model's name, and its input and output shapes.
+ * To trace numerical differences between environments, it also includes the versions of
+ * tract that compiled the model (`"tract_version"` and `"tract_core_version"`), whether
+ * the plan was optimized (`"optimized"`), and how long loading took
+ * (`"load_duration_ns"`).
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated, null-terminated C string containing JSON.
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ * On error (e.g., model not found), the JSON will contain an "error" key.
+ *
+ * # Safety
+ *
+ * * The `model_name` pointer must not be null and must point to a valid C string.
+ * * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+ char *infera_get_model_info(const char *model_name);
+
+/**
+ * Returns the names of a loaded model's outputs as a JSON array of strings.
+ *
+ * The names are in output order, matching the entries of `infera_predict_all`. Original
+ * outputs are named by their ONNX tensor names and are followed by any extra outputs
+ * requested with the `"extra_outputs"` load option.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated, null-terminated C string containing JSON.
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ * On error (e.g., model not found), the JSON will contain an "error" key.
+ *
+ * # Safety
+ *
+ * * The `model_name` pointer must not be null and must point to a valid C string.
+ * * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+char *infera_get_output_names(const char *model_name);
+
+/**
+ * Copies the shape of a loaded model's first input into a caller-provided buffer.
+ *
+ * This reads the same shape as the `"input_shape"` field of `infera_get_model_info`
+ * without building or parsing JSON. Dynamic dimensions are reported as -1.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `out` - A pointer to a buffer of at least `cap` `i64` values for the dimensions.
+ * * `cap` - The number of `i64` values `out` can hold.
+ * * `out_len` - A pointer that receives the number of dimensions, even when `cap` is too
+ *   small, so the call can be repeated with a large enough buffer.
+ *
+ * # Returns
+ *
+ * * `0` on success.
+ * * A negative `InferaStatus` code on failure (e.g., `-2` if the model is not loaded, or
+ *   `-1` if the buffer is too small or the shape is not known). Call
+ *   `infera_last_error()` to get a descriptive error message.
+ *
+ * # Safety
+ *
+ * * `model_name` and `out_len` must not be null, and `out` must not be null unless `cap`
+ *   is 0 or the shape has no dimensions.
+ * * `model_name` must point to a valid, null-terminated C string.
+ * * `out` must point to writable memory for `cap` `i64` values.
+ */
+int32_t infera_get_input_shape(const char *model_name,
+                               int64_t *out,
+                               uintptr_t cap,
+                               uintptr_t *out_len);
+
+/**
+ * Copies the shape of a loaded model's first output into a caller-provided buffer.
+ *
+ * This is the output counterpart of `infera_get_input_shape` and takes the same
+ * arguments. Dynamic dimensions are reported as -1.
+ *
+ * # Returns
+ *
+ * * `0` on success.
+ * * A negative `InferaStatus` code on failure. Call `infera_last_error()` to get a
+ *   descriptive error message.
+ *
+ * # Safety
+ *
+ * The same requirements as for `infera_get_input_shape` apply.
+ */
+int32_t infera_get_output_shape(const char *model_name,
+                                int64_t *out,
+                                uintptr_t cap,
+                                uintptr_t *out_len);
+
+/**
+ * Returns the number of output elements a batch of `batch_rows` input rows produces, for
+ * sizing output buffers before predicting.
+ *
+ * For models with a dynamic batch dimension, `batch_rows` is substituted into the
+ * output's dynamic dimension (see `"input_batch_dim"` and `"output_batch_dim"` in
+ * `infera_get_model_info`). For fully static models, the size of the fixed output is
+ * returned if `batch_rows` is the fixed batch size, or for any batch if the model was
+ * loaded with the `pad_batch` option.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `batch_rows` - The number of input rows of the planned batch.
+ *
+ * # Returns
+ *
+ * * The number of output elements on success.
+ * * `-1` on failure, including for models whose output batch does not scale linearly
+ *   with the input batch (such as a model with a dynamic input batch and a fixed output,
+ *   or with several dynamic dimensions). Call `infera_last_error()` to get a descriptive
+ *   error message.
+ *
+ * # Safety
+ *
+ * * The `model_name` pointer must not be null and must point to a valid C string.
+ */
+
+int64_t infera_get_output_size(const char *model_name, uintptr_t batch_rows);
+
+/**
+ * Checks whether an input of the given shape would be accepted by a loaded model,
+ * before building the input buffer.
+ *
+ * The shape must have the rank of the model's first input (see `infera_get_input_shape`)
+ * and match each of its fixed dimensions. Dynamic dimensions accept any size. If the
+ * model's input shape is not known, any shape is accepted.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ * * `shape` - A pointer to the `ndim` dimensions of the planned input.
+ * * `ndim` - The number of dimensions in `shape`.
+ *
+ * # Returns
+ *
+ * * `0` if the shape is compatible with the model's input.
+ * * `-1` if it is not, or on failure (e.g., the model is not loaded). Call
+ *   `infera_last_error()` to get a descriptive error message, which names the expected
+ *   and given shapes.
+ *
+ * # Safety
+ *
+ * * `model_name` must not be null and must point to a valid, null-terminated C string.
+ * * `shape` must point to `ndim` `i64` values, and may only be null if `ndim` is 0.
+ */
+
+int32_t infera_check_input_shape(const char *model_name,
+                                 const int64_t *shape,
+                                 uintptr_t ndim);
+
+/**
+ * Returns a hash that identifies the contents of a loaded model, for keying artifacts
+ * derived from it.
+ *
+ * The hash is the hex-encoded SHA-256 of the model file, computed when the model was
+ * loaded, so loading the same file again gives the same hash and any change to the file
+ * gives a different one. For remote models it is the hash of the downloaded file, the
+ * same one `infera_load_model_verified` checks.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated, null-terminated C string with 64 lowercase hexadecimal
+ * characters. The caller is responsible for freeing this string using `infera_free_string`.
+ * On error (e.g., model not found), the string is a JSON object with an "error" key.
+ *
+ * # Safety
+ *
+ * * The `model_name` pointer must not be null and must point to a valid C string.
+ * * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+char *infera_get_model_signature(const char *model_name);
+
+/**
+ * Returns how long the most recent inference on a loaded model took.
+ *
+ * The time is the wall-clock duration of running the model itself, measured separately
+ * for each model so concurrent callers of different models do not overwrite each other.
+ *
+ * # Arguments
+ *
+ * * `model_name` - A pointer to a null-terminated C string for the model's name.
+ *
+ * # Returns
+ *
+ * * The duration of the model's most recent run in nanoseconds.
+ * * `0` if the model has not run since it was loaded, or on failure (e.g., the model is
+ *   not loaded). Call `infera_last_error()` to tell a failure apart.
+ *
+ * # Safety
+ *
+ * * The `model_name` pointer must not be null.
+ * * The memory pointed to by `model_name` must be a valid, null-terminated C string.
+ */
+uint64_t infera_get_last_prediction_time_ns(const char *model_name);
+
+/**
+ * Checks whether a model is loaded under a name.
+ *
+ * This only looks the name up in the model registry, so it is cheaper than listing the
+ * loaded models or attempting a prediction. Canary aliases are not models, so they are
+ * not reported as existing.
+ *
+ * # Arguments
+ *
+ * * `name` - A pointer to a null-terminated C string for the model's name.
+ *
+ * # Returns
+ *
+ * * `1` if a model is loaded under `name`.
+ * * `0` if not.
+ * * `-1` on failure (e.g., `name` is not valid UTF-8). Call `infera_last_error()` to get
+ *   a descriptive error message.
+ *
+ * # Safety
+ *
+ * * The `name` pointer must not be null.
+ * * The memory pointed to by `name` must be a valid, null-terminated C string.
+ */
+int32_t infera_model_exists(const char *name);
+
+/**
+ * Returns a JSON array of the names of all currently loaded models.
+ *
+ * Only models in the current thread's default namespace are listed (see
+ * `infera_set_default_namespace`), or models loaded without a namespace if no default
+ * is set. Use `infera_get_loaded_models_ns` to list another namespace.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated, null-terminated C string containing a JSON array of strings.
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ *
+ * # Safety
+ *
+ * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+ char *infera_get_loaded_models(void);
+
+/**
+ * Returns a JSON array describing the models loaded in the current thread's default
+ * namespace, or loaded without a namespace if no default is set.
+ *
+ * Unlike `infera_get_loaded_models`, which only lists names, each entry is an object
+ * with the model's unqualified `name`, its registry key (`canonical_name`), its
+ * `namespace` (null for models loaded without one), the ID of the autoload group it
+ * belongs to (`group_id`, null if it was not autoloaded), the canary `aliases` currently
+ * routing to it, and the number of `previous_versions` kept for rollback. Entries are
+ * sorted by name and read from one consistent snapshot of the registries.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated, null-terminated C string containing a JSON array of objects.
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ *
+ * # Safety
+ *
+ * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+ char *infera_list_models_detailed(void);
+
+/**
+ * Returns a JSON array of the names of the models loaded in a namespace.
+ *
+ * The names are returned without the namespace prefix, sorted by name.
+ *
+ * # Arguments
+ *
+ * * `ns` - A pointer to a null-terminated C string naming the namespace.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated, null-terminated C string containing a JSON array of strings.
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ * On error (e.g., an invalid namespace), the JSON will contain an "error" key.
+ *
+ * # Safety
+ *
+ * * The `ns` pointer must not be null and must point to a valid C string.
+ * * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+
+ char *infera_get_loaded_models_ns(const char *ns);
+
+/**
+ * Unloads every model in a namespace.
+ *
+ * # Arguments
+ *
+ * * `ns` - A pointer to a null-terminated C string naming the namespace.
+ *
+ * # Returns
+ *
+ * * The number of models unloaded (`0` if the namespace was empty) on success.
+ * * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
+ *
+ * # Safety
+ *
+ * * The `ns` pointer must not be null.
+ * * The memory pointed to by `ns` must be a valid, null-terminated C string.
+ */
+
+ int32_t infera_unload_namespace(const char *ns);
+
+/**
+ * Sets the namespace that unqualified model names resolve to on the calling thread.
+ *
+ * After this call, functions such as `infera_load_model`, `infera_predict`,
+ * `infera_get_model_info`, `infera_unload_model`, and `infera_get_loaded_models` act on
+ * `ns/name` when given a bare `name`. Names that already contain a namespace
+ * (`other/name`) are not affected. The setting only applies to the calling thread.
+ *
+ * # Arguments
+ *
+ * * `ns` - A pointer to a null-terminated C string naming the namespace, or null
+ *   (or an empty string) to go back to the root namespace.
+ *
+ * # Returns
+ *
+ * * `0` on success.
+ * * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
+ *
+ * # Safety
+ *
+ * * If not null, `ns` must point to a valid, null-terminated C string.
+ */
+
+ int32_t infera_set_default_namespace(const char *ns);
+
+/**
+ * Returns a JSON string with version and build information about the Infera library.
+ *
+ * The JSON object includes the library version, the enabled ONNX backend (e.g., "tract"),
+ * and the directory used for caching remote models. To help reproduce differences in
+ * inference results between builds, it also includes the tract version the library was
+ * built with (`"tract_version"`, null without the "tract" feature), the target triple it
+ * was built for (`"target"`), and its enabled Cargo features (`"features"`). The versions
+ * of each backend library are listed under `"backend_versions"` by crate name (e.g.,
+ * `"tract-onnx"` and `"tract-core"`), which is empty without the "tract" feature.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated, null-terminated C string containing the version info.
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ *
+ * # Safety
+ *
+ * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+ char *infera_get_version(void);
+
+/**
+ * Returns the configuration Infera resolved from its environment variables as a JSON string.
+ *
+ * The JSON object has one key per configuration field (e.g., `"cache_dir"`,
+ * `"cache_size_limit"`, `"http_timeout_secs"`, `"http_retry_attempts"`,
+ * `"cache_eviction_strategy"`, and `"log_level"`), with the value in effect at startup.
+ * Use `infera_get_cache_info` for the current cache size limit, which can be changed
+ * at runtime.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated, null-terminated C string containing the configuration.
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ *
+ * # Safety
+ *
+ * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+
+ char *infera_get_config(void);
+
+/**
+ * Clears the entire model cache directory.
+ *
+ * This removes all cached remote models, freeing up disk space.
+ *
+ * # Returns
+ *
+ * * `0` on success.
+ * * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
+ *
+ * # Safety
+ *
+ * This function is safe to call at any time.
+ */
+ int32_t infera_clear_cache(void);
+
+/**
+ * Sets the cache size limit in bytes for the rest of the process lifetime.
+ *
+ * This overrides the limit read from `INFERA_CACHE_SIZE_LIMIT` at startup and
+ * immediately evicts least recently used cached models until the cache fits.
+ *
+ * # Arguments
+ *
+ * * `bytes` - The new cache size limit in bytes.
+ *
+ * # Returns
+ *
+ * * `0` on success.
+ * * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
+ *
+ * # Safety
+ *
+ * This function is safe to call at any time.
+ */
+ int32_t infera_set_cache_size_limit(uint64_t bytes);
+
+/**
+ * Turns offline mode on or off for the rest of the process lifetime.
+ *
+ * This overrides `INFERA_OFFLINE` read at startup. While offline, loading a model from
+ * a URL never makes a network request: a cached copy is served without revalidating
+ * it, and a model that is not cached fails right away. `infera_check_model_updated`
+ * fails too, and signatures are not fetched.
+ *
+ * # Arguments
+ *
+ * * `offline` - `true` to go offline, or `false` to allow network requests again.
+ *
+ * # Returns
+ *
+ * * `0` on success.
+ *
+ * # Safety
+ *
+ * This function is safe to call at any time.
+ */
+int32_t infera_set_offline(bool offline);
+
+/**
+ * Routes Infera's log records to a host callback instead of stderr.
+ *
+ * The callback receives the level (`0` error, `1` warn, `2` info, `3` debug), the
+ * module the record comes from, and the message as separate arguments. While a callback
+ * is set it receives every record, regardless of `INFERA_LOG_LEVEL`, so the host decides
+ * what to keep. The callback may be called from any thread, including worker threads.
+ *
+ * # Arguments
+ *
+ * * `callback` - The function to call for each log record, or null to go back to
+ *   printing records at or above `INFERA_LOG_LEVEL` to stderr.
+ *
+ * # Returns
+ *
+ * * `0` on success.
+ *
+ * # Safety
+ *
+ * This function is safe to call at any time. The strings passed to the callback are only
+ * valid for the duration of the call.
+ */
+
+ int32_t infera_set_log_callback(InferaLogCallback callback);
+
+/**
+ * Adds a public key that is trusted to sign models.
+ *
+ * Once a key is registered, every model load looks for a detached ed25519 signature
+ * next to the model file (`<path>.sig`, or `<url>.sig` for remote models) and reports
+ * the result in `infera_get_model_info`. With `INFERA_REQUIRE_SIGNED_MODELS=1`, models
+ * without a valid signature from a trusted key fail to load.
+ *
+ * # Arguments
+ *
+ * * `key` - A pointer to a null-terminated C string holding an ed25519 public key,
+ *   either PEM-encoded (SubjectPublicKeyInfo) or as 64 hex characters.
+ *
+ * # Returns
+ *
+ * * `0` on success.
+ * * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
+ *
+ * # Safety
+ *
+ * * The `key` pointer must not be null.
+ * * The memory pointed to by `key` must be a valid, null-terminated C string.
+ */
+
+ int32_t infera_add_trusted_key(const char *key);
+
+/**
+ * Returns cache statistics as a JSON string.
+ *
+ * The JSON object includes:
+ * * `"cache_dir"`: The path to the cache directory.
+ * * `"temp_dir"`: The directory in-progress downloads are written to (see `INFERA_TEMP_DIR`).
+ * * `"total_size_bytes"`: Total size of cached models in bytes (same as `"logical_size_bytes"`).
+ * * `"logical_size_bytes"`: Total size of all cached models, counting a payload shared by
+ *   several URLs once per URL.
+ * * `"physical_size_bytes"`: Size of the cached models on disk, counting each shared
+ *   payload once (see `INFERA_CACHE_DEDUP`).
+ * * `"file_count"`: Number of cached model files.
+ * * `"size_limit_bytes"`: The configured cache size limit.
+ * * `"stats"`: Cache activity counters since the process started or since
+ *   `infera_reset_cache_stats` was called: `hits` (loads served from a cached file),
+ *   `misses` (loads that downloaded the model or failed), `revalidations` (304 responses),
+ *   `downloads`, `bytes_downloaded`, `evictions` (entries removed to stay within the size
+ *   limit), and `bytes_evicted`.
+ * * `"orphaned_parts"`: The `removed_files` count and `removed_bytes` of the orphaned
+ *   `.part` files of unfinished downloads (for example, after a crash) that this call
+ *   cleaned up. A `.part` file is orphaned once no download is writing it and it has not
+ *   been modified for 10 minutes.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated, null-terminated C string containing the cache info.
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ *
+ * # Safety
+ *
+ * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+char *infera_get_cache_info(void);
+
+/**
+ * Resets the cache activity counters reported under `"stats"` by `infera_get_cache_info`.
+ *
+ * # Returns
+ *
+ * * `0` on success.
+ */
+int32_t infera_reset_cache_stats(void);
+
+/**
+ * Returns a JSON array describing each cached remote model.
+ *
+ * Each entry contains the original `url` (or `null` for models cached before URLs
+ * were recorded), the `final_url` it was downloaded from after redirects (or `null`
+ * if not recorded), the `sha256` of the download, computed while it was streamed (or
+ * `null` if not recorded), the cached `file` path, `size_bytes`, and `last_access` as
+ * seconds since the Unix epoch. Entries are ordered by access time, oldest first.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated, null-terminated C string containing the JSON array.
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ * On error, the JSON will be an object with an "error" key.
+ *
+ * # Safety
+ *
+ * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+ char *infera_list_cache(void);
+
+/**
+ * Returns a JSON array of the cached models that would be evicted to make room for
+ * `required_bytes` more bytes, without deleting anything.
+ *
+ * The selection uses the current cache size limit and eviction strategy
+ * (`INFERA_CACHE_EVICTION`), exactly as a download needing that much space would. Entries
+ * are in eviction order and contain the original `url`, the cached `file` path,
+ * `size_bytes`, `freed_bytes` (zero for a deduplicated payload still used by another
+ * entry or an entry in use), `last_access` and `downloaded_at` as seconds since the Unix
+ * epoch, `access_count`, and `in_use`. Entries in use back a loaded model and are
+ * skipped instead of evicted, so the next entries are evicted in their place. The
+ * array is empty if the space already fits.
+ *
+ * # Arguments
+ *
+ * * `required_bytes` - The number of bytes to make room for. Pass 0 to preview what
+ *   fitting the cache within its current limit would evict.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated, null-terminated C string containing the JSON array.
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ * On error, the JSON will be an object with an "error" key.
+ *
+ * # Safety
+ *
+ * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+char *infera_preview_eviction(uint64_t required_bytes);
+
+/**
+ * Scans a directory for `.onnx` files and loads them into Infera automatically.
+ *
+ * The name for each model is derived from its filename (without the extension). The
+ * loaded models are recorded as a group, which `infera_unload_group` can unload later
+ * without affecting models loaded in other ways.
+ *
+ * # Arguments
+ *
+ * * `path` - A pointer to a null-terminated C string representing the directory path.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated C string containing a JSON object with these fields:
+ * * `"group_id"`: The ID of the group of models loaded from the directory.
+ * * `"loaded"`: A list of model names that were successfully loaded.
+ * * `"reloaded"`, `"unchanged"`, and `"removed"`: Empty lists, filled in when syncing a
+ *   directory with `infera_set_autoload_dir_with_options`.
+ * * `"errors"`: A list of objects, each detailing a file that failed to load (or a
+ *   directory entry that could not be read) and the reason. Files that failed to load
+ *   also have the number of `"attempts"` made to load them (see `INFERA_LOAD_RETRY_ATTEMPTS`).
+ *
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ *
+ * # Safety
+ *
+ * * The `path` pointer must not be null and must point to a valid C string.
+ * * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+char *infera_set_autoload_dir(const char *path);
+
+/**
+ * Autoloads a directory like `infera_set_autoload_dir`, with a JSON object of options.
+ *
+ * With `{"sync": true}`, an earlier group of the same directory is reused instead of a
+ * new group being created. Models whose files did not change (by modification time and
+ * size) are kept and reported as `"unchanged"`, new files are loaded, changed files are
+ * reloaded and reported as `"reloaded"`, and models whose files were deleted are
+ * unloaded and reported as `"removed"`. A model whose reload fails stays loaded in its
+ * previous version.
+ *
+ * # Arguments
+ *
+ * * `path` - A pointer to a null-terminated C string representing the directory path.
+ * * `options_json` - An optional null-terminated C string with a JSON object of options.
+ *   Pass null or an empty string for the defaults.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated C string containing the JSON report described for
+ * `infera_set_autoload_dir`. On error, the JSON will be an object with an "error" key.
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ *
+ * # Safety
+ *
+ * * The `path` pointer must not be null and must point to a valid C string.
+ * * If not null, `options_json` must point to a valid C string.
+ * * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+char *infera_set_autoload_dir_with_options(const char *path,
+                                           const char *options_json);
+
+/**
+ * Unloads the models of an autoload group and forgets the group.
+ *
+ * Only the models the group loaded are unloaded. Members that were already unloaded or
+ * renamed are skipped.
+ *
+ * # Arguments
+ *
+ * * `group_id` - The `group_id` returned by `infera_set_autoload_dir`.
+ *
+ * # Returns
+ *
+ * * The number of models unloaded on success.
+ * * `-1` if no group has this ID. Call `infera_last_error()` to get a descriptive error
+ *   message.
+ */
+int32_t infera_unload_group(uint64_t group_id);
+
+/**
+ * Returns a JSON array describing each autoload group.
+ *
+ * Each entry contains the `group_id`, the canonical `dir` the models were loaded from,
+ * and the names of its `models`. Groups are ordered by ID.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated, null-terminated C string containing the JSON array.
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ *
+ * # Safety
+ *
+ * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+char *infera_list_groups(void);
+
+/**
+ * Autoloads a directory and starts a background thread that keeps it in sync.
+ *
+ * The directory is synced immediately, then every `poll_interval_secs` seconds, as if
+ * `infera_set_autoload_dir_with_options` were called with `{"sync": true}`: new files
+ * are loaded, changed files are hot-reloaded, and models whose files were deleted are
+ * unloaded. A changed file replaces the loaded model only once it compiled, so a file
+ * that fails to load keeps the previous version serving. Every action is logged, and
+ * the counts are reported under `autoload` by `infera_get_metrics`.
+ *
+ * # Arguments
+ *
+ * * `path` - A pointer to a null-terminated C string representing the directory path.
+ * * `poll_interval_secs` - The number of seconds between syncs. Must be at least 1.
+ *
+ * # Returns
+ *
+ * A pointer to a heap-allocated C string containing the JSON report of the first sync,
+ * as described for `infera_set_autoload_dir`. On error (for example, if the directory
+ * cannot be read or is already watched), the JSON will be an object with an "error" key.
+ * The caller is responsible for freeing this string using `infera_free_string`.
+ *
+ * # Safety
+ *
+ * * The `path` pointer must not be null and must point to a valid C string.
+ * * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+ */
+char *infera_watch_autoload_dir(const char *path, uint64_t poll_interval_secs);
+
+/**
+ * Stops watching a directory started with `infera_watch_autoload_dir`.
+ *
+ * Waits for the watcher thread to finish a sync in progress. The directory's models
+ * stay loaded.
+ *
+ * # Arguments
+ *
+ * * `path` - A pointer to a null-terminated C string representing the directory path.
+ *
+ * # Returns
+ *
+ * * `0` on success.
+ * * `-1` if the directory is not watched. Call `infera_last_error()` to get a
+ *   descriptive error message.
+ *
+ * # Safety
+ *
+ * The `path` pointer must not be null and must point to a valid C string.
+ */
+int32_t infera_unwatch_autoload_dir(const char *path);
+
+/**
+ * Initializes Infera's global state up front instead of on first use.
+ *
+ * This reads the configuration from the environment, sets up the model registry, and
+ * creates the cache directory and the download temp directory. Other functions
+ * initialize what they need on first use, which is safe from several threads at once,
+ * so calling this is optional. Hosts can call it at startup to take that work, and any
+ * error creating the cache directory, out of their first model load. It can be called
+ * more than once.
+ *
+ * # Returns
+ *
+ * * `0` on success.
+ * * `-1` on failure (e.g., the cache directory cannot be created). Call
+ *   `infera_last_error()` to get a descriptive error message.
+ */
+int32_t infera_init(void);
+
+/**
+ * Stops Infera's background threads and waits for them to finish.
+ *
+ * This stops every directory watcher started with `infera_watch_autoload_dir`. Loaded
+ * models stay loaded, and Infera can still be used afterwards. Call this before
+ * unloading the library so no watcher thread outlives it.
+ *
+ * # Returns
+ *
+ * The number of background threads stopped.
+ */
+int32_t infera_shutdown(void);
+
+/**
+ * Retrieves the last error message.
+ *
+ * After an FFI function returns an error code, this function can be called
+ * to get a more descriptive, human-readable error message. By default this is the last
+ * error of the calling thread. In the global error mode (see `infera_set_error_mode`),
+ * it is the most recent error of any thread.
+ *
+ * # Returns
+ *
+ * A pointer to a null-terminated C string containing the last error message.
+ * Returns a null pointer if no error has occurred since the last call.
+ * The caller **must not** free this pointer, as it is managed by a thread-local static variable.
+ * In the global error mode, the pointer is only valid until the next error on any
+ * thread, so use `infera_take_last_error` when other threads may fail in the meantime.
+ */
+ const char *infera_last_error(void);
+
+/**
+ * Removes the last error message and returns an owned copy of it.
+ *
+ * The error is read from the same place as `infera_last_error`, but since the caller
+ * owns the returned string, it cannot be replaced by a later error before it is read.
+ *
+ * # Returns
+ *
+ * A pointer to a null-terminated C string containing the last error message, or a null
+ * pointer if no error has occurred since the last call. The caller is responsible for
+ * freeing this string using `infera_free_string`.
+ */
+char *infera_take_last_error(void);
+
+/**
+ * Chooses where `infera_last_error` and `infera_take_last_error` read the last error
+ * from.
+ *
+ * Errors are always recorded for the thread they occur on. In the global mode, the most
+ * recent error of any thread is recorded in a process-wide slot as well, and is what
+ * both functions return. This suits hosts that may resume a task on another OS thread
+ * between a failing call and reading its error. Switching back to the thread-local mode
+ * drops the process-wide error.
+ *
+ * # Arguments
+ *
+ * * `mode` - `0` (`InferaErrorMode_ThreadLocal`, the default) or `1`
+ *   (`InferaErrorMode_Global`).
+ *
+ * # Returns
+ *
+ * * `0` on success.
+ * * `-1` if the mode is invalid. Call `infera_last_error()` to get a descriptive error
+ *   message.
+ *
+ * # Safety
+ *
+ * This function is safe to call at any time.
+ */
+int32_t infera_set_error_mode(int32_t mode);
+
+/**
+ * Frees a heap-allocated C string that was returned by an Infera FFI function.
+ *
+ * This function should be used to free the memory for strings returned by functions
+ * like `infera_get_model_info`, `infera_get_loaded_models`, and `infera_get_version`.
+ * The `data` buffer of an `InferaInferenceResult` must be freed with `infera_free_result`
+ * instead.
+ *
+ * # Safety
+ *
+ * The `ptr` must be a non-null pointer to a C string that was previously allocated
+ * by Rust's `CString::into_raw`. Passing any other pointer (e.g., a string literal,
+ * a pointer from a different allocator, or a null pointer) will result in undefined behavior.
+ */
+ void infera_free_string(char *ptr);
+
+/**
+ * Frees a heap-allocated C string that was returned by an Infera FFI function.
+ *
+ * Deprecated: this is an alias of `infera_free_string`, kept for compatibility. Its name
+ * does not say that it only frees strings, so new code should call `infera_free_string`
+ * for strings and `infera_free_result` for inference results.
+ *
+ * # Safety
+ *
+ * The same requirements as `infera_free_string` apply.
+ */
+ void infera_free(char *ptr);
+
+/**
+ * Frees the data buffer within an `InferaInferenceResult`.
+ *
+ * This function must be called on every `InferaInferenceResult` returned from
+ * `infera_predict` or `infera_predict_from_blob` to prevent memory leaks. When
+ * `INFERA_RESULT_POOL_SIZE` is set, the buffer is kept for reuse by a later prediction
+ * instead of being freed, until the pool is full.
+ *
+ * # Safety
+ *
+ * * The `res` struct must be a value that was returned by an Infera prediction function.
+ * * The `res.data` pointer must have been allocated by Rust's `Vec<f32>` and not yet freed.
+ * * The `res.len` field must accurately represent the capacity of the allocated buffer.
+ *
+ * Calling this function on a manually-created struct or calling it more than once
+ * on the same result will lead to undefined behavior.
+ */
+ void infera_free_result(struct InferaInferenceResult res);
+
+/**
+ * Frees every inference result buffer kept for reuse by the result pool.
+ *
+ * The pool is only used when `INFERA_RESULT_POOL_SIZE` is set. Results that are still
+ * held by the caller are not affected.
+ *
+ * # Returns
+ *
+ * The number of buffers that were freed.
+ */
+ uintptr_t infera_clear_result_pool(void);
+
+/**
+ * Frees the bytes of an `InferaBlob` returned by `infera_predict_to_blob`.
+ *
+ * # Safety
+ *
+ * * The `blob` struct must be a value that was returned by `infera_predict_to_blob`.
+ * * Its bytes must not have been freed yet. A blob with a null `ptr` is ignored.
+ */
+void infera_free_blob(struct InferaBlob blob);
+
+/**
+ * Creates a deep copy of an `InferaInferenceResult`.
+ *
+ * The copy owns a freshly allocated buffer holding the same `len` elements and the
+ * same `rows` and `cols` metadata, so it can be freed with `infera_free_result`
+ * independently of the source.
+ *
+ * # Returns
+ *
+ * A new `InferaInferenceResult`. If `src` is null or does not describe a successful
+ * result, an error result with `status` set to `-1` is returned.
+ *
+ * # Safety
+ *
+ * * `src`, if not null, must point to a valid `InferaInferenceResult` whose `data`
+ *   buffer has not been freed yet.
+ */
+ struct InferaInferenceResult infera_copy_result(const struct InferaInferenceResult *src);
 
 #ifdef __cplusplus
 } // extern "C"
@@ -75,19 +75,20 @@ static ScalarFunction InferaScalarFunction(const std::string &name, vector<Logic
 }
 
 /**
- * @brief Implements the `infera_set_autoload_dir(path)` SQL function.
+ * @brief Implements the `infera_set_autoload_dir(path[, options])` SQL function.
  *
  * This function takes a directory path, passes it to the Rust core to load all
  * valid ONNX models in that directory, and returns a JSON string with the
- * results of the operation.
+ * results of the operation, including the ID of the group of loaded models.
+ * The optional options are a JSON object such as `{"sync": true}`.
  *
  * @param args The input arguments from DuckDB.
  * @param state The expression state.
  * @param result The result vector to populate.
  */
 static void SetAutoloadDir(DataChunk &args, ExpressionState &state, Vector &result) {
-  if (args.ColumnCount() != 1) {
-    throw InvalidInputException("infera_set_autoload_dir(path) expects exactly 1 argument");
+  if (args.ColumnCount() != 1 && args.ColumnCount() != 2) {
+    throw InvalidInputException("infera_set_autoload_dir(path[, options]) expects 1 or 2 arguments");
   }
   if (args.size() == 0) { return; }
   auto path_val = args.data[0].GetValue(0);
@@ -95,11 +96,18 @@ static void SetAutoloadDir(DataChunk &args, ExpressionState &state, Vector &resu
     throw InvalidInputException("Path cannot be NULL");
   }
   std::string path_str = path_val.ToString();
-  char *result_json_c = infera::infera_set_autoload_dir(path_str.c_str());
+  std::string options_str;
+  if (args.ColumnCount() == 2) {
+    auto options_val = args.data[1].GetValue(0);
+    if (!options_val.IsNull()) {
+      options_str = options_val.ToString();
+    }
+  }
+  char *result_json_c = infera::infera_set_autoload_dir_with_options(path_str.c_str(), options_str.c_str());
   result.SetVectorType(VectorType::CONSTANT_VECTOR);
   ConstantVector::GetData<string_t>(result)[0] = StringVector::AddString(result, result_json_c);
   ConstantVector::SetNull(result, false);
-  infera::infera_free(result_json_c);
+  infera::infera_free_string(result_json_c);
 }
 
 /**
@@ -117,22 +125,40 @@ static void GetVersion(DataChunk &args, ExpressionState &state, Vector &result)
   result.SetVectorType(VectorType::CONSTANT_VECTOR);
   ConstantVector::GetData<string_t>(result)[0] = StringVector::AddString(result, info_json_c);
   ConstantVector::SetNull(result, false);
-  infera::infera_free(info_json_c);
+  infera::infera_free_string(info_json_c);
+}
+
+/**
+ * @brief Implements the `infera_get_config()` SQL function.
+ *
+ * Returns the configuration resolved from the environment as a JSON string.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void GetConfig(DataChunk &args, ExpressionState &state, Vector &result) {
+  char *config_json_c = infera::infera_get_config();
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<string_t>(result)[0] = StringVector::AddString(result, config_json_c);
+  ConstantVector::SetNull(result, false);
+  infera::infera_free_string(config_json_c);
 }
 
 /**
- * @brief Implements the `infera_load_model(name, path)` SQL function.
+ * @brief Implements the `infera_load_model(name, path[, options])` SQL function.
  *
  * Takes a model name and a file path/URL, passing them to the Rust core to
- * load an ONNX model.
+ * load an ONNX model. An optional third argument carries the load options as
+ * a JSON object.
  *
  * @param args The input arguments from DuckDB.
  * @param state The expression state.
  * @param result The result vector to populate.
  */
 static void LoadModel(DataChunk &args, ExpressionState &state, Vector &result) {
-  if (args.ColumnCount() != 2) {
-    throw InvalidInputException("infera_load_model(model_name, path) expects exactly 2 arguments");
+  if (args.ColumnCount() != 2 && args.ColumnCount() != 3) {
+    throw InvalidInputException("infera_load_model(model_name, path[, options]) expects 2 or 3 arguments");
   }
   if (args.size() == 0) { return; }
   auto model_name = args.data[0].GetValue(0);
@@ -142,10 +168,18 @@ static void LoadModel(DataChunk &args, ExpressionState &state, Vector &result) {
   }
   std::string model_name_str = model_name.ToString();
   std::string path_str = path.ToString();
-  if (model_name_str.empty()) {
+  // An empty name loads a model bundle under the name in its manifest
+  if (model_name_str.empty() && !StringUtil::EndsWith(StringUtil::Lower(path_str), ".infera.zip")) {
     throw InvalidInputException("Model name cannot be empty");
   }
-  int rc = infera::infera_load_model(model_name_str.c_str(), path_str.c_str());
+  int rc;
+  if (args.ColumnCount() == 3) {
+    auto options = args.data[2].GetValue(0);
+    std::string options_str = options.IsNull() ? std::string() : options.ToString();
+    rc = infera::infera_load_model_with_options(model_name_str.c_str(), path_str.c_str(), options_str.c_str());
+  } else {
+    rc = infera::infera_load_model(model_name_str.c_str(), path_str.c_str());
+  }
   bool success = rc == 0;
   if (!success) {
     throw InvalidInputException("Failed to load model '" + model_name_str + "': " + GetInferaError());
@@ -156,278 +190,883 @@ static void LoadModel(DataChunk &args, ExpressionState &state, Vector &result) {
 }
 
 /**
- * @brief Implements the `infera_unload_model(name)` SQL function.
+ * @brief Implements the `infera_load_model_verified(name, path, expected_sha256)` SQL function.
  *
- * Unloads a model from the Infera engine by its name.
+ * Loads a model like `infera_load_model` after checking that the model file
+ * has the expected SHA-256 checksum.
  *
  * @param args The input arguments from DuckDB.
  * @param state The expression state.
  * @param result The result vector to populate.
  */
-static void UnloadModel(DataChunk &args, ExpressionState &state, Vector &result) {
-  if (args.ColumnCount() != 1) {
-    throw InvalidInputException("infera_unload_model(model_name) expects exactly 1 argument");
+static void LoadModelVerified(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 3) {
+    throw InvalidInputException("infera_load_model_verified(model_name, path, expected_sha256) expects exactly 3 arguments");
   }
   if (args.size() == 0) { return; }
   auto model_name = args.data[0].GetValue(0);
-  if (model_name.IsNull()) {
-    throw InvalidInputException("Model name cannot be NULL");
+  auto path = args.data[1].GetValue(0);
+  auto checksum = args.data[2].GetValue(0);
+  if (model_name.IsNull() || path.IsNull() || checksum.IsNull()) {
+    throw InvalidInputException("Model name, path, and checksum cannot be NULL");
   }
   std::string model_name_str = model_name.ToString();
-  int rc = infera::infera_unload_model(model_name_str.c_str());
-  if (rc != 0) {
-    std::string err = GetInferaError();
-    // Treat model-not-found as benign idempotent success returning true
-    if (err.rfind("Model not found:", 0) != 0) {
-      throw InvalidInputException("Failed to unload model '" + model_name_str + "': " + err);
-    }
+  std::string path_str = path.ToString();
+  std::string checksum_str = checksum.ToString();
+  if (model_name_str.empty()) {
+    throw InvalidInputException("Model name cannot be empty");
+  }
+  if (infera::infera_load_model_verified(model_name_str.c_str(), path_str.c_str(), checksum_str.c_str()) != 0) {
+    throw InvalidInputException("Failed to load model '" + model_name_str + "': " + GetInferaError());
   }
   result.SetVectorType(VectorType::CONSTANT_VECTOR);
-  ConstantVector::GetData<bool>(result)[0] = true; // always true for idempotency & verification stability
+  ConstantVector::GetData<bool>(result)[0] = true;
   ConstantVector::SetNull(result, false);
 }
 
 /**
- * @brief Extracts numerical features from a DataChunk.
+ * @brief Implements the `infera_rename_model(old_name, new_name[, replace])` SQL function.
  *
- * Iterates over the input DataChunk (skipping the first column, which is the
- * model name) and converts all values to floats, storing them in a vector.
+ * Renames a loaded model without recompiling it. If `replace` is true, a model
+ * already loaded under the new name is replaced.
  *
- * @param args The input DataChunk containing the features.
- * @param features The output vector to store the extracted float features.
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
  */
-static void ExtractFeatures(DataChunk &args, std::vector<float> &features) {
-  const idx_t batch_size = args.size();
-  const idx_t feature_count = args.ColumnCount() - 1;
-  features.reserve(batch_size * feature_count);
-
-  for (idx_t row_idx = 0; row_idx < batch_size; ++row_idx) {
-    for (idx_t col_idx = 1; col_idx <= feature_count; ++col_idx) {
-      auto feature_val = args.data[col_idx].GetValue(row_idx);
-      if (feature_val.IsNull()) {
-        throw InvalidInputException("Feature values cannot be NULL");
-      }
-      float feature_float;
-      switch (feature_val.type().id()) {
-      case LogicalTypeId::FLOAT: feature_float = feature_val.GetValue<float>(); break;
-      case LogicalTypeId::DOUBLE: feature_float = static_cast<float>(feature_val.GetValue<double>()); break;
-      case LogicalTypeId::INTEGER: feature_float = static_cast<float>(feature_val.GetValue<int32_t>()); break;
-      case LogicalTypeId::BIGINT: feature_float = static_cast<float>(feature_val.GetValue<int64_t>()); break;
-      case LogicalTypeId::DECIMAL: {
-        // Cast DECIMAL to DOUBLE then to float for inference
-        auto casted = feature_val.DefaultCastAs(LogicalType::DOUBLE);
-        feature_float = static_cast<float>(casted.GetValue<double>());
-        break;
-      }
-      default: throw InvalidInputException("Unsupported feature type: " + feature_val.type().ToString());
-      }
-      features.push_back(feature_float);
-    }
+static void RenameModel(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 2 && args.ColumnCount() != 3) {
+    throw InvalidInputException("infera_rename_model(old_name, new_name[, replace]) expects 2 or 3 arguments");
+  }
+  if (args.size() == 0) { return; }
+  auto old_name = args.data[0].GetValue(0);
+  auto new_name = args.data[1].GetValue(0);
+  if (old_name.IsNull() || new_name.IsNull()) {
+    throw InvalidInputException("Model names cannot be NULL");
+  }
+  bool replace = false;
+  if (args.ColumnCount() == 3) {
+    auto replace_val = args.data[2].GetValue(0);
+    replace = !replace_val.IsNull() && replace_val.GetValue<bool>();
   }
+  std::string old_name_str = old_name.ToString();
+  std::string new_name_str = new_name.ToString();
+  int rc = infera::infera_rename_model(old_name_str.c_str(), new_name_str.c_str(), replace);
+  bool success = rc == 0;
+  if (!success) {
+    throw InvalidInputException("Failed to rename model '" + old_name_str + "': " + GetInferaError());
+  }
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<bool>(result)[0] = success;
+  ConstantVector::SetNull(result, false);
 }
 
 /**
- * @brief Validates the input arguments and extracts the model name.
+ * @brief Implements the `infera_load_model_ns(namespace, name, path)` SQL function.
  *
- * Checks that there are at least two arguments and that the first argument (the
- * model name) is not null.
+ * Loads an ONNX model into a namespace, where it is stored as `namespace/name`.
  *
- * @param args The input DataChunk.
- * @param func_name The name of the calling function for error messages.
- * @return The model name as a string.
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
  */
-static std::string ValidateAndGetModelName(DataChunk &args, const std::string &func_name) {
-  if (args.ColumnCount() < 2) {
-    throw InvalidInputException(func_name + "(model_name, feature1, ...) requires at least 2 arguments");
+static void LoadModelNs(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 3) {
+    throw InvalidInputException("infera_load_model_ns(namespace, model_name, path) expects exactly 3 arguments");
   }
-  auto model_name_val = args.data[0].GetValue(0);
-  if (model_name_val.IsNull()) {
-    throw InvalidInputException("Model name cannot be NULL");
+  if (args.size() == 0) { return; }
+  auto ns = args.data[0].GetValue(0);
+  auto model_name = args.data[1].GetValue(0);
+  auto path = args.data[2].GetValue(0);
+  if (ns.IsNull() || model_name.IsNull() || path.IsNull()) {
+    throw InvalidInputException("Namespace, model name, and path cannot be NULL");
   }
-  return model_name_val.ToString();
+  std::string ns_str = ns.ToString();
+  std::string model_name_str = model_name.ToString();
+  std::string path_str = path.ToString();
+  int rc = infera::infera_load_model_ns(ns_str.c_str(), model_name_str.c_str(), path_str.c_str());
+  bool success = rc == 0;
+  if (!success) {
+    throw InvalidInputException("Failed to load model '" + ns_str + "/" + model_name_str + "': " + GetInferaError());
+  }
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<bool>(result)[0] = success;
+  ConstantVector::SetNull(result, false);
 }
 
 /**
- * @brief Implements the `infera_predict(name, ...features)` SQL function.
+ * @brief Implements the `infera_unload_namespace(namespace)` SQL function.
  *
- * Extracts features, runs inference using the Rust core, and populates the
- * result vector with a single float prediction per row.
+ * Unloads every model in a namespace and returns how many were unloaded.
  *
  * @param args The input arguments from DuckDB.
  * @param state The expression state.
  * @param result The result vector to populate.
  */
-static void Predict(DataChunk &args, ExpressionState &state, Vector &result) {
-  if (args.size() == 0) { return; }
-  std::string model_name_str = ValidateAndGetModelName(args, "infera_predict");
-
-  const idx_t batch_size = args.size();
-  const idx_t feature_count = args.ColumnCount() - 1;
-
-  std::vector<float> features;
-  ExtractFeatures(args, features);
-
-  infera::InferaInferenceResult res = infera::infera_predict(model_name_str.c_str(), features.data(), batch_size, feature_count);
-  if (res.status != 0) {
-    infera::infera_free_result(res);
-    throw InvalidInputException("Inference failed for model '" + model_name_str + "': " + GetInferaError());
+static void UnloadNamespace(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 1) {
+    throw InvalidInputException("infera_unload_namespace(namespace) expects exactly 1 argument");
   }
-  if (res.rows != batch_size || res.cols != 1) {
-    std::string err_msg = StringUtil::Format("Model output shape mismatch. Expected (%d, 1), but got (%d, %d).", batch_size, res.rows, res.cols);
-    infera::infera_free_result(res);
-    throw InvalidInputException(err_msg);
+  if (args.size() == 0) { return; }
+  auto ns = args.data[0].GetValue(0);
+  if (ns.IsNull()) {
+    throw InvalidInputException("Namespace cannot be NULL");
   }
-  result.SetVectorType(VectorType::FLAT_VECTOR);
-  auto result_data = GetFlatVectorDataWritable<float>(result);
-  for (idx_t i = 0; i < batch_size; i++) {
-    result_data[i] = res.data[i];
+  std::string ns_str = ns.ToString();
+  int32_t count = infera::infera_unload_namespace(ns_str.c_str());
+  if (count < 0) {
+    throw InvalidInputException("Failed to unload namespace '" + ns_str + "': " + GetInferaError());
   }
-  infera::infera_free_result(res);
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<int32_t>(result)[0] = count;
+  ConstantVector::SetNull(result, false);
 }
 
 /**
- * @brief Implements the `infera_predict_from_blob(name, blob)` SQL function.
+ * @brief Implements the `infera_unload_group(group_id)` SQL function.
  *
- * Runs inference on raw blob data. The result is a list of floats.
+ * Unloads the models of an autoload group and returns how many were unloaded.
  *
  * @param args The input arguments from DuckDB.
  * @param state The expression state.
  * @param result The result vector to populate.
  */
-static void PredictFromBlob(DataChunk &args, ExpressionState &state, Vector &result) {
-  if (args.ColumnCount() != 2) {
-    throw InvalidInputException("infera_predict_from_blob(model_name, input_blob) requires 2 arguments");
+static void UnloadGroup(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 1) {
+    throw InvalidInputException("infera_unload_group(group_id) expects exactly 1 argument");
   }
   if (args.size() == 0) { return; }
-  result.SetVectorType(VectorType::FLAT_VECTOR);
-  for (idx_t i = 0; i < args.size(); i++) {
-    auto model_name_val = args.data[0].GetValue(i);
-    auto blob_val = args.data[1].GetValue(i);
-    if (model_name_val.IsNull() || blob_val.IsNull()) {
-      result.SetValue(i, Value());
-      continue;
-    }
-    std::string model_name_str = model_name_val.ToString();
-    string_t blob_str_t = blob_val.GetValueUnsafe<string_t>();
-    auto blob_ptr = reinterpret_cast<const uint8_t *>(blob_str_t.GetDataUnsafe());
-    auto blob_len = blob_str_t.GetSize();
-    infera::InferaInferenceResult res = infera::infera_predict_from_blob(model_name_str.c_str(), blob_ptr, blob_len);
-    if (res.status != 0) {
-      infera::infera_free_result(res);
-      throw InvalidInputException("Inference failed for model '" + model_name_str + "': " + GetInferaError());
-    }
-    std::vector<Value> elems;
-    elems.reserve(res.len);
-    for (size_t j = 0; j < res.len; ++j) {
-      elems.emplace_back(Value::FLOAT(res.data[j]));
-    }
-    result.SetValue(i, Value::LIST(std::move(elems)));
-    infera::infera_free_result(res);
+  auto group_val = args.data[0].GetValue(0);
+  if (group_val.IsNull()) {
+    throw InvalidInputException("Group ID cannot be NULL");
   }
-  VerifyVectorCompat(result, args.size());
+  int64_t group_id = group_val.GetValue<int64_t>();
+  if (group_id < 0) {
+    throw InvalidInputException("Group ID must be non-negative");
+  }
+  int32_t count = infera::infera_unload_group(static_cast<uint64_t>(group_id));
+  if (count < 0) {
+    throw InvalidInputException("Failed to unload group " + std::to_string(group_id) + ": " + GetInferaError());
+  }
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<int32_t>(result)[0] = count;
+  ConstantVector::SetNull(result, false);
 }
 
 /**
- * @brief Implements the `infera_get_loaded_models()` SQL function.
+ * @brief Implements the `infera_list_groups()` SQL function.
  *
- * Returns a JSON array of the names of all currently loaded models.
+ * Returns a JSON array with the ID, directory, and models of each autoload group.
  *
  * @param args The input arguments from DuckDB.
  * @param state The expression state.
  * @param result The result vector to populate.
  */
-static void GetLoadedModels(DataChunk &args, ExpressionState &state, Vector &result) {
-  char *models_json_c = infera::infera_get_loaded_models();
-  // Guard against the null_mut() path in the Rust fallback (should not occur in
-  // practice, but infera_get_loaded_models documents that it can return NULL).
-  std::string models_json = models_json_c ? std::string(models_json_c) : std::string("[]");
-  infera::infera_free(models_json_c);
+static void ListGroups(DataChunk &args, ExpressionState &state, Vector &result) {
+  char *groups_json = infera::infera_list_groups();
   result.SetVectorType(VectorType::CONSTANT_VECTOR);
-  ConstantVector::GetData<string_t>(result)[0] = StringVector::AddString(result, models_json);
+  ConstantVector::GetData<string_t>(result)[0] = StringVector::AddString(result, groups_json);
   ConstantVector::SetNull(result, false);
+  infera::infera_free_string(groups_json);
 }
 
-static void IsModelLoaded(DataChunk &args, ExpressionState &state, Vector &result) {
-  if (args.ColumnCount() != 1) {
-    throw InvalidInputException("infera_is_model_loaded(model_name) expects exactly 1 argument");
+/**
+ * @brief Implements the `infera_watch_autoload_dir(path, poll_interval_secs)` SQL function.
+ *
+ * Autoloads the directory and starts a background thread that re-syncs it every
+ * `poll_interval_secs` seconds. Returns the JSON report of the first sync.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void WatchAutoloadDir(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 2) {
+    throw InvalidInputException("infera_watch_autoload_dir(path, poll_interval_secs) expects exactly 2 arguments");
   }
   if (args.size() == 0) { return; }
-  auto model_name_val = args.data[0].GetValue(0);
-  if (model_name_val.IsNull()) {
-    throw InvalidInputException("Model name cannot be NULL");
+  auto path_val = args.data[0].GetValue(0);
+  auto interval_val = args.data[1].GetValue(0);
+  if (path_val.IsNull() || interval_val.IsNull()) {
+    throw InvalidInputException("Path and poll interval cannot be NULL");
   }
-  std::string model_name_str = model_name_val.ToString();
-  char *models_json_c = infera::infera_get_loaded_models();
-  std::string models_json = models_json_c ? std::string(models_json_c) : std::string();
-  infera::infera_free(models_json_c);
-
-  std::string needle = "\"" + model_name_str + "\""; // search for quoted name
-  bool found = models_json.find(needle) != std::string::npos;
-
+  int64_t interval = interval_val.GetValue<int64_t>();
+  if (interval <= 0) {
+    throw InvalidInputException("Poll interval must be greater than zero");
+  }
+  std::string path_str = path_val.ToString();
+  char *result_json_c = infera::infera_watch_autoload_dir(path_str.c_str(), static_cast<uint64_t>(interval));
   result.SetVectorType(VectorType::CONSTANT_VECTOR);
-  ConstantVector::GetData<bool>(result)[0] = found;
+  ConstantVector::GetData<string_t>(result)[0] = StringVector::AddString(result, result_json_c);
   ConstantVector::SetNull(result, false);
+  infera::infera_free_string(result_json_c);
 }
 
 /**
- * @brief Implements the `infera_predict_multi(name, ...features)` SQL function.
+ * @brief Implements the `infera_unwatch_autoload_dir(path)` SQL function.
  *
- * Similar to `Predict`, but returns the model's output as a JSON-encoded
- * string array, supporting models with multi-value outputs.
+ * Stops watching a directory. Its models stay loaded.
  *
  * @param args The input arguments from DuckDB.
  * @param state The expression state.
  * @param result The result vector to populate.
  */
-static void PredictMulti(DataChunk &args, ExpressionState &state, Vector &result) {
-  if (args.size() == 0) { return; }
-  std::string model_name_str = ValidateAndGetModelName(args, "infera_predict_multi");
-
-  const idx_t batch_size = args.size();
-  const idx_t feature_count = args.ColumnCount() - 1;
-
-  std::vector<float> features;
-  ExtractFeatures(args, features);
-
-  infera::InferaInferenceResult res = infera::infera_predict(model_name_str.c_str(), features.data(), batch_size, feature_count);
-  if (res.status != 0) {
-    infera::infera_free_result(res);
-    throw InvalidInputException("Inference failed for model '" + model_name_str + "': " + GetInferaError());
+static void UnwatchAutoloadDir(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 1) {
+    throw InvalidInputException("infera_unwatch_autoload_dir(path) expects exactly 1 argument");
   }
-  if (res.rows != batch_size) {
-    std::string err_msg = StringUtil::Format("Model output row count mismatch. Expected %d, but got %d.", batch_size, res.rows);
-    infera::infera_free_result(res);
-    throw InvalidInputException(err_msg);
+  if (args.size() == 0) { return; }
+  auto path_val = args.data[0].GetValue(0);
+  if (path_val.IsNull()) {
+    throw InvalidInputException("Path cannot be NULL");
   }
-  result.SetVectorType(VectorType::FLAT_VECTOR);
-  auto result_data = GetFlatVectorDataWritable<string_t>(result);
-  const size_t output_cols = res.cols;
-  for (idx_t row_idx = 0; row_idx < batch_size; row_idx++) {
-    std::ostringstream oss;
-    oss << "[";
-    for (size_t col_idx = 0; col_idx < output_cols; col_idx++) {
-      if (col_idx > 0) {
-        oss << ",";
-      }
-      oss << res.data[row_idx * output_cols + col_idx];
-    }
-    oss << "]";
-    result_data[row_idx] = StringVector::AddString(result, oss.str());
+  std::string path_str = path_val.ToString();
+  if (infera::infera_unwatch_autoload_dir(path_str.c_str()) != 0) {
+    throw InvalidInputException("Failed to unwatch '" + path_str + "': " + GetInferaError());
   }
-  infera::infera_free_result(res);
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<bool>(result)[0] = true;
+  ConstantVector::SetNull(result, false);
 }
 
 /**
- * @brief Implements the `infera_predict_multi_list(name, ...features)` SQL function.
+ * @brief Implements the `infera_list_model_versions(name)` SQL function.
  *
- * Similar to `PredictMulti`, but returns the model's output as a list of floats
- * instead of a JSON-encoded string.
+ * Returns a JSON array of the current and previous versions of a model,
+ * newest first.
  *
  * @param args The input arguments from DuckDB.
  * @param state The expression state.
  * @param result The result vector to populate.
  */
-static void PredictMultiList(DataChunk &args, ExpressionState &state, Vector &result) {
+static void ListModelVersions(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 1) {
+    throw InvalidInputException("infera_list_model_versions(model_name) expects exactly 1 argument");
+  }
+  if (args.size() == 0) { return; }
+  auto name_val = args.data[0].GetValue(0);
+  if (name_val.IsNull()) {
+    throw InvalidInputException("Model name cannot be NULL");
+  }
+  std::string name_str = name_val.ToString();
+  char *json = infera::infera_list_model_versions(name_str.c_str());
+  std::string json_str(json ? json : "");
+  infera::infera_free_string(json);
+  if (json_str.empty() || json_str.rfind("{\"error\"", 0) == 0) {
+    throw InvalidInputException("Failed to list versions of model '" + name_str + "': " + GetInferaError());
+  }
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<string_t>(result)[0] = StringVector::AddString(result, json_str);
+  ConstantVector::SetNull(result, false);
+}
+
+/**
+ * @brief Implements the `infera_rollback_model(name)` SQL function.
+ *
+ * Makes the previous version of a model current again.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void RollbackModel(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 1) {
+    throw InvalidInputException("infera_rollback_model(model_name) expects exactly 1 argument");
+  }
+  if (args.size() == 0) { return; }
+  auto name_val = args.data[0].GetValue(0);
+  if (name_val.IsNull()) {
+    throw InvalidInputException("Model name cannot be NULL");
+  }
+  std::string name_str = name_val.ToString();
+  if (infera::infera_rollback_model(name_str.c_str()) != 0) {
+    throw InvalidInputException("Failed to roll back model '" + name_str + "': " + GetInferaError());
+  }
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<bool>(result)[0] = true;
+  ConstantVector::SetNull(result, false);
+}
+
+/**
+ * @brief Implements the `infera_prune_model_versions(name, keep)` SQL function.
+ *
+ * Drops all but the `keep` newest previous versions of a model.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void PruneModelVersions(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 2) {
+    throw InvalidInputException("infera_prune_model_versions(model_name, keep) expects exactly 2 arguments");
+  }
+  if (args.size() == 0) { return; }
+  auto name_val = args.data[0].GetValue(0);
+  auto keep_val = args.data[1].GetValue(0);
+  if (name_val.IsNull() || keep_val.IsNull()) {
+    throw InvalidInputException("Model name and keep cannot be NULL");
+  }
+  int64_t keep = keep_val.GetValue<int64_t>();
+  if (keep < 0) {
+    throw InvalidInputException("keep must be non-negative");
+  }
+  std::string name_str = name_val.ToString();
+  int32_t removed = infera::infera_prune_model_versions(name_str.c_str(), static_cast<size_t>(keep));
+  if (removed < 0) {
+    throw InvalidInputException("Failed to prune versions of model '" + name_str + "': " + GetInferaError());
+  }
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<int32_t>(result)[0] = removed;
+  ConstantVector::SetNull(result, false);
+}
+
+/**
+ * @brief Implements the `infera_set_canary(alias, candidate, fallback, max_error_rate, window)` SQL function.
+ *
+ * Points an alias at a candidate model that falls back to another model if
+ * too many of its recent predictions fail.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void SetCanary(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 5) {
+    throw InvalidInputException("infera_set_canary(alias, candidate, fallback, max_error_rate, window) expects exactly 5 arguments");
+  }
+  if (args.size() == 0) { return; }
+  for (idx_t i = 0; i < 5; i++) {
+    if (args.data[i].GetValue(0).IsNull()) {
+      throw InvalidInputException("infera_set_canary arguments cannot be NULL");
+    }
+  }
+  std::string alias_str = args.data[0].GetValue(0).ToString();
+  std::string candidate_str = args.data[1].GetValue(0).ToString();
+  std::string fallback_str = args.data[2].GetValue(0).ToString();
+  double max_error_rate = args.data[3].GetValue(0).GetValue<double>();
+  int64_t window = args.data[4].GetValue(0).GetValue<int64_t>();
+  if (window <= 0) {
+    throw InvalidInputException("window must be greater than zero");
+  }
+  if (infera::infera_set_canary(alias_str.c_str(), candidate_str.c_str(), fallback_str.c_str(), max_error_rate,
+                                static_cast<size_t>(window)) != 0) {
+    throw InvalidInputException("Failed to set canary '" + alias_str + "': " + GetInferaError());
+  }
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<bool>(result)[0] = true;
+  ConstantVector::SetNull(result, false);
+}
+
+/**
+ * @brief Implements the `infera_promote_canary(alias)` SQL function.
+ *
+ * Makes the candidate of a canary the permanent target of its alias.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void PromoteCanary(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 1) {
+    throw InvalidInputException("infera_promote_canary(alias) expects exactly 1 argument");
+  }
+  if (args.size() == 0) { return; }
+  auto alias_val = args.data[0].GetValue(0);
+  if (alias_val.IsNull()) {
+    throw InvalidInputException("Alias cannot be NULL");
+  }
+  std::string alias_str = alias_val.ToString();
+  if (infera::infera_promote_canary(alias_str.c_str()) != 0) {
+    throw InvalidInputException("Failed to promote canary '" + alias_str + "': " + GetInferaError());
+  }
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<bool>(result)[0] = true;
+  ConstantVector::SetNull(result, false);
+}
+
+/**
+ * @brief Implements the `infera_get_canary_status(alias)` SQL function.
+ *
+ * Returns the state and statistics of a canary alias as a JSON object.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void GetCanaryStatus(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 1) {
+    throw InvalidInputException("infera_get_canary_status(alias) expects exactly 1 argument");
+  }
+  if (args.size() == 0) { return; }
+  auto alias_val = args.data[0].GetValue(0);
+  if (alias_val.IsNull()) {
+    throw InvalidInputException("Alias cannot be NULL");
+  }
+  std::string alias_str = alias_val.ToString();
+  char *json = infera::infera_get_canary_status(alias_str.c_str());
+  std::string json_str(json ? json : "");
+  infera::infera_free_string(json);
+  if (json_str.empty() || json_str.rfind("{\"error\"", 0) == 0) {
+    throw InvalidInputException("Failed to get status of canary '" + alias_str + "': " + GetInferaError());
+  }
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<string_t>(result)[0] = StringVector::AddString(result, json_str);
+  ConstantVector::SetNull(result, false);
+}
+
+/**
+ * @brief Implements the `infera_set_model_concurrency(name, max)` SQL function.
+ *
+ * Limits how many predictions can run on a model at once (0 removes the limit).
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void SetModelConcurrency(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 2) {
+    throw InvalidInputException("infera_set_model_concurrency(name, max) expects exactly 2 arguments");
+  }
+  if (args.size() == 0) { return; }
+  auto name_val = args.data[0].GetValue(0);
+  auto max_val = args.data[1].GetValue(0);
+  if (name_val.IsNull() || max_val.IsNull()) {
+    throw InvalidInputException("infera_set_model_concurrency arguments cannot be NULL");
+  }
+  std::string name_str = name_val.ToString();
+  int64_t max = max_val.GetValue<int64_t>();
+  if (max < 0) {
+    throw InvalidInputException("max must not be negative");
+  }
+  if (infera::infera_set_model_concurrency(name_str.c_str(), static_cast<size_t>(max)) != 0) {
+    throw InvalidInputException("Failed to set concurrency limit of model '" + name_str + "': " + GetInferaError());
+  }
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<bool>(result)[0] = true;
+  ConstantVector::SetNull(result, false);
+}
+
+/**
+ * @brief Implements the `infera_set_postprocessor(name, spec)` SQL function.
+ *
+ * Attaches postprocessing (scaling, an activation, clipping, and rounding) to a model's
+ * output, or detaches it if the spec is empty.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void SetPostprocessor(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 2) {
+    throw InvalidInputException("infera_set_postprocessor(name, spec) expects exactly 2 arguments");
+  }
+  if (args.size() == 0) { return; }
+  auto name_val = args.data[0].GetValue(0);
+  auto spec_val = args.data[1].GetValue(0);
+  if (name_val.IsNull() || spec_val.IsNull()) {
+    throw InvalidInputException("infera_set_postprocessor arguments cannot be NULL");
+  }
+  std::string name_str = name_val.ToString();
+  std::string spec_str = spec_val.ToString();
+  if (infera::infera_set_postprocessor(name_str.c_str(), spec_str.c_str()) != 0) {
+    throw InvalidInputException("Failed to set postprocessor of model '" + name_str + "': " + GetInferaError());
+  }
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<bool>(result)[0] = true;
+  ConstantVector::SetNull(result, false);
+}
+
+/**
+ * @brief Implements the `infera_unload_model(name)` SQL function.
+ *
+ * Unloads a model from the Infera engine by its name.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void UnloadModel(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 1) {
+    throw InvalidInputException("infera_unload_model(model_name) expects exactly 1 argument");
+  }
+  if (args.size() == 0) { return; }
+  auto model_name = args.data[0].GetValue(0);
+  if (model_name.IsNull()) {
+    throw InvalidInputException("Model name cannot be NULL");
+  }
+  std::string model_name_str = model_name.ToString();
+  int rc = infera::infera_unload_model(model_name_str.c_str());
+  if (rc != 0) {
+    std::string err = GetInferaError();
+    // Treat model-not-found as benign idempotent success returning true
+    if (err.rfind("Model not found:", 0) != 0) {
+      throw InvalidInputException("Failed to unload model '" + model_name_str + "': " + err);
+    }
+  }
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<bool>(result)[0] = true; // always true for idempotency & verification stability
+  ConstantVector::SetNull(result, false);
+}
+
+/**
+ * @brief Implements the `infera_model_exists(model_name)` SQL function.
+ *
+ * Returns whether a model is loaded under the given name.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void ModelExists(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 1) {
+    throw InvalidInputException("infera_model_exists(model_name) expects exactly 1 argument");
+  }
+  if (args.size() == 0) { return; }
+  auto model_name = args.data[0].GetValue(0);
+  if (model_name.IsNull()) {
+    throw InvalidInputException("Model name cannot be NULL");
+  }
+  std::string model_name_str = model_name.ToString();
+  int32_t rc = infera::infera_model_exists(model_name_str.c_str());
+  if (rc < 0) {
+    throw InvalidInputException("Failed to check model '" + model_name_str + "': " + GetInferaError());
+  }
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<bool>(result)[0] = rc == 1;
+  ConstantVector::SetNull(result, false);
+}
+
+/**
+ * @brief Extracts numerical features from a DataChunk.
+ *
+ * Iterates over the input DataChunk (skipping the first column, which is the
+ * model name) and converts all values to floats, storing them in a vector.
+ *
+ * @param args The input DataChunk containing the features.
+ * @param features The output vector to store the extracted float features.
+ */
+static void ExtractFeatures(DataChunk &args, std::vector<float> &features) {
+  const idx_t batch_size = args.size();
+  const idx_t feature_count = args.ColumnCount() - 1;
+  features.reserve(batch_size * feature_count);
+
+  for (idx_t row_idx = 0; row_idx < batch_size; ++row_idx) {
+    for (idx_t col_idx = 1; col_idx <= feature_count; ++col_idx) {
+      auto feature_val = args.data[col_idx].GetValue(row_idx);
+      if (feature_val.IsNull()) {
+        throw InvalidInputException("Feature values cannot be NULL");
+      }
+      float feature_float;
+      switch (feature_val.type().id()) {
+      case LogicalTypeId::FLOAT: feature_float = feature_val.GetValue<float>(); break;
+      case LogicalTypeId::DOUBLE: feature_float = static_cast<float>(feature_val.GetValue<double>()); break;
+      case LogicalTypeId::INTEGER: feature_float = static_cast<float>(feature_val.GetValue<int32_t>()); break;
+      case LogicalTypeId::BIGINT: feature_float = static_cast<float>(feature_val.GetValue<int64_t>()); break;
+      case LogicalTypeId::DECIMAL: {
+        // Cast DECIMAL to DOUBLE then to float for inference
+        auto casted = feature_val.DefaultCastAs(LogicalType::DOUBLE);
+        feature_float = static_cast<float>(casted.GetValue<double>());
+        break;
+      }
+      default: throw InvalidInputException("Unsupported feature type: " + feature_val.type().ToString());
+      }
+      features.push_back(feature_float);
+    }
+  }
+}
+
+/**
+ * @brief Validates the input arguments and extracts the model name.
+ *
+ * Checks that there are at least two arguments and that the first argument (the
+ * model name) is not null.
+ *
+ * @param args The input DataChunk.
+ * @param func_name The name of the calling function for error messages.
+ * @return The model name as a string.
+ */
+static std::string ValidateAndGetModelName(DataChunk &args, const std::string &func_name) {
+  if (args.ColumnCount() < 2) {
+    throw InvalidInputException(func_name + "(model_name, feature1, ...) requires at least 2 arguments");
+  }
+  auto model_name_val = args.data[0].GetValue(0);
+  if (model_name_val.IsNull()) {
+    throw InvalidInputException("Model name cannot be NULL");
+  }
+  return model_name_val.ToString();
+}
+
+/**
+ * @brief Implements the `infera_predict(name, ...features)` SQL function.
+ *
+ * Extracts features, runs inference using the Rust core, and populates the
+ * result vector with a single float prediction per row.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void Predict(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.size() == 0) { return; }
+  std::string model_name_str = ValidateAndGetModelName(args, "infera_predict");
+
+  const idx_t batch_size = args.size();
+  const idx_t feature_count = args.ColumnCount() - 1;
+
+  std::vector<float> features;
+  ExtractFeatures(args, features);
+
+  infera::InferaInferenceResult res = infera::infera_predict(model_name_str.c_str(), features.data(), batch_size, feature_count);
+  if (res.status != 0) {
+    infera::infera_free_result(res);
+    throw InvalidInputException("Inference failed for model '" + model_name_str + "': " + GetInferaError());
+  }
+  if (res.rows != batch_size || res.cols != 1) {
+    std::string err_msg = StringUtil::Format("Model output shape mismatch. Expected (%d, 1), but got (%d, %d).", batch_size, res.rows, res.cols);
+    infera::infera_free_result(res);
+    throw InvalidInputException(err_msg);
+  }
+  result.SetVectorType(VectorType::FLAT_VECTOR);
+  auto result_data = GetFlatVectorDataWritable<float>(result);
+  for (idx_t i = 0; i < batch_size; i++) {
+    result_data[i] = res.data[i];
+  }
+  infera::infera_free_result(res);
+}
+
+/**
+ * @brief Implements the `infera_predict_from_blob(name, blob)` SQL function.
+ *
+ * Runs inference on raw blob data. The result is a list of floats.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void PredictFromBlob(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 2) {
+    throw InvalidInputException("infera_predict_from_blob(model_name, input_blob) requires 2 arguments");
+  }
+  if (args.size() == 0) { return; }
+  result.SetVectorType(VectorType::FLAT_VECTOR);
+  for (idx_t i = 0; i < args.size(); i++) {
+    auto model_name_val = args.data[0].GetValue(i);
+    auto blob_val = args.data[1].GetValue(i);
+    if (model_name_val.IsNull() || blob_val.IsNull()) {
+      result.SetValue(i, Value());
+      continue;
+    }
+    std::string model_name_str = model_name_val.ToString();
+    string_t blob_str_t = blob_val.GetValueUnsafe<string_t>();
+    auto blob_ptr = reinterpret_cast<const uint8_t *>(blob_str_t.GetDataUnsafe());
+    auto blob_len = blob_str_t.GetSize();
+    infera::InferaInferenceResult res = infera::infera_predict_from_blob(model_name_str.c_str(), blob_ptr, blob_len);
+    if (res.status != 0) {
+      infera::infera_free_result(res);
+      throw InvalidInputException("Inference failed for model '" + model_name_str + "': " + GetInferaError());
+    }
+    std::vector<Value> elems;
+    elems.reserve(res.len);
+    for (size_t j = 0; j < res.len; ++j) {
+      elems.emplace_back(Value::FLOAT(res.data[j]));
+    }
+    result.SetValue(i, Value::LIST(std::move(elems)));
+    infera::infera_free_result(res);
+  }
+  VerifyVectorCompat(result, args.size());
+}
+
+/**
+ * @brief Implements the `infera_predict_to_blob(name, ...features)` SQL function.
+ *
+ * Runs inference on a batch of rows and returns each row's output as a BLOB of
+ * little-endian float bytes, the counterpart of `infera_predict_from_blob`.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void PredictToBlob(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.size() == 0) { return; }
+  std::string model_name_str = ValidateAndGetModelName(args, "infera_predict_to_blob");
+
+  const idx_t batch_size = args.size();
+  const idx_t feature_count = args.ColumnCount() - 1;
+
+  std::vector<float> features;
+  ExtractFeatures(args, features);
+
+  infera::InferaBlob blob;
+  int32_t status = infera::infera_predict_to_blob(model_name_str.c_str(), features.data(), batch_size, feature_count, false, &blob);
+  if (status != 0) {
+    throw InvalidInputException("Inference failed for model '" + model_name_str + "': " + GetInferaError());
+  }
+  if (blob.len % batch_size != 0) {
+    std::string err_msg = StringUtil::Format("Model output of %d bytes cannot be split into %d rows.", blob.len, batch_size);
+    infera::infera_free_blob(blob);
+    throw InvalidInputException(err_msg);
+  }
+  result.SetVectorType(VectorType::FLAT_VECTOR);
+  auto result_data = GetFlatVectorDataWritable<string_t>(result);
+  const size_t row_len = blob.len / batch_size;
+  for (idx_t row_idx = 0; row_idx < batch_size; row_idx++) {
+    result_data[row_idx] = StringVector::AddStringOrBlob(result, reinterpret_cast<const char *>(blob.ptr + row_idx * row_len), row_len);
+  }
+  infera::infera_free_blob(blob);
+}
+
+/**
+ * @brief Implements the `infera_get_loaded_models()` SQL function.
+ *
+ * Returns a JSON array of the names of all currently loaded models.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void GetLoadedModels(DataChunk &args, ExpressionState &state, Vector &result) {
+  char *models_json_c = infera::infera_get_loaded_models();
+  // Guard against the null_mut() path in the Rust fallback (should not occur in
+  // practice, but infera_get_loaded_models documents that it can return NULL).
+  std::string models_json = models_json_c ? std::string(models_json_c) : std::string("[]");
+  infera::infera_free_string(models_json_c);
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<string_t>(result)[0] = StringVector::AddString(result, models_json);
+  ConstantVector::SetNull(result, false);
+}
+
+/**
+ * @brief Implements the `infera_list_models_detailed()` SQL function.
+ *
+ * Returns a JSON array describing each loaded model with its namespace, autoload
+ * group, and the aliases pointing at it.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void ListModelsDetailed(DataChunk &args, ExpressionState &state, Vector &result) {
+  char *models_json_c = infera::infera_list_models_detailed();
+  std::string models_json = models_json_c ? std::string(models_json_c) : std::string("[]");
+  infera::infera_free_string(models_json_c);
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<string_t>(result)[0] = StringVector::AddString(result, models_json);
+  ConstantVector::SetNull(result, false);
+}
+
+/**
+ * @brief Implements the `infera_get_loaded_models_ns(namespace)` SQL function.
+ *
+ * Returns a JSON array of the names of the models loaded in a namespace.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void GetLoadedModelsNs(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 1) {
+    throw InvalidInputException("infera_get_loaded_models_ns(namespace) expects exactly 1 argument");
+  }
+  if (args.size() == 0) { return; }
+  auto ns = args.data[0].GetValue(0);
+  if (ns.IsNull()) {
+    throw InvalidInputException("Namespace cannot be NULL");
+  }
+  std::string ns_str = ns.ToString();
+  char *models_json_c = infera::infera_get_loaded_models_ns(ns_str.c_str());
+  std::string models_json = models_json_c ? std::string(models_json_c) : std::string();
+  if (models_json_c) {
+    infera::infera_free_string(models_json_c);
+  }
+  if (models_json.empty() || models_json.find("\"error\"") != std::string::npos) {
+    throw InvalidInputException("Failed to list models in namespace '" + ns_str + "': " + GetInferaError());
+  }
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<string_t>(result)[0] = StringVector::AddString(result, models_json);
+  ConstantVector::SetNull(result, false);
+}
+
+static void IsModelLoaded(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 1) {
+    throw InvalidInputException("infera_is_model_loaded(model_name) expects exactly 1 argument");
+  }
+  if (args.size() == 0) { return; }
+  auto model_name_val = args.data[0].GetValue(0);
+  if (model_name_val.IsNull()) {
+    throw InvalidInputException("Model name cannot be NULL");
+  }
+  std::string model_name_str = model_name_val.ToString();
+  char *models_json_c = infera::infera_get_loaded_models();
+  std::string models_json = models_json_c ? std::string(models_json_c) : std::string();
+  infera::infera_free_string(models_json_c);
+
+  std::string needle = "\"" + model_name_str + "\""; // search for quoted name
+  bool found = models_json.find(needle) != std::string::npos;
+
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<bool>(result)[0] = found;
+  ConstantVector::SetNull(result, false);
+}
+
+/**
+ * @brief Implements the `infera_predict_multi(name, ...features)` SQL function.
+ *
+ * Similar to `Predict`, but returns the model's output as a JSON-encoded
+ * string array, supporting models with multi-value outputs.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void PredictMulti(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.size() == 0) { return; }
+  std::string model_name_str = ValidateAndGetModelName(args, "infera_predict_multi");
+
+  const idx_t batch_size = args.size();
+  const idx_t feature_count = args.ColumnCount() - 1;
+
+  std::vector<float> features;
+  ExtractFeatures(args, features);
+
+  infera::InferaInferenceResult res = infera::infera_predict(model_name_str.c_str(), features.data(), batch_size, feature_count);
+  if (res.status != 0) {
+    infera::infera_free_result(res);
+    throw InvalidInputException("Inference failed for model '" + model_name_str + "': " + GetInferaError());
+  }
+  if (res.rows != batch_size) {
+    std::string err_msg = StringUtil::Format("Model output row count mismatch. Expected %d, but got %d.", batch_size, res.rows);
+    infera::infera_free_result(res);
+    throw InvalidInputException(err_msg);
+  }
+  result.SetVectorType(VectorType::FLAT_VECTOR);
+  auto result_data = GetFlatVectorDataWritable<string_t>(result);
+  const size_t output_cols = res.cols;
+  for (idx_t row_idx = 0; row_idx < batch_size; row_idx++) {
+    std::ostringstream oss;
+    oss << "[";
+    for (size_t col_idx = 0; col_idx < output_cols; col_idx++) {
+      if (col_idx > 0) {
+        oss << ",";
+      }
+      oss << res.data[row_idx * output_cols + col_idx];
+    }
+    oss << "]";
+    result_data[row_idx] = StringVector::AddString(result, oss.str());
+  }
+  infera::infera_free_result(res);
+}
+
+/**
+ * @brief Implements the `infera_predict_multi_list(name, ...features)` SQL function.
+ *
+ * Similar to `PredictMulti`, but returns the model's output as a list of floats
+ * instead of a JSON-encoded string.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void PredictMultiList(DataChunk &args, ExpressionState &state, Vector &result) {
   if (args.size() == 0) { return; }
   std::string model_name_str = ValidateAndGetModelName(args, "infera_predict_multi_list");
 
@@ -437,30 +1076,228 @@ static void PredictMultiList(DataChunk &args, ExpressionState &state, Vector &re
   std::vector<float> features;
   ExtractFeatures(args, features);
 
-  infera::InferaInferenceResult res = infera::infera_predict(model_name_str.c_str(), features.data(), batch_size, feature_count);
-  if (res.status != 0) {
-    infera::infera_free_result(res);
-    throw InvalidInputException("Inference failed for model '" + model_name_str + "': " + GetInferaError());
+  infera::InferaInferenceResult res = infera::infera_predict(model_name_str.c_str(), features.data(), batch_size, feature_count);
+  if (res.status != 0) {
+    infera::infera_free_result(res);
+    throw InvalidInputException("Inference failed for model '" + model_name_str + "': " + GetInferaError());
+  }
+  if (res.rows != batch_size) {
+    std::string err_msg = StringUtil::Format("Model output row count mismatch. Expected %d, but got %d.", batch_size, res.rows);
+    infera::infera_free_result(res);
+    throw InvalidInputException(err_msg);
+  }
+  result.SetVectorType(VectorType::FLAT_VECTOR);
+  for (idx_t row_idx = 0; row_idx < batch_size; row_idx++) {
+    std::vector<Value> elems;
+    elems.reserve(res.cols);
+    const size_t offset = static_cast<size_t>(row_idx) * res.cols;
+    for (size_t col_idx = 0; col_idx < res.cols; col_idx++) {
+      elems.emplace_back(Value::FLOAT(res.data[offset + col_idx]));
+    }
+    result.SetValue(row_idx, Value::LIST(std::move(elems)));
+  }
+  infera::infera_free_result(res);
+  VerifyVectorCompat(result, args.size());
+}
+
+/**
+ * @brief Implements the `infera_predict_all(name, ...features)` SQL function.
+ *
+ * Runs inference one row at a time and returns a JSON array with every model
+ * output (including extra outputs requested at load time) labeled by name.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void PredictAll(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.size() == 0) { return; }
+  std::string model_name_str = ValidateAndGetModelName(args, "infera_predict_all");
+
+  const idx_t batch_size = args.size();
+  const idx_t feature_count = args.ColumnCount() - 1;
+
+  std::vector<float> features;
+  ExtractFeatures(args, features);
+
+  result.SetVectorType(VectorType::FLAT_VECTOR);
+  auto result_data = GetFlatVectorDataWritable<string_t>(result);
+  for (idx_t row_idx = 0; row_idx < batch_size; row_idx++) {
+    char *json_c = infera::infera_predict_all(model_name_str.c_str(), features.data() + row_idx * feature_count, 1, feature_count);
+    std::string json = json_c ? std::string(json_c) : std::string();
+    if (json_c) {
+      infera::infera_free_string(json_c);
+    }
+    if (json.empty() || json.rfind("{\"error\"", 0) == 0) {
+      throw InvalidInputException("Inference failed for model '" + model_name_str + "': " + GetInferaError());
+    }
+    result_data[row_idx] = StringVector::AddString(result, json);
   }
-  if (res.rows != batch_size) {
-    std::string err_msg = StringUtil::Format("Model output row count mismatch. Expected %d, but got %d.", batch_size, res.rows);
-    infera::infera_free_result(res);
-    throw InvalidInputException(err_msg);
+}
+
+/**
+ * @brief Implements the `infera_predict_named(name, inputs)` SQL function.
+ *
+ * Runs inference on inputs given as a JSON object keyed by the model's ONNX
+ * input names and returns the outputs as a JSON object keyed by output name.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void PredictNamed(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 2) {
+    throw InvalidInputException("infera_predict_named(model_name, inputs) expects exactly 2 arguments");
+  }
+  if (args.size() == 0) { return; }
+  auto model_name = args.data[0].GetValue(0);
+  if (model_name.IsNull()) {
+    throw InvalidInputException("Model name cannot be NULL");
   }
+  std::string model_name_str = model_name.ToString();
+
   result.SetVectorType(VectorType::FLAT_VECTOR);
-  for (idx_t row_idx = 0; row_idx < batch_size; row_idx++) {
+  auto result_data = GetFlatVectorDataWritable<string_t>(result);
+  for (idx_t row_idx = 0; row_idx < args.size(); row_idx++) {
+    auto inputs = args.data[1].GetValue(row_idx);
+    if (inputs.IsNull()) {
+      throw InvalidInputException("Inputs cannot be NULL");
+    }
+    std::string inputs_str = inputs.ToString();
+    char *json_c = infera::infera_predict_named(model_name_str.c_str(), inputs_str.c_str());
+    std::string json = json_c ? std::string(json_c) : std::string();
+    infera::infera_free_string(json_c);
+    if (json.empty() || json.rfind("{\"error\"", 0) == 0) {
+      throw InvalidInputException("Inference failed for model '" + model_name_str + "': " + GetInferaError());
+    }
+    result_data[row_idx] = StringVector::AddString(result, json);
+  }
+}
+
+/**
+ * @brief Implements the `infera_predict_features(name, features)` SQL function.
+ *
+ * Runs inference on a JSON feature map (or an array of them) keyed by the
+ * model's feature names and returns the output values as a list of floats.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void PredictFeatures(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 2) {
+    throw InvalidInputException("infera_predict_features(model_name, features) expects exactly 2 arguments");
+  }
+  if (args.size() == 0) { return; }
+  auto model_name = args.data[0].GetValue(0);
+  if (model_name.IsNull()) {
+    throw InvalidInputException("Model name cannot be NULL");
+  }
+  std::string model_name_str = model_name.ToString();
+
+  result.SetVectorType(VectorType::FLAT_VECTOR);
+  for (idx_t row_idx = 0; row_idx < args.size(); row_idx++) {
+    auto features = args.data[1].GetValue(row_idx);
+    if (features.IsNull()) {
+      throw InvalidInputException("Features cannot be NULL");
+    }
+    std::string features_str = features.ToString();
+    infera::InferaInferenceResult res = infera::infera_predict_features(model_name_str.c_str(), features_str.c_str());
+    if (res.status != 0) {
+      infera::infera_free_result(res);
+      throw InvalidInputException("Inference failed for model '" + model_name_str + "': " + GetInferaError());
+    }
     std::vector<Value> elems;
-    elems.reserve(res.cols);
-    const size_t offset = static_cast<size_t>(row_idx) * res.cols;
-    for (size_t col_idx = 0; col_idx < res.cols; col_idx++) {
-      elems.emplace_back(Value::FLOAT(res.data[offset + col_idx]));
+    elems.reserve(res.len);
+    for (size_t i = 0; i < res.len; i++) {
+      elems.emplace_back(Value::FLOAT(res.data[i]));
     }
-    result.SetValue(row_idx, Value::LIST(std::move(elems)));
+    infera::infera_free_result(res);
+    result.SetValue(row_idx, Value::LIST(LogicalType::FLOAT, std::move(elems)));
   }
-  infera::infera_free_result(res);
   VerifyVectorCompat(result, args.size());
 }
 
+/**
+ * @brief Implements the `infera_compare_models(name_a, name_b[, options])` SQL function.
+ *
+ * Runs both models on the same inputs and returns a JSON report with the
+ * differences between their outputs.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void CompareModels(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 2 && args.ColumnCount() != 3) {
+    throw InvalidInputException("infera_compare_models(model_a, model_b[, options]) expects 2 or 3 arguments");
+  }
+  if (args.size() == 0) { return; }
+  auto name_a = args.data[0].GetValue(0);
+  auto name_b = args.data[1].GetValue(0);
+  if (name_a.IsNull() || name_b.IsNull()) {
+    throw InvalidInputException("Model names cannot be NULL");
+  }
+  std::string name_a_str = name_a.ToString();
+  std::string name_b_str = name_b.ToString();
+  std::string options_str;
+  if (args.ColumnCount() == 3) {
+    auto options = args.data[2].GetValue(0);
+    options_str = options.IsNull() ? std::string() : options.ToString();
+  }
+  char *report_c = infera::infera_compare_models(name_a_str.c_str(), name_b_str.c_str(), options_str.c_str());
+  std::string report = report_c ? std::string(report_c) : std::string();
+  if (report_c) {
+    infera::infera_free_string(report_c);
+  }
+  if (report.empty() || report.rfind("{\"error\"", 0) == 0) {
+    throw InvalidInputException("Failed to compare models '" + name_a_str + "' and '" + name_b_str + "': " + GetInferaError());
+  }
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<string_t>(result)[0] = StringVector::AddString(result, report);
+  ConstantVector::SetNull(result, false);
+}
+
+/**
+ * @brief Implements the `infera_profile_model(name, rows, iterations)` SQL function.
+ *
+ * Runs a model repeatedly on random input with each node timed and returns a
+ * JSON report of the time spent per node and per op type.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void ProfileModel(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 3) {
+    throw InvalidInputException("infera_profile_model(model_name, rows, iterations) expects exactly 3 arguments");
+  }
+  if (args.size() == 0) { return; }
+  auto name_val = args.data[0].GetValue(0);
+  auto rows_val = args.data[1].GetValue(0);
+  auto iterations_val = args.data[2].GetValue(0);
+  if (name_val.IsNull() || rows_val.IsNull() || iterations_val.IsNull()) {
+    throw InvalidInputException("Model name, rows, and iterations cannot be NULL");
+  }
+  int64_t rows = rows_val.GetValue<int64_t>();
+  int64_t iterations = iterations_val.GetValue<int64_t>();
+  if (rows < 0 || iterations < 0) {
+    throw InvalidInputException("rows and iterations must be non-negative");
+  }
+  std::string name_str = name_val.ToString();
+  char *report_c = infera::infera_profile_model(name_str.c_str(), static_cast<size_t>(rows), static_cast<size_t>(iterations));
+  std::string report = report_c ? std::string(report_c) : std::string();
+  if (report_c) {
+    infera::infera_free_string(report_c);
+  }
+  if (report.empty() || report.rfind("{\"error\"", 0) == 0) {
+    throw InvalidInputException("Failed to profile model '" + name_str + "': " + GetInferaError());
+  }
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<string_t>(result)[0] = StringVector::AddString(result, report);
+  ConstantVector::SetNull(result, false);
+}
+
 /**
  * @brief Implements the `infera_get_model_info(name)` SQL function.
  *
@@ -485,7 +1322,7 @@ static void GetModelInfo(DataChunk &args, ExpressionState &state, Vector &result
   // Convert to std::string and free the C string immediately to avoid leaks
   std::string json_meta = json_meta_c ? std::string(json_meta_c) : std::string();
   if (json_meta_c) {
-    infera::infera_free(json_meta_c);
+    infera::infera_free_string(json_meta_c);
   }
 
   // If Rust returned an error JSON, surface it as a DuckDB error per contract/tests
@@ -498,6 +1335,66 @@ static void GetModelInfo(DataChunk &args, ExpressionState &state, Vector &result
   ConstantVector::SetNull(result, false);
 }
 
+/**
+ * @brief Implements the `infera_get_output_names(name)` SQL function.
+ *
+ * Returns the names of a model's outputs as a JSON array, in output order.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void GetOutputNames(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 1) {
+    throw InvalidInputException("infera_get_output_names(model_name) expects exactly 1 argument");
+  }
+  if (args.size() == 0) { return; }
+  auto model_name = args.data[0].GetValue(0);
+  if (model_name.IsNull()) {
+    throw InvalidInputException("Model name cannot be NULL");
+  }
+  std::string model_name_str = model_name.ToString();
+  char *json = infera::infera_get_output_names(model_name_str.c_str());
+  std::string json_str(json ? json : "");
+  infera::infera_free_string(json);
+  if (json_str.empty() || json_str.rfind("{\"error\"", 0) == 0) {
+    throw InvalidInputException("Failed to get output names for model '" + model_name_str + "': " + GetInferaError());
+  }
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<string_t>(result)[0] = StringVector::AddString(result, json_str);
+  ConstantVector::SetNull(result, false);
+}
+
+/**
+ * @brief Implements the `infera_get_model_signature(model_name)` SQL function.
+ *
+ * Returns the hex-encoded SHA-256 of the file a loaded model was loaded from.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void GetModelSignature(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 1) {
+    throw InvalidInputException("infera_get_model_signature(model_name) expects exactly 1 argument");
+  }
+  if (args.size() == 0) { return; }
+  auto model_name = args.data[0].GetValue(0);
+  if (model_name.IsNull()) {
+    throw InvalidInputException("Model name cannot be NULL");
+  }
+  std::string model_name_str = model_name.ToString();
+  char *hash = infera::infera_get_model_signature(model_name_str.c_str());
+  std::string hash_str(hash ? hash : "");
+  infera::infera_free_string(hash);
+  if (hash_str.empty() || hash_str.rfind("{\"error\"", 0) == 0) {
+    throw InvalidInputException("Failed to get signature of model '" + model_name_str + "': " + GetInferaError());
+  }
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<string_t>(result)[0] = StringVector::AddString(result, hash_str);
+  ConstantVector::SetNull(result, false);
+}
+
 /**
  * @brief Implements the `infera_clear_cache()` SQL function.
  *
@@ -518,6 +1415,132 @@ static void ClearCache(DataChunk &args, ExpressionState &state, Vector &result)
   ConstantVector::SetNull(result, false);
 }
 
+/**
+ * @brief Implements the `infera_reset_cache_stats()` SQL function.
+ *
+ * Resets the cache hit, miss, and eviction counters reported by `infera_get_cache_info()`.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void ResetCacheStats(DataChunk &args, ExpressionState &state, Vector &result) {
+  int rc = infera::infera_reset_cache_stats();
+  bool success = rc == 0;
+  if (!success) {
+    throw InvalidInputException("Failed to reset cache statistics: " + GetInferaError());
+  }
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<bool>(result)[0] = success;
+  ConstantVector::SetNull(result, false);
+}
+
+/**
+ * @brief Implements the `infera_set_cache_size_limit(bytes)` SQL function.
+ *
+ * Sets the cache size limit for the current process and immediately evicts
+ * least recently used cached models until the cache fits the new limit.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void SetCacheSizeLimit(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 1) {
+    throw InvalidInputException("infera_set_cache_size_limit(bytes) expects exactly 1 argument");
+  }
+  if (args.size() == 0) { return; }
+  auto bytes_val = args.data[0].GetValue(0);
+  if (bytes_val.IsNull()) {
+    throw InvalidInputException("Cache size limit cannot be NULL");
+  }
+  int64_t bytes = bytes_val.GetValue<int64_t>();
+  if (bytes < 0) {
+    throw InvalidInputException("Cache size limit must be non-negative");
+  }
+  int rc = infera::infera_set_cache_size_limit(static_cast<uint64_t>(bytes));
+  bool success = rc == 0;
+  if (!success) {
+    throw InvalidInputException("Failed to set cache size limit: " + GetInferaError());
+  }
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<bool>(result)[0] = success;
+  ConstantVector::SetNull(result, false);
+}
+
+/**
+ * @brief Implements the `infera_set_offline(offline)` SQL function.
+ *
+ * Turns offline mode on or off for the current process. While offline, remote
+ * models are only served from the cache and never downloaded.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void SetOffline(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 1) {
+    throw InvalidInputException("infera_set_offline(offline) expects exactly 1 argument");
+  }
+  if (args.size() == 0) { return; }
+  auto offline_val = args.data[0].GetValue(0);
+  if (offline_val.IsNull()) {
+    throw InvalidInputException("Offline flag cannot be NULL");
+  }
+  infera::infera_set_offline(offline_val.GetValue<bool>());
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<bool>(result)[0] = true;
+  ConstantVector::SetNull(result, false);
+}
+
+/**
+ * @brief Implements the `infera_add_trusted_key(key)` SQL function.
+ *
+ * Adds an ed25519 public key (PEM or hex) to the keys trusted to sign models.
+ * Loaded models are then checked against their detached `.sig` signature.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void AddTrustedKey(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 1) {
+    throw InvalidInputException("infera_add_trusted_key(key) expects exactly 1 argument");
+  }
+  if (args.size() == 0) { return; }
+  auto key = args.data[0].GetValue(0);
+  if (key.IsNull()) {
+    throw InvalidInputException("Trusted key cannot be NULL");
+  }
+  std::string key_str = key.ToString();
+  int rc = infera::infera_add_trusted_key(key_str.c_str());
+  bool success = rc == 0;
+  if (!success) {
+    throw InvalidInputException("Failed to add trusted key: " + GetInferaError());
+  }
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<bool>(result)[0] = success;
+  ConstantVector::SetNull(result, false);
+}
+
+/**
+ * @brief Implements the `infera_get_metrics()` SQL function.
+ *
+ * Returns runtime metrics, such as per-priority queue depths and wait times, as a
+ * JSON string.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void GetMetrics(DataChunk &args, ExpressionState &state, Vector &result) {
+  char *metrics_json = infera::infera_get_metrics();
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<string_t>(result)[0] = StringVector::AddString(result, metrics_json);
+  ConstantVector::SetNull(result, false);
+  infera::infera_free_string(metrics_json);
+}
+
 /**
  * @brief Implements the `infera_get_cache_info()` SQL function.
  *
@@ -532,7 +1555,56 @@ static void GetCacheInfo(DataChunk &args, ExpressionState &state, Vector &result
   result.SetVectorType(VectorType::CONSTANT_VECTOR);
   ConstantVector::GetData<string_t>(result)[0] = StringVector::AddString(result, cache_info_json);
   ConstantVector::SetNull(result, false);
-  infera::infera_free(cache_info_json);
+  infera::infera_free_string(cache_info_json);
+}
+
+/**
+ * @brief Implements the `infera_list_cache()` SQL function.
+ *
+ * Returns a JSON array with the URL, size, and last access time of each
+ * cached model.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void ListCache(DataChunk &args, ExpressionState &state, Vector &result) {
+  char *cache_list_json = infera::infera_list_cache();
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<string_t>(result)[0] = StringVector::AddString(result, cache_list_json);
+  ConstantVector::SetNull(result, false);
+  infera::infera_free_string(cache_list_json);
+}
+
+/**
+ * @brief Implements the `infera_preview_eviction(required_bytes)` SQL function.
+ *
+ * Returns a JSON array of the cached models that would be evicted to make room
+ * for `required_bytes` more bytes under the current eviction strategy, without
+ * deleting anything.
+ *
+ * @param args The input arguments from DuckDB.
+ * @param state The expression state.
+ * @param result The result vector to populate.
+ */
+static void PreviewEviction(DataChunk &args, ExpressionState &state, Vector &result) {
+  if (args.ColumnCount() != 1) {
+    throw InvalidInputException("infera_preview_eviction(required_bytes) expects exactly 1 argument");
+  }
+  if (args.size() == 0) { return; }
+  auto bytes_val = args.data[0].GetValue(0);
+  if (bytes_val.IsNull()) {
+    throw InvalidInputException("Required bytes cannot be NULL");
+  }
+  int64_t bytes = bytes_val.GetValue<int64_t>();
+  if (bytes < 0) {
+    throw InvalidInputException("Required bytes must be non-negative");
+  }
+  char *preview_json = infera::infera_preview_eviction(static_cast<uint64_t>(bytes));
+  result.SetVectorType(VectorType::CONSTANT_VECTOR);
+  ConstantVector::GetData<string_t>(result)[0] = StringVector::AddString(result, preview_json);
+  ConstantVector::SetNull(result, false);
+  infera::infera_free_string(preview_json);
 }
 
 /**
@@ -545,7 +1617,13 @@ static void GetCacheInfo(DataChunk &args, ExpressionState &state, Vector &result
  */
 static void LoadInternal(ExtensionLoader &loader) {
   loader.RegisterFunction(InferaScalarFunction("infera_load_model", {LogicalType::VARCHAR, LogicalType::VARCHAR}, LogicalType::BOOLEAN, LoadModel, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_load_model", {LogicalType::VARCHAR, LogicalType::VARCHAR, LogicalType::VARCHAR}, LogicalType::BOOLEAN, LoadModel, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_load_model_verified", {LogicalType::VARCHAR, LogicalType::VARCHAR, LogicalType::VARCHAR}, LogicalType::BOOLEAN, LoadModelVerified, true));
   loader.RegisterFunction(InferaScalarFunction("infera_unload_model", {LogicalType::VARCHAR}, LogicalType::BOOLEAN, UnloadModel, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_rename_model", {LogicalType::VARCHAR, LogicalType::VARCHAR}, LogicalType::BOOLEAN, RenameModel, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_rename_model", {LogicalType::VARCHAR, LogicalType::VARCHAR, LogicalType::BOOLEAN}, LogicalType::BOOLEAN, RenameModel, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_load_model_ns", {LogicalType::VARCHAR, LogicalType::VARCHAR, LogicalType::VARCHAR}, LogicalType::BOOLEAN, LoadModelNs, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_unload_namespace", {LogicalType::VARCHAR}, LogicalType::INTEGER, UnloadNamespace, true));
 
   const idx_t MAX_FEATURES = 127;
   for (idx_t feature_count = 1; feature_count <= MAX_FEATURES; feature_count++) {
@@ -560,6 +1638,8 @@ static void LoadInternal(ExtensionLoader &loader) {
     loader.RegisterFunction(InferaScalarFunction("infera_predict", float_arg_types, LogicalType::FLOAT, Predict, true));
     loader.RegisterFunction(InferaScalarFunction("infera_predict_multi", float_arg_types, LogicalType::VARCHAR, PredictMulti, true));
     loader.RegisterFunction(InferaScalarFunction("infera_predict_multi_list", float_arg_types, LogicalType::LIST(LogicalType::FLOAT), PredictMultiList, true));
+    loader.RegisterFunction(InferaScalarFunction("infera_predict_all", float_arg_types, LogicalType::VARCHAR, PredictAll, true));
+    loader.RegisterFunction(InferaScalarFunction("infera_predict_to_blob", float_arg_types, LogicalType::BLOB, PredictToBlob, true));
 
     // DOUBLE overloads: DuckDB main changed how it handles DECIMAL→FLOAT implicit
     // casts at bind time, causing an internal error for DECIMAL literal inputs.
@@ -574,21 +1654,60 @@ static void LoadInternal(ExtensionLoader &loader) {
     loader.RegisterFunction(InferaScalarFunction("infera_predict", double_arg_types, LogicalType::FLOAT, Predict, true));
     loader.RegisterFunction(InferaScalarFunction("infera_predict_multi", double_arg_types, LogicalType::VARCHAR, PredictMulti, true));
     loader.RegisterFunction(InferaScalarFunction("infera_predict_multi_list", double_arg_types, LogicalType::LIST(LogicalType::FLOAT), PredictMultiList, true));
+    loader.RegisterFunction(InferaScalarFunction("infera_predict_all", double_arg_types, LogicalType::VARCHAR, PredictAll, true));
+    loader.RegisterFunction(InferaScalarFunction("infera_predict_to_blob", double_arg_types, LogicalType::BLOB, PredictToBlob, true));
   }
 
   // volatile_state=true: reads mutable model state; same reasoning as predict.
   loader.RegisterFunction(InferaScalarFunction("infera_predict_from_blob", {LogicalType::VARCHAR, LogicalType::BLOB}, LogicalType::LIST(LogicalType::FLOAT), PredictFromBlob, true));
+  // volatile_state=true: reads the live model registry.
+  loader.RegisterFunction(InferaScalarFunction("infera_model_exists", {LogicalType::VARCHAR}, LogicalType::BOOLEAN, ModelExists, true));
   loader.RegisterFunction(InferaScalarFunction("infera_get_loaded_models", {}, LogicalType::VARCHAR, GetLoadedModels, true, false));
+  loader.RegisterFunction(InferaScalarFunction("infera_list_models_detailed", {}, LogicalType::VARCHAR, ListModelsDetailed, true, false));
+  loader.RegisterFunction(InferaScalarFunction("infera_get_loaded_models_ns", {LogicalType::VARCHAR}, LogicalType::VARCHAR, GetLoadedModelsNs, true, false));
   // volatile_state=true: reads the live model registry; a model reload between
   // two calls in the same query must produce fresh metadata each time.
   loader.RegisterFunction(InferaScalarFunction("infera_get_model_info", {LogicalType::VARCHAR}, LogicalType::VARCHAR, GetModelInfo, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_get_output_names", {LogicalType::VARCHAR}, LogicalType::VARCHAR, GetOutputNames, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_get_model_signature", {LogicalType::VARCHAR}, LogicalType::VARCHAR, GetModelSignature, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_predict_named", {LogicalType::VARCHAR, LogicalType::VARCHAR}, LogicalType::VARCHAR, PredictNamed, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_predict_features", {LogicalType::VARCHAR, LogicalType::VARCHAR}, LogicalType::LIST(LogicalType::FLOAT), PredictFeatures, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_compare_models", {LogicalType::VARCHAR, LogicalType::VARCHAR}, LogicalType::VARCHAR, CompareModels, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_compare_models", {LogicalType::VARCHAR, LogicalType::VARCHAR, LogicalType::VARCHAR}, LogicalType::VARCHAR, CompareModels, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_profile_model", {LogicalType::VARCHAR, LogicalType::BIGINT, LogicalType::BIGINT}, LogicalType::VARCHAR, ProfileModel, true));
   loader.RegisterFunction(InferaScalarFunction("infera_get_version", {}, LogicalType::VARCHAR, GetVersion, false, false));
+  loader.RegisterFunction(InferaScalarFunction("infera_get_config", {}, LogicalType::VARCHAR, GetConfig, false, false));
   loader.RegisterFunction(InferaScalarFunction("infera_set_autoload_dir", {LogicalType::VARCHAR}, LogicalType::VARCHAR, SetAutoloadDir, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_set_autoload_dir", {LogicalType::VARCHAR, LogicalType::VARCHAR}, LogicalType::VARCHAR, SetAutoloadDir, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_unload_group", {LogicalType::BIGINT}, LogicalType::INTEGER, UnloadGroup, true));
+  // volatile_state=true: groups change with every autoload and group unload.
+  loader.RegisterFunction(InferaScalarFunction("infera_list_groups", {}, LogicalType::VARCHAR, ListGroups, true, false));
+  loader.RegisterFunction(InferaScalarFunction("infera_watch_autoload_dir", {LogicalType::VARCHAR, LogicalType::BIGINT}, LogicalType::VARCHAR, WatchAutoloadDir, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_unwatch_autoload_dir", {LogicalType::VARCHAR}, LogicalType::BOOLEAN, UnwatchAutoloadDir, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_list_model_versions", {LogicalType::VARCHAR}, LogicalType::VARCHAR, ListModelVersions, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_rollback_model", {LogicalType::VARCHAR}, LogicalType::BOOLEAN, RollbackModel, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_prune_model_versions", {LogicalType::VARCHAR, LogicalType::BIGINT}, LogicalType::INTEGER, PruneModelVersions, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_set_canary", {LogicalType::VARCHAR, LogicalType::VARCHAR, LogicalType::VARCHAR, LogicalType::DOUBLE, LogicalType::BIGINT}, LogicalType::BOOLEAN, SetCanary, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_promote_canary", {LogicalType::VARCHAR}, LogicalType::BOOLEAN, PromoteCanary, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_get_canary_status", {LogicalType::VARCHAR}, LogicalType::VARCHAR, GetCanaryStatus, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_set_model_concurrency", {LogicalType::VARCHAR, LogicalType::BIGINT}, LogicalType::BOOLEAN, SetModelConcurrency, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_set_postprocessor", {LogicalType::VARCHAR, LogicalType::VARCHAR}, LogicalType::BOOLEAN, SetPostprocessor, true));
   loader.RegisterFunction(InferaScalarFunction("infera_is_model_loaded", {LogicalType::VARCHAR}, LogicalType::BOOLEAN, IsModelLoaded, true, false));
   loader.RegisterFunction(InferaScalarFunction("infera_clear_cache", {}, LogicalType::BOOLEAN, ClearCache, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_reset_cache_stats", {}, LogicalType::BOOLEAN, ResetCacheStats, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_set_cache_size_limit", {LogicalType::BIGINT}, LogicalType::BOOLEAN, SetCacheSizeLimit, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_set_offline", {LogicalType::BOOLEAN}, LogicalType::BOOLEAN, SetOffline, true));
+  loader.RegisterFunction(InferaScalarFunction("infera_add_trusted_key", {LogicalType::VARCHAR}, LogicalType::BOOLEAN, AddTrustedKey, true));
   // volatile_state=true: cache state changes whenever infera_clear_cache or
-  // a remote model download updates the cache directory.
+  // a remote model download updates the cache directory, and the stats change
+  // with every cache lookup.
   loader.RegisterFunction(InferaScalarFunction("infera_get_cache_info", {}, LogicalType::VARCHAR, GetCacheInfo, true, false));
+  // volatile_state=true: the listing changes with every download and eviction.
+  loader.RegisterFunction(InferaScalarFunction("infera_list_cache", {}, LogicalType::VARCHAR, ListCache, true, false));
+  // volatile_state=true: the preview depends on the cache contents and size limit.
+  loader.RegisterFunction(InferaScalarFunction("infera_preview_eviction", {LogicalType::BIGINT}, LogicalType::VARCHAR, PreviewEviction, true, false));
+  // volatile_state=true: queue depths and wait times change as requests are served.
+  loader.RegisterFunction(InferaScalarFunction("infera_get_metrics", {}, LogicalType::VARCHAR, GetMetrics, true, false));
 }
 
 void InferaExtension::Load(ExtensionLoader &loader) { LoadInternal(loader); }
@@ -0,0 +1,55 @@
+// Records build information reported by `infera_get_version`: the target triple and the
+// resolved versions of the tract crates, which Cargo does not expose to the crate itself.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The dependencies whose resolved versions are recorded, with the environment variable
+/// each version is passed to the crate in.
+const TRACT_PACKAGES: [(&str, &str); 2] = [
+    ("tract-onnx", "INFERA_TRACT_VERSION"),
+    ("tract-core", "INFERA_TRACT_CORE_VERSION"),
+];
+
+fn main() {
+    let target = env::var("TARGET").unwrap_or_else(|_| "unknown".to_string());
+    println!("cargo:rustc-env=INFERA_BUILD_TARGET={}", target);
+
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap_or_default());
+    let lock_file = find_lock_file(&manifest_dir);
+    let lock = lock_file
+        .as_deref()
+        .and_then(|path| fs::read_to_string(path).ok());
+    for (package, var) in TRACT_PACKAGES {
+        let version = lock
+            .as_deref()
+            .and_then(|lock| locked_version(lock, package))
+            .unwrap_or_else(|| "unknown".to_string());
+        println!("cargo:rustc-env={}={}", var, version);
+    }
+    if let Some(path) = lock_file {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+}
+
+/// Returns the `Cargo.lock` of the crate's workspace, looking in the crate directory and
+/// its parents.
+fn find_lock_file(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|dir| dir.join("Cargo.lock"))
+        .find(|path| path.is_file())
+}
+
+/// Returns the version of `package` recorded in the contents of a `Cargo.lock` file.
+fn locked_version(lock: &str, package: &str) -> Option<String> {
+    let name_line = format!("name = \"{}\"", package);
+    let mut lines = lock.lines().map(str::trim);
+    lines.find(|line| *line == name_line)?;
+    lines
+        .next()?
+        .strip_prefix("version = \"")?
+        .strip_suffix('"')
+        .map(String::from)
+}
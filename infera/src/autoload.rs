@@ -0,0 +1,656 @@
+// Loads every model in a directory, tracks each directory's models as a group, and
+// watches directories to keep their groups in sync.
+
+use crate::config::LogLevel;
+use crate::engine;
+use crate::error::InferaError;
+use crate::log;
+use crate::model::{self, MODELS};
+use once_cell::sync::Lazy;
+use parking_lot::{Condvar, Mutex, RwLock};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+/// Options that control how a directory is autoloaded.
+///
+/// Options are passed as a JSON object to `infera_set_autoload_dir_with_options`.
+/// Unknown keys are ignored so callers can pass options meant for newer versions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct AutoloadOptions {
+    /// Reuse the group of an earlier autoload of the same directory, reloading only
+    /// changed models and unloading models whose files were deleted.
+    pub sync: bool,
+}
+
+impl AutoloadOptions {
+    /// Parses autoload options from a JSON object string.
+    ///
+    /// An empty string is treated as "no options".
+    pub(crate) fn from_json(json: &str) -> Result<Self, InferaError> {
+        let mut options = AutoloadOptions::default();
+        if json.trim().is_empty() {
+            return Ok(options);
+        }
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| InferaError::JsonError(e.to_string()))?;
+        let obj = value.as_object().ok_or_else(|| {
+            InferaError::JsonError("autoload options must be a JSON object".to_string())
+        })?;
+        if let Some(v) = obj.get("sync") {
+            options.sync = v
+                .as_bool()
+                .ok_or_else(|| InferaError::JsonError("\"sync\" must be a boolean".to_string()))?;
+        }
+        Ok(options)
+    }
+}
+
+/// The modification time and size of a model file when it was loaded, used to detect
+/// changed files when a directory is synced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    size: u64,
+}
+
+impl FileStamp {
+    /// Reads the stamp of the file at `path`.
+    fn of(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        Some(FileStamp {
+            modified: metadata.modified().ok(),
+            size: metadata.len(),
+        })
+    }
+}
+
+/// The models loaded by autoloading one directory.
+#[derive(Debug, Clone)]
+struct ModelGroup {
+    /// The canonical path of the directory.
+    dir: PathBuf,
+    /// The registry keys of the loaded models, with the stamp of their file.
+    members: BTreeMap<String, Option<FileStamp>>,
+}
+
+/// The groups of autoloaded models, by group ID.
+static GROUPS: Lazy<RwLock<BTreeMap<u64, ModelGroup>>> = Lazy::new(|| RwLock::new(BTreeMap::new()));
+
+/// The ID given to the next new group. IDs start at 1 and are never reused.
+static NEXT_GROUP_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Scans a directory for `.onnx` files, loads each under its file name (without the
+/// extension), and records the loaded models as a group.
+///
+/// Returns a JSON object with the `group_id`, the names of the newly `loaded` models, the
+/// `errors` of files that failed to load (or directory entries that could not be read),
+/// and, for a sync, the models `reloaded` because their files changed, the models left
+/// `unchanged`, and the models `removed` because their files were deleted.
+///
+/// Without `sync`, every call creates a new group. With `sync`, an earlier group of the
+/// same directory is reused: files whose modification time and size did not change are
+/// not reloaded, and a model whose reload fails stays loaded in its previous version.
+pub(crate) fn autoload_dir(path: &str, options: AutoloadOptions) -> Result<Value, InferaError> {
+    let entries = fs::read_dir(path).map_err(|e| InferaError::IoError(e.to_string()))?;
+    let dir = fs::canonicalize(path).map_err(|e| InferaError::IoError(e.to_string()))?;
+
+    let existing = if options.sync {
+        GROUPS
+            .read()
+            .iter()
+            .find(|(_, group)| group.dir == dir)
+            .map(|(id, group)| (*id, group.members.clone()))
+    } else {
+        None
+    };
+    let (group_id, previous) = existing.unwrap_or_else(|| {
+        (
+            NEXT_GROUP_ID.fetch_add(1, Ordering::Relaxed),
+            BTreeMap::new(),
+        )
+    });
+
+    let mut members = BTreeMap::new();
+    let mut loaded = Vec::new();
+    let mut reloaded = Vec::new();
+    let mut unchanged = Vec::new();
+    let mut errors = Vec::new();
+    for entry in entries {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                // Report unreadable entries instead of silently skipping them
+                errors.push(json!({
+                    "file": path,
+                    "error": format!("Failed to read directory entry: {}", e),
+                }));
+                continue;
+            }
+        };
+        let file_path = entry.path();
+        if !file_path.is_file() || file_path.extension().is_none_or(|ext| ext != "onnx") {
+            continue;
+        }
+        let (Some(name), Some(full_path)) = (
+            file_path.file_stem().and_then(|s| s.to_str()),
+            file_path.to_str(),
+        ) else {
+            continue;
+        };
+        let key = model::resolve_name(name);
+        let stamp = FileStamp::of(&file_path);
+        if let Some(previous_stamp) = previous.get(&key) {
+            if stamp.is_some() && *previous_stamp == stamp && MODELS.read().contains_key(&key) {
+                unchanged.push(name.to_string());
+                members.insert(key, stamp);
+                continue;
+            }
+        }
+        match engine::load_model_impl(&key, full_path) {
+            Ok(_) => {
+                if previous.contains_key(&key) {
+                    reloaded.push(name.to_string());
+                } else {
+                    loaded.push(name.to_string());
+                }
+                members.insert(key, stamp);
+            }
+            Err(e) => {
+                errors.push(json!({
+                    "file": full_path,
+                    "error": e.to_string(),
+                    "attempts": e.attempts(),
+                }));
+                // The previous version stays loaded, and is retried on the next sync
+                if let Some(previous_stamp) = previous.get(&key) {
+                    members.insert(key, *previous_stamp);
+                }
+            }
+        }
+    }
+
+    let mut removed = Vec::new();
+    for key in previous.keys().filter(|key| !members.contains_key(*key)) {
+        if MODELS.write().remove(key).is_some() {
+            removed.push(key.clone());
+        }
+    }
+
+    GROUPS.write().insert(group_id, ModelGroup { dir, members });
+    Ok(json!({
+        "group_id": group_id,
+        "loaded": loaded,
+        "reloaded": reloaded,
+        "unchanged": unchanged,
+        "removed": removed,
+        "errors": errors,
+    }))
+}
+
+/// Unloads the models of an autoload group and forgets the group.
+///
+/// Returns how many models were unloaded. Members that were already unloaded are skipped,
+/// and renamed members are unloaded under their new name, so other models are never
+/// affected.
+pub(crate) fn unload_group(group_id: u64) -> Result<usize, InferaError> {
+    let group = GROUPS
+        .write()
+        .remove(&group_id)
+        .ok_or(InferaError::GroupNotFound(group_id))?;
+    let mut models = MODELS.write();
+    Ok(group
+        .members
+        .keys()
+        .filter(|key| models.remove(*key).is_some())
+        .count())
+}
+
+/// Moves the group membership of the model `old_name` to `new_name` after the model is
+/// renamed, and drops the membership of a model replaced under `new_name`.
+///
+/// A later sync of the group loads the member's file under its file name again, and
+/// unloads the renamed model as if its file had been deleted.
+pub(crate) fn rename_member(old_name: &str, new_name: &str) {
+    let mut groups = GROUPS.write();
+    for group in groups.values_mut() {
+        group.members.remove(new_name);
+    }
+    for group in groups.values_mut() {
+        if let Some(stamp) = group.members.remove(old_name) {
+            group.members.insert(new_name.to_string(), stamp);
+        }
+    }
+}
+
+/// Lists the autoload groups, ordered by ID, with their directory and the registry keys
+/// of their models.
+pub(crate) fn list_groups() -> Value {
+    let groups = GROUPS.read();
+    Value::from(
+        groups
+            .iter()
+            .map(|(id, group)| {
+                json!({
+                    "group_id": id,
+                    "dir": group.dir.to_string_lossy(),
+                    "models": group.members.keys().collect::<Vec<_>>(),
+                })
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Calls `f` with the ID of the autoload group of each grouped model, by registry key,
+/// while holding the read lock of the groups.
+pub(crate) fn with_group_ids<R>(f: impl FnOnce(&HashMap<&str, u64>) -> R) -> R {
+    let groups = GROUPS.read();
+    let group_ids = groups
+        .iter()
+        .flat_map(|(&id, group)| group.members.keys().map(move |key| (key.as_str(), id)))
+        .collect();
+    f(&group_ids)
+}
+
+/// Counters of the actions taken by directory watchers, across all watchers.
+#[derive(Default)]
+struct WatchCounters {
+    scans: AtomicU64,
+    loaded: AtomicU64,
+    reloaded: AtomicU64,
+    unloaded: AtomicU64,
+    failed: AtomicU64,
+}
+
+static WATCH_COUNTERS: Lazy<WatchCounters> = Lazy::new(WatchCounters::default);
+
+/// A flag that tells a watcher thread to stop, and wakes it if it is waiting.
+#[derive(Default)]
+struct StopSignal {
+    stopped: Mutex<bool>,
+    condvar: Condvar,
+}
+
+impl StopSignal {
+    fn stop(&self) {
+        *self.stopped.lock() = true;
+        self.condvar.notify_all();
+    }
+
+    /// Waits up to `timeout` for the signal and returns true if the watcher should stop.
+    fn wait(&self, timeout: Duration) -> bool {
+        let mut stopped = self.stopped.lock();
+        if !*stopped {
+            self.condvar.wait_for(&mut stopped, timeout);
+        }
+        *stopped
+    }
+}
+
+/// A background thread that periodically syncs one directory.
+struct Watcher {
+    stop: Arc<StopSignal>,
+    handle: JoinHandle<()>,
+}
+
+impl Watcher {
+    /// Stops the thread and waits for it to finish its current scan.
+    fn join(self, dir: &Path) {
+        self.stop.stop();
+        if self.handle.join().is_err() {
+            log!(
+                LogLevel::Error,
+                "Watcher thread for {} panicked",
+                dir.display()
+            );
+        }
+    }
+}
+
+/// The running directory watchers, by the canonical path of their directory.
+static WATCHERS: Lazy<Mutex<BTreeMap<PathBuf, Watcher>>> =
+    Lazy::new(|| Mutex::new(BTreeMap::new()));
+
+/// Serializes the tests that start watchers, since `shutdown_watchers` stops all of them.
+#[cfg(all(test, feature = "tract"))]
+pub(crate) static WATCH_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+/// Logs and counts the actions of one sync of a watched directory.
+fn record_scan(path: &str, report: &Value) {
+    let counters = &*WATCH_COUNTERS;
+    counters.scans.fetch_add(1, Ordering::Relaxed);
+    let names = |key: &str| -> Vec<String> {
+        report[key]
+            .as_array()
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    for (key, action, counter) in [
+        ("loaded", "Loaded", &counters.loaded),
+        ("reloaded", "Reloaded", &counters.reloaded),
+        ("removed", "Unloaded", &counters.unloaded),
+    ] {
+        for name in names(key) {
+            log!(
+                LogLevel::Info,
+                "{} model '{}' from watched directory {}",
+                action,
+                name,
+                path
+            );
+            counter.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+    for error in report["errors"].as_array().into_iter().flatten() {
+        log!(
+            LogLevel::Warn,
+            "Failed to load {} from watched directory: {}",
+            error["file"].as_str().unwrap_or(path),
+            error["error"].as_str().unwrap_or_default()
+        );
+        counters.failed.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// The body of a watcher thread: syncs `path` every `interval` until stopped.
+fn watch_loop(path: String, interval: Duration, stop: Arc<StopSignal>) {
+    while !stop.wait(interval) {
+        match autoload_dir(&path, AutoloadOptions { sync: true }) {
+            Ok(report) => record_scan(&path, &report),
+            Err(e) => {
+                log!(
+                    LogLevel::Warn,
+                    "Failed to scan watched directory {}: {}",
+                    path,
+                    e
+                );
+                WATCH_COUNTERS.scans.fetch_add(1, Ordering::Relaxed);
+                WATCH_COUNTERS.failed.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// Syncs a directory now and starts a background thread that syncs it again every
+/// `interval`.
+///
+/// Each sync works like `autoload_dir` with `sync` set: new files are loaded, changed
+/// files are reloaded (the new version replaces the old one only once it compiled, so a
+/// broken file keeps the previous version serving), and models whose files were deleted
+/// are unloaded. Every action is logged and counted in `watch_metrics`.
+///
+/// Returns the report of the first sync. Fails if the directory cannot be read, if it is
+/// already watched, or if `interval` is zero.
+pub(crate) fn watch_dir(path: &str, interval: Duration) -> Result<Value, InferaError> {
+    if interval.is_zero() {
+        return Err(InferaError::InvalidPollInterval);
+    }
+    let dir = fs::canonicalize(path).map_err(|e| InferaError::IoError(e.to_string()))?;
+    let mut watchers = WATCHERS.lock();
+    if watchers.contains_key(&dir) {
+        return Err(InferaError::AlreadyWatched(dir.display().to_string()));
+    }
+    let report = autoload_dir(path, AutoloadOptions { sync: true })?;
+    record_scan(path, &report);
+
+    let stop = Arc::new(StopSignal::default());
+    let thread_stop = Arc::clone(&stop);
+    let thread_path = path.to_string();
+    let handle = thread::Builder::new()
+        .name("infera-autoload-watch".to_string())
+        .spawn(move || watch_loop(thread_path, interval, thread_stop))
+        .map_err(|e| InferaError::IoError(e.to_string()))?;
+    log!(
+        LogLevel::Info,
+        "Watching {} for model changes every {:?}",
+        dir.display(),
+        interval
+    );
+    watchers.insert(dir, Watcher { stop, handle });
+    Ok(report)
+}
+
+/// Stops watching a directory and waits for its watcher thread to finish.
+///
+/// The models of the directory stay loaded.
+pub(crate) fn unwatch_dir(path: &str) -> Result<(), InferaError> {
+    let dir = fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+    let watcher = WATCHERS
+        .lock()
+        .remove(&dir)
+        .ok_or_else(|| InferaError::NotWatched(dir.display().to_string()))?;
+    watcher.join(&dir);
+    log!(LogLevel::Info, "Stopped watching {}", dir.display());
+    Ok(())
+}
+
+/// Stops every directory watcher and waits for their threads to finish.
+///
+/// Returns the number of watchers stopped.
+pub(crate) fn shutdown_watchers() -> usize {
+    let watchers = std::mem::take(&mut *WATCHERS.lock());
+    for watcher in watchers.values() {
+        watcher.stop.stop();
+    }
+    let count = watchers.len();
+    for (dir, watcher) in watchers {
+        watcher.join(&dir);
+    }
+    count
+}
+
+/// Returns the watched directories and the counters of watcher actions as JSON.
+pub(crate) fn watch_metrics() -> Value {
+    let counters = &*WATCH_COUNTERS;
+    let dirs: Vec<String> = WATCHERS
+        .lock()
+        .keys()
+        .map(|dir| dir.to_string_lossy().into_owned())
+        .collect();
+    json!({
+        "watched_dirs": dirs,
+        "scans": counters.scans.load(Ordering::Relaxed),
+        "loaded": counters.loaded.load(Ordering::Relaxed),
+        "reloaded": counters.reloaded.load(Ordering::Relaxed),
+        "unloaded": counters.unloaded.load(Ordering::Relaxed),
+        "failed": counters.failed.load(Ordering::Relaxed),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_autoload_options_from_json() {
+        assert_eq!(
+            AutoloadOptions::from_json("").unwrap(),
+            AutoloadOptions::default()
+        );
+        assert!(
+            AutoloadOptions::from_json(r#"{"sync": true}"#)
+                .unwrap()
+                .sync
+        );
+        assert!(AutoloadOptions::from_json(r#"{"sync": "yes"}"#).is_err());
+        assert!(AutoloadOptions::from_json("[]").is_err());
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_unload_group_removes_only_members() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["group_member_a", "group_member_b"] {
+            fs::copy(
+                "../test/models/linear.onnx",
+                dir.path().join(format!("{}.onnx", name)),
+            )
+            .unwrap();
+        }
+        let path = dir.path().to_str().unwrap();
+        let result = autoload_dir(path, AutoloadOptions::default()).unwrap();
+        assert!(result["errors"].as_array().unwrap().is_empty());
+        let group_id = result["group_id"].as_u64().unwrap();
+        let listed = list_groups();
+        assert!(listed
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|g| g["group_id"] == group_id && g["models"].as_array().unwrap().len() == 2));
+
+        engine::load_model_impl("group_unrelated", "../test/models/linear.onnx").unwrap();
+        model::rename_model("group_member_b", "group_member_renamed", false).unwrap();
+        assert_eq!(unload_group(group_id).unwrap(), 2);
+        assert!(!MODELS.read().contains_key("group_member_a"));
+        assert!(!MODELS.read().contains_key("group_member_renamed"));
+        assert!(MODELS.read().contains_key("group_unrelated"));
+        assert!(matches!(
+            unload_group(group_id),
+            Err(InferaError::GroupNotFound(id)) if id == group_id
+        ));
+        MODELS.write().remove("group_unrelated");
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_sync_reuses_group_and_removes_deleted_models() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["group_sync_kept", "group_sync_deleted"] {
+            fs::copy(
+                "../test/models/linear.onnx",
+                dir.path().join(format!("{}.onnx", name)),
+            )
+            .unwrap();
+        }
+        let path = dir.path().to_str().unwrap();
+        let sync = AutoloadOptions { sync: true };
+        let first = autoload_dir(path, sync).unwrap();
+        let group_id = first["group_id"].as_u64().unwrap();
+        assert_eq!(first["loaded"].as_array().unwrap().len(), 2);
+
+        fs::remove_file(dir.path().join("group_sync_deleted.onnx")).unwrap();
+        fs::copy(
+            "../test/models/linear.onnx",
+            dir.path().join("group_sync_added.onnx"),
+        )
+        .unwrap();
+        let second = autoload_dir(path, sync).unwrap();
+        assert_eq!(second["group_id"], group_id);
+        assert_eq!(second["unchanged"], json!(["group_sync_kept"]));
+        assert_eq!(second["loaded"], json!(["group_sync_added"]));
+        assert_eq!(second["reloaded"], json!([]));
+        assert_eq!(second["removed"], json!(["group_sync_deleted"]));
+        assert!(!MODELS.read().contains_key("group_sync_deleted"));
+
+        // Without sync, the same directory gets a new group
+        let third = autoload_dir(path, AutoloadOptions::default()).unwrap();
+        let third_id = third["group_id"].as_u64().unwrap();
+        assert_ne!(third_id, group_id);
+        unload_group(third_id).unwrap();
+        assert_eq!(unload_group(group_id).unwrap(), 0);
+    }
+
+    #[cfg(feature = "tract")]
+    /// Returns the names of the loaded models as listed by `infera_get_loaded_models`.
+    fn loaded_models() -> Vec<String> {
+        let ptr = crate::infera_get_loaded_models();
+        let names = unsafe { std::ffi::CStr::from_ptr(ptr) }
+            .to_str()
+            .map(|s| serde_json::from_str(s).unwrap())
+            .unwrap();
+        unsafe { crate::infera_free_string(ptr) };
+        names
+    }
+
+    #[cfg(feature = "tract")]
+    /// Polls `condition` until it holds, failing the test after five seconds.
+    fn wait_until(what: &str, condition: impl Fn() -> bool) {
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while !condition() {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "timed out waiting until {}",
+                what
+            );
+            thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_watch_dir_keeps_models_in_sync() {
+        let _guard = WATCH_TEST_LOCK.lock();
+        let dir = tempfile::tempdir().unwrap();
+        let kept = dir.path().join("watch_kept.onnx");
+        let added = dir.path().join("watch_added.onnx");
+        fs::copy("../test/models/linear.onnx", &kept).unwrap();
+        let path = dir.path().to_str().unwrap();
+        let interval = Duration::from_millis(50);
+
+        let report = watch_dir(path, interval).unwrap();
+        assert_eq!(report["loaded"], json!(["watch_kept"]));
+        assert!(matches!(
+            watch_dir(path, interval),
+            Err(InferaError::AlreadyWatched(_))
+        ));
+        assert!(watch_metrics()["watched_dirs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|d| d.as_str() == dir.path().canonicalize().unwrap().to_str()));
+
+        // New files are loaded
+        fs::copy("../test/models/linear.onnx", &added).unwrap();
+        wait_until("the new model is loaded", || {
+            loaded_models().contains(&"watch_added".to_string())
+        });
+
+        // Changed files are reloaded (linear.onnx has a fixed batch size of 1)
+        fs::copy("../test/models/dynamic_batch.onnx", &kept).unwrap();
+        wait_until("the changed model is reloaded", || {
+            model::get_model("watch_kept")
+                .is_ok_and(|m| m.input_shape.as_deref() == Some(&[-1, 3][..]))
+        });
+
+        // A file that fails to compile keeps the previous version serving
+        let failed = WATCH_COUNTERS.failed.load(Ordering::Relaxed);
+        fs::write(&kept, b"not an onnx model").unwrap();
+        wait_until("the broken file is reported", || {
+            WATCH_COUNTERS.failed.load(Ordering::Relaxed) > failed
+        });
+        let model = model::get_model("watch_kept").unwrap();
+        assert_eq!(model.input_shape.as_deref(), Some(&[-1, 3][..]));
+
+        // Deleted files are unloaded
+        fs::remove_file(&added).unwrap();
+        wait_until("the deleted model is unloaded", || {
+            !loaded_models().contains(&"watch_added".to_string())
+        });
+
+        unwatch_dir(path).unwrap();
+        assert!(matches!(unwatch_dir(path), Err(InferaError::NotWatched(_))));
+        // The models stay loaded after the watcher stops
+        assert!(MODELS.read().contains_key("watch_kept"));
+        MODELS.write().remove("watch_kept");
+    }
+
+    #[test]
+    fn test_watch_dir_rejects_zero_interval() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            watch_dir(dir.path().to_str().unwrap(), Duration::ZERO),
+            Err(InferaError::InvalidPollInterval)
+        ));
+    }
+}
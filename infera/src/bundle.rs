@@ -0,0 +1,346 @@
+// Loading of `.infera.zip` model bundles: one archive holding a model with its manifest,
+// labels, preprocessing, and feature names.
+
+use crate::config::CONFIG;
+use crate::error::{context, InferaError};
+use crate::http;
+use crate::model::parse_feature_names;
+#[cfg(feature = "tract")]
+use serde_json::json;
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The file name suffix that marks a model bundle.
+pub(crate) const BUNDLE_SUFFIX: &str = ".infera.zip";
+
+/// The cache subdirectory bundles are unpacked into, one directory per bundle hash.
+pub(crate) const BUNDLES_DIR: &str = "bundles";
+
+/// The ONNX model of a bundle. Required.
+pub(crate) const MODEL_FILE: &str = "model.onnx";
+/// The bundle's logical model name and version. Required.
+const MANIFEST_FILE: &str = "manifest.json";
+/// The class names of the model's output columns, as a JSON array of strings.
+const LABELS_FILE: &str = "labels.json";
+/// The preprocessing the model expects of its inputs, as a JSON object.
+const PREPROCESS_FILE: &str = "preprocess.json";
+/// The names of the model's input columns, as a JSON array of strings.
+const FEATURES_FILE: &str = "features.json";
+
+/// The files unpacked from a bundle. Other entries in the archive are ignored.
+const BUNDLE_FILES: [&str; 5] = [
+    MODEL_FILE,
+    MANIFEST_FILE,
+    LABELS_FILE,
+    PREPROCESS_FILE,
+    FEATURES_FILE,
+];
+
+/// The manifest, labels, and preprocessing of the bundle a model was loaded from.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BundleInfo {
+    /// The model name declared in the manifest.
+    pub name: String,
+    /// The model version declared in the manifest, if any.
+    pub version: Option<String>,
+    /// The class name of each output column, or empty if the bundle has no labels.
+    pub labels: Vec<String>,
+    /// The preprocessing spec from `preprocess.json`, if the bundle has one.
+    pub preprocess: Option<Value>,
+}
+
+impl BundleInfo {
+    /// Returns the bundle info reported in model info.
+    #[cfg(feature = "tract")]
+    pub(crate) fn to_json(&self) -> Value {
+        json!({
+            "name": self.name,
+            "version": self.version,
+            "labels": self.labels,
+            "preprocess": self.preprocess,
+        })
+    }
+}
+
+/// A bundle unpacked into the cache, ready for its model to be loaded.
+#[derive(Debug)]
+pub(crate) struct Bundle {
+    /// The unpacked `model.onnx` file.
+    pub model_path: PathBuf,
+    /// The feature names from `features.json`, if the bundle has one.
+    pub feature_names: Option<Vec<String>>,
+    /// The manifest, labels, and preprocessing to keep on the loaded model.
+    pub info: BundleInfo,
+}
+
+/// Returns true if `path_or_url` names a model bundle, ignoring the query string and
+/// fragment of URLs.
+pub(crate) fn is_bundle(path_or_url: &str) -> bool {
+    let path = if path_or_url.starts_with("http") {
+        path_or_url.split(['?', '#']).next().unwrap_or(path_or_url)
+    } else {
+        path_or_url
+    };
+    path.to_ascii_lowercase().ends_with(BUNDLE_SUFFIX)
+}
+
+/// Unpacks the bundle at `path` into `<cache>/bundles/<sha256>/` and parses its files.
+///
+/// A bundle whose directory already exists is not unpacked again. Fails with
+/// `BundleFileMissing` if the archive has no `model.onnx` or `manifest.json`, and with
+/// `InvalidBundle` if it is not a zip archive, one of its files is larger than
+/// `max_file_bytes`, or one of its JSON files is malformed.
+///
+/// Unpacked bundles are cache entries: they count toward the cache size limit and are
+/// evicted like cached model files.
+pub(crate) fn open_bundle(path: &Path) -> Result<Bundle, InferaError> {
+    let subject = path.to_string_lossy().to_string();
+    let dir = http::cache_dir()
+        .join(BUNDLES_DIR)
+        .join(http::file_sha256(path)?);
+    if dir.join(MODEL_FILE).is_file() {
+        http::touch_cache_file(&dir.join(MODEL_FILE))?;
+    } else {
+        unpack(path, &subject, &dir)?;
+    }
+    let manifest = read_json(&dir, &subject, MANIFEST_FILE)?
+        .ok_or_else(|| missing(&subject, MANIFEST_FILE))?;
+    let (name, version) = parse_manifest(&subject, &manifest)?;
+    let labels = match read_json(&dir, &subject, LABELS_FILE)? {
+        Some(value) => parse_labels(&subject, &value)?,
+        None => Vec::new(),
+    };
+    let preprocess = read_json(&dir, &subject, PREPROCESS_FILE)?;
+    if preprocess.as_ref().is_some_and(|spec| !spec.is_object()) {
+        return Err(invalid(&subject, "preprocess.json must be a JSON object"));
+    }
+    let feature_names = read_json(&dir, &subject, FEATURES_FILE)?
+        .map(|value| parse_feature_names(&value))
+        .transpose()
+        .map_err(|e| invalid(&subject, format!("features.json: {}", e)))?;
+    Ok(Bundle {
+        model_path: dir.join(MODEL_FILE),
+        feature_names,
+        info: BundleInfo {
+            name,
+            version,
+            labels,
+            preprocess,
+        },
+    })
+}
+
+/// Extracts the bundle files of the archive at `path` into `dir`.
+///
+/// The files are extracted into a temporary directory that is renamed to `dir` once
+/// complete, so a concurrent or interrupted load never sees a partly unpacked bundle.
+fn unpack(path: &Path, subject: &str, dir: &Path) -> Result<(), InferaError> {
+    let file = File::open(path).map_err(context(InferaError::IoError, "bundle_open", subject))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| invalid(subject, format!("not a zip archive: {}", e)))?;
+    if archive.index_for_name(MODEL_FILE).is_none() {
+        return Err(missing(subject, MODEL_FILE));
+    }
+    http::ensure_dir(dir.parent().unwrap_or(dir))?;
+    let staging = dir.with_extension(format!("{}.part", http::unique_suffix()));
+    let result = extract_files(&mut archive, subject, &staging, max_file_bytes()).and_then(|()| {
+        match fs::rename(&staging, dir) {
+            // Another load unpacked the same bundle first
+            Err(_) if dir.join(MODEL_FILE).is_file() => Ok(()),
+            result => result.map_err(context(InferaError::IoError, "bundle_unpack", subject)),
+        }
+    });
+    let _ = fs::remove_dir_all(&staging);
+    result
+}
+
+/// Returns the maximum size of a file unpacked from a bundle: `INFERA_MAX_DOWNLOAD_BYTES`
+/// if it is set, and otherwise the cache size limit.
+fn max_file_bytes() -> u64 {
+    match CONFIG.max_download_bytes {
+        0 => crate::config::cache_size_limit(),
+        limit => limit,
+    }
+}
+
+/// Writes the bundle files found in `archive` into `staging`.
+///
+/// A file larger than `limit` bytes fails the extraction. The limit is checked against
+/// the bytes actually extracted, since the size recorded in the archive may not be true.
+fn extract_files<R: io::Read + io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    subject: &str,
+    staging: &Path,
+    limit: u64,
+) -> Result<(), InferaError> {
+    fs::create_dir_all(staging).map_err(context(InferaError::IoError, "bundle_unpack", subject))?;
+    let too_large = |name: &str| {
+        invalid(
+            subject,
+            format!("{} is larger than the limit of {} bytes", name, limit),
+        )
+    };
+    for name in BUNDLE_FILES {
+        let entry = match archive.by_name(name) {
+            Ok(entry) => entry,
+            Err(zip::result::ZipError::FileNotFound) => continue,
+            Err(e) => return Err(invalid(subject, format!("{}: {}", name, e))),
+        };
+        if entry.size() > limit {
+            return Err(too_large(name));
+        }
+        let mut out = File::create(staging.join(name)).map_err(context(
+            InferaError::IoError,
+            "bundle_unpack",
+            subject,
+        ))?;
+        let written = io::copy(
+            &mut io::Read::take(entry, limit.saturating_add(1)),
+            &mut out,
+        )
+        .map_err(|e| invalid(subject, format!("{}: {}", name, e)))?;
+        if written > limit {
+            return Err(too_large(name));
+        }
+    }
+    Ok(())
+}
+
+/// Reads and parses an unpacked JSON file of a bundle, or returns `None` if the bundle
+/// does not have it.
+fn read_json(dir: &Path, subject: &str, name: &str) -> Result<Option<Value>, InferaError> {
+    let content = match fs::read_to_string(dir.join(name)) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(context(InferaError::IoError, "bundle_read", subject)(e)),
+    };
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| invalid(subject, format!("{}: {}", name, e)))
+}
+
+/// Parses the manifest's model name and optional version.
+fn parse_manifest(
+    subject: &str,
+    manifest: &Value,
+) -> Result<(String, Option<String>), InferaError> {
+    let name = manifest["name"]
+        .as_str()
+        .filter(|name| !name.is_empty() && !name.contains('/'))
+        .ok_or_else(|| {
+            invalid(
+                subject,
+                "manifest.json must have a \"name\" string that is non-empty and has no '/'",
+            )
+        })?;
+    let version = match &manifest["version"] {
+        Value::Null => None,
+        Value::String(version) => Some(version.clone()),
+        _ => {
+            return Err(invalid(
+                subject,
+                "the \"version\" in manifest.json must be a string",
+            ))
+        }
+    };
+    Ok((name.to_string(), version))
+}
+
+/// Parses the class names in `labels.json`.
+fn parse_labels(subject: &str, value: &Value) -> Result<Vec<String>, InferaError> {
+    value
+        .as_array()
+        .and_then(|labels| {
+            labels
+                .iter()
+                .map(|label| label.as_str().map(String::from))
+                .collect()
+        })
+        .ok_or_else(|| invalid(subject, "labels.json must be an array of strings"))
+}
+
+/// Returns the error for a bundle that lacks a required file.
+fn missing(subject: &str, file: &str) -> InferaError {
+    InferaError::BundleFileMissing {
+        bundle: subject.to_string(),
+        file: file.to_string(),
+    }
+}
+
+/// Returns the error for a malformed bundle.
+fn invalid(subject: &str, reason: impl Into<String>) -> InferaError {
+    InferaError::InvalidBundle {
+        bundle: subject.to_string(),
+        reason: reason.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_is_bundle() {
+        assert!(is_bundle("/models/churn.infera.zip"));
+        assert!(is_bundle("C:\\models\\CHURN.INFERA.ZIP"));
+        assert!(is_bundle("https://example.com/churn.infera.zip?token=abc"));
+        assert!(!is_bundle("/models/churn.zip"));
+        assert!(!is_bundle("/models/churn.onnx"));
+        assert!(!is_bundle(
+            "https://example.com/churn.onnx?file=a.infera.zip"
+        ));
+    }
+
+    #[test]
+    fn test_extract_files_rejects_files_over_limit() {
+        use std::io::Write;
+
+        let mut zip = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+        for (name, size) in [(MODEL_FILE, 64), (MANIFEST_FILE, 16)] {
+            zip.start_file(name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(&vec![b' '; size]).unwrap();
+        }
+        let mut archive = zip::ZipArchive::new(zip.finish().unwrap()).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+
+        let staging = dir.path().join("fits");
+        extract_files(&mut archive, "b", &staging, 64).unwrap();
+        assert_eq!(fs::metadata(staging.join(MODEL_FILE)).unwrap().len(), 64);
+
+        let err = extract_files(&mut archive, "b", &dir.path().join("too_large"), 63).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("model.onnx is larger than the limit of 63 bytes"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let (name, version) =
+            parse_manifest("b", &json!({"name": "churn", "version": "1.2"})).unwrap();
+        assert_eq!(name, "churn");
+        assert_eq!(version.as_deref(), Some("1.2"));
+        assert_eq!(
+            parse_manifest("b", &json!({"name": "churn"})).unwrap().1,
+            None
+        );
+        for manifest in [
+            json!({}),
+            json!({"name": ""}),
+            json!({"name": "ns/churn"}),
+            json!({"name": "churn", "version": 2}),
+            json!(["churn"]),
+        ] {
+            assert!(matches!(
+                parse_manifest("b", &manifest),
+                Err(InferaError::InvalidBundle { .. })
+            ));
+        }
+    }
+}
@@ -0,0 +1,376 @@
+// Routes predictions through canary aliases, falling back to a known-good model when
+// the candidate model starts failing.
+
+use crate::config::{LogLevel, CONFIG};
+use crate::error::InferaError;
+use crate::ffi_utils::{InferaInferenceResult, InferaProfiledResult, OutputBytes};
+use crate::log;
+use crate::model::MODELS;
+use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Where a canary alias sends predictions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CanaryState {
+    /// Predictions go to the candidate, and its error rate is tracked.
+    Canary,
+    /// The candidate exceeded the maximum error rate, so predictions go to the fallback.
+    Tripped,
+    /// The candidate was promoted, so predictions go to it without tracking.
+    Promoted,
+}
+
+impl CanaryState {
+    fn as_str(self) -> &'static str {
+        match self {
+            CanaryState::Canary => "canary",
+            CanaryState::Tripped => "tripped",
+            CanaryState::Promoted => "promoted",
+        }
+    }
+}
+
+/// An alias that sends predictions to a candidate model while tracking its errors.
+#[derive(Debug)]
+struct Canary {
+    candidate: String,
+    fallback: String,
+    max_error_rate: f64,
+    window: usize,
+    state: CanaryState,
+    /// Whether each of the last `window` candidate predictions failed, oldest first.
+    outcomes: VecDeque<bool>,
+    /// Candidate predictions tracked since the canary was set.
+    requests: u64,
+    /// Failed candidate predictions tracked since the canary was set.
+    errors: u64,
+    /// When the canary tripped (seconds since the Unix epoch) and the error rate it saw.
+    tripped: Option<(u64, f64)>,
+}
+
+impl Canary {
+    fn new(candidate: &str, fallback: &str, max_error_rate: f64, window: usize) -> Self {
+        Canary {
+            candidate: candidate.to_string(),
+            fallback: fallback.to_string(),
+            max_error_rate,
+            window,
+            state: CanaryState::Canary,
+            outcomes: VecDeque::with_capacity(window),
+            requests: 0,
+            errors: 0,
+            tripped: None,
+        }
+    }
+
+    /// Returns the name of the model predictions through the alias go to.
+    fn target(&self) -> &str {
+        match self.state {
+            CanaryState::Tripped => &self.fallback,
+            CanaryState::Canary | CanaryState::Promoted => &self.candidate,
+        }
+    }
+
+    /// Returns the error rate over the sliding window, or 0 if it is empty.
+    fn window_error_rate(&self) -> f64 {
+        if self.outcomes.is_empty() {
+            return 0.0;
+        }
+        let failed = self.outcomes.iter().filter(|&&f| f).count();
+        failed as f64 / self.outcomes.len() as f64
+    }
+
+    /// Records the outcome of a candidate prediction.
+    ///
+    /// Once the window is full, an error rate above `max_error_rate` trips the canary to
+    /// its fallback, and the error rate is returned.
+    fn record(&mut self, failed: bool) -> Option<f64> {
+        self.requests += 1;
+        if failed {
+            self.errors += 1;
+        }
+        if self.outcomes.len() == self.window {
+            self.outcomes.pop_front();
+        }
+        self.outcomes.push_back(failed);
+        if self.outcomes.len() < self.window {
+            return None;
+        }
+        let error_rate = self.window_error_rate();
+        if error_rate <= self.max_error_rate {
+            return None;
+        }
+        self.state = CanaryState::Tripped;
+        self.tripped = Some((unix_now(), error_rate));
+        Some(error_rate)
+    }
+
+    fn status(&self, alias: &str) -> Value {
+        json!({
+            "alias": alias,
+            "candidate": self.candidate,
+            "fallback": self.fallback,
+            "target": self.target(),
+            "state": self.state.as_str(),
+            "max_error_rate": self.max_error_rate,
+            "window": self.window,
+            "window_requests": self.outcomes.len(),
+            "window_error_rate": self.window_error_rate(),
+            "requests": self.requests,
+            "errors": self.errors,
+            "tripped_at": self.tripped.map(|(at, _)| at),
+            "trip_error_rate": self.tripped.map(|(_, rate)| rate),
+        })
+    }
+}
+
+/// Canary aliases by name. Each canary has its own lock, so predictions through
+/// different aliases do not contend.
+static CANARIES: Lazy<RwLock<HashMap<String, Mutex<Canary>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Calls `f` with the canary aliases routing to each model, sorted by alias and keyed by
+/// the model's registry key, while holding the read lock of the canaries.
+pub(crate) fn with_alias_targets<R>(f: impl FnOnce(&HashMap<String, Vec<String>>) -> R) -> R {
+    let canaries = CANARIES.read();
+    let mut targets: HashMap<String, Vec<String>> = HashMap::new();
+    for (alias, canary) in canaries.iter() {
+        let target = canary.lock().target().to_string();
+        targets.entry(target).or_default().push(alias.clone());
+    }
+    for aliases in targets.values_mut() {
+        aliases.sort();
+    }
+    f(&targets)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// The output of a successful prediction, which canaries check for non-finite values.
+pub(crate) trait Prediction {
+    /// Returns whether every output value is finite.
+    fn is_finite(&self) -> bool;
+}
+
+impl Prediction for InferaInferenceResult {
+    fn is_finite(&self) -> bool {
+        if self.data.is_null() || self.len == 0 {
+            return true;
+        }
+        // SAFETY: successful results own `len` initialized values at `data` (see `from_vec`)
+        let data = unsafe { std::slice::from_raw_parts(self.data, self.len) };
+        data.iter().all(|v| v.is_finite())
+    }
+}
+
+impl Prediction for InferaProfiledResult {
+    fn is_finite(&self) -> bool {
+        self.result.is_finite()
+    }
+}
+
+impl Prediction for OutputBytes {
+    fn is_finite(&self) -> bool {
+        self.finite
+    }
+}
+
+/// Points `alias` at `candidate`, falling back to `fallback` if more than
+/// `max_error_rate` of the last `window` candidate predictions fail.
+///
+/// Setting an alias that is already a canary replaces it and resets its statistics.
+pub(crate) fn set_canary(
+    alias: &str,
+    candidate: &str,
+    fallback: &str,
+    max_error_rate: f64,
+    window: usize,
+) -> Result<(), InferaError> {
+    if alias.is_empty() {
+        return Err(InferaError::InvalidModelName(alias.to_string()));
+    }
+    if !(0.0..=1.0).contains(&max_error_rate) {
+        return Err(InferaError::InvalidCanary(format!(
+            "max_error_rate must be between 0 and 1, got {}",
+            max_error_rate
+        )));
+    }
+    if window == 0 {
+        return Err(InferaError::InvalidCanary(
+            "window must be greater than zero".to_string(),
+        ));
+    }
+    if candidate == fallback {
+        return Err(InferaError::InvalidCanary(
+            "candidate and fallback must be different models".to_string(),
+        ));
+    }
+    {
+        let models = MODELS.read();
+        if models.contains_key(alias) {
+            return Err(InferaError::ModelAlreadyExists(alias.to_string()));
+        }
+        for name in [candidate, fallback] {
+            if !models.contains_key(name) {
+                return Err(InferaError::ModelNotFound(name.to_string()));
+            }
+        }
+    }
+    CANARIES.write().insert(
+        alias.to_string(),
+        Mutex::new(Canary::new(candidate, fallback, max_error_rate, window)),
+    );
+    log!(
+        LogLevel::Info,
+        "Canary '{}' set: candidate '{}', fallback '{}', max error rate {} over {} predictions",
+        alias,
+        candidate,
+        fallback,
+        max_error_rate,
+        window
+    );
+    Ok(())
+}
+
+/// Re-points the canaries whose candidate or fallback is the model `old_name` at
+/// `new_name`, after the model is renamed.
+pub(crate) fn rename_target(old_name: &str, new_name: &str) {
+    for canary in CANARIES.read().values() {
+        let canary = &mut *canary.lock();
+        for name in [&mut canary.candidate, &mut canary.fallback] {
+            if name == old_name {
+                *name = new_name.to_string();
+            }
+        }
+    }
+}
+
+/// Removes the canary aliases whose name matches `remove`.
+pub(crate) fn remove_aliases(remove: impl Fn(&str) -> bool) {
+    CANARIES.write().retain(|alias, _| !remove(alias));
+}
+
+/// Makes the candidate of a canary the permanent target of its alias and stops
+/// tracking its errors. This also undoes a trip to the fallback.
+pub(crate) fn promote_canary(alias: &str) -> Result<(), InferaError> {
+    let canaries = CANARIES.read();
+    let mut canary = canaries
+        .get(alias)
+        .ok_or_else(|| InferaError::CanaryNotFound(alias.to_string()))?
+        .lock();
+    canary.state = CanaryState::Promoted;
+    log!(
+        LogLevel::Info,
+        "Canary '{}' promoted: predictions go to '{}'",
+        alias,
+        canary.candidate
+    );
+    Ok(())
+}
+
+/// Returns the state and statistics of a canary alias as a JSON object.
+pub(crate) fn canary_status(alias: &str) -> Result<Value, InferaError> {
+    CANARIES
+        .read()
+        .get(alias)
+        .map(|canary| canary.lock().status(alias))
+        .ok_or_else(|| InferaError::CanaryNotFound(alias.to_string()))
+}
+
+/// Runs a prediction, resolving `name` through its canary alias if it has one.
+///
+/// Predictions that go to a canary's candidate are tracked: an error (or a non-finite
+/// output, with `INFERA_CANARY_COUNT_NON_FINITE`) counts as a failure, and too many
+/// failures trip the alias to its fallback before the next prediction.
+pub(crate) fn predict<T: Prediction>(
+    name: &str,
+    run: impl FnOnce(&str) -> Result<T, InferaError>,
+) -> Result<T, InferaError> {
+    let route = CANARIES.read().get(name).map(|canary| {
+        let canary = canary.lock();
+        (
+            canary.target().to_string(),
+            canary.state == CanaryState::Canary,
+        )
+    });
+    let Some((target, tracked)) = route else {
+        return run(name);
+    };
+    let result = run(&target);
+    if tracked {
+        let failed = match &result {
+            Ok(res) => CONFIG.canary_count_non_finite && !res.is_finite(),
+            // A prediction rejected at the concurrency limit never ran, so it is not tracked
+            Err(InferaError::ConcurrencyLimitReached { .. }) => return result,
+            Err(_) => true,
+        };
+        record(name, &target, failed);
+    }
+    result
+}
+
+/// Records the outcome of a candidate prediction made through `alias`, tripping the
+/// alias to its fallback if the error rate is too high.
+fn record(alias: &str, target: &str, failed: bool) {
+    let canaries = CANARIES.read();
+    let Some(canary) = canaries.get(alias) else {
+        return;
+    };
+    let mut canary = canary.lock();
+    // The canary may have tripped, been promoted, or been replaced since the prediction began
+    if canary.state != CanaryState::Canary || canary.candidate != target {
+        return;
+    }
+    if let Some(error_rate) = canary.record(failed) {
+        log!(
+            LogLevel::Error,
+            "Canary '{}' tripped: candidate '{}' failed {:.1}% of the last {} predictions \
+             (maximum {:.1}%), falling back to '{}'",
+            alias,
+            canary.candidate,
+            error_rate * 100.0,
+            canary.window,
+            canary.max_error_rate * 100.0,
+            canary.fallback
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canary_trips_only_once_the_window_exceeds_the_error_rate() {
+        let mut canary = Canary::new("new", "old", 0.5, 4);
+
+        // A full window of failures is needed before a trip, so early errors are tolerated
+        assert_eq!(canary.record(true), None);
+        assert_eq!(canary.record(true), None);
+        assert_eq!(canary.record(false), None);
+        assert_eq!(canary.record(false), None);
+        assert_eq!(canary.target(), "new");
+
+        // The window slides and stays at exactly 50%, which is allowed
+        assert_eq!(canary.record(true), None);
+        assert_eq!(canary.record(true), None);
+        assert_eq!(canary.record(true), Some(0.75));
+        assert_eq!(canary.state, CanaryState::Tripped);
+        assert_eq!(canary.target(), "old");
+
+        let status = canary.status("alias");
+        assert_eq!(status["requests"], 7);
+        assert_eq!(status["errors"], 5);
+        assert_eq!(status["state"], "tripped");
+        assert_eq!(status["trip_error_rate"], 0.75);
+        assert!(status["tripped_at"].as_u64().is_some());
+    }
+}
@@ -0,0 +1,399 @@
+// Compares the outputs of two loaded models on the same inputs.
+
+use crate::error::InferaError;
+#[cfg(feature = "tract")]
+use crate::model::{get_model, OnnxModel};
+#[cfg(feature = "tract")]
+use serde_json::{json, Value};
+
+/// Default number of random input rows generated for a comparison.
+#[cfg(feature = "tract")]
+const DEFAULT_SAMPLES: usize = 100;
+
+/// Default seed for the random input generator.
+#[cfg(feature = "tract")]
+const DEFAULT_SEED: u64 = 42;
+
+/// Default absolute tolerance.
+#[cfg(feature = "tract")]
+const DEFAULT_ATOL: f64 = 1e-5;
+
+/// Default relative tolerance.
+#[cfg(feature = "tract")]
+const DEFAULT_RTOL: f64 = 1e-4;
+
+/// Options for `infera_compare_models`, parsed from a JSON object.
+#[cfg(feature = "tract")]
+#[derive(Debug, Clone)]
+pub(crate) struct CompareOptions {
+    /// Number of random input rows to generate when `inputs` is not given.
+    pub samples: usize,
+    /// Seed for the random input generator.
+    pub seed: u64,
+    /// A user-provided input matrix, one inner array per row.
+    pub inputs: Option<Vec<Vec<f32>>>,
+    /// Absolute tolerance used for the pass/fail verdict.
+    pub atol: f64,
+    /// Relative tolerance used for the pass/fail verdict.
+    pub rtol: f64,
+}
+
+#[cfg(feature = "tract")]
+impl Default for CompareOptions {
+    fn default() -> Self {
+        Self {
+            samples: DEFAULT_SAMPLES,
+            seed: DEFAULT_SEED,
+            inputs: None,
+            atol: DEFAULT_ATOL,
+            rtol: DEFAULT_RTOL,
+        }
+    }
+}
+
+#[cfg(feature = "tract")]
+impl CompareOptions {
+    /// Parses comparison options from a JSON object string.
+    ///
+    /// An empty string is treated as "no options".
+    pub(crate) fn from_json(json: &str) -> Result<Self, InferaError> {
+        let mut options = CompareOptions::default();
+        if json.trim().is_empty() {
+            return Ok(options);
+        }
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| InferaError::JsonError(e.to_string()))?;
+        let obj = value.as_object().ok_or_else(|| {
+            InferaError::JsonError("compare options must be a JSON object".to_string())
+        })?;
+        if let Some(v) = obj.get("samples") {
+            options.samples = v.as_u64().filter(|&n| n > 0).ok_or_else(|| {
+                InferaError::JsonError("\"samples\" must be a positive integer".to_string())
+            })? as usize;
+        }
+        if let Some(v) = obj.get("seed") {
+            options.seed = v.as_u64().ok_or_else(|| {
+                InferaError::JsonError("\"seed\" must be a non-negative integer".to_string())
+            })?;
+        }
+        if let Some(v) = obj.get("atol") {
+            options.atol = non_negative_f64(v, "atol")?;
+        }
+        if let Some(v) = obj.get("rtol") {
+            options.rtol = non_negative_f64(v, "rtol")?;
+        }
+        if let Some(v) = obj.get("inputs") {
+            options.inputs = Some(parse_input_matrix(v)?);
+        }
+        Ok(options)
+    }
+}
+
+/// Reads a non-negative number from a JSON value.
+#[cfg(feature = "tract")]
+fn non_negative_f64(value: &Value, key: &str) -> Result<f64, InferaError> {
+    value
+        .as_f64()
+        .filter(|&x| x >= 0.0)
+        .ok_or_else(|| InferaError::JsonError(format!("\"{}\" must be a non-negative number", key)))
+}
+
+/// Parses a non-empty JSON array of equally sized numeric rows.
+#[cfg(feature = "tract")]
+fn parse_input_matrix(value: &Value) -> Result<Vec<Vec<f32>>, InferaError> {
+    let invalid = || {
+        InferaError::JsonError(
+            "\"inputs\" must be a non-empty array of equally sized number arrays".to_string(),
+        )
+    };
+    let rows = value
+        .as_array()
+        .filter(|r| !r.is_empty())
+        .ok_or_else(invalid)?;
+    let mut matrix = Vec::with_capacity(rows.len());
+    for row in rows {
+        let row = row
+            .as_array()
+            .filter(|r| !r.is_empty())
+            .ok_or_else(invalid)?;
+        let values = row
+            .iter()
+            .map(|x| x.as_f64().map(|x| x as f32))
+            .collect::<Option<Vec<f32>>>()
+            .ok_or_else(invalid)?;
+        matrix.push(values);
+    }
+    if matrix.iter().any(|r| r.len() != matrix[0].len()) {
+        return Err(invalid());
+    }
+    Ok(matrix)
+}
+
+/// A small, seedable pseudo-random generator (SplitMix64) for reproducible inputs.
+#[cfg(feature = "tract")]
+struct SplitMix64(u64);
+
+#[cfg(feature = "tract")]
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a value uniformly distributed in `[-1, 1)`.
+    fn next_f32(&mut self) -> f32 {
+        let unit = (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32;
+        unit * 2.0 - 1.0
+    }
+}
+
+/// Generates `rows x cols` reproducible random values in `[-1, 1)`.
+#[cfg(feature = "tract")]
+pub(crate) fn random_inputs(seed: u64, rows: usize, cols: usize) -> Vec<f32> {
+    let mut rng = SplitMix64(seed);
+    (0..rows * cols).map(|_| rng.next_f32()).collect()
+}
+
+/// Returns the index of the largest value in `row`.
+#[cfg(feature = "tract")]
+fn argmax(row: &[f32]) -> usize {
+    row.iter()
+        .enumerate()
+        .fold((0, f32::NEG_INFINITY), |(best_i, best), (i, &x)| {
+            if x > best {
+                (i, x)
+            } else {
+                (best_i, best)
+            }
+        })
+        .0
+}
+
+/// Builds the comparison report for two `rows x cols` outputs.
+///
+/// The relative difference of an element is `|a - b| / max(|a|, |b|)`, or zero when both
+/// are zero. The comparison passes if every element satisfies
+/// `|a - b| <= atol + rtol * |b|`.
+#[cfg(feature = "tract")]
+fn diff_report(a: &[f32], b: &[f32], rows: usize, cols: usize, atol: f64, rtol: f64) -> Value {
+    let mut max_abs = 0.0f64;
+    let mut sum_abs = 0.0f64;
+    let mut max_rel = 0.0f64;
+    let mut sum_rel = 0.0f64;
+    let mut pass = true;
+    for (&x, &y) in a.iter().zip(b) {
+        let (x, y) = (x as f64, y as f64);
+        let abs = (x - y).abs();
+        let scale = x.abs().max(y.abs());
+        let rel = if scale > 0.0 { abs / scale } else { 0.0 };
+        max_abs = max_abs.max(abs);
+        max_rel = max_rel.max(rel);
+        sum_abs += abs;
+        sum_rel += rel;
+        if abs.is_nan() || abs > atol + rtol * y.abs() {
+            pass = false;
+        }
+    }
+    let count = a.len().max(1) as f64;
+    let argmax_mismatches = if cols > 0 {
+        a.chunks(cols)
+            .zip(b.chunks(cols))
+            .filter(|(ra, rb)| argmax(ra) != argmax(rb))
+            .count()
+    } else {
+        0
+    };
+    json!({
+        "rows": rows,
+        "cols": cols,
+        "max_abs_diff": max_abs,
+        "mean_abs_diff": sum_abs / count,
+        "max_rel_diff": max_rel,
+        "mean_rel_diff": sum_rel / count,
+        "argmax_mismatch_fraction": argmax_mismatches as f64 / rows.max(1) as f64,
+        "atol": atol,
+        "rtol": rtol,
+        "pass": pass,
+    })
+}
+
+/// Formats a model shape for error messages, or "unknown" if it could not be determined.
+#[cfg(feature = "tract")]
+fn shape_label(shape: &Option<Vec<i64>>) -> String {
+    match shape {
+        Some(shape) => format!("{:?}", shape),
+        None => "unknown".to_string(),
+    }
+}
+
+/// Runs a model on `rows` input rows, one row at a time if its batch size is fixed to 1.
+#[cfg(feature = "tract")]
+fn run_rows(
+    model: &OnnxModel,
+    input: &[f32],
+    rows: usize,
+    cols: usize,
+) -> Result<(Vec<f32>, usize, usize), InferaError> {
+    if model.input_shape.as_deref().and_then(|s| s.first()) == Some(&1) && rows > 1 {
+        let mut output = Vec::new();
+        let mut output_cols = 0;
+        for row in input.chunks(cols) {
+            let (row_output, _, row_cols) = crate::engine::infer_rows(model, row, 1, cols)?;
+            output_cols = row_cols;
+            output.extend(row_output);
+        }
+        return Ok((output, rows, output_cols));
+    }
+    crate::engine::infer_rows(model, input, rows, cols)
+}
+
+/// Compares two loaded models on the same inputs and returns a JSON report.
+///
+/// Inputs are either the `inputs` matrix from the options or `samples` rows of seeded
+/// random values in `[-1, 1)`. Both models must have the same input shape.
+///
+/// # Arguments
+///
+/// * `name_a` - The name of the reference model.
+/// * `name_b` - The name of the model compared against the reference.
+/// * `options_json` - A JSON object with comparison options (may be empty).
+///
+/// # Returns
+///
+/// * `Ok(String)` containing the JSON report.
+/// * `Err(InferaError)` if a model is not found, the models are incompatible, or inference fails.
+#[cfg(feature = "tract")]
+pub(crate) fn compare_models_impl(
+    name_a: &str,
+    name_b: &str,
+    options_json: &str,
+) -> Result<String, InferaError> {
+    let options = CompareOptions::from_json(options_json)?;
+    let model_a = get_model(name_a)?;
+    let model_b = get_model(name_b)?;
+    if model_a.input_shape != model_b.input_shape {
+        return Err(InferaError::IncompatibleModels(format!(
+            "input shape of '{}' is {} but input shape of '{}' is {}",
+            name_a,
+            shape_label(&model_a.input_shape),
+            name_b,
+            shape_label(&model_b.input_shape)
+        )));
+    }
+    let inner = model_a
+        .input_shape
+        .as_deref()
+        .and_then(|s| s.get(1..))
+        .unwrap_or_default();
+
+    let (input, rows, cols) = match &options.inputs {
+        Some(matrix) => {
+            let cols = matrix[0].len();
+            if !inner.is_empty() && inner.iter().all(|&d| d > 0) {
+                let expected: usize = inner.iter().map(|&d| d as usize).product();
+                if cols != expected {
+                    return Err(InferaError::InvalidInputShape {
+                        expected: format!("batch x {:?}", inner),
+                        actual: format!("{} x {}", matrix.len(), cols),
+                    });
+                }
+            }
+            (matrix.concat(), matrix.len(), cols)
+        }
+        None => {
+            if inner.is_empty() || inner.iter().any(|&d| d <= 0) {
+                return Err(InferaError::IncompatibleModels(format!(
+                    "cannot generate random inputs for input shape {}; pass \"inputs\" instead",
+                    shape_label(&model_a.input_shape)
+                )));
+            }
+            let cols = inner.iter().map(|&d| d as usize).product();
+            let rows = options.samples;
+            (random_inputs(options.seed, rows, cols), rows, cols)
+        }
+    };
+
+    let (out_a, rows_a, cols_a) = run_rows(&model_a, &input, rows, cols)?;
+    let (out_b, rows_b, cols_b) = run_rows(&model_b, &input, rows, cols)?;
+    if (rows_a, cols_a) != (rows_b, cols_b) {
+        return Err(InferaError::IncompatibleModels(format!(
+            "output of '{}' is {} x {} but output of '{}' is {} x {}",
+            name_a, rows_a, cols_a, name_b, rows_b, cols_b
+        )));
+    }
+
+    let mut report = diff_report(&out_a, &out_b, rows_a, cols_a, options.atol, options.rtol);
+    report["model_a"] = json!(name_a);
+    report["model_b"] = json!(name_b);
+    serde_json::to_string(&report).map_err(|e| InferaError::JsonError(e.to_string()))
+}
+
+/// A stub for `compare_models_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn compare_models_impl(
+    _name_a: &str,
+    _name_b: &str,
+    _options_json: &str,
+) -> Result<String, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+#[cfg(all(test, feature = "tract"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_options_from_json() {
+        let options = CompareOptions::from_json("").unwrap();
+        assert_eq!(options.samples, DEFAULT_SAMPLES);
+        assert!(options.inputs.is_none());
+
+        let options = CompareOptions::from_json(
+            r#"{"samples": 10, "seed": 7, "atol": 0.1, "inputs": [[1, 2], [3, 4]]}"#,
+        )
+        .unwrap();
+        assert_eq!(options.samples, 10);
+        assert_eq!(options.seed, 7);
+        assert_eq!(options.atol, 0.1);
+        assert_eq!(options.inputs, Some(vec![vec![1.0, 2.0], vec![3.0, 4.0]]));
+
+        assert!(CompareOptions::from_json(r#"{"samples": 0}"#).is_err());
+        assert!(CompareOptions::from_json(r#"{"atol": -1}"#).is_err());
+        assert!(CompareOptions::from_json(r#"{"inputs": [[1, 2], [3]]}"#).is_err());
+    }
+
+    #[test]
+    fn test_random_inputs_are_seeded() {
+        let a = random_inputs(1, 4, 3);
+        assert_eq!(a, random_inputs(1, 4, 3));
+        assert_ne!(a, random_inputs(2, 4, 3));
+        assert!(a.iter().all(|&x| (-1.0..1.0).contains(&x)));
+    }
+
+    #[test]
+    fn test_diff_report() {
+        let a = [1.0, 2.0, 3.0, 0.0];
+        let b = [1.0, 2.5, 1.0, 0.0];
+        let report = diff_report(&a, &b, 2, 2, 1e-5, 1e-4);
+        assert_eq!(report["max_abs_diff"], 2.0);
+        assert_eq!(report["mean_abs_diff"], 0.625);
+        assert_eq!(report["max_rel_diff"], 2.0 / 3.0);
+        // Row 0 argmax: 1 vs 1; row 1 argmax: 0 vs 0
+        assert_eq!(report["argmax_mismatch_fraction"], 0.0);
+        assert_eq!(report["pass"], false);
+
+        let report = diff_report(&[0.0, 1.0], &[1.0, 0.0], 1, 2, 1e-5, 1e-4);
+        assert_eq!(report["argmax_mismatch_fraction"], 1.0);
+
+        let report = diff_report(&a, &a, 2, 2, 0.0, 0.0);
+        assert_eq!(report["max_abs_diff"], 0.0);
+        assert_eq!(report["pass"], true);
+    }
+}
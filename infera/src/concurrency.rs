@@ -0,0 +1,209 @@
+// Per-model concurrency limits, which cap how many predictions run on a model at once.
+
+#[cfg(feature = "tract")]
+use crate::config::CONFIG;
+#[cfg(feature = "tract")]
+use crate::error::InferaError;
+use once_cell::sync::Lazy;
+use parking_lot::{Condvar, Mutex, RwLock};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Debug, Default)]
+struct LimitState {
+    /// The maximum number of concurrent predictions, or 0 once the limit is removed.
+    max: usize,
+    /// The number of predictions holding a permit.
+    #[cfg(feature = "tract")]
+    running: usize,
+}
+
+/// A counting semaphore for one model.
+#[derive(Debug, Default)]
+struct Limit {
+    state: Mutex<LimitState>,
+    released: Condvar,
+}
+
+/// Concurrency limits by model name. Models without an entry are unlimited.
+static LIMITS: Lazy<RwLock<HashMap<String, Arc<Limit>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Permission to run one prediction on a model, released when dropped.
+#[cfg(feature = "tract")]
+#[derive(Debug)]
+pub(crate) struct Permit {
+    limit: Option<Arc<Limit>>,
+}
+
+#[cfg(feature = "tract")]
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if let Some(limit) = self.limit.take() {
+            let mut state = limit.state.lock();
+            state.running = state.running.saturating_sub(1);
+            drop(state);
+            limit.released.notify_one();
+        }
+    }
+}
+
+/// Limits the model `name` to `max` concurrent predictions, or removes its limit if `max`
+/// is 0.
+///
+/// Changing the limit applies to predictions that have not started yet. Raising or
+/// removing it wakes predictions waiting for a permit.
+pub(crate) fn set_limit(name: &str, max: usize) {
+    let mut limits = LIMITS.write();
+    let limit = if max == 0 {
+        limits.remove(name)
+    } else {
+        Some(Arc::clone(limits.entry(name.to_string()).or_default()))
+    };
+    drop(limits);
+    if let Some(limit) = limit {
+        limit.state.lock().max = max;
+        limit.released.notify_all();
+    }
+}
+
+/// Moves the limit of the model `old_name` to `new_name` after the model is renamed.
+///
+/// The limit of a model replaced under `new_name` is removed, waking predictions waiting
+/// for its permits.
+pub(crate) fn rename_limit(old_name: &str, new_name: &str) {
+    let mut limits = LIMITS.write();
+    let replaced = match limits.remove(old_name) {
+        Some(limit) => limits.insert(new_name.to_string(), limit),
+        None => limits.remove(new_name),
+    };
+    drop(limits);
+    if let Some(limit) = replaced {
+        limit.state.lock().max = 0;
+        limit.released.notify_all();
+    }
+}
+
+/// Returns the concurrency limit of the model `name`, or 0 if it is unlimited.
+#[cfg(feature = "tract")]
+pub(crate) fn limit(name: &str) -> usize {
+    LIMITS
+        .read()
+        .get(name)
+        .map(|limit| limit.state.lock().max)
+        .unwrap_or(0)
+}
+
+/// Acquires a permit to run a prediction on the model `name`.
+///
+/// If the model is at its limit, this waits for a running prediction to finish, or fails
+/// with `InferaError::ConcurrencyLimitReached` when `INFERA_CONCURRENCY_BLOCK` is disabled.
+#[cfg(feature = "tract")]
+pub(crate) fn acquire(name: &str) -> Result<Permit, InferaError> {
+    acquire_with(name, CONFIG.concurrency_block)
+}
+
+#[cfg(feature = "tract")]
+fn acquire_with(name: &str, block: bool) -> Result<Permit, InferaError> {
+    let Some(limit) = LIMITS.read().get(name).cloned() else {
+        return Ok(Permit { limit: None });
+    };
+    let mut state = limit.state.lock();
+    while state.max != 0 && state.running >= state.max {
+        if !block {
+            return Err(InferaError::ConcurrencyLimitReached {
+                model: name.to_string(),
+                max: state.max,
+            });
+        }
+        limit.released.wait(&mut state);
+    }
+    state.running += 1;
+    drop(state);
+    Ok(Permit { limit: Some(limit) })
+}
+
+#[cfg(all(test, feature = "tract"))]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_limit_is_respected_by_more_threads_than_permits() {
+        let name = "concurrency_test_blocking";
+        set_limit(name, 2);
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let running = Arc::clone(&running);
+                let peak = Arc::clone(&peak);
+                thread::spawn(move || {
+                    let _permit = acquire_with(name, true).unwrap();
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(20));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+        assert_eq!(running.load(Ordering::SeqCst), 0);
+        set_limit(name, 0);
+    }
+
+    #[test]
+    fn test_non_blocking_acquire_fails_at_limit() {
+        let name = "concurrency_test_non_blocking";
+        set_limit(name, 1);
+        assert_eq!(limit(name), 1);
+        let permit = acquire_with(name, false).unwrap();
+        let err = acquire_with(name, false).unwrap_err();
+        assert!(matches!(
+            err,
+            InferaError::ConcurrencyLimitReached { max: 1, .. }
+        ));
+        assert_eq!(err.status() as i32, -6);
+        drop(permit);
+        let _permit = acquire_with(name, false).unwrap();
+
+        // Removing the limit makes the model unlimited again
+        set_limit(name, 0);
+        assert_eq!(limit(name), 0);
+        let _other = acquire_with(name, false).unwrap();
+    }
+
+    #[test]
+    fn test_renamed_model_keeps_its_limit() {
+        let (old_name, new_name) = ("concurrency_test_old", "concurrency_test_new");
+        set_limit(old_name, 3);
+        set_limit(new_name, 1);
+        let replaced = acquire_with(new_name, false).unwrap();
+        rename_limit(old_name, new_name);
+        assert_eq!(limit(old_name), 0);
+        assert_eq!(limit(new_name), 3);
+        drop(replaced);
+
+        // Without a limit to move, the replaced model's limit is dropped
+        rename_limit(old_name, new_name);
+        assert_eq!(limit(new_name), 0);
+    }
+
+    #[test]
+    fn test_raising_limit_wakes_waiters() {
+        let name = "concurrency_test_raise";
+        set_limit(name, 1);
+        let held = acquire_with(name, true).unwrap();
+        let waiter = thread::spawn(move || acquire_with(name, true).map(drop));
+        thread::sleep(Duration::from_millis(20));
+        set_limit(name, 2);
+        assert!(waiter.join().unwrap().is_ok());
+        drop(held);
+        set_limit(name, 0);
+    }
+}
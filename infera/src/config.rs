@@ -1,18 +1,69 @@
 // Centralized configuration management for Infera
 
 use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde_json::{json, Value};
 use std::env;
+use std::ffi::{c_char, CString};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
 /// Default cache size limit: 1GB
 const DEFAULT_CACHE_SIZE_LIMIT_BYTES: u64 = 1024 * 1024 * 1024;
 
+/// Default row threshold above which a batch is split into parallel chunks
+const DEFAULT_BATCH_CHUNK_ROWS: usize = 65536;
+
+/// Default maximum number of queued `infera_submit` requests
+const DEFAULT_MAX_PENDING: usize = 1024;
+
+/// Default time in milliseconds after which a queued request is run ahead of higher priorities
+const DEFAULT_QUEUE_AGING_MS: u64 = 1000;
+
+/// Default delay in milliseconds before the first retry of a failed model load
+const DEFAULT_LOAD_RETRY_DELAY_MS: u64 = 100;
+
+/// Default maximum number of redirects followed for a single HTTP request
+const DEFAULT_HTTP_MAX_REDIRECTS: usize = 10;
+
+/// Default number of previous versions kept per model name when versioning is enabled
+const DEFAULT_MODEL_HISTORY_DEPTH: usize = 3;
+
+/// Default maximum size of a single inference input: 256MB
+const DEFAULT_MAX_BLOB_BYTES: usize = 256 * 1024 * 1024;
+
 /// Default cache directory name
 const DEFAULT_CACHE_DIR_NAME: &str = "infera_cache";
 
 /// Global configuration singleton
 pub static CONFIG: Lazy<InferaConfig> = Lazy::new(InferaConfig::from_env);
 
+/// Cache size limit in bytes, initialized from `CONFIG` and adjustable at runtime
+static CACHE_SIZE_LIMIT: Lazy<AtomicU64> = Lazy::new(|| AtomicU64::new(CONFIG.cache_size_limit));
+
+/// Get the current cache size limit in bytes
+pub fn cache_size_limit() -> u64 {
+    CACHE_SIZE_LIMIT.load(Ordering::Relaxed)
+}
+
+/// Set the cache size limit in bytes for the rest of the process lifetime
+pub fn set_cache_size_limit(bytes: u64) {
+    CACHE_SIZE_LIMIT.store(bytes, Ordering::Relaxed);
+}
+
+/// Whether Infera is offline, initialized from `CONFIG` and adjustable at runtime
+static OFFLINE: Lazy<AtomicBool> = Lazy::new(|| AtomicBool::new(CONFIG.offline));
+
+/// Get whether Infera is offline (remote models are only served from the cache)
+pub fn offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Set whether Infera is offline for the rest of the process lifetime
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
 /// Logging levels
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum LogLevel {
@@ -49,9 +100,9 @@ impl LogLevel {
 pub enum CacheEvictionStrategy {
     /// Least Recently Used - evict oldest accessed files first
     LRU,
-    /// Least Frequently Used - evict least accessed files first (future)
+    /// Least Frequently Used - evict least accessed files first
     LFU,
-    /// First In First Out - evict oldest downloaded files first (future)
+    /// First In First Out - evict oldest downloaded files first
     FIFO,
 }
 
@@ -64,6 +115,66 @@ impl CacheEvictionStrategy {
             _ => None,
         }
     }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheEvictionStrategy::LRU => "LRU",
+            CacheEvictionStrategy::LFU => "LFU",
+            CacheEvictionStrategy::FIFO => "FIFO",
+        }
+    }
+}
+
+/// How cached model files are named
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheNaming {
+    /// The hex-encoded SHA-256 of the URL, e.g. `3f2a...9c.onnx`
+    Hash,
+    /// The sanitized last path segment of the URL plus a short hash suffix, e.g.
+    /// `resnet50-a1b2c3d4.onnx`
+    Readable,
+}
+
+impl CacheNaming {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "hash" => Some(CacheNaming::Hash),
+            "readable" => Some(CacheNaming::Readable),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheNaming::Hash => "hash",
+            CacheNaming::Readable => "readable",
+        }
+    }
+}
+
+/// Download settings that apply to the hosts matching a glob pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostOverride {
+    /// Glob pattern matched against the host name (e.g., `*.internal.corp`)
+    pub host: String,
+    /// HTTP request timeout in seconds, replacing `http_timeout_secs` for matching hosts
+    pub timeout_secs: Option<u64>,
+    /// HTTP connection timeout in seconds, replacing `http_connect_timeout_secs` for matching hosts
+    pub connect_timeout_secs: Option<u64>,
+    /// Extra request headers (e.g., `Authorization`) sent to matching hosts
+    pub headers: Vec<(String, String)>,
+}
+
+impl HostOverride {
+    /// Serialize the override with its header names only, since header values usually hold credentials
+    pub fn to_json(&self) -> Value {
+        json!({
+            "host": self.host,
+            "timeout_secs": self.timeout_secs,
+            "connect_timeout_secs": self.connect_timeout_secs,
+            "headers": self.headers.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+        })
+    }
 }
 
 /// Configuration options for Infera
@@ -72,6 +183,9 @@ pub struct InferaConfig {
     /// Directory path for caching remote models
     pub cache_dir: PathBuf,
 
+    /// Directory for in-progress (`.part`) downloads, or `None` to use the cache directory
+    pub temp_dir: Option<PathBuf>,
+
     /// Maximum cache size in bytes
     pub cache_size_limit: u64,
 
@@ -82,12 +196,107 @@ pub struct InferaConfig {
     /// HTTP request timeout in seconds
     pub http_timeout_secs: u64,
 
+    /// HTTP connection timeout in seconds
+    pub http_connect_timeout_secs: u64,
+
     /// Number of retry attempts for failed downloads
     pub http_retry_attempts: u32,
 
     /// Delay between retry attempts in milliseconds
     pub http_retry_delay_ms: u64,
 
+    /// Total time in seconds a download may spend across all retry attempts (0 = no limit)
+    pub http_total_deadline_secs: u64,
+
+    /// Maximum number of redirects followed for a single request
+    pub http_max_redirects: usize,
+
+    /// Whether redirects from HTTPS to plain HTTP are followed
+    pub allow_insecure_redirects: bool,
+
+    /// Whether to reject downloads whose Content-Type is not an ONNX/binary type
+    pub require_octet_stream: bool,
+
+    /// Whether to keep failed partial downloads as `.part.failed` files for debugging
+    pub keep_partial: bool,
+
+    /// Glob patterns of the hosts remote models may be downloaded from (empty allows any host)
+    pub allowed_hosts: Vec<String>,
+
+    /// Per-host download settings, applied by the first pattern that matches
+    pub host_overrides: Vec<HostOverride>,
+
+    /// Whether identical models cached from different URLs are stored once by content hash
+    pub cache_dedup: bool,
+
+    /// How cached model files are named
+    pub cache_naming: CacheNaming,
+
+    /// Whether remote models are only served from the cache, without any network request
+    pub offline: bool,
+
+    /// Seconds after a successful validation during which cached remote models are reused
+    /// without contacting the server (0 always revalidates, `u64::MAX` never does)
+    pub cache_ttl_secs: u64,
+
+    /// Free disk space in bytes that remote model downloads must leave on the cache filesystem
+    pub min_free_disk_bytes: u64,
+
+    /// Maximum number of bytes accepted from a single model download (0 means no limit)
+    pub max_download_bytes: u64,
+
+    /// Whether models must carry a valid detached signature from a trusted key to be loaded
+    pub require_signed_models: bool,
+
+    /// Whether loading a model under a taken name keeps the replaced model as a previous version
+    pub model_versioning: bool,
+
+    /// Maximum number of previous versions kept per model name
+    pub model_history_depth: usize,
+
+    /// Whether non-finite outputs count as errors of a canary's candidate model
+    pub canary_count_non_finite: bool,
+
+    /// Whether predictions on a model at its concurrency limit wait for a permit instead of failing
+    pub concurrency_block: bool,
+
+    /// Whether to reject inputs containing NaN or infinite values before inference
+    pub validate_input: bool,
+
+    /// Whether a BLOB input must hold exactly one sample unless a batch size is given
+    pub strict_blob: bool,
+
+    /// Whether a model output that is not f32 (or f16) is an error instead of being cast to f32
+    pub strict_output_types: bool,
+
+    /// Maximum size in bytes of a single inference input (BLOB or rows x cols buffer)
+    pub max_blob_bytes: usize,
+
+    /// Row threshold above which inputs are split into chunks run in parallel (0 disables chunking)
+    pub batch_chunk_rows: usize,
+
+    /// Maximum number of threads used to run chunks of a single batch
+    pub intra_op_threads: usize,
+
+    /// Number of worker threads serving `infera_submit` requests
+    pub worker_threads: usize,
+
+    /// Maximum number of queued `infera_submit` requests before submission fails
+    pub max_pending: usize,
+
+    /// Wait time in milliseconds after which a normal or low priority request runs first
+    pub queue_aging_ms: u64,
+
+    /// Maximum number of freed inference result buffers kept for reuse (0 disables pooling)
+    pub result_pool_size: usize,
+
+    /// Number of attempts made to load a local model file that may be transiently unreadable
+    /// or still being written (1 = no retry)
+    pub load_retry_attempts: u32,
+
+    /// Delay before the first load retry in milliseconds, doubled for each later retry
+    pub load_retry_delay_ms: u64,
+
     /// Cache eviction strategy
     #[allow(dead_code)]
     pub cache_eviction_strategy: CacheEvictionStrategy,
@@ -101,11 +310,43 @@ impl InferaConfig {
     pub fn from_env() -> Self {
         Self {
             cache_dir: Self::get_cache_dir_from_env(),
+            temp_dir: Self::get_temp_dir_from_env(),
             cache_size_limit: Self::get_cache_size_limit_from_env(),
             verbose_logging: Self::get_verbose_logging_from_env(),
             http_timeout_secs: Self::get_http_timeout_from_env(),
+            http_connect_timeout_secs: Self::get_http_connect_timeout_from_env(),
             http_retry_attempts: Self::get_http_retry_attempts_from_env(),
             http_retry_delay_ms: Self::get_http_retry_delay_from_env(),
+            http_total_deadline_secs: Self::get_http_total_deadline_from_env(),
+            http_max_redirects: Self::get_http_max_redirects_from_env(),
+            allow_insecure_redirects: Self::get_allow_insecure_redirects_from_env(),
+            require_octet_stream: Self::get_require_octet_stream_from_env(),
+            keep_partial: Self::get_keep_partial_from_env(),
+            allowed_hosts: Self::get_allowed_hosts_from_env(),
+            host_overrides: Self::get_host_overrides_from_env(),
+            cache_dedup: Self::get_cache_dedup_from_env(),
+            cache_naming: Self::get_cache_naming_from_env(),
+            offline: Self::get_offline_from_env(),
+            cache_ttl_secs: Self::get_cache_ttl_secs_from_env(),
+            min_free_disk_bytes: Self::get_min_free_disk_bytes_from_env(),
+            max_download_bytes: Self::get_max_download_bytes_from_env(),
+            require_signed_models: Self::get_require_signed_models_from_env(),
+            model_versioning: Self::get_model_versioning_from_env(),
+            model_history_depth: Self::get_model_history_depth_from_env(),
+            canary_count_non_finite: Self::get_canary_count_non_finite_from_env(),
+            concurrency_block: Self::get_concurrency_block_from_env(),
+            validate_input: Self::get_validate_input_from_env(),
+            strict_blob: Self::get_strict_blob_from_env(),
+            strict_output_types: Self::get_strict_output_types_from_env(),
+            max_blob_bytes: Self::get_max_blob_bytes_from_env(),
+            batch_chunk_rows: Self::get_batch_chunk_rows_from_env(),
+            intra_op_threads: Self::get_intra_op_threads_from_env(),
+            worker_threads: Self::get_worker_threads_from_env(),
+            max_pending: Self::get_max_pending_from_env(),
+            result_pool_size: Self::get_result_pool_size_from_env(),
+            queue_aging_ms: Self::get_queue_aging_ms_from_env(),
+            load_retry_attempts: Self::get_load_retry_attempts_from_env(),
+            load_retry_delay_ms: Self::get_load_retry_delay_from_env(),
             cache_eviction_strategy: Self::get_cache_eviction_strategy_from_env(),
             log_level: Self::get_log_level_from_env(),
         }
@@ -119,11 +360,31 @@ impl InferaConfig {
             .unwrap_or_else(|| env::temp_dir().join(DEFAULT_CACHE_DIR_NAME))
     }
 
+    /// Get the download temp directory from INFERA_TEMP_DIR or default (the cache directory)
+    fn get_temp_dir_from_env() -> Option<PathBuf> {
+        env::var("INFERA_TEMP_DIR")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .map(PathBuf::from)
+    }
+
     /// Get cache size limit from INFERA_CACHE_SIZE_LIMIT or default (1GB)
+    ///
+    /// The value is a number of bytes with an optional unit (e.g., `2GB` or `512MiB`). An
+    /// unparseable value logs a warning and falls back to the default.
     fn get_cache_size_limit_from_env() -> u64 {
         env::var("INFERA_CACHE_SIZE_LIMIT")
             .ok()
-            .and_then(|s| s.parse().ok())
+            .and_then(|s| {
+                let bytes = parse_byte_size(&s);
+                if bytes.is_none() {
+                    warn_during_init(format!(
+                        "Ignoring invalid INFERA_CACHE_SIZE_LIMIT '{}', using the default of {} bytes",
+                        s, DEFAULT_CACHE_SIZE_LIMIT_BYTES
+                    ));
+                }
+                bytes
+            })
             .unwrap_or(DEFAULT_CACHE_SIZE_LIMIT_BYTES)
     }
 
@@ -143,6 +404,14 @@ impl InferaConfig {
             .unwrap_or(30)
     }
 
+    /// Get HTTP connection timeout from INFERA_HTTP_CONNECT_TIMEOUT or default (10 seconds)
+    fn get_http_connect_timeout_from_env() -> u64 {
+        env::var("INFERA_HTTP_CONNECT_TIMEOUT")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(10)
+    }
+
     /// Get HTTP retry attempts from INFERA_HTTP_RETRY_ATTEMPTS or default (3)
     fn get_http_retry_attempts_from_env() -> u32 {
         env::var("INFERA_HTTP_RETRY_ATTEMPTS")
@@ -151,6 +420,22 @@ impl InferaConfig {
             .unwrap_or(3)
     }
 
+    /// Get the redirect limit from INFERA_HTTP_MAX_REDIRECTS or default (10)
+    fn get_http_max_redirects_from_env() -> usize {
+        env::var("INFERA_HTTP_MAX_REDIRECTS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_HTTP_MAX_REDIRECTS)
+    }
+
+    /// Get HTTPS to HTTP redirect permission from INFERA_ALLOW_INSECURE_REDIRECTS or default (false)
+    fn get_allow_insecure_redirects_from_env() -> bool {
+        env::var("INFERA_ALLOW_INSECURE_REDIRECTS")
+            .ok()
+            .map(|s| s == "1" || s.to_lowercase() == "true")
+            .unwrap_or(false)
+    }
+
     /// Get HTTP retry delay from INFERA_HTTP_RETRY_DELAY or default (1000ms)
     fn get_http_retry_delay_from_env() -> u64 {
         env::var("INFERA_HTTP_RETRY_DELAY")
@@ -159,6 +444,232 @@ impl InferaConfig {
             .unwrap_or(1000)
     }
 
+    /// Get the download deadline from INFERA_HTTP_TOTAL_DEADLINE_SECS or default (0, no limit)
+    fn get_http_total_deadline_from_env() -> u64 {
+        env::var("INFERA_HTTP_TOTAL_DEADLINE_SECS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0)
+    }
+
+    /// Get strict Content-Type checking from INFERA_REQUIRE_OCTET_STREAM or default (false)
+    fn get_require_octet_stream_from_env() -> bool {
+        env::var("INFERA_REQUIRE_OCTET_STREAM")
+            .ok()
+            .map(|s| s == "1" || s.to_lowercase() == "true")
+            .unwrap_or(false)
+    }
+
+    /// Get partial download retention from INFERA_KEEP_PARTIAL or default (false)
+    fn get_keep_partial_from_env() -> bool {
+        env::var("INFERA_KEEP_PARTIAL")
+            .ok()
+            .map(|s| s == "1" || s.to_lowercase() == "true")
+            .unwrap_or(false)
+    }
+
+    /// Get cache deduplication from INFERA_CACHE_DEDUP or default (false)
+    fn get_cache_dedup_from_env() -> bool {
+        env::var("INFERA_CACHE_DEDUP")
+            .ok()
+            .map(|s| s == "1" || s.to_lowercase() == "true")
+            .unwrap_or(false)
+    }
+
+    /// Get the cache file naming scheme from INFERA_CACHE_NAMING or default (hash)
+    fn get_cache_naming_from_env() -> CacheNaming {
+        env::var("INFERA_CACHE_NAMING")
+            .ok()
+            .and_then(|s| CacheNaming::from_str(&s))
+            .unwrap_or(CacheNaming::Hash)
+    }
+
+    /// Get offline mode from INFERA_OFFLINE or default (false)
+    fn get_offline_from_env() -> bool {
+        env::var("INFERA_OFFLINE")
+            .ok()
+            .map(|s| s == "1" || s.to_lowercase() == "true")
+            .unwrap_or(false)
+    }
+
+    /// Get the host allowlist from INFERA_ALLOWED_HOSTS or default (empty, any host)
+    fn get_allowed_hosts_from_env() -> Vec<String> {
+        env::var("INFERA_ALLOWED_HOSTS")
+            .map(|s| parse_allowed_hosts(&s))
+            .unwrap_or_default()
+    }
+
+    /// Get per-host download settings from INFERA_HOST_OVERRIDES or default (none)
+    fn get_host_overrides_from_env() -> Vec<HostOverride> {
+        env::var("INFERA_HOST_OVERRIDES")
+            .ok()
+            .and_then(|s| parse_host_overrides(&s))
+            .unwrap_or_default()
+    }
+
+    /// Get the cache revalidation interval from INFERA_CACHE_TTL_SECS or default (0)
+    fn get_cache_ttl_secs_from_env() -> u64 {
+        env::var("INFERA_CACHE_TTL_SECS")
+            .ok()
+            .and_then(|s| parse_ttl_secs(&s))
+            .unwrap_or(0)
+    }
+
+    /// Get the free disk space guard from INFERA_MIN_FREE_DISK_BYTES or default (0)
+    fn get_min_free_disk_bytes_from_env() -> u64 {
+        env::var("INFERA_MIN_FREE_DISK_BYTES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0)
+    }
+
+    /// Get the download size cap from INFERA_MAX_DOWNLOAD_BYTES or default (0, no limit)
+    fn get_max_download_bytes_from_env() -> u64 {
+        env::var("INFERA_MAX_DOWNLOAD_BYTES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0)
+    }
+
+    /// Get signed model requirement from INFERA_REQUIRE_SIGNED_MODELS or default (false)
+    fn get_require_signed_models_from_env() -> bool {
+        env::var("INFERA_REQUIRE_SIGNED_MODELS")
+            .ok()
+            .map(|s| s == "1" || s.to_lowercase() == "true")
+            .unwrap_or(false)
+    }
+
+    /// Get model versioning from INFERA_MODEL_VERSIONING or default (false)
+    fn get_model_versioning_from_env() -> bool {
+        env::var("INFERA_MODEL_VERSIONING")
+            .ok()
+            .map(|s| s == "1" || s.to_lowercase() == "true")
+            .unwrap_or(false)
+    }
+
+    /// Get the per-name version history depth from INFERA_MODEL_HISTORY_DEPTH or default (3)
+    fn get_model_history_depth_from_env() -> usize {
+        env::var("INFERA_MODEL_HISTORY_DEPTH")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_MODEL_HISTORY_DEPTH)
+    }
+
+    /// Get the canary non-finite output policy from INFERA_CANARY_COUNT_NON_FINITE or default (false)
+    fn get_canary_count_non_finite_from_env() -> bool {
+        env::var("INFERA_CANARY_COUNT_NON_FINITE")
+            .ok()
+            .map(|s| s == "1" || s.to_lowercase() == "true")
+            .unwrap_or(false)
+    }
+
+    /// Get the concurrency limit policy from INFERA_CONCURRENCY_BLOCK or default (true)
+    fn get_concurrency_block_from_env() -> bool {
+        env::var("INFERA_CONCURRENCY_BLOCK")
+            .ok()
+            .map(|s| s == "1" || s.to_lowercase() == "true")
+            .unwrap_or(true)
+    }
+
+    /// Get input validation setting from INFERA_VALIDATE_INPUT or default (false)
+    fn get_validate_input_from_env() -> bool {
+        env::var("INFERA_VALIDATE_INPUT")
+            .ok()
+            .map(|s| s == "1" || s.to_lowercase() == "true")
+            .unwrap_or(false)
+    }
+
+    /// Get strict BLOB size checking from INFERA_STRICT_BLOB or default (false)
+    fn get_strict_blob_from_env() -> bool {
+        env::var("INFERA_STRICT_BLOB")
+            .ok()
+            .map(|s| s == "1" || s.to_lowercase() == "true")
+            .unwrap_or(false)
+    }
+
+    /// Get strict output type checking from INFERA_STRICT_OUTPUT_TYPES or default (false)
+    fn get_strict_output_types_from_env() -> bool {
+        env::var("INFERA_STRICT_OUTPUT_TYPES")
+            .ok()
+            .map(|s| s == "1" || s.to_lowercase() == "true")
+            .unwrap_or(false)
+    }
+
+    /// Get the maximum input size from INFERA_MAX_BLOB_BYTES or default (256MB)
+    fn get_max_blob_bytes_from_env() -> usize {
+        env::var("INFERA_MAX_BLOB_BYTES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_MAX_BLOB_BYTES)
+    }
+
+    /// Get batch chunking threshold from INFERA_BATCH_CHUNK_ROWS or default (65536 rows)
+    fn get_batch_chunk_rows_from_env() -> usize {
+        env::var("INFERA_BATCH_CHUNK_ROWS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_BATCH_CHUNK_ROWS)
+    }
+
+    /// Get intra-op thread count from INFERA_INTRA_OP_THREADS or default (available parallelism)
+    fn get_intra_op_threads_from_env() -> usize {
+        env::var("INFERA_INTRA_OP_THREADS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|&n: &usize| n > 0)
+            .unwrap_or_else(default_intra_op_threads)
+    }
+
+    /// Get worker thread count from INFERA_WORKER_THREADS or default (available parallelism)
+    fn get_worker_threads_from_env() -> usize {
+        env::var("INFERA_WORKER_THREADS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|&n: &usize| n > 0)
+            .unwrap_or_else(default_intra_op_threads)
+    }
+
+    /// Get the result buffer pool size from INFERA_RESULT_POOL_SIZE or default (0, disabled)
+    fn get_result_pool_size_from_env() -> usize {
+        env::var("INFERA_RESULT_POOL_SIZE")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0)
+    }
+
+    /// Get the submission queue bound from INFERA_MAX_PENDING or default (1024)
+    fn get_max_pending_from_env() -> usize {
+        env::var("INFERA_MAX_PENDING")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|&n: &usize| n > 0)
+            .unwrap_or(DEFAULT_MAX_PENDING)
+    }
+
+    /// Get the queue aging threshold from INFERA_QUEUE_AGING_MS or default (1000ms)
+    fn get_queue_aging_ms_from_env() -> u64 {
+        env::var("INFERA_QUEUE_AGING_MS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_QUEUE_AGING_MS)
+    }
+
+    /// Get load retry attempts from INFERA_LOAD_RETRY_ATTEMPTS or default (1)
+    fn get_load_retry_attempts_from_env() -> u32 {
+        env::var("INFERA_LOAD_RETRY_ATTEMPTS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(1)
+    }
+
+    /// Get the load retry delay from INFERA_LOAD_RETRY_DELAY or default (100ms)
+    fn get_load_retry_delay_from_env() -> u64 {
+        env::var("INFERA_LOAD_RETRY_DELAY")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_LOAD_RETRY_DELAY_MS)
+    }
+
     /// Get cache eviction strategy from INFERA_CACHE_EVICTION or default (LRU)
     fn get_cache_eviction_strategy_from_env() -> CacheEvictionStrategy {
         env::var("INFERA_CACHE_EVICTION")
@@ -175,6 +686,52 @@ impl InferaConfig {
             .unwrap_or(LogLevel::Warn)
     }
 
+    /// Serialize every configuration field into a JSON object keyed by field name
+    pub fn to_json(&self) -> Value {
+        json!({
+            "cache_dir": self.cache_dir.to_string_lossy(),
+            "temp_dir": self.temp_dir.as_ref().map(|dir| dir.to_string_lossy()),
+            "cache_size_limit": self.cache_size_limit,
+            "verbose_logging": self.verbose_logging,
+            "http_timeout_secs": self.http_timeout_secs,
+            "http_connect_timeout_secs": self.http_connect_timeout_secs,
+            "http_retry_attempts": self.http_retry_attempts,
+            "http_retry_delay_ms": self.http_retry_delay_ms,
+            "http_total_deadline_secs": self.http_total_deadline_secs,
+            "http_max_redirects": self.http_max_redirects,
+            "allow_insecure_redirects": self.allow_insecure_redirects,
+            "require_octet_stream": self.require_octet_stream,
+            "keep_partial": self.keep_partial,
+            "allowed_hosts": self.allowed_hosts,
+            "host_overrides": self.host_overrides.iter().map(HostOverride::to_json).collect::<Vec<_>>(),
+            "cache_dedup": self.cache_dedup,
+            "cache_naming": self.cache_naming.as_str(),
+            "offline": self.offline,
+            "cache_ttl_secs": self.cache_ttl_secs,
+            "min_free_disk_bytes": self.min_free_disk_bytes,
+            "max_download_bytes": self.max_download_bytes,
+            "require_signed_models": self.require_signed_models,
+            "model_versioning": self.model_versioning,
+            "model_history_depth": self.model_history_depth,
+            "canary_count_non_finite": self.canary_count_non_finite,
+            "concurrency_block": self.concurrency_block,
+            "validate_input": self.validate_input,
+            "strict_blob": self.strict_blob,
+            "strict_output_types": self.strict_output_types,
+            "max_blob_bytes": self.max_blob_bytes,
+            "batch_chunk_rows": self.batch_chunk_rows,
+            "intra_op_threads": self.intra_op_threads,
+            "worker_threads": self.worker_threads,
+            "max_pending": self.max_pending,
+            "result_pool_size": self.result_pool_size,
+            "queue_aging_ms": self.queue_aging_ms,
+            "load_retry_attempts": self.load_retry_attempts,
+            "load_retry_delay_ms": self.load_retry_delay_ms,
+            "cache_eviction_strategy": self.cache_eviction_strategy.as_str(),
+            "log_level": self.log_level.as_str(),
+        })
+    }
+
     /// Check if a log message should be printed based on current log level
     pub fn should_log(&self, level: LogLevel) -> bool {
         level <= self.log_level
@@ -185,24 +742,190 @@ impl Default for InferaConfig {
     fn default() -> Self {
         Self {
             cache_dir: env::temp_dir().join(DEFAULT_CACHE_DIR_NAME),
+            temp_dir: None,
             cache_size_limit: DEFAULT_CACHE_SIZE_LIMIT_BYTES,
             verbose_logging: false,
             http_timeout_secs: 30,
+            http_connect_timeout_secs: 10,
             http_retry_attempts: 3,
             http_retry_delay_ms: 1000,
+            http_total_deadline_secs: 0,
+            http_max_redirects: DEFAULT_HTTP_MAX_REDIRECTS,
+            allow_insecure_redirects: false,
+            require_octet_stream: false,
+            keep_partial: false,
+            allowed_hosts: Vec::new(),
+            host_overrides: Vec::new(),
+            cache_dedup: false,
+            cache_naming: CacheNaming::Hash,
+            offline: false,
+            cache_ttl_secs: 0,
+            min_free_disk_bytes: 0,
+            max_download_bytes: 0,
+            require_signed_models: false,
+            model_versioning: false,
+            model_history_depth: DEFAULT_MODEL_HISTORY_DEPTH,
+            canary_count_non_finite: false,
+            concurrency_block: true,
+            validate_input: false,
+            strict_blob: false,
+            strict_output_types: false,
+            max_blob_bytes: DEFAULT_MAX_BLOB_BYTES,
+            batch_chunk_rows: DEFAULT_BATCH_CHUNK_ROWS,
+            intra_op_threads: default_intra_op_threads(),
+            worker_threads: default_intra_op_threads(),
+            max_pending: DEFAULT_MAX_PENDING,
+            result_pool_size: 0,
+            queue_aging_ms: DEFAULT_QUEUE_AGING_MS,
+            load_retry_attempts: 1,
+            load_retry_delay_ms: DEFAULT_LOAD_RETRY_DELAY_MS,
             cache_eviction_strategy: CacheEvictionStrategy::LRU,
             log_level: LogLevel::Warn,
         }
     }
 }
 
-/// Log a message if the log level is enabled
+/// Parses a cache TTL in seconds, where "infinite" (any case) means `u64::MAX`
+pub fn parse_ttl_secs(value: &str) -> Option<u64> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("infinite") {
+        return Some(u64::MAX);
+    }
+    value.parse().ok()
+}
+
+/// Parses a comma-separated list of host glob patterns, ignoring empty entries
+pub fn parse_allowed_hosts(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Parses per-host download settings from a JSON array of objects with a `host` pattern
+/// and optional `timeout_secs`, `connect_timeout_secs`, and `headers` (an object of
+/// header names to values). Returns `None` if the JSON is malformed.
+pub fn parse_host_overrides(value: &str) -> Option<Vec<HostOverride>> {
+    let value: Value = serde_json::from_str(value).ok()?;
+    value
+        .as_array()?
+        .iter()
+        .map(|entry| {
+            let host = entry.get("host")?.as_str()?.trim().to_lowercase();
+            let headers = match entry.get("headers") {
+                Some(headers) => headers
+                    .as_object()?
+                    .iter()
+                    .map(|(name, value)| Some((name.clone(), value.as_str()?.to_string())))
+                    .collect::<Option<Vec<_>>>()?,
+                None => Vec::new(),
+            };
+            Some(HostOverride {
+                host,
+                timeout_secs: entry.get("timeout_secs").and_then(Value::as_u64),
+                connect_timeout_secs: entry.get("connect_timeout_secs").and_then(Value::as_u64),
+                headers,
+            })
+        })
+        .collect()
+}
+
+/// The number of threads available to the process, or 1 if it cannot be determined
+fn default_intra_op_threads() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+/// Parse a size in bytes, with an optional unit suffix
+///
+/// Accepts a plain number of bytes (`1024`) or a number followed by `B`, a decimal unit
+/// (`KB`, `MB`, `GB`, powers of 1000), or a binary unit (`KiB`, `MiB`, `GiB`, powers of
+/// 1024). Units are case-insensitive and may be separated from the number by spaces.
+/// Returns `None` for anything else, including sizes that overflow a `u64`.
+pub(crate) fn parse_byte_size(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: u64 = number.parse().ok()?;
+    let multiplier: u64 = match unit.trim_start().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "kb" => 1000,
+        "mb" => 1000 * 1000,
+        "gb" => 1000 * 1000 * 1000,
+        "kib" => 1024,
+        "mib" => 1024 * 1024,
+        "gib" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    number.checked_mul(multiplier)
+}
+
+/// Log a warning found while `CONFIG` is being initialized
+///
+/// `log!` reads the log level from `CONFIG`, which would re-enter its initialization, so
+/// the level is read from `INFERA_LOG_LEVEL` directly.
+fn warn_during_init(message: String) {
+    deliver_log(
+        LogLevel::Warn,
+        module_path!(),
+        || LogLevel::Warn <= InferaConfig::get_log_level_from_env(),
+        || message,
+    );
+}
+
+/// The callback that receives log records when set with `infera_set_log_callback`.
+///
+/// `level` is `0` (error), `1` (warn), `2` (info), or `3` (debug). `target` is the Rust
+/// module the record comes from (e.g., `infera::http`) and `message` is the formatted
+/// message. Both strings are only valid for the duration of the call.
+pub type InferaLogCallback =
+    extern "C" fn(level: i32, target: *const c_char, message: *const c_char);
+
+/// The registered log callback, if any
+static LOG_CALLBACK: Lazy<RwLock<Option<InferaLogCallback>>> = Lazy::new(|| RwLock::new(None));
+
+/// Set or clear (with `None`) the callback that receives every log record
+pub fn set_log_callback(callback: Option<InferaLogCallback>) {
+    *LOG_CALLBACK.write() = callback;
+}
+
+/// Send a log record to the registered callback, or print it to stderr if the level is enabled
+///
+/// A registered callback receives every record regardless of `INFERA_LOG_LEVEL`, so the host
+/// can apply its own filtering. The message is only formatted if it will be delivered.
+pub fn emit_log(level: LogLevel, target: &str, message: impl FnOnce() -> String) {
+    deliver_log(level, target, || CONFIG.should_log(level), message);
+}
+
+/// Send a log record to the registered callback, or print it to stderr if `enabled` says so
+fn deliver_log(
+    level: LogLevel,
+    target: &str,
+    enabled: impl FnOnce() -> bool,
+    message: impl FnOnce() -> String,
+) {
+    let callback = *LOG_CALLBACK.read();
+    match callback {
+        Some(callback) => {
+            let target = CString::new(target).unwrap_or_default();
+            let message = CString::new(message().replace('\0', "")).unwrap_or_default();
+            callback(level as i32, target.as_ptr(), message.as_ptr());
+        }
+        None => {
+            if enabled() {
+                eprintln!("[{}] {}", level.as_str(), message());
+            }
+        }
+    }
+}
+
+/// Log a message through the log callback, or to stderr if the log level is enabled
 #[macro_export]
 macro_rules! log {
     ($level:expr, $($arg:tt)*) => {
-        if $crate::config::CONFIG.should_log($level) {
-            eprintln!("[{}] {}", $level.as_str(), format!($($arg)*));
-        }
+        $crate::config::emit_log($level, module_path!(), || format!($($arg)*))
     };
 }
 
@@ -210,14 +933,102 @@ macro_rules! log {
 mod tests {
     use super::*;
 
+    static RECORDS: Lazy<parking_lot::Mutex<Vec<(i32, String, String)>>> =
+        Lazy::new(|| parking_lot::Mutex::new(Vec::new()));
+
+    extern "C" fn record_log(level: i32, target: *const c_char, message: *const c_char) {
+        let (target, message) = unsafe {
+            (
+                std::ffi::CStr::from_ptr(target)
+                    .to_string_lossy()
+                    .into_owned(),
+                std::ffi::CStr::from_ptr(message)
+                    .to_string_lossy()
+                    .into_owned(),
+            )
+        };
+        RECORDS.lock().push((level, target, message));
+    }
+
+    #[test]
+    fn test_log_callback_receives_structured_fields() {
+        set_log_callback(Some(record_log));
+        // Debug is below the default WARN level, but the callback still receives it.
+        crate::log!(LogLevel::Debug, "callback test {}", 42);
+        crate::log!(LogLevel::Error, "callback test error");
+        // Warnings raised while CONFIG initializes bypass log! but still reach the callback
+        warn_during_init("callback test config warning".to_string());
+        set_log_callback(None);
+
+        let records = RECORDS.lock();
+        let ours: Vec<_> = records
+            .iter()
+            .filter(|(_, _, message)| message.starts_with("callback test"))
+            .collect();
+        assert_eq!(ours.len(), 3);
+        assert_eq!(ours[0].0, LogLevel::Debug as i32);
+        assert_eq!(ours[0].1, module_path!());
+        assert_eq!(ours[0].2, "callback test 42");
+        assert_eq!(ours[1].0, LogLevel::Error as i32);
+        assert_eq!(ours[1].2, "callback test error");
+        assert_eq!(ours[2].0, LogLevel::Warn as i32);
+        assert_eq!(ours[2].2, "callback test config warning");
+    }
+
+    #[test]
+    fn test_parse_byte_size() {
+        assert_eq!(parse_byte_size("2GB"), Some(2_000_000_000));
+        assert_eq!(parse_byte_size("512MiB"), Some(512 * 1024 * 1024));
+        assert_eq!(parse_byte_size("1024"), Some(1024));
+        assert_eq!(parse_byte_size(" 10 kib "), Some(10 * 1024));
+        assert_eq!(parse_byte_size("7B"), Some(7));
+        assert_eq!(parse_byte_size("garbage"), None);
+        assert_eq!(parse_byte_size("2TB"), None);
+        assert_eq!(parse_byte_size("GB"), None);
+        assert_eq!(parse_byte_size("-1"), None);
+        assert_eq!(parse_byte_size("1.5GB"), None);
+        assert_eq!(parse_byte_size("99999999999GiB"), None);
+    }
+
     #[test]
     fn test_default_config() {
         let config = InferaConfig::default();
         assert_eq!(config.cache_size_limit, DEFAULT_CACHE_SIZE_LIMIT_BYTES);
         assert!(!config.verbose_logging);
         assert_eq!(config.http_timeout_secs, 30);
+        assert_eq!(config.http_connect_timeout_secs, 10);
         assert_eq!(config.http_retry_attempts, 3);
         assert_eq!(config.http_retry_delay_ms, 1000);
+        assert_eq!(config.http_total_deadline_secs, 0);
+        assert_eq!(config.http_max_redirects, DEFAULT_HTTP_MAX_REDIRECTS);
+        assert!(!config.allow_insecure_redirects);
+        assert!(!config.require_octet_stream);
+        assert!(!config.keep_partial);
+        assert!(config.allowed_hosts.is_empty());
+        assert!(config.host_overrides.is_empty());
+        assert!(!config.cache_dedup);
+        assert_eq!(config.cache_naming, CacheNaming::Hash);
+        assert!(!config.offline);
+        assert_eq!(config.cache_ttl_secs, 0);
+        assert_eq!(config.min_free_disk_bytes, 0);
+        assert_eq!(config.max_download_bytes, 0);
+        assert!(!config.require_signed_models);
+        assert!(!config.model_versioning);
+        assert_eq!(config.model_history_depth, DEFAULT_MODEL_HISTORY_DEPTH);
+        assert!(!config.canary_count_non_finite);
+        assert!(config.concurrency_block);
+        assert!(!config.validate_input);
+        assert!(!config.strict_blob);
+        assert!(!config.strict_output_types);
+        assert_eq!(config.max_blob_bytes, DEFAULT_MAX_BLOB_BYTES);
+        assert_eq!(config.batch_chunk_rows, DEFAULT_BATCH_CHUNK_ROWS);
+        assert!(config.intra_op_threads >= 1);
+        assert!(config.worker_threads >= 1);
+        assert_eq!(config.max_pending, DEFAULT_MAX_PENDING);
+        assert_eq!(config.result_pool_size, 0);
+        assert_eq!(config.queue_aging_ms, DEFAULT_QUEUE_AGING_MS);
+        assert_eq!(config.load_retry_attempts, 1);
+        assert_eq!(config.load_retry_delay_ms, DEFAULT_LOAD_RETRY_DELAY_MS);
         assert_eq!(config.cache_eviction_strategy, CacheEvictionStrategy::LRU);
         assert_eq!(config.log_level, LogLevel::Warn);
     }
@@ -258,6 +1069,64 @@ mod tests {
         assert_eq!(CacheEvictionStrategy::from_str("invalid"), None);
     }
 
+    #[test]
+    fn test_cache_naming_parsing() {
+        assert_eq!(CacheNaming::from_str("hash"), Some(CacheNaming::Hash));
+        assert_eq!(
+            CacheNaming::from_str("Readable"),
+            Some(CacheNaming::Readable)
+        );
+        assert_eq!(CacheNaming::from_str("pretty"), None);
+    }
+
+    #[test]
+    fn test_parse_ttl_secs() {
+        assert_eq!(parse_ttl_secs("0"), Some(0));
+        assert_eq!(parse_ttl_secs(" 300 "), Some(300));
+        assert_eq!(parse_ttl_secs("Infinite"), Some(u64::MAX));
+        assert_eq!(parse_ttl_secs("-1"), None);
+        assert_eq!(parse_ttl_secs("soon"), None);
+    }
+
+    #[test]
+    fn test_parse_allowed_hosts() {
+        assert_eq!(
+            parse_allowed_hosts(" *.Internal.corp, models.example.com ,,"),
+            vec!["*.internal.corp", "models.example.com"]
+        );
+        assert!(parse_allowed_hosts("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_host_overrides() {
+        let overrides = parse_host_overrides(
+            r#"[{"host": "*.internal.corp", "timeout_secs": 120,
+                 "headers": {"Authorization": "Bearer token"}},
+                {"host": "models.example.com", "connect_timeout_secs": 5}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            overrides,
+            vec![
+                HostOverride {
+                    host: "*.internal.corp".to_string(),
+                    timeout_secs: Some(120),
+                    connect_timeout_secs: None,
+                    headers: vec![("Authorization".to_string(), "Bearer token".to_string())],
+                },
+                HostOverride {
+                    host: "models.example.com".to_string(),
+                    timeout_secs: None,
+                    connect_timeout_secs: Some(5),
+                    headers: Vec::new(),
+                },
+            ]
+        );
+        assert!(parse_host_overrides(r#"[{"timeout_secs": 1}]"#).is_none());
+        assert!(parse_host_overrides(r#"[{"host": "a", "headers": {"X": 1}}]"#).is_none());
+        assert!(parse_host_overrides("not json").is_none());
+    }
+
     #[test]
     fn test_should_log() {
         let mut config = InferaConfig::default();
@@ -1,21 +1,107 @@
 // Contains the core ONNX inference logic using the Tract library.
 
+#[cfg(feature = "tract")]
+use crate::concurrency;
+#[cfg(feature = "tract")]
+use crate::config::LogLevel;
+use crate::config::CONFIG;
+#[cfg(feature = "tract")]
+use crate::error::context;
+#[cfg(feature = "tract")]
+use crate::error::ErrorContext;
 use crate::error::InferaError;
-use crate::ffi_utils::InferaInferenceResult;
-use crate::model::{OnnxModel, MODELS};
+use crate::ffi_utils::{InferaInferenceResult, InferaProfiledResult, OutputBytes};
+#[cfg(feature = "tract")]
+use crate::ffi_utils::{ResultPool, RESULT_POOL};
+#[cfg(feature = "tract")]
+use crate::http;
+#[cfg(feature = "tract")]
+use crate::log;
+#[cfg(feature = "tract")]
+use crate::model::get_model;
+#[cfg(feature = "tract")]
+use crate::model::insert_model;
+#[cfg(feature = "tract")]
+use crate::model::namespace_of;
+#[cfg(feature = "tract")]
+use crate::model::parse_feature_names;
+#[cfg(feature = "tract")]
+use crate::model::set_postprocessor;
+#[cfg(feature = "tract")]
+use crate::model::InferaLoadOutcome;
+#[cfg(feature = "tract")]
+use crate::model::InitializerValue;
+#[cfg(feature = "tract")]
+use crate::model::PadMode;
+#[cfg(feature = "tract")]
+use crate::model::SourceKind;
+use crate::model::{LoadOptions, OnnxModel};
+#[cfg(feature = "tract")]
+use crate::postprocess::Postprocessor;
+#[cfg(feature = "tract")]
+use crate::signature;
 use serde_json::json;
+#[cfg(feature = "tract")]
+use std::borrow::Cow;
+#[cfg(feature = "tract")]
+use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
 use std::mem;
+#[cfg(feature = "tract")]
+use std::path::Path;
+#[cfg(feature = "tract")]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "tract")]
+use std::sync::Arc;
+#[cfg(feature = "tract")]
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+#[cfg(feature = "tract")]
+use once_cell::sync::Lazy;
+#[cfg(feature = "tract")]
+use rayon::prelude::*;
+#[cfg(feature = "tract")]
+use tract_onnx::pb::tensor_shape_proto::dimension;
+#[cfg(feature = "tract")]
+use tract_onnx::pb::{tensor_proto, type_proto, TensorProto, ValueInfoProto};
 #[cfg(feature = "tract")]
 use tract_onnx::prelude::*;
 
+/// The versions of `tract-onnx` and `tract-core` the library was built with, recorded by
+/// the build script from `Cargo.lock`.
+#[cfg(feature = "tract")]
+pub(crate) const TRACT_VERSIONS: (&str, &str) = (
+    env!("INFERA_TRACT_VERSION"),
+    env!("INFERA_TRACT_CORE_VERSION"),
+);
+
+/// Thread pool used to run the chunks of a large batch in parallel.
+///
+/// The pool is sized by `INFERA_INTRA_OP_THREADS` so chunked inference does not
+/// oversubscribe the machine. If the pool cannot be created, chunks run sequentially.
+#[cfg(feature = "tract")]
+static CHUNK_POOL: Lazy<Option<rayon::ThreadPool>> = Lazy::new(|| {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(CONFIG.intra_op_threads)
+        .thread_name(|i| format!("infera-chunk-{}", i))
+        .build()
+        .map_err(|e| {
+            log!(
+                LogLevel::Warn,
+                "Failed to create chunk thread pool, running chunks sequentially: {}",
+                e
+            )
+        })
+        .ok()
+});
+
 /// Compute (rows, cols) from a tensor shape by flattening all dimensions after the first into cols.
 ///
 /// Rules:
 /// - Scalar (shape = []): (1, 1)
 /// - 1D (shape = [n]): (n, 1)
 /// - N-D (shape = [d0, d1, d2, ...]): (d0, product(d1..))
+#[cfg(feature = "tract")]
 pub(crate) fn shape_rows_cols(shape: &[usize]) -> (usize, usize) {
     match shape.len() {
         0 => (1, 1),
@@ -28,6 +114,66 @@ pub(crate) fn shape_rows_cols(shape: &[usize]) -> (usize, usize) {
     }
 }
 
+/// Returns an error with the index of the first NaN or infinite value in `data`, if any.
+#[cfg(feature = "tract")]
+pub(crate) fn check_finite(data: &[f32]) -> Result<(), InferaError> {
+    match data.iter().position(|v| !v.is_finite()) {
+        Some(index) => Err(InferaError::NonFiniteInput { index }),
+        None => Ok(()),
+    }
+}
+
+/// Returns an error if the input has no rows or no columns.
+///
+/// An empty input would otherwise become a zero-sized tensor that the backend rejects
+/// with an unclear error.
+#[cfg(feature = "tract")]
+pub(crate) fn check_nonempty(rows: usize, cols: usize) -> Result<(), InferaError> {
+    if rows == 0 || cols == 0 {
+        return Err(InferaError::InvalidInputShape {
+            expected: "at least 1 row and 1 column".to_string(),
+            actual: format!("{} x {}", rows, cols),
+        });
+    }
+    Ok(())
+}
+
+/// Returns an error if `rows x cols` elements of `element_size` bytes exceed
+/// `INFERA_MAX_BLOB_BYTES`.
+///
+/// This runs before the input pointer is read, so an oversized length from the host
+/// never leads to a large allocation.
+pub(crate) fn check_input_size(
+    rows: usize,
+    cols: usize,
+    element_size: usize,
+) -> Result<(), InferaError> {
+    let bytes = rows
+        .checked_mul(cols)
+        .and_then(|n| n.checked_mul(element_size))
+        .unwrap_or(usize::MAX);
+    check_input_bytes(bytes)
+}
+
+/// Returns an error if an input of `bytes` bytes exceeds `INFERA_MAX_BLOB_BYTES`.
+pub(crate) fn check_input_bytes(bytes: usize) -> Result<(), InferaError> {
+    let limit = CONFIG.max_blob_bytes;
+    if bytes > limit {
+        return Err(InferaError::InputTooLarge {
+            limit,
+            actual: bytes,
+        });
+    }
+    Ok(())
+}
+
+/// Loads, compiles, and stores an ONNX model using the default load options.
+///
+/// See `load_model_with_options_impl` for details.
+pub(crate) fn load_model_impl(name: &str, path: &str) -> Result<(), InferaError> {
+    load_model_with_options_impl(name, path, LoadOptions::default())
+}
+
 /// Loads, compiles, and stores an ONNX model.
 ///
 /// This function reads an ONNX model from the given path, uses the Tract library
@@ -39,53 +185,653 @@ pub(crate) fn shape_rows_cols(shape: &[usize]) -> (usize, usize) {
 ///
 /// * `name` - The name to assign to the loaded model.
 /// * `path` - The file system path to the `.onnx` model file.
+/// * `options` - Options that control how the model is run.
+///
+/// The model is loaded up to `INFERA_LOAD_RETRY_ATTEMPTS` times (or `load_retries` + 1),
+/// waiting `INFERA_LOAD_RETRY_DELAY * 2^(n-1)` milliseconds after failed attempt `n`.
+/// A failed read is retried unless the file is unreadable or not a file. A model that
+/// fails to parse or compile is retried only if its file changed while waiting, since
+/// it was then still being written.
 ///
 /// # Returns
 ///
 /// * `Ok(())` on successful loading and compilation.
 /// * `Err(InferaError)` if the model cannot be found, parsed, or compiled.
 #[cfg(feature = "tract")]
-pub(crate) fn load_model_impl(name: &str, path: &str) -> Result<(), InferaError> {
-    let model = tract_onnx::onnx()
-        .model_for_path(path)
-        .map_err(|e| InferaError::OnnxError(e.to_string()))?
-        .into_optimized()
-        .map_err(|e| InferaError::OnnxError(e.to_string()))?
-        .into_runnable()
-        .map_err(|e| InferaError::OnnxError(e.to_string()))?;
-    let input_facts = model
-        .model()
-        .input_fact(0)
-        .map_err(|e| InferaError::OnnxError(e.to_string()))?;
-    let output_facts = model
-        .model()
-        .output_fact(0)
-        .map_err(|e| InferaError::OnnxError(e.to_string()))?;
-    let input_shape: Vec<i64> = input_facts
-        .shape
+pub(crate) fn load_model_with_options_impl(
+    name: &str,
+    path: &str,
+    options: LoadOptions,
+) -> Result<(), InferaError> {
+    let retries = options
+        .load_retries
+        .unwrap_or_else(|| CONFIG.load_retry_attempts.saturating_sub(1));
+    let attempts = retries.saturating_add(1);
+    let mut attempt = 1;
+    loop {
+        let stamp = file_stamp(path);
+        let (e, transient) = match read_file(path) {
+            Ok(bytes) => {
+                let result =
+                    load_model_bytes(name, path, Some(Path::new(path)), &bytes, options.clone());
+                match result {
+                    Ok(()) => return Ok(()),
+                    // The file may still be being written, which is only known after waiting
+                    Err(e) => (e, None),
+                }
+            }
+            Err(e) => {
+                let transient = is_transient_io_error(&e);
+                let e = InferaError::IoError(e.to_string())
+                    .context(ErrorContext::new("model_read", path));
+                (e, Some(transient))
+            }
+        };
+        let e = if retries > 0 {
+            e.context(ErrorContext::new("model_load", path).with_attempt(attempt, attempts))
+        } else {
+            e
+        };
+        if transient == Some(false) || attempt >= attempts {
+            return Err(e);
+        }
+        let delay_ms = CONFIG
+            .load_retry_delay_ms
+            .saturating_mul(1u64 << (attempt - 1).min(20));
+        log!(
+            LogLevel::Warn,
+            "Model load attempt {} of {} failed, waiting {}ms: {}",
+            attempt,
+            attempts,
+            delay_ms,
+            e
+        );
+        std::thread::sleep(Duration::from_millis(delay_ms));
+        // A model that fails to load from a file that has not changed is broken, not
+        // partially written
+        if transient.is_none() && file_stamp(path) == stamp {
+            return Err(e);
+        }
+        attempt += 1;
+    }
+}
+
+/// The modification time and size of the file at `path`, used to tell a model file that
+/// is still being written from one that is broken.
+#[cfg(feature = "tract")]
+fn file_stamp(path: &str) -> Option<(Option<SystemTime>, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok(), metadata.len()))
+}
+
+/// Returns whether a failed read may succeed if it is retried, such as after `EIO` or
+/// `ESTALE` on a network filesystem or when the file is about to be moved into place.
+/// Errors that say the file is unreadable or not a file are permanent.
+#[cfg(feature = "tract")]
+fn is_transient_io_error(e: &std::io::Error) -> bool {
+    !matches!(
+        e.kind(),
+        std::io::ErrorKind::PermissionDenied
+            | std::io::ErrorKind::InvalidInput
+            | std::io::ErrorKind::InvalidData
+            | std::io::ErrorKind::IsADirectory
+            | std::io::ErrorKind::Unsupported
+    )
+}
+
+/// Number of reads of each path that fail with a transient I/O error before the file is
+/// read, set by tests with `fail_reads` to stand in for a flaky filesystem.
+#[cfg(all(test, feature = "tract"))]
+static FAILING_READS: Lazy<parking_lot::Mutex<HashMap<String, u32>>> =
+    Lazy::new(|| parking_lot::Mutex::new(HashMap::new()));
+
+/// Makes the next `count` reads of the model file at `path` fail with a transient I/O
+/// error.
+#[cfg(all(test, feature = "tract"))]
+pub(crate) fn fail_reads(path: &str, count: u32) {
+    FAILING_READS.lock().insert(path.to_string(), count);
+}
+
+/// Reads the file at `path`, failing as set up by `fail_reads` in tests.
+#[cfg(feature = "tract")]
+fn read_file(path: &str) -> std::io::Result<Vec<u8>> {
+    #[cfg(test)]
+    {
+        let mut failing = FAILING_READS.lock();
+        if let Some(remaining) = failing.get_mut(path).filter(|n| **n > 0) {
+            *remaining -= 1;
+            return Err(std::io::Error::other("injected read failure"));
+        }
+    }
+    std::fs::read(path)
+}
+
+/// Loads, compiles, and stores an ONNX model from its bytes, like
+/// `load_model_with_options_impl` does with the bytes of a file.
+///
+/// `source` describes where the bytes came from (e.g., `fd:3`) and is reported as the
+/// model's path. Since there is no model file, no detached signature or
+/// `<model>.features.json` file is looked for.
+#[cfg(feature = "tract")]
+pub(crate) fn load_model_from_bytes_impl(
+    name: &str,
+    source: &str,
+    bytes: &[u8],
+    options: LoadOptions,
+) -> Result<(), InferaError> {
+    load_model_bytes(name, source, None, bytes, options)
+}
+
+/// Compiles and stores the ONNX model in `bytes`, read from the model file at `file` if
+/// there is one.
+#[cfg(feature = "tract")]
+fn load_model_bytes(
+    name: &str,
+    path: &str,
+    file: Option<&Path>,
+    bytes: &[u8],
+    options: LoadOptions,
+) -> Result<(), InferaError> {
+    let started = Instant::now();
+    let content_sha256 = http::bytes_sha256(bytes);
+    if let Some(expected) = &options.expected_sha256 {
+        http::verify_sha256(path, &content_sha256, expected)?;
+    }
+    let signature = match file {
+        Some(file) => signature::verify_model(file, bytes)?,
+        None => signature::verify_unsigned_model(path)?,
+    };
+    let onnx = tract_onnx::onnx();
+    let mut proto = onnx.proto_model_for_read(&mut &bytes[..]).map_err(context(
+        InferaError::OnnxError,
+        "model_parse",
+        path,
+    ))?;
+    let model_metadata: HashMap<String, String> = proto
+        .metadata_props
         .iter()
-        .map(|d| d.to_i64().unwrap_or(-1))
+        .map(|p| (p.key.clone(), p.value.clone()))
         .collect();
-    let output_shape: Vec<i64> = output_facts
-        .shape
-        .iter()
-        .map(|d| d.to_i64().unwrap_or(-1))
+    let feature_names = match &options.feature_names {
+        Some(names) => names.clone(),
+        None => default_feature_names(path, file, &model_metadata)?,
+    };
+    let defaulted_inputs = bind_initializers(&mut proto, &options.initializers)?;
+    let mut inference_model = onnx.model_for_proto_model(&proto).map_err(context(
+        InferaError::OnnxError,
+        "model_compile",
+        name,
+    ))?;
+    let input_names = graph_input_names(&inference_model)?;
+    let output_names = unique_names(
+        add_extra_outputs(&mut inference_model, &options.extra_outputs)?,
+        "output",
+    );
+    let model = inference_model
+        .into_optimized()
+        .map_err(context(InferaError::OnnxError, "model_compile", name))?
+        .into_runnable()
+        .map_err(context(InferaError::OnnxError, "model_compile", name))?;
+    if model.model().outputs.is_empty() {
+        return Err(InferaError::NoModelOutputs(name.to_string()));
+    }
+    // Generator graphs take no inputs: they are recorded with an empty input shape and
+    // run with `rows = cols = 0`.
+    let (input_shape, input_dtype, input_symbols) = if model.model().inputs.is_empty() {
+        (Some(Vec::new()), DatumType::F32, Vec::new())
+    } else {
+        let (shape, dtype) = fact_shape_and_dtype(model.model().input_fact(0), name, "input");
+        (
+            shape,
+            dtype,
+            input_symbol_names(model.model().input_fact(0)),
+        )
+    };
+    let input_dtypes = (0..model.model().inputs.len())
+        .map(|i| {
+            model
+                .model()
+                .input_fact(i)
+                .map_or(DatumType::F32, |fact| fact.datum_type)
+        })
         .collect();
+    let (output_shape, output_dtype) =
+        fact_shape_and_dtype(model.model().output_fact(0), name, "output");
+    if !matches!(output_dtype, DatumType::F32 | DatumType::F16) {
+        log!(
+            LogLevel::Warn,
+            "Model '{}' has a {:?} output, which {} f32",
+            name,
+            output_dtype,
+            if CONFIG.strict_output_types {
+                "is rejected by INFERA_STRICT_OUTPUT_TYPES instead of being cast to"
+            } else {
+                "will be cast to"
+            }
+        );
+    }
     let onnx_model = OnnxModel {
-        model,
+        model: Arc::new(model),
         input_shape,
+        input_symbols,
         output_shape,
+        input_dtype,
+        input_dtypes,
+        output_dtype,
+        input_names,
+        defaulted_inputs,
+        output_names,
+        model_metadata,
+        signature,
         name: name.to_string(),
+        options,
+        source: None,
+        source_kind: match file {
+            Some(_) => SourceKind::Local,
+            None => SourceKind::Memory,
+        },
+        path: path.to_string(),
+        bundle: None,
+        loaded_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        last_inference_ns: Arc::new(AtomicU64::new(0)),
+        postprocessor: None,
+        content_sha256,
+        feature_names,
+        seeded_plans: Default::default(),
+        tract_versions: TRACT_VERSIONS,
+        // Plans are always optimized; there is no option to skip it yet
+        optimized: true,
+        load_duration_ns: u64::try_from(started.elapsed().as_nanos()).unwrap_or(u64::MAX),
+    };
+    let versioning = onnx_model
+        .options
+        .versioning
+        .unwrap_or(CONFIG.model_versioning);
+    let history_depth = if versioning {
+        CONFIG.model_history_depth
+    } else {
+        0
     };
-    MODELS.write().insert(name.to_string(), onnx_model);
+    insert_model(name, onnx_model, history_depth);
     Ok(())
 }
 
-/// A stub for `load_model_impl` when the "tract" feature is disabled.
+/// Returns the feature names of a model loaded without the `feature_names` option.
+///
+/// They are read from a `<model>.features.json` file next to the model file (if there is
+/// one), holding a JSON array of names, or else from the `feature_names` metadata,
+/// holding a JSON array or a comma-separated list. A model with neither has no feature
+/// names. An unreadable or invalid features file fails the load, while invalid metadata
+/// is only logged.
+#[cfg(feature = "tract")]
+fn default_feature_names(
+    path: &str,
+    file: Option<&Path>,
+    metadata: &HashMap<String, String>,
+) -> Result<Vec<String>, InferaError> {
+    if let Some(sidecar) = file
+        .map(|file| file.with_extension("features.json"))
+        .filter(|sidecar| sidecar.is_file())
+    {
+        let contents = std::fs::read_to_string(&sidecar).map_err(context(
+            InferaError::IoError,
+            "features_read",
+            &sidecar.to_string_lossy(),
+        ))?;
+        let value: serde_json::Value = serde_json::from_str(&contents).map_err(context(
+            InferaError::JsonError,
+            "features_read",
+            &sidecar.to_string_lossy(),
+        ))?;
+        return parse_feature_names(&value);
+    }
+    let Some(listed) = metadata.get("feature_names") else {
+        return Ok(Vec::new());
+    };
+    let value = serde_json::from_str(listed)
+        .unwrap_or_else(|_| json!(listed.split(',').map(str::trim).collect::<Vec<_>>()));
+    Ok(parse_feature_names(&value).unwrap_or_else(|e| {
+        log!(
+            LogLevel::Warn,
+            "Ignoring the feature_names metadata of '{}': {}",
+            path,
+            e
+        );
+        Vec::new()
+    }))
+}
+
+/// Reads the shape and element type of a model's first input or output.
+///
+/// This is best-effort: if tract cannot report the fact, the shape is recorded as unknown
+/// (`None`) and the type defaults to f32, so the model still loads and shape checks are
+/// skipped at inference time. Dynamic dimensions are represented by -1.
+#[cfg(feature = "tract")]
+fn fact_shape_and_dtype(
+    fact: TractResult<&TypedFact>,
+    name: &str,
+    kind: &str,
+) -> (Option<Vec<i64>>, DatumType) {
+    match fact {
+        Ok(fact) => {
+            let shape = fact
+                .shape
+                .iter()
+                .map(|d| d.to_i64().unwrap_or(-1))
+                .collect();
+            (Some(shape), fact.datum_type)
+        }
+        Err(e) => {
+            log!(
+                LogLevel::Warn,
+                "Could not determine the {} shape of model '{}', recording it as unknown: {}",
+                kind,
+                name,
+                e
+            );
+            (None, DatumType::F32)
+        }
+    }
+}
+
+/// Returns the symbol name of each dimension of a model's input, with `None` for fixed
+/// dimensions and dimensions given by an expression.
+#[cfg(feature = "tract")]
+fn input_symbol_names(fact: TractResult<&TypedFact>) -> Vec<Option<String>> {
+    fact.map(|fact| {
+        fact.shape
+            .iter()
+            .map(|d| match d {
+                TDim::Sym(symbol) => Some(symbol.to_string()),
+                _ => None,
+            })
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// Maximum number of node names listed in an `UnknownNode` error.
+#[cfg(feature = "tract")]
+const MAX_LISTED_NODE_NAMES: usize = 20;
+
+/// Marks the given internal nodes as additional model outputs.
+///
+/// This must run before optimization, while the graph still has the original ONNX
+/// node names. Returns the names of all model outputs in order: the original outputs
+/// (labelled by their ONNX tensor names) followed by the extra outputs.
+#[cfg(feature = "tract")]
+fn add_extra_outputs(
+    model: &mut InferenceModel,
+    extra_outputs: &[String],
+) -> Result<Vec<String>, InferaError> {
+    let mut outlets = model
+        .output_outlets()
+        .map_err(context(InferaError::OnnxError, "extra_outputs", ""))?
+        .to_vec();
+    let mut names: Vec<String> = outlets
+        .iter()
+        .map(|o| {
+            model
+                .outlet_label(*o)
+                .map(String::from)
+                .unwrap_or_else(|| model.node(o.node).name.clone())
+        })
+        .collect();
+    if extra_outputs.is_empty() {
+        return Ok(names);
+    }
+    for node_name in extra_outputs {
+        let node_id = model
+            .node_id_by_name(node_name)
+            .map_err(|_| InferaError::UnknownNode {
+                name: node_name.clone(),
+                available: available_node_names(model),
+            })?;
+        outlets.push(OutletId::new(node_id, 0));
+        names.push(node_name.clone());
+    }
+    model.set_output_outlets(&outlets).map_err(context(
+        InferaError::OnnxError,
+        "extra_outputs",
+        "",
+    ))?;
+    Ok(names)
+}
+
+/// Adds the values of the `"initializers"` load option to the graph as initializers of
+/// their inputs, and returns the names of all graph inputs that have an initializer.
+///
+/// Graph inputs with an initializer are turned into constants when the graph is built,
+/// so models that take their weights as inputs can be run with a single input. Only
+/// inputs without an initializer in the model file can be bound.
+#[cfg(feature = "tract")]
+fn bind_initializers(
+    proto: &mut tract_onnx::pb::ModelProto,
+    initializers: &[(String, InitializerValue)],
+) -> Result<Vec<String>, InferaError> {
+    let Some(graph) = proto.graph.as_mut() else {
+        return Ok(Vec::new());
+    };
+    let initialized: HashSet<String> = graph.initializer.iter().map(|t| t.name.clone()).collect();
+    let (mut defaulted, required): (Vec<&ValueInfoProto>, Vec<&ValueInfoProto>) = graph
+        .input
+        .iter()
+        .partition(|input| initialized.contains(&input.name));
+    let mut bound = Vec::with_capacity(initializers.len());
+    for (name, value) in initializers {
+        let input = required
+            .iter()
+            .copied()
+            .find(|input| input.name == *name)
+            .ok_or_else(|| InferaError::UnknownInput {
+                name: name.clone(),
+                available: required
+                    .iter()
+                    .map(|input| input.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            })?;
+        bound.push(initializer_tensor(input, value)?);
+        defaulted.push(input);
+    }
+    let defaulted: Vec<String> = defaulted.iter().map(|input| input.name.clone()).collect();
+    graph.initializer.extend(bound);
+    Ok(defaulted)
+}
+
+/// Builds the initializer for a graph input from a value of the `"initializers"` load
+/// option, checking it against the input's declared type and shape.
+#[cfg(feature = "tract")]
+fn initializer_tensor(
+    input: &ValueInfoProto,
+    value: &InitializerValue,
+) -> Result<TensorProto, InferaError> {
+    let invalid = |reason: String| InferaError::InvalidInitializer {
+        input: input.name.clone(),
+        reason,
+    };
+    let Some(type_proto::Value::TensorType(tensor_type)) =
+        input.r#type.as_ref().and_then(|t| t.value.as_ref())
+    else {
+        return Err(invalid("the input is not a tensor".to_string()));
+    };
+    // Dimensions given by a symbol or not given at all are recorded as `None`
+    let declared: Option<Vec<Option<usize>>> = tensor_type.shape.as_ref().map(|shape| {
+        shape
+            .dim
+            .iter()
+            .map(|d| match d.value {
+                Some(dimension::Value::DimValue(size)) => usize::try_from(size).ok(),
+                _ => None,
+            })
+            .collect()
+    });
+    let shape = match (&value.shape, &declared) {
+        (Some(shape), Some(declared)) => {
+            let fits = shape.len() == declared.len()
+                && shape
+                    .iter()
+                    .zip(declared)
+                    .all(|(&size, dim)| dim.is_none_or(|dim| dim == size));
+            if !fits {
+                return Err(invalid(format!(
+                    "shape {:?} does not match the declared shape {:?}",
+                    shape,
+                    declared
+                        .iter()
+                        .map(|dim| dim.map_or(-1, |dim| dim as i64))
+                        .collect::<Vec<_>>()
+                )));
+            }
+            shape.clone()
+        }
+        (Some(shape), None) => shape.clone(),
+        (None, Some(declared)) => declared
+            .iter()
+            .copied()
+            .collect::<Option<Vec<usize>>>()
+            .ok_or_else(|| {
+                invalid(
+                    "the input has dynamic dimensions, so a \"shape\" must be given".to_string(),
+                )
+            })?,
+        (None, None) => {
+            return Err(invalid(
+                "the input has no declared shape, so a \"shape\" must be given".to_string(),
+            ))
+        }
+    };
+    let expected: usize = shape.iter().product();
+    if value.values.len() != expected {
+        return Err(invalid(format!(
+            "shape {:?} holds {} values, but {} were given",
+            shape,
+            expected,
+            value.values.len()
+        )));
+    }
+    let integers = || -> Result<Vec<i64>, InferaError> {
+        value
+            .values
+            .iter()
+            .map(|&v| {
+                // f64 holds every integer up to 2^53 exactly
+                (v.fract() == 0.0 && v.abs() <= 9_007_199_254_740_992.0)
+                    .then_some(v as i64)
+                    .ok_or_else(|| invalid(format!("{} is not an integer", v)))
+            })
+            .collect()
+    };
+    let mut tensor = TensorProto {
+        name: input.name.clone(),
+        dims: shape.iter().map(|&d| d as i64).collect(),
+        data_type: tensor_type.elem_type,
+        ..TensorProto::default()
+    };
+    match tensor_proto::DataType::try_from(tensor_type.elem_type) {
+        Ok(tensor_proto::DataType::Float) => {
+            tensor.float_data = value.values.iter().map(|&v| v as f32).collect();
+        }
+        Ok(tensor_proto::DataType::Double) => tensor.double_data = value.values.clone(),
+        Ok(tensor_proto::DataType::Int64) => tensor.int64_data = integers()?,
+        Ok(tensor_proto::DataType::Int32) => {
+            tensor.int32_data = integers()?
+                .into_iter()
+                .map(|v| i32::try_from(v).map_err(|_| invalid(format!("{} does not fit in i32", v))))
+                .collect::<Result<_, _>>()?;
+        }
+        _ => {
+            return Err(invalid(format!(
+                "inputs of ONNX type {} cannot be bound; only float, double, int32, and int64 inputs can",
+                tensor_type.elem_type
+            )))
+        }
+    }
+    Ok(tensor)
+}
+
+/// Returns the names of a model's inputs as declared in the ONNX graph.
+///
+/// This must run before optimization, which may rename or fuse the source nodes.
+#[cfg(feature = "tract")]
+fn graph_input_names(model: &InferenceModel) -> Result<Vec<String>, InferaError> {
+    let names = model
+        .input_outlets()
+        .map_err(context(InferaError::OnnxError, "model_inputs", ""))?
+        .iter()
+        .map(|o| model.node(o.node).name.clone())
+        .collect();
+    Ok(unique_names(names, "input"))
+}
+
+/// Makes graph input or output names usable as keys: an empty name becomes
+/// `<prefix>_<index>`, and a name that was already taken gets a `_<index>` suffix.
+#[cfg(feature = "tract")]
+fn unique_names(names: Vec<String>, prefix: &str) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    names
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let base = if name.is_empty() {
+                format!("{}_{}", prefix, i)
+            } else {
+                name
+            };
+            let mut name = base.clone();
+            let mut suffix = i;
+            while !seen.insert(name.clone()) {
+                name = format!("{}_{}", base, suffix);
+                suffix += 1;
+            }
+            name
+        })
+        .collect()
+}
+
+/// Formats the node names of a model for error messages, truncated to a fixed count.
+#[cfg(feature = "tract")]
+fn available_node_names(model: &InferenceModel) -> String {
+    let nodes = model.nodes();
+    let mut listed = nodes
+        .iter()
+        .take(MAX_LISTED_NODE_NAMES)
+        .map(|n| n.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    if nodes.len() > MAX_LISTED_NODE_NAMES {
+        listed.push_str(&format!(
+            ", ... ({} more)",
+            nodes.len() - MAX_LISTED_NODE_NAMES
+        ));
+    }
+    listed
+}
+
+/// A stub for `load_model_with_options_impl` when the "tract" feature is disabled.
 ///
 /// Always returns an `InferaError::FeatureNotEnabled` error.
 #[cfg(not(feature = "tract"))]
-pub(crate) fn load_model_impl(_name: &str, _path: &str) -> Result<(), InferaError> {
+pub(crate) fn load_model_with_options_impl(
+    _name: &str,
+    _path: &str,
+    _options: LoadOptions,
+) -> Result<(), InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+#[cfg(not(feature = "tract"))]
+pub(crate) fn load_model_from_bytes_impl(
+    _name: &str,
+    _source: &str,
+    _bytes: &[u8],
+    _options: LoadOptions,
+) -> Result<(), InferaError> {
     Err(InferaError::FeatureNotEnabled(
         "ONNX inference requires 'tract' feature to be enabled".to_string(),
     ))
@@ -115,151 +861,2092 @@ pub(crate) fn run_inference_impl(
     rows: usize,
     cols: usize,
 ) -> Result<InferaInferenceResult, InferaError> {
-    let models = MODELS.read();
-    let model = models
-        .get(model_name)
-        .ok_or_else(|| InferaError::ModelNotFound(model_name.to_string()))?;
-
-    // If model inner dimensions (after the first/batch dim) are all known (>0),
-    // validate that the provided `cols` matches their product. This yields clearer errors
-    // than deferring to the backend.
-    if !model.input_shape.is_empty() {
-        let inner_dims = &model.input_shape[1..];
-        if inner_dims.iter().all(|&d| d > 0) {
-            let expected_inner: usize = inner_dims.iter().map(|&d| d as usize).product();
-            if cols != expected_inner {
-                return Err(InferaError::InvalidInputShape {
-                    expected: format!("batch x {:?}", inner_dims),
-                    actual: format!("{} x {}", rows, cols),
-                });
-            }
+    run_inference_with(model_name, data, rows, cols, None)
+}
+
+/// Runs inference like `run_inference_impl`, also returning the total duration of the
+/// model runs and the number of input and output values.
+#[cfg(feature = "tract")]
+pub(crate) fn run_inference_profiled_impl(
+    model_name: &str,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+) -> Result<InferaProfiledResult, InferaError> {
+    let profile_ns = AtomicU64::new(0);
+    let result = run_inference_with(model_name, data, rows, cols, Some(&profile_ns))?;
+    let output_elements = result.len;
+    Ok(InferaProfiledResult {
+        result,
+        inference_ns: profile_ns.load(Ordering::Relaxed),
+        input_elements: rows * cols,
+        output_elements,
+    })
+}
+
+/// Runs inference like `run_inference_impl`, adding the duration of each model run to
+/// `profile_ns` if it is given.
+#[cfg(feature = "tract")]
+fn run_inference_with(
+    model_name: &str,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+    profile_ns: Option<&AtomicU64>,
+) -> Result<InferaInferenceResult, InferaError> {
+    let no_inputs = rows == 0 && cols == 0;
+    if !no_inputs {
+        check_nonempty(rows, cols)?;
+        check_input_size(rows, cols, mem::size_of::<f32>())?;
+    }
+
+    let model = get_model(model_name)?;
+    match (model.input_names.is_empty(), no_inputs) {
+        (true, true) => return run_without_inputs(&model, profile_ns),
+        (true, false) => {
+            return Err(InferaError::InvalidInputShape {
+                expected: "0 x 0 (the model has no inputs)".to_string(),
+                actual: format!("{} x {}", rows, cols),
+            })
         }
+        // A model with inputs needs a non-empty input
+        (false, true) => check_nonempty(rows, cols)?,
+        (false, false) => {}
     }
 
-    let input_data = unsafe { std::slice::from_raw_parts(data, rows * cols) };
-    let input_tensor = Tensor::from_shape(&[rows, cols], input_data)
-        .map_err(|e| InferaError::OnnxError(e.to_string()))?;
-    let outputs = model
-        .model
-        .run(tvec!(input_tensor.into()))
-        .map_err(|e| InferaError::OnnxError(e.to_string()))?;
-    let output_tensor = outputs
-        .into_iter()
-        .next()
-        .ok_or_else(|| InferaError::OnnxError("No output tensor".to_string()))?;
-    let output_array = output_tensor
-        .to_array_view::<f32>()
-        .map_err(|e| InferaError::OnnxError(e.to_string()))?;
-    let (output_rows, output_cols) = shape_rows_cols(output_array.shape());
-    let output_data: Vec<f32> = output_array.iter().cloned().collect();
-    let output_len = output_data.len();
-    let output_ptr = Box::into_raw(output_data.into_boxed_slice()) as *mut f32;
-    Ok(InferaInferenceResult {
-        data: output_ptr,
-        len: output_len,
-        rows: output_rows,
-        cols: output_cols,
-        status: 0,
-    })
+    let input_data = unsafe { checked_input(&model, data, rows, cols)? };
+    let (output_data, output_rows, output_cols) =
+        infer_rows_in(&model, input_data, rows, cols, &*RESULT_POOL, profile_ns)?;
+    Ok(boxed_output(&model, output_data, output_rows, output_cols))
 }
 
-/// A stub for `run_inference_impl` when the "tract" feature is disabled.
-///
-/// Always returns an `InferaError::FeatureNotEnabled` error.
-#[cfg(not(feature = "tract"))]
-pub(crate) fn run_inference_impl(
-    _model_name: &str,
-    _data: *const f32,
-    _rows: usize,
-    _cols: usize,
+/// Runs a model that takes no inputs, such as a graph that generates a constant tensor.
+#[cfg(feature = "tract")]
+fn run_without_inputs(
+    model: &OnnxModel,
+    profile_ns: Option<&AtomicU64>,
 ) -> Result<InferaInferenceResult, InferaError> {
-    Err(InferaError::FeatureNotEnabled(
-        "ONNX inference requires 'tract' feature to be enabled".to_string(),
-    ))
+    let outputs = run_plan_inputs(model, tvec!(), profile_ns)?;
+    let (output_data, output_shape) =
+        first_output_with(outputs, |values| RESULT_POOL.copy_of(values))?;
+    let (output_rows, output_cols) = shape_rows_cols(&output_shape);
+    Ok(boxed_output(model, output_data, output_rows, output_cols))
 }
 
-/// Runs inference with a given model and raw BLOB input data.
-///
-/// This function is similar to `run_inference_impl` but takes a raw byte slice (`BLOB`)
-/// as input. It converts the bytes to `f32` values, validates the shape against the
-/// model's expected input, and attempts to infer the batch size for models with
-/// dynamic input dimensions.
-///
-/// # Arguments
-///
-/// * `model_name` - The name of the loaded model to use for inference.
-/// * `blob_data` - A pointer to the raw byte data.
-/// * `blob_len` - The length of the byte data slice.
-///
-/// # Returns
+/// Runs inference on feature maps, JSON objects keyed by the model's feature names.
 ///
-/// * `Ok(InferaInferenceResult)` containing the output tensor data and metadata.
-/// * `Err(InferaError)` if the model is not found, the blob size is invalid,
-///   the shape does not match, or an error occurs during inference.
+/// `features_json` is a single object, for one row, or a non-empty array of objects, one
+/// per row. The values of each object are put in the model's feature name order. A
+/// missing (or null) feature takes the model's `feature_default`, or fails if it has
+/// none, and a feature the model does not have fails unless the model was loaded with
+/// `ignore_unknown`.
 #[cfg(feature = "tract")]
-pub(crate) fn run_inference_blob_impl(
+pub(crate) fn run_features_impl(
     model_name: &str,
-    blob_data: *const u8,
-    blob_len: usize,
+    features_json: &str,
 ) -> Result<InferaInferenceResult, InferaError> {
-    let models = MODELS.read();
-    let model = models
-        .get(model_name)
-        .ok_or_else(|| InferaError::ModelNotFound(model_name.to_string()))?;
-    if !blob_len.is_multiple_of(mem::size_of::<f32>()) {
-        return Err(InferaError::InvalidBlobSize);
+    let model = get_model(model_name)?;
+    if model.feature_names.is_empty() {
+        return Err(InferaError::NoFeatureNames(model_name.to_string()));
     }
-    let blob_bytes = unsafe { std::slice::from_raw_parts(blob_data, blob_len) };
-    let float_vec: Vec<f32> = blob_bytes
-        .chunks_exact(4)
-        .map(|chunk| {
-            // SAFETY: chunks_exact(4) guarantees exactly 4 bytes, so this conversion cannot fail
-            let array: [u8; 4] = chunk.try_into().unwrap_or_default();
-            f32::from_ne_bytes(array)
-        })
-        .collect();
-    let expected_elements: usize = model
-        .input_shape
-        .iter()
-        .filter(|&&d| d > 0)
-        .map(|&d| d as usize)
-        .product();
-    if expected_elements == 0 || !float_vec.len().is_multiple_of(expected_elements) {
-        return Err(InferaError::BlobShapeMismatch {
-            expected: expected_elements,
-            actual: float_vec.len(),
-        });
+    let value: serde_json::Value = serde_json::from_str(features_json).map_err(context(
+        InferaError::JsonError,
+        "features_parse",
+        model_name,
+    ))?;
+    let objects = match &value {
+        serde_json::Value::Object(obj) => vec![obj],
+        serde_json::Value::Array(items) if !items.is_empty() => items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                item.as_object().ok_or_else(|| {
+                    InferaError::InvalidFeatures(format!("row {} is not a JSON object", i))
+                })
+            })
+            .collect::<Result<Vec<_>, InferaError>>()?,
+        _ => {
+            return Err(InferaError::InvalidFeatures(
+                "features must be a JSON object or a non-empty array of objects".to_string(),
+            ))
+        }
+    };
+
+    let rows = objects.len();
+    let cols = model.feature_names.len();
+    let known: HashSet<&str> = model.feature_names.iter().map(String::as_str).collect();
+    let mut input_data = Vec::with_capacity(rows * cols);
+    for (i, object) in objects.iter().enumerate() {
+        if !model.options.ignore_unknown {
+            if let Some(unknown) = object.keys().find(|k| !known.contains(k.as_str())) {
+                return Err(InferaError::InvalidFeatures(format!(
+                    "unknown feature \"{}\" in row {}",
+                    unknown, i
+                )));
+            }
+        }
+        for name in &model.feature_names {
+            let value = match object.get(name).filter(|v| !v.is_null()) {
+                Some(v) => v.as_f64().map(|v| v as f32).ok_or_else(|| {
+                    InferaError::InvalidFeatures(format!(
+                        "feature \"{}\" in row {} is not a number",
+                        name, i
+                    ))
+                })?,
+                None => model.options.feature_default.ok_or_else(|| {
+                    InferaError::InvalidFeatures(format!(
+                        "feature \"{}\" is missing from row {}",
+                        name, i
+                    ))
+                })?,
+            };
+            input_data.push(value);
+        }
     }
-    let batch_size = float_vec.len() / expected_elements;
-    let final_shape: Vec<usize> = model
-        .input_shape
-        .iter()
-        .map(|&d| if d == -1 { batch_size } else { d as usize })
-        .collect();
-    let input_tensor = Tensor::from_shape(&final_shape, &float_vec)
-        .map_err(|e| InferaError::OnnxError(e.to_string()))?;
-    let outputs = model
-        .model
-        .run(tvec!(input_tensor.into()))
-        .map_err(|e| InferaError::OnnxError(e.to_string()))?;
-    let output_tensor = outputs
-        .into_iter()
-        .next()
-        .ok_or_else(|| InferaError::OnnxError("No output tensor".to_string()))?;
-    let output_array = output_tensor
-        .to_array_view::<f32>()
-        .map_err(|e| InferaError::OnnxError(e.to_string()))?;
-    let (output_rows, output_cols) = shape_rows_cols(output_array.shape());
-    let output_data: Vec<f32> = output_array.iter().cloned().collect();
-    let output_len = output_data.len();
-    let output_ptr = Box::into_raw(output_data.into_boxed_slice()) as *mut f32;
-    Ok(InferaInferenceResult {
-        data: output_ptr,
-        len: output_len,
-        rows: output_rows,
-        cols: output_cols,
-        status: 0,
-    })
+    check_input_size(rows, cols, mem::size_of::<f32>())?;
+    check_input_cols(&model, rows, cols)?;
+    if CONFIG.validate_input {
+        check_finite(&input_data)?;
+    }
+    infer_boxed(&model, &input_data, rows, cols)
+}
+
+/// Runs inference like `run_inference_impl` and returns the output serialized as `f32`
+/// bytes, little-endian unless `native_endian` is set.
+///
+/// The bytes are written while the output is copied out of the model, so there is no
+/// separate conversion pass, except for inputs that are padded or split into chunks,
+/// whose outputs are stitched together first, and models with a postprocessor.
+#[cfg(feature = "tract")]
+pub(crate) fn run_inference_bytes_impl(
+    model_name: &str,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+    native_endian: bool,
+) -> Result<OutputBytes, InferaError> {
+    check_nonempty(rows, cols)?;
+    check_input_size(rows, cols, mem::size_of::<f32>())?;
+
+    let model = get_model(model_name)?;
+    let input_data = unsafe { checked_input(&model, data, rows, cols)? };
+    if padded_batch(&model, rows, cols).is_some()
+        || is_chunked(&model, rows, cols)
+        || model.postprocessor.is_some()
+    {
+        let (mut output_data, _, output_cols) = infer_rows(&model, input_data, rows, cols)?;
+        if let Some(postprocessor) = &model.postprocessor {
+            postprocessor.apply(&mut output_data, output_cols);
+        }
+        return Ok(OutputBytes::encode(output_data.into_iter(), native_endian));
+    }
+
+    let input_tensor = Tensor::from_shape(&[rows, cols], input_data).map_err(context(
+        InferaError::OnnxError,
+        "input_tensor",
+        model_name,
+    ))?;
+    let outputs = run_plan(&model, to_model_input(&model, input_tensor)?)?;
+    let (output, _) = first_output_with(outputs, |values| {
+        OutputBytes::encode(values.iter().copied(), native_endian)
+    })?;
+    Ok(output)
+}
+
+/// A reusable input tensor for `run_inference_reusing_impl`, empty until the first run.
+#[cfg(feature = "tract")]
+pub(crate) type InputScratch = Option<Arc<Tensor>>;
+
+/// A reusable input tensor for `run_inference_reusing_impl`, empty until the first run.
+#[cfg(not(feature = "tract"))]
+pub(crate) type InputScratch = Option<()>;
+
+/// Runs inference like `run_inference_impl` on an already resolved model, staging the
+/// input in `scratch`.
+///
+/// When the previous input in `scratch` has the same shape and the model no longer holds
+/// a reference to it, it is refilled in place, so a stream of equally sized chunks
+/// allocates its input tensor once. Inputs that need padding, chunking, or a type
+/// conversion go through `infer_rows` instead.
+#[cfg(feature = "tract")]
+pub(crate) fn run_inference_reusing_impl(
+    model: &OnnxModel,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+    scratch: &mut InputScratch,
+) -> Result<InferaInferenceResult, InferaError> {
+    check_nonempty(rows, cols)?;
+    check_input_size(rows, cols, mem::size_of::<f32>())?;
+    let input_data = unsafe { checked_input(model, data, rows, cols)? };
+
+    if model.input_dtype != DatumType::F32
+        || padded_batch(model, rows, cols).is_some()
+        || is_chunked(model, rows, cols)
+    {
+        return infer_boxed(model, input_data, rows, cols);
+    }
+
+    let reused = scratch
+        .take()
+        .filter(|tensor| tensor.shape() == [rows, cols])
+        .and_then(|mut tensor| {
+            let values = Arc::get_mut(&mut tensor)?.as_slice_mut::<f32>().ok()?;
+            values.copy_from_slice(input_data);
+            Some(tensor)
+        });
+    let tensor = match reused {
+        Some(tensor) => tensor,
+        None => Arc::new(
+            Tensor::from_shape(&[rows, cols], input_data).map_err(context(
+                InferaError::OnnxError,
+                "input_tensor",
+                &model.name,
+            ))?,
+        ),
+    };
+    *scratch = Some(Arc::clone(&tensor));
+    let outputs = run_plan(model, TValue::Const(tensor))?;
+    let (output_data, output_shape) =
+        first_output_with(outputs, |values| RESULT_POOL.copy_of(values))?;
+    let (output_rows, output_cols) = shape_rows_cols(&output_shape);
+    Ok(boxed_output(model, output_data, output_rows, output_cols))
+}
+
+/// Runs inference like `run_inference_impl`, with the model's random ops seeded so that
+/// repeated calls with the same `seed` produce identical output.
+///
+/// The seeded ops are `RandomNormal`, `RandomUniform`, `RandomNormalLike`, and
+/// `RandomUniformLike`, which otherwise draw from a generator seeded from entropy on every
+/// run (unless the model sets their `seed` attribute). `Dropout` is already deterministic,
+/// since it is a no-op at inference time. Models with random ops are recompiled the first
+/// time a seed is used, and the plans of the last `SEEDED_PLANS_KEPT` seeds are kept.
+///
+/// # Returns
+///
+/// * `Ok(InferaInferenceResult)` containing the output tensor data and metadata.
+/// * `Err(InferaError)` if the model is not found or if an error occurs during inference.
+#[cfg(feature = "tract")]
+pub(crate) fn run_inference_seeded_impl(
+    model_name: &str,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+    seed: u64,
+) -> Result<InferaInferenceResult, InferaError> {
+    check_nonempty(rows, cols)?;
+    check_input_size(rows, cols, mem::size_of::<f32>())?;
+
+    let base = get_model(model_name)?;
+    let model = seeded_model(&base, seed)?;
+
+    let input_data = unsafe { checked_input(&model, data, rows, cols)? };
+    infer_boxed(&model, input_data, rows, cols)
+}
+
+/// The number of seeds whose recompiled plans are kept per model.
+#[cfg(feature = "tract")]
+const SEEDED_PLANS_KEPT: usize = 8;
+
+/// Returns a copy of a model whose random ops draw from generators seeded with `seed`.
+///
+/// Each random op gets the seed offset by its node id, so two ops in one model do not
+/// produce the same values. A model without random ops is returned as it is. Plans are
+/// reused from the model's `seeded_plans` when the seed was used recently.
+#[cfg(feature = "tract")]
+fn seeded_model(model: &OnnxModel, seed: u64) -> Result<OnnxModel, InferaError> {
+    use tract_onnx::tract_core::ops::random::Random;
+
+    {
+        let mut cache = model.seeded_plans.lock();
+        if cache.has_random_ops == Some(false) {
+            return Ok(model.clone());
+        }
+        if let Some(index) = cache.plans.iter().position(|(s, _)| *s == seed) {
+            let entry = cache.plans.remove(index).expect("index is in bounds");
+            let plan = Arc::clone(&entry.1);
+            cache.plans.push_front(entry);
+            return Ok(OnnxModel {
+                model: plan,
+                ..model.clone()
+            });
+        }
+    }
+
+    // Compiling can take a while, so it happens without holding the cache lock
+    let mut graph = model.model.model().clone();
+    let mut seeded = false;
+    for node in graph.nodes.iter_mut() {
+        if let Some(mut random) = node.op_as::<Random>().cloned() {
+            random.seed = Some(seed.wrapping_add(node.id as u64));
+            node.op = Box::new(random);
+            seeded = true;
+        }
+    }
+    if !seeded {
+        model.seeded_plans.lock().has_random_ops = Some(false);
+        return Ok(model.clone());
+    }
+    let plan = Arc::new(graph.into_runnable().map_err(context(
+        InferaError::OnnxError,
+        "model_compile",
+        &model.name,
+    ))?);
+    let mut cache = model.seeded_plans.lock();
+    cache.has_random_ops = Some(true);
+    cache.plans.retain(|(s, _)| *s != seed);
+    cache.plans.push_front((seed, Arc::clone(&plan)));
+    cache.plans.truncate(SEEDED_PLANS_KEPT);
+    drop(cache);
+    Ok(OnnxModel {
+        model: plan,
+        ..model.clone()
+    })
+}
+
+/// Runs inference like `run_inference_impl`, with symbolic input dimensions bound to
+/// concrete values.
+///
+/// `symbols_json` is a JSON object mapping symbol names, as declared in the ONNX graph
+/// (e.g., `{"S": 128}` for a sequence length `S`), to positive integers. The bound and
+/// fixed dimensions give the input tensor its shape, and one remaining dynamic dimension
+/// (usually the batch) is inferred from the element count. The resolved shape must
+/// flatten to `rows x cols`, with all dimensions after the first in `cols`.
+///
+/// # Returns
+///
+/// * `Ok(InferaInferenceResult)` containing the output data.
+/// * `Err(InferaError)` if the model is not found, a symbol is unknown, the shape cannot
+///   be resolved, or inference fails.
+#[cfg(feature = "tract")]
+pub(crate) fn run_inference_with_symbols_impl(
+    model_name: &str,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+    symbols_json: &str,
+) -> Result<InferaInferenceResult, InferaError> {
+    check_nonempty(rows, cols)?;
+    check_input_size(rows, cols, mem::size_of::<f32>())?;
+
+    let model = get_model(model_name)?;
+    check_input_dtype(&model, DatumType::F32)?;
+    let symbols = parse_symbols(symbols_json)?;
+    let input_shape =
+        model
+            .input_shape
+            .as_deref()
+            .ok_or_else(|| InferaError::InvalidInputShape {
+                expected: "a model with a known input shape".to_string(),
+                actual: format!("{} x {}", rows, cols),
+            })?;
+    let bound_shape = bind_symbols(input_shape, &model.input_symbols, &symbols)?;
+    let final_shape = resolve_blob_shape(&bound_shape, rows * cols)?;
+    validate_input_shape(&model, &final_shape)?;
+    if shape_rows_cols(&final_shape) != (rows, cols) {
+        return Err(InferaError::InvalidInputShape {
+            expected: format!("{:?}", final_shape),
+            actual: format!("{} x {}", rows, cols),
+        });
+    }
+
+    let input_data = unsafe { std::slice::from_raw_parts(data, rows * cols) };
+    if CONFIG.validate_input {
+        check_finite(input_data)?;
+    }
+    let input_tensor = Tensor::from_shape(&final_shape, input_data).map_err(context(
+        InferaError::OnnxError,
+        "input_tensor",
+        model_name,
+    ))?;
+    let (output_data, output_shape) = run_model(&model, input_tensor)?;
+    let (output_rows, output_cols) = shape_rows_cols(&output_shape);
+    Ok(boxed_output(&model, output_data, output_rows, output_cols))
+}
+
+/// Parses symbol values from a JSON object mapping names to positive integers.
+///
+/// An empty string binds no symbols.
+#[cfg(feature = "tract")]
+fn parse_symbols(json: &str) -> Result<HashMap<String, i64>, InferaError> {
+    if json.trim().is_empty() {
+        return Ok(HashMap::new());
+    }
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(context(InferaError::JsonError, "symbols_parse", ""))?;
+    let object = value
+        .as_object()
+        .ok_or_else(|| InferaError::JsonError("symbols must be a JSON object".to_string()))?;
+    object
+        .iter()
+        .map(|(name, v)| {
+            v.as_i64()
+                .filter(|&n| n > 0)
+                .map(|n| (name.clone(), n))
+                .ok_or_else(|| {
+                    InferaError::JsonError(format!("symbol '{}' must be a positive integer", name))
+                })
+        })
+        .collect()
+}
+
+/// Replaces the dimensions of `shape` whose symbol (from `names`) has a value in `symbols`.
+///
+/// Every name in `symbols` must be a symbolic dimension of the model input.
+#[cfg(feature = "tract")]
+fn bind_symbols(
+    shape: &[i64],
+    names: &[Option<String>],
+    symbols: &HashMap<String, i64>,
+) -> Result<Vec<i64>, InferaError> {
+    let available: Vec<&str> = names.iter().flatten().map(String::as_str).collect();
+    if let Some(unknown) = symbols.keys().find(|k| !available.contains(&k.as_str())) {
+        return Err(InferaError::UnknownSymbol {
+            name: unknown.clone(),
+            available: available.join(", "),
+        });
+    }
+    Ok(shape
+        .iter()
+        .enumerate()
+        .map(|(i, &dim)| {
+            names
+                .get(i)
+                .and_then(Option::as_ref)
+                .and_then(|name| symbols.get(name))
+                .copied()
+                .unwrap_or(dim)
+        })
+        .collect())
+}
+
+/// Allocates the buffers that `infer_rows_in` copies model outputs into.
+#[cfg(feature = "tract")]
+pub(crate) trait OutputAlloc: Sync {
+    /// The buffer type, which is boxed as it is for the caller.
+    type Buffer: AsMut<[f32]> + Into<Box<[f32]>>;
+
+    /// Returns a buffer of `len` values, all of which the caller overwrites.
+    fn with_len(&self, len: usize) -> Self::Buffer;
+
+    /// Returns a buffer holding a copy of `values`.
+    fn copy_of(&self, values: &[f32]) -> Self::Buffer;
+}
+
+/// Allocates output buffers as plain vectors.
+#[cfg(feature = "tract")]
+pub(crate) struct VecAlloc;
+
+#[cfg(feature = "tract")]
+impl OutputAlloc for VecAlloc {
+    type Buffer = Vec<f32>;
+
+    fn with_len(&self, len: usize) -> Vec<f32> {
+        vec![0.0; len]
+    }
+
+    fn copy_of(&self, values: &[f32]) -> Vec<f32> {
+        values.to_vec()
+    }
+}
+
+/// Draws output buffers from the pool, so an output of a size that was freed before is
+/// copied straight out of the model without allocating.
+#[cfg(feature = "tract")]
+impl OutputAlloc for ResultPool {
+    type Buffer = Box<[f32]>;
+
+    fn with_len(&self, len: usize) -> Box<[f32]> {
+        self.take(len)
+    }
+
+    fn copy_of(&self, values: &[f32]) -> Box<[f32]> {
+        ResultPool::copy_of(self, values)
+    }
+}
+
+/// Runs a model on a `rows x cols` input and returns the flat output with its rows and cols.
+///
+/// Inputs are padded for fixed-batch models loaded with `pad_batch`, and large batches
+/// are split into chunks for models with a dynamic batch dimension.
+#[cfg(feature = "tract")]
+pub(crate) fn infer_rows(
+    model: &OnnxModel,
+    input_data: &[f32],
+    rows: usize,
+    cols: usize,
+) -> Result<(Vec<f32>, usize, usize), InferaError> {
+    infer_rows_in(model, input_data, rows, cols, &VecAlloc, None)
+}
+
+/// Runs a model like `infer_rows`, copying the output into a buffer from `alloc` and
+/// adding the duration of each model run to `profile_ns` if it is given.
+#[cfg(feature = "tract")]
+fn infer_rows_in<A: OutputAlloc>(
+    model: &OnnxModel,
+    input_data: &[f32],
+    rows: usize,
+    cols: usize,
+    alloc: &A,
+    profile_ns: Option<&AtomicU64>,
+) -> Result<(A::Buffer, usize, usize), InferaError> {
+    if let Some(batch) = padded_batch(model, rows, cols) {
+        return run_padded_batches(model, input_data, rows, cols, batch, alloc, profile_ns);
+    }
+    if is_chunked(model, rows, cols) {
+        let chunk_rows = CONFIG.batch_chunk_rows;
+        return run_chunked_batches(model, input_data, rows, cols, chunk_rows, alloc, profile_ns);
+    }
+
+    let input_tensor = Tensor::from_shape(&[rows, cols], input_data).map_err(context(
+        InferaError::OnnxError,
+        "input_tensor",
+        &model.name,
+    ))?;
+    let input = to_model_input(model, input_tensor)?;
+    let outputs = run_plan_inputs(model, tvec!(input), profile_ns)?;
+    let (output_data, output_shape) = first_output_with(outputs, |values| alloc.copy_of(values))?;
+    let (output_rows, output_cols) = shape_rows_cols(&output_shape);
+    Ok((output_data, output_rows, output_cols))
+}
+
+/// Runs a model like `infer_rows` and boxes its postprocessed output for the caller, in a
+/// buffer drawn from the result pool.
+#[cfg(feature = "tract")]
+fn infer_boxed(
+    model: &OnnxModel,
+    input_data: &[f32],
+    rows: usize,
+    cols: usize,
+) -> Result<InferaInferenceResult, InferaError> {
+    let (output_data, output_rows, output_cols) =
+        infer_rows_in(model, input_data, rows, cols, &*RESULT_POOL, None)?;
+    Ok(boxed_output(model, output_data, output_rows, output_cols))
+}
+
+/// Returns the fixed batch size `infer_rows` pads a `rows x cols` input to, if it pads it.
+#[cfg(feature = "tract")]
+fn padded_batch(model: &OnnxModel, rows: usize, cols: usize) -> Option<usize> {
+    if !model.options.pad_batch || rows == 0 || cols == 0 {
+        return None;
+    }
+    let batch = *model.input_shape.as_deref()?.first()?;
+    (batch > 0 && rows != batch as usize).then_some(batch as usize)
+}
+
+/// Returns whether `infer_rows` splits a `rows x cols` input into parallel chunks.
+#[cfg(feature = "tract")]
+fn is_chunked(model: &OnnxModel, rows: usize, cols: usize) -> bool {
+    let chunk_rows = CONFIG.batch_chunk_rows;
+    chunk_rows > 0 && rows > chunk_rows && cols > 0 && supports_chunking(model)
+}
+
+/// Validates `rows x cols` against the model's input type and shape and returns the input
+/// as a slice.
+///
+/// Non-finite values are rejected when `INFERA_VALIDATE_INPUT` is set.
+///
+/// # Safety
+///
+/// `data` must point to at least `rows * cols` contiguous `f32` values.
+#[cfg(feature = "tract")]
+pub(crate) unsafe fn checked_input<'a>(
+    model: &OnnxModel,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+) -> Result<&'a [f32], InferaError> {
+    check_input_dtype(model, DatumType::F32)?;
+    check_input_cols(model, rows, cols)?;
+    let input_data = std::slice::from_raw_parts(data, rows * cols);
+    if CONFIG.validate_input {
+        check_finite(input_data)?;
+    }
+    Ok(input_data)
+}
+
+/// Checks that `cols` matches the model's inner input dimensions.
+///
+/// The batch dimension is padded or split to fit on the `rows x cols` paths, so only the
+/// dimensions after it are checked. Rank 2 inputs are checked with `validate_input_shape`,
+/// and the columns of higher rank inputs must match the product of their inner
+/// dimensions if all of them are fixed. This yields clearer errors than deferring to
+/// the backend.
+#[cfg(feature = "tract")]
+fn check_input_cols(model: &OnnxModel, rows: usize, cols: usize) -> Result<(), InferaError> {
+    let Some(shape) = model.input_shape.as_deref().filter(|s| !s.is_empty()) else {
+        return Ok(());
+    };
+    let inner_dims = &shape[1..];
+    let matches = match inner_dims {
+        [_] => {
+            let batch = usize::try_from(shape[0]).unwrap_or(rows);
+            validate_input_shape(model, &[batch, cols]).is_ok()
+        }
+        _ if inner_dims.iter().all(|&d| d > 0) => {
+            cols == inner_dims.iter().map(|&d| d as usize).product::<usize>()
+        }
+        _ => true,
+    };
+    if !matches {
+        return Err(InferaError::InvalidInputShape {
+            expected: format!("batch x {:?}", inner_dims),
+            actual: format!("{} x {}", rows, cols),
+        });
+    }
+    Ok(())
+}
+
+/// Runs inference on a `rows x cols` input of unsigned bytes.
+///
+/// This is the entry point for quantized models that take `u8` (or `i8`) inputs. For
+/// `i8` models each byte is reinterpreted as a two's complement value, and for other
+/// models the bytes are converted to the model's input type. Batch padding and
+/// chunking are not applied on this path.
+///
+/// # Arguments
+///
+/// * `model_name` - The name of the loaded model to use for inference.
+/// * `data` - A pointer to the raw `u8` tensor data.
+/// * `rows` - The number of rows in the input tensor.
+/// * `cols` - The number of columns in the input tensor.
+#[cfg(feature = "tract")]
+pub(crate) fn run_inference_u8_impl(
+    model_name: &str,
+    data: *const u8,
+    rows: usize,
+    cols: usize,
+) -> Result<InferaInferenceResult, InferaError> {
+    check_nonempty(rows, cols)?;
+    check_input_size(rows, cols, mem::size_of::<u8>())?;
+
+    let model = get_model(model_name)?;
+
+    check_input_dtype(&model, DatumType::U8)?;
+    check_input_cols(&model, rows, cols)?;
+    let input_data = unsafe { std::slice::from_raw_parts(data, rows * cols) };
+    let input_tensor = byte_tensor(model.input_dtype, &[rows, cols], input_data)?;
+    let (output_data, output_shape) = run_model(&model, input_tensor)?;
+    let (output_rows, output_cols) = shape_rows_cols(&output_shape);
+    Ok(boxed_output(&model, output_data, output_rows, output_cols))
+}
+
+/// Runs inference on a `rows x cols` input of half-precision (f16) values.
+///
+/// `data` holds raw IEEE 754 binary16 values. They are widened to f32 and run through
+/// the same path as `run_inference_impl` (so batch padding and chunking apply), and
+/// narrowed back to f16 for models with f16 inputs, which is lossless. Outputs are
+/// returned as f32.
+///
+/// # Arguments
+///
+/// * `model_name` - The name of the loaded model to use for inference.
+/// * `data` - A pointer to the raw f16 tensor data, one `u16` per value.
+/// * `rows` - The number of rows in the input tensor.
+/// * `cols` - The number of columns in the input tensor.
+#[cfg(feature = "tract")]
+pub(crate) fn run_inference_f16_impl(
+    model_name: &str,
+    data: *const u16,
+    rows: usize,
+    cols: usize,
+) -> Result<InferaInferenceResult, InferaError> {
+    check_nonempty(rows, cols)?;
+    check_input_size(rows, cols, mem::size_of::<u16>())?;
+
+    let model = get_model(model_name)?;
+
+    check_input_dtype(&model, DatumType::F32)?;
+    check_input_cols(&model, rows, cols)?;
+    let bits = unsafe { std::slice::from_raw_parts(data, rows * cols) };
+    let input_data: Vec<f32> = bits
+        .iter()
+        .map(|&b| half::f16::from_bits(b).to_f32())
+        .collect();
+    if CONFIG.validate_input {
+        check_finite(&input_data)?;
+    }
+    infer_boxed(&model, &input_data, rows, cols)
+}
+
+/// Returns true if the model input is a one-byte integer type (`u8` or `i8`).
+#[cfg(feature = "tract")]
+fn is_byte_input(dt: DatumType) -> bool {
+    matches!(dt, DatumType::U8 | DatumType::I8)
+}
+
+/// Builds a tensor from raw bytes, as `i8` for `i8` models and as `u8` otherwise.
+#[cfg(feature = "tract")]
+fn byte_tensor(dt: DatumType, shape: &[usize], bytes: &[u8]) -> Result<Tensor, InferaError> {
+    let tensor = if dt == DatumType::I8 {
+        let values: Vec<i8> = bytes.iter().map(|&b| b as i8).collect();
+        Tensor::from_shape(shape, &values)
+    } else {
+        Tensor::from_shape(shape, bytes)
+    };
+    tensor.map_err(context(InferaError::OnnxError, "input_tensor", ""))
+}
+
+/// Runs inference and returns a single model output, chosen by `output_selector`.
+///
+/// The selector is an output name (as listed by `get_output_names_impl`) or, if no
+/// output has that name, a zero-based output index. The whole graph still runs, but only
+/// the selected output is converted to f32 and copied out. The model's postprocessor is
+/// only applied to its first output, since that is the output it was set up for.
+///
+/// # Arguments
+///
+/// * `model_name` - The name of the loaded model to use for inference.
+/// * `data` - A pointer to the raw f32 tensor data.
+/// * `rows` - The number of rows in the input tensor.
+/// * `cols` - The number of columns in the input tensor.
+/// * `output_selector` - The name or index of the output to return.
+#[cfg(feature = "tract")]
+pub(crate) fn run_inference_output_impl(
+    model_name: &str,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+    output_selector: &str,
+) -> Result<InferaInferenceResult, InferaError> {
+    run_selected_output(model_name, data, rows, cols, |model| {
+        output_index(model, output_selector)
+    })
+}
+
+/// Runs inference and returns the model output at a zero-based `index`, like
+/// `run_inference_output_impl` does for an output selected by name or index.
+///
+/// Unlike a selector, an index never matches an output whose name is a number.
+///
+/// # Arguments
+///
+/// * `model_name` - The name of the loaded model to use for inference.
+/// * `data` - A pointer to the raw f32 tensor data.
+/// * `rows` - The number of rows in the input tensor.
+/// * `cols` - The number of columns in the input tensor.
+/// * `index` - The index of the output to return, in the order of `get_output_names_impl`.
+#[cfg(feature = "tract")]
+pub(crate) fn run_inference_output_index_impl(
+    model_name: &str,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+    index: usize,
+) -> Result<InferaInferenceResult, InferaError> {
+    run_selected_output(model_name, data, rows, cols, |model| {
+        if index < model.output_names.len() {
+            Ok(index)
+        } else {
+            Err(unknown_output(model, &index.to_string()))
+        }
+    })
+}
+
+/// Runs inference and returns the output whose index `select` picks for the model.
+#[cfg(feature = "tract")]
+fn run_selected_output(
+    model_name: &str,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+    select: impl FnOnce(&OnnxModel) -> Result<usize, InferaError>,
+) -> Result<InferaInferenceResult, InferaError> {
+    check_nonempty(rows, cols)?;
+    check_input_size(rows, cols, mem::size_of::<f32>())?;
+
+    let model = get_model(model_name)?;
+    let index = select(&model)?;
+    let input_data = unsafe { checked_input(&model, data, rows, cols)? };
+    let input_tensor = Tensor::from_shape(&[rows, cols], input_data).map_err(context(
+        InferaError::OnnxError,
+        "input_tensor",
+        model_name,
+    ))?;
+    let outputs = run_plan(&model, to_model_input(&model, input_tensor)?)?;
+    let (output_data, output_shape) = nth_output_with(outputs, index, |values| values.to_vec())?;
+    let (output_rows, output_cols) = shape_rows_cols(&output_shape);
+    if index == 0 {
+        Ok(boxed_output(&model, output_data, output_rows, output_cols))
+    } else {
+        Ok(InferaInferenceResult::from_vec(
+            output_data,
+            output_rows,
+            output_cols,
+        ))
+    }
+}
+
+/// Resolves an output selector, an output name or a zero-based index, to an output index.
+#[cfg(feature = "tract")]
+fn output_index(model: &OnnxModel, selector: &str) -> Result<usize, InferaError> {
+    model
+        .output_names
+        .iter()
+        .position(|name| name == selector)
+        .or_else(|| {
+            selector
+                .parse::<usize>()
+                .ok()
+                .filter(|&i| i < model.output_names.len())
+        })
+        .ok_or_else(|| unknown_output(model, selector))
+}
+
+/// Returns the error for an output selector that matches none of the model's outputs,
+/// listing each output with its index.
+#[cfg(feature = "tract")]
+fn unknown_output(model: &OnnxModel, selector: &str) -> InferaError {
+    InferaError::UnknownOutput {
+        name: selector.to_string(),
+        available: model
+            .output_names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| format!("{} ({})", name, i))
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+/// Runs inference and returns every model output, labelled by name, as a JSON string.
+///
+/// The JSON is an array with one object per output, in output order, each holding the
+/// output's `name`, `shape`, and flattened `data`. Outputs added with the
+/// `extra_outputs` load option are included after the model's own outputs.
+///
+/// # Arguments
+///
+/// * `model_name` - The name of the loaded model to use for inference.
+/// * `data` - A pointer to the raw f32 tensor data.
+/// * `rows` - The number of rows in the input tensor.
+/// * `cols` - The number of columns in the input tensor.
+#[cfg(feature = "tract")]
+pub(crate) fn run_inference_all_impl(
+    model_name: &str,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+) -> Result<String, InferaError> {
+    check_nonempty(rows, cols)?;
+    check_input_size(rows, cols, mem::size_of::<f32>())?;
+
+    let model = get_model(model_name)?;
+    let input_data = unsafe { checked_input(&model, data, rows, cols)? };
+    let input_tensor = Tensor::from_shape(&[rows, cols], input_data).map_err(context(
+        InferaError::OnnxError,
+        "input_tensor",
+        model_name,
+    ))?;
+    let outputs = run_plan(&model, to_model_input(&model, input_tensor)?)?;
+    let mut labelled = Vec::with_capacity(outputs.len());
+    for (i, output) in outputs.iter().enumerate() {
+        let output_tensor = output_as_f32(output, CONFIG.strict_output_types)?;
+        let output_array = output_tensor.to_array_view::<f32>().map_err(context(
+            InferaError::OnnxError,
+            "model_output",
+            model_name,
+        ))?;
+        let name = model
+            .output_names
+            .get(i)
+            .cloned()
+            .unwrap_or_else(|| format!("output_{}", i));
+        labelled.push(json!({
+            "name": name,
+            "shape": output_array.shape(),
+            "data": output_array.iter().cloned().collect::<Vec<f32>>(),
+        }));
+    }
+    serde_json::to_string(&labelled).map_err(context(
+        InferaError::JsonError,
+        "json_write",
+        model_name,
+    ))
+}
+
+/// Returns true if a batch can be split into row chunks for the given model.
+///
+/// Chunking is only safe when both the input and the output have a dynamic batch
+/// dimension, so each output row corresponds to one input row.
+#[cfg(feature = "tract")]
+fn supports_chunking(model: &OnnxModel) -> bool {
+    match (model.input_shape.as_deref(), model.output_shape.as_deref()) {
+        (Some(input), Some(output)) => {
+            input.len() == 2 && input.first() == Some(&-1) && output.first() == Some(&-1)
+        }
+        _ => false,
+    }
+}
+
+/// Splits the input into chunks of at most `chunk_rows` rows and runs them in parallel.
+///
+/// The chunk outputs are stitched together in input order. When the model's output shape
+/// fixes the width of its rows, each chunk copies its output straight into its part of
+/// the buffer from `alloc`. An error is returned if a chunk's output batch does not match
+/// its input batch. The chunks share one concurrency permit, since they make up a single
+/// prediction.
+#[cfg(feature = "tract")]
+fn run_chunked_batches<A: OutputAlloc>(
+    model: &OnnxModel,
+    input: &[f32],
+    rows: usize,
+    cols: usize,
+    chunk_rows: usize,
+    alloc: &A,
+    profile_ns: Option<&AtomicU64>,
+) -> Result<(A::Buffer, usize, usize), InferaError> {
+    let _permit = concurrency::acquire(&model.name)?;
+    let run_chunk = |chunk: &[f32]| -> Result<(TVec<TValue>, usize), InferaError> {
+        let n = chunk.len() / cols;
+        let input_tensor = Tensor::from_shape(&[n, cols], chunk).map_err(context(
+            InferaError::OnnxError,
+            "input_tensor",
+            &model.name,
+        ))?;
+        let input = to_model_input(model, input_tensor)?;
+        let outputs = run_plan_permitted(model, tvec!(input), profile_ns)?;
+        let (chunk_output_rows, chunk_output_cols) = outputs
+            .first()
+            .map_or((0, 0), |output| shape_rows_cols(output.shape()));
+        if chunk_output_rows != n {
+            return Err(InferaError::OnnxError(format!(
+                "Chunked inference requires the output batch ({}) to match the input batch ({})",
+                chunk_output_rows, n
+            )));
+        }
+        Ok((outputs, chunk_output_cols))
+    };
+    let width_mismatch = || {
+        InferaError::OnnxError(
+            "Chunked inference produced outputs with different widths".to_string(),
+        )
+    };
+
+    if let Some(output_cols) = fixed_output_cols(model) {
+        let mut output = alloc.with_len(rows * output_cols);
+        let fill = |(chunk, out): (&[f32], &mut [f32])| -> Result<(), InferaError> {
+            let (outputs, chunk_output_cols) = run_chunk(chunk)?;
+            if chunk_output_cols != output_cols {
+                return Err(width_mismatch());
+            }
+            first_output_with(outputs, |values| out.copy_from_slice(values))?;
+            Ok(())
+        };
+        let out = output.as_mut();
+        match CHUNK_POOL.as_ref() {
+            Some(pool) => pool.install(|| {
+                input
+                    .par_chunks(chunk_rows * cols)
+                    .zip(out.par_chunks_mut(chunk_rows * output_cols))
+                    .try_for_each(fill)
+            })?,
+            None => input
+                .chunks(chunk_rows * cols)
+                .zip(out.chunks_mut(chunk_rows * output_cols))
+                .try_for_each(fill)?,
+        }
+        return Ok((output, rows, output_cols));
+    }
+
+    // Without a fixed row width, each chunk's output is copied out before it is stitched
+    let run_to_vec = |chunk: &[f32]| -> Result<(Vec<f32>, usize), InferaError> {
+        let (outputs, chunk_output_cols) = run_chunk(chunk)?;
+        let (values, _) = first_output(outputs)?;
+        Ok((values, chunk_output_cols))
+    };
+    let chunks: Vec<&[f32]> = input.chunks(chunk_rows * cols).collect();
+    let results: Vec<(Vec<f32>, usize)> = match CHUNK_POOL.as_ref() {
+        Some(pool) => pool.install(|| {
+            chunks
+                .par_iter()
+                .map(|c| run_to_vec(c))
+                .collect::<Result<Vec<_>, InferaError>>()
+        })?,
+        None => chunks
+            .iter()
+            .map(|c| run_to_vec(c))
+            .collect::<Result<Vec<_>, InferaError>>()?,
+    };
+    let output_cols = results.first().map(|(_, c)| *c).unwrap_or(0);
+    let mut output = alloc.with_len(rows * output_cols);
+    let mut offset = 0;
+    for (chunk_output, chunk_output_cols) in results {
+        if chunk_output_cols != output_cols {
+            return Err(width_mismatch());
+        }
+        output.as_mut()[offset..offset + chunk_output.len()].copy_from_slice(&chunk_output);
+        offset += chunk_output.len();
+    }
+    Ok((output, rows, output_cols))
+}
+
+/// Returns the number of values in each row of the model's output, if its output shape
+/// fixes it.
+#[cfg(feature = "tract")]
+fn fixed_output_cols(model: &OnnxModel) -> Option<usize> {
+    let inner_dims = model.output_shape.as_deref()?.get(1..)?;
+    inner_dims
+        .iter()
+        .all(|&d| d > 0)
+        .then(|| inner_dims.iter().product::<i64>().max(1) as usize)
+}
+
+/// Runs the model on a single input tensor and returns its first output.
+///
+/// The output is returned as flat `f32` data together with its shape.
+#[cfg(feature = "tract")]
+fn run_model(model: &OnnxModel, input: Tensor) -> Result<(Vec<f32>, Vec<usize>), InferaError> {
+    first_output(run_plan(model, to_model_input(model, input)?)?)
+}
+
+/// Applies the model's postprocessor, if it has one, to its first output and boxes the
+/// output for the caller.
+#[cfg(feature = "tract")]
+fn boxed_output(
+    model: &OnnxModel,
+    mut output_data: impl AsMut<[f32]> + Into<Box<[f32]>>,
+    output_rows: usize,
+    output_cols: usize,
+) -> InferaInferenceResult {
+    if let Some(postprocessor) = &model.postprocessor {
+        postprocessor.apply(output_data.as_mut(), output_cols);
+    }
+    InferaInferenceResult::from_boxed(output_data.into(), output_rows, output_cols)
+}
+
+/// Returns the first of a model's outputs as `f32` values with its shape.
+#[cfg(feature = "tract")]
+fn first_output(outputs: TVec<TValue>) -> Result<(Vec<f32>, Vec<usize>), InferaError> {
+    first_output_with(outputs, |values| values.to_vec())
+}
+
+/// Returns the first of a model's outputs with its shape, copying its `f32` values out
+/// with `copy_out`.
+#[cfg(feature = "tract")]
+fn first_output_with<T>(
+    outputs: TVec<TValue>,
+    copy_out: impl FnOnce(&[f32]) -> T,
+) -> Result<(T, Vec<usize>), InferaError> {
+    nth_output_with(outputs, 0, copy_out)
+}
+
+/// Returns the model output at `index` with its shape, copying its `f32` values out with
+/// `copy_out`. The other outputs are dropped without being converted.
+#[cfg(feature = "tract")]
+fn nth_output_with<T>(
+    outputs: TVec<TValue>,
+    index: usize,
+    copy_out: impl FnOnce(&[f32]) -> T,
+) -> Result<(T, Vec<usize>), InferaError> {
+    let output_value = outputs.into_iter().nth(index).ok_or_else(|| {
+        InferaError::OnnxError(format!("The model produced no output tensor {}", index))
+    })?;
+    let output_tensor = output_as_f32(&output_value, CONFIG.strict_output_types)?;
+    let output_values = output_tensor.as_slice::<f32>().map_err(context(
+        InferaError::OnnxError,
+        "model_output",
+        "",
+    ))?;
+    Ok((copy_out(output_values), output_tensor.shape().to_vec()))
+}
+
+/// Runs the model plan on one input and records the wall-clock duration of the run in
+/// the model's `last_inference_ns`.
+#[cfg(feature = "tract")]
+fn run_plan(model: &OnnxModel, input: TValue) -> Result<TVec<TValue>, InferaError> {
+    run_plan_inputs(model, tvec!(input), None)
+}
+
+/// Runs the model plan on any number of inputs (including none) and records the
+/// duration of the run like `run_plan`, also adding it to `profile_ns` if it is given.
+///
+/// Every prediction runs the plan through here, so the run holds one of the model's
+/// concurrency permits (see `infera_set_model_concurrency`).
+#[cfg(feature = "tract")]
+fn run_plan_inputs(
+    model: &OnnxModel,
+    inputs: TVec<TValue>,
+    profile_ns: Option<&AtomicU64>,
+) -> Result<TVec<TValue>, InferaError> {
+    let _permit = concurrency::acquire(&model.name)?;
+    run_plan_permitted(model, inputs, profile_ns)
+}
+
+/// Runs the model plan like `run_plan_inputs`, for a caller that already holds a
+/// concurrency permit for the model.
+#[cfg(feature = "tract")]
+fn run_plan_permitted(
+    model: &OnnxModel,
+    inputs: TVec<TValue>,
+    profile_ns: Option<&AtomicU64>,
+) -> Result<TVec<TValue>, InferaError> {
+    let start = Instant::now();
+    let outputs = model.model.run(inputs).map_err(context(
+        InferaError::OnnxError,
+        "model_run",
+        &model.name,
+    ))?;
+    let elapsed_ns = u64::try_from(start.elapsed().as_nanos()).unwrap_or(u64::MAX);
+    model.last_inference_ns.store(elapsed_ns, Ordering::Relaxed);
+    if let Some(profile_ns) = profile_ns {
+        profile_ns.fetch_add(elapsed_ns, Ordering::Relaxed);
+    }
+    Ok(outputs)
+}
+
+/// Returns the wall-clock duration in nanoseconds of the most recent run of a loaded
+/// model, or 0 if it has not run since it was loaded.
+#[cfg(feature = "tract")]
+pub(crate) fn last_inference_ns_impl(model_name: &str) -> Result<u64, InferaError> {
+    Ok(get_model(model_name)?
+        .last_inference_ns
+        .load(Ordering::Relaxed))
+}
+
+/// A stub for `last_inference_ns_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn last_inference_ns_impl(_model_name: &str) -> Result<u64, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+/// Checks that the model's first input can take values of type `provided`.
+///
+/// `f32` values are cast for f16 and f64 models, and byte values for models of any
+/// numeric type. Other combinations, such as `f32` values for a model that takes `i64`
+/// token ids, return `InferaError::InputDtypeMismatch` before any tensor is built, instead
+/// of a backend error deep inside the plan.
+#[cfg(feature = "tract")]
+fn check_input_dtype(model: &OnnxModel, provided: DatumType) -> Result<(), InferaError> {
+    let expected = model.input_dtype;
+    let accepted = expected == provided
+        || match provided {
+            DatumType::F32 => matches!(expected, DatumType::F16 | DatumType::F64),
+            DatumType::U8 => expected.is_float() || expected.is_integer(),
+            _ => false,
+        };
+    if accepted {
+        return Ok(());
+    }
+    Err(InferaError::InputDtypeMismatch {
+        input: model
+            .input_names
+            .first()
+            .cloned()
+            .unwrap_or_else(|| "input".to_string()),
+        expected: dtype_name(expected),
+        provided: dtype_name(provided),
+    })
+}
+
+/// Converts an input tensor to the model's input type.
+///
+/// `f32` inputs are converted to f16 for half-precision models, and byte inputs are
+/// converted for models that take another type. Inputs the model cannot take are
+/// rejected by `check_input_dtype`.
+#[cfg(feature = "tract")]
+pub(crate) fn to_model_input(model: &OnnxModel, input: Tensor) -> Result<TValue, InferaError> {
+    let input_dt = input.datum_type();
+    if input_dt == model.input_dtype {
+        return Ok(input.into());
+    }
+    check_input_dtype(model, input_dt)?;
+    let converted = input
+        .cast_to_dt(model.input_dtype)
+        .map_err(context(InferaError::OnnxError, "input_cast", &model.name))?
+        .into_owned();
+    Ok(converted.into())
+}
+
+/// Returns an output tensor as f32, converting it from f16, bool, or an integer type if needed.
+///
+/// f16 outputs are always widened. Other types are cast too (booleans become 0.0 or 1.0)
+/// unless `strict` is set, in which case they are reported as `NonF32Output`.
+#[cfg(feature = "tract")]
+fn output_as_f32(output: &Tensor, strict: bool) -> Result<Cow<'_, Tensor>, InferaError> {
+    match output.datum_type() {
+        DatumType::F32 => Ok(Cow::Borrowed(output)),
+        DatumType::F16 => {
+            output
+                .cast_to::<f32>()
+                .map_err(context(InferaError::OnnxError, "model_output", ""))
+        }
+        dtype if strict => Err(InferaError::NonF32Output(format!("{:?}", dtype))),
+        DatumType::Bool => {
+            let values = output
+                .as_slice::<bool>()
+                .map_err(context(InferaError::OnnxError, "model_output", ""))?
+                .iter()
+                .map(|&b| if b { 1.0f32 } else { 0.0 })
+                .collect::<Vec<_>>();
+            Tensor::from_shape(output.shape(), &values)
+                .map(Cow::Owned)
+                .map_err(context(InferaError::OnnxError, "model_output", ""))
+        }
+        _ => output
+            .cast_to::<f32>()
+            .map_err(context(InferaError::OnnxError, "model_output", "")),
+    }
+}
+
+/// Runs a model with a fixed batch dimension on an arbitrary number of rows.
+///
+/// The input is split into chunks of `batch` rows. The last chunk is padded up to
+/// `batch` rows according to the model's `PadMode`, and the outputs for the padding
+/// rows are dropped, so the result always has exactly `rows` rows. The kept rows of each
+/// chunk are copied straight into the buffer from `alloc`.
+#[cfg(feature = "tract")]
+fn run_padded_batches<A: OutputAlloc>(
+    model: &OnnxModel,
+    input: &[f32],
+    rows: usize,
+    cols: usize,
+    batch: usize,
+    alloc: &A,
+    profile_ns: Option<&AtomicU64>,
+) -> Result<(A::Buffer, usize, usize), InferaError> {
+    let mut output: Option<A::Buffer> = None;
+    let mut output_cols = 0;
+    let mut offset = 0;
+    for chunk in input.chunks(batch * cols) {
+        let chunk_rows = chunk.len() / cols;
+        let mut padded = Vec::with_capacity(batch * cols);
+        padded.extend_from_slice(chunk);
+        match model.options.pad_mode {
+            PadMode::Zeros => padded.resize(batch * cols, 0.0),
+            PadMode::RepeatLast => {
+                let last_row = &chunk[(chunk_rows - 1) * cols..];
+                while padded.len() < batch * cols {
+                    padded.extend_from_slice(last_row);
+                }
+            }
+        }
+        let input_tensor = Tensor::from_shape(&[batch, cols], &padded).map_err(context(
+            InferaError::OnnxError,
+            "input_tensor",
+            &model.name,
+        ))?;
+        let input = to_model_input(model, input_tensor)?;
+        let outputs = run_plan_inputs(model, tvec!(input), profile_ns)?;
+        let (chunk_output_rows, chunk_output_cols) = outputs
+            .first()
+            .map_or((0, 0), |output| shape_rows_cols(output.shape()));
+        if chunk_output_rows != batch {
+            return Err(InferaError::OnnxError(format!(
+                "Batch padding requires the output batch ({}) to match the input batch ({})",
+                chunk_output_rows, batch
+            )));
+        }
+        let output = output.get_or_insert_with(|| {
+            output_cols = chunk_output_cols;
+            alloc.with_len(rows * chunk_output_cols)
+        });
+        if chunk_output_cols != output_cols {
+            return Err(InferaError::OnnxError(
+                "Batch padding produced outputs with different widths".to_string(),
+            ));
+        }
+        let kept = chunk_rows * output_cols;
+        first_output_with(outputs, |values| {
+            output.as_mut()[offset..offset + kept].copy_from_slice(&values[..kept])
+        })?;
+        offset += kept;
+    }
+    let output = output.unwrap_or_else(|| alloc.with_len(0));
+    Ok((output, rows, output_cols))
+}
+
+/// A stub for `run_inference_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn run_inference_impl(
+    _model_name: &str,
+    _data: *const f32,
+    _rows: usize,
+    _cols: usize,
+) -> Result<InferaInferenceResult, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+/// A stub for `run_inference_profiled_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn run_inference_profiled_impl(
+    _model_name: &str,
+    _data: *const f32,
+    _rows: usize,
+    _cols: usize,
+) -> Result<InferaProfiledResult, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+/// A stub for `run_features_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn run_features_impl(
+    _model_name: &str,
+    _features_json: &str,
+) -> Result<InferaInferenceResult, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+/// A stub for `run_inference_output_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn run_inference_output_impl(
+    _model_name: &str,
+    _data: *const f32,
+    _rows: usize,
+    _cols: usize,
+    _output_selector: &str,
+) -> Result<InferaInferenceResult, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+/// A stub for `run_inference_output_index_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn run_inference_output_index_impl(
+    _model_name: &str,
+    _data: *const f32,
+    _rows: usize,
+    _cols: usize,
+    _index: usize,
+) -> Result<InferaInferenceResult, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+/// A stub for `run_inference_bytes_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn run_inference_bytes_impl(
+    _model_name: &str,
+    _data: *const f32,
+    _rows: usize,
+    _cols: usize,
+    _native_endian: bool,
+) -> Result<OutputBytes, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+/// A stub for `run_inference_reusing_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn run_inference_reusing_impl(
+    _model: &OnnxModel,
+    _data: *const f32,
+    _rows: usize,
+    _cols: usize,
+    _scratch: &mut InputScratch,
+) -> Result<InferaInferenceResult, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+/// A stub for `run_inference_seeded_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn run_inference_seeded_impl(
+    _model_name: &str,
+    _data: *const f32,
+    _rows: usize,
+    _cols: usize,
+    _seed: u64,
+) -> Result<InferaInferenceResult, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+/// A stub for `run_inference_with_symbols_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn run_inference_with_symbols_impl(
+    _model_name: &str,
+    _data: *const f32,
+    _rows: usize,
+    _cols: usize,
+    _symbols_json: &str,
+) -> Result<InferaInferenceResult, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+/// A stub for `run_inference_u8_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn run_inference_u8_impl(
+    _model_name: &str,
+    _data: *const u8,
+    _rows: usize,
+    _cols: usize,
+) -> Result<InferaInferenceResult, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+/// A stub for `run_inference_f16_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn run_inference_f16_impl(
+    _model_name: &str,
+    _data: *const u16,
+    _rows: usize,
+    _cols: usize,
+) -> Result<InferaInferenceResult, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+/// A stub for `run_inference_all_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn run_inference_all_impl(
+    _model_name: &str,
+    _data: *const f32,
+    _rows: usize,
+    _cols: usize,
+) -> Result<String, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+/// Runs inference with a given model and raw BLOB input data.
+///
+/// This function is similar to `run_inference_impl` but takes a raw byte slice (`BLOB`)
+/// as input. It converts the bytes to `f32` values (or uses one byte per element for
+/// models with `u8` or `i8` inputs), validates the shape against the model's expected
+/// input, and attempts to infer the batch size for models with dynamic input dimensions.
+///
+/// # Arguments
+///
+/// * `model_name` - The name of the loaded model to use for inference.
+/// * `blob_data` - A pointer to the raw byte data.
+/// * `blob_len` - The length of the byte data slice.
+/// * `batch` - The number of samples the blob must hold, or `None` to infer it. With
+///   `INFERA_STRICT_BLOB` set, `None` means exactly one sample.
+///
+/// # Returns
+///
+/// * `Ok(InferaInferenceResult)` containing the output tensor data and metadata.
+/// * `Err(InferaError)` if the model is not found, the blob size is invalid,
+///   the shape does not match, or an error occurs during inference.
+#[cfg(feature = "tract")]
+pub(crate) fn run_inference_blob_impl(
+    model_name: &str,
+    blob_data: *const u8,
+    blob_len: usize,
+    batch: Option<usize>,
+) -> Result<InferaInferenceResult, InferaError> {
+    run_blob_within(model_name, blob_data, blob_len, batch, None)
+}
+
+/// Runs inference like `run_inference_blob_impl`, giving up with
+/// `InferaError::InferenceDeadlineExceeded` if `timeout_ms` milliseconds have passed when
+/// the next chunk of the input is about to start. A `timeout_ms` of 0 means no timeout.
+///
+/// A chunk that has started always runs to completion, so the call can take longer than
+/// `timeout_ms` by up to one chunk. An input that is not split into chunks is checked
+/// once, before it runs.
+#[cfg(feature = "tract")]
+pub(crate) fn run_inference_blob_timeout_impl(
+    model_name: &str,
+    blob_data: *const u8,
+    blob_len: usize,
+    timeout_ms: u64,
+) -> Result<InferaInferenceResult, InferaError> {
+    let deadline = (timeout_ms > 0).then(|| Deadline::after(timeout_ms));
+    run_blob_within(model_name, blob_data, blob_len, None, deadline)
+}
+
+/// The point in time after which a prediction stops at the next chunk boundary.
+#[cfg(feature = "tract")]
+#[derive(Debug, Clone, Copy)]
+struct Deadline {
+    /// When the deadline passes, or `None` if it is too far away to represent.
+    at: Option<Instant>,
+    /// The timeout the deadline was set from, for the error message.
+    timeout_ms: u64,
+}
+
+#[cfg(feature = "tract")]
+impl Deadline {
+    /// Returns the deadline `timeout_ms` milliseconds from now.
+    fn after(timeout_ms: u64) -> Self {
+        Deadline {
+            at: Instant::now().checked_add(Duration::from_millis(timeout_ms)),
+            timeout_ms,
+        }
+    }
+
+    /// Returns an error if the deadline has passed before chunk `chunk` (counting from 0)
+    /// of `chunks` starts.
+    fn check(&self, chunk: usize, chunks: usize) -> Result<(), InferaError> {
+        match self.at {
+            Some(at) if Instant::now() >= at => Err(InferaError::InferenceDeadlineExceeded {
+                timeout_ms: self.timeout_ms,
+                chunk: chunk + 1,
+                chunks,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Runs a blob prediction, checking `deadline` before each chunk of the input.
+#[cfg(feature = "tract")]
+fn run_blob_within(
+    model_name: &str,
+    blob_data: *const u8,
+    blob_len: usize,
+    batch: Option<usize>,
+    deadline: Option<Deadline>,
+) -> Result<InferaInferenceResult, InferaError> {
+    check_input_bytes(blob_len)?;
+
+    let model = get_model(model_name)?;
+    let byte_input = is_byte_input(model.input_dtype);
+    let provided = if byte_input {
+        DatumType::U8
+    } else {
+        DatumType::F32
+    };
+    check_input_dtype(&model, provided)?;
+    if !byte_input && !blob_len.is_multiple_of(mem::size_of::<f32>()) {
+        return Err(InferaError::InvalidBlobSize);
+    }
+    let blob_bytes = unsafe { std::slice::from_raw_parts(blob_data, blob_len) };
+    let element_count = if byte_input {
+        blob_len
+    } else {
+        blob_len / mem::size_of::<f32>()
+    };
+    let final_shape = match &model.input_shape {
+        Some(shape) => {
+            if let Some(batch) = batch.or(CONFIG.strict_blob.then_some(1)) {
+                check_blob_batch(shape, element_count, batch)?;
+            }
+            resolve_blob_shape(shape, element_count)?
+        }
+        None => vec![element_count],
+    };
+    validate_input_shape(&model, &final_shape)?;
+    let chunk_rows = blob_chunk_rows(&model, &final_shape, CONFIG.batch_chunk_rows);
+    let check = |chunk: usize, chunks: usize| match deadline {
+        Some(deadline) => deadline.check(chunk, chunks),
+        None => Ok(()),
+    };
+    let (output_data, output_shape) =
+        run_blob_chunks(&model, blob_bytes, &final_shape, chunk_rows, &check)?;
+    let (output_rows, output_cols) = shape_rows_cols(&output_shape);
+    Ok(boxed_output(&model, output_data, output_rows, output_cols))
+}
+
+/// Returns the number of samples per chunk a blob input of `shape` is split into, or 0 if
+/// it runs whole.
+///
+/// Like `infer_rows`, inputs with more than `chunk_rows` samples are split along the
+/// batch dimension for models whose input and output batch dimensions are both dynamic.
+#[cfg(feature = "tract")]
+fn blob_chunk_rows(model: &OnnxModel, shape: &[usize], chunk_rows: usize) -> usize {
+    let dynamic_batch = |shape: Option<&[i64]>| shape.and_then(|s| s.first()) == Some(&-1);
+    let chunked = chunk_rows > 0
+        && shape.first().is_some_and(|&rows| rows > chunk_rows)
+        && shape[1..].iter().product::<usize>() > 0
+        && dynamic_batch(model.input_shape.as_deref())
+        && dynamic_batch(model.output_shape.as_deref());
+    if chunked {
+        chunk_rows
+    } else {
+        0
+    }
+}
+
+/// Runs a blob input of `shape`, in chunks of `chunk_rows` samples unless it is 0, and
+/// returns the first output with its shape.
+///
+/// Each chunk is decoded from `bytes` only when it runs, so a large blob is never held as
+/// a whole tensor. Chunks run in parallel on the chunk pool and their outputs are joined
+/// in input order. `check` is called with the index of each chunk and the number of chunks
+/// before the chunk starts, and stops the prediction at that boundary if it fails. The
+/// outputs of chunks that already ran are dropped with the error.
+#[cfg(feature = "tract")]
+fn run_blob_chunks(
+    model: &OnnxModel,
+    bytes: &[u8],
+    shape: &[usize],
+    chunk_rows: usize,
+    check: &(dyn Fn(usize, usize) -> Result<(), InferaError> + Sync),
+) -> Result<(Vec<f32>, Vec<usize>), InferaError> {
+    if chunk_rows == 0 {
+        check(0, 1)?;
+        return run_model(model, blob_tensor(model, shape, bytes)?);
+    }
+    let element_bytes = if is_byte_input(model.input_dtype) {
+        1
+    } else {
+        mem::size_of::<f32>()
+    };
+    let sample_bytes = shape[1..].iter().product::<usize>() * element_bytes;
+    let chunks: Vec<&[u8]> = bytes.chunks(chunk_rows * sample_bytes).collect();
+    let count = chunks.len();
+    let run_chunk = |(i, chunk): (usize, &[u8])| -> Result<(Vec<f32>, Vec<usize>), InferaError> {
+        check(i, count)?;
+        let n = chunk.len() / sample_bytes;
+        let mut chunk_shape = shape.to_vec();
+        chunk_shape[0] = n;
+        let (chunk_output, chunk_output_shape) =
+            run_model(model, blob_tensor(model, &chunk_shape, chunk)?)?;
+        if chunk_output_shape.first() != Some(&n) {
+            return Err(InferaError::OnnxError(format!(
+                "Chunked inference requires the output batch ({}) to match the input batch ({})",
+                chunk_output_shape.first().copied().unwrap_or(0),
+                n
+            )));
+        }
+        Ok((chunk_output, chunk_output_shape))
+    };
+    let results: Vec<(Vec<f32>, Vec<usize>)> = match CHUNK_POOL.as_ref() {
+        Some(pool) => pool.install(|| {
+            chunks
+                .par_iter()
+                .copied()
+                .enumerate()
+                .map(run_chunk)
+                .collect::<Result<Vec<_>, InferaError>>()
+        })?,
+        None => chunks
+            .iter()
+            .copied()
+            .enumerate()
+            .map(run_chunk)
+            .collect::<Result<Vec<_>, InferaError>>()?,
+    };
+    let sample_shape = results
+        .first()
+        .map(|(_, shape)| shape[1..].to_vec())
+        .unwrap_or_default();
+    let mut output = Vec::with_capacity(shape[0] * sample_shape.iter().product::<usize>());
+    for (chunk_output, chunk_output_shape) in results {
+        if chunk_output_shape[1..] != sample_shape[..] {
+            return Err(InferaError::OnnxError(
+                "Chunked inference produced outputs with different widths".to_string(),
+            ));
+        }
+        output.extend_from_slice(&chunk_output);
+    }
+    let mut output_shape = vec![shape[0]];
+    output_shape.extend(sample_shape);
+    Ok((output, output_shape))
+}
+
+/// Decodes the bytes of a blob input of `shape` into a tensor of the model's input type,
+/// or of `f32` values for models that do not take bytes.
+#[cfg(feature = "tract")]
+fn blob_tensor(model: &OnnxModel, shape: &[usize], bytes: &[u8]) -> Result<Tensor, InferaError> {
+    if is_byte_input(model.input_dtype) {
+        check_shape_len(shape, bytes.len())?;
+        return byte_tensor(model.input_dtype, shape, bytes);
+    }
+    let values: Vec<f32> = bytes
+        .chunks_exact(4)
+        .map(|chunk| {
+            // SAFETY: chunks_exact(4) guarantees exactly 4 bytes, so this conversion cannot fail
+            let array: [u8; 4] = chunk.try_into().unwrap_or_default();
+            f32::from_ne_bytes(array)
+        })
+        .collect();
+    if CONFIG.validate_input {
+        check_finite(&values)?;
+    }
+    check_shape_len(shape, values.len())?;
+    Tensor::from_shape(shape, &values).map_err(context(
+        InferaError::OnnxError,
+        "input_tensor",
+        &model.name,
+    ))
+}
+
+/// Runs inference on a single input tensor with an explicit `shape`.
+///
+/// The shape must have the same rank as the model's input, and every static dimension
+/// of the input must match.
+#[cfg(feature = "image")]
+pub(crate) fn run_shaped_impl(
+    model_name: &str,
+    shape: &[usize],
+    data: &[f32],
+) -> Result<InferaInferenceResult, InferaError> {
+    let model = get_model(model_name)?;
+    let (output_data, output_shape) = run_shaped(&model, shape, data)?;
+    let (output_rows, output_cols) = shape_rows_cols(&output_shape);
+    Ok(boxed_output(&model, output_data, output_rows, output_cols))
+}
+
+/// Checks an explicit input `shape` against the model's input and runs inference on it.
+///
+/// Returns the first output and its shape.
+#[cfg(feature = "tract")]
+fn run_shaped(
+    model: &OnnxModel,
+    shape: &[usize],
+    data: &[f32],
+) -> Result<(Vec<f32>, Vec<usize>), InferaError> {
+    run_model(model, shaped_input(model, shape, data)?)
+}
+
+/// Checks a full input shape against the model's input.
+///
+/// `provided` must have the rank of the model's input and match each of its fixed
+/// dimensions. Dynamic dimensions accept any size, except that dimensions sharing a
+/// symbol (such as the two `S` dimensions of an `[N, S, S]` attention mask) must have the
+/// same size. Any shape is accepted if the model's input shape is not known.
+#[cfg(feature = "tract")]
+fn validate_input_shape(model: &OnnxModel, provided: &[usize]) -> Result<(), InferaError> {
+    let Some(expected) = model.input_shape.as_deref() else {
+        return Ok(());
+    };
+    let mismatch = || InferaError::InvalidInputShape {
+        expected: format!("{:?}", expected),
+        actual: format!("{:?}", provided),
+    };
+    if expected.len() != provided.len() {
+        return Err(mismatch());
+    }
+    let mut symbol_sizes: HashMap<&str, usize> = HashMap::new();
+    for (i, (&dim, &size)) in expected.iter().zip(provided).enumerate() {
+        if dim >= 0 {
+            if dim as usize != size {
+                return Err(mismatch());
+            }
+        } else if let Some(symbol) = model.input_symbols.get(i).and_then(Option::as_deref) {
+            if *symbol_sizes.entry(symbol).or_insert(size) != size {
+                return Err(mismatch());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks a prospective input shape against a loaded model's input without running the
+/// model, like the check made before running an explicitly shaped input.
+///
+/// Negative dimensions are rejected, since an input always has concrete dimensions.
+#[cfg(feature = "tract")]
+pub(crate) fn check_input_shape_impl(model_name: &str, shape: &[i64]) -> Result<(), InferaError> {
+    let model = get_model(model_name)?;
+    let dims = shape
+        .iter()
+        .map(|&dim| usize::try_from(dim))
+        .collect::<Result<Vec<usize>, _>>()
+        .map_err(|_| InferaError::InvalidInputShape {
+            expected: "non-negative dimensions".to_string(),
+            actual: format!("{:?}", shape),
+        })?;
+    validate_input_shape(&model, &dims)
+}
+
+/// A stub for `check_input_shape_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn check_input_shape_impl(_model_name: &str, _shape: &[i64]) -> Result<(), InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+/// Checks an explicit input `shape` against the model's input and builds the input tensor.
+#[cfg(feature = "tract")]
+fn shaped_input(model: &OnnxModel, shape: &[usize], data: &[f32]) -> Result<Tensor, InferaError> {
+    validate_input_shape(model, shape)?;
+    if CONFIG.validate_input {
+        check_finite(data)?;
+    }
+    check_shape_len(shape, data.len())?;
+    Tensor::from_shape(shape, data).map_err(context(
+        InferaError::OnnxError,
+        "input_tensor",
+        &model.name,
+    ))
+}
+
+/// Parses one item of a bulk prediction request, a JSON object with a `shape` array of
+/// dimensions and a flat `data` array of numbers.
+#[cfg(feature = "tract")]
+fn parse_shaped_input(item: &serde_json::Value) -> Result<(Vec<usize>, Vec<f32>), InferaError> {
+    let invalid = |msg: &str| InferaError::JsonError(msg.to_string());
+    let shape = item
+        .get("shape")
+        .and_then(serde_json::Value::as_array)
+        .ok_or_else(|| invalid("\"shape\" must be an array of dimensions"))?
+        .iter()
+        .map(|d| d.as_u64().map(|d| d as usize))
+        .collect::<Option<Vec<usize>>>()
+        .ok_or_else(|| invalid("\"shape\" must contain non-negative integers"))?;
+    let data = item
+        .get("data")
+        .and_then(serde_json::Value::as_array)
+        .ok_or_else(|| invalid("\"data\" must be an array of numbers"))?
+        .iter()
+        .map(|v| v.as_f64().map(|v| v as f32))
+        .collect::<Option<Vec<f32>>>()
+        .ok_or_else(|| invalid("\"data\" must contain only numbers"))?;
+    Ok((shape, data))
+}
+
+/// Runs inference on each input of a JSON array of `{"shape": [...], "data": [...]}`
+/// objects.
+///
+/// The model is looked up once, and the inputs are run in order. Returns a JSON array
+/// with one entry per input: `{"shape": [...], "data": [...]}` for the first output, or
+/// `{"error": "..."}` if that input was malformed or failed, so one bad input does not
+/// fail the others. An unknown model or an `inputs_json` that is not an array fails the
+/// whole call.
+#[cfg(feature = "tract")]
+pub(crate) fn run_many_json_impl(
+    model_name: &str,
+    inputs_json: &str,
+) -> Result<String, InferaError> {
+    let inputs: serde_json::Value = serde_json::from_str(inputs_json).map_err(context(
+        InferaError::JsonError,
+        "inputs_parse",
+        model_name,
+    ))?;
+    let items = inputs
+        .as_array()
+        .ok_or_else(|| InferaError::JsonError("inputs must be a JSON array".to_string()))?;
+    let model = get_model(model_name)?;
+    let results: Vec<serde_json::Value> = items
+        .iter()
+        .map(|item| {
+            parse_shaped_input(item)
+                .and_then(|(shape, data)| run_shaped(&model, &shape, &data))
+                .map_or_else(
+                    |e| e.to_json(),
+                    |(data, shape)| json!({ "shape": shape, "data": data }),
+                )
+        })
+        .collect();
+    serde_json::to_string(&results).map_err(context(
+        InferaError::JsonError,
+        "json_write",
+        model_name,
+    ))
+}
+
+/// Runs inference on inputs given as a JSON object keyed by the model's input names, as
+/// declared in the ONNX graph, with `{"shape": [...], "data": [...]}` values.
+///
+/// Returns a JSON object keyed by output name with a `{"shape": [...], "data": [...]}`
+/// value for every model output, including extra outputs.
+///
+/// # Returns
+///
+/// * `Ok(String)` containing the JSON outputs.
+/// * `Err(InferaError)` if the model is not found, an input name is unknown or missing,
+///   an input is malformed or does not match the model, or inference fails.
+#[cfg(feature = "tract")]
+pub(crate) fn run_named_impl(model_name: &str, inputs_json: &str) -> Result<String, InferaError> {
+    let inputs: serde_json::Value = serde_json::from_str(inputs_json).map_err(context(
+        InferaError::JsonError,
+        "inputs_parse",
+        model_name,
+    ))?;
+    let inputs = inputs.as_object().ok_or_else(|| {
+        InferaError::JsonError("inputs must be a JSON object keyed by input name".to_string())
+    })?;
+    let model = get_model(model_name)?;
+    if let Some(name) = inputs.keys().find(|k| !model.input_names.contains(k)) {
+        return Err(InferaError::UnknownInput {
+            name: name.clone(),
+            available: model.input_names.join(", "),
+        });
+    }
+    // The plan is run with a single input, like on every other prediction path
+    let [input_name] = model.input_names.as_slice() else {
+        return Err(InferaError::OnnxError(format!(
+            "models with {} inputs are not supported; bind the other inputs with the \"initializers\" load option",
+            model.input_names.len()
+        )));
+    };
+    let item = inputs
+        .get(input_name)
+        .ok_or_else(|| InferaError::JsonError(format!("missing input '{}'", input_name)))?;
+    let (shape, data) = parse_shaped_input(item)?;
+    let input = shaped_input(&model, &shape, &data)?;
+    let outputs = run_plan(&model, to_model_input(&model, input)?)?;
+    let mut named = serde_json::Map::with_capacity(outputs.len());
+    for (i, output) in outputs.iter().enumerate() {
+        let output_tensor = output_as_f32(output, CONFIG.strict_output_types)?;
+        let output_array = output_tensor.to_array_view::<f32>().map_err(context(
+            InferaError::OnnxError,
+            "model_output",
+            model_name,
+        ))?;
+        let name = model
+            .output_names
+            .get(i)
+            .cloned()
+            .unwrap_or_else(|| format!("output_{}", i));
+        named.insert(
+            name,
+            json!({
+                "shape": output_array.shape(),
+                "data": output_array.iter().cloned().collect::<Vec<f32>>(),
+            }),
+        );
+    }
+    serde_json::to_string(&named).map_err(context(InferaError::JsonError, "json_write", model_name))
+}
+
+/// A stub for `run_named_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn run_named_impl(_model_name: &str, _inputs_json: &str) -> Result<String, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+/// A stub for `run_many_json_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn run_many_json_impl(
+    _model_name: &str,
+    _inputs_json: &str,
+) -> Result<String, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+/// Resolves a model input shape against the number of elements in a blob.
+///
+/// A single dynamic dimension (`-1`) is set to whatever makes the element counts match,
+/// and fully static shapes must match the element count exactly. Shapes with more than
+/// one dynamic dimension cannot be resolved from an element count alone and return
+/// `InferaError::AmbiguousShape`.
+#[cfg(feature = "tract")]
+pub(crate) fn resolve_blob_shape(
+    input_shape: &[i64],
+    element_count: usize,
+) -> Result<Vec<usize>, InferaError> {
+    let dynamic_dims = input_shape.iter().filter(|&&d| d < 0).count();
+    if dynamic_dims > 1 {
+        return Err(InferaError::AmbiguousShape {
+            shape: input_shape.to_vec(),
+        });
+    }
+    let known_elements: usize = input_shape
+        .iter()
+        .filter(|&&d| d >= 0)
+        .map(|&d| d as usize)
+        .product();
+    let mismatch = || InferaError::BlobShapeMismatch {
+        expected: known_elements,
+        actual: element_count,
+    };
+    if known_elements == 0 || element_count == 0 {
+        return Err(mismatch());
+    }
+    let dynamic_size = if dynamic_dims == 1 {
+        if !element_count.is_multiple_of(known_elements) {
+            return Err(mismatch());
+        }
+        element_count / known_elements
+    } else {
+        1
+    };
+    let final_shape: Vec<usize> = input_shape
+        .iter()
+        .map(|&d| if d < 0 { dynamic_size } else { d as usize })
+        .collect();
+    if final_shape.iter().product::<usize>() != element_count {
+        return Err(mismatch());
+    }
+    Ok(final_shape)
+}
+
+/// Checks that a blob holds exactly `batch` samples of a model input with a dynamic
+/// dimension, instead of any multiple of one sample.
+///
+/// Shapes without a dynamic dimension are left to `resolve_blob_shape`, which already
+/// requires an exact match.
+#[cfg(feature = "tract")]
+fn check_blob_batch(
+    input_shape: &[i64],
+    element_count: usize,
+    batch: usize,
+) -> Result<(), InferaError> {
+    if !input_shape.iter().any(|&d| d < 0) {
+        return Ok(());
+    }
+    let sample_elements: usize = input_shape
+        .iter()
+        .filter(|&&d| d >= 0)
+        .map(|&d| d as usize)
+        .product();
+    let expected = sample_elements.saturating_mul(batch);
+    if element_count != expected {
+        return Err(InferaError::BlobShapeMismatch {
+            expected,
+            actual: element_count,
+        });
+    }
+    Ok(())
+}
+
+/// Checks that a resolved input shape holds exactly `len` elements.
+///
+/// This guards tensor construction against a shape that disagrees with the data, so a
+/// mistake in resolving dynamic dimensions surfaces as `BlobShapeMismatch` instead of a
+/// malformed tensor.
+#[cfg(feature = "tract")]
+fn check_shape_len(shape: &[usize], len: usize) -> Result<(), InferaError> {
+    let expected: usize = shape.iter().product();
+    if expected != len {
+        return Err(InferaError::BlobShapeMismatch {
+            expected,
+            actual: len,
+        });
+    }
+    Ok(())
 }
 
 /// A stub for `run_inference_blob_impl` when the "tract" feature is disabled.
@@ -270,6 +2957,22 @@ pub(crate) fn run_inference_blob_impl(
     _model_name: &str,
     _blob_data: *const u8,
     _blob_len: usize,
+    _batch: Option<usize>,
+) -> Result<InferaInferenceResult, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+/// A stub for `run_inference_blob_timeout_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn run_inference_blob_timeout_impl(
+    _model_name: &str,
+    _blob_data: *const u8,
+    _blob_len: usize,
+    _timeout_ms: u64,
 ) -> Result<InferaInferenceResult, InferaError> {
     Err(InferaError::FeatureNotEnabled(
         "ONNX inference requires 'tract' feature to be enabled".to_string(),
@@ -279,7 +2982,7 @@ pub(crate) fn run_inference_blob_impl(
 /// Retrieves metadata for a loaded model as a JSON string.
 ///
 /// This function looks up the model by name and serializes its metadata
-/// (name, input shape, output shape) into a JSON string.
+/// (name, input shape, output shape, and where it was loaded from) into a JSON string.
 ///
 /// # Arguments
 ///
@@ -291,17 +2994,64 @@ pub(crate) fn run_inference_blob_impl(
 /// * `Err(InferaError)` if the model is not found or if JSON serialization fails.
 #[cfg(feature = "tract")]
 pub(crate) fn get_model_metadata_impl(model_name: &str) -> Result<String, InferaError> {
-    let models = MODELS.read();
-    let model = models
-        .get(model_name)
-        .ok_or_else(|| InferaError::ModelNotFound(model_name.to_string()))?;
+    let model = get_model(model_name)?;
+    // Shapes that could not be determined at load time are reported as null, along with
+    // an "unknown" type, since both come from the same tract fact.
+    let fact_dtype = |shape: &Option<Vec<i64>>, dt: DatumType| match shape {
+        Some(_) => dtype_name(dt),
+        None => "unknown".to_string(),
+    };
     let info = json!({
         "name": model.name,
         "input_shape": model.input_shape,
+        "input_names": model.input_names,
+        "defaulted_inputs": model.defaulted_inputs,
+        "input_symbols": model.input_symbols,
         "output_shape": model.output_shape,
+        "input_batch_dim": batch_dim(&model.input_shape),
+        "output_batch_dim": batch_dim(&model.output_shape),
+        "input_dtype": fact_dtype(&model.input_shape, model.input_dtype),
+        "input_dtypes": model
+            .input_dtypes
+            .iter()
+            .map(|&dt| dtype_name(dt))
+            .collect::<Vec<_>>(),
+        "output_dtype": fact_dtype(&model.output_shape, model.output_dtype),
+        "output_names": model.output_names,
+        "model_metadata": model.model_metadata,
+        "signature": model.signature.as_str(),
+        "max_concurrency": concurrency::limit(model_name),
+        "postprocessor": model.postprocessor.as_ref().map(|p| p.to_json()),
+        "feature_names": model.feature_names,
+        "source_kind": model.source_kind.as_str(),
+        "path": model.path,
+        "url": model.source.as_ref().map(|source| source.url.as_str()),
+        "cache_path": model.source.as_ref().map(|_| model.path.as_str()),
+        "load_outcome": model
+            .source
+            .as_ref()
+            .map_or(InferaLoadOutcome::Local, |source| source.outcome)
+            .as_str(),
+        "content_hash": model.content_sha256,
+        "tract_version": model.tract_versions.0,
+        "tract_core_version": model.tract_versions.1,
+        "optimized": model.optimized,
+        "load_duration_ns": model.load_duration_ns,
+        "bundle": model.bundle.as_ref().map(|bundle| bundle.to_json()),
+        "namespace": namespace_of(model_name),
+        "group_id": crate::autoload::with_group_ids(|ids| ids.get(model_name).copied()),
+        "aliases": crate::canary::with_alias_targets(|targets| {
+            targets.get(model_name).cloned().unwrap_or_default()
+        }),
         "loaded": true
     });
-    serde_json::to_string(&info).map_err(|e| InferaError::JsonError(e.to_string()))
+    serde_json::to_string(&info).map_err(context(InferaError::JsonError, "json_write", model_name))
+}
+
+/// Returns a lowercase name for a tensor element type (e.g., "f32", "f16").
+#[cfg(feature = "tract")]
+fn dtype_name(dt: DatumType) -> String {
+    format!("{:?}", dt).to_lowercase()
 }
 
 /// A stub for `get_model_metadata_impl` when the "tract" feature is disabled.
@@ -314,10 +3064,279 @@ pub(crate) fn get_model_metadata_impl(_model_name: &str) -> Result<String, Infer
     ))
 }
 
-#[cfg(test)]
+/// Returns the hex-encoded SHA-256 of the file a loaded model was loaded from.
+#[cfg(feature = "tract")]
+pub(crate) fn get_model_signature_impl(model_name: &str) -> Result<String, InferaError> {
+    Ok(get_model(model_name)?.content_sha256.clone())
+}
+
+/// A stub for `get_model_signature_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn get_model_signature_impl(_model_name: &str) -> Result<String, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+/// Attaches postprocessing parsed from `spec_json` to a loaded model, or detaches it if
+/// the spec has no steps.
+#[cfg(feature = "tract")]
+pub(crate) fn set_postprocessor_impl(model_name: &str, spec_json: &str) -> Result<(), InferaError> {
+    let postprocessor = Postprocessor::from_json(spec_json)?;
+    set_postprocessor(
+        model_name,
+        (!postprocessor.is_empty()).then_some(postprocessor),
+    )
+}
+
+/// A stub for `set_postprocessor_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn set_postprocessor_impl(
+    _model_name: &str,
+    _spec_json: &str,
+) -> Result<(), InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+/// Returns the names of a loaded model's outputs in output order, including any extra
+/// outputs requested when it was loaded.
+#[cfg(feature = "tract")]
+pub(crate) fn get_output_names_impl(model_name: &str) -> Result<Vec<String>, InferaError> {
+    Ok(get_model(model_name)?.output_names.clone())
+}
+
+/// A stub for `get_output_names_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn get_output_names_impl(_model_name: &str) -> Result<Vec<String>, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+/// Returns the shape of a loaded model's first input, or of its first output if `output`
+/// is set. Dynamic dimensions are -1.
+#[cfg(feature = "tract")]
+pub(crate) fn get_model_shape_impl(
+    model_name: &str,
+    output: bool,
+) -> Result<Vec<i64>, InferaError> {
+    let model = get_model(model_name)?;
+    let (shape, kind) = if output {
+        (&model.output_shape, "output")
+    } else {
+        (&model.input_shape, "input")
+    };
+    shape.clone().ok_or_else(|| InferaError::UnknownShape {
+        model: model_name.to_string(),
+        kind,
+    })
+}
+
+/// A stub for `get_model_shape_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn get_model_shape_impl(
+    _model_name: &str,
+    _output: bool,
+) -> Result<Vec<i64>, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+/// Returns the index of the batch dimension of a shape: its first dynamic dimension, or
+/// -1 if every dimension is fixed. `None` if the shape is not known.
+#[cfg(feature = "tract")]
+fn batch_dim(shape: &Option<Vec<i64>>) -> Option<i64> {
+    let shape = shape.as_deref()?;
+    Some(shape.iter().position(|&d| d < 0).map_or(-1, |i| i as i64))
+}
+
+/// Returns the number of output elements a batch of `batch_rows` input rows produces.
+///
+/// For a model with one dynamic dimension in both its input and its output, the batch
+/// size is substituted into the output's dynamic dimension. For a fully static model, the
+/// size is that of the fixed output, if `batch_rows` is the fixed batch size or the model
+/// pads and splits batches (the `pad_batch` load option). Any other model fails with
+/// `OutputSizeNotScalable`, since its output batch does not follow the input batch.
+#[cfg(feature = "tract")]
+pub(crate) fn get_output_size_impl(
+    model_name: &str,
+    batch_rows: usize,
+) -> Result<usize, InferaError> {
+    let input = get_model_shape_impl(model_name, false)?;
+    let output = get_model_shape_impl(model_name, true)?;
+    let pad_batch = get_model(model_name)?.options.pad_batch;
+    let dynamic = |shape: &[i64]| shape.iter().filter(|&&d| d < 0).count();
+    let fixed = |shape: &[i64]| -> usize {
+        shape
+            .iter()
+            .filter(|&&d| d >= 0)
+            .map(|&d| d as usize)
+            .product()
+    };
+    let size = match (dynamic(&input), dynamic(&output)) {
+        (1, 1) => batch_rows.checked_mul(fixed(&output)),
+        (0, 0) => match (input.first(), output.first()) {
+            // Generator graphs take no input rows
+            (None, _) => Some(fixed(&output)),
+            (Some(&batch), _) if batch as usize == batch_rows => Some(fixed(&output)),
+            // The output of padded and split batches is cut back to the caller's rows
+            (Some(&batch), Some(&output_batch)) if pad_batch && batch == output_batch => {
+                batch_rows.checked_mul(fixed(&output[1..]))
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+    size.ok_or_else(|| InferaError::OutputSizeNotScalable {
+        model: model_name.to_string(),
+        rows: batch_rows,
+    })
+}
+
+/// A stub for `get_output_size_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn get_output_size_impl(
+    _model_name: &str,
+    _batch_rows: usize,
+) -> Result<usize, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+#[cfg(all(test, feature = "tract"))]
 mod tests {
     use super::*;
+    use crate::error::InferaStatus;
+    use crate::model::MODELS;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    /// Counts, per thread, the allocations of the size set in `COUNTED_SIZE`.
+    struct CountingAlloc;
+
+    thread_local! {
+        static COUNTED_SIZE: Cell<usize> = const { Cell::new(0) };
+        static COUNTED_ALLOCS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for CountingAlloc {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            // `try_with` because the thread-locals may already be gone while a thread exits
+            let _ = COUNTED_SIZE.try_with(|size| {
+                if size.get() == layout.size() {
+                    let _ = COUNTED_ALLOCS.try_with(|n| n.set(n.get() + 1));
+                }
+            });
+            unsafe { System.alloc(layout) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { System.dealloc(ptr, layout) }
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAlloc = CountingAlloc;
+
+    /// Returns the number of allocations of `bytes` bytes that `f` makes on this thread.
+    #[cfg(feature = "tract")]
+    fn count_allocs_of<T>(bytes: usize, f: impl FnOnce() -> T) -> usize {
+        COUNTED_ALLOCS.with(|n| n.set(0));
+        COUNTED_SIZE.with(|size| size.set(bytes));
+        let result = f();
+        COUNTED_SIZE.with(|size| size.set(0));
+        drop(result);
+        COUNTED_ALLOCS.with(|n| n.get())
+    }
+
+    #[test]
+    #[cfg(feature = "tract")]
+    fn test_unique_names_fills_empty_and_duplicate_names() {
+        let names = ["x", "", "x", "x_2", "y"].map(String::from).to_vec();
+        assert_eq!(
+            unique_names(names, "input"),
+            ["x", "input_1", "x_2", "x_2_3", "y"]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "tract")]
+    fn test_output_as_f32_casts_i64_outputs() {
+        let output = Tensor::from_shape(&[1, 3], &[-2i64, 0, 7]).unwrap();
+        let cast = output_as_f32(&output, false).unwrap();
+        assert_eq!(cast.datum_type(), DatumType::F32);
+        assert_eq!(cast.shape(), &[1, 3]);
+        assert_eq!(cast.as_slice::<f32>().unwrap(), &[-2.0, 0.0, 7.0]);
+        assert!(matches!(
+            output_as_f32(&output, true),
+            Err(InferaError::NonF32Output(ref dtype)) if dtype == "I64"
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "tract")]
+    fn test_output_as_f32_casts_bool_outputs() {
+        let output = Tensor::from_shape(&[2, 2], &[true, false, false, true]).unwrap();
+        let cast = output_as_f32(&output, false).unwrap();
+        assert_eq!(cast.shape(), &[2, 2]);
+        assert_eq!(cast.as_slice::<f32>().unwrap(), &[1.0, 0.0, 0.0, 1.0]);
+        assert!(matches!(
+            output_as_f32(&output, true),
+            Err(InferaError::NonF32Output(_))
+        ));
+
+        // f32 outputs are passed through and f16 outputs are widened even in strict mode.
+        let output = Tensor::from_shape(&[2], &[1.5f32, -1.0]).unwrap();
+        assert!(matches!(output_as_f32(&output, true), Ok(Cow::Borrowed(_))));
+        let output = output.cast_to_dt(DatumType::F16).unwrap().into_owned();
+        assert_eq!(
+            output_as_f32(&output, true)
+                .unwrap()
+                .as_slice::<f32>()
+                .unwrap(),
+            &[1.5, -1.0]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "tract")]
+    fn test_default_feature_names_from_metadata() {
+        let path = "../test/models/dynamic_batch.onnx";
+        let file = Some(Path::new(path));
+        let metadata =
+            |listed: &str| HashMap::from([("feature_names".to_string(), listed.to_string())]);
+        assert!(default_feature_names(path, file, &HashMap::new())
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            default_feature_names(path, file, &metadata(r#"["age", "income"]"#)).unwrap(),
+            ["age", "income"]
+        );
+        assert_eq!(
+            default_feature_names(path, file, &metadata("age, income")).unwrap(),
+            ["age", "income"]
+        );
+        // Invalid metadata does not fail the load
+        assert!(default_feature_names(path, file, &metadata("age,age"))
+            .unwrap()
+            .is_empty());
+    }
 
+    #[cfg(feature = "tract")]
     #[test]
     fn test_shape_rows_cols() {
         assert_eq!(shape_rows_cols(&[]), (1, 1));
@@ -326,4 +3345,816 @@ mod tests {
         assert_eq!(shape_rows_cols(&[2, 3, 4]), (2, 12));
         assert_eq!(shape_rows_cols(&[1, 1, 1, 1]), (1, 1));
     }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_run_inference_rejects_empty_input() {
+        let data = [1.0f32; 3];
+        for (rows, cols) in [(0, 3), (1, 0)] {
+            // The check happens before the model lookup, so any model name will do.
+            match run_inference_impl("__missing_model__", data.as_ptr(), rows, cols) {
+                Err(InferaError::InvalidInputShape { actual, .. }) => {
+                    assert_eq!(actual, format!("{} x {}", rows, cols));
+                }
+                Err(e) => panic!("expected InvalidInputShape, got {}", e),
+                Ok(_) => panic!("expected InvalidInputShape for {} x {}", rows, cols),
+            }
+        }
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_resolve_blob_shape() {
+        // One dynamic dimension is inferred from the element count.
+        assert_eq!(resolve_blob_shape(&[-1, 3], 12).unwrap(), vec![4, 3]);
+        assert_eq!(resolve_blob_shape(&[2, -1, 5], 30).unwrap(), vec![2, 3, 5]);
+        assert!(matches!(
+            resolve_blob_shape(&[-1, 3], 10),
+            Err(InferaError::BlobShapeMismatch {
+                expected: 3,
+                actual: 10
+            })
+        ));
+
+        // Static shapes must fit exactly, not just divide the element count.
+        assert_eq!(resolve_blob_shape(&[1, 4], 4).unwrap(), vec![1, 4]);
+        assert!(matches!(
+            resolve_blob_shape(&[1, 4], 8),
+            Err(InferaError::BlobShapeMismatch {
+                expected: 4,
+                actual: 8
+            })
+        ));
+        assert!(resolve_blob_shape(&[-1, 3], 0).is_err());
+
+        // Two dynamic dimensions cannot be resolved from the element count alone.
+        match resolve_blob_shape(&[-1, -1, 80], 160) {
+            Err(InferaError::AmbiguousShape { shape }) => assert_eq!(shape, vec![-1, -1, 80]),
+            other => panic!("expected AmbiguousShape, got {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_check_blob_batch() {
+        assert!(check_blob_batch(&[-1, 3], 3, 1).is_ok());
+        assert!(check_blob_batch(&[-1, 3], 12, 4).is_ok());
+        // Oversized input that is still a multiple of one sample is rejected.
+        assert!(matches!(
+            check_blob_batch(&[-1, 3], 6, 1),
+            Err(InferaError::BlobShapeMismatch {
+                expected: 3,
+                actual: 6
+            })
+        ));
+        // Static shapes are checked exactly by resolve_blob_shape instead.
+        assert!(check_blob_batch(&[1, 4], 4, 3).is_ok());
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_validate_input_shape() {
+        let name = "engine_validate_input_shape";
+        load_model_impl(name, "../test/models/symbolic_sequence.onnx").unwrap();
+        let mut model = (*get_model(name).unwrap()).clone();
+        MODELS.write().remove(name);
+
+        // [N, S, 2]: symbolic dimensions take any size, and fixed ones must match
+        assert!(validate_input_shape(&model, &[2, 3, 2]).is_ok());
+        assert!(validate_input_shape(&model, &[1, 7, 2]).is_ok());
+        for shape in [&[2, 3, 3][..], &[2, 3], &[2, 3, 2, 1], &[]] {
+            assert!(matches!(
+                validate_input_shape(&model, shape),
+                Err(InferaError::InvalidInputShape { .. })
+            ));
+        }
+
+        // Dimensions that share a symbol must have the same size
+        model.input_shape = Some(vec![-1, -1, -1]);
+        model.input_symbols = vec![
+            Some("N".to_string()),
+            Some("S".to_string()),
+            Some("S".to_string()),
+        ];
+        assert!(validate_input_shape(&model, &[2, 5, 5]).is_ok());
+        assert!(matches!(
+            validate_input_shape(&model, &[2, 5, 4]),
+            Err(InferaError::InvalidInputShape { .. })
+        ));
+
+        // Dynamic dimensions without a symbol are independent
+        model.input_symbols = vec![None, None, None];
+        assert!(validate_input_shape(&model, &[2, 5, 4]).is_ok());
+
+        // Any shape is accepted when the input shape is not known
+        model.input_shape = None;
+        assert!(validate_input_shape(&model, &[1, 2, 3, 4]).is_ok());
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_check_input_cols_uses_inner_dimensions() {
+        let (fixed_name, image_name) = ("engine_cols_fixed_batch", "engine_cols_image_4d");
+        load_model_impl(fixed_name, "../test/models/fixed_batch.onnx").unwrap();
+        load_model_impl(image_name, "../test/models/image_4d.onnx").unwrap();
+        let fixed = get_model(fixed_name).unwrap();
+        let image = get_model(image_name).unwrap();
+        MODELS.write().remove(fixed_name);
+        MODELS.write().remove(image_name);
+
+        // The fixed batch of 4 is padded or split to, so any number of rows is accepted
+        assert!(check_input_cols(&fixed, 3, 3).is_ok());
+        assert!(check_input_cols(&fixed, 9, 3).is_ok());
+        assert!(matches!(
+            check_input_cols(&fixed, 4, 2),
+            Err(InferaError::InvalidInputShape { .. })
+        ));
+        // The columns of a [N, 3, 8, 8] input hold all of its inner dimensions
+        assert!(check_input_cols(&image, 2, 192).is_ok());
+        assert!(check_input_cols(&image, 2, 64).is_err());
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_blob_inference_with_4d_dynamic_model() {
+        let name = "engine_image_4d";
+        load_model_impl(name, "../test/models/image_4d.onnx").unwrap();
+
+        // Two [3, 8, 8] images where every pixel of channel c in image i is (i + 1) * (c + 1).
+        let images = 2;
+        let input: Vec<f32> = (0..images)
+            .flat_map(|i| (0..3).flat_map(move |c| vec![((i + 1) * (c + 1)) as f32; 64]))
+            .collect();
+        let bytes: Vec<u8> = input.iter().flat_map(|x| x.to_ne_bytes()).collect();
+        let result = run_inference_blob_impl(name, bytes.as_ptr(), bytes.len(), None).unwrap();
+        assert_eq!((result.rows, result.cols), (images, 3));
+        let data = unsafe { std::slice::from_raw_parts(result.data, result.len) }.to_vec();
+        unsafe { crate::ffi_utils::infera_free_result(result) };
+        assert_eq!(data, vec![1.0, 2.0, 3.0, 2.0, 4.0, 6.0]);
+
+        // A partial image cannot fill the [N, 3, 8, 8] shape.
+        let partial = &bytes[..bytes.len() - 4];
+        assert!(matches!(
+            run_inference_blob_impl(name, partial.as_ptr(), partial.len(), None),
+            Err(InferaError::BlobShapeMismatch {
+                expected: 192,
+                actual: 383
+            })
+        ));
+
+        // With an explicit batch, two images are not accepted as one, or the other way around.
+        assert!(matches!(
+            run_inference_blob_impl(name, bytes.as_ptr(), bytes.len(), Some(1)),
+            Err(InferaError::BlobShapeMismatch {
+                expected: 192,
+                actual: 384
+            })
+        ));
+        let result = run_inference_blob_impl(name, bytes.as_ptr(), bytes.len(), Some(2)).unwrap();
+        assert_eq!((result.rows, result.cols), (images, 3));
+        unsafe { crate::ffi_utils::infera_free_result(result) };
+
+        assert!(check_shape_len(&[2, 3, 8, 8], 384).is_ok());
+        assert!(matches!(
+            check_shape_len(&[2, 3, 8, 8], 383),
+            Err(InferaError::BlobShapeMismatch {
+                expected: 384,
+                actual: 383
+            })
+        ));
+
+        MODELS.write().remove(name);
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_oversized_inputs_rejected_before_reading() {
+        // The pointers are never dereferenced because the size check comes first.
+        let dangling = std::ptr::NonNull::<u8>::dangling().as_ptr();
+        let over_limit = CONFIG.max_blob_bytes + 1;
+        match run_inference_blob_impl("__missing_model__", dangling, over_limit, None) {
+            Err(InferaError::InputTooLarge { limit, actual }) => {
+                assert_eq!(limit, CONFIG.max_blob_bytes);
+                assert_eq!(actual, over_limit);
+            }
+            Err(e) => panic!("expected InputTooLarge, got {}", e),
+            Ok(_) => panic!("expected InputTooLarge"),
+        }
+
+        let dangling = std::ptr::NonNull::<f32>::dangling().as_ptr();
+        for (rows, cols) in [(over_limit / 4 + 1, 1), (usize::MAX / 2, 3)] {
+            assert!(matches!(
+                run_inference_impl("__missing_model__", dangling, rows, cols),
+                Err(InferaError::InputTooLarge { .. })
+            ));
+        }
+        assert!(check_input_size(1024, 3, 4).is_ok());
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_check_finite() {
+        assert!(check_finite(&[0.0, 1.5, -2.0]).is_ok());
+        assert!(check_finite(&[]).is_ok());
+        match check_finite(&[1.0, 2.0, f32::NAN, f32::INFINITY]) {
+            Err(InferaError::NonFiniteInput { index }) => assert_eq!(index, 2),
+            other => panic!("expected NonFiniteInput, got {:?}", other),
+        }
+        assert!(matches!(
+            check_finite(&[f32::NEG_INFINITY]),
+            Err(InferaError::NonFiniteInput { index: 0 })
+        ));
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_model_metadata_props() {
+        let name = "engine_model_metadata";
+        load_model_impl(name, "../test/models/with_metadata.onnx").unwrap();
+        let info: serde_json::Value =
+            serde_json::from_str(&get_model_metadata_impl(name).unwrap()).unwrap();
+        let metadata = &info["model_metadata"];
+        assert_eq!(metadata["author"], "Infera Authors");
+        assert_eq!(metadata["description"], "Linear model with metadata props");
+        assert_eq!(metadata["version"], "1.2.0");
+        MODELS.write().remove(name);
+
+        // Models without metadata props report an empty object
+        load_model_impl(name, "../test/models/linear.onnx").unwrap();
+        let info: serde_json::Value =
+            serde_json::from_str(&get_model_metadata_impl(name).unwrap()).unwrap();
+        assert_eq!(info["model_metadata"], json!({}));
+        MODELS.write().remove(name);
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_load_retries_transient_read_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("flaky.onnx");
+        std::fs::copy("../test/models/linear.onnx", &path).unwrap();
+        let path = path.to_str().unwrap();
+        let name = "engine_load_retries";
+        let retries = |n| LoadOptions {
+            load_retries: Some(n),
+            ..LoadOptions::default()
+        };
+
+        // Two failed reads are covered by three retries
+        fail_reads(path, 2);
+        load_model_with_options_impl(name, path, retries(3)).unwrap();
+        assert!(MODELS.read().contains_key(name));
+        MODELS.write().remove(name);
+
+        // Running out of retries reports the attempt the load failed on
+        fail_reads(path, 2);
+        let err = load_model_with_options_impl(name, path, retries(1)).unwrap_err();
+        assert!(matches!(err.root(), InferaError::IoError(_)));
+        assert_eq!(err.attempts(), 2);
+        assert!(err.to_string().contains("(attempt 2 of 2)"), "{}", err);
+        fail_reads(path, 0);
+
+        // A file that reads fine but fails to parse is not retried
+        let garbage = dir.path().join("garbage.onnx");
+        std::fs::write(&garbage, b"not an onnx model").unwrap();
+        let err =
+            load_model_with_options_impl(name, garbage.to_str().unwrap(), retries(3)).unwrap_err();
+        assert!(matches!(err.root(), InferaError::OnnxError(_)));
+        assert_eq!(err.attempts(), 1);
+
+        // A missing file may be about to be moved into place, so it is retried
+        let missing = dir.path().join("missing.onnx");
+        let err =
+            load_model_with_options_impl(name, missing.to_str().unwrap(), retries(1)).unwrap_err();
+        assert!(matches!(err.root(), InferaError::IoError(_)));
+        assert_eq!(err.attempts(), 2);
+        assert!(!MODELS.read().contains_key(name));
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_load_retries_files_still_being_written() {
+        let dir = tempfile::tempdir().unwrap();
+        let bytes = std::fs::read("../test/models/linear.onnx").unwrap();
+        let retries = LoadOptions {
+            load_retries: Some(3),
+            ..LoadOptions::default()
+        };
+        // Writes the whole model to `path` shortly after the first load attempt
+        let deploy = |path: std::path::PathBuf| {
+            let bytes = bytes.clone();
+            let staging = path.with_extension("partial");
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(CONFIG.load_retry_delay_ms / 4));
+                std::fs::write(&staging, bytes).unwrap();
+                std::fs::rename(&staging, &path).unwrap();
+            })
+        };
+
+        // The file appears after the first attempt
+        let name = "engine_load_appearing";
+        let path = dir.path().join("appearing.onnx");
+        let writer = deploy(path.clone());
+        load_model_with_options_impl(name, path.to_str().unwrap(), retries.clone()).unwrap();
+        writer.join().unwrap();
+        assert!(MODELS.read().contains_key(name));
+        MODELS.write().remove(name);
+
+        // The first attempt reads a partially written file that fails to parse
+        let name = "engine_load_partial";
+        let path = dir.path().join("partial.onnx");
+        std::fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+        let writer = deploy(path.clone());
+        load_model_with_options_impl(name, path.to_str().unwrap(), retries).unwrap();
+        writer.join().unwrap();
+        assert!(MODELS.read().contains_key(name));
+        MODELS.write().remove(name);
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_symbolic_dimensions_bound_at_predict_time() {
+        let name = "engine_symbolic_sequence";
+        load_model_impl(name, "../test/models/symbolic_sequence.onnx").unwrap();
+        let model = get_model(name).unwrap();
+        assert_eq!(model.input_shape, Some(vec![-1, -1, 2]));
+        assert_eq!(
+            model.input_symbols,
+            vec![Some("N".to_string()), Some("S".to_string()), None]
+        );
+
+        // Two sequences of length 3 with 2 features each; the model sums over the sequence
+        let input: Vec<f32> = (1..=12).map(|v| v as f32).collect();
+        let run =
+            |symbols: &str| run_inference_with_symbols_impl(name, input.as_ptr(), 2, 6, symbols);
+        for symbols in [r#"{"S": 3}"#, r#"{"S": 3, "N": 2}"#] {
+            let result = run(symbols).unwrap();
+            assert_eq!((result.rows, result.cols), (2, 2));
+            let output = unsafe { std::slice::from_raw_parts(result.data, result.len) }.to_vec();
+            unsafe { crate::ffi_utils::infera_free_result(result) };
+            assert_eq!(output, vec![9.0, 12.0, 27.0, 30.0]);
+        }
+
+        // Without S, both N and S are unknown and the shape cannot be resolved
+        assert!(matches!(run(""), Err(InferaError::AmbiguousShape { .. })));
+        // S = 4 does not divide the 12 input values into sequences
+        assert!(matches!(
+            run(r#"{"S": 4}"#),
+            Err(InferaError::BlobShapeMismatch { .. })
+        ));
+        // S = 2 resolves to [3, 2, 2], which does not match the 2 x 6 input
+        assert!(matches!(
+            run(r#"{"S": 2}"#),
+            Err(InferaError::InvalidInputShape { .. })
+        ));
+        assert!(matches!(
+            run(r#"{"T": 3}"#),
+            Err(InferaError::UnknownSymbol { .. })
+        ));
+        assert!(matches!(run(r#"{"S": 0}"#), Err(InferaError::JsonError(_))));
+        MODELS.write().remove(name);
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_unknown_shapes_do_not_block_loading_or_inference() {
+        let (shape, dtype) = fact_shape_and_dtype(Err(format_err!("no fact")), "m", "output");
+        assert_eq!(shape, None);
+        assert_eq!(dtype, DatumType::F32);
+
+        // The output of this model is declared without a shape, so its rank is dynamic
+        let name = "engine_dynamic_rank_output";
+        load_model_impl(name, "../test/models/dynamic_rank_output.onnx").unwrap();
+        let input = [1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let result = run_inference_impl(name, input.as_ptr(), 2, 3).unwrap();
+        assert_eq!((result.rows, result.cols), (2, 3));
+        let output = unsafe { std::slice::from_raw_parts(result.data, result.len) }.to_vec();
+        unsafe { crate::ffi_utils::infera_free_result(result) };
+        assert_eq!(output, input);
+
+        // Shapes that could not be determined are reported as unknown, and inference
+        // still runs without them
+        if let Some(model) = MODELS.write().get_mut(name) {
+            let model = Arc::make_mut(model);
+            model.input_shape = None;
+            model.output_shape = None;
+        }
+        let info: serde_json::Value =
+            serde_json::from_str(&get_model_metadata_impl(name).unwrap()).unwrap();
+        assert_eq!(info["input_shape"], serde_json::Value::Null);
+        assert_eq!(info["output_shape"], serde_json::Value::Null);
+        assert_eq!(info["input_dtype"], "unknown");
+        assert_eq!(info["output_dtype"], "unknown");
+        let result = run_inference_impl(name, input.as_ptr(), 2, 3).unwrap();
+        assert_eq!((result.rows, result.cols), (2, 3));
+        unsafe { crate::ffi_utils::infera_free_result(result) };
+        MODELS.write().remove(name);
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_concurrent_inference_on_shared_model() {
+        let name = "engine_concurrent_shared";
+        load_model_impl(name, "../test/models/dynamic_batch.onnx").unwrap();
+        let workers: Vec<_> = (0..8)
+            .map(|t| {
+                std::thread::spawn(move || {
+                    for i in 0..200 {
+                        let x = (t * 200 + i) as f32 / 100.0;
+                        let input = [x, 1.0, 2.0];
+                        let result = run_inference_impl(name, input.as_ptr(), 1, 3).unwrap();
+                        let output = unsafe { *result.data };
+                        unsafe { crate::ffi_utils::infera_free_result(result) };
+                        // y = 2*x1 - x2 + 0.5*x3 + 0.25
+                        assert!((output - (2.0 * x + 0.25)).abs() < 1e-3, "{} {}", x, output);
+                    }
+                })
+            })
+            .collect();
+
+        // Loads and unloads take the write lock while the predictions are running
+        for i in 0..10 {
+            let other = format!("engine_concurrent_other_{}", i);
+            load_model_impl(&other, "../test/models/linear.onnx").unwrap();
+            MODELS.write().remove(&other);
+        }
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        // A handle taken before the model is unloaded keeps it usable
+        let handle = get_model(name).unwrap();
+        MODELS.write().remove(name);
+        assert!(matches!(
+            get_model(name),
+            Err(InferaError::ModelNotFound(_))
+        ));
+        let input = [1.0f32, 2.0, 3.0];
+        let output = infer_rows(&handle, &input, 1, 3).unwrap().0;
+        assert!((output[0] - 1.75).abs() < 1e-5);
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_f16_model_matches_f32_model() {
+        let f32_name = "engine_linear_f32";
+        let f16_name = "engine_linear_f16";
+        load_model_impl(f32_name, "../test/models/dynamic_batch.onnx").unwrap();
+        load_model_impl(f16_name, "../test/models/dynamic_batch_fp16.onnx").unwrap();
+
+        let info: serde_json::Value =
+            serde_json::from_str(&get_model_metadata_impl(f16_name).unwrap()).unwrap();
+        assert_eq!(info["input_dtype"], "f16");
+        assert_eq!(info["output_dtype"], "f16");
+        let info: serde_json::Value =
+            serde_json::from_str(&get_model_metadata_impl(f32_name).unwrap()).unwrap();
+        assert_eq!(info["input_dtype"], "f32");
+
+        let rows = 64;
+        let input = dynamic_batch_input(rows)
+            .iter()
+            .map(|x| x / 8.0)
+            .collect::<Vec<f32>>();
+        let expected = run_inference_impl(f32_name, input.as_ptr(), rows, 3).unwrap();
+        let actual = run_inference_impl(f16_name, input.as_ptr(), rows, 3).unwrap();
+        assert_eq!((actual.rows, actual.cols), (rows, 1));
+        let expected_data = unsafe { std::slice::from_raw_parts(expected.data, expected.len) };
+        let actual_data = unsafe { std::slice::from_raw_parts(actual.data, actual.len) };
+        for (e, a) in expected_data.iter().zip(actual_data) {
+            assert!((e - a).abs() <= 1e-2 * e.abs().max(1.0), "{} vs {}", e, a);
+        }
+        unsafe {
+            crate::ffi_utils::infera_free_result(expected);
+            crate::ffi_utils::infera_free_result(actual);
+        }
+
+        MODELS.write().remove(f32_name);
+        MODELS.write().remove(f16_name);
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_f16_input_matches_f32_input() {
+        let f32_name = "engine_f16_input_linear_f32";
+        let f16_name = "engine_f16_input_linear_f16";
+        load_model_impl(f32_name, "../test/models/dynamic_batch.onnx").unwrap();
+        load_model_impl(f16_name, "../test/models/dynamic_batch_fp16.onnx").unwrap();
+
+        // Multiples of 1/8 in this range are exact in f16, so both paths see the same input.
+        let rows = 16;
+        let input = dynamic_batch_input(rows)
+            .iter()
+            .map(|x| x / 8.0)
+            .collect::<Vec<f32>>();
+        let bits: Vec<u16> = input
+            .iter()
+            .map(|&x| half::f16::from_f32(x).to_bits())
+            .collect();
+        let expected = run_inference_impl(f32_name, input.as_ptr(), rows, 3).unwrap();
+        let expected_data =
+            unsafe { std::slice::from_raw_parts(expected.data, expected.len) }.to_vec();
+        unsafe { crate::ffi_utils::infera_free_result(expected) };
+
+        // f16 input on an f32 model is widened exactly, and on an f16 model it is fed natively.
+        for (name, tolerance) in [(f32_name, 0.0), (f16_name, 1e-2)] {
+            let actual = run_inference_f16_impl(name, bits.as_ptr(), rows, 3).unwrap();
+            assert_eq!((actual.rows, actual.cols), (rows, 1));
+            let actual_data = unsafe { std::slice::from_raw_parts(actual.data, actual.len) };
+            for (e, a) in expected_data.iter().zip(actual_data) {
+                assert!(
+                    (e - a).abs() <= tolerance * e.abs().max(1.0),
+                    "{}: {} vs {}",
+                    name,
+                    e,
+                    a
+                );
+            }
+            unsafe { crate::ffi_utils::infera_free_result(actual) };
+        }
+
+        MODELS.write().remove(f32_name);
+        MODELS.write().remove(f16_name);
+    }
+
+    #[cfg(feature = "tract")]
+    fn dynamic_batch_input(rows: usize) -> Vec<f32> {
+        (0..rows * 3)
+            .map(|i| (i % 97) as f32 * 0.37 - 11.0)
+            .collect()
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_pooled_outputs_are_copied_without_allocating() {
+        let name = "engine_pooled_dynamic";
+        load_model_impl(name, "../test/models/dynamic_batch.onnx").unwrap();
+        let model = get_model(name).unwrap();
+        let pool = ResultPool::new(4);
+
+        // 257 rows -> a 257 x 1 output of 1028 bytes, run on the plain path
+        let rows = 257;
+        let input = dynamic_batch_input(rows);
+        let bytes = rows * std::mem::size_of::<f32>();
+        let run_vec = || infer_rows_in(&model, &input, rows, 3, &VecAlloc, None).unwrap();
+        let run_pooled = || infer_rows_in(&model, &input, rows, 3, &pool, None).unwrap();
+        pool.put(run_pooled().0);
+        let vec_allocs = count_allocs_of(bytes, run_vec);
+        let pooled_allocs = count_allocs_of(bytes, || pool.put(run_pooled().0));
+        assert_eq!(pooled_allocs + 1, vec_allocs);
+
+        // The same rows split into chunks write into one pooled buffer
+        let run_chunks = || run_chunked_batches(&model, &input, rows, 3, 100, &pool, None).unwrap();
+        pool.put(run_chunks().0);
+        let pooled_allocs = count_allocs_of(bytes, || pool.put(run_chunks().0));
+        assert_eq!(pooled_allocs, 0);
+        MODELS.write().remove(name);
+
+        // 10 rows through a fixed batch of 4 -> a 10 x 1 output of 40 bytes
+        let name = "engine_pooled_padded";
+        let options = LoadOptions::from_json(r#"{"pad_batch": true}"#).unwrap();
+        load_model_with_options_impl(name, "../test/models/fixed_batch.onnx", options).unwrap();
+        let model = get_model(name).unwrap();
+        let rows = 10;
+        let input = dynamic_batch_input(rows);
+        let bytes = rows * std::mem::size_of::<f32>();
+        pool.put(
+            infer_rows_in(&model, &input, rows, 3, &pool, None)
+                .unwrap()
+                .0,
+        );
+        let vec_allocs = count_allocs_of(bytes, || {
+            infer_rows_in(&model, &input, rows, 3, &VecAlloc, None).unwrap()
+        });
+        let pooled_allocs = count_allocs_of(bytes, || {
+            pool.put(
+                infer_rows_in(&model, &input, rows, 3, &pool, None)
+                    .unwrap()
+                    .0,
+            )
+        });
+        assert_eq!(vec_allocs, 1);
+        assert_eq!(pooled_allocs, 0);
+        MODELS.write().remove(name);
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_chunked_inference_matches_unchunked() {
+        let name = "engine_chunking_dynamic";
+        load_model_impl(name, "../test/models/dynamic_batch.onnx").unwrap();
+        let model = get_model(name).unwrap();
+        assert!(supports_chunking(&model));
+
+        let rows = 1001;
+        let input = dynamic_batch_input(rows);
+        let tensor = Tensor::from_shape(&[rows, 3], &input).unwrap();
+        let (expected, _) = run_model(&model, tensor).unwrap();
+
+        for chunk_rows in [1, 7, 250, 1000, 5000] {
+            let (actual, actual_rows, actual_cols) =
+                run_chunked_batches(&model, &input, rows, 3, chunk_rows, &VecAlloc, None).unwrap();
+            assert_eq!(actual_rows, rows);
+            assert_eq!(actual_cols, 1);
+            let same_bits = expected
+                .iter()
+                .zip(&actual)
+                .all(|(a, b)| a.to_bits() == b.to_bits());
+            assert!(same_bits && actual.len() == expected.len());
+        }
+        MODELS.write().remove(name);
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_chunked_blob_matches_unchunked() {
+        let name = "engine_blob_chunking";
+        load_model_impl(name, "../test/models/dynamic_batch.onnx").unwrap();
+        let model = get_model(name).unwrap();
+
+        let rows = 1001;
+        let bytes: Vec<u8> = dynamic_batch_input(rows)
+            .iter()
+            .flat_map(|v| v.to_ne_bytes())
+            .collect();
+        let shape = [rows, 3];
+        let no_deadline = |_: usize, _: usize| Ok(());
+        assert_eq!(blob_chunk_rows(&model, &shape, 0), 0);
+        assert_eq!(blob_chunk_rows(&model, &shape, rows), 0);
+        assert_eq!(blob_chunk_rows(&model, &shape, 250), 250);
+        let (expected, expected_shape) =
+            run_blob_chunks(&model, &bytes, &shape, 0, &no_deadline).unwrap();
+
+        for chunk_rows in [1, 7, 250, 1000] {
+            let (actual, actual_shape) =
+                run_blob_chunks(&model, &bytes, &shape, chunk_rows, &no_deadline).unwrap();
+            assert_eq!(actual_shape, expected_shape);
+            let same_bits = expected
+                .iter()
+                .zip(&actual)
+                .all(|(a, b)| a.to_bits() == b.to_bits());
+            assert!(same_bits && actual.len() == expected.len());
+        }
+        MODELS.write().remove(name);
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_blob_deadline_stops_at_chunk_boundary() {
+        let name = "engine_blob_deadline";
+        load_model_impl(name, "../test/models/dynamic_batch.onnx").unwrap();
+        let model = get_model(name).unwrap();
+        let rows = 1000;
+        let bytes: Vec<u8> = dynamic_batch_input(rows)
+            .iter()
+            .flat_map(|v| v.to_ne_bytes())
+            .collect();
+
+        // A deadline that passes after two of the ten chunks have started
+        let expire_at_third = |chunk: usize, chunks: usize| {
+            if chunk < 2 {
+                return Ok(());
+            }
+            Err(InferaError::InferenceDeadlineExceeded {
+                timeout_ms: 1,
+                chunk: chunk + 1,
+                chunks,
+            })
+        };
+        let err = run_blob_chunks(&model, &bytes, &[rows, 3], 100, &expire_at_third).unwrap_err();
+        assert_eq!(err.status(), InferaStatus::Timeout);
+        assert!(err.to_string().contains("of 10"), "{}", err);
+
+        // A deadline that has passed stops the call before the first chunk
+        let err = Deadline::after(0).check(0, 3).unwrap_err();
+        assert!(
+            err.to_string().contains("stopped at chunk 1 of 3"),
+            "{}",
+            err
+        );
+        assert!(Deadline::after(60_000).check(0, 3).is_ok());
+        assert!(Deadline::after(u64::MAX).check(0, 3).is_ok());
+
+        // No partial output is returned with the error
+        let result = InferaInferenceResult::from_error(&err);
+        assert!(result.data.is_null());
+        assert_eq!(result.len, 0);
+        MODELS.write().remove(name);
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_seeded_inference_is_reproducible() {
+        let name = "engine_random_noise";
+        load_model_impl(name, "../test/models/random_noise.onnx").unwrap();
+        let input = [0.0f32; 6];
+        let run = |seed: Option<u64>| {
+            let result = match seed {
+                Some(seed) => run_inference_seeded_impl(name, input.as_ptr(), 2, 3, seed),
+                None => run_inference_impl(name, input.as_ptr(), 2, 3),
+            }
+            .unwrap();
+            let output = unsafe { std::slice::from_raw_parts(result.data, result.len) }.to_vec();
+            unsafe { crate::ffi_utils::infera_free_result(result) };
+            output
+        };
+
+        let seeded = run(Some(42));
+        assert_eq!(seeded.len(), 6);
+        assert_eq!(run(Some(42)), seeded);
+        assert_ne!(run(Some(7)), seeded);
+        // Each seed's plan is compiled once and then reused
+        let model = get_model(name).unwrap();
+        let first = seeded_model(&model, 42).unwrap();
+        assert!(!Arc::ptr_eq(&first.model, &model.model));
+        assert!(Arc::ptr_eq(
+            &seeded_model(&model, 42).unwrap().model,
+            &first.model
+        ));
+        assert_eq!(model.seeded_plans.lock().plans.len(), 2);
+        // Without a seed, the noise is drawn afresh on every run
+        assert_ne!(run(None), run(None));
+        MODELS.write().remove(name);
+
+        // Models without random ops give the same output with or without a seed
+        let name = "engine_seeded_deterministic";
+        load_model_impl(name, "../test/models/dynamic_batch.onnx").unwrap();
+        let input = dynamic_batch_input(4);
+        let seeded = run_inference_seeded_impl(name, input.as_ptr(), 4, 3, 42).unwrap();
+        let unseeded = run_inference_impl(name, input.as_ptr(), 4, 3).unwrap();
+        let model = get_model(name).unwrap();
+        assert_eq!(model.seeded_plans.lock().has_random_ops, Some(false));
+        assert!(Arc::ptr_eq(
+            &seeded_model(&model, 7).unwrap().model,
+            &model.model
+        ));
+        unsafe {
+            assert_eq!(
+                std::slice::from_raw_parts(seeded.data, seeded.len),
+                std::slice::from_raw_parts(unseeded.data, unseeded.len)
+            );
+            crate::ffi_utils::infera_free_result(seeded);
+            crate::ffi_utils::infera_free_result(unseeded);
+        }
+        MODELS.write().remove(name);
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_chunking_skipped_for_fixed_batch_models() {
+        let name = "engine_chunking_fixed";
+        load_model_impl(name, "../test/models/fixed_batch.onnx").unwrap();
+        assert!(!supports_chunking(&get_model(name).unwrap()));
+        MODELS.write().remove(name);
+    }
+
+    /// Checks that chunked inference on a large batch is faster than unchunked inference
+    /// when it has more than one thread to run chunks on.
+    ///
+    /// Run with `cargo test --release -- --ignored --nocapture bench_chunked_inference`.
+    #[cfg(feature = "tract")]
+    #[test]
+    #[ignore]
+    fn bench_chunked_inference() {
+        let name = "engine_chunking_bench";
+        load_model_impl(name, "../test/models/dynamic_batch.onnx").unwrap();
+        let model = get_model(name).unwrap();
+        let rows = 1_000_000;
+        let input = dynamic_batch_input(rows);
+        let unchunked_run = || {
+            let tensor = Tensor::from_shape(&[rows, 3], &input).unwrap();
+            run_model(&model, tensor).unwrap()
+        };
+        let chunked_run =
+            || run_chunked_batches(&model, &input, rows, 3, 65536, &VecAlloc, None).unwrap();
+        // Warm up the plan and the chunk thread pool so neither run pays for them
+        chunked_run();
+
+        let start = std::time::Instant::now();
+        unchunked_run();
+        let unchunked = start.elapsed();
+
+        let start = std::time::Instant::now();
+        chunked_run();
+        let chunked = start.elapsed();
+
+        let threads = CONFIG.intra_op_threads.min(
+            std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1),
+        );
+        println!(
+            "rows={} threads={} unchunked={:?} chunked={:?}",
+            rows, threads, unchunked, chunked
+        );
+        if threads > 1 {
+            assert!(
+                chunked < unchunked,
+                "chunked inference on {} threads took {:?}, unchunked took {:?}",
+                threads,
+                chunked,
+                unchunked
+            );
+        }
+        MODELS.write().remove(name);
+    }
 }
@@ -0,0 +1,185 @@
+// Runs several loaded models on the same input and averages their outputs.
+
+use crate::error::InferaError;
+use crate::ffi_utils::InferaInferenceResult;
+#[cfg(feature = "tract")]
+use crate::model::get_model;
+use serde_json::Value;
+
+/// Parses the model names of an ensemble from a JSON array of strings.
+pub(crate) fn parse_names(json: &str) -> Result<Vec<String>, InferaError> {
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| InferaError::JsonError(e.to_string()))?;
+    let items = value.as_array().filter(|a| !a.is_empty()).ok_or_else(|| {
+        InferaError::JsonError("ensemble names must be a non-empty JSON array".to_string())
+    })?;
+    items
+        .iter()
+        .map(|v| {
+            v.as_str()
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .ok_or_else(|| {
+                    InferaError::JsonError("ensemble names must be non-empty strings".to_string())
+                })
+        })
+        .collect()
+}
+
+/// Parses ensemble weights from a JSON array with one non-negative number per model and
+/// normalizes them to sum to 1.
+///
+/// An empty string gives every model the same weight.
+pub(crate) fn parse_weights(json: &str, count: usize) -> Result<Vec<f64>, InferaError> {
+    if json.trim().is_empty() {
+        return Ok(vec![1.0 / count as f64; count]);
+    }
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| InferaError::JsonError(e.to_string()))?;
+    let items = value.as_array().ok_or_else(|| {
+        InferaError::JsonError("ensemble weights must be a JSON array".to_string())
+    })?;
+    if items.len() != count {
+        return Err(InferaError::JsonError(format!(
+            "expected {} ensemble weights, got {}",
+            count,
+            items.len()
+        )));
+    }
+    let weights = items
+        .iter()
+        .map(|v| {
+            v.as_f64()
+                .filter(|w| w.is_finite() && *w >= 0.0)
+                .ok_or_else(|| {
+                    InferaError::JsonError(
+                        "ensemble weights must be non-negative numbers".to_string(),
+                    )
+                })
+        })
+        .collect::<Result<Vec<f64>, InferaError>>()?;
+    let total: f64 = weights.iter().sum();
+    if total <= 0.0 {
+        return Err(InferaError::JsonError(
+            "ensemble weights must not all be zero".to_string(),
+        ));
+    }
+    Ok(weights.iter().map(|w| w / total).collect())
+}
+
+/// Returns the element-wise weighted sum of equally sized outputs.
+#[cfg(feature = "tract")]
+fn weighted_average(outputs: &[Vec<f32>], weights: &[f64]) -> Vec<f32> {
+    let len = outputs.first().map_or(0, Vec::len);
+    let mut sum = vec![0.0f64; len];
+    for (output, &weight) in outputs.iter().zip(weights) {
+        for (acc, &value) in sum.iter_mut().zip(output) {
+            *acc += weight * value as f64;
+        }
+    }
+    sum.into_iter().map(|v| v as f32).collect()
+}
+
+/// Runs each named model on the same `rows x cols` input and returns the weighted
+/// element-wise average of their outputs.
+///
+/// # Arguments
+///
+/// * `names` - The names of the loaded models in the ensemble.
+/// * `data` - A pointer to the raw f32 tensor data.
+/// * `rows` - The number of rows in the input tensor.
+/// * `cols` - The number of columns in the input tensor.
+/// * `weights` - One normalized weight per model.
+///
+/// # Returns
+///
+/// * `Ok(InferaInferenceResult)` containing the averaged output.
+/// * `Err(InferaError)` if a model is not found, inference fails, or the output shapes differ.
+#[cfg(feature = "tract")]
+pub(crate) fn predict_ensemble_impl(
+    names: &[String],
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+    weights: &[f64],
+) -> Result<InferaInferenceResult, InferaError> {
+    crate::engine::check_nonempty(rows, cols)?;
+    crate::engine::check_input_size(rows, cols, std::mem::size_of::<f32>())?;
+
+    let mut outputs = Vec::with_capacity(names.len());
+    let mut output_shape: Option<(usize, usize)> = None;
+    for name in names {
+        let model = get_model(name)?;
+        let input_data = unsafe { crate::engine::checked_input(&model, data, rows, cols)? };
+        let (output, output_rows, output_cols) =
+            crate::engine::infer_rows(&model, input_data, rows, cols)?;
+        match output_shape {
+            Some((first_rows, first_cols))
+                if (first_rows, first_cols) != (output_rows, output_cols) =>
+            {
+                return Err(InferaError::IncompatibleModels(format!(
+                    "output of '{}' is {} x {} but output of '{}' is {} x {}",
+                    names[0], first_rows, first_cols, name, output_rows, output_cols
+                )));
+            }
+            Some(_) => {}
+            None => output_shape = Some((output_rows, output_cols)),
+        }
+        outputs.push(output);
+    }
+
+    let (output_rows, output_cols) = output_shape.unwrap_or((0, 0));
+    Ok(InferaInferenceResult::from_vec(
+        weighted_average(&outputs, weights),
+        output_rows,
+        output_cols,
+    ))
+}
+
+/// A stub for `predict_ensemble_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn predict_ensemble_impl(
+    _names: &[String],
+    _data: *const f32,
+    _rows: usize,
+    _cols: usize,
+    _weights: &[f64],
+) -> Result<InferaInferenceResult, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_names() {
+        assert_eq!(parse_names(r#"["a", "b"]"#).unwrap(), vec!["a", "b"]);
+        assert!(parse_names("[]").is_err());
+        assert!(parse_names(r#"["a", ""]"#).is_err());
+        assert!(parse_names(r#"["a", 1]"#).is_err());
+        assert!(parse_names(r#"{"a": 1}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_weights() {
+        assert_eq!(parse_weights("", 4).unwrap(), vec![0.25; 4]);
+        assert_eq!(parse_weights("[1, 3]", 2).unwrap(), vec![0.25, 0.75]);
+        assert!(parse_weights("[1]", 2).is_err());
+        assert!(parse_weights("[1, -1]", 2).is_err());
+        assert!(parse_weights("[0, 0]", 2).is_err());
+        assert!(parse_weights(r#"["1", 1]"#, 2).is_err());
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_weighted_average() {
+        let outputs = vec![vec![1.0, 2.0], vec![3.0, 6.0]];
+        assert_eq!(weighted_average(&outputs, &[0.5, 0.5]), vec![2.0, 4.0]);
+        assert_eq!(weighted_average(&outputs, &[0.25, 0.75]), vec![2.5, 5.0]);
+    }
+}
@@ -1,8 +1,12 @@
 // Contains the InferaError enum and thread-local error handling logic.
 
+use parking_lot::Mutex;
+use serde_json::{json, Value};
 use std::cell::RefCell;
 use std::ffi::{c_char, CString};
+use std::fmt;
 use std::str::Utf8Error as StdUtf8Error;
+use std::sync::atomic::{AtomicBool, Ordering};
 use thiserror::Error;
 
 /// Represents all possible errors that can occur within the Infera library.
@@ -12,6 +16,49 @@ pub enum InferaError {
     /// Error indicating that a requested model could not be found.
     #[error("Model not found: {0}")]
     ModelNotFound(String),
+    /// Error for when no autoload group has the given ID.
+    #[error("Model group not found: {0}")]
+    GroupNotFound(u64),
+    /// Error for when a directory passed to `infera_watch_autoload_dir` is already watched.
+    #[error("Directory is already watched: {0}")]
+    AlreadyWatched(String),
+    /// Error for when a directory passed to `infera_unwatch_autoload_dir` is not watched.
+    #[error("Directory is not watched: {0}")]
+    NotWatched(String),
+    /// Error for when a directory watcher is started with a poll interval of zero.
+    #[error("Poll interval must be greater than zero")]
+    InvalidPollInterval,
+    /// Error for when a namespace is empty or contains the `/` separator.
+    #[error("Invalid namespace '{0}': namespaces must be non-empty and must not contain '/'")]
+    InvalidNamespace(String),
+    /// Error for when a model name passed with a namespace is empty or contains `/`.
+    #[error("Invalid model name '{0}': names must be non-empty and must not contain '/'")]
+    InvalidModelName(String),
+    /// Error for when a model is rolled back but has no previous version.
+    #[error("Model '{0}' has no previous version to roll back to")]
+    NoPreviousVersion(String),
+    /// Error for when a model is renamed to a name that is already loaded.
+    #[error("A model named '{0}' is already loaded")]
+    ModelAlreadyExists(String),
+    /// Error for when a canary alias is looked up but was never set.
+    #[error("Canary not found: {0}")]
+    CanaryNotFound(String),
+    /// Error for when a canary is set with an invalid policy or models.
+    #[error("Invalid canary: {0}")]
+    InvalidCanary(String),
+    /// Error for when `infera_predict_features` is called on a model without feature names.
+    #[error("Model '{0}' has no feature names: set them with the \"feature_names\" load option, a <model>.features.json file, or \"feature_names\" metadata")]
+    NoFeatureNames(String),
+    /// Error for when a feature map is missing a feature, has an unknown or non-numeric
+    /// feature, or is not a JSON object.
+    #[error("Invalid features: {0}")]
+    InvalidFeatures(String),
+    /// Error for when a postprocessing spec has an unknown or invalid step.
+    #[error("Invalid postprocessor: {0}")]
+    InvalidPostprocessor(String),
+    /// Error for when a streaming session handle is not open (never begun, or already ended).
+    #[error("Stream {0} is not open (it may never have begun or may have ended)")]
+    StreamNotFound(i64),
     /// Error for when the provided input tensor shape does not match the model's expected shape.
     #[error("Invalid input shape: expected {expected}, got {actual}")]
     InvalidInputShape {
@@ -44,12 +91,249 @@ pub enum InferaError {
     /// An error that occurred during an HTTP request to fetch a remote model.
     #[error("HTTP request failed: {0}")]
     HttpRequestError(String),
+    /// Error for when a server responds with a Content-Type that is not accepted in strict mode.
+    #[error("Unexpected Content-Type for model download: {0}")]
+    UnexpectedContentType(String),
+    /// Error for when a remote-only operation is used on a model loaded from a local file.
+    #[error("Model '{0}' was not loaded from a URL")]
+    NotRemoteModel(String),
+    /// Error for when a remote model's freshness cannot be checked without a stored ETag.
+    #[error("No ETag was recorded for '{0}', so changes to the remote model cannot be detected")]
+    MissingEtag(String),
+    /// Error for when a requested node name does not exist in the model graph.
+    #[error("Unknown node '{name}'. Available nodes: {available}")]
+    UnknownNode {
+        /// The node name that was requested.
+        name: String,
+        /// A (possibly truncated) list of the node names in the model.
+        available: String,
+    },
+    /// Error for when an input is given for a name that is not one of the model's inputs.
+    #[error("Unknown input '{name}'. Model inputs: {available}")]
+    UnknownInput {
+        /// The input name that was given.
+        name: String,
+        /// The names of the model's inputs.
+        available: String,
+    },
+    /// Error for when a value bound with the `"initializers"` load option does not fit its input.
+    #[error("Invalid initializer for input '{input}': {reason}")]
+    InvalidInitializer {
+        /// The name of the input the value was bound to.
+        input: String,
+        /// Why the value does not fit the input.
+        reason: String,
+    },
+    /// Error for when an output is selected by a name or index the model does not have.
+    #[error("Unknown output '{name}'. Model outputs: {available}")]
+    UnknownOutput {
+        /// The output name or index that was given.
+        name: String,
+        /// The names of the model's outputs, each with its index.
+        available: String,
+    },
+    /// Error for when a symbol value is given for a name that is not a symbolic input dimension.
+    #[error("Unknown symbol '{name}'. Symbolic input dimensions: {available}")]
+    UnknownSymbol {
+        /// The symbol name that was given.
+        name: String,
+        /// The names of the model's symbolic input dimensions.
+        available: String,
+    },
+    /// Error for when two models cannot be compared because their inputs or outputs differ.
+    #[error("Models are not compatible: {0}")]
+    IncompatibleModels(String),
+    /// Error for when input validation finds a NaN or infinite value.
+    #[error("Input contains a non-finite value (NaN or Inf) at index {index}")]
+    NonFiniteInput {
+        /// The flat index of the first non-finite value.
+        index: usize,
+    },
+    /// Error for when an entry point provides values of a type the model input cannot take.
+    #[error("Model input '{input}' expects {expected} values but was given {provided}; {}", dtype_hint(.expected))]
+    InputDtypeMismatch {
+        /// The name of the model input.
+        input: String,
+        /// The element type the model input expects (e.g., "i64").
+        expected: String,
+        /// The element type the entry point provides (e.g., "f32").
+        provided: String,
+    },
+    /// Error for when a model output is not f32 and INFERA_STRICT_OUTPUT_TYPES is set.
+    #[error("Model output has type {0}, not f32, and INFERA_STRICT_OUTPUT_TYPES is set")]
+    NonF32Output(String),
+    /// Error for when a model's graph declares no outputs.
+    #[error("Model '{0}' has no outputs")]
+    NoModelOutputs(String),
+    /// Error for when a model's input or output shape could not be determined at load time.
+    #[error("The {kind} shape of model '{model}' is not known")]
+    UnknownShape {
+        /// The name of the model.
+        model: String,
+        /// Either "input" or "output".
+        kind: &'static str,
+    },
+    /// Error for when the output size of a batch cannot be computed because the model's
+    /// output does not scale with its input batch.
+    #[error("The output size of model '{model}' for a batch of {rows} rows is not known: its output batch does not scale with its input batch")]
+    OutputSizeNotScalable {
+        /// The name of the model.
+        model: String,
+        /// The number of input rows the size was asked for.
+        rows: usize,
+    },
+    /// Error for when a caller-provided buffer cannot hold the result.
+    #[error("Buffer of {capacity} elements is too small: {needed} are needed")]
+    BufferTooSmall {
+        /// The number of elements needed.
+        needed: usize,
+        /// The number of elements the buffer can hold.
+        capacity: usize,
+    },
+    /// Error for when `infera_profile_model` is called with invalid arguments.
+    #[error("Invalid profiling request: {0}")]
+    InvalidProfile(String),
+    /// Error for when `infera_submit` is called while the request queue is full.
+    #[error("Request queue is full ({0} pending requests)")]
+    QueueFull(usize),
+    /// Error for when a request ID does not refer to a queued request.
+    #[error("Request {0} is not queued (it may be running, finished, or cancelled)")]
+    RequestNotFound(u64),
+    /// Error for when an invalid priority is passed to `infera_submit` or `infera_set_priority`.
+    #[error("Invalid priority {0}: expected 0 (high), 1 (normal), or 2 (low)")]
+    InvalidPriority(i32),
+    /// Error for when `infera_load_model_from_fd` is given a negative file descriptor.
+    #[error("Invalid file descriptor: {0}")]
+    InvalidFileDescriptor(i32),
+    /// Error for when `infera_set_error_mode` is given an unknown mode.
+    #[error("Invalid error mode {0}: expected 0 (thread-local) or 1 (global)")]
+    InvalidErrorMode(i32),
+    /// Error for when an input is larger than `INFERA_MAX_BLOB_BYTES`.
+    #[error("Input of {actual} bytes exceeds the maximum input size of {limit} bytes")]
+    InputTooLarge {
+        /// The configured maximum input size in bytes.
+        limit: usize,
+        /// The size of the rejected input in bytes.
+        actual: usize,
+    },
+    /// Error for when signed models are required and a model has no detached signature.
+    #[error("Model '{0}' has no signature file ('.sig') and INFERA_REQUIRE_SIGNED_MODELS is set")]
+    SignatureMissing(String),
+    /// Error for when a model's detached signature does not verify against any trusted key.
+    #[error("Signature verification failed for model '{0}'")]
+    SignatureInvalid(String),
+    /// Error for when a trusted public key cannot be parsed.
+    #[error("Invalid public key: {0}")]
+    InvalidKey(String),
+    /// Error for when an expected SHA-256 checksum is not 64 hexadecimal characters.
+    #[error("Invalid SHA-256 checksum '{0}': expected 64 hexadecimal characters")]
+    InvalidChecksum(String),
+    /// Error for when a model file's SHA-256 does not match the checksum the caller expected.
+    #[error("Checksum mismatch for '{path}': expected SHA-256 {expected}, got {actual}")]
+    ChecksumMismatch {
+        /// The path or URL of the model that was checked.
+        path: String,
+        /// The SHA-256 the caller expected, in lowercase hex.
+        expected: String,
+        /// The SHA-256 of the model file, in lowercase hex.
+        actual: String,
+    },
+    /// Error for when a remote model does not fit within the cache size limit or the free
+    /// disk space left after `INFERA_MIN_FREE_DISK_BYTES`.
+    #[error(
+        "Not enough space to download model: {needed} bytes needed, {available} bytes available"
+    )]
+    InsufficientDiskSpace {
+        /// The size of the model in bytes (or the bytes received so far, if the size was not reported).
+        needed: u64,
+        /// The space in bytes the download was allowed to use.
+        available: u64,
+    },
+    /// Error for when a remote model is larger than `INFERA_MAX_DOWNLOAD_BYTES`.
+    #[error("Download exceeds the maximum download size: {received} bytes received or reported, {limit} bytes allowed")]
+    DownloadTooLarge {
+        /// The size the server reported, or the bytes received when the body outgrew the limit.
+        received: u64,
+        /// The maximum download size in bytes.
+        limit: u64,
+    },
+    /// Error for when retrying a download would run past `INFERA_HTTP_TOTAL_DEADLINE_SECS`.
+    #[error("Download did not finish within the {deadline_secs}s deadline after {attempts} attempts: {last_error}")]
+    DownloadDeadlineExceeded {
+        /// The total deadline in seconds.
+        deadline_secs: u64,
+        /// The number of attempts made before the deadline.
+        attempts: u32,
+        /// The error of the last attempt.
+        last_error: String,
+    },
+    /// Error for when a prediction with a timeout reaches a chunk boundary after its
+    /// deadline.
+    #[error(
+        "Inference did not finish within {timeout_ms}ms; stopped at chunk {chunk} of {chunks}"
+    )]
+    InferenceDeadlineExceeded {
+        /// The timeout of the call in milliseconds.
+        timeout_ms: u64,
+        /// The chunk that was not started because the deadline had passed, counting from 1.
+        chunk: usize,
+        /// The number of chunks the input was split into.
+        chunks: usize,
+    },
+    /// Error for when a remote model is needed while offline (`INFERA_OFFLINE`) and it is
+    /// not in the cache.
+    #[error("Offline mode is enabled and the model is not cached: {0}")]
+    Offline(String),
+    /// Error for when a model is running its maximum number of concurrent predictions and
+    /// `INFERA_CONCURRENCY_BLOCK` is disabled.
+    #[error("Model '{model}' is already running its maximum of {max} concurrent predictions")]
+    ConcurrencyLimitReached {
+        /// The name of the model.
+        model: String,
+        /// The model's concurrency limit.
+        max: usize,
+    },
+    /// Error for when a model URL's host does not match any pattern in `INFERA_ALLOWED_HOSTS`.
+    #[error("Downloading models from this host is not allowed by INFERA_ALLOWED_HOSTS: {0}")]
+    HostNotAllowed(String),
+    /// Error for when a server redirects an HTTPS request to plain HTTP and
+    /// `INFERA_ALLOW_INSECURE_REDIRECTS` is not set.
+    #[error("Refusing redirect from HTTPS to plain HTTP: {from} -> {to}")]
+    InsecureRedirect {
+        /// The HTTPS URL that sent the redirect.
+        from: String,
+        /// The plain HTTP URL it redirected to.
+        to: String,
+    },
+    /// Error for when a model bundle (`.infera.zip`) lacks a file it must contain.
+    #[error("Model bundle '{bundle}' has no {file}")]
+    BundleFileMissing {
+        /// The path of the bundle.
+        bundle: String,
+        /// The name of the missing file (e.g., `model.onnx`).
+        file: String,
+    },
+    /// Error for when a model bundle is not a zip archive or one of its files is malformed.
+    #[error("Invalid model bundle '{bundle}': {reason}")]
+    InvalidBundle {
+        /// The path of the bundle.
+        bundle: String,
+        /// What is wrong with the bundle.
+        reason: String,
+    },
     /// Error for when the model cache directory cannot be created.
     #[error("Failed to create cache directory: {0}")]
     CacheDirError(String),
     /// Error for when `infera_predict_from_blob` receives a blob whose size is not a multiple of 4.
     #[error("Invalid BLOB size: length must be a multiple of 4")]
     InvalidBlobSize,
+    /// Error for when a blob's shape cannot be inferred because the model input has several dynamic dimensions.
+    #[error("Model input shape {shape:?} has more than one dynamic dimension, so it cannot be inferred from the BLOB size. Provide an explicit input shape instead."
+    )]
+    AmbiguousShape {
+        /// The model's input shape, with `-1` for dynamic dimensions.
+        shape: Vec<i64>,
+    },
     /// Error indicating a mismatch between the number of elements in a blob and the model's expected input tensor size.
     #[error("BLOB data does not match model's expected input shape. Expected {expected} elements, but BLOB contained {actual}."
     )]
@@ -59,6 +343,223 @@ pub enum InferaError {
         /// The actual number of elements found in the blob.
         actual: usize,
     },
+    /// Error for when image bytes cannot be decoded as a supported image format.
+    #[error("Failed to decode image: {0}")]
+    ImageDecodeError(String),
+    /// Error for when an image size, layout, or normalization argument is invalid.
+    #[error("Invalid image option: {0}")]
+    InvalidImageOption(String),
+    /// A lower-level error with the operation it happened in, added with
+    /// `InferaError::context`. Use `InferaError::root` to get the underlying error.
+    #[error("{context}: {source}")]
+    Context {
+        /// The operation that failed.
+        context: ErrorContext,
+        /// The error the operation failed with.
+        source: Box<InferaError>,
+    },
+}
+
+/// The operation an error happened in, such as downloading a URL or compiling a model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// A short name for the operation (e.g., `download`, `cache_evict`, `model_compile`).
+    pub operation: &'static str,
+    /// What the operation was working on, such as a model name, URL, or file path. Empty
+    /// if there is nothing more specific than the operation.
+    pub subject: String,
+    /// The attempt the operation failed on and the number of attempts allowed, for
+    /// operations that are retried.
+    pub attempt: Option<(u32, u32)>,
+}
+
+impl ErrorContext {
+    pub(crate) fn new(operation: &'static str, subject: impl Into<String>) -> Self {
+        Self {
+            operation,
+            subject: subject.into(),
+            attempt: None,
+        }
+    }
+
+    /// Records that the operation failed on `attempt` out of `attempts` attempts.
+    pub(crate) fn with_attempt(mut self, attempt: u32, attempts: u32) -> Self {
+        self.attempt = Some((attempt, attempts));
+        self
+    }
+
+    fn to_json(&self) -> Value {
+        let mut value = json!({ "operation": self.operation });
+        if !self.subject.is_empty() {
+            value["subject"] = json!(self.subject);
+        }
+        if let Some((attempt, attempts)) = self.attempt {
+            value["attempt"] = json!(attempt);
+            value["attempts"] = json!(attempts);
+        }
+        value
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.operation)?;
+        if !self.subject.is_empty() {
+            write!(f, " {}", self.subject)?;
+        }
+        if let Some((attempt, attempts)) = self.attempt {
+            write!(f, " (attempt {} of {})", attempt, attempts)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns a `map_err` adapter that turns a lower-level error into the `InferaError` made
+/// by `kind` from its message, with the operation and subject it happened in attached.
+///
+/// For example, `.map_err(context(InferaError::IoError, "cache_evict", &path))`.
+pub(crate) fn context<'a, E: fmt::Display>(
+    kind: fn(String) -> InferaError,
+    operation: &'static str,
+    subject: &'a str,
+) -> impl FnOnce(E) -> InferaError + 'a {
+    move |e| kind(e.to_string()).context(ErrorContext::new(operation, subject))
+}
+
+/// Returns how to feed a model input that expects `expected` values, for
+/// `InferaError::InputDtypeMismatch`.
+fn dtype_hint(expected: &str) -> &'static str {
+    match expected {
+        "u8" | "i8" => {
+            "use infera_predict_u8 or infera_predict_from_blob with one byte per element"
+        }
+        "i16" | "i32" | "i64" | "u16" | "u32" | "u64" => {
+            "use infera_predict_u8 for values below 256, or add a Cast node to the model so it takes f32 inputs"
+        }
+        _ => "Infera cannot provide values of this type, so add a Cast node to the model so it takes f32 inputs",
+    }
+}
+
+/// Status codes reported in the `status` field of `InferaInferenceResult`.
+///
+/// The values are part of the stable ABI. New codes may be added, but existing codes keep
+/// their values and meanings. Call `infera_last_error` for the detailed message.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InferaStatus {
+    /// The operation succeeded.
+    Ok = 0,
+    /// An error without a more specific status code.
+    Error = -1,
+    /// The named model is not loaded.
+    ModelNotFound = -2,
+    /// The input's size or shape does not match what the model expects.
+    InvalidShape = -3,
+    /// The inference backend failed to run the model.
+    BackendError = -4,
+    /// The operation did not finish in time, such as a prediction past its timeout.
+    Timeout = -5,
+    /// The request was rejected because Infera is at capacity.
+    Busy = -6,
+}
+
+/// Where `infera_last_error` and `infera_take_last_error` read the last error from, set
+/// with `infera_set_error_mode`.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InferaErrorMode {
+    /// The last error of the calling thread (the default).
+    ThreadLocal = 0,
+    /// The most recent error of any thread in the process.
+    Global = 1,
+}
+
+impl InferaErrorMode {
+    /// Parses the C API error mode value: `0` (thread-local) or `1` (global).
+    pub(crate) fn from_i32(value: i32) -> Result<Self, InferaError> {
+        match value {
+            0 => Ok(InferaErrorMode::ThreadLocal),
+            1 => Ok(InferaErrorMode::Global),
+            _ => Err(InferaError::InvalidErrorMode(value)),
+        }
+    }
+}
+
+impl InferaError {
+    /// Wraps this error with the operation it happened in.
+    pub(crate) fn context(self, context: ErrorContext) -> Self {
+        InferaError::Context {
+            context,
+            source: Box::new(self),
+        }
+    }
+
+    /// Returns the underlying error, without the operations added with `context`.
+    pub(crate) fn root(&self) -> &InferaError {
+        match self {
+            InferaError::Context { source, .. } => source.root(),
+            other => other,
+        }
+    }
+
+    /// Returns the operations this error happened in, outermost first.
+    pub(crate) fn contexts(&self) -> Vec<&ErrorContext> {
+        let mut contexts = Vec::new();
+        let mut error = self;
+        while let InferaError::Context { context, source } = error {
+            contexts.push(context);
+            error = source;
+        }
+        contexts
+    }
+
+    /// Returns the number of attempts made before this error, from the innermost
+    /// operation that records its attempt, or 1 if none does.
+    pub(crate) fn attempts(&self) -> u32 {
+        self.contexts()
+            .iter()
+            .rev()
+            .find_map(|context| context.attempt)
+            .map_or(1, |(attempt, _)| attempt)
+    }
+
+    /// Returns the error as a JSON object, `{"error": "..."}`, for functions that report
+    /// errors in their JSON output.
+    ///
+    /// Errors with context also have a `"context"` array with one object per operation,
+    /// outermost first, with its `"operation"` and, where known, its `"subject"` and
+    /// `"attempt"` out of `"attempts"`.
+    pub(crate) fn to_json(&self) -> Value {
+        let mut value = json!({ "error": self.to_string() });
+        let contexts = self.contexts();
+        if !contexts.is_empty() {
+            value["context"] = Value::from(
+                contexts
+                    .into_iter()
+                    .map(ErrorContext::to_json)
+                    .collect::<Vec<_>>(),
+            );
+        }
+        value
+    }
+
+    /// Returns the status code that classifies this error.
+    pub(crate) fn status(&self) -> InferaStatus {
+        match self {
+            InferaError::Context { source, .. } => source.status(),
+            InferaError::ModelNotFound(_) => InferaStatus::ModelNotFound,
+            InferaError::InvalidInputShape { .. }
+            | InferaError::InvalidBlobSize
+            | InferaError::AmbiguousShape { .. }
+            | InferaError::BlobShapeMismatch { .. } => InferaStatus::InvalidShape,
+            InferaError::OnnxError(_) => InferaStatus::BackendError,
+            InferaError::InferenceDeadlineExceeded { .. } => InferaStatus::Timeout,
+            InferaError::QueueFull(_) | InferaError::ConcurrencyLimitReached { .. } => {
+                InferaStatus::Busy
+            }
+            _ => InferaStatus::Error,
+        }
+    }
 }
 
 impl From<StdUtf8Error> for InferaError {
@@ -71,32 +572,280 @@ thread_local! {
     static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
 }
 
+/// The most recent error of any thread, recorded while the global error mode is on.
+struct GlobalError {
+    enabled: AtomicBool,
+    last: Mutex<Option<CString>>,
+}
+
+impl GlobalError {
+    const fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            last: Mutex::new(None),
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Acquire)
+    }
+
+    /// Turns the global mode on or off. Turning it off drops the recorded error.
+    fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Release);
+        if !enabled {
+            *self.last.lock() = None;
+        }
+    }
+
+    /// Records `message` as the most recent error if the global mode is on.
+    fn record(&self, message: &CString) {
+        if self.is_enabled() {
+            *self.last.lock() = Some(message.clone());
+        }
+    }
+
+    fn as_ptr(&self) -> *const c_char {
+        self.last
+            .lock()
+            .as_ref()
+            .map_or(std::ptr::null(), |c_string| c_string.as_ptr())
+    }
+
+    fn take(&self) -> Option<CString> {
+        self.last.lock().take()
+    }
+}
+
+static GLOBAL_ERROR: GlobalError = GlobalError::new();
+
 /// Sets the last error for the current thread.
 ///
 /// This stores the given error in a thread-local variable so it can be retrieved
-/// later by FFI clients using `infera_last_error`.
+/// later by FFI clients using `infera_last_error`. In the global error mode, it is also
+/// stored as the most recent error of the process.
 pub(crate) fn set_last_error(err: &InferaError) {
+    set_last_error_in(err, &GLOBAL_ERROR);
+}
+
+fn set_last_error_in(err: &InferaError, global: &GlobalError) {
     if let Ok(c_string) = CString::new(err.to_string()) {
+        global.record(&c_string);
         LAST_ERROR.with(|cell| {
             *cell.borrow_mut() = Some(c_string);
         });
     }
 }
 
-/// Retrieves the last error message set in the current thread.
+/// Retrieves the last error message.
 ///
 /// After an FFI function returns an error code, this function can be called
-/// to get a more descriptive, human-readable error message.
+/// to get a more descriptive, human-readable error message. By default this is the last
+/// error of the calling thread. In the global error mode (see `infera_set_error_mode`),
+/// it is the most recent error of any thread.
 ///
 /// # Returns
 ///
 /// A pointer to a null-terminated C string containing the last error message.
 /// Returns a null pointer if no error has occurred since the last call.
 /// The caller **must not** free this pointer, as it is managed by a thread-local static variable.
+/// In the global error mode, the pointer is only valid until the next error on any
+/// thread, so use `infera_take_last_error` when other threads may fail in the meantime.
 #[no_mangle]
 pub extern "C" fn infera_last_error() -> *const c_char {
+    if GLOBAL_ERROR.is_enabled() {
+        return GLOBAL_ERROR.as_ptr();
+    }
     LAST_ERROR.with(|cell| match *cell.borrow() {
         Some(ref c_string) => c_string.as_ptr(),
         None => std::ptr::null(),
     })
 }
+
+/// Removes the last error message and returns an owned copy of it.
+///
+/// The error is read from the same place as `infera_last_error`, but since the caller
+/// owns the returned string, it cannot be replaced by a later error before it is read.
+///
+/// # Returns
+///
+/// A pointer to a null-terminated C string containing the last error message, or a null
+/// pointer if no error has occurred since the last call. The caller is responsible for
+/// freeing this string using `infera_free_string`.
+#[no_mangle]
+pub extern "C" fn infera_take_last_error() -> *mut c_char {
+    take_last_error_in(&GLOBAL_ERROR).map_or(std::ptr::null_mut(), CString::into_raw)
+}
+
+fn take_last_error_in(global: &GlobalError) -> Option<CString> {
+    if global.is_enabled() {
+        return global.take();
+    }
+    LAST_ERROR.with(|cell| cell.borrow_mut().take())
+}
+
+/// Chooses where `infera_last_error` and `infera_take_last_error` read the last error
+/// from.
+///
+/// Errors are always recorded for the thread they occur on. In the global mode, the most
+/// recent error of any thread is recorded in a process-wide slot as well, and is what
+/// both functions return. This suits hosts that may resume a task on another OS thread
+/// between a failing call and reading its error. Switching back to the thread-local mode
+/// drops the process-wide error.
+///
+/// # Arguments
+///
+/// * `mode` - `0` (`InferaErrorMode_ThreadLocal`, the default) or `1`
+///   (`InferaErrorMode_Global`).
+///
+/// # Returns
+///
+/// * `0` on success.
+/// * `-1` if the mode is invalid. Call `infera_last_error()` to get a descriptive error
+///   message.
+///
+/// # Safety
+///
+/// This function is safe to call at any time.
+#[no_mangle]
+pub extern "C" fn infera_set_error_mode(mode: i32) -> i32 {
+    match InferaErrorMode::from_i32(mode) {
+        Ok(mode) => {
+            GLOBAL_ERROR.set_enabled(mode == InferaErrorMode::Global);
+            0
+        }
+        Err(e) => {
+            set_last_error(&e);
+            -1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_codes() {
+        assert_eq!(
+            InferaError::ModelNotFound("m".to_string()).status() as i32,
+            -2
+        );
+        assert_eq!(InferaError::InvalidBlobSize.status() as i32, -3);
+        assert_eq!(
+            InferaError::BlobShapeMismatch {
+                expected: 3,
+                actual: 4
+            }
+            .status() as i32,
+            -3
+        );
+        assert_eq!(InferaError::OnnxError("e".to_string()).status() as i32, -4);
+        assert_eq!(InferaError::QueueFull(8).status() as i32, -6);
+        assert_eq!(
+            InferaError::InferenceDeadlineExceeded {
+                timeout_ms: 10,
+                chunk: 2,
+                chunks: 4
+            }
+            .status() as i32,
+            -5
+        );
+        assert_eq!(InferaError::NullPointer.status() as i32, -1);
+        assert_eq!(InferaStatus::Timeout as i32, -5);
+    }
+
+    #[test]
+    fn test_error_context_chain() {
+        let err = InferaError::IoError("No such file or directory".to_string())
+            .context(ErrorContext::new("cache_write", "/tmp/model.onnx"))
+            .context(
+                ErrorContext::new("download", "https://example.com/model.onnx").with_attempt(2, 3),
+            );
+        assert_eq!(
+            err.to_string(),
+            "download https://example.com/model.onnx (attempt 2 of 3): cache_write \
+             /tmp/model.onnx: IO error: No such file or directory"
+        );
+        assert!(matches!(err.root(), InferaError::IoError(_)));
+        assert_eq!(err.status(), InferaStatus::Error);
+        assert_eq!(
+            err.to_json(),
+            json!({
+                "error": err.to_string(),
+                "context": [
+                    {
+                        "operation": "download",
+                        "subject": "https://example.com/model.onnx",
+                        "attempt": 2,
+                        "attempts": 3
+                    },
+                    { "operation": "cache_write", "subject": "/tmp/model.onnx" }
+                ]
+            })
+        );
+
+        let plain = InferaError::OnnxError("bad graph".to_string());
+        assert_eq!(plain.to_json(), json!({ "error": "ONNX error: bad graph" }));
+        let err = std::fs::read("/nonexistent/model.onnx")
+            .map_err(context(InferaError::IoError, "model_read", "m"))
+            .unwrap_err();
+        assert!(err.to_string().starts_with("model_read m: IO error: "));
+        assert_eq!(err.status(), InferaStatus::Error);
+        assert_eq!(
+            InferaError::OnnxError("e".to_string())
+                .context(ErrorContext::new("model_run", ""))
+                .status(),
+            InferaStatus::BackendError
+        );
+    }
+
+    #[test]
+    fn test_take_last_error_returns_owned_copy() {
+        set_last_error(&InferaError::ModelNotFound("take_me".to_string()));
+        let ptr = infera_take_last_error();
+        assert!(!ptr.is_null());
+        let message = unsafe { CString::from_raw(ptr) };
+        assert_eq!(message.to_str().unwrap(), "Model not found: take_me");
+        // Taking the error clears it
+        assert!(infera_last_error().is_null());
+        assert!(infera_take_last_error().is_null());
+
+        assert_eq!(infera_set_error_mode(2), -1);
+        assert!(!infera_last_error().is_null());
+    }
+
+    #[test]
+    fn test_global_error_is_readable_from_another_thread() {
+        let global = GlobalError::new();
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                set_last_error_in(&InferaError::ModelNotFound("ignored".to_string()), &global)
+            });
+        });
+        // Thread-local mode records nothing process-wide
+        assert!(global.as_ptr().is_null());
+
+        global.set_enabled(true);
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                set_last_error_in(
+                    &InferaError::ModelNotFound("elsewhere".to_string()),
+                    &global,
+                )
+            });
+        });
+        let ptr = global.as_ptr();
+        assert!(!ptr.is_null());
+        let message = unsafe { std::ffi::CStr::from_ptr(ptr) };
+        assert_eq!(message.to_str().unwrap(), "Model not found: elsewhere");
+        assert_eq!(
+            take_last_error_in(&global).unwrap().to_str().unwrap(),
+            "Model not found: elsewhere"
+        );
+        assert!(take_last_error_in(&global).is_none());
+
+        global.set_enabled(false);
+        assert!(global.as_ptr().is_null());
+    }
+}
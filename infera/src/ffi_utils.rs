@@ -1,6 +1,13 @@
 // Contains C-compatible structs and memory management functions for the FFI boundary.
 
+use crate::config::CONFIG;
+use crate::error::{self, InferaError};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::ffi::{c_char, CString};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// A C-compatible struct that holds the result of an inference operation.
 ///
@@ -17,7 +24,8 @@ pub struct InferaInferenceResult {
     pub rows: usize,
     /// The number of columns in the output tensor.
     pub cols: usize,
-    /// The status of the inference operation. `0` for success, `-1` for failure.
+    /// The status of the inference operation. `0` for success, or a negative
+    /// `InferaStatus` code classifying the failure.
     pub status: i32,
 }
 
@@ -34,12 +42,239 @@ impl InferaInferenceResult {
             status: -1,
         }
     }
+
+    /// Creates an error `InferaInferenceResult` whose `status` classifies `err`.
+    pub(crate) fn from_error(err: &InferaError) -> Self {
+        InferaInferenceResult {
+            status: err.status() as i32,
+            ..Self::error()
+        }
+    }
+
+    /// Creates a successful `InferaInferenceResult` that takes ownership of `data`.
+    ///
+    /// The buffer is leaked into a raw pointer and must be released with `infera_free_result`.
+    pub(crate) fn from_vec(data: Vec<f32>, rows: usize, cols: usize) -> Self {
+        Self::from_boxed(data.into_boxed_slice(), rows, cols)
+    }
+
+    /// Creates a successful `InferaInferenceResult` that takes ownership of a boxed buffer,
+    /// such as one drawn from the result pool.
+    pub(crate) fn from_boxed(data: Box<[f32]>, rows: usize, cols: usize) -> Self {
+        let len = data.len();
+        let ptr = Box::into_raw(data) as *mut f32;
+        InferaInferenceResult {
+            data: ptr,
+            len,
+            rows,
+            cols,
+            status: 0,
+        }
+    }
+}
+
+/// The buffers currently held by a `ResultPool`.
+#[derive(Default)]
+struct PooledBuffers {
+    /// Free buffers, keyed by their length.
+    by_len: HashMap<usize, Vec<Box<[f32]>>>,
+    /// The total number of buffers in `by_len`.
+    count: usize,
+}
+
+/// A free list of inference result buffers, so repeated predictions with the same
+/// output size do not allocate a new buffer each time.
+///
+/// Buffers are returned to the pool by `infera_free_result` and drawn from it for
+/// prediction results of the same length. Pooling is off unless the pool has a
+/// capacity, set with `INFERA_RESULT_POOL_SIZE`.
+pub(crate) struct ResultPool {
+    /// The maximum number of buffers kept; 0 disables pooling.
+    capacity: usize,
+    buffers: Mutex<PooledBuffers>,
+    /// The number of results that reused a pooled buffer.
+    reuses: AtomicU64,
+}
+
+impl ResultPool {
+    pub(crate) fn new(capacity: usize) -> Self {
+        ResultPool {
+            capacity,
+            buffers: Mutex::new(PooledBuffers::default()),
+            reuses: AtomicU64::new(0),
+        }
+    }
+
+    /// Whether buffers are pooled at all.
+    pub(crate) fn enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Returns a buffer holding a copy of `values`, reusing a pooled buffer of the same
+    /// length if there is one.
+    #[cfg(feature = "tract")]
+    pub(crate) fn copy_of(&self, values: &[f32]) -> Box<[f32]> {
+        match self.reuse(values.len()) {
+            Some(mut buffer) => {
+                buffer.copy_from_slice(values);
+                buffer
+            }
+            None => values.into(),
+        }
+    }
+
+    /// Returns a buffer of `len` values to be overwritten by the caller, reusing a pooled
+    /// buffer of that length if there is one.
+    #[cfg(feature = "tract")]
+    pub(crate) fn take(&self, len: usize) -> Box<[f32]> {
+        self.reuse(len)
+            .unwrap_or_else(|| vec![0.0; len].into_boxed_slice())
+    }
+
+    /// Removes a pooled buffer of `len` values from the pool, if there is one.
+    #[cfg(feature = "tract")]
+    fn reuse(&self, len: usize) -> Option<Box<[f32]>> {
+        if !self.enabled() {
+            return None;
+        }
+        let mut buffers = self.buffers.lock();
+        let buffer = buffers.by_len.get_mut(&len).and_then(Vec::pop)?;
+        buffers.count -= 1;
+        self.reuses.fetch_add(1, Ordering::Relaxed);
+        Some(buffer)
+    }
+
+    /// Keeps `buffer` for reuse, or drops it if the pool is full or disabled. Empty
+    /// buffers own no memory and are never kept.
+    pub(crate) fn put(&self, buffer: Box<[f32]>) {
+        if buffer.is_empty() {
+            return;
+        }
+        let mut buffers = self.buffers.lock();
+        if buffers.count < self.capacity {
+            buffers.by_len.entry(buffer.len()).or_default().push(buffer);
+            buffers.count += 1;
+        }
+    }
+
+    /// Frees every pooled buffer and returns how many there were.
+    pub(crate) fn clear(&self) -> usize {
+        let mut buffers = self.buffers.lock();
+        let count = buffers.count;
+        *buffers = PooledBuffers::default();
+        count
+    }
+
+    /// Returns the pool's capacity, the number of buffers it holds, and the number of
+    /// reuses as a JSON object.
+    pub(crate) fn to_json(&self) -> Value {
+        json!({
+            "capacity": self.capacity,
+            "pooled": self.buffers.lock().count,
+            "reuses": self.reuses.load(Ordering::Relaxed),
+        })
+    }
+}
+
+/// The process-wide pool of inference result buffers.
+pub(crate) static RESULT_POOL: Lazy<ResultPool> =
+    Lazy::new(|| ResultPool::new(CONFIG.result_pool_size));
+
+/// A C-compatible byte buffer that holds model outputs serialized as `f32` bytes.
+///
+/// This struct is filled in by `infera_predict_to_blob`. The caller is responsible for
+/// freeing the bytes by passing the entire struct to `infera_free_blob`.
+#[repr(C)]
+pub struct InferaBlob {
+    /// A pointer to the bytes, or null if there are none.
+    pub ptr: *mut u8,
+    /// The number of bytes at `ptr`.
+    pub len: usize,
+}
+
+impl InferaBlob {
+    /// Creates an `InferaBlob` that holds no bytes.
+    pub(crate) fn empty() -> Self {
+        InferaBlob {
+            ptr: std::ptr::null_mut(),
+            len: 0,
+        }
+    }
+
+    /// Creates an `InferaBlob` that takes ownership of `bytes`.
+    ///
+    /// The buffer is leaked into a raw pointer and must be released with `infera_free_blob`.
+    pub(crate) fn from_vec(bytes: Vec<u8>) -> Self {
+        let len = bytes.len();
+        let ptr = Box::into_raw(bytes.into_boxed_slice()) as *mut u8;
+        InferaBlob { ptr, len }
+    }
+}
+
+/// A C-compatible struct that holds the result of an inference operation along with
+/// how long the model ran and how many values went in and out.
+///
+/// This struct is returned by `infera_predict_profiled`. The caller is responsible for
+/// freeing the output by passing the inner `result` to `infera_free_result`.
+#[repr(C)]
+pub struct InferaProfiledResult {
+    /// The output of the inference, as returned by `infera_predict`.
+    pub result: InferaInferenceResult,
+    /// The total wall-clock duration in nanoseconds of the model runs, summed over the
+    /// chunks of a batch that was split. Input conversion and output copying are not
+    /// included.
+    pub inference_ns: u64,
+    /// The number of input values (`rows * cols`).
+    pub input_elements: usize,
+    /// The number of output values (`result.len`).
+    pub output_elements: usize,
+}
+
+impl InferaProfiledResult {
+    /// Creates an error `InferaProfiledResult` whose `result.status` classifies `err`.
+    pub(crate) fn from_error(err: &InferaError) -> Self {
+        InferaProfiledResult {
+            result: InferaInferenceResult::from_error(err),
+            inference_ns: 0,
+            input_elements: 0,
+            output_elements: 0,
+        }
+    }
+}
+
+/// Output values serialized as `f32` bytes, noting whether every value was finite.
+pub(crate) struct OutputBytes {
+    /// The values, four bytes each, in output order.
+    pub bytes: Vec<u8>,
+    /// Whether every value was finite (neither NaN nor infinite).
+    pub finite: bool,
+}
+
+impl OutputBytes {
+    /// Serializes `values` as little-endian `f32` bytes, or in the host's byte order if
+    /// `native_endian` is set, in a single pass.
+    #[cfg(feature = "tract")]
+    pub(crate) fn encode(values: impl ExactSizeIterator<Item = f32>, native_endian: bool) -> Self {
+        let mut bytes = Vec::with_capacity(values.len() * std::mem::size_of::<f32>());
+        let mut finite = true;
+        for value in values {
+            finite &= value.is_finite();
+            if native_endian {
+                bytes.extend_from_slice(&value.to_ne_bytes());
+            } else {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        OutputBytes { bytes, finite }
+    }
 }
 
 /// Frees a heap-allocated C string that was returned by an Infera FFI function.
 ///
 /// This function should be used to free the memory for strings returned by functions
 /// like `infera_get_model_info`, `infera_get_loaded_models`, and `infera_get_version`.
+/// The `data` buffer of an `InferaInferenceResult` must be freed with `infera_free_result`
+/// instead.
 ///
 /// # Safety
 ///
@@ -47,16 +282,32 @@ impl InferaInferenceResult {
 /// by Rust's `CString::into_raw`. Passing any other pointer (e.g., a string literal,
 /// a pointer from a different allocator, or a null pointer) will result in undefined behavior.
 #[no_mangle]
-pub unsafe extern "C" fn infera_free(ptr: *mut c_char) {
+pub unsafe extern "C" fn infera_free_string(ptr: *mut c_char) {
     if !ptr.is_null() {
         let _ = CString::from_raw(ptr);
     }
 }
 
+/// Frees a heap-allocated C string that was returned by an Infera FFI function.
+///
+/// Deprecated: this is an alias of `infera_free_string`, kept for compatibility. Its name
+/// does not say that it only frees strings, so new code should call `infera_free_string`
+/// for strings and `infera_free_result` for inference results.
+///
+/// # Safety
+///
+/// The same requirements as `infera_free_string` apply.
+#[no_mangle]
+pub unsafe extern "C" fn infera_free(ptr: *mut c_char) {
+    infera_free_string(ptr);
+}
+
 /// Frees the data buffer within an `InferaInferenceResult`.
 ///
 /// This function must be called on every `InferaInferenceResult` returned from
-/// `infera_predict` or `infera_predict_from_blob` to prevent memory leaks.
+/// `infera_predict` or `infera_predict_from_blob` to prevent memory leaks. When
+/// `INFERA_RESULT_POOL_SIZE` is set, the buffer is kept for reuse by a later prediction
+/// instead of being freed, until the pool is full.
 ///
 /// # Safety
 ///
@@ -72,8 +323,73 @@ pub unsafe extern "C" fn infera_free_result(res: InferaInferenceResult) {
         // SAFETY: `res.data` was allocated from a Box<[f32]> via `into_raw` with length `res.len`.
         // Reconstruct the slice pointer and drop it to free the allocation correctly.
         let slice_ptr: *mut [f32] = std::ptr::slice_from_raw_parts_mut(res.data, res.len);
-        let _ = Box::from_raw(slice_ptr);
+        let buffer = Box::from_raw(slice_ptr);
+        if RESULT_POOL.enabled() {
+            RESULT_POOL.put(buffer);
+        }
+    }
+}
+
+/// Frees every inference result buffer kept for reuse by the result pool.
+///
+/// The pool is only used when `INFERA_RESULT_POOL_SIZE` is set. Results that are still
+/// held by the caller are not affected.
+///
+/// # Returns
+///
+/// The number of buffers that were freed.
+#[no_mangle]
+pub extern "C" fn infera_clear_result_pool() -> usize {
+    RESULT_POOL.clear()
+}
+
+/// Frees the bytes of an `InferaBlob` returned by `infera_predict_to_blob`.
+///
+/// # Safety
+///
+/// * The `blob` struct must be a value that was returned by `infera_predict_to_blob`.
+/// * Its bytes must not have been freed yet. A blob with a null `ptr` is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn infera_free_blob(blob: InferaBlob) {
+    if !blob.ptr.is_null() {
+        // SAFETY: `blob.ptr` was allocated from a Box<[u8]> via `into_raw` with length `blob.len`.
+        let _ = Box::from_raw(std::ptr::slice_from_raw_parts_mut(blob.ptr, blob.len));
+    }
+}
+
+/// Creates a deep copy of an `InferaInferenceResult`.
+///
+/// The copy owns a freshly allocated buffer holding the same `len` elements and the
+/// same `rows` and `cols` metadata, so it can be freed with `infera_free_result`
+/// independently of the source.
+///
+/// # Returns
+///
+/// A new `InferaInferenceResult`. If `src` is null or does not describe a successful
+/// result, an error result with `status` set to `-1` is returned.
+///
+/// # Safety
+///
+/// * `src`, if not null, must point to a valid `InferaInferenceResult` whose `data`
+///   buffer has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn infera_copy_result(
+    src: *const InferaInferenceResult,
+) -> InferaInferenceResult {
+    if src.is_null() {
+        error::set_last_error(&InferaError::NullPointer);
+        return InferaInferenceResult::error();
+    }
+    let src = &*src;
+    if src.status != 0 || (src.data.is_null() && src.len > 0) {
+        return InferaInferenceResult::error();
     }
+    let data = if src.len == 0 {
+        Vec::new()
+    } else {
+        std::slice::from_raw_parts(src.data, src.len).to_vec()
+    };
+    InferaInferenceResult::from_vec(data, src.rows, src.cols)
 }
 
 #[cfg(test)]
@@ -109,4 +425,122 @@ mod tests {
         };
         unsafe { infera_free_result(res) }; // should free without UB
     }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_result_pool_reuses_freed_buffers() {
+        // A separate pool, so other tests freeing results do not affect the counts
+        let pool = ResultPool::new(2);
+        let first = pool.copy_of(&[1.0, 2.0, 3.0]);
+        let first_ptr = first.as_ptr();
+        pool.put(first);
+        assert_eq!(pool.to_json()["pooled"], 1);
+
+        // Repeated predict/free cycles of the same size reuse the same buffer
+        for i in 0..5 {
+            let values = [i as f32, 0.0, -1.0];
+            let buffer = pool.copy_of(&values);
+            assert_eq!(buffer.as_ptr(), first_ptr);
+            assert_eq!(&*buffer, &values);
+            pool.put(buffer);
+        }
+        assert_eq!(pool.to_json()["reuses"], 5);
+
+        // A different size allocates, and the pool keeps no more than its capacity
+        let other = pool.copy_of(&[4.0]);
+        assert_eq!(pool.to_json()["reuses"], 5);
+        pool.put(other);
+        pool.put(vec![0.0; 3].into_boxed_slice());
+        assert_eq!(pool.to_json()["pooled"], 2);
+        pool.put(Vec::new().into_boxed_slice());
+        assert_eq!(pool.clear(), 2);
+        assert_eq!(pool.to_json()["pooled"], 0);
+
+        // A pool without capacity never keeps buffers
+        let disabled = ResultPool::new(0);
+        assert!(!disabled.enabled());
+        disabled.put(vec![1.0].into_boxed_slice());
+        assert_eq!(disabled.to_json()["pooled"], 0);
+    }
+
+    #[test]
+    fn test_infera_free_string_and_deprecated_alias() {
+        // Each pointer is freed exactly once, by one of the two functions.
+        let ptr = CString::new("freed with infera_free_string")
+            .unwrap()
+            .into_raw();
+        unsafe { infera_free_string(ptr) };
+        let ptr = CString::new("freed with infera_free").unwrap().into_raw();
+        unsafe { infera_free(ptr) };
+
+        // Null pointers are ignored by both.
+        unsafe { infera_free_string(std::ptr::null_mut()) };
+        unsafe { infera_free(std::ptr::null_mut()) };
+    }
+
+    // The blob tests only touch memory, so they can also run under Miri
+    // (`make rust-miri`) to check `infera_free_blob` for undefined behavior.
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_output_bytes_encode_little_endian_layout() {
+        let encoded = OutputBytes::encode([1.75f32, -2.0, 0.5].into_iter(), false);
+        assert_eq!(
+            encoded.bytes,
+            [
+                0x00, 0x00, 0xe0, 0x3f, // 1.75
+                0x00, 0x00, 0x00, 0xc0, // -2.0
+                0x00, 0x00, 0x00, 0x3f, // 0.5
+            ]
+        );
+        assert!(encoded.finite);
+
+        let native = OutputBytes::encode([1.75f32].into_iter(), true);
+        assert_eq!(native.bytes, 1.75f32.to_ne_bytes());
+
+        let non_finite = OutputBytes::encode([1.0f32, f32::NAN].into_iter(), false);
+        assert!(!non_finite.finite);
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_infera_free_blob() {
+        let blob =
+            InferaBlob::from_vec(OutputBytes::encode([1.0f32, 2.0].into_iter(), false).bytes);
+        assert_eq!(blob.len, 8);
+        let bytes = unsafe { std::slice::from_raw_parts(blob.ptr, blob.len) };
+        assert_eq!(&bytes[4..], &2.0f32.to_le_bytes());
+        unsafe { infera_free_blob(blob) };
+
+        // Empty and null blobs are freed without touching memory
+        unsafe { infera_free_blob(InferaBlob::from_vec(Vec::new())) };
+        unsafe { infera_free_blob(InferaBlob::empty()) };
+    }
+
+    #[test]
+    fn test_infera_copy_result_survives_free_of_original() {
+        let original = InferaInferenceResult::from_vec(vec![1.0, 2.0, 3.0, 4.0], 2, 2);
+        let copy = unsafe { infera_copy_result(&original) };
+        assert_eq!(copy.status, 0);
+        assert_ne!(copy.data, original.data);
+        unsafe { infera_free_result(original) };
+
+        assert_eq!(copy.len, 4);
+        assert_eq!(copy.rows, 2);
+        assert_eq!(copy.cols, 2);
+        let values = unsafe { std::slice::from_raw_parts(copy.data, copy.len) };
+        assert_eq!(values, &[1.0, 2.0, 3.0, 4.0]);
+        unsafe { infera_free_result(copy) };
+    }
+
+    #[test]
+    fn test_infera_copy_result_null_and_error_sources() {
+        let copy = unsafe { infera_copy_result(std::ptr::null()) };
+        assert_eq!(copy.status, -1);
+        assert!(copy.data.is_null());
+
+        let failed = InferaInferenceResult::error();
+        let copy = unsafe { infera_copy_result(&failed) };
+        assert_eq!(copy.status, -1);
+        assert!(copy.data.is_null());
+    }
 }
@@ -1,28 +1,45 @@
 // Handles downloading and caching of remote models.
 
-use crate::config::{LogLevel, CONFIG};
-use crate::error::InferaError;
+use crate::bundle;
+use crate::config::{CacheEvictionStrategy, CacheNaming, HostOverride, LogLevel, CONFIG};
+use crate::error::{context, ErrorContext, InferaError};
 use crate::log;
+use crate::signature;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde_json::{json, Value};
 use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
-use std::io;
+use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Once};
 use std::thread;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 /// A guard that guarantees a temporary file is deleted when it goes out of scope.
 /// This is used to implement a panic-safe cleanup of partial downloads.
+///
+/// When `keep_on_failure` is set (via `INFERA_KEEP_PARTIAL`), an uncommitted file is
+/// renamed to `<path>.failed` instead of being deleted, so it can be inspected.
+///
+/// While the guard is alive, the path is registered as an active download so it is never
+/// mistaken for an orphaned `.part` file.
 struct TempFileGuard<'a> {
     path: &'a Path,
     committed: bool,
+    keep_on_failure: bool,
 }
 
 impl<'a> TempFileGuard<'a> {
     /// Creates a new guard for the given path.
-    fn new(path: &'a Path) -> Self {
+    fn new(path: &'a Path, keep_on_failure: bool) -> Self {
+        ACTIVE_DOWNLOADS.lock().insert(path.to_path_buf());
         Self {
             path,
             committed: false,
+            keep_on_failure,
         }
     }
 
@@ -36,41 +53,487 @@ impl<'a> TempFileGuard<'a> {
 
 impl Drop for TempFileGuard<'_> {
     fn drop(&mut self) {
-        if !self.committed {
+        ACTIVE_DOWNLOADS.lock().remove(self.path);
+        if self.committed {
+            return;
+        }
+        if self.keep_on_failure && self.path.exists() {
+            let kept_path = failed_path(self.path);
+            match fs::rename(self.path, &kept_path) {
+                Ok(()) => log!(
+                    LogLevel::Warn,
+                    "Kept partial download for inspection: {:?}",
+                    kept_path
+                ),
+                Err(e) => log!(LogLevel::Warn, "Failed to keep partial download: {}", e),
+            }
+        } else {
             let _ = fs::remove_file(self.path);
         }
     }
 }
 
+/// The `.part` files of downloads currently in progress in this process.
+static ACTIVE_DOWNLOADS: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// How long a `.part` file that no download of this process is writing must go unmodified
+/// before it is treated as orphaned. This leaves downloads of other processes sharing the
+/// directory alone, since an active download keeps writing to its file.
+const ORPHANED_PART_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// Per-URL locks held while a URL is looked up in the cache and downloaded, keyed by
+/// cache key.
+///
+/// Concurrent first loads of one URL then download it once, and no thread sees (and
+/// repairs) an entry that another thread is still committing.
+static FETCH_LOCKS: Lazy<Mutex<HashMap<String, Arc<Mutex<()>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Returns the lock that serializes fetches of the URL with the given cache key.
+fn fetch_lock(key: &str) -> Arc<Mutex<()>> {
+    FETCH_LOCKS
+        .lock()
+        .entry(key.to_string())
+        .or_default()
+        .clone()
+}
+
+/// Ensures orphaned `.part` files are cleaned once, on the first remote model download.
+static STARTUP_CLEANUP: Once = Once::new();
+
+/// Returns the path a failed partial download is kept at (`<path>.failed`).
+fn failed_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".failed");
+    PathBuf::from(name)
+}
+
+/// Content types accepted for model downloads when strict checking is enabled.
+const ACCEPTED_CONTENT_TYPES: [&str; 2] = ["application/octet-stream", "application/onnx"];
+
+/// Per-download settings, resolved from the global configuration by default.
+#[derive(Debug, Clone)]
+pub(crate) struct DownloadOptions {
+    /// HTTP request timeout in seconds, covering the whole request.
+    pub timeout_secs: u64,
+    /// HTTP connection timeout in seconds.
+    pub connect_timeout_secs: u64,
+    /// Whether to reject responses whose Content-Type is not in `ACCEPTED_CONTENT_TYPES`.
+    pub require_octet_stream: bool,
+    /// Cache size limit in bytes that a single download must fit within.
+    pub cache_size_limit: u64,
+    /// Free disk space in bytes that a download must leave on the cache filesystem.
+    pub min_free_disk_bytes: u64,
+    /// Maximum number of bytes accepted from a single download; 0 means no limit.
+    pub max_download_bytes: u64,
+    /// Whether models in the download directory may be evicted to make room for a download.
+    pub evict: bool,
+    /// Glob patterns of the hosts requests (and redirects) may go to; empty allows any host.
+    pub allowed_hosts: Vec<String>,
+    /// Extra headers sent with every request (e.g., `Authorization`).
+    pub headers: Vec<(String, String)>,
+    /// Maximum number of redirects followed for a single request.
+    pub max_redirects: usize,
+    /// Whether redirects from HTTPS to plain HTTP are followed.
+    pub allow_insecure_redirects: bool,
+    /// The cache directory a download is moved into once complete, where evictions make
+    /// room for it and free space is checked. `None` uses the destination's directory.
+    pub cache_dir: Option<PathBuf>,
+    /// Number of attempts made before a download fails.
+    pub retry_attempts: u32,
+    /// Delay in milliseconds before the second attempt, growing linearly with each attempt.
+    pub retry_delay_ms: u64,
+    /// Total time in seconds all attempts may take; 0 means no limit.
+    pub total_deadline_secs: u64,
+    /// Whether remote models are only served from the cache, without any network request.
+    pub offline: bool,
+}
+
+impl DownloadOptions {
+    /// Builds download options from the current `CONFIG`.
+    pub(crate) fn from_config() -> Self {
+        Self {
+            timeout_secs: CONFIG.http_timeout_secs,
+            connect_timeout_secs: CONFIG.http_connect_timeout_secs,
+            require_octet_stream: CONFIG.require_octet_stream,
+            cache_size_limit: get_cache_size_limit(),
+            min_free_disk_bytes: CONFIG.min_free_disk_bytes,
+            max_download_bytes: CONFIG.max_download_bytes,
+            evict: true,
+            allowed_hosts: CONFIG.allowed_hosts.clone(),
+            headers: Vec::new(),
+            max_redirects: CONFIG.http_max_redirects,
+            allow_insecure_redirects: CONFIG.allow_insecure_redirects,
+            cache_dir: None,
+            retry_attempts: CONFIG.http_retry_attempts,
+            retry_delay_ms: CONFIG.http_retry_delay_ms,
+            total_deadline_secs: CONFIG.http_total_deadline_secs,
+            offline: crate::config::offline(),
+        }
+    }
+
+    /// Builds download options for `url` from the current `CONFIG`, applying the first
+    /// entry of `INFERA_HOST_OVERRIDES` whose pattern matches the URL's host.
+    pub(crate) fn for_url(url: &str) -> Self {
+        let options = Self::from_config();
+        match url_host(url).and_then(|host| find_host_override(&host, &CONFIG.host_overrides)) {
+            Some(host_override) => options.with_override(host_override),
+            None => options,
+        }
+    }
+
+    /// Returns these options with the settings of a per-host override applied.
+    fn with_override(self, host_override: &HostOverride) -> Self {
+        Self {
+            timeout_secs: host_override.timeout_secs.unwrap_or(self.timeout_secs),
+            connect_timeout_secs: host_override
+                .connect_timeout_secs
+                .unwrap_or(self.connect_timeout_secs),
+            headers: host_override.headers.clone(),
+            ..self
+        }
+    }
+
+    /// Builds an HTTP client with these timeouts and headers.
+    ///
+    /// Each redirect is logged at the `DEBUG` level and checked with `check_redirect`, so
+    /// an allowed server cannot send a download to a host that is not allowed or over
+    /// plain HTTP.
+    fn client(&self) -> Result<reqwest::blocking::Client, InferaError> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        for (name, value) in &self.headers {
+            let header = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+                .map_err(context(InferaError::HttpRequestError, "http_header", name))?;
+            let value = reqwest::header::HeaderValue::from_str(value).map_err(context(
+                InferaError::HttpRequestError,
+                "http_header",
+                name,
+            ))?;
+            headers.insert(header, value);
+        }
+        let options = self.clone();
+        let redirect = reqwest::redirect::Policy::custom(move |attempt| {
+            if let Some(from) = attempt.previous().last() {
+                log!(
+                    LogLevel::Debug,
+                    "Redirect {}/{}: {} -> {}",
+                    attempt.previous().len(),
+                    options.max_redirects,
+                    from,
+                    attempt.url()
+                );
+            }
+            match check_redirect(attempt.previous(), attempt.url(), &options) {
+                Ok(()) => attempt.follow(),
+                Err(e) => attempt.error(e),
+            }
+        });
+        reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(self.timeout_secs))
+            .connect_timeout(Duration::from_secs(self.connect_timeout_secs))
+            .default_headers(headers)
+            .redirect(redirect)
+            .build()
+            .map_err(context(InferaError::HttpRequestError, "http_client", ""))
+    }
+}
+
+/// Checks whether a redirect to `next` may be followed, given the URLs requested so far.
+///
+/// A redirect fails if it exceeds `max_redirects`, leads to a host outside
+/// `allowed_hosts`, or leads from HTTPS to plain HTTP without `allow_insecure_redirects`.
+fn check_redirect(
+    previous: &[reqwest::Url],
+    next: &reqwest::Url,
+    options: &DownloadOptions,
+) -> Result<(), InferaError> {
+    if previous.len() > options.max_redirects {
+        return Err(InferaError::HttpRequestError(format!(
+            "too many redirects (more than {}) for {}",
+            options.max_redirects,
+            previous.first().map(|u| u.as_str()).unwrap_or_default()
+        )));
+    }
+    if !host_allowed(next.host_str().unwrap_or_default(), &options.allowed_hosts) {
+        return Err(InferaError::HostNotAllowed(next.to_string()));
+    }
+    if let Some(from) = previous.last() {
+        if from.scheme() == "https" && next.scheme() == "http" && !options.allow_insecure_redirects
+        {
+            return Err(InferaError::InsecureRedirect {
+                from: from.to_string(),
+                to: next.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Converts a failed request into an `InferaError`, keeping the error of a refused
+/// redirect (see `check_redirect`) instead of reqwest's generic redirect error.
+fn request_error(e: reqwest::Error) -> InferaError {
+    let refused = std::error::Error::source(&e)
+        .and_then(|source| source.downcast_ref::<InferaError>())
+        .and_then(|refused| match refused {
+            InferaError::InsecureRedirect { from, to } => Some(InferaError::InsecureRedirect {
+                from: from.clone(),
+                to: to.clone(),
+            }),
+            InferaError::HostNotAllowed(url) => Some(InferaError::HostNotAllowed(url.clone())),
+            InferaError::HttpRequestError(message) => {
+                Some(InferaError::HttpRequestError(message.clone()))
+            }
+            _ => None,
+        });
+    refused.unwrap_or_else(|| InferaError::HttpRequestError(e.to_string()))
+}
+
+/// Returns whether `text` matches a glob `pattern`, where `*` matches any run of
+/// characters (including none) and `?` matches exactly one. Matching is case-insensitive.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // The position of the last `*` in the pattern and the text position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` absorb one more character and retry
+                Some((star, star_t)) => {
+                    backtrack = Some((star, star_t + 1));
+                    p = star + 1;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Returns the host name of a URL in lowercase, or `None` if it cannot be parsed.
+fn url_host(url: &str) -> Option<String> {
+    reqwest::Url::parse(url)
+        .ok()?
+        .host_str()
+        .map(|host| host.to_lowercase())
+}
+
+/// Returns whether `host` matches one of the `allowed_hosts` patterns, or any host if
+/// there are none.
+fn host_allowed(host: &str, allowed_hosts: &[String]) -> bool {
+    allowed_hosts.is_empty()
+        || allowed_hosts
+            .iter()
+            .any(|pattern| glob_match(pattern, host))
+}
+
+/// Returns the first override whose pattern matches `host`.
+fn find_host_override<'a>(host: &str, overrides: &'a [HostOverride]) -> Option<&'a HostOverride> {
+    overrides.iter().find(|o| glob_match(&o.host, host))
+}
+
+/// Checks that the host of `url` is allowed by the `allowed_hosts` patterns.
+///
+/// URLs whose host cannot be determined are only allowed when there are no patterns.
+fn check_host_allowed(url: &str, allowed_hosts: &[String]) -> Result<(), InferaError> {
+    if allowed_hosts.is_empty() {
+        return Ok(());
+    }
+    match url_host(url) {
+        Some(host) if host_allowed(&host, allowed_hosts) => Ok(()),
+        _ => Err(InferaError::HostNotAllowed(url.to_string())),
+    }
+}
+
+/// Subdirectory of the cache holding content-addressed model payloads.
+const CONTENT_DIR: &str = "content";
+
+/// Counters of cache activity since the process started or the counters were last reset.
+struct CacheStats {
+    /// Loads served from a cached file (within the TTL, without an ETag, or after a 304).
+    hits: AtomicU64,
+    /// Loads that could not be served from the cache (downloads and failed requests).
+    misses: AtomicU64,
+    /// Conditional requests the server answered with 304 Not Modified.
+    revalidations: AtomicU64,
+    /// Completed model downloads.
+    downloads: AtomicU64,
+    /// Bytes written to the cache by completed downloads.
+    bytes_downloaded: AtomicU64,
+    /// Cache entries removed to stay within the cache size limit.
+    evictions: AtomicU64,
+    /// Disk space freed by those evictions.
+    bytes_evicted: AtomicU64,
+}
+
+impl CacheStats {
+    const fn new() -> Self {
+        Self {
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            revalidations: AtomicU64::new(0),
+            downloads: AtomicU64::new(0),
+            bytes_downloaded: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+            bytes_evicted: AtomicU64::new(0),
+        }
+    }
+
+    fn counters(&self) -> [(&'static str, &AtomicU64); 7] {
+        [
+            ("hits", &self.hits),
+            ("misses", &self.misses),
+            ("revalidations", &self.revalidations),
+            ("downloads", &self.downloads),
+            ("bytes_downloaded", &self.bytes_downloaded),
+            ("evictions", &self.evictions),
+            ("bytes_evicted", &self.bytes_evicted),
+        ]
+    }
+
+    fn to_json(&self) -> Value {
+        let counters = self
+            .counters()
+            .into_iter()
+            .map(|(name, counter)| (name.to_string(), json!(counter.load(Ordering::Relaxed))))
+            .collect();
+        Value::Object(counters)
+    }
+
+    fn reset(&self) {
+        for (_, counter) in self.counters() {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Cache activity counters for the process, reported by `infera_get_cache_info` and
+/// `infera_get_metrics`.
+static CACHE_STATS: CacheStats = CacheStats::new();
+
+/// Increments a cache counter by `amount`.
+fn count(counter: &AtomicU64, amount: u64) {
+    counter.fetch_add(amount, Ordering::Relaxed);
+}
+
+/// Returns the cache activity counters as a JSON object.
+pub(crate) fn cache_stats() -> Value {
+    CACHE_STATS.to_json()
+}
+
+/// Resets all cache activity counters to zero.
+pub(crate) fn reset_cache_stats() {
+    CACHE_STATS.reset();
+}
+
 /// Return the cache directory path used by Infera for remote models.
 pub(crate) fn cache_dir() -> PathBuf {
     CONFIG.cache_dir.clone()
 }
 
-/// Gets the cache size limit in bytes from environment variable or default.
+/// Creates `dir` and its parents if they are missing.
+///
+/// Several threads may create the same directory at once, so a directory that exists
+/// when creating it fails (for example, one another thread just created) is not an error.
+pub(crate) fn ensure_dir(dir: &Path) -> Result<(), InferaError> {
+    if dir.is_dir() {
+        return Ok(());
+    }
+    log!(LogLevel::Info, "Creating cache directory: {:?}", dir);
+    match fs::create_dir_all(dir) {
+        Err(_) if dir.is_dir() => Ok(()),
+        result => result.map_err(context(
+            InferaError::CacheDirError,
+            "cache_dir_create",
+            &dir.to_string_lossy(),
+        )),
+    }
+}
+
+/// Creates the cache directory and the download temp directory if they are missing.
+pub(crate) fn ensure_cache_dirs() -> Result<(), InferaError> {
+    ensure_dir(&cache_dir())?;
+    ensure_dir(&temp_dir())
+}
+
+/// Numbers the temporary files written by this process, see `unique_suffix`.
+static TEMP_FILE_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Returns a suffix that is unique to this process and call, for naming temporary files
+/// that several threads or processes may write for the same target at once.
+pub(crate) fn unique_suffix() -> String {
+    format!(
+        "{}-{}",
+        std::process::id(),
+        TEMP_FILE_SEQ.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Return the directory in-progress downloads are written to (`INFERA_TEMP_DIR`, or the
+/// cache directory if unset).
+pub(crate) fn temp_dir() -> PathBuf {
+    CONFIG.temp_dir.clone().unwrap_or_else(cache_dir)
+}
+
+/// Gets the current cache size limit in bytes (from the environment or set at runtime).
 fn get_cache_size_limit() -> u64 {
-    CONFIG.cache_size_limit
+    crate::config::cache_size_limit()
 }
 
-/// Updates the access time of a cached file by touching it.
-fn touch_cache_file(path: &Path) -> Result<(), InferaError> {
+/// Updates the access time of a cached file by touching it, and counts the access in
+/// its metadata sidecar (used by the LFU eviction strategy).
+pub(crate) fn touch_cache_file(path: &Path) -> Result<(), InferaError> {
     if path.exists() {
         let now = filetime::FileTime::now();
-        filetime::set_file_atime(path, now).map_err(|e| InferaError::IoError(e.to_string()))?;
+        filetime::set_file_atime(path, now).map_err(context(
+            InferaError::IoError,
+            "cache_touch",
+            &path.to_string_lossy(),
+        ))?;
+    }
+    if let Some(mut meta) = read_cache_meta(path).filter(Value::is_object) {
+        let access_count = meta["access_count"].as_u64().unwrap_or(0);
+        meta["access_count"] = json!(access_count.saturating_add(1));
+        if let Err(e) = store_cache_meta(path, &meta) {
+            log!(LogLevel::Warn, "Failed to write cache metadata: {}", e);
+        }
     }
     Ok(())
 }
 
 /// Gets metadata about cached files sorted by access time (oldest first).
 fn get_cached_files_by_access_time() -> Result<Vec<(PathBuf, SystemTime, u64)>, InferaError> {
-    let dir = cache_dir();
+    cached_files_by_access_time_in(&cache_dir())
+}
+
+/// Gets metadata about the cached model files in `dir`, sorted by access time (oldest first).
+///
+/// Unpacked bundles are listed as one entry per bundle directory, with the total size of
+/// its files and the access time of its model.
+fn cached_files_by_access_time_in(
+    dir: &Path,
+) -> Result<Vec<(PathBuf, SystemTime, u64)>, InferaError> {
     if !dir.exists() {
         return Ok(Vec::new());
     }
 
-    let mut files = Vec::new();
-    for entry in fs::read_dir(&dir)
-        .map_err(|e| InferaError::IoError(e.to_string()))?
+    let mut files = cached_bundles_in(&dir.join(bundle::BUNDLES_DIR));
+    for entry in fs::read_dir(dir)
+        .map_err(context(
+            InferaError::IoError,
+            "cache_scan",
+            &dir.to_string_lossy(),
+        ))?
         .flatten()
     {
         let path = entry.path();
@@ -88,195 +551,1189 @@ fn get_cached_files_by_access_time() -> Result<Vec<(PathBuf, SystemTime, u64)>,
     Ok(files)
 }
 
-/// Calculates total cache size in bytes.
-fn get_cache_size() -> Result<u64, InferaError> {
-    let files = get_cached_files_by_access_time()?;
-    Ok(files.iter().map(|(_, _, size)| size).sum())
+/// Gets metadata about the unpacked bundles in `bundles_dir`, skipping bundles that are
+/// still being unpacked.
+fn cached_bundles_in(bundles_dir: &Path) -> Vec<(PathBuf, SystemTime, u64)> {
+    let Ok(entries) = fs::read_dir(bundles_dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_none() && path.join(bundle::MODEL_FILE).is_file())
+        .filter_map(|path| {
+            let accessed = fs::metadata(path.join(bundle::MODEL_FILE))
+                .and_then(|metadata| metadata.accessed())
+                .unwrap_or_else(|_| SystemTime::now());
+            let size = fs::read_dir(&path)
+                .ok()?
+                .flatten()
+                .filter_map(|file| file.metadata().ok())
+                .filter(fs::Metadata::is_file)
+                .map(|metadata| metadata.len())
+                .sum();
+            Some((path, accessed, size))
+        })
+        .collect()
 }
 
-/// Evicts least recently used cache files until cache size is below limit.
-fn evict_cache_if_needed(required_space: u64) -> Result<(), InferaError> {
-    let limit = get_cache_size_limit();
-    let current_size = get_cache_size()?;
+/// Returns the file a loaded model is loaded from for a cache entry: the model of an
+/// unpacked bundle, or the cached model file itself.
+fn entry_model_path(cached_path: &Path) -> PathBuf {
+    if cached_path.is_dir() {
+        cached_path.join(bundle::MODEL_FILE)
+    } else {
+        cached_path.to_path_buf()
+    }
+}
 
-    if current_size + required_space <= limit {
-        return Ok(());
+/// Returns the path of the metadata sidecar for a cached model file.
+fn meta_path(cached_path: &Path) -> PathBuf {
+    cached_path.with_extension("meta.json")
+}
+
+/// Replaces the metadata sidecar of a cached model with `meta`.
+///
+/// The sidecar is written to a temporary file that is then renamed over it, so a crash
+/// leaves either the old or the new metadata and never a partially written file.
+fn store_cache_meta(cached_path: &Path, meta: &Value) -> std::io::Result<()> {
+    let temp_path = cached_path.with_extension(format!("meta.json.{}.tmp", unique_suffix()));
+    fs::write(&temp_path, meta.to_string())?;
+    fs::rename(&temp_path, meta_path(cached_path)).inspect_err(|_| {
+        let _ = fs::remove_file(&temp_path);
+    })
+}
+
+/// Builds the metadata of a cache entry for `url` that was just downloaded or validated.
+fn cache_meta(url: &str) -> Value {
+    let now = unix_secs(SystemTime::now());
+    json!({
+        "url": url,
+        "downloaded_at": now,
+        "validated_at": now,
+    })
+}
+
+/// Builds the metadata of a committed download: the `cache_meta` fields, the URL it was
+/// finally downloaded from after redirects, its ETag (if the server sent one), its SHA-256,
+/// and, for deduplicated entries, the SHA-256 of the content payload it links to.
+fn download_meta(commit: &DownloadCommit, content_hash: Option<&str>) -> Value {
+    let mut meta = cache_meta(commit.url);
+    meta["final_url"] = json!(commit.final_url);
+    meta["sha256"] = json!(commit.sha256);
+    if let Some(etag) = commit.etag {
+        meta["etag"] = json!(etag);
+    }
+    if let Some(hash) = content_hash {
+        meta["content_hash"] = json!(hash);
     }
+    meta
+}
 
-    let target_size = limit.saturating_sub(required_space);
-    let mut freed_size = 0u64;
-    let files = get_cached_files_by_access_time()?;
+/// Writes the metadata sidecar of a cached model that has no ETag, recording only its URL.
+///
+/// Failures are logged and ignored, since an entry without a sidecar is still served.
+fn write_cache_meta(cached_path: &Path, url: &str) {
+    if let Err(e) = store_cache_meta(cached_path, &cache_meta(url)) {
+        log!(LogLevel::Warn, "Failed to write cache metadata: {}", e);
+    }
+}
 
-    for (path, _, size) in files {
-        if current_size - freed_size <= target_size {
-            break;
-        }
+/// Reads the metadata sidecar of a cached model, if present.
+fn read_cache_meta(cached_path: &Path) -> Option<Value> {
+    let content = fs::read_to_string(meta_path(cached_path)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Reads the original URL of a cached model from its metadata sidecar, if present.
+fn read_cache_url(cached_path: &Path) -> Option<String> {
+    read_cache_meta(cached_path)?["url"]
+        .as_str()
+        .map(String::from)
+}
 
-        fs::remove_file(&path).map_err(|e| InferaError::IoError(e.to_string()))?;
-        freed_size += size;
+/// Reads the content hash of a deduplicated cache entry from its metadata sidecar.
+fn read_content_hash(cached_path: &Path) -> Option<String> {
+    read_cache_meta(cached_path)?["content_hash"]
+        .as_str()
+        .map(String::from)
+}
+
+/// Reads the ETag of a cached model from its metadata sidecar, falling back to the
+/// `.etag` file that older versions stored it in.
+///
+/// An entry whose download was not completely committed has no ETag.
+fn read_cache_etag(cached_path: &Path) -> Option<String> {
+    match read_cache_meta(cached_path) {
+        Some(meta) if meta["pending"].as_bool() == Some(true) => None,
+        Some(meta) if meta["etag"].is_string() => meta["etag"].as_str().map(String::from),
+        _ => fs::read_to_string(cached_path.with_extension("etag"))
+            .ok()
+            .map(|etag| etag.trim().to_string()),
     }
+}
 
-    Ok(())
+/// Records in the metadata sidecar that the cached copy of a model was just confirmed
+/// to be current by the server, keeping the other metadata fields.
+fn mark_cache_validated(cached_path: &Path, url: &str) {
+    let Some(mut meta) = read_cache_meta(cached_path).filter(Value::is_object) else {
+        write_cache_meta(cached_path, url);
+        return;
+    };
+    meta["validated_at"] = json!(unix_secs(SystemTime::now()));
+    if let Err(e) = store_cache_meta(cached_path, &meta) {
+        log!(LogLevel::Warn, "Failed to write cache metadata: {}", e);
+    }
 }
 
-/// Clears the entire cache directory by deleting its contents.
-/// If the directory does not exist, this is a no-op.
-pub(crate) fn clear_cache() -> Result<(), InferaError> {
-    let dir = cache_dir();
-    if !dir.exists() {
-        return Ok(());
+/// Returns true if the cached copy of a model was last validated less than `ttl_secs`
+/// seconds ago.
+fn validated_within(cached_path: &Path, ttl_secs: u64) -> bool {
+    if ttl_secs == 0 {
+        return false;
     }
-    for entry in fs::read_dir(&dir)
-        .map_err(|e| InferaError::IoError(e.to_string()))?
-        .flatten()
-    {
-        let path = entry.path();
-        if path.is_file() {
-            fs::remove_file(&path).map_err(|e| InferaError::IoError(e.to_string()))?;
-        } else if path.is_dir() {
-            fs::remove_dir_all(&path).map_err(|e| InferaError::IoError(e.to_string()))?;
+    read_cache_meta(cached_path)
+        .and_then(|meta| meta["validated_at"].as_u64())
+        .is_some_and(|validated_at| {
+            unix_secs(SystemTime::now()).saturating_sub(validated_at) < ttl_secs
+        })
+}
+
+/// Returns the path of the content-addressed payload with the given SHA-256.
+fn content_path(dir: &Path, content_hash: &str) -> PathBuf {
+    dir.join(CONTENT_DIR).join(format!("{}.onnx", content_hash))
+}
+
+/// Computes the hex-encoded SHA-256 of a file's contents.
+pub(crate) fn file_sha256(path: &Path) -> Result<String, InferaError> {
+    let subject = path.to_string_lossy();
+    let mut file = File::open(path).map_err(context(InferaError::IoError, "hash", &subject))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read =
+            file.read(&mut buffer)
+                .map_err(context(InferaError::IoError, "hash", &subject))?;
+        if read == 0 {
+            break;
         }
+        hasher.update(&buffer[..read]);
     }
-    Ok(())
+    Ok(hex::encode(hasher.finalize()))
 }
 
-/// The result of a remote model cache validation or download check.
-#[derive(Debug, PartialEq, Eq)]
-enum DownloadResult {
-    /// The remote model has not been modified on the server.
-    NotModified,
-    /// A new model was downloaded, optionally returning the server's new ETag.
-    Downloaded { etag: Option<String> },
+/// Computes the hex-encoded SHA-256 of a byte slice.
+#[cfg(feature = "tract")]
+pub(crate) fn bytes_sha256(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
 }
 
-/// Handles the download and caching of a remote model from a URL.
-///
-/// If the model for the given URL is already present in the local cache, this
-/// function performs an HTTP cache validation check using the stored ETag metadata
-/// if available. If the server confirms that the model is unmodified (HTTP 304),
-/// the cached model is reused. If the server has an updated model (HTTP 200),
-/// it is downloaded, local cache is evicted if needed, and the new ETag metadata
-/// is stored.
-///
-/// If no local ETag exists but the cached model file does, it falls back to
-/// assuming the cached model is valid to prevent unnecessary server requests.
-///
-/// The cache uses an LRU (Least Recently Used) eviction policy with a configurable
-/// size limit (default 1GB, configurable via INFERA_CACHE_SIZE_LIMIT env var).
-///
-/// Downloads support automatic retries with exponential backoff.
-///
-/// # Arguments
+/// Parses a caller-supplied SHA-256 checksum, returning it in lowercase hex.
+pub(crate) fn parse_sha256(checksum: &str) -> Result<String, InferaError> {
+    let checksum = checksum.trim();
+    if checksum.len() != 64 || !checksum.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(InferaError::InvalidChecksum(checksum.to_string()));
+    }
+    Ok(checksum.to_ascii_lowercase())
+}
+
+/// Checks that the SHA-256 `actual` of the model at `path` is the `expected` checksum.
+pub(crate) fn verify_sha256(path: &str, actual: &str, expected: &str) -> Result<(), InferaError> {
+    if actual == expected {
+        return Ok(());
+    }
+    Err(InferaError::ChecksumMismatch {
+        path: path.to_string(),
+        expected: expected.to_string(),
+        actual: actual.to_string(),
+    })
+}
+
+/// Stores a cached model file once under `content/<sha256>.onnx` and makes the cache
+/// entry a hard link to that payload, returning the content hash.
 ///
-/// * `url` - The HTTP/HTTPS URL of the ONNX model to be downloaded.
+/// If a payload with the same content already exists, the entry is atomically replaced
+/// by a link to it, so identical models downloaded from different URLs share one copy.
+#[cfg(feature = "tract")]
+fn link_to_content(dir: &Path, cached_path: &Path) -> Result<String, InferaError> {
+    link_hashed_content(dir, cached_path, &file_sha256(cached_path)?)
+}
+
+/// Links a cache entry to its content payload like `link_to_content`, with the entry's
+/// SHA-256 already known (e.g., computed while it was downloaded).
+fn link_hashed_content(
+    dir: &Path,
+    cached_path: &Path,
+    content_hash: &str,
+) -> Result<String, InferaError> {
+    let payload = content_path(dir, content_hash);
+    let subject = cached_path.to_string_lossy();
+    if payload.exists() {
+        let link_path = cached_path.with_extension(format!("onnx.{}.link", unique_suffix()));
+        let _ = fs::remove_file(&link_path);
+        fs::hard_link(&payload, &link_path).map_err(context(
+            InferaError::IoError,
+            "cache_link",
+            &subject,
+        ))?;
+        fs::rename(&link_path, cached_path).map_err(context(
+            InferaError::IoError,
+            "cache_link",
+            &subject,
+        ))?;
+    } else {
+        ensure_dir(&dir.join(CONTENT_DIR))?;
+        fs::hard_link(cached_path, &payload).map_err(context(
+            InferaError::IoError,
+            "cache_link",
+            &subject,
+        ))?;
+    }
+    Ok(content_hash.to_string())
+}
+
+/// Deletes the content payload with the given hash if no cache entry in `dir` refers to
+/// it anymore. Returns true if the payload was deleted.
+fn release_content(dir: &Path, content_hash: &str) -> bool {
+    let referenced = cached_files_by_access_time_in(dir)
+        .unwrap_or_default()
+        .iter()
+        .any(|(path, _, _)| read_content_hash(path).as_deref() == Some(content_hash));
+    if referenced {
+        return false;
+    }
+    fs::remove_file(content_path(dir, content_hash)).is_ok()
+}
+
+/// Removes a cache entry with its metadata and signature files, and its content payload
+/// if this was the last entry referring to it.
 ///
-/// # Returns
+/// Returns the number of bytes freed on disk.
+fn remove_cache_entry(dir: &Path, cached_path: &Path, size: u64) -> Result<u64, InferaError> {
+    if cached_path.is_dir() {
+        fs::remove_dir_all(cached_path).map_err(context(
+            InferaError::IoError,
+            "cache_evict",
+            &cached_path.to_string_lossy(),
+        ))?;
+        return Ok(size);
+    }
+    let content_hash = read_content_hash(cached_path);
+    fs::remove_file(cached_path).map_err(context(
+        InferaError::IoError,
+        "cache_evict",
+        &cached_path.to_string_lossy(),
+    ))?;
+    let _ = fs::remove_file(meta_path(cached_path));
+    let _ = fs::remove_file(cached_path.with_extension("etag"));
+    let _ = fs::remove_file(signature::signature_path(cached_path));
+    match content_hash {
+        Some(hash) if !release_content(dir, &hash) => Ok(0),
+        _ => Ok(size),
+    }
+}
+
+/// The steps of moving a finished download into the cache, in the order they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum CommitStep {
+    /// Marks the entry's metadata as pending, recording the new URL and ETag.
+    Intent,
+    /// Renames the download over the cached model file.
+    Move,
+    /// Links the cached model file to its content payload, if deduplication is enabled.
+    Link,
+    /// Writes the final metadata, which completes the commit.
+    Finish,
+}
+
+/// What is recorded about a download when it is committed to the cache.
+struct DownloadCommit<'a> {
+    url: &'a str,
+    final_url: &'a str,
+    etag: Option<&'a str>,
+    /// The hex-encoded SHA-256 of the download, computed while it was streamed.
+    sha256: &'a str,
+    dedup: bool,
+}
+
+/// Moves the download at `temp_path` into the cache at `cached_path`, running the steps
+/// up to and including `last_step`, and returns the content hash of a deduplicated entry.
 ///
-/// A `Result` which is:
-/// * `Ok(PathBuf)`: The local file path of the cached model.
-/// * `Err(InferaError)`: An error indicating failure in creating the cache directory,
-///   making the HTTP request, or writing the file to disk.
-pub(crate) fn handle_remote_model(url: &str) -> Result<PathBuf, InferaError> {
-    let cache_dir = cache_dir();
-    if !cache_dir.exists() {
-        log!(LogLevel::Info, "Creating cache directory: {:?}", cache_dir);
-        fs::create_dir_all(&cache_dir).map_err(|e| InferaError::CacheDirError(e.to_string()))?;
+/// The metadata is first rewritten with a `pending` flag (keeping the content hash of the
+/// previous entry, so its payload can still be released), and the flag is only cleared by
+/// the final metadata write. An entry whose commit was interrupted at any point in between
+/// is therefore recognized as pending and removed by `repair_cache_entry`.
+fn commit_download(
+    dir: &Path,
+    temp_path: &Path,
+    cached_path: &Path,
+    commit: &DownloadCommit,
+    last_step: CommitStep,
+) -> Result<Option<String>, InferaError> {
+    let subject = cached_path.to_string_lossy();
+    let previous_hash = read_content_hash(cached_path);
+    let mut intent = download_meta(commit, previous_hash.as_deref());
+    intent["pending"] = json!(true);
+    store_cache_meta(cached_path, &intent).map_err(context(
+        InferaError::IoError,
+        "cache_write",
+        &subject,
+    ))?;
+    if last_step == CommitStep::Intent {
+        return Ok(None);
     }
-    let mut hasher = Sha256::new();
-    hasher.update(url.as_bytes());
-    let hash_hex = hex::encode(hasher.finalize());
-    let cached_path = cache_dir.join(format!("{}.onnx", hash_hex));
-    let etag_path = cache_dir.join(format!("{}.etag", hash_hex));
 
-    let mut local_etag = None;
+    // A rename only works within one filesystem, which is why INFERA_TEMP_DIR must be on
+    // the same filesystem as the cache directory
+    fs::rename(temp_path, cached_path).map_err(|e| {
+        InferaError::IoError(format!(
+            "Failed to move the download into the cache (INFERA_TEMP_DIR must be on the \
+             same filesystem as the cache directory): {}",
+            e
+        ))
+        .context(ErrorContext::new("cache_write", subject.clone()))
+    })?;
+    if last_step == CommitStep::Move {
+        return Ok(None);
+    }
+
+    let content_hash = if commit.dedup {
+        link_hashed_content(dir, cached_path, commit.sha256)
+            .map_err(|e| log!(LogLevel::Warn, "Failed to deduplicate cached model: {}", e))
+            .ok()
+    } else {
+        None
+    };
+    if last_step == CommitStep::Link {
+        return Ok(content_hash);
+    }
+
+    let meta = download_meta(commit, content_hash.as_deref());
+    store_cache_meta(cached_path, &meta).map_err(context(
+        InferaError::IoError,
+        "cache_write",
+        &subject,
+    ))?;
+    let _ = fs::remove_file(cached_path.with_extension("etag"));
+    if let Some(hash) = previous_hash.filter(|h| Some(h) != content_hash.as_ref()) {
+        release_content(dir, &hash);
+    }
+    Ok(content_hash)
+}
+
+/// Removes a cache entry whose download was not completely committed, so it is
+/// downloaded again instead of being served with metadata that may not match it.
+///
+/// Returns true if the entry was removed.
+fn repair_cache_entry(dir: &Path, cached_path: &Path) -> bool {
+    let pending =
+        read_cache_meta(cached_path).is_some_and(|meta| meta["pending"].as_bool() == Some(true));
+    if !pending {
+        return false;
+    }
+    log!(
+        LogLevel::Warn,
+        "Removing interrupted cache entry: {}",
+        cached_path.display()
+    );
     if cached_path.exists() {
-        if etag_path.exists() {
-            if let Ok(etag_val) = fs::read_to_string(&etag_path) {
-                local_etag = Some(etag_val.trim().to_string());
-                log!(LogLevel::Info, "Found local ETag metadata for URL: {}", url);
-            }
-        } else {
+        if let Err(e) = remove_cache_entry(dir, cached_path, 0) {
             log!(
-                LogLevel::Info,
-                "Cache hit for URL (no ETag metadata): {}",
-                url
+                LogLevel::Warn,
+                "Failed to remove interrupted cache entry: {}",
+                e
             );
-            touch_cache_file(&cached_path)?;
-            return Ok(cached_path);
         }
     }
+    let _ = fs::remove_file(meta_path(cached_path));
+    true
+}
 
-    log!(
-        LogLevel::Info,
-        "Cache check/download path engaged for URL: {}, local_etag: {:?}",
-        url,
-        local_etag
-    );
+/// The number of cached models and their size before and after deduplication.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CacheUsage {
+    /// The number of cached model entries.
+    pub file_count: usize,
+    /// The total size of all entries, counting shared payloads once per entry.
+    pub logical_bytes: u64,
+    /// The size on disk, counting each shared payload once.
+    pub physical_bytes: u64,
+}
 
-    let temp_path = cached_path.with_extension("onnx.part");
-    let mut guard = TempFileGuard::new(&temp_path);
+/// Returns the logical and physical size of the cache.
+pub(crate) fn cache_usage() -> Result<CacheUsage, InferaError> {
+    cache_usage_in(&cache_dir())
+}
 
-    // Download or validate with retry logic
-    let max_attempts = CONFIG.http_retry_attempts;
-    let retry_delay_ms = CONFIG.http_retry_delay_ms;
-    let timeout_secs = CONFIG.http_timeout_secs;
+/// Returns the logical and physical size of the cache in `dir`.
+fn cache_usage_in(dir: &Path) -> Result<CacheUsage, InferaError> {
+    let files = cached_files_by_access_time_in(dir)?;
+    let mut payloads = HashSet::new();
+    let mut usage = CacheUsage {
+        file_count: files.len(),
+        logical_bytes: 0,
+        physical_bytes: 0,
+    };
+    for (path, _, size) in files {
+        usage.logical_bytes += size;
+        let shared = read_content_hash(&path).is_some_and(|hash| !payloads.insert(hash));
+        if !shared {
+            usage.physical_bytes += size;
+        }
+    }
+    Ok(usage)
+}
 
-    let mut last_error = None;
+/// Converts a `SystemTime` to seconds since the Unix epoch.
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
 
-    for attempt in 1..=max_attempts {
-        log!(
-            LogLevel::Debug,
-            "Download/Validation attempt {}/{} for {}",
-            attempt,
+/// Lists the cached models with their original URL, the URL they were finally downloaded
+/// from after redirects, size, and last access time.
+///
+/// Entries are ordered by access time, oldest first. Models cached before URLs were
+/// recorded have a `null` URL, and models cached before final URLs were recorded have a
+/// `null` final URL.
+pub(crate) fn list_cache() -> Result<Vec<Value>, InferaError> {
+    let files = get_cached_files_by_access_time()?;
+    Ok(files
+        .into_iter()
+        .map(|(path, accessed, size)| {
+            let meta = read_cache_meta(&path).unwrap_or(Value::Null);
+            json!({
+                "url": read_cache_url(&path),
+                "final_url": meta["final_url"].as_str(),
+                "sha256": meta["sha256"].as_str(),
+                "file": path.to_string_lossy(),
+                "size_bytes": size,
+                "last_access": unix_secs(accessed),
+            })
+        })
+        .collect())
+}
+
+/// A cached model considered for eviction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct EvictionCandidate {
+    /// The path of the cached model file.
+    path: PathBuf,
+    /// The size of the file in bytes.
+    size: u64,
+    /// The last time the file was accessed.
+    last_access: SystemTime,
+    /// When the model was downloaded, in seconds since the Unix epoch.
+    downloaded_at: u64,
+    /// How many times the cached file was reused.
+    access_count: u64,
+    /// The SHA-256 of the shared content payload, for deduplicated entries.
+    content_hash: Option<String>,
+    /// Whether a loaded model was loaded from the file, which keeps it from being evicted.
+    in_use: bool,
+}
+
+/// A cached model selected for eviction, with the bytes its removal frees on disk.
+///
+/// Entries that back a loaded model are listed where they would have been evicted, but
+/// are skipped when the plan is executed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PlannedEviction {
+    candidate: EvictionCandidate,
+    /// Zero for an entry whose shared payload is still used by a remaining entry, and for
+    /// an entry that is in use.
+    freed_bytes: u64,
+}
+
+/// Gets the eviction candidates for the cached model files in `dir`.
+///
+/// The download time and access count come from the metadata sidecar. Models cached
+/// without them fall back to the file's modification time and zero accesses. Files that
+/// a loaded model was loaded from are marked as in use.
+fn eviction_candidates_in(dir: &Path) -> Result<Vec<EvictionCandidate>, InferaError> {
+    let files = cached_files_by_access_time_in(dir)?;
+    let loaded_paths = crate::model::loaded_model_paths();
+    Ok(files
+        .into_iter()
+        .map(|(path, last_access, size)| {
+            let meta = read_cache_meta(&path).unwrap_or(Value::Null);
+            let downloaded_at = meta["downloaded_at"].as_u64().unwrap_or_else(|| {
+                fs::metadata(&path)
+                    .and_then(|m| m.modified())
+                    .map(unix_secs)
+                    .unwrap_or(0)
+            });
+            let model_path = entry_model_path(&path);
+            let in_use =
+                loaded_paths.contains(&fs::canonicalize(&model_path).unwrap_or(model_path));
+            EvictionCandidate {
+                access_count: meta["access_count"].as_u64().unwrap_or(0),
+                content_hash: meta["content_hash"].as_str().map(String::from),
+                in_use,
+                path,
+                size,
+                last_access,
+                downloaded_at,
+            }
+        })
+        .collect())
+}
+
+/// Selects the cached models to evict so that `required_space` more bytes fit within
+/// `limit`, in the order given by `strategy`.
+///
+/// This only plans the eviction and does not touch the filesystem. A deduplicated payload
+/// is counted once, and only frees space when its last referring entry is evicted.
+/// Entries in use free nothing, so the plan spills over to the next candidates, and it
+/// may not free enough space if too many entries are in use.
+fn plan_eviction(
+    mut candidates: Vec<EvictionCandidate>,
+    strategy: CacheEvictionStrategy,
+    limit: u64,
+    required_space: u64,
+) -> Vec<PlannedEviction> {
+    let mut references: HashMap<String, usize> = HashMap::new();
+    let mut current_size = 0u64;
+    for candidate in &candidates {
+        match &candidate.content_hash {
+            Some(hash) => {
+                let count = references.entry(hash.clone()).or_insert(0);
+                if *count == 0 {
+                    current_size += candidate.size;
+                }
+                *count += 1;
+            }
+            None => current_size += candidate.size,
+        }
+    }
+    if current_size + required_space <= limit {
+        return Vec::new();
+    }
+
+    // Ties are broken by access time and then by path, so the order is deterministic
+    candidates.sort_by(|a, b| {
+        let primary = match strategy {
+            CacheEvictionStrategy::LRU => std::cmp::Ordering::Equal,
+            CacheEvictionStrategy::LFU => a.access_count.cmp(&b.access_count),
+            CacheEvictionStrategy::FIFO => a.downloaded_at.cmp(&b.downloaded_at),
+        };
+        primary
+            .then(a.last_access.cmp(&b.last_access))
+            .then(a.path.cmp(&b.path))
+    });
+
+    let target_size = limit.saturating_sub(required_space);
+    let mut freed_size = 0u64;
+    let mut plan = Vec::new();
+    for candidate in candidates {
+        if current_size - freed_size <= target_size {
+            break;
+        }
+        if candidate.in_use {
+            plan.push(PlannedEviction {
+                candidate,
+                freed_bytes: 0,
+            });
+            continue;
+        }
+        let freed_bytes = match &candidate.content_hash {
+            Some(hash) => match references.get_mut(hash) {
+                Some(count) if *count > 1 => {
+                    *count -= 1;
+                    0
+                }
+                _ => candidate.size,
+            },
+            None => candidate.size,
+        };
+        freed_size += freed_bytes;
+        plan.push(PlannedEviction {
+            candidate,
+            freed_bytes,
+        });
+    }
+    plan
+}
+
+/// Removes the cache entries selected by `plan_eviction` from `dir`, skipping entries
+/// that are in use.
+fn execute_eviction(dir: &Path, plan: &[PlannedEviction]) -> Result<(), InferaError> {
+    for planned in plan.iter().filter(|planned| !planned.candidate.in_use) {
+        let freed = remove_cache_entry(dir, &planned.candidate.path, planned.candidate.size)?;
+        count(&CACHE_STATS.evictions, 1);
+        count(&CACHE_STATS.bytes_evicted, freed);
+    }
+    Ok(())
+}
+
+/// Evicts cache files with the configured strategy until cache size is below limit.
+fn evict_cache_if_needed(required_space: u64) -> Result<(), InferaError> {
+    evict_files_in(
+        &cache_dir(),
+        get_cache_size_limit(),
+        required_space,
+        CONFIG.cache_eviction_strategy,
+    )
+}
+
+/// Evicts model files in `dir`, in the order given by `strategy`, until `required_space`
+/// more bytes fit within `limit`.
+fn evict_files_in(
+    dir: &Path,
+    limit: u64,
+    required_space: u64,
+    strategy: CacheEvictionStrategy,
+) -> Result<(), InferaError> {
+    let plan = plan_eviction(
+        eviction_candidates_in(dir)?,
+        strategy,
+        limit,
+        required_space,
+    );
+    execute_eviction(dir, &plan)?;
+    let skipped = plan.iter().filter(|p| p.candidate.in_use).count();
+    if skipped > 0 && cache_usage_in(dir)?.physical_bytes + required_space > limit {
+        log!(
+            LogLevel::Warn,
+            "Could not shrink the cache to {} bytes without evicting {} cached models that loaded models use",
+            limit.saturating_sub(required_space),
+            skipped
+        );
+    }
+    Ok(())
+}
+
+/// Lists the cached models that would be evicted to make room for `required_bytes` more
+/// bytes under the current size limit and eviction strategy, without deleting anything.
+///
+/// Entries are in eviction order. Each reports its original URL, file, size, the bytes
+/// its eviction frees on disk (zero for a shared payload that is still referenced), last
+/// access time, download time, access count, and whether it is in use. Entries in use
+/// back a loaded model and are skipped instead of evicted.
+pub(crate) fn preview_eviction(required_bytes: u64) -> Result<Vec<Value>, InferaError> {
+    let plan = plan_eviction(
+        eviction_candidates_in(&cache_dir())?,
+        CONFIG.cache_eviction_strategy,
+        get_cache_size_limit(),
+        required_bytes,
+    );
+    Ok(plan
+        .into_iter()
+        .map(|planned| {
+            let candidate = planned.candidate;
+            json!({
+                "url": read_cache_url(&candidate.path),
+                "file": candidate.path.to_string_lossy(),
+                "size_bytes": candidate.size,
+                "freed_bytes": planned.freed_bytes,
+                "last_access": unix_secs(candidate.last_access),
+                "downloaded_at": candidate.downloaded_at,
+                "access_count": candidate.access_count,
+                "in_use": candidate.in_use,
+            })
+        })
+        .collect())
+}
+
+/// Sets the cache size limit at runtime and immediately evicts files above it.
+pub(crate) fn set_cache_size_limit(bytes: u64) -> Result<(), InferaError> {
+    crate::config::set_cache_size_limit(bytes);
+    log!(LogLevel::Info, "Cache size limit set to {} bytes", bytes);
+    evict_cache_if_needed(0)
+}
+
+/// The `.part` files removed by one orphan cleanup.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PartCleanup {
+    /// The number of orphaned `.part` files removed.
+    pub removed_files: usize,
+    /// The total size of the removed files in bytes.
+    pub removed_bytes: u64,
+}
+
+/// Removes orphaned `.part` files, left behind by downloads that never finished (for
+/// example, because the process crashed), from the temp and cache directories.
+///
+/// Cleanup failures are logged and ignored, since the files only take up space.
+pub(crate) fn clean_orphaned_parts() -> PartCleanup {
+    let temp_dir = temp_dir();
+    let cache_dir = cache_dir();
+    let mut dirs = vec![temp_dir.as_path()];
+    if cache_dir != temp_dir {
+        dirs.push(cache_dir.as_path());
+    }
+    let cleanup = clean_orphaned_parts_in(&dirs, ORPHANED_PART_AGE);
+    if cleanup.removed_files > 0 {
+        log!(
+            LogLevel::Info,
+            "Removed {} orphaned partial download(s) ({} bytes)",
+            cleanup.removed_files,
+            cleanup.removed_bytes
+        );
+    }
+    cleanup
+}
+
+/// Removes the `.part` files in `dirs` that no download of this process is writing and
+/// that were not modified for at least `min_age`.
+///
+/// Failed downloads kept with `INFERA_KEEP_PARTIAL` (`.part.failed`) are left alone.
+fn clean_orphaned_parts_in(dirs: &[&Path], min_age: Duration) -> PartCleanup {
+    let mut cleanup = PartCleanup::default();
+    let now = SystemTime::now();
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if !path.is_file() || path.extension().and_then(|s| s.to_str()) != Some("part") {
+                continue;
+            }
+            if ACTIVE_DOWNLOADS.lock().contains(&path) {
+                continue;
+            }
+            let Ok(metadata) = fs::metadata(&path) else {
+                continue;
+            };
+            let age = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .unwrap_or_default();
+            if age < min_age {
+                continue;
+            }
+            match fs::remove_file(&path) {
+                Ok(()) => {
+                    log!(
+                        LogLevel::Debug,
+                        "Removed orphaned partial download {:?}",
+                        path
+                    );
+                    cleanup.removed_files += 1;
+                    cleanup.removed_bytes += metadata.len();
+                }
+                Err(e) => log!(
+                    LogLevel::Warn,
+                    "Failed to remove orphaned partial download {:?}: {}",
+                    path,
+                    e
+                ),
+            }
+        }
+    }
+    cleanup
+}
+
+/// Clears the entire cache directory by deleting its contents.
+/// If the directory does not exist, this is a no-op.
+pub(crate) fn clear_cache() -> Result<(), InferaError> {
+    let dir = cache_dir();
+    if !dir.exists() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(&dir)
+        .map_err(context(
+            InferaError::IoError,
+            "cache_clear",
+            &dir.to_string_lossy(),
+        ))?
+        .flatten()
+    {
+        let path = entry.path();
+        let subject = path.to_string_lossy();
+        if path.is_file() {
+            fs::remove_file(&path).map_err(context(
+                InferaError::IoError,
+                "cache_clear",
+                &subject,
+            ))?;
+        } else if path.is_dir() {
+            fs::remove_dir_all(&path).map_err(context(
+                InferaError::IoError,
+                "cache_clear",
+                &subject,
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+/// The result of a remote model cache validation or download check.
+#[derive(Debug, PartialEq, Eq)]
+enum DownloadResult {
+    /// The remote model has not been modified on the server.
+    NotModified,
+    /// A new model was downloaded, optionally returning the server's new ETag, along with
+    /// the URL it was finally downloaded from after redirects and the hex-encoded SHA-256
+    /// of the body, computed while it was written.
+    Downloaded {
+        etag: Option<String>,
+        final_url: String,
+        sha256: String,
+    },
+}
+
+/// Handles the download and caching of a remote model from a URL.
+///
+/// If the model for the given URL is already present in the local cache, this
+/// function performs an HTTP cache validation check using the stored ETag metadata
+/// if available. If the server confirms that the model is unmodified (HTTP 304),
+/// the cached model is reused. If the server has an updated model (HTTP 200),
+/// it is downloaded, local cache is evicted if needed, and the new ETag metadata
+/// is stored.
+///
+/// If no local ETag exists but the cached model file does, it falls back to
+/// assuming the cached model is valid to prevent unnecessary server requests.
+///
+/// The cache evicts models with the strategy set by INFERA_CACHE_EVICTION (LRU by
+/// default) to stay within a configurable size limit (default 1GB, configurable via
+/// INFERA_CACHE_SIZE_LIMIT env var).
+///
+/// Downloads support automatic retries with exponential backoff.
+///
+/// # Arguments
+///
+/// * `url` - The HTTP/HTTPS URL of the ONNX model to be downloaded.
+///
+/// # Returns
+///
+/// A `Result` which is:
+/// * `Ok(PathBuf)`: The local file path of the cached model.
+/// * `Err(InferaError)`: An error indicating failure in creating the cache directory,
+///   making the HTTP request, or writing the file to disk.
+///
+/// When signature checking is enabled, the detached signature at `<url>.sig` is
+/// downloaded next to the cached model so it can be verified when the model is loaded.
+#[cfg(feature = "tract")]
+pub(crate) fn handle_remote_model(url: &str) -> Result<PathBuf, InferaError> {
+    resolve_remote_model(url, None, None).map(|cached| cached.path)
+}
+
+/// A remote model resolved to a file in the local cache.
+#[derive(Debug)]
+pub(crate) struct CachedModel {
+    /// The local path of the cached model file.
+    pub path: PathBuf,
+    /// Whether the existing cached file was reused instead of being downloaded.
+    pub from_cache: bool,
+    /// Whether the cached file was reused without contacting the server, because it was
+    /// validated within the cache TTL.
+    pub within_ttl: bool,
+    /// Whether the server confirmed that the cached file is current (`304 Not Modified`).
+    pub revalidated: bool,
+}
+
+/// Resolves a remote model like `handle_remote_model`, also reporting whether the
+/// cached file was reused.
+///
+/// `ttl_secs` overrides `INFERA_CACHE_TTL_SECS`: a cached copy validated less than this
+/// many seconds ago is returned without any network request. With `expected_sha256` set,
+/// a download whose SHA-256 differs is refused instead of cached (see `fetch_remote_model`).
+pub(crate) fn resolve_remote_model(
+    url: &str,
+    ttl_secs: Option<u64>,
+    expected_sha256: Option<&str>,
+) -> Result<CachedModel, InferaError> {
+    let options = DownloadOptions::for_url(url);
+    // Checked before the cache, so models from hosts that are no longer allowed are refused too
+    check_host_allowed(url, &options.allowed_hosts)?;
+    let ttl_secs = ttl_secs.unwrap_or(CONFIG.cache_ttl_secs);
+    let cached = fetch_remote_model(url, &options, CONFIG.cache_dedup, ttl_secs, expected_sha256)?;
+    if signature::checking_enabled() && !cached.within_ttl && !options.offline {
+        fetch_model_signature(url, &cached.path, &options);
+    }
+    Ok(cached)
+}
+
+/// Removes the cached copy of a remote model with its metadata and signature files, so
+/// the next load downloads it again.
+pub(crate) fn evict_cached_model(url: &str) -> Result<(), InferaError> {
+    let cached_path = cached_model_path(url);
+    if cached_path.exists() {
+        remove_cache_entry(&cache_dir(), &cached_path, 0)?;
+    } else {
+        let _ = fs::remove_file(meta_path(&cached_path));
+        let _ = fs::remove_file(cached_path.with_extension("etag"));
+    }
+    Ok(())
+}
+
+/// Downloads the detached signature at `<url>.sig` to the signature path of `cached_path`.
+///
+/// A missing or unreachable signature is logged and removes any stale signature file, so
+/// the model is then reported (or rejected) as unsigned when it is loaded.
+fn fetch_model_signature(url: &str, cached_path: &Path, options: &DownloadOptions) {
+    let sig_url = format!("{}.sig", url);
+    let sig_path = signature::signature_path(cached_path);
+    let result = options
+        .client()
+        .and_then(|client| {
+            client
+                .get(&sig_url)
+                .send()
+                .and_then(|response| response.error_for_status())
+                .and_then(|response| response.bytes())
+                .map_err(|e| {
+                    request_error(e).context(ErrorContext::new("signature_download", &sig_url))
+                })
+        })
+        .and_then(|bytes| {
+            fs::write(&sig_path, &bytes).map_err(context(
+                InferaError::IoError,
+                "signature_write",
+                &sig_path.to_string_lossy(),
+            ))
+        });
+    if let Err(e) = result {
+        log!(
+            LogLevel::Warn,
+            "Failed to fetch model signature {}: {}",
+            sig_url,
+            e
+        );
+        let _ = fs::remove_file(&sig_path);
+    }
+}
+
+/// Downloads a remote model into the cache, or revalidates the cached copy, and returns
+/// its local path.
+///
+/// With `dedup` set, a newly downloaded model is stored once by content (see
+/// `link_to_content`), so identical models cached from different URLs share one payload.
+/// A cached copy that was validated less than `ttl_secs` seconds ago is returned without
+/// revalidating it.
+///
+/// With `expected_sha256` set, a cached copy with a different SHA-256 is downloaded again,
+/// and a download with a different SHA-256 fails with `InferaError::ChecksumMismatch`
+/// without being retried or moved into the cache.
+///
+/// With `options.offline` set, no request is made: the cached copy is returned without
+/// revalidating it, and a model that is not cached fails with `InferaError::Offline`.
+fn fetch_remote_model(
+    url: &str,
+    options: &DownloadOptions,
+    dedup: bool,
+    ttl_secs: u64,
+    expected_sha256: Option<&str>,
+) -> Result<CachedModel, InferaError> {
+    let cache_dir = cache_dir();
+    let temp_dir = temp_dir();
+    ensure_cache_dirs()?;
+    STARTUP_CLEANUP.call_once(|| {
+        clean_orphaned_parts();
+    });
+    let hash_hex = cache_key(url);
+    let lock = fetch_lock(&hash_hex);
+    let _fetching = lock.lock();
+    let cached_path = cache_dir.join(format!("{}.onnx", hash_hex));
+    repair_cache_entry(&cache_dir, &cached_path);
+
+    let mut local_etag = None;
+    let cached_matches = match expected_sha256 {
+        Some(expected) if cached_path.exists() => {
+            let matches = file_sha256(&cached_path).is_ok_and(|actual| actual == expected);
+            if !matches {
+                log!(
+                    LogLevel::Warn,
+                    "Cached model for {} does not match the expected checksum, downloading it again",
+                    url
+                );
+            }
+            matches
+        }
+        _ => true,
+    };
+    if options.offline {
+        // Offline, the cached copy is served as is, since it cannot be revalidated
+        if !(cached_path.exists() && cached_matches) {
+            log!(
+                LogLevel::Error,
+                "Offline and not cached, refusing to download: {}",
+                url
+            );
+            count(&CACHE_STATS.misses, 1);
+            return Err(InferaError::Offline(url.to_string()));
+        }
+        log!(LogLevel::Info, "Cache hit (offline) for URL: {}", url);
+        if !meta_path(&cached_path).exists() {
+            write_cache_meta(&cached_path, url);
+        }
+        touch_cache_file(&cached_path)?;
+        count(&CACHE_STATS.hits, 1);
+        return Ok(CachedModel {
+            path: cached_path,
+            from_cache: true,
+            within_ttl: false,
+            revalidated: false,
+        });
+    }
+    if cached_path.exists() && cached_matches {
+        if validated_within(&cached_path, ttl_secs) {
+            log!(
+                LogLevel::Info,
+                "Cache hit (validated within TTL) for URL: {}",
+                url
+            );
+            touch_cache_file(&cached_path)?;
+            count(&CACHE_STATS.hits, 1);
+            return Ok(CachedModel {
+                path: cached_path,
+                from_cache: true,
+                within_ttl: true,
+                revalidated: false,
+            });
+        }
+        if let Some(etag_val) = read_cache_etag(&cached_path) {
+            local_etag = Some(etag_val);
+            log!(LogLevel::Info, "Found local ETag metadata for URL: {}", url);
+        } else {
+            log!(
+                LogLevel::Info,
+                "Cache hit for URL (no ETag metadata): {}",
+                url
+            );
+            if !meta_path(&cached_path).exists() {
+                write_cache_meta(&cached_path, url);
+            }
+            touch_cache_file(&cached_path)?;
+            count(&CACHE_STATS.hits, 1);
+            return Ok(CachedModel {
+                path: cached_path,
+                from_cache: true,
+                within_ttl: false,
+                revalidated: false,
+            });
+        }
+    }
+
+    log!(
+        LogLevel::Info,
+        "Cache check/download path engaged for URL: {}, local_etag: {:?}",
+        url,
+        local_etag
+    );
+
+    let temp_path = temp_dir.join(format!("{}.{}.onnx.part", hash_hex, unique_suffix()));
+    let mut guard = TempFileGuard::new(&temp_path, CONFIG.keep_partial);
+    let download_options = DownloadOptions {
+        cache_dir: Some(cache_dir.clone()),
+        ..options.clone()
+    };
+
+    // Download or validate with retry logic
+    let max_attempts = options.retry_attempts;
+    let retry_delay_ms = options.retry_delay_ms;
+    let deadline = (options.total_deadline_secs > 0)
+        .then(|| Instant::now() + Duration::from_secs(options.total_deadline_secs));
+
+    let mut last_error = None;
+
+    for attempt in 1..=max_attempts {
+        if let Some(deadline) = deadline {
+            if attempt > 1 && Instant::now() >= deadline {
+                return Err(deadline_exceeded(url, options, attempt - 1, last_error));
+            }
+        }
+        log!(
+            LogLevel::Debug,
+            "Download/Validation attempt {}/{} for {}",
+            attempt,
             max_attempts,
             url
         );
 
-        match download_file(url, &temp_path, timeout_secs, local_etag.as_deref()) {
+        match download_file(url, &temp_path, &download_options, local_etag.as_deref()) {
             Ok(DownloadResult::NotModified) => {
                 log!(LogLevel::Info, "Cache hit (ETag verified) for URL: {}", url);
+                mark_cache_validated(&cached_path, url);
                 touch_cache_file(&cached_path)?;
-                return Ok(cached_path);
+                count(&CACHE_STATS.hits, 1);
+                count(&CACHE_STATS.revalidations, 1);
+                return Ok(CachedModel {
+                    path: cached_path,
+                    from_cache: true,
+                    within_ttl: false,
+                    revalidated: true,
+                });
             }
-            Ok(DownloadResult::Downloaded { etag: new_etag }) => {
+            Ok(DownloadResult::Downloaded {
+                etag: new_etag,
+                final_url,
+                sha256,
+            }) => {
                 log!(LogLevel::Info, "Successfully downloaded: {}", url);
 
                 // Check file size and evict cache if needed
                 let file_size = fs::metadata(&temp_path)
-                    .map_err(|e| InferaError::IoError(e.to_string()))?
+                    .map_err(context(
+                        InferaError::IoError,
+                        "cache_write",
+                        &temp_path.to_string_lossy(),
+                    ))?
                     .len();
 
                 log!(LogLevel::Debug, "Downloaded file size: {} bytes", file_size);
-                evict_cache_if_needed(file_size)?;
-
-                fs::rename(&temp_path, &cached_path)
-                    .map_err(|e| InferaError::IoError(e.to_string()))?;
-
-                // Save new ETag metadata if provided, otherwise clean up stale metadata
-                if let Some(etag_val) = new_etag {
-                    if let Err(e) = fs::write(&etag_path, etag_val) {
-                        log!(LogLevel::Warn, "Failed to write ETag metadata: {}", e);
+                if let Some(expected) = expected_sha256 {
+                    // Retrying cannot fix a pinned checksum, and the guard deletes the download
+                    if let Err(e) = verify_sha256(url, &sha256, expected) {
+                        log!(LogLevel::Error, "Refusing to cache {}: {}", url, e);
+                        count(&CACHE_STATS.misses, 1);
+                        return Err(e);
                     }
-                } else {
-                    let _ = fs::remove_file(&etag_path);
                 }
+                evict_cache_if_needed(file_size)?;
+
+                let commit = DownloadCommit {
+                    url,
+                    final_url: &final_url,
+                    etag: new_etag.as_deref(),
+                    sha256: &sha256,
+                    dedup,
+                };
+                commit_download(
+                    &cache_dir,
+                    &temp_path,
+                    &cached_path,
+                    &commit,
+                    CommitStep::Finish,
+                )?;
 
                 guard.commit();
-                return Ok(cached_path);
+                count(&CACHE_STATS.misses, 1);
+                count(&CACHE_STATS.downloads, 1);
+                count(&CACHE_STATS.bytes_downloaded, file_size);
+                return Ok(CachedModel {
+                    path: cached_path,
+                    from_cache: false,
+                    within_ttl: false,
+                    revalidated: false,
+                });
             }
             Err(e) => {
-                log!(
-                    LogLevel::Warn,
-                    "Download/Validation attempt {}/{} failed: {}",
-                    attempt,
-                    max_attempts,
-                    e
+                let e = e.context(
+                    ErrorContext::new("download", url).with_attempt(attempt, max_attempts),
                 );
+                log!(LogLevel::Warn, "Download/Validation failed: {}", e);
+                // Retrying cannot make a model fit, so give up right away
+                if matches!(
+                    e.root(),
+                    InferaError::InsufficientDiskSpace { .. }
+                        | InferaError::DownloadTooLarge { .. }
+                ) {
+                    last_error = Some(e);
+                    break;
+                }
                 last_error = Some(e);
 
                 // Don't sleep after the last attempt
                 if attempt < max_attempts {
                     let delay = Duration::from_millis(retry_delay_ms * attempt as u64);
+                    // Sleeping past the deadline would only delay the same failure
+                    if deadline.is_some_and(|deadline| Instant::now() + delay >= deadline) {
+                        return Err(deadline_exceeded(url, options, attempt, last_error));
+                    }
                     log!(LogLevel::Debug, "Waiting {:?} before retry", delay);
                     thread::sleep(delay);
                 }
@@ -290,329 +1747,2319 @@ pub(crate) fn handle_remote_model(url: &str) -> Result<PathBuf, InferaError> {
         max_attempts,
         url
     );
+    count(&CACHE_STATS.misses, 1);
     Err(last_error.unwrap_or_else(|| InferaError::HttpRequestError("Unknown error".to_string())))
 }
 
-/// Download a file from a URL to a local path with timeout, optionally verifying via ETag.
-fn download_file(
+/// Logs and counts a download that ran out of time after `attempts` attempts, returning
+/// the error for it.
+fn deadline_exceeded(
     url: &str,
-    dest: &Path,
-    timeout_secs: u64,
-    etag: Option<&str>,
-) -> Result<DownloadResult, InferaError> {
-    let client = reqwest::blocking::Client::builder()
-        .timeout(Duration::from_secs(timeout_secs))
-        .build()
-        .map_err(|e| InferaError::HttpRequestError(e.to_string()))?;
+    options: &DownloadOptions,
+    attempts: u32,
+    last_error: Option<InferaError>,
+) -> InferaError {
+    log!(
+        LogLevel::Error,
+        "Giving up on {} after {} attempts: the {}s download deadline has passed",
+        url,
+        attempts,
+        options.total_deadline_secs
+    );
+    count(&CACHE_STATS.misses, 1);
+    InferaError::DownloadDeadlineExceeded {
+        deadline_secs: options.total_deadline_secs,
+        attempts,
+        last_error: last_error
+            .map(|e| e.to_string())
+            .unwrap_or_else(|| "Unknown error".to_string()),
+    }
+}
 
-    let mut request = client.get(url);
-    if let Some(etag_val) = etag {
-        request = request.header(reqwest::header::IF_NONE_MATCH, etag_val);
+/// The number of hex digits of the URL's SHA-256 kept in readable cache file names.
+const READABLE_HASH_LEN: usize = 8;
+
+/// The longest URL path segment kept in readable cache file names.
+const READABLE_STEM_MAX_LEN: usize = 64;
+
+/// Returns the cache file stem for a URL with the naming scheme set by INFERA_CACHE_NAMING.
+///
+/// Every cache file of a URL (the model, ETag, metadata, and `.part` files) is named from
+/// this stem, so downloads, evictions, and lookups agree on it.
+fn cache_key(url: &str) -> String {
+    cache_key_with(url, CONFIG.cache_naming)
+}
+
+/// Returns the cache file stem for a URL with the given naming scheme.
+///
+/// * `CacheNaming::Hash`: the hex-encoded SHA-256 of the URL.
+/// * `CacheNaming::Readable`: the URL's last path segment without its `.onnx` extension,
+///   with characters other than ASCII letters, digits, `-`, and `_` replaced by `_`,
+///   followed by `-` and the first 8 hex digits of the URL's SHA-256 so different URLs
+///   ending in the same file name do not collide (e.g., `resnet50-a1b2c3d4`).
+fn cache_key_with(url: &str, naming: CacheNaming) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(url.as_bytes());
+    let hash_hex = hex::encode(hasher.finalize());
+    match naming {
+        CacheNaming::Hash => hash_hex,
+        CacheNaming::Readable => {
+            format!("{}-{}", readable_stem(url), &hash_hex[..READABLE_HASH_LEN])
+        }
+    }
+}
+
+/// Returns the sanitized last path segment of a URL, or `model` if it has none.
+fn readable_stem(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let path = path.split_once("://").map_or(path, |(_, rest)| rest);
+    let segment = path
+        .split_once('/')
+        .map_or("", |(_, path)| path)
+        .rsplit('/')
+        .find(|s| !s.is_empty())
+        .unwrap_or_default();
+    let segment = segment.strip_suffix(".onnx").unwrap_or(segment);
+    let stem: String = segment
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(READABLE_STEM_MAX_LEN)
+        .collect();
+    if stem.is_empty() {
+        "model".to_string()
+    } else {
+        stem
+    }
+}
+
+/// Returns the path of the cached model file for a URL.
+pub(crate) fn cached_model_path(url: &str) -> PathBuf {
+    cache_dir().join(format!("{}.onnx", cache_key(url)))
+}
+
+/// Returns the ETag stored for the cached copy of a remote model, if there is one.
+pub(crate) fn cached_etag(url: &str) -> Option<String> {
+    read_cache_etag(&cached_model_path(url))
+}
+
+/// Asks the server whether a remote model has changed since the copy with `etag` was
+/// downloaded, without downloading it.
+///
+/// A conditional `HEAD` request is sent with `If-None-Match`, falling back to a
+/// conditional `GET` (whose body is not read) if the server does not allow `HEAD`. The
+/// model is unchanged if the server answers 304, or answers 2xx with the same ETag.
+///
+/// # Returns
+///
+/// * `Ok(true)` if the remote model has changed.
+/// * `Ok(false)` if it has not.
+/// * `Err(InferaError)` if Infera is offline, the request fails, or the server returns an
+///   error status.
+pub(crate) fn check_remote_updated(url: &str, etag: &str) -> Result<bool, InferaError> {
+    let options = DownloadOptions::for_url(url);
+    check_host_allowed(url, &options.allowed_hosts)?;
+    if options.offline {
+        return Err(InferaError::Offline(url.to_string()));
+    }
+    let client = options.client()?;
+    let send = |method: reqwest::Method| {
+        client
+            .request(method, url)
+            .header(reqwest::header::IF_NONE_MATCH, etag)
+            .send()
+            .map_err(|e| request_error(e).context(ErrorContext::new("update_check", url)))
+    };
+
+    let mut response = send(reqwest::Method::HEAD)?;
+    if matches!(
+        response.status(),
+        reqwest::StatusCode::METHOD_NOT_ALLOWED | reqwest::StatusCode::NOT_IMPLEMENTED
+    ) {
+        response = send(reqwest::Method::GET)?;
+    }
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(false);
+    }
+    let response = response.error_for_status().map_err(context(
+        InferaError::HttpRequestError,
+        "update_check",
+        url,
+    ))?;
+    let remote_etag = response
+        .headers()
+        .get(reqwest::header::ETAG)
+        .and_then(|v| v.to_str().ok());
+    log!(
+        LogLevel::Debug,
+        "Freshness check for {}: local ETag {}, remote ETag {:?}",
+        url,
+        etag,
+        remote_etag
+    );
+    Ok(remote_etag != Some(etag))
+}
+
+/// Downloads a model to `dest` without using the cache, returning its size in bytes.
+///
+/// The download is written to a `.part` file next to `dest` and renamed over it once
+/// complete, so `dest` is never left partially written. Failed attempts are retried like
+/// cached downloads, but no ETag is sent, nothing in the cache is evicted or counted, and
+/// the size limit of the cache does not apply (only the free disk space check does).
+///
+/// # Returns
+///
+/// * `Ok(u64)` containing the size of the downloaded file.
+/// * `Err(InferaError)` if Infera is offline, `dest` has no file name, or every attempt
+///   fails.
+pub(crate) fn download_model_to(url: &str, dest: &Path) -> Result<u64, InferaError> {
+    // The destination directory is not a cache, so nothing in it is evicted
+    let options = DownloadOptions {
+        cache_size_limit: u64::MAX,
+        cache_dir: None,
+        evict: false,
+        ..DownloadOptions::for_url(url)
+    };
+    if options.offline {
+        return Err(InferaError::Offline(url.to_string()));
+    }
+    let subject = dest.to_string_lossy();
+    let file_name = dest.file_name().ok_or_else(|| {
+        InferaError::IoError("destination has no file name".to_string())
+            .context(ErrorContext::new("download_write", subject.clone()))
+    })?;
+    let mut part_name = file_name.to_os_string();
+    part_name.push(format!(".{}.part", unique_suffix()));
+    let temp_path = dest.with_file_name(part_name);
+    let mut guard = TempFileGuard::new(&temp_path, CONFIG.keep_partial);
+
+    let max_attempts = options.retry_attempts;
+    let deadline = (options.total_deadline_secs > 0)
+        .then(|| Instant::now() + Duration::from_secs(options.total_deadline_secs));
+    let mut last_error: Option<InferaError> = None;
+    for attempt in 1..=max_attempts {
+        if attempt > 1 {
+            let delay = Duration::from_millis(options.retry_delay_ms * (attempt - 1) as u64);
+            if deadline.is_some_and(|deadline| Instant::now() + delay >= deadline) {
+                return Err(InferaError::DownloadDeadlineExceeded {
+                    deadline_secs: options.total_deadline_secs,
+                    attempts: attempt - 1,
+                    last_error: last_error
+                        .map(|e| e.to_string())
+                        .unwrap_or_else(|| "Unknown error".to_string()),
+                });
+            }
+            thread::sleep(delay);
+        }
+        match download_file(url, &temp_path, &options, None) {
+            Ok(_) => {
+                let size = fs::metadata(&temp_path)
+                    .map_err(context(InferaError::IoError, "download_write", &subject))?
+                    .len();
+                fs::rename(&temp_path, dest).map_err(context(
+                    InferaError::IoError,
+                    "download_write",
+                    &subject,
+                ))?;
+                guard.commit();
+                log!(LogLevel::Info, "Downloaded {} to {:?}", url, dest);
+                return Ok(size);
+            }
+            Err(e) => {
+                let e = e.context(
+                    ErrorContext::new("download", url).with_attempt(attempt, max_attempts),
+                );
+                log!(LogLevel::Warn, "Download failed: {}", e);
+                let fatal = matches!(
+                    e.root(),
+                    InferaError::InsufficientDiskSpace { .. }
+                        | InferaError::DownloadTooLarge { .. }
+                );
+                last_error = Some(e);
+                if fatal {
+                    break;
+                }
+            }
+        }
+    }
+    Err(last_error.unwrap_or_else(|| InferaError::HttpRequestError("Unknown error".to_string())))
+}
+
+/// Returns the media type of a Content-Type header value, without parameters, in lowercase.
+fn media_type(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase()
+}
+
+/// Returns how many bytes a download into `dir` may write, after making room for it.
+///
+/// When the server reports the model size (`content_length`), models larger than the cache
+/// size limit are refused, least recently used models are evicted to make room for it, and
+/// the remaining free disk space (less `min_free_disk_bytes`) is checked before anything is
+/// written. If the disk is too full for the download even within the cache limit, more
+/// models are evicted until the free space suffices or the cache is empty. The returned
+/// budget caps the stream, so a download whose size was not reported is aborted once it
+/// outgrows the available space.
+fn reserve_download_space(
+    dir: &Path,
+    content_length: Option<u64>,
+    options: &DownloadOptions,
+) -> Result<u64, InferaError> {
+    reserve_download_space_with(dir, content_length, options, |dir| {
+        fs2::available_space(dir)
+    })
+}
+
+/// Reserves space for a download like `reserve_download_space`, reading the free space of
+/// the filesystem holding `dir` with `available_space`.
+fn reserve_download_space_with(
+    dir: &Path,
+    content_length: Option<u64>,
+    options: &DownloadOptions,
+    available_space: impl Fn(&Path) -> io::Result<u64>,
+) -> Result<u64, InferaError> {
+    if let Some(needed) = content_length {
+        if needed > options.cache_size_limit {
+            return Err(InferaError::InsufficientDiskSpace {
+                needed,
+                available: options.cache_size_limit,
+            });
+        }
+        if options.evict {
+            evict_files_in(
+                dir,
+                options.cache_size_limit,
+                needed,
+                CONFIG.cache_eviction_strategy,
+            )?;
+        }
+    }
+
+    let read_budget = || -> Result<u64, InferaError> {
+        let free = available_space(dir).map_err(context(
+            InferaError::IoError,
+            "disk_space",
+            &dir.to_string_lossy(),
+        ))?;
+        Ok(free
+            .saturating_sub(options.min_free_disk_bytes)
+            .min(options.cache_size_limit))
+    };
+    let mut budget = read_budget()?;
+    if let Some(needed) = content_length.filter(|&needed| needed > budget) {
+        if options.evict {
+            // The disk is fuller than the cache limit accounts for
+            let shortfall = needed - budget;
+            log!(
+                LogLevel::Info,
+                "Evicting cached models to free {} bytes of disk space for a download",
+                shortfall
+            );
+            let cached_bytes = cache_usage_in(dir)?.physical_bytes;
+            evict_files_in(
+                dir,
+                cached_bytes.saturating_sub(shortfall),
+                0,
+                CONFIG.cache_eviction_strategy,
+            )?;
+            budget = read_budget()?;
+        }
+    }
+    match content_length {
+        Some(needed) if needed > budget => Err(InferaError::InsufficientDiskSpace {
+            needed,
+            available: budget,
+        }),
+        _ => Ok(budget),
+    }
+}
+
+/// Download a file from a URL to a local path with connect and overall timeouts, optionally
+/// verifying via ETag.
+///
+/// The size check of `reserve_download_space` runs before the body is streamed.
+fn download_file(
+    url: &str,
+    dest: &Path,
+    options: &DownloadOptions,
+    etag: Option<&str>,
+) -> Result<DownloadResult, InferaError> {
+    check_host_allowed(url, &options.allowed_hosts)?;
+    let client = options.client()?;
+
+    let mut request = client.get(url);
+    if let Some(etag_val) = etag {
+        request = request.header(reqwest::header::IF_NONE_MATCH, etag_val);
+    }
+
+    let mut response = request.send().map_err(request_error)?;
+    let final_url = response.url().to_string();
+
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(DownloadResult::NotModified);
+    }
+
+    response = response
+        .error_for_status()
+        .map_err(|e| InferaError::HttpRequestError(e.to_string()))?;
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string());
+    log!(
+        LogLevel::Debug,
+        "Received Content-Type {:?} for {}",
+        content_type,
+        url
+    );
+    if options.require_octet_stream {
+        let accepted = content_type
+            .as_deref()
+            .map(media_type)
+            .is_some_and(|m| ACCEPTED_CONTENT_TYPES.contains(&m.as_str()));
+        if !accepted {
+            return Err(InferaError::UnexpectedContentType(
+                content_type.unwrap_or_else(|| "<missing>".to_string()),
+            ));
+        }
+    }
+
+    let new_etag = response
+        .headers()
+        .get(reqwest::header::ETAG)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string());
+
+    let dir = options.cache_dir.as_deref().unwrap_or_else(|| {
+        dest.parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."))
+    });
+    let max_bytes = (options.max_download_bytes > 0).then_some(options.max_download_bytes);
+    if let (Some(limit), Some(reported)) = (max_bytes, response.content_length()) {
+        if reported > limit {
+            return Err(InferaError::DownloadTooLarge {
+                received: reported,
+                limit,
+            });
+        }
+    }
+    let budget = reserve_download_space(dir, response.content_length(), options)?;
+    let cap = max_bytes.map_or(budget, |limit| limit.min(budget));
+
+    let subject = dest.to_string_lossy();
+    let file =
+        File::create(dest).map_err(context(InferaError::IoError, "download_write", &subject))?;
+    let mut writer = HashingWriter::new(file);
+    let written = io::copy(
+        &mut (&mut response).take(cap.saturating_add(1)),
+        &mut writer,
+    )
+    .map_err(context(InferaError::IoError, "download_write", &subject))?;
+    if written > cap {
+        drop(writer);
+        let _ = fs::remove_file(dest);
+        return Err(match max_bytes {
+            Some(limit) if limit <= budget => InferaError::DownloadTooLarge {
+                received: written,
+                limit,
+            },
+            _ => InferaError::InsufficientDiskSpace {
+                needed: written,
+                available: budget,
+            },
+        });
+    }
+
+    Ok(DownloadResult::Downloaded {
+        etag: new_etag,
+        final_url,
+        sha256: writer.finish(),
+    })
+}
+
+/// A writer that computes the SHA-256 of everything written through it.
+struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W: Write> HashingWriter<W> {
+    /// Wraps `inner`, starting with an empty hash.
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+        }
+    }
+
+    /// Returns the hex-encoded SHA-256 of the bytes written.
+    fn finish(self) -> String {
+        hex::encode(self.hasher.finalize())
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server;
+    #[cfg(feature = "tract")]
+    use std::env; // moved here: used in tests only
+    use std::thread;
+    #[cfg(feature = "tract")]
+    use tiny_http::Header;
+    use tiny_http::{Response, Server as TinyServer};
+
+    /// Returns the `.part` files of downloads of `url` in the temp directory.
+    fn part_files(url: &str) -> Vec<PathBuf> {
+        let prefix = format!("{}.", cache_key(url));
+        fs::read_dir(temp_dir())
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| {
+                        path.file_name()
+                            .and_then(|name| name.to_str())
+                            .is_some_and(|name| {
+                                name.starts_with(&prefix) && name.ends_with(".part")
+                            })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_ensure_dir_tolerates_concurrent_creation() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("a").join("b").join("c");
+        let handles: Vec<_> = (0..16)
+            .map(|_| {
+                let dir = dir.clone();
+                thread::spawn(move || ensure_dir(&dir))
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap().unwrap();
+        }
+        assert!(dir.is_dir());
+
+        // A file in the way is still an error
+        let file = root.path().join("file");
+        fs::write(&file, b"").unwrap();
+        let err = ensure_dir(&file).unwrap_err();
+        assert!(
+            matches!(err.root(), InferaError::CacheDirError(_)),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_concurrent_first_fetches_download_once() {
+        let mut server = Server::new();
+        let download = server
+            .mock("GET", "/concurrent_first.onnx")
+            .with_status(200)
+            .with_header("ETag", "\"v1\"")
+            .with_body("onnxdata")
+            .expect(1)
+            .create();
+        let url = format!("{}/concurrent_first.onnx", server.url());
+        evict_cached_model(&url).unwrap();
+
+        let handles: Vec<_> = (0..16)
+            .map(|_| {
+                let url = url.clone();
+                thread::spawn(move || {
+                    fetch_remote_model(&url, &DownloadOptions::from_config(), false, 3600, None)
+                })
+            })
+            .collect();
+        let fetched: Vec<CachedModel> = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap().unwrap())
+            .collect();
+
+        // One thread downloads, and the others wait for it and then reuse its copy
+        assert_eq!(fetched.iter().filter(|c| !c.from_cache).count(), 1);
+        assert!(fetched.iter().all(|c| c.path == cached_model_path(&url)));
+        assert_eq!(fs::read(cached_model_path(&url)).unwrap(), b"onnxdata");
+        assert!(part_files(&url).is_empty());
+        download.assert();
+        evict_cached_model(&url).unwrap();
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_handle_remote_model_cleanup_on_incomplete_download() {
+        let server = TinyServer::http("127.0.0.1:0").unwrap();
+        let port = server.server_addr().to_ip().unwrap().port();
+        let model_url = format!("http://127.0.0.1:{}/incomplete_model.onnx", port);
+
+        let server_handle = thread::spawn(move || {
+            if let Ok(request) = server.recv() {
+                let mut response = Response::from_string("incomplete data");
+                let header = Header::from_bytes(&b"Content-Length"[..], &b"100"[..]).unwrap();
+                response.add_header(header);
+                let _ = request.respond(response);
+            }
+        });
+
+        // The download should fail because the response body is shorter than the content-length.
+        let result = handle_remote_model(&model_url);
+        assert!(result.is_err());
+
+        // Ensure no partial or final file is left in the cache.
+        let cache_dir = env::temp_dir().join("infera_cache");
+        let mut hasher = Sha256::new();
+        hasher.update(model_url.as_bytes());
+        let hash_hex = hex::encode(hasher.finalize());
+        let cached_path = cache_dir.join(format!("{}.onnx", hash_hex));
+        let temp_path = cached_path.with_extension("onnx.part");
+
+        assert!(!cached_path.exists(), "Final cache file should not exist");
+        assert!(!temp_path.exists(), "Partial file should be cleaned up");
+
+        server_handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_temp_file_guard_keeps_failed_partial_when_requested() {
+        let dir = tempfile::tempdir().unwrap();
+        let temp_path = dir.path().join("model.onnx.part");
+
+        fs::write(&temp_path, b"corrupt bytes").unwrap();
+        drop(TempFileGuard::new(&temp_path, false));
+        assert!(!temp_path.exists());
+        assert!(!failed_path(&temp_path).exists());
+
+        fs::write(&temp_path, b"corrupt bytes").unwrap();
+        drop(TempFileGuard::new(&temp_path, true));
+        assert!(!temp_path.exists());
+        let kept_path = dir.path().join("model.onnx.part.failed");
+        assert_eq!(fs::read(&kept_path).unwrap(), b"corrupt bytes");
+
+        // Committed files are left alone either way.
+        fs::write(&temp_path, b"complete").unwrap();
+        let mut guard = TempFileGuard::new(&temp_path, true);
+        guard.commit();
+        drop(guard);
+        assert!(temp_path.exists());
+    }
+
+    #[test]
+    fn test_clean_orphaned_parts_removes_only_stale_unowned_parts() {
+        let temp = tempfile::tempdir().unwrap();
+        let cache = tempfile::tempdir().unwrap();
+        let stale =
+            filetime::FileTime::from_system_time(SystemTime::now() - Duration::from_secs(3600));
+        let orphan = temp.path().join("orphan.onnx.part");
+        let old_cache_orphan = cache.path().join("old.onnx.part");
+        let active = temp.path().join("active.onnx.part");
+        let recent = temp.path().join("recent.onnx.part");
+        let kept_failure = temp.path().join("failed.onnx.part.failed");
+        let model = cache.path().join("model.onnx");
+        for path in [
+            &orphan,
+            &old_cache_orphan,
+            &active,
+            &recent,
+            &kept_failure,
+            &model,
+        ] {
+            fs::write(path, b"12345").unwrap();
+            if path != &recent {
+                filetime::set_file_mtime(path, stale).unwrap();
+            }
+        }
+
+        let guard = TempFileGuard::new(&active, false);
+        let cleanup =
+            clean_orphaned_parts_in(&[temp.path(), cache.path()], Duration::from_secs(60));
+        assert_eq!(
+            cleanup,
+            PartCleanup {
+                removed_files: 2,
+                removed_bytes: 10,
+            }
+        );
+        assert!(!orphan.exists());
+        assert!(!old_cache_orphan.exists());
+        assert!(
+            active.exists(),
+            "a download in progress must not be removed"
+        );
+        assert!(
+            recent.exists(),
+            "a recently written part may belong to another process"
+        );
+        assert!(kept_failure.exists());
+        assert!(model.exists());
+        drop(guard);
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_handle_remote_model_download_error() {
+        // Simulate a server error instead of an interrupted download,
+        // as it's more reliable to test the error handling path.
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/server_error_model.onnx")
+            .with_status(500)
+            .create();
+
+        let url = server.url();
+        let model_url = format!("{}/server_error_model.onnx", url);
+
+        let result = handle_remote_model(&model_url);
+
+        // The download should fail because of the server error.
+        assert!(
+            result.is_err(),
+            "handle_remote_model should return an error on 500 status"
+        );
+
+        // Ensure no partial or final file is left in the cache.
+        let cache_dir = env::temp_dir().join("infera_cache");
+        let mut hasher = Sha256::new();
+        hasher.update(model_url.as_bytes());
+        let hash_hex = hex::encode(hasher.finalize());
+        let cached_path = cache_dir.join(format!("{}.onnx", hash_hex));
+        let temp_path = cached_path.with_extension("onnx.part");
+
+        assert!(!cached_path.exists(), "Final cache file should not exist");
+        assert!(!temp_path.exists(), "Partial file should be cleaned up");
+    }
+
+    #[test]
+    fn test_failed_download_error_has_url_and_attempts() {
+        let mut server = Server::new();
+        let m = server
+            .mock("GET", "/context_error_model.onnx")
+            .with_status(500)
+            .expect(2)
+            .create();
+        let url = format!("{}/context_error_model.onnx", server.url());
+        let options = DownloadOptions {
+            retry_attempts: 2,
+            retry_delay_ms: 1,
+            ..DownloadOptions::from_config()
+        };
+
+        let err = fetch_remote_model(&url, &options, false, 0, None).unwrap_err();
+        let message = err.to_string();
+        assert!(
+            message.starts_with(&format!("download {} (attempt 2 of 2): ", url)),
+            "{}",
+            message
+        );
+        assert!(matches!(err.root(), InferaError::HttpRequestError(_)));
+        let json = err.to_json();
+        assert_eq!(json["context"][0]["operation"], "download");
+        assert_eq!(json["context"][0]["subject"], url);
+        assert_eq!(json["context"][0]["attempt"], 2);
+        assert_eq!(json["context"][0]["attempts"], 2);
+        m.assert();
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_handle_remote_model_cleanup_on_connection_drop() {
+        let server = TinyServer::http("127.0.0.1:0").unwrap();
+        let port = server.server_addr().to_ip().unwrap().port();
+        let model_url = format!("http://127.0.0.1:{}/dropped_connection.onnx", port);
+
+        let server_handle = thread::spawn(move || {
+            if let Ok(request) = server.recv() {
+                // By responding with a Content-Length header but then dropping the
+                // request without sending a body, we simulate a connection drop.
+                // reqwest will receive the headers and expect a body, but the
+                // connection will be closed prematurely, resulting in an I/O error.
+                let response = Response::empty(200)
+                    .with_header(Header::from_bytes(&b"Content-Length"[..], &b"1024"[..]).unwrap());
+                // The `respond` call sends the headers, but the `Request` object
+                // is dropped immediately after, closing the connection.
+                let _ = request.respond(response);
+            }
+        });
+
+        // The download should fail because the server closes the connection prematurely.
+        let result = handle_remote_model(&model_url);
+        assert!(
+            result.is_err(),
+            "The download should fail on a connection drop"
+        );
+
+        // After the failure, the temporary file should be cleaned up.
+        let cache_dir = env::temp_dir().join("infera_cache");
+        let mut hasher = Sha256::new();
+        hasher.update(model_url.as_bytes());
+        let hash_hex = hex::encode(hasher.finalize());
+        let cached_path = cache_dir.join(format!("{}.onnx", hash_hex));
+        let temp_path = cached_path.with_extension("onnx.part");
+
+        assert!(!cached_path.exists(), "Final cache file should not exist");
+        assert!(
+            !temp_path.exists(),
+            "Partial file should be cleaned up after a connection drop"
+        );
+
+        server_handle.join().unwrap();
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_handle_remote_model_success_and_cache() {
+        // Serve a small body with an accurate Content-Length
+        let mut server = Server::new();
+        let body = b"onnxdata".to_vec();
+        let _m = server
+            .mock("GET", "/ok_model.onnx")
+            .with_status(200)
+            .with_header("Content-Length", &body.len().to_string())
+            .with_body(body.clone())
+            .create();
+        let url = format!("{}/ok_model.onnx", server.url());
+
+        let path1 = handle_remote_model(&url).expect("download should succeed");
+        assert!(path1.exists(), "cached file must exist");
+        let content1 = fs::read(&path1).expect("read cached file");
+        assert_eq!(content1, body);
+
+        // Second call should hit cache and return same path without network
+        let path2 = handle_remote_model(&url).expect("cache should hit");
+        assert_eq!(path1, path2);
+        let temp_path = path1.with_extension("onnx.part");
+        assert!(!temp_path.exists(), "no partial file should remain");
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_handle_remote_model_etag_verified_304() {
+        let mut server = Server::new();
+        let body = b"onnxdata".to_vec();
+
+        // Use a path unique to this test so the URL hash never collides with other
+        // tests that mockito may schedule on the same port (OS port reuse).
+        // 1. Initial request (no ETag matched) returns 200 with ETag "tag1"
+        let m1 = server
+            .mock("GET", "/ok_model_etag_304.onnx")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_header("ETag", "tag1")
+            .with_body(body.clone())
+            .create();
+
+        // 2. Subsequent request (with If-None-Match: tag1) returns 304 Not Modified
+        let m2 = server
+            .mock("GET", "/ok_model_etag_304.onnx")
+            .match_header("if-none-match", "tag1")
+            .with_status(304)
+            .create();
+
+        let url = format!("{}/ok_model_etag_304.onnx", server.url());
+
+        // First download creates the file and records the ETag in its metadata
+        let path1 = handle_remote_model(&url).expect("initial download should succeed");
+        assert!(path1.exists(), "cached file must exist");
+        let content1 = fs::read(&path1).expect("read cached file");
+        assert_eq!(content1, body);
+
+        let meta = read_cache_meta(&path1).expect("metadata must exist");
+        assert_eq!(meta["etag"], "tag1");
+        assert!(meta.get("pending").is_none());
+        assert!(!path1.with_extension("etag").exists());
+
+        // Second check hits the server, gets 304, and reuses the cached file
+        let path2 = handle_remote_model(&url).expect("validation should succeed");
+        assert_eq!(path1, path2);
+
+        m1.assert();
+        m2.assert();
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_resolve_remote_model_verifies_expected_checksum() {
+        let mut server = Server::new();
+        let download = server
+            .mock("GET", "/checksum_model.onnx")
+            .with_status(200)
+            .with_body("onnxdata")
+            .expect(3)
+            .create();
+        let url = format!("{}/checksum_model.onnx", server.url());
+        evict_cached_model(&url).unwrap();
+        let expected = bytes_sha256(b"onnxdata");
+        let wrong = "0".repeat(64);
+
+        // A mismatching download is neither cached nor left behind as a partial file
+        let err = resolve_remote_model(&url, Some(3600), Some(&wrong)).unwrap_err();
+        match err {
+            InferaError::ChecksumMismatch {
+                expected: e,
+                actual,
+                ..
+            } => {
+                assert_eq!(e, wrong);
+                assert_eq!(actual, expected);
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        assert!(!cached_model_path(&url).exists());
+        assert!(part_files(&url).is_empty());
+
+        // A matching download is cached, and the verified copy is then reused
+        let first = resolve_remote_model(&url, Some(3600), Some(&expected)).unwrap();
+        assert!(!first.from_cache);
+        let cached = resolve_remote_model(&url, Some(3600), Some(&expected)).unwrap();
+        assert!(cached.from_cache && cached.within_ttl);
+
+        // A cached copy that does not match is downloaded again, and refused again
+        assert!(resolve_remote_model(&url, Some(3600), Some(&wrong)).is_err());
+        download.assert();
+
+        assert!(matches!(
+            parse_sha256("xyz"),
+            Err(InferaError::InvalidChecksum(_))
+        ));
+        assert_eq!(parse_sha256(&expected.to_uppercase()).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_resolve_remote_model_skips_revalidation_within_ttl() {
+        let mut server = Server::new();
+        let download = server
+            .mock("GET", "/ok_model_cache_ttl.onnx")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_header("ETag", "ttl1")
+            .with_body("onnxdata")
+            .expect(1)
+            .create();
+        let revalidate = server
+            .mock("GET", "/ok_model_cache_ttl.onnx")
+            .match_header("if-none-match", "ttl1")
+            .with_status(304)
+            .expect(2)
+            .create();
+        let url = format!("{}/ok_model_cache_ttl.onnx", server.url());
+        evict_cached_model(&url).unwrap();
+
+        let first = resolve_remote_model(&url, Some(3600), None).unwrap();
+        assert!(!first.from_cache);
+        let set_validated_at = |secs: u64| {
+            let mut meta = read_cache_meta(&first.path).unwrap();
+            meta["validated_at"] = json!(secs);
+            fs::write(meta_path(&first.path), meta.to_string()).unwrap();
+        };
+
+        // Within the TTL the cached file is returned without any request
+        let cached = resolve_remote_model(&url, Some(3600), None).unwrap();
+        assert!(cached.from_cache && cached.within_ttl);
+        assert_eq!(cached.path, first.path);
+
+        // After the TTL the copy is revalidated once, which restarts the TTL
+        set_validated_at(0);
+        let revalidated = resolve_remote_model(&url, Some(3600), None).unwrap();
+        assert!(revalidated.from_cache && !revalidated.within_ttl);
+        assert!(
+            resolve_remote_model(&url, Some(3600), None)
+                .unwrap()
+                .within_ttl
+        );
+
+        // An infinite TTL never revalidates, and a TTL of 0 always does
+        set_validated_at(0);
+        assert!(
+            resolve_remote_model(&url, Some(u64::MAX), None)
+                .unwrap()
+                .within_ttl
+        );
+        assert!(
+            !resolve_remote_model(&url, Some(0), None)
+                .unwrap()
+                .within_ttl
+        );
+
+        download.assert();
+        revalidate.assert();
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_handle_remote_model_etag_changed_200() {
+        let mut server = Server::new();
+        let body1 = b"onnxdata1".to_vec();
+        let body2 = b"onnxdata2".to_vec();
+
+        // Use a path unique to this test so the URL hash never collides with other
+        // tests that mockito may schedule on the same port (OS port reuse).
+        // 1. Initial request returns 200 with ETag "tag1" and body1
+        let m1 = server
+            .mock("GET", "/ok_model_etag_200.onnx")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_header("ETag", "tag1")
+            .with_body(body1.clone())
+            .create();
+
+        // 2. Subsequent request (with If-None-Match: tag1) returns 200 with ETag "tag2" and body2
+        let m2 = server
+            .mock("GET", "/ok_model_etag_200.onnx")
+            .match_header("if-none-match", "tag1")
+            .with_status(200)
+            .with_header("ETag", "tag2")
+            .with_body(body2.clone())
+            .create();
+
+        let url = format!("{}/ok_model_etag_200.onnx", server.url());
+
+        // First download gets body1
+        let path1 = handle_remote_model(&url).expect("initial download should succeed");
+        let content1 = fs::read(&path1).expect("read cached file");
+        assert_eq!(content1, body1);
+
+        // Second validation check gets 200 and downloads body2
+        let path2 = handle_remote_model(&url).expect("updated download should succeed");
+        assert_eq!(path1, path2);
+        let content2 = fs::read(&path2).expect("read updated file");
+        assert_eq!(content2, body2);
+        assert_eq!(cached_etag(&url).as_deref(), Some("tag2"));
+
+        m1.assert();
+        m2.assert();
+    }
+
+    #[test]
+    fn test_check_remote_updated_compares_etags() {
+        let mut server = Server::new();
+        let url = format!("{}/freshness_check.onnx", server.url());
+
+        let unchanged = server
+            .mock("HEAD", "/freshness_check.onnx")
+            .match_header("if-none-match", "tag1")
+            .with_status(304)
+            .create();
+        assert!(!check_remote_updated(&url, "tag1").unwrap());
+        unchanged.assert();
+        unchanged.remove();
+
+        // The remote now serves a new version with a different ETag
+        let changed = server
+            .mock("HEAD", "/freshness_check.onnx")
+            .with_status(200)
+            .with_header("ETag", "tag2")
+            .create();
+        assert!(check_remote_updated(&url, "tag1").unwrap());
+        assert!(!check_remote_updated(&url, "tag2").unwrap());
+        changed.remove();
+
+        // Servers that reject HEAD are asked with a conditional GET instead
+        let head_rejected = server
+            .mock("HEAD", "/freshness_check.onnx")
+            .with_status(405)
+            .create();
+        let get = server
+            .mock("GET", "/freshness_check.onnx")
+            .match_header("if-none-match", "tag2")
+            .with_status(304)
+            .create();
+        assert!(!check_remote_updated(&url, "tag2").unwrap());
+        head_rejected.assert();
+        get.assert();
+
+        let missing = format!("{}/missing_model.onnx", server.url());
+        let not_found = server
+            .mock("HEAD", "/missing_model.onnx")
+            .with_status(404)
+            .create();
+        assert!(check_remote_updated(&missing, "tag1").is_err());
+        not_found.assert();
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_interrupted_download_commit_is_repaired_on_next_load() {
+        let mut server = Server::new();
+        let body = b"committed".to_vec();
+        let download = server
+            .mock("GET", "/interrupted_commit.onnx")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_header("ETag", "v1")
+            .with_body(body.clone())
+            .expect(5)
+            .create();
+        let url = format!("{}/interrupted_commit.onnx", server.url());
+        let _ = evict_cached_model(&url);
+        let path = handle_remote_model(&url).expect("initial download should succeed");
+        let temp_path = path.with_extension("commit_test");
+        let commit = DownloadCommit {
+            url: &url,
+            final_url: &url,
+            etag: Some("v2"),
+            sha256: "",
+            dedup: false,
+        };
+
+        // A crash after any step but the last leaves a pending entry, which is neither
+        // revalidated with its ETag nor served, but downloaded again
+        for step in [CommitStep::Intent, CommitStep::Move, CommitStep::Link] {
+            fs::write(&temp_path, b"interrupted").unwrap();
+            commit_download(&cache_dir(), &temp_path, &path, &commit, step).unwrap();
+            assert_eq!(cached_etag(&url), None, "{:?}", step);
+
+            let repaired = handle_remote_model(&url).expect("reload should succeed");
+            assert_eq!(fs::read(&repaired).unwrap(), body, "{:?}", step);
+            let meta = read_cache_meta(&repaired).unwrap();
+            assert_eq!(meta["etag"], "v1");
+            assert!(meta.get("pending").is_none());
+        }
+        let _ = fs::remove_file(&temp_path);
+
+        // A complete commit replaces the ETag stored by older versions in a `.etag` file
+        fs::write(path.with_extension("etag"), "legacy").unwrap();
+        fs::remove_file(meta_path(&path)).unwrap();
+        assert_eq!(cached_etag(&url).as_deref(), Some("legacy"));
+        fs::write(&temp_path, b"finished").unwrap();
+        commit_download(&cache_dir(), &temp_path, &path, &commit, CommitStep::Finish).unwrap();
+        assert_eq!(cached_etag(&url).as_deref(), Some("v2"));
+        assert_eq!(fs::read(&path).unwrap(), b"finished");
+        assert!(!path.with_extension("etag").exists());
+        assert!(!temp_path.exists());
+
+        // Evicting the entry forces the fifth download
+        evict_cached_model(&url).unwrap();
+        handle_remote_model(&url).expect("download after eviction should succeed");
+        download.assert();
+        let _ = evict_cached_model(&url);
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_handle_remote_model_no_etag_support() {
+        let mut server = Server::new();
+        let body = b"onnxdata".to_vec();
+
+        // Server does not return ETag
+        let m = server
+            .mock("GET", "/no_etag_model.onnx")
+            .with_status(200)
+            .with_body(body.clone())
+            .create();
+
+        let url = format!("{}/no_etag_model.onnx", server.url());
+
+        // First download succeeds but no ETag is recorded
+        let path1 = handle_remote_model(&url).expect("download should succeed");
+        assert!(path1.exists());
+        let content1 = fs::read(&path1).expect("read cached file");
+        assert_eq!(content1, body);
+        assert_eq!(
+            cached_etag(&url),
+            None,
+            "etag metadata should not be created"
+        );
+
+        // Second download immediately treats it as a fast-path cache hit (no server request)
+        let path2 = handle_remote_model(&url).expect("fast cache hit should succeed");
+        assert_eq!(path1, path2);
+
+        // Verify that the server mock was only called once
+        m.assert();
+    }
+
+    #[test]
+    fn test_unpacked_bundles_count_toward_cache_and_are_evicted() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.onnx"), vec![0u8; 100]).unwrap();
+        let bundles = dir.path().join(bundle::BUNDLES_DIR);
+        let unpacked = bundles.join("abc123");
+        fs::create_dir_all(&unpacked).unwrap();
+        fs::write(unpacked.join(bundle::MODEL_FILE), vec![0u8; 150]).unwrap();
+        fs::write(unpacked.join("manifest.json"), vec![b' '; 50]).unwrap();
+        // A bundle that is still being unpacked is not an entry yet
+        let staging = bundles.join("def456.1.part");
+        fs::create_dir_all(&staging).unwrap();
+        fs::write(staging.join(bundle::MODEL_FILE), vec![0u8; 10]).unwrap();
+        let old = SystemTime::now() - Duration::from_secs(100);
+        filetime::set_file_atime(
+            unpacked.join(bundle::MODEL_FILE),
+            filetime::FileTime::from_system_time(old),
+        )
+        .unwrap();
+
+        let usage = cache_usage_in(dir.path()).unwrap();
+        assert_eq!(usage.file_count, 2);
+        assert_eq!(usage.logical_bytes, 300);
+        assert_eq!(usage.physical_bytes, 300);
+
+        // The bundle is the least recently used entry, so it is evicted as a whole
+        evict_files_in(dir.path(), 200, 0, CacheEvictionStrategy::LRU).unwrap();
+        assert!(!unpacked.exists());
+        assert!(dir.path().join("a.onnx").exists());
+        assert_eq!(cache_usage_in(dir.path()).unwrap().logical_bytes, 100);
+    }
+
+    #[test]
+    fn test_evict_files_in_trims_to_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = SystemTime::now();
+        for (i, name) in ["a", "b", "c", "d"].iter().enumerate() {
+            let path = dir.path().join(format!("{}.onnx", name));
+            fs::write(&path, vec![0u8; 100]).unwrap();
+            // Older files get older access times, so "a" is the least recently used.
+            let accessed = now - Duration::from_secs(100 * (4 - i as u64));
+            filetime::set_file_atime(&path, filetime::FileTime::from_system_time(accessed))
+                .unwrap();
+        }
+
+        evict_files_in(dir.path(), 250, 0, CacheEvictionStrategy::LRU).unwrap();
+        let remaining = cached_files_by_access_time_in(dir.path()).unwrap();
+        let total: u64 = remaining.iter().map(|(_, _, size)| size).sum();
+        assert!(total <= 250);
+        assert_eq!(remaining.len(), 2);
+        assert!(!dir.path().join("a.onnx").exists());
+        assert!(!dir.path().join("b.onnx").exists());
+        assert!(dir.path().join("d.onnx").exists());
+    }
+
+    /// Builds an eviction candidate for a file that does not need to exist.
+    fn candidate(
+        name: &str,
+        age_secs: u64,
+        downloaded_at: u64,
+        access_count: u64,
+    ) -> EvictionCandidate {
+        EvictionCandidate {
+            path: PathBuf::from(format!("{}.onnx", name)),
+            size: 100,
+            last_access: SystemTime::now() - Duration::from_secs(age_secs),
+            downloaded_at,
+            access_count,
+            content_hash: None,
+            in_use: false,
+        }
+    }
+
+    #[test]
+    fn test_plan_eviction_orders_by_strategy() {
+        // "a" is the least recently used, "b" the least frequently used, and "c" the
+        // first downloaded
+        let candidates = vec![
+            candidate("a", 400, 300, 5),
+            candidate("b", 300, 200, 1),
+            candidate("c", 200, 100, 9),
+            candidate("d", 100, 400, 3),
+        ];
+        let first = |strategy| {
+            let plan = plan_eviction(candidates.clone(), strategy, 350, 0);
+            assert_eq!(plan.len(), 1);
+            assert_eq!(plan[0].freed_bytes, 100);
+            plan[0].candidate.path.clone()
+        };
+        assert_eq!(first(CacheEvictionStrategy::LRU), PathBuf::from("a.onnx"));
+        assert_eq!(first(CacheEvictionStrategy::LFU), PathBuf::from("b.onnx"));
+        assert_eq!(first(CacheEvictionStrategy::FIFO), PathBuf::from("c.onnx"));
+
+        assert!(plan_eviction(candidates.clone(), CacheEvictionStrategy::LRU, 400, 0).is_empty());
+        let plan = plan_eviction(candidates, CacheEvictionStrategy::LFU, 400, 150);
+        let names: Vec<_> = plan.iter().map(|p| p.candidate.path.clone()).collect();
+        assert_eq!(
+            names,
+            vec![PathBuf::from("b.onnx"), PathBuf::from("d.onnx")]
+        );
+    }
+
+    #[test]
+    fn test_plan_eviction_frees_shared_payload_with_last_referrer() {
+        let mut a = candidate("a", 300, 0, 0);
+        let mut b = candidate("b", 200, 0, 0);
+        a.content_hash = Some("shared".to_string());
+        b.content_hash = Some("shared".to_string());
+        let c = candidate("c", 100, 0, 0);
+
+        // The cache holds 200 bytes on disk, so the shared payload must go
+        let plan = plan_eviction(vec![a, b, c], CacheEvictionStrategy::LRU, 150, 0);
+        let freed: Vec<_> = plan.iter().map(|p| p.freed_bytes).collect();
+        assert_eq!(freed, vec![0, 100]);
+    }
+
+    #[test]
+    fn test_plan_eviction_skips_entries_in_use() {
+        let mut a = candidate("a", 400, 0, 0);
+        a.in_use = true;
+        let candidates = vec![
+            a,
+            candidate("b", 300, 0, 0),
+            candidate("c", 200, 0, 0),
+            candidate("d", 100, 0, 0),
+        ];
+
+        // "a" is listed where it would have been evicted, and "c" is evicted in its place
+        let plan = plan_eviction(candidates.clone(), CacheEvictionStrategy::LRU, 250, 0);
+        let entries: Vec<_> = plan
+            .iter()
+            .map(|p| (p.candidate.path.clone(), p.freed_bytes))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                (PathBuf::from("a.onnx"), 0),
+                (PathBuf::from("b.onnx"), 100),
+                (PathBuf::from("c.onnx"), 100),
+            ]
+        );
+
+        // When too much is in use, everything else is evicted and the rest stays
+        let all_in_use = candidates
+            .into_iter()
+            .map(|mut c| {
+                c.in_use = c.path != Path::new("d.onnx");
+                c
+            })
+            .collect();
+        let plan = plan_eviction(all_in_use, CacheEvictionStrategy::LRU, 150, 0);
+        let freed: u64 = plan.iter().map(|p| p.freed_bytes).sum();
+        assert_eq!(plan.len(), 4);
+        assert_eq!(freed, 100);
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_evict_files_in_keeps_files_of_loaded_models() {
+        let dir = tempfile::tempdir().unwrap();
+        let model_bytes = fs::read("../test/models/linear.onnx").unwrap();
+        let size = model_bytes.len() as u64;
+        let names = ["a", "b", "c", "d"];
+        for name in names {
+            fs::write(dir.path().join(format!("{}.onnx", name)), &model_bytes).unwrap();
+        }
+        let name = "http_eviction_in_use";
+        let loaded = dir.path().join("a.onnx");
+        crate::engine::load_model_impl(name, &loaded.to_string_lossy()).unwrap();
+        let now = SystemTime::now();
+        for (i, name) in names.iter().enumerate() {
+            // "a" is the least recently used
+            let accessed = now - Duration::from_secs(100 * (4 - i as u64));
+            let path = dir.path().join(format!("{}.onnx", name));
+            filetime::set_file_atime(&path, filetime::FileTime::from_system_time(accessed))
+                .unwrap();
+        }
+
+        let candidates = eviction_candidates_in(dir.path()).unwrap();
+        let in_use: Vec<_> = candidates.iter().filter(|c| c.in_use).collect();
+        assert_eq!(in_use.len(), 1);
+        assert_eq!(in_use[0].path, loaded);
+
+        // The loaded model's file survives, and the next candidates are evicted instead
+        evict_files_in(dir.path(), 2 * size, 0, CacheEvictionStrategy::LRU).unwrap();
+        let exists = |name: &str| dir.path().join(format!("{}.onnx", name)).exists();
+        assert!(exists("a"));
+        assert!(!exists("b"));
+        assert!(!exists("c"));
+        assert!(exists("d"));
+
+        // More pressure than can be relieved evicts everything but the file in use
+        evict_files_in(dir.path(), 0, 0, CacheEvictionStrategy::LRU).unwrap();
+        assert!(exists("a"));
+        assert!(!exists("d"));
+
+        // Once the model is unloaded, its file can be evicted
+        crate::model::unload_model(name).unwrap();
+        evict_files_in(dir.path(), 0, 0, CacheEvictionStrategy::LRU).unwrap();
+        assert!(!exists("a"));
+    }
+
+    #[test]
+    fn test_eviction_plan_matches_execution() {
+        let now = SystemTime::now();
+        for strategy in [
+            CacheEvictionStrategy::LRU,
+            CacheEvictionStrategy::LFU,
+            CacheEvictionStrategy::FIFO,
+        ] {
+            let dir = tempfile::tempdir().unwrap();
+            let entries = [
+                ("a", 400, 300, 5),
+                ("b", 300, 200, 1),
+                ("c", 200, 100, 9),
+                ("d", 100, 400, 3),
+            ];
+            for (name, age, downloaded_at, access_count) in entries {
+                let path = dir.path().join(format!("{}.onnx", name));
+                fs::write(&path, vec![0u8; 100]).unwrap();
+                let meta = json!({
+                    "url": name,
+                    "downloaded_at": downloaded_at,
+                    "access_count": access_count,
+                });
+                fs::write(meta_path(&path), meta.to_string()).unwrap();
+                let accessed = now - Duration::from_secs(age);
+                filetime::set_file_atime(&path, filetime::FileTime::from_system_time(accessed))
+                    .unwrap();
+            }
+
+            let plan = plan_eviction(
+                eviction_candidates_in(dir.path()).unwrap(),
+                strategy,
+                250,
+                0,
+            );
+            assert_eq!(plan.len(), 2, "{:?}", strategy);
+            // Planning leaves the cache untouched
+            assert_eq!(cached_files_by_access_time_in(dir.path()).unwrap().len(), 4);
+
+            evict_files_in(dir.path(), 250, 0, strategy).unwrap();
+            for (name, ..) in entries {
+                let path = dir.path().join(format!("{}.onnx", name));
+                let planned = plan.iter().any(|p| p.candidate.path == path);
+                assert_eq!(path.exists(), !planned, "{:?}: {}", strategy, name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_touch_cache_file_counts_accesses() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.onnx");
+        fs::write(&path, b"x").unwrap();
+        write_cache_meta(&path, "model");
+        touch_cache_file(&path).unwrap();
+        touch_cache_file(&path).unwrap();
+        let candidates = eviction_candidates_in(dir.path()).unwrap();
+        assert_eq!(candidates[0].access_count, 2);
+    }
+
+    #[test]
+    fn test_cache_stats_to_json_and_reset() {
+        let stats = CacheStats::new();
+        count(&stats.hits, 2);
+        count(&stats.bytes_evicted, 100);
+        let json = stats.to_json();
+        assert_eq!(json["hits"], 2);
+        assert_eq!(json["bytes_evicted"], 100);
+        assert_eq!(json["misses"], 0);
+        assert_eq!(json.as_object().unwrap().len(), 7);
+
+        stats.reset();
+        assert!(stats
+            .to_json()
+            .as_object()
+            .unwrap()
+            .values()
+            .all(|v| v == 0));
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_cache_stats_count_hits_misses_and_evictions() {
+        let mut server = Server::new();
+        let body = b"onnxdata".to_vec();
+        let mock = server
+            .mock("GET", "/ok_model_cache_stats.onnx")
+            .with_status(200)
+            .with_body(body.clone())
+            .expect(1)
+            .create();
+        let url = format!("{}/ok_model_cache_stats.onnx", server.url());
+        evict_cached_model(&url).unwrap();
+
+        // Other tests share the counters, so they can only be checked from below. This is
+        // the only test that resets them.
+        reset_cache_stats();
+        let stat = |name: &str| cache_stats()[name].as_u64().unwrap();
+
+        // The first load is a miss that downloads the model, and the second is a hit that
+        // makes no request since the server sent no ETag
+        handle_remote_model(&url).unwrap();
+        handle_remote_model(&url).unwrap();
+        mock.assert();
+        assert!(stat("misses") >= 1);
+        assert!(stat("downloads") >= 1);
+        assert!(stat("bytes_downloaded") >= body.len() as u64);
+        assert!(stat("hits") >= 1);
+
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["a", "b"] {
+            fs::write(dir.path().join(format!("{}.onnx", name)), vec![0u8; 100]).unwrap();
+        }
+        evict_files_in(dir.path(), 150, 0, CacheEvictionStrategy::LRU).unwrap();
+        assert!(stat("evictions") >= 1);
+        assert!(stat("bytes_evicted") >= 100);
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_dedup_counts_shared_payload_once_and_evicts_with_last_referrer() {
+        let dir = tempfile::tempdir().unwrap();
+        let old =
+            filetime::FileTime::from_system_time(SystemTime::now() - Duration::from_secs(600));
+        for name in ["a", "b"] {
+            let path = dir.path().join(format!("{}.onnx", name));
+            fs::write(&path, vec![1u8; 100]).unwrap();
+            let hash = link_to_content(dir.path(), &path).unwrap();
+            let mut meta = cache_meta(name);
+            meta["content_hash"] = json!(hash);
+            store_cache_meta(&path, &meta).unwrap();
+            filetime::set_file_atime(&path, old).unwrap();
+        }
+        fs::write(dir.path().join("c.onnx"), vec![2u8; 100]).unwrap();
+
+        let payloads: Vec<_> = fs::read_dir(dir.path().join(CONTENT_DIR))
+            .unwrap()
+            .flatten()
+            .collect();
+        assert_eq!(payloads.len(), 1);
+        let usage = cache_usage_in(dir.path()).unwrap();
+        assert_eq!(usage.file_count, 3);
+        assert_eq!(usage.logical_bytes, 300);
+        assert_eq!(usage.physical_bytes, 200);
+
+        // Evicting the first referrer frees nothing, so the second is evicted as well.
+        evict_files_in(dir.path(), 150, 0, CacheEvictionStrategy::LRU).unwrap();
+        assert!(!dir.path().join("a.onnx").exists());
+        assert!(!dir.path().join("b.onnx").exists());
+        assert!(dir.path().join("c.onnx").exists());
+        assert!(
+            !payloads[0].path().exists(),
+            "payload must go with its last referrer"
+        );
+        assert_eq!(cache_usage_in(dir.path()).unwrap().physical_bytes, 100);
+    }
+
+    #[test]
+    fn test_fetch_remote_model_dedup_stores_single_payload() {
+        let mut server = Server::new();
+        let body = b"onnxdata-mirrored-payload".to_vec();
+        let _east = server
+            .mock("GET", "/east/mirrored_model.onnx")
+            .with_status(200)
+            .with_body(body.clone())
+            .create();
+        let _west = server
+            .mock("GET", "/west/mirrored_model.onnx")
+            .with_status(200)
+            .with_body(body.clone())
+            .create();
+        let east = format!("{}/east/mirrored_model.onnx", server.url());
+        let west = format!("{}/west/mirrored_model.onnx", server.url());
+        evict_cached_model(&east).unwrap();
+        evict_cached_model(&west).unwrap();
+
+        let options = DownloadOptions::from_config();
+        let east_path = fetch_remote_model(&east, &options, true, 0, None)
+            .unwrap()
+            .path;
+        let west_path = fetch_remote_model(&west, &options, true, 0, None)
+            .unwrap()
+            .path;
+        assert_ne!(east_path, west_path);
+        assert_eq!(fs::read(&east_path).unwrap(), body);
+        assert_eq!(fs::read(&west_path).unwrap(), body);
+
+        let hash = read_content_hash(&east_path).expect("entry must record its payload");
+        assert_eq!(
+            read_content_hash(&west_path).as_deref(),
+            Some(hash.as_str())
+        );
+        let payload = content_path(&cache_dir(), &hash);
+        assert_eq!(fs::read(&payload).unwrap(), body);
+        let same_content = fs::read_dir(cache_dir().join(CONTENT_DIR))
+            .unwrap()
+            .flatten()
+            .filter(|e| fs::read(e.path()).is_ok_and(|b| b == body))
+            .count();
+        assert_eq!(same_content, 1, "identical content must be stored once");
+
+        evict_cached_model(&east).unwrap();
+        assert!(payload.exists(), "payload is kept while a referrer remains");
+        evict_cached_model(&west).unwrap();
+        assert!(!payload.exists());
+    }
+
+    #[test]
+    fn test_fetch_model_signature_downloads_or_removes_stale() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/signed_model.onnx.sig")
+            .with_status(200)
+            .with_body(vec![5u8; 64])
+            .create();
+        let _missing = server
+            .mock("GET", "/unsigned_model.onnx.sig")
+            .with_status(404)
+            .create();
+        let dir = tempfile::tempdir().unwrap();
+        let cached_path = dir.path().join("model.onnx");
+        let sig_path = signature::signature_path(&cached_path);
+        let options = DownloadOptions::from_config();
+
+        let url = format!("{}/signed_model.onnx", server.url());
+        fetch_model_signature(&url, &cached_path, &options);
+        assert_eq!(fs::read(&sig_path).unwrap(), vec![5u8; 64]);
+
+        // A model without a published signature must not keep a stale one.
+        let url = format!("{}/unsigned_model.onnx", server.url());
+        fetch_model_signature(&url, &cached_path, &options);
+        assert!(!sig_path.exists());
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_list_cache_includes_downloaded_url() {
+        let mut server = Server::new();
+        let body = b"onnxdata-listed".to_vec();
+        let _m = server
+            .mock("GET", "/listed_model.onnx")
+            .with_status(200)
+            .with_body(body.clone())
+            .create();
+        let url = format!("{}/listed_model.onnx", server.url());
+
+        let path = handle_remote_model(&url).expect("download should succeed");
+        assert!(meta_path(&path).exists(), "metadata sidecar must exist");
+
+        let entries = list_cache().expect("listing should succeed");
+        let entry = entries
+            .iter()
+            .find(|e| e["url"] == url.as_str())
+            .expect("downloaded URL should be listed");
+        assert_eq!(entry["size_bytes"], body.len() as u64);
+        assert!(entry["last_access"].as_u64().unwrap() > 0);
+    }
+
+    #[test]
+    fn test_download_file_strict_content_type_rejects_html() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/html_model.onnx")
+            .with_status(200)
+            .with_header("Content-Type", "text/html; charset=utf-8")
+            .with_body("<html></html>")
+            .create();
+        let url = format!("{}/html_model.onnx", server.url());
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("model.onnx");
+
+        let options = DownloadOptions {
+            require_octet_stream: true,
+            ..DownloadOptions::from_config()
+        };
+        let result = download_file(&url, &dest, &options, None);
+        assert!(matches!(
+            result,
+            Err(InferaError::UnexpectedContentType(ref ct)) if ct.starts_with("text/html")
+        ));
+        assert!(!dest.exists(), "nothing should be written in strict mode");
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_download_file_lenient_content_type_accepts_html() {
+        let mut server = Server::new();
+        let body = b"onnxdata".to_vec();
+        let _m = server
+            .mock("GET", "/html_model_lenient.onnx")
+            .with_status(200)
+            .with_header("Content-Type", "text/html")
+            .with_body(body.clone())
+            .create();
+        let url = format!("{}/html_model_lenient.onnx", server.url());
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("model.onnx");
+
+        let options = DownloadOptions {
+            require_octet_stream: false,
+            ..DownloadOptions::from_config()
+        };
+        let result = download_file(&url, &dest, &options, None).expect("lenient download");
+        assert_eq!(
+            result,
+            DownloadResult::Downloaded {
+                etag: None,
+                final_url: url,
+                sha256: bytes_sha256(&body),
+            }
+        );
+        assert_eq!(fs::read(&dest).unwrap(), body);
+    }
+
+    #[test]
+    fn test_download_file_connect_timeout_fails_fast() {
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("model.onnx");
+
+        // 10.255.255.1 is unroutable, so the connection attempt hangs until the timeout.
+        let options = DownloadOptions {
+            timeout_secs: 60,
+            connect_timeout_secs: 1,
+            ..DownloadOptions::from_config()
+        };
+        let start = std::time::Instant::now();
+        let result = download_file("http://10.255.255.1/model.onnx", &dest, &options, None);
+        assert!(matches!(result, Err(InferaError::HttpRequestError(_))));
+        assert!(
+            start.elapsed() < Duration::from_secs(30),
+            "connect timeout was not applied: {:?}",
+            start.elapsed()
+        );
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn test_fetch_remote_model_gives_up_at_total_deadline() {
+        let mut server = Server::new();
+        // Attempts start at 0ms, 200ms, and 600ms, and the next one would start after 1s
+        let m = server
+            .mock("GET", "/deadline_model.onnx")
+            .with_status(500)
+            .expect(3)
+            .create();
+        let url = format!("{}/deadline_model.onnx", server.url());
+        evict_cached_model(&url).unwrap();
+
+        let options = DownloadOptions {
+            retry_attempts: 100,
+            retry_delay_ms: 200,
+            total_deadline_secs: 1,
+            ..DownloadOptions::from_config()
+        };
+        let start = std::time::Instant::now();
+        let err = fetch_remote_model(&url, &options, false, 0, None).unwrap_err();
+        match err {
+            InferaError::DownloadDeadlineExceeded {
+                deadline_secs,
+                attempts,
+                ..
+            } => {
+                assert_eq!(deadline_secs, 1);
+                assert_eq!(attempts, 3);
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        // The last retry is abandoned instead of sleeping past the deadline
+        assert!(
+            start.elapsed() < Duration::from_secs(1),
+            "retries ran past the deadline: {:?}",
+            start.elapsed()
+        );
+        assert!(!cached_model_path(&url).exists());
+        m.assert();
+    }
+
+    #[test]
+    fn test_fetch_remote_model_offline_serves_only_cached_models() {
+        let mut server = Server::new();
+        let cached = server
+            .mock("GET", "/offline_cached.onnx")
+            .with_status(200)
+            .with_header("ETag", "\"v1\"")
+            .with_body("onnxdata")
+            .expect(1)
+            .create();
+        let uncached = server
+            .mock("GET", "/offline_uncached.onnx")
+            .expect(0)
+            .create();
+        let cached_url = format!("{}/offline_cached.onnx", server.url());
+        let uncached_url = format!("{}/offline_uncached.onnx", server.url());
+        evict_cached_model(&cached_url).unwrap();
+        evict_cached_model(&uncached_url).unwrap();
+
+        let online = DownloadOptions::from_config();
+        let first = fetch_remote_model(&cached_url, &online, false, 0, None).unwrap();
+        assert!(!first.from_cache);
+
+        // Offline, the cached copy is served without a conditional request
+        let offline = DownloadOptions {
+            offline: true,
+            ..DownloadOptions::from_config()
+        };
+        let second = fetch_remote_model(&cached_url, &offline, false, 0, None).unwrap();
+        assert!(second.from_cache);
+        assert_eq!(second.path, first.path);
+
+        // A model that is not cached fails without a request
+        let err = fetch_remote_model(&uncached_url, &offline, false, 0, None).unwrap_err();
+        assert!(matches!(err, InferaError::Offline(ref url) if url == &uncached_url));
+        assert!(!cached_model_path(&uncached_url).exists());
+
+        cached.assert();
+        uncached.assert();
+    }
+
+    #[test]
+    fn test_download_file_refuses_model_larger_than_cache_limit() {
+        let mut server = Server::new();
+        let m = server
+            .mock("GET", "/oversized_model.onnx")
+            .with_status(200)
+            .with_body(vec![1u8; 1024])
+            .expect(1)
+            .create();
+        let url = format!("{}/oversized_model.onnx", server.url());
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("model.onnx");
+
+        let options = DownloadOptions {
+            cache_size_limit: 100,
+            ..DownloadOptions::from_config()
+        };
+        let result = download_file(&url, &dest, &options, None);
+        assert!(matches!(
+            result,
+            Err(InferaError::InsufficientDiskSpace {
+                needed: 1024,
+                available: 100
+            })
+        ));
+        assert!(
+            !dest.exists(),
+            "nothing should be written for an oversized model"
+        );
+        m.assert();
     }
 
-    let mut response = request
-        .send()
-        .map_err(|e| InferaError::HttpRequestError(e.to_string()))?;
-
-    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
-        return Ok(DownloadResult::NotModified);
+    #[test]
+    fn test_download_file_refuses_model_below_min_free_disk() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/no_room_model.onnx")
+            .with_status(200)
+            .with_body(vec![1u8; 64])
+            .create();
+        let url = format!("{}/no_room_model.onnx", server.url());
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("model.onnx");
+
+        // Keeping all of the disk free leaves no room for any download.
+        let options = DownloadOptions {
+            min_free_disk_bytes: u64::MAX,
+            ..DownloadOptions::from_config()
+        };
+        let result = download_file(&url, &dest, &options, None);
+        assert!(matches!(
+            result,
+            Err(InferaError::InsufficientDiskSpace {
+                needed: 64,
+                available: 0
+            })
+        ));
+        assert!(!dest.exists());
     }
 
-    response = response
-        .error_for_status()
-        .map_err(|e| InferaError::HttpRequestError(e.to_string()))?;
-
-    let new_etag = response
-        .headers()
-        .get(reqwest::header::ETAG)
-        .and_then(|v| v.to_str().ok())
-        .map(|s| s.to_string());
-
-    let mut file = File::create(dest).map_err(|e| InferaError::IoError(e.to_string()))?;
-    io::copy(&mut response, &mut file).map_err(|e| InferaError::IoError(e.to_string()))?;
-
-    Ok(DownloadResult::Downloaded { etag: new_etag })
-}
+    #[test]
+    fn test_reserve_download_space_evicts_to_free_disk_space() {
+        let dir = tempfile::tempdir().unwrap();
+        let old =
+            filetime::FileTime::from_system_time(SystemTime::now() - Duration::from_secs(600));
+        let older = dir.path().join("older.onnx");
+        let newer = dir.path().join("newer.onnx");
+        fs::write(&older, vec![0u8; 40]).unwrap();
+        fs::write(&newer, vec![0u8; 40]).unwrap();
+        filetime::set_file_atime(&older, old).unwrap();
+
+        // A 100-byte disk that holds nothing but the files in the directory
+        let disk = |dir: &Path| -> io::Result<u64> {
+            let used: u64 = fs::read_dir(dir)?
+                .flatten()
+                .filter_map(|entry| entry.metadata().ok())
+                .filter(|metadata| metadata.is_file())
+                .map(|metadata| metadata.len())
+                .sum();
+            Ok(100 - used)
+        };
+        let options = DownloadOptions {
+            cache_size_limit: 1000,
+            min_free_disk_bytes: 10,
+            ..DownloadOptions::from_config()
+        };
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use mockito::Server;
-    use std::env; // moved here: used in tests only
-    use std::thread;
-    use tiny_http::{Header, Response, Server as TinyServer};
+        // 10 bytes are free for the download, so the least recently used model is evicted
+        let budget = reserve_download_space_with(dir.path(), Some(40), &options, disk).unwrap();
+        assert_eq!(budget, 50);
+        assert!(!older.exists());
+        assert!(newer.exists());
+
+        // A download that does not fit even on an empty disk fails after evicting everything
+        let result = reserve_download_space_with(dir.path(), Some(95), &options, disk);
+        assert!(matches!(
+            result,
+            Err(InferaError::InsufficientDiskSpace {
+                needed: 95,
+                available: 90
+            })
+        ));
+        assert!(!newer.exists());
+
+        // Without eviction, files in the directory are left alone
+        fs::write(&older, vec![0u8; 40]).unwrap();
+        let keep = DownloadOptions {
+            evict: false,
+            ..options
+        };
+        let result = reserve_download_space_with(dir.path(), Some(55), &keep, disk);
+        assert!(matches!(
+            result,
+            Err(InferaError::InsufficientDiskSpace {
+                needed: 55,
+                available: 50
+            })
+        ));
+        assert!(older.exists());
+    }
 
     #[test]
-    fn test_handle_remote_model_cleanup_on_incomplete_download() {
+    fn test_download_file_aborts_unsized_stream_over_limit() {
         let server = TinyServer::http("127.0.0.1:0").unwrap();
         let port = server.server_addr().to_ip().unwrap().port();
-        let model_url = format!("http://127.0.0.1:{}/incomplete_model.onnx", port);
+        let url = format!("http://127.0.0.1:{}/unsized_model.onnx", port);
 
         let server_handle = thread::spawn(move || {
             if let Ok(request) = server.recv() {
-                let mut response = Response::from_string("incomplete data");
-                let header = Header::from_bytes(&b"Content-Length"[..], &b"100"[..]).unwrap();
-                response.add_header(header);
+                // Without a data length, the body is sent chunked and no Content-Length
+                // is reported.
+                let body = io::Cursor::new(vec![1u8; 1024]);
+                let response = Response::new(tiny_http::StatusCode(200), vec![], body, None, None);
                 let _ = request.respond(response);
             }
         });
 
-        // The download should fail because the response body is shorter than the content-length.
-        let result = handle_remote_model(&model_url);
-        assert!(result.is_err());
-
-        // Ensure no partial or final file is left in the cache.
-        let cache_dir = env::temp_dir().join("infera_cache");
-        let mut hasher = Sha256::new();
-        hasher.update(model_url.as_bytes());
-        let hash_hex = hex::encode(hasher.finalize());
-        let cached_path = cache_dir.join(format!("{}.onnx", hash_hex));
-        let temp_path = cached_path.with_extension("onnx.part");
-
-        assert!(!cached_path.exists(), "Final cache file should not exist");
-        assert!(!temp_path.exists(), "Partial file should be cleaned up");
-
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("model.onnx");
+        let options = DownloadOptions {
+            cache_size_limit: 100,
+            ..DownloadOptions::from_config()
+        };
+        let result = download_file(&url, &dest, &options, None);
+        assert!(matches!(
+            result,
+            Err(InferaError::InsufficientDiskSpace { available: 100, .. })
+        ));
+        assert!(!dest.exists(), "the partial stream should be removed");
         server_handle.join().unwrap();
     }
 
     #[test]
-    fn test_handle_remote_model_download_error() {
-        // Simulate a server error instead of an interrupted download,
-        // as it's more reliable to test the error handling path.
+    fn test_download_file_rejects_reported_size_over_max_download_bytes() {
         let mut server = Server::new();
         let _m = server
-            .mock("GET", "/server_error_model.onnx")
-            .with_status(500)
+            .mock("GET", "/too_large_model.onnx")
+            .with_status(200)
+            .with_body(vec![1u8; 64])
             .create();
+        let url = format!("{}/too_large_model.onnx", server.url());
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("model.onnx");
 
-        let url = server.url();
-        let model_url = format!("{}/server_error_model.onnx", url);
-
-        let result = handle_remote_model(&model_url);
-
-        // The download should fail because of the server error.
-        assert!(
-            result.is_err(),
-            "handle_remote_model should return an error on 500 status"
-        );
-
-        // Ensure no partial or final file is left in the cache.
-        let cache_dir = env::temp_dir().join("infera_cache");
-        let mut hasher = Sha256::new();
-        hasher.update(model_url.as_bytes());
-        let hash_hex = hex::encode(hasher.finalize());
-        let cached_path = cache_dir.join(format!("{}.onnx", hash_hex));
-        let temp_path = cached_path.with_extension("onnx.part");
-
-        assert!(!cached_path.exists(), "Final cache file should not exist");
-        assert!(!temp_path.exists(), "Partial file should be cleaned up");
+        let options = DownloadOptions {
+            max_download_bytes: 32,
+            ..DownloadOptions::from_config()
+        };
+        let result = download_file(&url, &dest, &options, None);
+        assert!(matches!(
+            result,
+            Err(InferaError::DownloadTooLarge {
+                received: 64,
+                limit: 32
+            })
+        ));
+        assert!(!dest.exists());
     }
 
     #[test]
-    fn test_handle_remote_model_cleanup_on_connection_drop() {
+    fn test_fetch_remote_model_aborts_unsized_stream_over_max_download_bytes() {
         let server = TinyServer::http("127.0.0.1:0").unwrap();
         let port = server.server_addr().to_ip().unwrap().port();
-        let model_url = format!("http://127.0.0.1:{}/dropped_connection.onnx", port);
+        let url = format!("http://127.0.0.1:{}/unsized_too_large.onnx", port);
 
+        // Only one request is answered, so a retry would fail with a different error
         let server_handle = thread::spawn(move || {
             if let Ok(request) = server.recv() {
-                // By responding with a Content-Length header but then dropping the
-                // request without sending a body, we simulate a connection drop.
-                // reqwest will receive the headers and expect a body, but the
-                // connection will be closed prematurely, resulting in an I/O error.
-                let response = Response::empty(200)
-                    .with_header(Header::from_bytes(&b"Content-Length"[..], &b"1024"[..]).unwrap());
-                // The `respond` call sends the headers, but the `Request` object
-                // is dropped immediately after, closing the connection.
+                let body = io::Cursor::new(vec![1u8; 1024]);
+                let response = Response::new(tiny_http::StatusCode(200), vec![], body, None, None);
                 let _ = request.respond(response);
             }
         });
 
-        // The download should fail because the server closes the connection prematurely.
-        let result = handle_remote_model(&model_url);
+        let options = DownloadOptions {
+            max_download_bytes: 100,
+            retry_delay_ms: 0,
+            ..DownloadOptions::from_config()
+        };
+        let err = fetch_remote_model(&url, &options, false, 0, None).unwrap_err();
         assert!(
-            result.is_err(),
-            "The download should fail on a connection drop"
+            matches!(err.root(), InferaError::DownloadTooLarge { limit: 100, .. }),
+            "{}",
+            err
         );
-
-        // After the failure, the temporary file should be cleaned up.
-        let cache_dir = env::temp_dir().join("infera_cache");
-        let mut hasher = Sha256::new();
-        hasher.update(model_url.as_bytes());
-        let hash_hex = hex::encode(hasher.finalize());
-        let cached_path = cache_dir.join(format!("{}.onnx", hash_hex));
-        let temp_path = cached_path.with_extension("onnx.part");
-
-        assert!(!cached_path.exists(), "Final cache file should not exist");
         assert!(
-            !temp_path.exists(),
-            "Partial file should be cleaned up after a connection drop"
+            part_files(&url).is_empty(),
+            "the partial download should be removed"
         );
-
+        assert!(!cached_model_path(&url).exists());
         server_handle.join().unwrap();
     }
 
     #[test]
-    fn test_handle_remote_model_success_and_cache() {
-        // Serve a small body with an accurate Content-Length
+    fn test_downloads_record_the_sha256_computed_while_streaming() {
         let mut server = Server::new();
-        let body = b"onnxdata".to_vec();
         let _m = server
-            .mock("GET", "/ok_model.onnx")
+            .mock("GET", "/streamed_hash.onnx")
             .with_status(200)
-            .with_header("Content-Length", &body.len().to_string())
-            .with_body(body.clone())
+            .with_body("onnxdata")
             .create();
-        let url = format!("{}/ok_model.onnx", server.url());
-
-        let path1 = handle_remote_model(&url).expect("download should succeed");
-        assert!(path1.exists(), "cached file must exist");
-        let content1 = fs::read(&path1).expect("read cached file");
-        assert_eq!(content1, body);
+        let url = format!("{}/streamed_hash.onnx", server.url());
+        let expected = "1443e318c461eea6099fff9293f04bee3ce85fba1df8bc1c93b34cd4c938b098";
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("model.onnx");
+
+        let options = DownloadOptions::from_config();
+        match download_file(&url, &dest, &options, None).unwrap() {
+            DownloadResult::Downloaded { sha256, .. } => assert_eq!(sha256, expected),
+            other => panic!("unexpected result: {:?}", other),
+        }
 
-        // Second call should hit cache and return same path without network
-        let path2 = handle_remote_model(&url).expect("cache should hit");
-        assert_eq!(path1, path2);
-        let temp_path = path1.with_extension("onnx.part");
-        assert!(!temp_path.exists(), "no partial file should remain");
+        // The hash is kept in the cache metadata and listed with the entry
+        let _ = evict_cached_model(&url);
+        let cached = fetch_remote_model(&url, &options, false, 0, None).unwrap();
+        assert_eq!(read_cache_meta(&cached.path).unwrap()["sha256"], expected);
+        let file = cached.path.to_string_lossy();
+        let listed = list_cache()
+            .unwrap()
+            .into_iter()
+            .find(|entry| entry["file"] == file.as_ref())
+            .expect("the entry is listed");
+        assert_eq!(listed["sha256"], expected);
+
+        // A pinned checksum is checked against the same hash
+        let _ = evict_cached_model(&url);
+        assert!(fetch_remote_model(&url, &options, false, 0, Some(expected)).is_ok());
+        let _ = evict_cached_model(&url);
     }
 
     #[test]
-    fn test_handle_remote_model_etag_verified_304() {
+    fn test_download_file_evicts_before_streaming() {
         let mut server = Server::new();
-        let body = b"onnxdata".to_vec();
-
-        // Use a path unique to this test so the URL hash never collides with other
-        // tests that mockito may schedule on the same port (OS port reuse).
-        // 1. Initial request (no ETag matched) returns 200 with ETag "tag1"
-        let m1 = server
-            .mock("GET", "/ok_model_etag_304.onnx")
-            .match_header("if-none-match", mockito::Matcher::Missing)
+        let body = vec![1u8; 60];
+        let _m = server
+            .mock("GET", "/evicting_model.onnx")
             .with_status(200)
-            .with_header("ETag", "tag1")
             .with_body(body.clone())
             .create();
+        let url = format!("{}/evicting_model.onnx", server.url());
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("old.onnx");
+        fs::write(&old, vec![0u8; 80]).unwrap();
+        let dest = dir.path().join("model.onnx.part");
+
+        let options = DownloadOptions {
+            cache_size_limit: 100,
+            ..DownloadOptions::from_config()
+        };
+        download_file(&url, &dest, &options, None).expect("download should fit after eviction");
+        assert!(
+            !old.exists(),
+            "the older model should be evicted to make room"
+        );
+        assert_eq!(fs::read(&dest).unwrap(), body);
+    }
 
-        // 2. Subsequent request (with If-None-Match: tag1) returns 304 Not Modified
-        let m2 = server
-            .mock("GET", "/ok_model_etag_304.onnx")
-            .match_header("if-none-match", "tag1")
-            .with_status(304)
-            .create();
-
-        let url = format!("{}/ok_model_etag_304.onnx", server.url());
+    #[test]
+    fn test_cache_key_readable_names() {
+        let url = "https://models.example.com/vision/resnet50.onnx?download=1";
+        let hash = cache_key_with(url, CacheNaming::Hash);
+        assert_eq!(hash.len(), 64);
+        assert_eq!(
+            cache_key_with(url, CacheNaming::Readable),
+            format!("resnet50-{}", &hash[..READABLE_HASH_LEN])
+        );
 
-        // First download creates the file and the .etag metadata
-        let path1 = handle_remote_model(&url).expect("initial download should succeed");
-        assert!(path1.exists(), "cached file must exist");
-        let content1 = fs::read(&path1).expect("read cached file");
-        assert_eq!(content1, body);
+        // The same file name at different URLs gets different names
+        let other = "https://mirror.example.com/resnet50.onnx";
+        assert!(cache_key_with(other, CacheNaming::Readable).starts_with("resnet50-"));
+        assert_ne!(
+            cache_key_with(url, CacheNaming::Readable),
+            cache_key_with(other, CacheNaming::Readable)
+        );
 
-        let hash_hex = {
-            let mut hasher = Sha256::new();
-            hasher.update(url.as_bytes());
-            hex::encode(hasher.finalize())
-        };
-        let etag_path = cache_dir().join(format!("{}.etag", hash_hex));
-        assert!(etag_path.exists(), "etag metadata must exist");
-        let etag_content = fs::read_to_string(&etag_path).expect("read etag metadata");
-        assert_eq!(etag_content.trim(), "tag1");
+        assert!(cache_key_with(
+            "https://example.com/my model (v2).onnx",
+            CacheNaming::Readable
+        )
+        .starts_with("my_model__v2_-"));
+        assert!(
+            cache_key_with("https://example.com/", CacheNaming::Readable).starts_with("model-")
+        );
+        assert!(cache_key_with("https://example.com", CacheNaming::Readable).starts_with("model-"));
+        let long = format!("https://example.com/{}.onnx", "a".repeat(200));
+        assert_eq!(
+            cache_key_with(&long, CacheNaming::Readable).len(),
+            READABLE_STEM_MAX_LEN + 1 + READABLE_HASH_LEN
+        );
+    }
 
-        // Second check hits the server, gets 304, and reuses the cached file
-        let path2 = handle_remote_model(&url).expect("validation should succeed");
-        assert_eq!(path1, path2);
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("models.example.com", "models.example.com"));
+        assert!(glob_match("models.example.com", "Models.Example.com"));
+        assert!(!glob_match("models.example.com", "models.example.com.evil"));
+        assert!(glob_match("*.internal.corp", "a.internal.corp"));
+        assert!(glob_match("*.internal.corp", "a.b.internal.corp"));
+        assert!(!glob_match("*.internal.corp", "internal.corp"));
+        assert!(!glob_match("*.internal.corp", "evilinternal.corp"));
+        assert!(glob_match("models-??.example.com", "models-eu.example.com"));
+        assert!(!glob_match("models-??.example.com", "models-e.example.com"));
+        assert!(glob_match("*", "anything"));
+        assert!(glob_match("a*b*c", "a-x-b-y-c"));
+        assert!(!glob_match("a*b*c", "a-x-c-y-b"));
+    }
 
-        m1.assert();
-        m2.assert();
+    #[test]
+    fn test_check_host_allowed() {
+        let allowed = vec!["*.internal.corp".to_string(), "127.0.0.1".to_string()];
+        assert!(check_host_allowed("https://evil.example.com/m.onnx", &[]).is_ok());
+        assert!(check_host_allowed("https://models.internal.corp/m.onnx", &allowed).is_ok());
+        assert!(check_host_allowed("http://127.0.0.1:8080/m.onnx", &allowed).is_ok());
+        assert!(matches!(
+            check_host_allowed("https://evil.example.com/m.onnx", &allowed),
+            Err(InferaError::HostNotAllowed(ref url)) if url == "https://evil.example.com/m.onnx"
+        ));
+        assert!(matches!(
+            check_host_allowed("not a url", &allowed),
+            Err(InferaError::HostNotAllowed(_))
+        ));
     }
 
     #[test]
-    fn test_handle_remote_model_etag_changed_200() {
+    fn test_download_file_allowed_host_with_override_headers() {
         let mut server = Server::new();
-        let body1 = b"onnxdata1".to_vec();
-        let body2 = b"onnxdata2".to_vec();
-
-        // Use a path unique to this test so the URL hash never collides with other
-        // tests that mockito may schedule on the same port (OS port reuse).
-        // 1. Initial request returns 200 with ETag "tag1" and body1
-        let m1 = server
-            .mock("GET", "/ok_model_etag_200.onnx")
-            .match_header("if-none-match", mockito::Matcher::Missing)
+        let body = b"onnxdata-allowed".to_vec();
+        let m = server
+            .mock("GET", "/allowed_model.onnx")
+            .match_header("authorization", "Bearer mirror-token")
             .with_status(200)
-            .with_header("ETag", "tag1")
-            .with_body(body1.clone())
+            .with_body(body.clone())
+            .expect(1)
             .create();
+        let url = format!("{}/allowed_model.onnx", server.url());
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("model.onnx");
+
+        let host_override = HostOverride {
+            host: "127.0.0.*".to_string(),
+            timeout_secs: Some(5),
+            connect_timeout_secs: None,
+            headers: vec![(
+                "Authorization".to_string(),
+                "Bearer mirror-token".to_string(),
+            )],
+        };
+        let overrides = [host_override];
+        let found = find_host_override("127.0.0.1", &overrides).expect("pattern should match");
+        let options = DownloadOptions {
+            allowed_hosts: vec!["127.0.0.1".to_string()],
+            ..DownloadOptions::from_config()
+        }
+        .with_override(found);
+        assert_eq!(options.timeout_secs, 5);
 
-        // 2. Subsequent request (with If-None-Match: tag1) returns 200 with ETag "tag2" and body2
-        let m2 = server
-            .mock("GET", "/ok_model_etag_200.onnx")
-            .match_header("if-none-match", "tag1")
+        download_file(&url, &dest, &options, None).expect("allowed host should download");
+        assert_eq!(fs::read(&dest).unwrap(), body);
+        m.assert();
+    }
+
+    #[test]
+    fn test_download_file_blocked_host_sends_no_request() {
+        let mut server = Server::new();
+        let m = server
+            .mock("GET", "/blocked_model.onnx")
             .with_status(200)
-            .with_header("ETag", "tag2")
-            .with_body(body2.clone())
+            .with_body("onnxdata")
+            .expect(0)
             .create();
+        let url = format!("{}/blocked_model.onnx", server.url());
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("model.onnx");
 
-        let url = format!("{}/ok_model_etag_200.onnx", server.url());
-
-        // First download gets body1
-        let path1 = handle_remote_model(&url).expect("initial download should succeed");
-        let content1 = fs::read(&path1).expect("read cached file");
-        assert_eq!(content1, body1);
+        let options = DownloadOptions {
+            allowed_hosts: vec!["models.example.com".to_string()],
+            ..DownloadOptions::from_config()
+        };
+        let result = download_file(&url, &dest, &options, None);
+        assert!(matches!(result, Err(InferaError::HostNotAllowed(ref u)) if *u == url));
+        assert!(!dest.exists());
+        m.assert();
+    }
 
-        // Second validation check gets 200 and downloads body2
-        let path2 = handle_remote_model(&url).expect("updated download should succeed");
-        assert_eq!(path1, path2);
-        let content2 = fs::read(&path2).expect("read updated file");
-        assert_eq!(content2, body2);
+    #[test]
+    fn test_download_file_refuses_redirect_to_blocked_host() {
+        let mut server = Server::new();
+        // The same server under another host name, which the allowlist does not cover
+        let target = format!(
+            "http://localhost:{}/redirect_target.onnx",
+            server.host_with_port().rsplit(':').next().unwrap()
+        );
+        let _redirect = server
+            .mock("GET", "/redirected_model.onnx")
+            .with_status(302)
+            .with_header("Location", &target)
+            .create();
+        let target_mock = server
+            .mock("GET", "/redirect_target.onnx")
+            .with_status(200)
+            .with_body("onnxdata")
+            .expect(0)
+            .create();
+        let url = format!("{}/redirected_model.onnx", server.url());
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("model.onnx");
 
-        let hash_hex = {
-            let mut hasher = Sha256::new();
-            hasher.update(url.as_bytes());
-            hex::encode(hasher.finalize())
+        let options = DownloadOptions {
+            allowed_hosts: vec!["127.0.0.1".to_string()],
+            ..DownloadOptions::from_config()
         };
-        let etag_path = cache_dir().join(format!("{}.etag", hash_hex));
-        assert!(etag_path.exists());
-        let etag_content = fs::read_to_string(&etag_path).expect("read etag metadata");
-        assert_eq!(etag_content.trim(), "tag2");
+        let result = download_file(&url, &dest, &options, None);
+        assert!(matches!(result, Err(InferaError::HostNotAllowed(ref u)) if *u == target));
+        assert!(!dest.exists());
+        target_mock.assert();
+    }
 
-        m1.assert();
-        m2.assert();
+    #[test]
+    fn test_check_redirect() {
+        let parse = |url: &str| reqwest::Url::parse(url).unwrap();
+        let options = DownloadOptions {
+            allowed_hosts: Vec::new(),
+            max_redirects: 2,
+            allow_insecure_redirects: false,
+            ..DownloadOptions::from_config()
+        };
+        let https = [parse("https://models.example.com/m.onnx")];
+        let http = parse("http://mirror.example.com/m.onnx");
+
+        assert!(check_redirect(
+            &https,
+            &parse("https://mirror.example.com/m.onnx"),
+            &options
+        )
+        .is_ok());
+        assert!(matches!(
+            check_redirect(&https, &http, &options),
+            Err(InferaError::InsecureRedirect { ref from, ref to })
+                if from == "https://models.example.com/m.onnx" && to == "http://mirror.example.com/m.onnx"
+        ));
+        let insecure = DownloadOptions {
+            allow_insecure_redirects: true,
+            ..options.clone()
+        };
+        assert!(check_redirect(&https, &http, &insecure).is_ok());
+
+        let chain = [
+            parse("http://a.example.com/m.onnx"),
+            parse("http://b.example.com/m.onnx"),
+            parse("http://c.example.com/m.onnx"),
+        ];
+        assert!(check_redirect(&chain[..2], &http, &options).is_ok());
+        assert!(matches!(
+            check_redirect(&chain, &http, &options),
+            Err(InferaError::HttpRequestError(ref m)) if m.contains("too many redirects")
+        ));
+
+        let restricted = DownloadOptions {
+            allowed_hosts: vec!["*.example.com".to_string()],
+            ..options.clone()
+        };
+        assert!(matches!(
+            check_redirect(&https, &parse("https://evil.test/m.onnx"), &restricted),
+            Err(InferaError::HostNotAllowed(_))
+        ));
     }
 
+    #[cfg(feature = "tract")]
     #[test]
-    fn test_handle_remote_model_no_etag_support() {
+    fn test_download_file_redirect_chain_limit() {
         let mut server = Server::new();
-        let body = b"onnxdata".to_vec();
-
-        // Server does not return ETag
-        let m = server
-            .mock("GET", "/no_etag_model.onnx")
+        let mut hops = Vec::new();
+        for i in 0..3 {
+            hops.push(
+                server
+                    .mock("GET", format!("/r{}", i).as_str())
+                    .with_status(302)
+                    .with_header("Location", &format!("/r{}", i + 1))
+                    .create(),
+            );
+        }
+        let _target = server
+            .mock("GET", "/r3")
             .with_status(200)
-            .with_body(body.clone())
+            .with_body("onnxdata-chain")
             .create();
+        let url = format!("{}/r0", server.url());
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("model.onnx");
 
-        let url = format!("{}/no_etag_model.onnx", server.url());
-
-        // First download succeeds but no .etag file is written
-        let path1 = handle_remote_model(&url).expect("download should succeed");
-        assert!(path1.exists());
-        let content1 = fs::read(&path1).expect("read cached file");
-        assert_eq!(content1, body);
-
-        let hash_hex = {
-            let mut hasher = Sha256::new();
-            hasher.update(url.as_bytes());
-            hex::encode(hasher.finalize())
+        let short = DownloadOptions {
+            max_redirects: 2,
+            ..DownloadOptions::from_config()
         };
-        let etag_path = cache_dir().join(format!("{}.etag", hash_hex));
-        assert!(!etag_path.exists(), "etag metadata should not be created");
+        let result = download_file(&url, &dest, &short, None);
+        assert!(
+            matches!(result, Err(InferaError::HttpRequestError(ref m)) if m.contains("too many redirects")),
+            "unexpected result: {:?}",
+            result
+        );
 
-        // Second download immediately treats it as a fast-path cache hit (no server request)
-        let path2 = handle_remote_model(&url).expect("fast cache hit should succeed");
-        assert_eq!(path1, path2);
+        let long = DownloadOptions {
+            max_redirects: 3,
+            ..DownloadOptions::from_config()
+        };
+        let result = download_file(&url, &dest, &long, None).expect("chain within the limit");
+        assert_eq!(
+            result,
+            DownloadResult::Downloaded {
+                etag: None,
+                final_url: format!("{}/r3", server.url()),
+                sha256: bytes_sha256(b"onnxdata-chain"),
+            }
+        );
+        assert_eq!(fs::read(&dest).unwrap(), b"onnxdata-chain");
+    }
 
-        // Verify that the server mock was only called once
-        m.assert();
+    #[test]
+    fn test_media_type_strips_parameters() {
+        assert_eq!(
+            media_type("Application/Octet-Stream; charset=binary"),
+            "application/octet-stream"
+        );
+        assert_eq!(media_type("application/onnx"), "application/onnx");
     }
 
     #[test]
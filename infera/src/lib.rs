@@ -1,26 +1,55 @@
 // The public C API layer and module declarations.
 
+use config::LogLevel;
 use serde_json::json;
-use std::ffi::{c_char, CStr, CString};
-use std::fs;
+use std::ffi::{c_char, c_void, CStr, CString};
 
 // Declare the internal modules
+mod autoload;
+mod bundle;
+mod canary;
+mod compare;
+mod concurrency;
 mod config;
 mod engine;
+mod ensemble;
 mod error;
 mod ffi_utils;
 mod http;
 mod model;
+#[cfg(feature = "tract")]
+mod postprocess;
+mod profile;
+mod signature;
+mod stream;
+mod vision;
+mod worker;
 
 // Re-export the public FFI utility functions and types
-pub use error::infera_last_error;
-pub use ffi_utils::{infera_free, infera_free_result, InferaInferenceResult};
+pub use config::InferaLogCallback;
+pub use error::{
+    infera_last_error, infera_set_error_mode, infera_take_last_error, InferaErrorMode, InferaStatus,
+};
+pub use ffi_utils::{
+    infera_clear_result_pool, infera_copy_result, infera_free, infera_free_blob,
+    infera_free_result, infera_free_string, InferaBlob, InferaInferenceResult,
+    InferaProfiledResult,
+};
+pub use model::InferaLoadOutcome;
+pub use worker::InferaPredictCallback;
 
 /// Loads an ONNX model from a local file path or a remote URL and assigns it a unique name.
 ///
 /// If the `path` starts with "http://" or "https://", the model will be downloaded
 /// and cached locally. Otherwise, it will be treated as a local file path.
 ///
+/// A path or URL ending in `.infera.zip` is a model bundle: a zip archive with
+/// `model.onnx` and a `manifest.json` holding the model's `"name"` and optional
+/// `"version"`, and optionally `labels.json`, `preprocess.json`, and `features.json`. The
+/// bundle is unpacked into the cache, its feature names are attached to the model, and its
+/// manifest, labels, and preprocessing are reported by `infera_get_model_info` under
+/// `"bundle"`. An empty `name` loads the model under the manifest's name.
+///
 /// # Arguments
 ///
 /// * `name` - A pointer to a null-terminated C string representing the unique name for the model.
@@ -43,15 +72,12 @@ pub unsafe extern "C" fn infera_load_model(name: *const c_char, path: *const c_c
         }
         let name_str = CStr::from_ptr(name).to_str()?;
         let path_or_url_str = CStr::from_ptr(path).to_str()?;
-
-        let local_path = if path_or_url_str.starts_with("http") {
-            http::handle_remote_model(path_or_url_str)?
-        } else {
-            path_or_url_str.into()
-        };
-        let local_path_str = local_path.to_str().ok_or(error::InferaError::Utf8Error)?;
-
-        engine::load_model_impl(name_str, local_path_str)
+        load_model_from_path_or_url(
+            &model::resolve_name(name_str),
+            path_or_url_str,
+            model::LoadOptions::default(),
+        )?;
+        Ok(())
     })();
 
     match result {
@@ -63,37 +89,44 @@ pub unsafe extern "C" fn infera_load_model(name: *const c_char, path: *const c_c
     }
 }
 
-/// Unloads a model, freeing its associated resources.
+/// Loads an ONNX model like `infera_load_model` and reports how it was obtained.
+///
+/// For a URL, this tells a cache hit apart from a download, for example to log slow loads.
+/// The outcome is also reported as `"load_outcome"` by `infera_get_model_info`.
 ///
 /// # Arguments
 ///
-/// * `name` - A pointer to a null-terminated C string representing the name of the model to unload.
+/// * `name` - A pointer to a null-terminated C string representing the unique name for the model.
+/// * `path` - A pointer to a null-terminated C string representing the file path or URL of the model.
 ///
 /// # Returns
 ///
-/// * `0` on success.
-/// * `-1` if the model was not found or an error occurred.
+/// * An `InferaLoadOutcome` value on success: `0` for a local file, `1` for a cache hit
+///   without contacting the server, `2` for a download, or `3` for a cached copy the
+///   server confirmed with `304 Not Modified`.
+/// * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
 ///
 /// # Safety
 ///
-/// * The `name` pointer must not be null.
-/// * The memory pointed to by `name` must be a valid, null-terminated C string.
+/// * The `name` and `path` pointers must not be null.
+/// * The memory pointed to by `name` and `path` must be valid, null-terminated C strings.
 #[no_mangle]
-pub unsafe extern "C" fn infera_unload_model(name: *const c_char) -> i32 {
-    let result = (|| -> Result<(), error::InferaError> {
-        if name.is_null() {
+pub unsafe extern "C" fn infera_load_model_ex(name: *const c_char, path: *const c_char) -> i32 {
+    let result = (|| -> Result<model::InferaLoadOutcome, error::InferaError> {
+        if name.is_null() || path.is_null() {
             return Err(error::InferaError::NullPointer);
         }
         let name_str = CStr::from_ptr(name).to_str()?;
-        if model::MODELS.write().remove(name_str).is_some() {
-            Ok(())
-        } else {
-            Err(error::InferaError::ModelNotFound(name_str.to_string()))
-        }
+        let path_or_url_str = CStr::from_ptr(path).to_str()?;
+        load_model_from_path_or_url(
+            &model::resolve_name(name_str),
+            path_or_url_str,
+            model::LoadOptions::default(),
+        )
     })();
 
     match result {
-        Ok(()) => 0,
+        Ok(outcome) => outcome as i32,
         Err(e) => {
             error::set_last_error(&e);
             -1
@@ -101,205 +134,439 @@ pub unsafe extern "C" fn infera_unload_model(name: *const c_char) -> i32 {
     }
 }
 
-/// Runs inference on a loaded model with the given input data.
+/// Loads an ONNX model like `infera_load_model`, with additional load options.
 ///
-/// The input data is provided as a raw pointer to a flat array of `f32` values.
-/// The result of the inference is returned in an `InferaInferenceResult` struct.
-/// The caller is responsible for freeing the result using `infera_free_result`.
+/// The options are given as a JSON object. Supported keys:
+///
+/// * `"pad_batch"` (bool): For models with a fixed batch dimension, pad inputs with
+///   fewer rows up to the fixed batch and split inputs with more rows into several runs.
+///   The output is truncated back to the caller's row count. Defaults to `false`.
+/// * `"pad_mode"` (string): `"zeros"` (default) or `"repeat_last"`, selecting how the
+///   padding rows are filled.
+/// * `"extra_outputs"` (array of strings): Names of internal nodes to expose as additional
+///   outputs, returned by `infera_predict_all`. Unknown names fail the load.
+/// * `"cache_ttl_secs"` (integer or `"infinite"`): For models loaded from a URL, overrides
+///   `INFERA_CACHE_TTL_SECS` for this load.
+/// * `"versioning"` (bool): Overrides `INFERA_MODEL_VERSIONING` for this load. When set,
+///   a model already loaded under the name is kept as a previous version (see
+///   `infera_rollback_model`) instead of being dropped.
+/// * `"initializers"` (object): Constant values for graph inputs that have no initializer,
+///   such as weights a model takes as inputs, keyed by input name. Each value is an array
+///   of numbers in the input's declared shape, or an object with a `"shape"` and
+///   `"values"` for inputs with dynamic dimensions. Bound inputs are no longer inputs of
+///   the loaded model and are listed under `defaulted_inputs` by `infera_get_model_info`.
+/// * `"load_retries"` (integer): The number of times loading a local model file is retried
+///   after a transient I/O error or while the file is still being written. Overrides
+///   `INFERA_LOAD_RETRY_ATTEMPTS`, which counts the first attempt too.
 ///
 /// # Arguments
 ///
-/// * `model_name` - A pointer to a null-terminated C string for the model's name.
-/// * `data` - A pointer to the input tensor data, organized as a flat array of `f32`.
-/// * `rows` - The number of rows in the input tensor.
-/// * `cols` - The number of columns in the input tensor.
+/// * `name` - A pointer to a null-terminated C string representing the unique name for the model.
+/// * `path` - A pointer to a null-terminated C string representing the file path or URL of the model.
+/// * `options_json` - A pointer to a null-terminated C string containing the JSON options,
+///   or null to use the defaults.
 ///
 /// # Returns
 ///
-/// An `InferaInferenceResult` struct containing the output tensor data and metadata.
-/// If an error occurs, the `status` field of the struct will be `-1`.
+/// * `0` on success.
+/// * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
 ///
 /// # Safety
 ///
-/// * `model_name` and `data` must not be null.
-/// * `model_name` must point to a valid, null-terminated C string.
-/// * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+/// * The `name` and `path` pointers must not be null.
+/// * All non-null pointers must point to valid, null-terminated C strings.
 #[no_mangle]
-pub unsafe extern "C" fn infera_predict(
-    model_name: *const c_char,
-    data: *const f32,
-    rows: usize,
-    cols: usize,
-) -> InferaInferenceResult {
-    let result = (|| -> Result<InferaInferenceResult, error::InferaError> {
-        if model_name.is_null() || data.is_null() {
+pub unsafe extern "C" fn infera_load_model_with_options(
+    name: *const c_char,
+    path: *const c_char,
+    options_json: *const c_char,
+) -> i32 {
+    let result = (|| -> Result<(), error::InferaError> {
+        if name.is_null() || path.is_null() {
             return Err(error::InferaError::NullPointer);
         }
-        let name_str = CStr::from_ptr(model_name).to_str()?;
-        engine::run_inference_impl(name_str, data, rows, cols)
+        let name_str = CStr::from_ptr(name).to_str()?;
+        let path_or_url_str = CStr::from_ptr(path).to_str()?;
+        let options = if options_json.is_null() {
+            model::LoadOptions::default()
+        } else {
+            model::LoadOptions::from_json(CStr::from_ptr(options_json).to_str()?)?
+        };
+        load_model_from_path_or_url(&model::resolve_name(name_str), path_or_url_str, options)?;
+        Ok(())
     })();
 
     match result {
-        Ok(res) => res,
+        Ok(()) => 0,
         Err(e) => {
             error::set_last_error(&e);
-            InferaInferenceResult::error()
+            -1
         }
     }
 }
 
-/// Runs inference on a loaded model with input data from a raw byte `BLOB`.
+/// Loads an ONNX model like `infera_load_model`, after checking that the model file has
+/// the expected SHA-256 checksum.
 ///
-/// This function is useful when the input tensor is stored as a `BLOB`. The byte data
-/// is interpreted as a flat array of `f32` values (native-endian). The function
-/// will attempt to infer the batch size based on the model's expected input shape.
+/// For a URL, a download whose checksum does not match is deleted instead of being
+/// cached, and a cached copy whose checksum does not match is downloaded again. Local
+/// files are checked before they are loaded.
 ///
 /// # Arguments
 ///
-/// * `model_name` - A pointer to a null-terminated C string for the model's name.
-/// * `blob_data` - A pointer to the input data as a raw byte array.
-/// * `blob_len` - The total length of the byte array in `blob_data`.
+/// * `name` - A pointer to a null-terminated C string representing the unique name for the model.
+/// * `path` - A pointer to a null-terminated C string representing the file path or URL of the model.
+/// * `expected_sha256` - A pointer to a null-terminated C string containing the expected
+///   SHA-256 of the model file as 64 hexadecimal characters (case-insensitive).
 ///
 /// # Returns
 ///
-/// An `InferaInferenceResult` struct containing the output. The caller is responsible
-/// for freeing this result using `infera_free_result`.
+/// * `0` on success.
+/// * `-1` on failure, including a checksum mismatch. Call `infera_last_error()` to get a
+///   descriptive error message.
 ///
 /// # Safety
 ///
-/// * `model_name` and `blob_data` must not be null.
-/// * `model_name` must point to a valid, null-terminated C string.
-/// * `blob_data` must point to a contiguous block of memory of size `blob_len`.
-/// * `blob_len` must be a multiple of `std::mem::size_of::<f32>()`.
+/// * The `name`, `path`, and `expected_sha256` pointers must not be null.
+/// * The memory pointed to by them must be valid, null-terminated C strings.
 #[no_mangle]
-pub unsafe extern "C" fn infera_predict_from_blob(
-    model_name: *const c_char,
-    blob_data: *const u8,
-    blob_len: usize,
-) -> InferaInferenceResult {
-    let result = (|| -> Result<InferaInferenceResult, error::InferaError> {
-        if model_name.is_null() || blob_data.is_null() {
+pub unsafe extern "C" fn infera_load_model_verified(
+    name: *const c_char,
+    path: *const c_char,
+    expected_sha256: *const c_char,
+) -> i32 {
+    let result = (|| -> Result<(), error::InferaError> {
+        if name.is_null() || path.is_null() || expected_sha256.is_null() {
             return Err(error::InferaError::NullPointer);
         }
-        let name_str = CStr::from_ptr(model_name).to_str()?;
-        engine::run_inference_blob_impl(name_str, blob_data, blob_len)
+        let name_str = CStr::from_ptr(name).to_str()?;
+        let path_or_url_str = CStr::from_ptr(path).to_str()?;
+        let checksum = http::parse_sha256(CStr::from_ptr(expected_sha256).to_str()?)?;
+        let options = model::LoadOptions {
+            expected_sha256: Some(checksum),
+            ..model::LoadOptions::default()
+        };
+        load_model_from_path_or_url(&model::resolve_name(name_str), path_or_url_str, options)?;
+        Ok(())
     })();
 
     match result {
-        Ok(res) => res,
+        Ok(()) => 0,
         Err(e) => {
             error::set_last_error(&e);
-            InferaInferenceResult::error()
+            -1
         }
     }
 }
 
-/// Retrieves metadata about a specific loaded model as a JSON string.
+/// Loads an ONNX model like `infera_load_model`, into the given namespace.
 ///
-/// The returned JSON string includes the model's name, and its input and output shapes.
+/// The model is stored as `ns/name`. It is only listed by
+/// `infera_get_loaded_models_ns` for its namespace, and other functions find it either by
+/// its qualified name or by `name` on a thread whose default namespace is `ns`.
 ///
 /// # Arguments
 ///
-/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `ns` - A pointer to a null-terminated C string naming the namespace. It must
+///   be non-empty and must not contain `/`.
+/// * `name` - A pointer to a null-terminated C string representing the name of the model
+///   within the namespace. It must be non-empty and must not contain `/`.
+/// * `path` - A pointer to a null-terminated C string representing the file path or URL of the model.
 ///
 /// # Returns
 ///
-/// A pointer to a heap-allocated, null-terminated C string containing JSON.
-/// The caller is responsible for freeing this string using `infera_free`.
-/// On error (e.g., model not found), the JSON will contain an "error" key.
+/// * `0` on success.
+/// * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
 ///
 /// # Safety
 ///
-/// * The `model_name` pointer must not be null and must point to a valid C string.
-/// * The returned pointer must be freed with `infera_free` to avoid memory leaks.
+/// * The `ns`, `name`, and `path` pointers must not be null.
+/// * The memory they point to must be valid, null-terminated C strings.
 #[no_mangle]
-pub unsafe extern "C" fn infera_get_model_info(model_name: *const c_char) -> *mut c_char {
-    let result = (|| -> Result<String, error::InferaError> {
-        if model_name.is_null() {
+pub unsafe extern "C" fn infera_load_model_ns(
+    ns: *const c_char,
+    name: *const c_char,
+    path: *const c_char,
+) -> i32 {
+    let result = (|| -> Result<(), error::InferaError> {
+        if ns.is_null() || name.is_null() || path.is_null() {
             return Err(error::InferaError::NullPointer);
         }
-        let name_str = CStr::from_ptr(model_name).to_str()?;
-        engine::get_model_metadata_impl(name_str)
+        let ns_str = CStr::from_ptr(ns).to_str()?;
+        let name_str = CStr::from_ptr(name).to_str()?;
+        let path_or_url_str = CStr::from_ptr(path).to_str()?;
+        let key = model::qualified_name(ns_str, name_str)?;
+        load_model_from_path_or_url(&key, path_or_url_str, model::LoadOptions::default())?;
+        Ok(())
     })();
 
     match result {
-        Ok(json) => CString::new(json).unwrap_or_default().into_raw(),
+        Ok(()) => 0,
         Err(e) => {
             error::set_last_error(&e);
-            let error_json = json!({ "error": e.to_string() }).to_string();
-            CString::new(error_json).unwrap_or_default().into_raw()
+            -1
         }
     }
 }
 
-/// Returns a JSON array of the names of all currently loaded models.
+/// Loads an ONNX model from an open file descriptor and assigns it a unique name.
+///
+/// This is for sandboxed hosts that hand over pre-opened file descriptors instead of
+/// paths. The descriptor is borrowed: it is read through a duplicate and is neither
+/// closed nor owned by Infera. A seekable descriptor (such as a regular file) is read
+/// from its start; one that cannot seek (such as a pipe) is read from its current
+/// position, so it must deliver the whole model and then reach end-of-file (for
+/// example, when the writer closes its end). The model is reported with the path `fd:<fd>`, and no detached
+/// signature or `<model>.features.json` file is looked for.
+///
+/// # Arguments
+///
+/// * `name` - A pointer to a null-terminated C string representing the unique name for the model.
+/// * `fd` - An open file descriptor to read the ONNX model bytes from.
 ///
 /// # Returns
 ///
-/// A pointer to a heap-allocated, null-terminated C string containing a JSON array of strings.
-/// The caller is responsible for freeing this string using `infera_free`.
+/// * `0` on success.
+/// * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
 ///
 /// # Safety
 ///
-/// The returned pointer must be freed with `infera_free` to avoid memory leaks.
+/// * The `name` pointer must not be null.
+/// * The memory pointed to by `name` must be a valid, null-terminated C string.
+/// * `fd` must be an open file descriptor for the duration of the call.
+#[cfg(unix)]
 #[no_mangle]
-pub extern "C" fn infera_get_loaded_models() -> *mut c_char {
-    let models = model::MODELS.read();
-    let list: Vec<String> = models.keys().cloned().collect();
-    let joined = serde_json::to_string(&list).unwrap_or_else(|_| "[]".to_string());
-    match CString::new(joined) {
-        Ok(cstr) => cstr.into_raw(),
-        Err(_) => {
-            // Fallback to empty JSON array if string contains null bytes
-            match CString::new("[]") {
-                Ok(cstr) => cstr.into_raw(),
-                Err(_) => std::ptr::null_mut(), // This should never happen with "[]"
-            }
+pub unsafe extern "C" fn infera_load_model_from_fd(name: *const c_char, fd: i32) -> i32 {
+    let result = (|| -> Result<(), error::InferaError> {
+        use std::io::{Read, Seek};
+        use std::os::fd::BorrowedFd;
+
+        if name.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(name).to_str()?;
+        if fd < 0 {
+            return Err(error::InferaError::InvalidFileDescriptor(fd));
+        }
+        let owned = BorrowedFd::borrow_raw(fd)
+            .try_clone_to_owned()
+            .map_err(|e| error::InferaError::IoError(e.to_string()))?;
+        let mut file = std::fs::File::from(owned);
+        // Descriptors that cannot seek are read from where they are
+        let _ = file.rewind();
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)
+            .map_err(|e| error::InferaError::IoError(e.to_string()))?;
+        engine::load_model_from_bytes_impl(
+            &model::resolve_name(name_str),
+            &format!("fd:{}", fd),
+            &bytes,
+            model::LoadOptions::default(),
+        )
+    })();
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
         }
     }
 }
 
-/// Returns a JSON string with version and build information about the Infera library.
+/// Resolves a local path or remote URL to a local file and loads the model from it.
 ///
-/// The JSON object includes the library version, the enabled ONNX backend (e.g., "tract"),
-/// and the directory used for caching remote models.
+/// If a remote model was served from the cache but fails to parse (for example, because
+/// the cached file is truncated or corrupt), its cache entry is evicted and the model is
+/// downloaded and loaded once more before the error is returned.
+fn load_model_from_path_or_url(
+    name: &str,
+    path_or_url: &str,
+    options: model::LoadOptions,
+) -> Result<model::InferaLoadOutcome, error::InferaError> {
+    if bundle::is_bundle(path_or_url) {
+        return load_bundle(name, path_or_url, options);
+    }
+    if !path_or_url.starts_with("http") {
+        engine::load_model_with_options_impl(name, path_or_url, options)?;
+        return Ok(model::InferaLoadOutcome::Local);
+    }
+
+    let ttl_secs = options.cache_ttl_secs;
+    let expected_sha256 = options.expected_sha256.clone();
+    let cached = http::resolve_remote_model(path_or_url, ttl_secs, expected_sha256.as_deref())?;
+    let mut outcome = model::InferaLoadOutcome::of_cached(&cached);
+    match load_model_from_file(name, &cached.path, options.clone()) {
+        Err(e) if cached.from_cache && matches!(e.root(), error::InferaError::OnnxError(_)) => {
+            crate::log!(
+                LogLevel::Warn,
+                "Cached model for {} failed to load, downloading it again: {}",
+                path_or_url,
+                e
+            );
+            http::evict_cached_model(path_or_url)?;
+            let downloaded =
+                http::resolve_remote_model(path_or_url, ttl_secs, expected_sha256.as_deref())?;
+            load_model_from_file(name, &downloaded.path, options)?;
+            outcome = model::InferaLoadOutcome::of_cached(&downloaded);
+        }
+        result => result?,
+    }
+    model::set_remote_source(
+        name,
+        model::RemoteSource {
+            url: path_or_url.to_string(),
+            etag: http::cached_etag(path_or_url),
+            outcome,
+        },
+    );
+    Ok(outcome)
+}
+
+/// Loads the model in an `.infera.zip` bundle from a local path or remote URL.
+///
+/// A remote bundle is downloaded through the cache like a model file. The bundle is
+/// unpacked into the cache, and its model is loaded with the feature names from its
+/// `features.json` (unless the `feature_names` option is set). Its manifest, labels, and
+/// preprocessing are kept on the model and reported by `infera_get_model_info`. An empty
+/// `name` (or an empty name after a namespace) loads the model under the manifest's name.
+/// The `expected_sha256` option pins the bundle file rather than the model inside it.
+fn load_bundle(
+    name: &str,
+    path_or_url: &str,
+    mut options: model::LoadOptions,
+) -> Result<model::InferaLoadOutcome, error::InferaError> {
+    let expected_sha256 = options.expected_sha256.take();
+    let remote = path_or_url.starts_with("http");
+    let (bundle_path, outcome) = if remote {
+        let cached = http::resolve_remote_model(
+            path_or_url,
+            options.cache_ttl_secs,
+            expected_sha256.as_deref(),
+        )?;
+        let outcome = model::InferaLoadOutcome::of_cached(&cached);
+        (cached.path, outcome)
+    } else {
+        let path = std::path::PathBuf::from(path_or_url);
+        if let Some(expected) = &expected_sha256 {
+            http::verify_sha256(path_or_url, &http::file_sha256(&path)?, expected)?;
+        }
+        (path, model::InferaLoadOutcome::Local)
+    };
+    let bundle = bundle::open_bundle(&bundle_path)?;
+    let name = model::name_or_default(name, &bundle.info.name);
+    if options.feature_names.is_none() {
+        options.feature_names = bundle.feature_names;
+    }
+    load_model_from_file(&name, &bundle.model_path, options)?;
+    model::set_bundle(&name, bundle.info);
+    if remote {
+        model::set_remote_source(
+            &name,
+            model::RemoteSource {
+                url: path_or_url.to_string(),
+                etag: http::cached_etag(path_or_url),
+                outcome,
+            },
+        );
+    }
+    Ok(outcome)
+}
+
+/// Loads a model from a local file path.
+fn load_model_from_file(
+    name: &str,
+    path: &std::path::Path,
+    options: model::LoadOptions,
+) -> Result<(), error::InferaError> {
+    let path_str = path.to_str().ok_or(error::InferaError::Utf8Error)?;
+    engine::load_model_with_options_impl(name, path_str, options)
+}
+
+/// Downloads a model from a URL to a file, without caching or loading it.
+///
+/// The file is written next to `dest_path` and renamed over it once the download is
+/// complete, so an existing file at `dest_path` is only replaced by a complete download.
+/// Failed attempts are retried like cached downloads, and `INFERA_ALLOWED_HOSTS`,
+/// `INFERA_HOST_OVERRIDES`, and the HTTP timeouts apply, but the managed cache is not
+/// touched.
+///
+/// # Arguments
+///
+/// * `url` - A pointer to a null-terminated C string for the model's URL.
+/// * `dest_path` - A pointer to a null-terminated C string for the file to write.
 ///
 /// # Returns
 ///
-/// A pointer to a heap-allocated, null-terminated C string containing the version info.
-/// The caller is responsible for freeing this string using `infera_free`.
+/// * `0` on success.
+/// * `-1` on failure (e.g., Infera is offline, the download fails, or `dest_path` cannot
+///   be written). Call `infera_last_error()` to get a descriptive error message.
 ///
 /// # Safety
 ///
-/// The returned pointer must be freed with `infera_free` to avoid memory leaks.
+/// * The `url` and `dest_path` pointers must not be null.
+/// * The memory pointed to by `url` and `dest_path` must be valid, null-terminated C strings.
 #[no_mangle]
-pub extern "C" fn infera_get_version() -> *mut c_char {
-    let cache_dir_str = config::CONFIG.cache_dir.to_string_lossy().to_string();
-    let info = json!({
-        "version": env!("CARGO_PKG_VERSION"),
-        "onnx_backend": if cfg!(feature = "tract") { "tract" } else { "disabled" },
-        "model_cache_dir": cache_dir_str,
-    });
-    let json_str = serde_json::to_string(&info).unwrap_or_default();
-    CString::new(json_str).unwrap_or_default().into_raw()
+pub unsafe extern "C" fn infera_download_model(
+    url: *const c_char,
+    dest_path: *const c_char,
+) -> i32 {
+    let result = (|| -> Result<u64, error::InferaError> {
+        if url.is_null() || dest_path.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let url_str = CStr::from_ptr(url).to_str()?;
+        let dest_str = CStr::from_ptr(dest_path).to_str()?;
+        http::download_model_to(url_str, std::path::Path::new(dest_str))
+    })();
+
+    match result {
+        Ok(_) => 0,
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
 }
 
-/// Clears the entire model cache directory.
+/// Checks whether the remote copy of a model loaded from a URL has changed.
 ///
-/// This removes all cached remote models, freeing up disk space.
+/// A conditional request is sent with the ETag of the downloaded copy. Nothing is
+/// downloaded and the loaded model is not reloaded.
+///
+/// # Arguments
+///
+/// * `name` - A pointer to a null-terminated C string for the model's name.
 ///
 /// # Returns
 ///
-/// * `0` on success.
-/// * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
+/// * `1` if the remote model has changed since it was downloaded.
+/// * `0` if it has not changed.
+/// * `-1` on failure (e.g., the model was loaded from a local file, the server sent no
+///   ETag, or the request failed). Call `infera_last_error()` to get a descriptive error
+///   message.
 ///
 /// # Safety
 ///
-/// This function is safe to call at any time.
+/// * The `name` pointer must not be null.
+/// * The memory pointed to by `name` must be a valid, null-terminated C string.
 #[no_mangle]
-pub extern "C" fn infera_clear_cache() -> i32 {
-    match http::clear_cache() {
-        Ok(()) => 0,
+pub unsafe extern "C" fn infera_check_model_updated(name: *const c_char) -> i32 {
+    let result = (|| -> Result<bool, error::InferaError> {
+        if name.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(name).to_str()?;
+        let source = model::remote_source(&model::resolve_name(name_str))?;
+        let etag = source
+            .etag
+            .ok_or_else(|| error::InferaError::MissingEtag(source.url.clone()))?;
+        http::check_remote_updated(&source.url, &etag)
+    })();
+
+    match result {
+        Ok(true) => 1,
+        Ok(false) => 0,
         Err(e) => {
             error::set_last_error(&e);
             -1
@@ -307,351 +574,6276 @@ pub extern "C" fn infera_clear_cache() -> i32 {
     }
 }
 
-/// Returns cache statistics as a JSON string.
+/// Unloads a model, freeing its associated resources.
 ///
-/// The JSON object includes:
-/// * `"cache_dir"`: The path to the cache directory.
-/// * `"total_size_bytes"`: Total size of cached models in bytes.
-/// * `"file_count"`: Number of cached model files.
-/// * `"size_limit_bytes"`: The configured cache size limit.
+/// # Arguments
+///
+/// * `name` - A pointer to a null-terminated C string representing the name of the model to unload.
 ///
 /// # Returns
 ///
-/// A pointer to a heap-allocated, null-terminated C string containing the cache info.
-/// The caller is responsible for freeing this string using `infera_free`.
+/// * `0` on success.
+/// * `-1` if the model was not found or an error occurred.
 ///
 /// # Safety
 ///
-/// The returned pointer must be freed with `infera_free` to avoid memory leaks.
+/// * The `name` pointer must not be null.
+/// * The memory pointed to by `name` must be a valid, null-terminated C string.
 #[no_mangle]
-pub extern "C" fn infera_get_cache_info() -> *mut c_char {
-    let result = (|| -> Result<serde_json::Value, error::InferaError> {
-        let cache_dir = http::cache_dir();
-        let cache_dir_str = cache_dir.to_string_lossy().to_string();
-
-        let mut total_size = 0u64;
-        let mut file_count = 0usize;
-
-        if cache_dir.exists() {
-            for entry in fs::read_dir(&cache_dir)
-                .map_err(|e| error::InferaError::IoError(e.to_string()))?
-                .flatten()
-            {
-                let path = entry.path();
-                if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("onnx") {
-                    if let Ok(metadata) = fs::metadata(&path) {
-                        total_size += metadata.len();
-                        file_count += 1;
-                    }
-                }
-            }
+pub unsafe extern "C" fn infera_unload_model(name: *const c_char) -> i32 {
+    let result = (|| -> Result<(), error::InferaError> {
+        if name.is_null() {
+            return Err(error::InferaError::NullPointer);
         }
-
-        let size_limit = config::CONFIG.cache_size_limit;
-
-        Ok(json!({
-            "cache_dir": cache_dir_str,
-            "total_size_bytes": total_size,
-            "file_count": file_count,
-            "size_limit_bytes": size_limit,
-        }))
+        let name_str = model::resolve_name(CStr::from_ptr(name).to_str()?);
+        model::unload_model(&name_str)
     })();
 
-    let final_json = result.unwrap_or_else(|e| {
-        error::set_last_error(&e);
-        json!({"error": e.to_string()})
-    });
-    let json_str = serde_json::to_string(&final_json).unwrap_or_default();
-    CString::new(json_str).unwrap_or_default().into_raw()
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
 }
 
-/// Scans a directory for `.onnx` files and loads them into Infera automatically.
+/// Renames a loaded model without recompiling it.
 ///
-/// The name for each model is derived from its filename (without the extension).
+/// Canary aliases whose candidate or fallback is the model are re-pointed at the new name,
+/// and the model keeps its concurrency limit and its autoload group membership.
 ///
 /// # Arguments
 ///
-/// * `path` - A pointer to a null-terminated C string representing the directory path.
+/// * `old_name` - A pointer to a null-terminated C string with the model's current name.
+/// * `new_name` - A pointer to a null-terminated C string with the model's new name.
+/// * `replace` - If `true`, a model already loaded under `new_name` is unloaded and
+///   replaced. If `false`, the rename fails when `new_name` is taken.
 ///
 /// # Returns
 ///
-/// A pointer to a heap-allocated C string containing a JSON object with two fields:
-/// * `"loaded"`: A list of model names that were successfully loaded.
-/// * `"errors"`: A list of objects, each detailing a file that failed to load and the reason.
-///
-/// The caller is responsible for freeing this string using `infera_free`.
+/// * `0` on success.
+/// * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
 ///
 /// # Safety
 ///
-/// * The `path` pointer must not be null and must point to a valid C string.
-/// * The returned pointer must be freed with `infera_free` to avoid memory leaks.
+/// * The `old_name` and `new_name` pointers must not be null.
+/// * The memory pointed to by `old_name` and `new_name` must be valid, null-terminated C strings.
 #[no_mangle]
-pub unsafe extern "C" fn infera_set_autoload_dir(path: *const c_char) -> *mut c_char {
+pub unsafe extern "C" fn infera_rename_model(
+    old_name: *const c_char,
+    new_name: *const c_char,
+    replace: bool,
+) -> i32 {
+    let result = (|| -> Result<(), error::InferaError> {
+        if old_name.is_null() || new_name.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let old_name_str = model::resolve_name(CStr::from_ptr(old_name).to_str()?);
+        let new_name_str = model::resolve_name(CStr::from_ptr(new_name).to_str()?);
+        model::rename_model(&old_name_str, &new_name_str, replace)
+    })();
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Returns a JSON array of the current and previous versions of a model, newest first.
+///
+/// Previous versions are kept when a model is loaded under a taken name with versioning
+/// enabled (`INFERA_MODEL_VERSIONING` or the `"versioning"` load option), up to
+/// `INFERA_MODEL_HISTORY_DEPTH` versions per name. Each entry has its `index` (0 for the
+/// current version, 1 for the version `infera_rollback_model` would restore, and so on),
+/// whether it is `current`, the `source` it was loaded from (the URL for remote models,
+/// otherwise the file path), and `loaded_at` (seconds since the Unix epoch).
+///
+/// # Arguments
+///
+/// * `name` - A pointer to a null-terminated C string for the model's name.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated, null-terminated C string containing JSON.
+/// The caller is responsible for freeing this string using `infera_free_string`.
+/// If the model is not loaded, the JSON will be an object with an "error" key.
+///
+/// # Safety
+///
+/// * The `name` pointer must not be null and must point to a valid C string.
+/// * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub unsafe extern "C" fn infera_list_model_versions(name: *const c_char) -> *mut c_char {
     let result = (|| -> Result<serde_json::Value, error::InferaError> {
-        if path.is_null() {
+        if name.is_null() {
             return Err(error::InferaError::NullPointer);
         }
-        let path_str = CStr::from_ptr(path).to_str()?;
+        let name_str = model::resolve_name(CStr::from_ptr(name).to_str()?);
+        model::list_versions(&name_str)
+    })();
 
-        let mut loaded = Vec::new();
-        let mut errors = Vec::new();
-
-        let entries =
-            fs::read_dir(path_str).map_err(|e| error::InferaError::IoError(e.to_string()))?;
-        for entry in entries.flatten() {
-            let file_path = entry.path();
-            if file_path.is_file() && file_path.extension().is_some_and(|ext| ext == "onnx") {
-                if let Some(name) = file_path.file_stem().and_then(|s| s.to_str()) {
-                    if let Some(full_path) = file_path.to_str() {
-                        match engine::load_model_impl(name, full_path) {
-                            Ok(_) => loaded.push(name.to_string()),
-                            Err(e) => {
-                                errors.push(json!({ "file": full_path, "error": e.to_string() }))
-                            }
-                        }
-                    }
-                }
-            }
+    let final_json = result.unwrap_or_else(|e| {
+        error::set_last_error(&e);
+        e.to_json()
+    });
+    let json_str = serde_json::to_string(&final_json).unwrap_or_default();
+    CString::new(json_str).unwrap_or_default().into_raw()
+}
+
+/// Makes the previous version of a model current again.
+///
+/// The current version is dropped, and predictions that start after the call run against
+/// the restored version. The swap is atomic, so no prediction ever sees the model missing.
+///
+/// # Arguments
+///
+/// * `name` - A pointer to a null-terminated C string for the model's name.
+///
+/// # Returns
+///
+/// * `0` on success.
+/// * `-1` if the model has no previous version. Call `infera_last_error()` to get a
+///   descriptive error message.
+///
+/// # Safety
+///
+/// The `name` pointer must not be null and must point to a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn infera_rollback_model(name: *const c_char) -> i32 {
+    let result = (|| -> Result<(), error::InferaError> {
+        if name.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = model::resolve_name(CStr::from_ptr(name).to_str()?);
+        model::rollback_model(&name_str)
+    })();
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Drops all but the `keep` newest previous versions of a model to free their memory.
+///
+/// The current version is never dropped. Pass `0` to drop every previous version.
+///
+/// # Arguments
+///
+/// * `name` - A pointer to a null-terminated C string for the model's name.
+/// * `keep` - The number of previous versions to keep.
+///
+/// # Returns
+///
+/// * The number of versions dropped on success.
+/// * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
+///
+/// # Safety
+///
+/// The `name` pointer must not be null and must point to a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn infera_prune_model_versions(name: *const c_char, keep: usize) -> i32 {
+    let result = (|| -> Result<usize, error::InferaError> {
+        if name.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = model::resolve_name(CStr::from_ptr(name).to_str()?);
+        Ok(model::prune_versions(&name_str, keep))
+    })();
+
+    match result {
+        Ok(count) => i32::try_from(count).unwrap_or(i32::MAX),
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Points an alias at a candidate model, falling back to a known-good model if the
+/// candidate starts failing.
+///
+/// Predictions through the alias (with `infera_predict` and the other prediction
+/// functions, including `infera_submit`) go to the candidate. Each one is recorded in a
+/// sliding window of the last `window` candidate predictions, counting inference errors
+/// and, with `INFERA_CANARY_COUNT_NON_FINITE`, outputs containing NaN or infinite values
+/// as failures. Once the window is full and more than `max_error_rate` of it failed, the
+/// alias is atomically re-pointed to the fallback, an `ERROR` is logged, and the trip is
+/// recorded for `infera_get_canary_status`. Setting an alias that is already a canary
+/// replaces it and resets its statistics.
+///
+/// # Arguments
+///
+/// * `alias` - A pointer to a null-terminated C string for the alias. It must not be the
+///   name of a loaded model.
+/// * `candidate` - A pointer to a null-terminated C string naming the loaded model under test.
+/// * `fallback` - A pointer to a null-terminated C string naming the loaded model to fall
+///   back to.
+/// * `max_error_rate` - The highest tolerated fraction of failed predictions, from 0 to 1.
+/// * `window` - The number of most recent candidate predictions the error rate is computed
+///   over. Must be greater than zero.
+///
+/// # Returns
+///
+/// * `0` on success.
+/// * `-1` on failure (e.g., a model is not loaded or the policy is invalid). Call
+///   `infera_last_error()` to get a descriptive error message.
+///
+/// # Safety
+///
+/// * The `alias`, `candidate`, and `fallback` pointers must not be null.
+/// * The memory they point to must be valid, null-terminated C strings.
+#[no_mangle]
+pub unsafe extern "C" fn infera_set_canary(
+    alias: *const c_char,
+    candidate: *const c_char,
+    fallback: *const c_char,
+    max_error_rate: f64,
+    window: usize,
+) -> i32 {
+    let result = (|| -> Result<(), error::InferaError> {
+        if alias.is_null() || candidate.is_null() || fallback.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let alias_str = model::resolve_name(CStr::from_ptr(alias).to_str()?);
+        let candidate_str = model::resolve_name(CStr::from_ptr(candidate).to_str()?);
+        let fallback_str = model::resolve_name(CStr::from_ptr(fallback).to_str()?);
+        canary::set_canary(
+            &alias_str,
+            &candidate_str,
+            &fallback_str,
+            max_error_rate,
+            window,
+        )
+    })();
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Finalizes a canary by making its candidate the permanent target of the alias.
+///
+/// Errors of the candidate are no longer tracked, so the alias never falls back
+/// afterwards. Promoting a canary that has tripped points the alias back at the candidate.
+///
+/// # Arguments
+///
+/// * `alias` - A pointer to a null-terminated C string for the alias.
+///
+/// # Returns
+///
+/// * `0` on success.
+/// * `-1` if the alias is not a canary. Call `infera_last_error()` to get a descriptive
+///   error message.
+///
+/// # Safety
+///
+/// * The `alias` pointer must not be null.
+/// * The memory pointed to by `alias` must be a valid, null-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn infera_promote_canary(alias: *const c_char) -> i32 {
+    let result = (|| -> Result<(), error::InferaError> {
+        if alias.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        canary::promote_canary(&model::resolve_name(CStr::from_ptr(alias).to_str()?))
+    })();
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Returns the state and statistics of a canary alias as a JSON object.
+///
+/// The object has the `alias`, `candidate`, and `fallback` names, the `target`
+/// predictions currently go to, the `state` (`"canary"`, `"tripped"`, or `"promoted"`),
+/// the policy (`max_error_rate` and `window`), the current window (`window_requests` and
+/// `window_error_rate`), the totals since the canary was set (`requests` and `errors`),
+/// and, once it has tripped, `tripped_at` (seconds since the Unix epoch) and
+/// `trip_error_rate`.
+///
+/// # Arguments
+///
+/// * `alias` - A pointer to a null-terminated C string for the alias.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated, null-terminated C string containing JSON.
+/// The caller is responsible for freeing this string using `infera_free_string`.
+/// If the alias is not a canary, the JSON will be an object with an "error" key.
+///
+/// # Safety
+///
+/// * The `alias` pointer must not be null and must point to a valid C string.
+/// * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub unsafe extern "C" fn infera_get_canary_status(alias: *const c_char) -> *mut c_char {
+    let result = (|| -> Result<serde_json::Value, error::InferaError> {
+        if alias.is_null() {
+            return Err(error::InferaError::NullPointer);
         }
-        Ok(json!({"loaded": loaded, "errors": errors}))
+        canary::canary_status(&model::resolve_name(CStr::from_ptr(alias).to_str()?))
     })();
 
     let final_json = result.unwrap_or_else(|e| {
         error::set_last_error(&e);
-        json!({"error": e.to_string()})
+        e.to_json()
     });
     let json_str = serde_json::to_string(&final_json).unwrap_or_default();
     CString::new(json_str).unwrap_or_default().into_raw()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::ffi::CString;
-    use std::fs;
-    use tempfile::tempdir;
+/// Limits how many predictions can run on a model at once.
+///
+/// Every run of the model (by `infera_predict` and the other prediction, streaming,
+/// ensemble, comparison, and profiling functions, including those through a canary alias
+/// and `infera_submit`) holds one of `max` permits while it runs. The chunks of a large
+/// batch share one permit. When all permits are taken, a prediction waits for one to be
+/// released, or fails with status `Busy` if `INFERA_CONCURRENCY_BLOCK` is disabled. This
+/// keeps a heavy model from running on more threads than the CPU caches can serve.
+/// Changing the limit applies to predictions that have not started yet. The limit stays
+/// in place if the model is reloaded under the same name, and is removed when the model
+/// is unloaded. `infera_get_model_info`
+/// reports it as `max_concurrency`.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the loaded model's name.
+/// * `max` - The maximum number of concurrent predictions, or `0` to remove the limit.
+///
+/// # Returns
+///
+/// * `0` on success.
+/// * `-1` on failure (e.g., the model is not loaded). Call `infera_last_error()` to get a
+///   descriptive error message.
+///
+/// # Safety
+///
+/// * The `model_name` pointer must not be null.
+/// * The memory pointed to by `model_name` must be a valid, null-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn infera_set_model_concurrency(
+    model_name: *const c_char,
+    max: usize,
+) -> i32 {
+    let result = (|| -> Result<(), error::InferaError> {
+        if model_name.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = model::resolve_name(CStr::from_ptr(model_name).to_str()?);
+        model::get_model(&name_str)?;
+        concurrency::set_limit(&name_str, max);
+        log!(
+            LogLevel::Info,
+            "Model '{}' concurrency limit set to {}",
+            name_str,
+            max
+        );
+        Ok(())
+    })();
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Attaches postprocessing to a model's output, such as clamping scores to `[0, 1]` and
+/// rounding them, so it does not have to be done per row in the caller.
+///
+/// `spec_json` is a JSON object with any of these steps, which run in this order
+/// regardless of their order in the object:
+///
+/// * `scale` and `offset` - Multiply every value by `scale`, then add `offset`.
+/// * `activation` - Apply `"sigmoid"` to every value, or `"softmax"` to each output row.
+/// * `clip_min` and `clip_max` - Clamp every value to the range.
+/// * `round` - Round every value to the given number of decimals (0 to 10).
+///
+/// The steps run on the model's first output, before it is returned, in `infera_predict`
+/// and the other prediction functions that return it as floats or bytes, including
+/// streams. Outputs returned as JSON are not postprocessed. An empty string or object
+/// detaches the postprocessing. Predictions that have already started finish with the
+/// postprocessing they started with. Loading a model under the same name drops it, and
+/// `infera_get_model_info` reports it as `postprocessor`.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the loaded model's name.
+/// * `spec_json` - A pointer to a null-terminated C string with the postprocessing spec.
+///
+/// # Returns
+///
+/// * `0` on success.
+/// * `-1` on failure (e.g., the model is not loaded or the spec is invalid). Call
+///   `infera_last_error()` to get a descriptive error message.
+///
+/// # Safety
+///
+/// * The `model_name` and `spec_json` pointers must not be null.
+/// * The memory they point to must be valid, null-terminated C strings.
+#[no_mangle]
+pub unsafe extern "C" fn infera_set_postprocessor(
+    model_name: *const c_char,
+    spec_json: *const c_char,
+) -> i32 {
+    let result = (|| -> Result<(), error::InferaError> {
+        if model_name.is_null() || spec_json.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = model::resolve_name(CStr::from_ptr(model_name).to_str()?);
+        let spec_str = CStr::from_ptr(spec_json).to_str()?;
+        engine::set_postprocessor_impl(&name_str, spec_str)
+    })();
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Runs inference on a loaded model with the given input data.
+///
+/// The input data is provided as a raw pointer to a flat array of `f32` values.
+/// The result of the inference is returned in an `InferaInferenceResult` struct.
+/// The caller is responsible for freeing the result using `infera_free_result`.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `data` - A pointer to the input tensor data, organized as a flat array of `f32`.
+/// * `rows` - The number of rows in the input tensor.
+/// * `cols` - The number of columns in the input tensor.
+///
+/// A model that takes no inputs (a generator graph) is run by passing `rows = cols = 0`,
+/// in which case `data` may be null.
+///
+/// # Returns
+///
+/// An `InferaInferenceResult` struct containing the output tensor data and metadata.
+/// If an error occurs, the `status` field of the struct is a negative `InferaStatus` code
+/// (e.g., `-2` if the model is not loaded or `-3` for an input shape mismatch).
+///
+/// # Safety
+///
+/// * `model_name` must not be null, and `data` must not be null unless `rows` and `cols` are 0.
+/// * `model_name` must point to a valid, null-terminated C string.
+/// * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+#[no_mangle]
+pub unsafe extern "C" fn infera_predict(
+    model_name: *const c_char,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+) -> InferaInferenceResult {
+    let result = (|| -> Result<InferaInferenceResult, error::InferaError> {
+        if model_name.is_null() || (data.is_null() && (rows != 0 || cols != 0)) {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        canary::predict(&model::resolve_name(name_str), |name| {
+            engine::run_inference_impl(name, data, rows, cols)
+        })
+    })();
+
+    match result {
+        Ok(res) => res,
+        Err(e) => {
+            error::set_last_error(&e);
+            InferaInferenceResult::from_error(&e)
+        }
+    }
+}
+
+/// Runs inference like `infera_predict`, with the model's random ops seeded so that
+/// repeated calls with the same `seed` produce identical output.
+///
+/// This makes models with `RandomNormal`, `RandomUniform`, `RandomNormalLike`, or
+/// `RandomUniformLike` nodes reproducible, for example in golden tests. `Dropout` is a
+/// no-op at inference time and needs no seed. Other models give the same output as with
+/// `infera_predict`. Models with random ops are recompiled on each call, so seeded
+/// predictions are slower for them. The caller is responsible for freeing the result
+/// using `infera_free_result`.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `data` - A pointer to the input tensor data, organized as a flat array of `f32`.
+/// * `rows` - The number of rows in the input tensor.
+/// * `cols` - The number of columns in the input tensor.
+/// * `seed` - The seed for the model's random number generators.
+///
+/// # Returns
+///
+/// An `InferaInferenceResult` struct containing the output tensor data and metadata.
+/// If an error occurs, the `status` field of the struct is a negative `InferaStatus` code.
+///
+/// # Safety
+///
+/// * `model_name` and `data` must not be null.
+/// * `model_name` must point to a valid, null-terminated C string.
+/// * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+#[no_mangle]
+pub unsafe extern "C" fn infera_predict_seeded(
+    model_name: *const c_char,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+    seed: u64,
+) -> InferaInferenceResult {
+    let result = (|| -> Result<InferaInferenceResult, error::InferaError> {
+        if model_name.is_null() || data.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        canary::predict(&model::resolve_name(name_str), |name| {
+            engine::run_inference_seeded_impl(name, data, rows, cols, seed)
+        })
+    })();
+
+    match result {
+        Ok(res) => res,
+        Err(e) => {
+            error::set_last_error(&e);
+            InferaInferenceResult::from_error(&e)
+        }
+    }
+}
+
+/// Runs inference like `infera_predict`, also returning how long the model ran and how
+/// many values went in and out, for observability.
+///
+/// `inference_ns` is the wall-clock duration of the model runs, summed over the chunks of
+/// a batch split by `INFERA_BATCH_CHUNK_ROWS`. It does not include converting the input or
+/// copying the output. The caller is responsible for freeing the output by passing the
+/// inner `result` to `infera_free_result`.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `data` - A pointer to the input tensor data, organized as a flat array of `f32`.
+/// * `rows` - The number of rows in the input tensor.
+/// * `cols` - The number of columns in the input tensor.
+///
+/// # Returns
+///
+/// An `InferaProfiledResult` struct with the output in `result`, `inference_ns`,
+/// `input_elements` (`rows * cols`), and `output_elements` (`result.len`). If an error
+/// occurs, `result.status` is a negative `InferaStatus` code and the other fields are 0.
+///
+/// # Safety
+///
+/// * `model_name` must not be null, and `data` must not be null unless `rows` and `cols` are 0.
+/// * `model_name` must point to a valid, null-terminated C string.
+/// * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+#[no_mangle]
+pub unsafe extern "C" fn infera_predict_profiled(
+    model_name: *const c_char,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+) -> InferaProfiledResult {
+    let result = (|| -> Result<InferaProfiledResult, error::InferaError> {
+        if model_name.is_null() || (data.is_null() && (rows != 0 || cols != 0)) {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        canary::predict(&model::resolve_name(name_str), |name| {
+            engine::run_inference_profiled_impl(name, data, rows, cols)
+        })
+    })();
+
+    match result {
+        Ok(res) => res,
+        Err(e) => {
+            error::set_last_error(&e);
+            InferaProfiledResult::from_error(&e)
+        }
+    }
+}
+
+/// Queues a prediction to run on a background worker thread.
+///
+/// The input is copied before this function returns, so the caller may free `data`
+/// immediately. When the prediction finishes, `callback` is invoked on a worker thread
+/// with the request ID, the result, and `user_data`. The callback owns the result and
+/// must free it with `infera_free_result`. If the prediction fails, `result.status` is a
+/// negative `InferaStatus` code and calling `infera_last_error()` from within the callback
+/// returns the error.
+///
+/// Requests are served by `INFERA_WORKER_THREADS` workers from priority queues holding
+/// at most `INFERA_MAX_PENDING` requests in total. When the queues are full, submission
+/// fails immediately. Higher priority requests run first, but a normal or low priority
+/// request that has waited `INFERA_QUEUE_AGING_MS` runs ahead of them.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `data` - A pointer to the input tensor data, organized as a flat array of `f32`.
+/// * `rows` - The number of rows in the input tensor.
+/// * `cols` - The number of columns in the input tensor.
+/// * `priority` - `0` (high), `1` (normal), or `2` (low).
+/// * `callback` - The function to call with the result.
+/// * `user_data` - An opaque pointer passed back to `callback` as is.
+///
+/// # Returns
+///
+/// * A non-zero request ID on success.
+/// * `0` on failure (for example, when the queue is full). Call `infera_last_error()`
+///   to get a descriptive error message.
+///
+/// # Safety
+///
+/// * `model_name`, `data`, and `callback` must not be null.
+/// * `model_name` must point to a valid, null-terminated C string.
+/// * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+/// * `user_data` must remain valid, and be safe to use from another thread, until the
+///   callback has run.
+#[no_mangle]
+pub unsafe extern "C" fn infera_submit(
+    model_name: *const c_char,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+    priority: i32,
+    callback: Option<InferaPredictCallback>,
+    user_data: *mut c_void,
+) -> u64 {
+    let result = (|| -> Result<u64, error::InferaError> {
+        if model_name.is_null() || data.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let callback = callback.ok_or(error::InferaError::NullPointer)?;
+        let priority = worker::Priority::from_i32(priority)?;
+        engine::check_input_size(rows, cols, std::mem::size_of::<f32>())?;
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        let request = worker::Request {
+            model_name: model::resolve_name(name_str),
+            data: std::slice::from_raw_parts(data, rows * cols).to_vec(),
+            rows,
+            cols,
+            callback,
+            user_data,
+        };
+        worker::submit(priority, request)
+    })();
+
+    match result {
+        Ok(id) => id,
+        Err(e) => {
+            error::set_last_error(&e);
+            0
+        }
+    }
+}
+
+/// Cancels a prediction queued with `infera_submit` that has not started running yet.
+///
+/// A cancelled request's callback is never called, so the caller can release any
+/// resources tied to its `user_data` once this function succeeds.
+///
+/// # Arguments
+///
+/// * `request_id` - The ID returned by `infera_submit`.
+///
+/// # Returns
+///
+/// * `0` on success.
+/// * `-1` if the request is not queued (it may be running, finished, or already
+///   cancelled). Call `infera_last_error()` to get a descriptive error message.
+///
+/// # Safety
+///
+/// This function is safe to call at any time.
+#[no_mangle]
+pub extern "C" fn infera_cancel(request_id: u64) -> i32 {
+    match worker::cancel(request_id) {
+        Ok(()) => 0,
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Changes the priority of a prediction queued with `infera_submit`.
+///
+/// The request keeps its original submission time, so it is ordered among requests of
+/// the new priority by when it was submitted.
+///
+/// # Arguments
+///
+/// * `request_id` - The ID returned by `infera_submit`.
+/// * `priority` - `0` (high), `1` (normal), or `2` (low).
+///
+/// # Returns
+///
+/// * `0` on success.
+/// * `-1` if the priority is invalid or the request is not queued. Call
+///   `infera_last_error()` to get a descriptive error message.
+///
+/// # Safety
+///
+/// This function is safe to call at any time.
+#[no_mangle]
+pub extern "C" fn infera_set_priority(request_id: u64, priority: i32) -> i32 {
+    let result = worker::Priority::from_i32(priority)
+        .and_then(|priority| worker::set_priority(request_id, priority));
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Returns runtime metrics as a JSON string.
+///
+/// The JSON object has a `"queue"` key with one entry per priority (`"high"`,
+/// `"normal"`, and `"low"`) for requests submitted with `infera_submit`. Each entry holds
+/// the current queue `depth` and a `wait_ms` histogram of how long requests waited before
+/// running, with cumulative bucket `count`s for each upper bound `le` (in milliseconds).
+/// The `"cache"` key holds the cache activity counters described in `infera_get_cache_info`.
+/// The `"autoload"` key lists the `watched_dirs` of `infera_watch_autoload_dir` and counts
+/// the `scans` of watched directories and the models they `loaded`, `reloaded`, and
+/// `unloaded`, and the files that `failed` to load. The `"result_pool"` key holds the
+/// `capacity` of the result buffer pool (`INFERA_RESULT_POOL_SIZE`), the number of
+/// buffers it holds (`pooled`), and the number of results that reused a pooled buffer
+/// (`reuses`).
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated, null-terminated C string containing the metrics.
+/// The caller is responsible for freeing this string using `infera_free_string`.
+///
+/// # Safety
+///
+/// The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub extern "C" fn infera_get_metrics() -> *mut c_char {
+    let metrics = json!({
+        "queue": worker::metrics(),
+        "cache": http::cache_stats(),
+        "autoload": autoload::watch_metrics(),
+        "result_pool": ffi_utils::RESULT_POOL.to_json(),
+    });
+    let json_str = serde_json::to_string(&metrics).unwrap_or_default();
+    CString::new(json_str).unwrap_or_default().into_raw()
+}
+
+/// Runs inference on a loaded model with half-precision (f16) input data.
+///
+/// The input is a flat array of raw IEEE 754 binary16 values, one `u16` per value. It
+/// is fed natively to models with f16 inputs and widened to f32 for other models. The
+/// output is always returned as f32 in the `InferaInferenceResult`.
+/// The caller is responsible for freeing the result using `infera_free_result`.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `data` - A pointer to the input tensor data, organized as a flat array of f16 bit patterns.
+/// * `rows` - The number of rows in the input tensor.
+/// * `cols` - The number of columns in the input tensor.
+///
+/// # Returns
+///
+/// An `InferaInferenceResult` struct containing the output tensor data and metadata.
+/// If an error occurs, the `status` field of the struct is a negative `InferaStatus` code
+/// (e.g., `-2` if the model is not loaded or `-3` for an input shape mismatch).
+///
+/// # Safety
+///
+/// * `model_name` and `data` must not be null.
+/// * `model_name` must point to a valid, null-terminated C string.
+/// * `data` must point to a contiguous block of memory of size `rows * cols * size_of<u16>()`.
+#[no_mangle]
+pub unsafe extern "C" fn infera_predict_f16(
+    model_name: *const c_char,
+    data: *const u16,
+    rows: usize,
+    cols: usize,
+) -> InferaInferenceResult {
+    let result = (|| -> Result<InferaInferenceResult, error::InferaError> {
+        if model_name.is_null() || data.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        canary::predict(&model::resolve_name(name_str), |name| {
+            engine::run_inference_f16_impl(name, data, rows, cols)
+        })
+    })();
+
+    match result {
+        Ok(res) => res,
+        Err(e) => {
+            error::set_last_error(&e);
+            InferaInferenceResult::from_error(&e)
+        }
+    }
+}
+
+/// Runs inference on a loaded model with `u8` input data.
+///
+/// This is the entry point for quantized models whose input tensor is `u8` (or `i8`,
+/// in which case each byte is reinterpreted as a two's complement value). For models
+/// with other input types, the bytes are converted to the model's input type.
+/// The caller is responsible for freeing the result using `infera_free_result`.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `data` - A pointer to the input tensor data, organized as a flat array of `u8`.
+/// * `rows` - The number of rows in the input tensor.
+/// * `cols` - The number of columns in the input tensor.
+///
+/// # Returns
+///
+/// An `InferaInferenceResult` struct containing the output tensor data and metadata.
+/// If an error occurs, the `status` field of the struct is a negative `InferaStatus` code
+/// (e.g., `-2` if the model is not loaded or `-3` for an input shape mismatch).
+///
+/// # Safety
+///
+/// * `model_name` and `data` must not be null.
+/// * `model_name` must point to a valid, null-terminated C string.
+/// * `data` must point to a contiguous block of memory of size `rows * cols`.
+#[no_mangle]
+pub unsafe extern "C" fn infera_predict_u8(
+    model_name: *const c_char,
+    data: *const u8,
+    rows: usize,
+    cols: usize,
+) -> InferaInferenceResult {
+    let result = (|| -> Result<InferaInferenceResult, error::InferaError> {
+        if model_name.is_null() || data.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        canary::predict(&model::resolve_name(name_str), |name| {
+            engine::run_inference_u8_impl(name, data, rows, cols)
+        })
+    })();
+
+    match result {
+        Ok(res) => res,
+        Err(e) => {
+            error::set_last_error(&e);
+            InferaInferenceResult::from_error(&e)
+        }
+    }
+}
+
+/// Runs inference on a loaded model whose input has named symbolic dimensions.
+///
+/// Models with symbolic dimensions other than the batch (e.g., a sequence length `S` in
+/// an `[N, S, F]` input) need those dimensions bound before the flat `rows x cols` input
+/// can be given its shape. `symbols_json` maps symbol names to values (for example,
+/// `{"S": 128}`), and one remaining dynamic dimension is inferred from the input size.
+/// The symbol names of a model's input are listed under `input_symbols` by
+/// `infera_get_model_info`. The caller is responsible for freeing the result using
+/// `infera_free_result`.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `data` - A pointer to the input tensor data, organized as a flat array of `f32`.
+/// * `rows` - The number of rows in the input tensor (the first input dimension).
+/// * `cols` - The number of columns in the input tensor (all other dimensions flattened).
+/// * `symbols_json` - A pointer to a null-terminated C string containing a JSON object
+///   that maps symbol names to positive integers, or null to bind no symbols.
+///
+/// # Returns
+///
+/// An `InferaInferenceResult` struct containing the output tensor data and metadata.
+/// If an error occurs, the `status` field of the struct is a negative `InferaStatus` code
+/// (e.g., `-3` if the bound shape does not match `rows x cols`).
+///
+/// # Safety
+///
+/// * `model_name` and `data` must not be null.
+/// * `model_name` and `symbols_json` (if not null) must point to valid, null-terminated
+///   C strings.
+/// * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+#[no_mangle]
+pub unsafe extern "C" fn infera_predict_with_symbols(
+    model_name: *const c_char,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+    symbols_json: *const c_char,
+) -> InferaInferenceResult {
+    let result = (|| -> Result<InferaInferenceResult, error::InferaError> {
+        if model_name.is_null() || data.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        let symbols_str = if symbols_json.is_null() {
+            ""
+        } else {
+            CStr::from_ptr(symbols_json).to_str()?
+        };
+        canary::predict(&model::resolve_name(name_str), |name| {
+            engine::run_inference_with_symbols_impl(name, data, rows, cols, symbols_str)
+        })
+    })();
+
+    match result {
+        Ok(res) => res,
+        Err(e) => {
+            error::set_last_error(&e);
+            InferaInferenceResult::from_error(&e)
+        }
+    }
+}
+
+/// Runs inference on a loaded model with input data from a raw byte `BLOB`.
+///
+/// This function is useful when the input tensor is stored as a `BLOB`. The byte data
+/// is interpreted as a flat array of `f32` values (native-endian), or as one element
+/// per byte for models with `u8` or `i8` inputs. The function will attempt to infer
+/// the batch size based on the model's expected input shape.
+/// With `INFERA_STRICT_BLOB` set, the blob must hold exactly one sample instead; use
+/// `infera_predict_from_blob_batch` to pass more.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `blob_data` - A pointer to the input data as a raw byte array.
+/// * `blob_len` - The total length of the byte array in `blob_data`.
+///
+/// # Returns
+///
+/// An `InferaInferenceResult` struct containing the output. The caller is responsible
+/// for freeing this result using `infera_free_result`. If an error occurs, the `status`
+/// field of the struct is a negative `InferaStatus` code.
+///
+/// # Safety
+///
+/// * `model_name` and `blob_data` must not be null.
+/// * `model_name` must point to a valid, null-terminated C string.
+/// * `blob_data` must point to a contiguous block of memory of size `blob_len`.
+/// * `blob_len` must be a multiple of `std::mem::size_of::<f32>()` for `f32` models.
+#[no_mangle]
+pub unsafe extern "C" fn infera_predict_from_blob(
+    model_name: *const c_char,
+    blob_data: *const u8,
+    blob_len: usize,
+) -> InferaInferenceResult {
+    let result = (|| -> Result<InferaInferenceResult, error::InferaError> {
+        if model_name.is_null() || blob_data.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        canary::predict(&model::resolve_name(name_str), |name| {
+            engine::run_inference_blob_impl(name, blob_data, blob_len, None)
+        })
+    })();
+
+    match result {
+        Ok(res) => res,
+        Err(e) => {
+            error::set_last_error(&e);
+            InferaInferenceResult::from_error(&e)
+        }
+    }
+}
+
+/// Runs inference on a loaded model with a raw byte `BLOB` holding exactly `batch` samples.
+///
+/// This works like `infera_predict_from_blob`, but instead of inferring the batch size
+/// from the blob length, the blob must hold exactly `batch` samples of the model input.
+/// A blob with trailing data that happens to fill more samples is rejected.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `blob_data` - A pointer to the input data as a raw byte array.
+/// * `blob_len` - The total length of the byte array in `blob_data`.
+/// * `batch` - The number of samples in the blob.
+///
+/// # Returns
+///
+/// An `InferaInferenceResult` struct containing the output. The caller is responsible
+/// for freeing this result using `infera_free_result`. If an error occurs, the `status`
+/// field of the struct is a negative `InferaStatus` code.
+///
+/// # Safety
+///
+/// * `model_name` and `blob_data` must not be null.
+/// * `model_name` must point to a valid, null-terminated C string.
+/// * `blob_data` must point to a contiguous block of memory of size `blob_len`.
+#[no_mangle]
+pub unsafe extern "C" fn infera_predict_from_blob_batch(
+    model_name: *const c_char,
+    blob_data: *const u8,
+    blob_len: usize,
+    batch: usize,
+) -> InferaInferenceResult {
+    let result = (|| -> Result<InferaInferenceResult, error::InferaError> {
+        if model_name.is_null() || blob_data.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        canary::predict(&model::resolve_name(name_str), |name| {
+            engine::run_inference_blob_impl(name, blob_data, blob_len, Some(batch))
+        })
+    })();
+
+    match result {
+        Ok(res) => res,
+        Err(e) => {
+            error::set_last_error(&e);
+            InferaInferenceResult::from_error(&e)
+        }
+    }
+}
+
+/// Runs inference like `infera_predict_from_blob`, giving up once `timeout_ms`
+/// milliseconds have passed.
+///
+/// Blobs with more samples than `INFERA_BATCH_CHUNK_ROWS` are run in chunks (for models
+/// whose input and output batch dimensions are dynamic), and the timeout is checked
+/// before each chunk starts, so a call can take longer than `timeout_ms` by up to one
+/// chunk. A blob that is not split is checked once, before it runs. A `timeout_ms` of 0
+/// means no timeout.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `blob_data` - A pointer to the input data as a raw byte array.
+/// * `blob_len` - The total length of the byte array in `blob_data`.
+/// * `timeout_ms` - The timeout in milliseconds, or 0 for none.
+///
+/// # Returns
+///
+/// An `InferaInferenceResult` struct containing the output. The caller is responsible
+/// for freeing this result using `infera_free_result`. If an error occurs, the `status`
+/// field of the struct is a negative `InferaStatus` code, such as `-5` (timeout) when the
+/// deadline passes, and no output is returned.
+///
+/// # Safety
+///
+/// * `model_name` and `blob_data` must not be null.
+/// * `model_name` must point to a valid, null-terminated C string.
+/// * `blob_data` must point to a contiguous block of memory of size `blob_len`.
+#[no_mangle]
+pub unsafe extern "C" fn infera_predict_from_blob_timeout(
+    model_name: *const c_char,
+    blob_data: *const u8,
+    blob_len: usize,
+    timeout_ms: u64,
+) -> InferaInferenceResult {
+    let result = (|| -> Result<InferaInferenceResult, error::InferaError> {
+        if model_name.is_null() || blob_data.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        canary::predict(&model::resolve_name(name_str), |name| {
+            engine::run_inference_blob_timeout_impl(name, blob_data, blob_len, timeout_ms)
+        })
+    })();
+
+    match result {
+        Ok(res) => res,
+        Err(e) => {
+            error::set_last_error(&e);
+            InferaInferenceResult::from_error(&e)
+        }
+    }
+}
+
+/// Runs inference like `infera_predict` and returns the output as a byte `BLOB`.
+///
+/// The output values are flattened row by row and written as little-endian `f32` bytes
+/// regardless of the host's byte order (or in the host's byte order with
+/// `native_endian`), so hosts can store them in a `BLOB` column without converting
+/// them. This mirrors `infera_predict_from_blob` on the output side. The bytes are
+/// written while the output is copied out of the model, without a separate pass. On
+/// success, the caller is responsible for freeing `*out` using `infera_free_blob`.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `data` - A pointer to the input tensor data, organized as a flat array of `f32`.
+/// * `rows` - The number of rows in the input tensor.
+/// * `cols` - The number of columns in the input tensor.
+/// * `native_endian` - Whether to write the values in the host's byte order instead of
+///   little-endian.
+/// * `out` - Where to store the `InferaBlob` with the output bytes.
+///
+/// # Returns
+///
+/// * `0` on success.
+/// * A negative `InferaStatus` code on failure, with `*out` set to an empty blob (if
+///   `out` is not null).
+///
+/// # Safety
+///
+/// * `model_name`, `data`, and `out` must not be null.
+/// * `model_name` must point to a valid, null-terminated C string.
+/// * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+#[no_mangle]
+pub unsafe extern "C" fn infera_predict_to_blob(
+    model_name: *const c_char,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+    native_endian: bool,
+    out: *mut InferaBlob,
+) -> i32 {
+    if !out.is_null() {
+        *out = InferaBlob::empty();
+    }
+    let result = (|| -> Result<ffi_utils::OutputBytes, error::InferaError> {
+        if model_name.is_null() || data.is_null() || out.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        canary::predict(&model::resolve_name(name_str), |name| {
+            engine::run_inference_bytes_impl(name, data, rows, cols, native_endian)
+        })
+    })();
+
+    match result {
+        Ok(output) => {
+            *out = InferaBlob::from_vec(output.bytes);
+            0
+        }
+        Err(e) => {
+            error::set_last_error(&e);
+            e.status() as i32
+        }
+    }
+}
+
+/// Begins a streaming session for running inference on a dataset one chunk at a time.
+///
+/// Push chunks with `infera_stream_push` and end the session with `infera_stream_end`.
+/// The session holds on to the model it began with, so unloading or replacing the model
+/// does not affect it, and the model's memory is only released once the session ends.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `options_json` - A pointer to a null-terminated C string containing a JSON object of
+///   options, or null to use the defaults. The only option is `cols`, the number of
+///   columns every chunk must have; without it, the first chunk sets the number.
+///
+/// # Returns
+///
+/// * A positive handle for the session on success.
+/// * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
+///
+/// # Safety
+///
+/// * The `model_name` pointer must not be null.
+/// * All non-null pointers must point to valid, null-terminated C strings.
+#[no_mangle]
+pub unsafe extern "C" fn infera_stream_begin(
+    model_name: *const c_char,
+    options_json: *const c_char,
+) -> i64 {
+    let result = (|| -> Result<i64, error::InferaError> {
+        if model_name.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        let options = if options_json.is_null() {
+            stream::StreamOptions::default()
+        } else {
+            stream::StreamOptions::from_json(CStr::from_ptr(options_json).to_str()?)?
+        };
+        stream::begin(&model::resolve_name(name_str), options)
+    })();
+
+    match result {
+        Ok(handle) => handle,
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Runs inference on the next chunk of a streaming session and returns its outputs.
+///
+/// Each chunk is run like `infera_predict`, and its outputs are returned right away. The
+/// session reuses its input buffer between chunks of the same size. Every chunk must have
+/// the same number of columns; a chunk that fails is not counted, and the session stays
+/// open. The caller is responsible for freeing the result using `infera_free_result`.
+///
+/// # Arguments
+///
+/// * `handle` - The handle returned by `infera_stream_begin`.
+/// * `data` - A pointer to the chunk's input data, organized as a flat array of `f32`.
+/// * `rows` - The number of rows in the chunk.
+/// * `cols` - The number of columns in the chunk.
+///
+/// # Returns
+///
+/// An `InferaInferenceResult` struct containing the chunk's output tensor data and metadata.
+/// If an error occurs, the `status` field of the struct is a negative `InferaStatus` code
+/// (e.g., `-3` if the chunk has a different number of columns than the others).
+///
+/// # Safety
+///
+/// * `data` must not be null.
+/// * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+#[no_mangle]
+pub unsafe extern "C" fn infera_stream_push(
+    handle: i64,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+) -> InferaInferenceResult {
+    let result = (|| -> Result<InferaInferenceResult, error::InferaError> {
+        if data.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        stream::push(handle, data, rows, cols)
+    })();
+
+    match result {
+        Ok(res) => res,
+        Err(e) => {
+            error::set_last_error(&e);
+            InferaInferenceResult::from_error(&e)
+        }
+    }
+}
+
+/// Ends a streaming session and returns its statistics as a JSON string.
+///
+/// The object has the session's `model` and `cols`, the number of `chunks` and
+/// `total_rows` run successfully, the time spent running them (`inference_ns`), and the
+/// time since the session began (`elapsed_ns`).
+///
+/// # Arguments
+///
+/// * `handle` - The handle returned by `infera_stream_begin`.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated, null-terminated C string containing JSON.
+/// The caller is responsible for freeing this string using `infera_free_string`.
+/// If the session is not open, the JSON will be an object with an "error" key.
+///
+/// # Safety
+///
+/// The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub extern "C" fn infera_stream_end(handle: i64) -> *mut c_char {
+    let final_json = stream::end(handle).unwrap_or_else(|e| {
+        error::set_last_error(&e);
+        e.to_json()
+    });
+    let json_str = serde_json::to_string(&final_json).unwrap_or_default();
+    CString::new(json_str).unwrap_or_default().into_raw()
+}
+
+/// Runs inference on a loaded vision model with an encoded image as input.
+///
+/// The image (PNG, JPEG, or WebP) is decoded, converted to RGB, resized to `target_w x target_h`,
+/// and passed to the model as a batch of one `f32` image. Pixel values are scaled to
+/// `[0, 1]` and can then be normalized per channel as `(value - mean) / std`.
+/// Requires the `image` feature. The caller is responsible for freeing the result using
+/// `infera_free_result`.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `image_bytes` - A pointer to the encoded image bytes.
+/// * `len` - The number of bytes in `image_bytes`.
+/// * `target_w` - The width the image is resized to.
+/// * `target_h` - The height the image is resized to.
+/// * `layout` - A null-terminated C string, `"chw"` for a `[1, 3, H, W]` input or `"hwc"`
+///   for a `[1, H, W, 3]` input.
+/// * `normalization_json` - An optional null-terminated C string with a JSON object like
+///   `{"mean": [0.485, 0.456, 0.406], "std": [0.229, 0.224, 0.225]}`. Pass null or an
+///   empty string to skip normalization.
+///
+/// # Returns
+///
+/// An `InferaInferenceResult` struct containing the output tensor data and metadata.
+/// If an error occurs, the `status` field of the struct is a negative `InferaStatus` code
+/// (e.g., `-3` if the resized image does not match the model's input shape).
+///
+/// # Safety
+///
+/// * `model_name`, `image_bytes`, and `layout` must not be null.
+/// * `model_name`, `layout`, and `normalization_json` (if not null) must point to valid,
+///   null-terminated C strings.
+/// * `image_bytes` must point to a contiguous block of memory of size `len`.
+#[no_mangle]
+pub unsafe extern "C" fn infera_predict_image(
+    model_name: *const c_char,
+    image_bytes: *const u8,
+    len: usize,
+    target_w: u32,
+    target_h: u32,
+    layout: *const c_char,
+    normalization_json: *const c_char,
+) -> InferaInferenceResult {
+    let result = (|| -> Result<InferaInferenceResult, error::InferaError> {
+        if model_name.is_null() || image_bytes.is_null() || layout.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        let layout_str = CStr::from_ptr(layout).to_str()?;
+        let normalization_str = if normalization_json.is_null() {
+            ""
+        } else {
+            CStr::from_ptr(normalization_json).to_str()?
+        };
+        let bytes = std::slice::from_raw_parts(image_bytes, len);
+        vision::predict_image_impl(
+            &model::resolve_name(name_str),
+            bytes,
+            None,
+            target_w,
+            target_h,
+            layout_str,
+            normalization_str,
+        )
+    })();
+
+    match result {
+        Ok(res) => res,
+        Err(e) => {
+            error::set_last_error(&e);
+            InferaInferenceResult::from_error(&e)
+        }
+    }
+}
+
+/// Runs inference on a loaded vision model with an image in an explicit format.
+///
+/// Works like `infera_predict_image`, but `format` names the format of `image_bytes`
+/// instead of it being detected. Besides encoded `"png"`, `"jpeg"`, and `"webp"` images,
+/// it accepts already decoded `"rgb"` (3 bytes per pixel) and `"rgba"` (4 bytes per pixel)
+/// pixels in row-major order, so callers that decoded an image elsewhere skip a second
+/// decode. Requires the `image` feature. The caller is responsible for freeing the result
+/// using `infera_free_result`.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `image_bytes` - A pointer to the image bytes.
+/// * `len` - The number of bytes in `image_bytes`.
+/// * `format` - A null-terminated C string, one of `"png"`, `"jpeg"`, `"webp"`, `"rgb"`,
+///   or `"rgba"`.
+/// * `source_w` - The width of raw `"rgb"` or `"rgba"` pixels (ignored otherwise).
+/// * `source_h` - The height of raw `"rgb"` or `"rgba"` pixels (ignored otherwise).
+/// * `target_w` - The width the image is resized to.
+/// * `target_h` - The height the image is resized to.
+/// * `layout` - A null-terminated C string, `"chw"` for a `[1, 3, H, W]` input or `"hwc"`
+///   for a `[1, H, W, 3]` input.
+/// * `normalization_json` - An optional null-terminated C string with normalization
+///   parameters, as for `infera_predict_image`.
+///
+/// # Returns
+///
+/// An `InferaInferenceResult` struct containing the output tensor data and metadata.
+/// If an error occurs, the `status` field of the struct is a negative `InferaStatus` code
+/// (e.g., `-1` if raw pixels do not match `source_w x source_h`).
+///
+/// # Safety
+///
+/// * `model_name`, `image_bytes`, `format`, and `layout` must not be null.
+/// * `model_name`, `format`, `layout`, and `normalization_json` (if not null) must point
+///   to valid, null-terminated C strings.
+/// * `image_bytes` must point to a contiguous block of memory of size `len`.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn infera_predict_image_with_format(
+    model_name: *const c_char,
+    image_bytes: *const u8,
+    len: usize,
+    format: *const c_char,
+    source_w: u32,
+    source_h: u32,
+    target_w: u32,
+    target_h: u32,
+    layout: *const c_char,
+    normalization_json: *const c_char,
+) -> InferaInferenceResult {
+    let result = (|| -> Result<InferaInferenceResult, error::InferaError> {
+        if model_name.is_null() || image_bytes.is_null() || format.is_null() || layout.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        let format =
+            vision::ImageFormat::parse(CStr::from_ptr(format).to_str()?, source_w, source_h)?;
+        let layout_str = CStr::from_ptr(layout).to_str()?;
+        let normalization_str = if normalization_json.is_null() {
+            ""
+        } else {
+            CStr::from_ptr(normalization_json).to_str()?
+        };
+        let bytes = std::slice::from_raw_parts(image_bytes, len);
+        vision::predict_image_impl(
+            &model::resolve_name(name_str),
+            bytes,
+            Some(format),
+            target_w,
+            target_h,
+            layout_str,
+            normalization_str,
+        )
+    })();
+
+    match result {
+        Ok(res) => res,
+        Err(e) => {
+            error::set_last_error(&e);
+            InferaInferenceResult::from_error(&e)
+        }
+    }
+}
+
+/// Runs inference and returns all model outputs, labelled by name, as a JSON string.
+///
+/// The JSON is an array with one object per output, each holding the output's `name`,
+/// `shape`, and flattened `data`. Internal nodes exposed with the `extra_outputs` load
+/// option (see `infera_load_model_with_options`) are included after the model's own outputs.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `data` - A pointer to the input tensor data, organized as a flat array of `f32`.
+/// * `rows` - The number of rows in the input tensor.
+/// * `cols` - The number of columns in the input tensor.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated, null-terminated C string containing JSON.
+/// The caller is responsible for freeing this string using `infera_free_string`.
+/// On error, the JSON will contain an "error" key.
+///
+/// # Safety
+///
+/// * `model_name` and `data` must not be null.
+/// * `model_name` must point to a valid, null-terminated C string.
+/// * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+/// * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub unsafe extern "C" fn infera_predict_all(
+    model_name: *const c_char,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+) -> *mut c_char {
+    let result = (|| -> Result<String, error::InferaError> {
+        if model_name.is_null() || data.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        engine::run_inference_all_impl(&model::resolve_name(name_str), data, rows, cols)
+    })();
+
+    match result {
+        Ok(json) => CString::new(json).unwrap_or_default().into_raw(),
+        Err(e) => {
+            error::set_last_error(&e);
+            let error_json = e.to_json().to_string();
+            CString::new(error_json).unwrap_or_default().into_raw()
+        }
+    }
+}
+
+/// Runs inference and returns a single model output, selected by name or index.
+///
+/// Models with several outputs (for example detection models with boxes and class
+/// logits) otherwise return their first output from `infera_predict`. The whole model
+/// still runs, but only the selected output is converted and copied out. The model's
+/// postprocessor, if any, is only applied when the first output is selected.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `data` - A pointer to the input tensor data, organized as a flat array of `f32`.
+/// * `rows` - The number of rows in the input tensor.
+/// * `cols` - The number of columns in the input tensor.
+/// * `output_selector` - A pointer to a null-terminated C string with an output name (see
+///   `infera_get_output_names`) or, if no output has that name, a zero-based output index
+///   such as `"1"`.
+///
+/// # Returns
+///
+/// An `InferaInferenceResult` struct containing the selected output and its shape. If
+/// an error occurs, the `status` field of the struct is a negative `InferaStatus` code,
+/// and an unknown selector gives an error listing the model's outputs.
+///
+/// # Safety
+///
+/// * `model_name`, `data`, and `output_selector` must not be null.
+/// * `model_name` and `output_selector` must point to valid, null-terminated C strings.
+/// * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+#[no_mangle]
+pub unsafe extern "C" fn infera_predict_output(
+    model_name: *const c_char,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+    output_selector: *const c_char,
+) -> InferaInferenceResult {
+    let result = (|| -> Result<InferaInferenceResult, error::InferaError> {
+        if model_name.is_null() || data.is_null() || output_selector.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        let selector = CStr::from_ptr(output_selector).to_str()?;
+        engine::run_inference_output_impl(
+            &model::resolve_name(name_str),
+            data,
+            rows,
+            cols,
+            selector,
+        )
+    })();
+
+    match result {
+        Ok(res) => res,
+        Err(e) => {
+            error::set_last_error(&e);
+            InferaInferenceResult::from_error(&e)
+        }
+    }
+}
+
+/// Runs inference and returns the model output at a zero-based index.
+///
+/// This is `infera_predict_output` with the output given by its position in
+/// `infera_get_output_names` instead of a selector string, so an index never matches an
+/// output whose name is a number. The whole model still runs once, but only the selected
+/// output is converted and copied out.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `data` - A pointer to the input tensor data, organized as a flat array of `f32`.
+/// * `rows` - The number of rows in the input tensor.
+/// * `cols` - The number of columns in the input tensor.
+/// * `output_index` - The zero-based index of the output to return.
+///
+/// # Returns
+///
+/// An `InferaInferenceResult` struct containing the selected output and its shape. If
+/// an error occurs, the `status` field of the struct is a negative `InferaStatus` code,
+/// and an index out of range gives an error listing the model's outputs.
+///
+/// # Safety
+///
+/// * `model_name` and `data` must not be null.
+/// * `model_name` must point to a valid, null-terminated C string.
+/// * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+#[no_mangle]
+pub unsafe extern "C" fn infera_predict_output_index(
+    model_name: *const c_char,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+    output_index: usize,
+) -> InferaInferenceResult {
+    let result = (|| -> Result<InferaInferenceResult, error::InferaError> {
+        if model_name.is_null() || data.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        engine::run_inference_output_index_impl(
+            &model::resolve_name(name_str),
+            data,
+            rows,
+            cols,
+            output_index,
+        )
+    })();
+
+    match result {
+        Ok(res) => res,
+        Err(e) => {
+            error::set_last_error(&e);
+            InferaInferenceResult::from_error(&e)
+        }
+    }
+}
+
+/// Profiles the time a loaded model spends in each of its nodes and returns a JSON report.
+///
+/// The model is run `iterations` times on `rows` rows of seeded random input, with each
+/// node timed as it is evaluated. Profiling uses its own run state, so it does not slow
+/// down predictions. The report holds `wall_time_ns` (the total time of the runs),
+/// `profiled_time_ns` (the part of it spent evaluating nodes), `nodes` (the slowest
+/// nodes, each with its `name`, `op` type, `output_shapes`, `total_ns`, `mean_ns`, and
+/// `percent` of the wall time), and `ops` (the total time per op type).
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `rows` - The number of input rows per run. It must fit the model's batch dimension.
+/// * `iterations` - The number of runs to time. Must be greater than zero.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated, null-terminated C string containing JSON.
+/// The caller is responsible for freeing this string using `infera_free_string`.
+/// On error, the JSON will contain an "error" key.
+///
+/// # Safety
+///
+/// * The `model_name` pointer must not be null and must point to a valid C string.
+/// * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub unsafe extern "C" fn infera_profile_model(
+    model_name: *const c_char,
+    rows: usize,
+    iterations: usize,
+) -> *mut c_char {
+    let result = (|| -> Result<String, error::InferaError> {
+        if model_name.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        profile::profile_model_impl(&model::resolve_name(name_str), rows, iterations)
+    })();
+
+    match result {
+        Ok(json) => CString::new(json).unwrap_or_default().into_raw(),
+        Err(e) => {
+            error::set_last_error(&e);
+            let error_json = e.to_json().to_string();
+            CString::new(error_json).unwrap_or_default().into_raw()
+        }
+    }
+}
+
+/// Runs inference with inputs and outputs addressed by their names in the ONNX graph.
+///
+/// `inputs_json` is a JSON object keyed by input name, as the model's export tool named
+/// it (see `input_names` in `infera_get_model_info`), with a `shape` array and a flat
+/// `data` array for each input, for example
+/// `{"float_input": {"shape": [1, 3], "data": [1, 2, 3]}}`. The result is a JSON object
+/// keyed by output name with the `shape` and `data` of every model output.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `inputs_json` - A pointer to a null-terminated C string containing the JSON inputs.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated, null-terminated C string containing JSON.
+/// The caller is responsible for freeing this string using `infera_free_string`.
+/// On error (e.g., an unknown or missing input name), the JSON will be an object with an
+/// "error" key instead.
+///
+/// # Safety
+///
+/// * `model_name` and `inputs_json` must be valid, null-terminated C strings.
+/// * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub unsafe extern "C" fn infera_predict_named(
+    model_name: *const c_char,
+    inputs_json: *const c_char,
+) -> *mut c_char {
+    let result = (|| -> Result<String, error::InferaError> {
+        if model_name.is_null() || inputs_json.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        let inputs_str = CStr::from_ptr(inputs_json).to_str()?;
+        engine::run_named_impl(&model::resolve_name(name_str), inputs_str)
+    })();
+
+    match result {
+        Ok(json) => CString::new(json).unwrap_or_default().into_raw(),
+        Err(e) => {
+            error::set_last_error(&e);
+            let error_json = e.to_json().to_string();
+            CString::new(error_json).unwrap_or_default().into_raw()
+        }
+    }
+}
+
+/// Runs inference on feature maps, JSON objects keyed by feature name, so callers do not
+/// have to put their values in the model's column order themselves.
+///
+/// `features_json` is a single object for one row, for example
+/// `{"age": 41, "income": 52000}`, or an array of such objects for a batch. The model's
+/// feature names, which give the column order, come from the `feature_names` load option,
+/// a `<model>.features.json` file next to the model holding a JSON array of names, or the
+/// `feature_names` ONNX metadata, in that order of precedence, and are listed in
+/// `infera_get_model_info`. A missing (or null) feature takes the `feature_default` load
+/// option, or fails if it is not set. A feature that is not in the list fails unless the
+/// model was loaded with `"ignore_unknown": true`. The caller is responsible for freeing
+/// the result using `infera_free_result`.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `features_json` - A pointer to a null-terminated C string containing the JSON
+///   feature map or array of feature maps.
+///
+/// # Returns
+///
+/// An `InferaInferenceResult` struct with one output row per feature map. On failure,
+/// `status` is a negative `InferaStatus` code and `data` is null. Call
+/// `infera_last_error()` for details.
+///
+/// # Safety
+///
+/// * `model_name` and `features_json` must be valid, null-terminated C strings.
+#[no_mangle]
+pub unsafe extern "C" fn infera_predict_features(
+    model_name: *const c_char,
+    features_json: *const c_char,
+) -> InferaInferenceResult {
+    let result = (|| -> Result<InferaInferenceResult, error::InferaError> {
+        if model_name.is_null() || features_json.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        let features_str = CStr::from_ptr(features_json).to_str()?;
+        canary::predict(&model::resolve_name(name_str), |name| {
+            engine::run_features_impl(name, features_str)
+        })
+    })();
+
+    match result {
+        Ok(res) => res,
+        Err(e) => {
+            error::set_last_error(&e);
+            InferaInferenceResult::from_error(&e)
+        }
+    }
+}
+
+/// Runs inference on many differently shaped inputs in one call.
+///
+/// Each input is a JSON object with a `shape` array and a flat `data` array, for example
+/// `[{"shape": [1, 3], "data": [1, 2, 3]}, {"shape": [2, 3], "data": [1, 2, 3, 4, 5, 6]}]`.
+/// The inputs are run in order, and the result is a JSON array with one entry per input:
+/// `{"shape": [...], "data": [...]}` for the model's first output, or `{"error": "..."}`
+/// if that input was malformed or failed. This amortizes the call overhead for many
+/// small inputs.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `inputs_json` - A pointer to a null-terminated C string containing a JSON array of
+///   `{"shape", "data"}` objects.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated, null-terminated C string containing JSON.
+/// The caller is responsible for freeing this string using `infera_free_string`.
+/// If the model is not loaded or `inputs_json` is not a JSON array, the JSON is an
+/// object with an "error" key instead.
+///
+/// # Safety
+///
+/// * `model_name` and `inputs_json` must be valid, null-terminated C strings.
+/// * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub unsafe extern "C" fn infera_predict_many_json(
+    model_name: *const c_char,
+    inputs_json: *const c_char,
+) -> *mut c_char {
+    let result = (|| -> Result<String, error::InferaError> {
+        if model_name.is_null() || inputs_json.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        let inputs_str = CStr::from_ptr(inputs_json).to_str()?;
+        engine::run_many_json_impl(&model::resolve_name(name_str), inputs_str)
+    })();
+
+    match result {
+        Ok(json) => CString::new(json).unwrap_or_default().into_raw(),
+        Err(e) => {
+            error::set_last_error(&e);
+            let error_json = e.to_json().to_string();
+            CString::new(error_json).unwrap_or_default().into_raw()
+        }
+    }
+}
+
+/// Runs several loaded models on the same input and averages their outputs.
+///
+/// Every model in the ensemble is run on the same `rows x cols` input, and the result is
+/// the element-wise weighted average of their outputs. All models must produce outputs of
+/// the same shape.
+///
+/// # Arguments
+///
+/// * `names_json` - A pointer to a null-terminated C string containing a JSON array of
+///   model names (e.g., `["model_a", "model_b"]`).
+/// * `data` - A pointer to the input tensor data, organized as a flat array of `f32`.
+/// * `rows` - The number of rows in the input tensor.
+/// * `cols` - The number of columns in the input tensor.
+/// * `weights_json` - A pointer to a null-terminated C string containing a JSON array with
+///   one non-negative weight per model, or null to weight all models equally. Weights
+///   are normalized to sum to 1.
+///
+/// # Returns
+///
+/// An `InferaInferenceResult` struct containing the averaged output tensor.
+/// If an error occurs (e.g., a model is not loaded or the output shapes differ), the
+/// `status` field of the struct is a negative `InferaStatus` code.
+///
+/// # Safety
+///
+/// * `names_json` and `data` must not be null.
+/// * All non-null string pointers must point to valid, null-terminated C strings.
+/// * `data` must point to a contiguous block of memory of size `rows * cols * size_of<f32>()`.
+#[no_mangle]
+pub unsafe extern "C" fn infera_predict_ensemble(
+    names_json: *const c_char,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+    weights_json: *const c_char,
+) -> InferaInferenceResult {
+    let result = (|| -> Result<InferaInferenceResult, error::InferaError> {
+        if names_json.is_null() || data.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let names: Vec<String> = ensemble::parse_names(CStr::from_ptr(names_json).to_str()?)?
+            .iter()
+            .map(|name| model::resolve_name(name))
+            .collect();
+        let weights_str = if weights_json.is_null() {
+            ""
+        } else {
+            CStr::from_ptr(weights_json).to_str()?
+        };
+        let weights = ensemble::parse_weights(weights_str, names.len())?;
+        ensemble::predict_ensemble_impl(&names, data, rows, cols, &weights)
+    })();
+
+    match result {
+        Ok(res) => res,
+        Err(e) => {
+            error::set_last_error(&e);
+            InferaInferenceResult::from_error(&e)
+        }
+    }
+}
+
+/// Compares two loaded models on the same inputs and returns a JSON report.
+///
+/// Both models are run on either a user-provided input matrix or seeded random inputs
+/// matching their shared input shape. The report contains the maximum and mean absolute
+/// and relative differences, the fraction of rows whose argmax differs, and a `pass`
+/// verdict against the given tolerances.
+///
+/// The options are given as a JSON object. Supported keys:
+///
+/// * `"samples"` (integer): Number of random input rows to generate. Defaults to `100`.
+/// * `"seed"` (integer): Seed for the random inputs. Defaults to `42`.
+/// * `"inputs"` (array of number arrays): Input rows to use instead of random inputs.
+/// * `"atol"` (number): Absolute tolerance. Defaults to `1e-5`.
+/// * `"rtol"` (number): Relative tolerance. Defaults to `1e-4`.
+///
+/// The comparison passes if every output element satisfies `|a - b| <= atol + rtol * |b|`.
+///
+/// # Arguments
+///
+/// * `name_a` - A pointer to a null-terminated C string for the reference model's name.
+/// * `name_b` - A pointer to a null-terminated C string for the compared model's name.
+/// * `options_json` - A pointer to a null-terminated C string containing the JSON options,
+///   or null to use the defaults.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated, null-terminated C string containing JSON.
+/// The caller is responsible for freeing this string using `infera_free_string`.
+/// On error (e.g., incompatible input shapes), the JSON will contain an "error" key.
+///
+/// # Safety
+///
+/// * The `name_a` and `name_b` pointers must not be null.
+/// * All non-null pointers must point to valid, null-terminated C strings.
+/// * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub unsafe extern "C" fn infera_compare_models(
+    name_a: *const c_char,
+    name_b: *const c_char,
+    options_json: *const c_char,
+) -> *mut c_char {
+    let result = (|| -> Result<String, error::InferaError> {
+        if name_a.is_null() || name_b.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_a_str = CStr::from_ptr(name_a).to_str()?;
+        let name_b_str = CStr::from_ptr(name_b).to_str()?;
+        let options_str = if options_json.is_null() {
+            ""
+        } else {
+            CStr::from_ptr(options_json).to_str()?
+        };
+        compare::compare_models_impl(
+            &model::resolve_name(name_a_str),
+            &model::resolve_name(name_b_str),
+            options_str,
+        )
+    })();
+
+    match result {
+        Ok(json) => CString::new(json).unwrap_or_default().into_raw(),
+        Err(e) => {
+            error::set_last_error(&e);
+            let error_json = e.to_json().to_string();
+            CString::new(error_json).unwrap_or_default().into_raw()
+        }
+    }
+}
+
+/// Retrieves metadata about a specific loaded model as a JSON string.
+///
+/// The returned JSON string includes the model's name, and its input and output shapes.
+/// To trace numerical differences between environments, it also includes the versions of
+/// tract that compiled the model (`"tract_version"` and `"tract_core_version"`), whether
+/// the plan was optimized (`"optimized"`), and how long loading took
+/// (`"load_duration_ns"`).
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated, null-terminated C string containing JSON.
+/// The caller is responsible for freeing this string using `infera_free_string`.
+/// On error (e.g., model not found), the JSON will contain an "error" key.
+///
+/// # Safety
+///
+/// * The `model_name` pointer must not be null and must point to a valid C string.
+/// * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub unsafe extern "C" fn infera_get_model_info(model_name: *const c_char) -> *mut c_char {
+    let result = (|| -> Result<String, error::InferaError> {
+        if model_name.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        engine::get_model_metadata_impl(&model::resolve_name(name_str))
+    })();
+
+    match result {
+        Ok(json) => CString::new(json).unwrap_or_default().into_raw(),
+        Err(e) => {
+            error::set_last_error(&e);
+            let error_json = e.to_json().to_string();
+            CString::new(error_json).unwrap_or_default().into_raw()
+        }
+    }
+}
+
+/// Returns the names of a loaded model's outputs as a JSON array of strings.
+///
+/// The names are in output order, matching the entries of `infera_predict_all`. Original
+/// outputs are named by their ONNX tensor names and are followed by any extra outputs
+/// requested with the `"extra_outputs"` load option.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated, null-terminated C string containing JSON.
+/// The caller is responsible for freeing this string using `infera_free_string`.
+/// On error (e.g., model not found), the JSON will contain an "error" key.
+///
+/// # Safety
+///
+/// * The `model_name` pointer must not be null and must point to a valid C string.
+/// * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub unsafe extern "C" fn infera_get_output_names(model_name: *const c_char) -> *mut c_char {
+    let result = (|| -> Result<Vec<String>, error::InferaError> {
+        if model_name.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        engine::get_output_names_impl(&model::resolve_name(name_str))
+    })();
+
+    let final_json = result.map(|names| json!(names)).unwrap_or_else(|e| {
+        error::set_last_error(&e);
+        e.to_json()
+    });
+    let json_str = serde_json::to_string(&final_json).unwrap_or_default();
+    CString::new(json_str).unwrap_or_default().into_raw()
+}
+
+/// Copies the shape of a loaded model's input or output into a caller buffer.
+///
+/// `out_len` is always set to the number of dimensions, so a caller can pass `cap = 0`
+/// (and a null `out`) to find the size of buffer needed.
+unsafe fn copy_model_shape(
+    model_name: *const c_char,
+    output: bool,
+    out: *mut i64,
+    cap: usize,
+    out_len: *mut usize,
+) -> i32 {
+    let result = (|| -> Result<(), error::InferaError> {
+        if model_name.is_null() || out_len.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        let shape = engine::get_model_shape_impl(&model::resolve_name(name_str), output)?;
+        *out_len = shape.len();
+        if shape.len() > cap {
+            return Err(error::InferaError::BufferTooSmall {
+                needed: shape.len(),
+                capacity: cap,
+            });
+        }
+        if !shape.is_empty() {
+            if out.is_null() {
+                return Err(error::InferaError::NullPointer);
+            }
+            std::ptr::copy_nonoverlapping(shape.as_ptr(), out, shape.len());
+        }
+        Ok(())
+    })();
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            error::set_last_error(&e);
+            e.status() as i32
+        }
+    }
+}
+
+/// Copies the shape of a loaded model's first input into a caller-provided buffer.
+///
+/// This reads the same shape as the `"input_shape"` field of `infera_get_model_info`
+/// without building or parsing JSON. Dynamic dimensions are reported as -1.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `out` - A pointer to a buffer of at least `cap` `i64` values for the dimensions.
+/// * `cap` - The number of `i64` values `out` can hold.
+/// * `out_len` - A pointer that receives the number of dimensions, even when `cap` is too
+///   small, so the call can be repeated with a large enough buffer.
+///
+/// # Returns
+///
+/// * `0` on success.
+/// * A negative `InferaStatus` code on failure (e.g., `-2` if the model is not loaded, or
+///   `-1` if the buffer is too small or the shape is not known). Call
+///   `infera_last_error()` to get a descriptive error message.
+///
+/// # Safety
+///
+/// * `model_name` and `out_len` must not be null, and `out` must not be null unless `cap`
+///   is 0 or the shape has no dimensions.
+/// * `model_name` must point to a valid, null-terminated C string.
+/// * `out` must point to writable memory for `cap` `i64` values.
+#[no_mangle]
+pub unsafe extern "C" fn infera_get_input_shape(
+    model_name: *const c_char,
+    out: *mut i64,
+    cap: usize,
+    out_len: *mut usize,
+) -> i32 {
+    copy_model_shape(model_name, false, out, cap, out_len)
+}
+
+/// Copies the shape of a loaded model's first output into a caller-provided buffer.
+///
+/// This is the output counterpart of `infera_get_input_shape` and takes the same
+/// arguments. Dynamic dimensions are reported as -1.
+///
+/// # Returns
+///
+/// * `0` on success.
+/// * A negative `InferaStatus` code on failure. Call `infera_last_error()` to get a
+///   descriptive error message.
+///
+/// # Safety
+///
+/// The same requirements as for `infera_get_input_shape` apply.
+#[no_mangle]
+pub unsafe extern "C" fn infera_get_output_shape(
+    model_name: *const c_char,
+    out: *mut i64,
+    cap: usize,
+    out_len: *mut usize,
+) -> i32 {
+    copy_model_shape(model_name, true, out, cap, out_len)
+}
+
+/// Returns the number of output elements a batch of `batch_rows` input rows produces, for
+/// sizing output buffers before predicting.
+///
+/// For models with a dynamic batch dimension, `batch_rows` is substituted into the
+/// output's dynamic dimension (see `"input_batch_dim"` and `"output_batch_dim"` in
+/// `infera_get_model_info`). For fully static models, the size of the fixed output is
+/// returned if `batch_rows` is the fixed batch size, or for any batch if the model was
+/// loaded with the `pad_batch` option.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `batch_rows` - The number of input rows of the planned batch.
+///
+/// # Returns
+///
+/// * The number of output elements on success.
+/// * `-1` on failure, including for models whose output batch does not scale linearly
+///   with the input batch (such as a model with a dynamic input batch and a fixed output,
+///   or with several dynamic dimensions). Call `infera_last_error()` to get a descriptive
+///   error message.
+///
+/// # Safety
+///
+/// * The `model_name` pointer must not be null and must point to a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn infera_get_output_size(
+    model_name: *const c_char,
+    batch_rows: usize,
+) -> i64 {
+    let result = (|| -> Result<usize, error::InferaError> {
+        if model_name.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        engine::get_output_size_impl(&model::resolve_name(name_str), batch_rows)
+    })();
+
+    match result {
+        Ok(size) => i64::try_from(size).unwrap_or(i64::MAX),
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Checks whether an input of the given shape would be accepted by a loaded model,
+/// before building the input buffer.
+///
+/// The shape must have the rank of the model's first input (see `infera_get_input_shape`)
+/// and match each of its fixed dimensions. Dynamic dimensions accept any size. If the
+/// model's input shape is not known, any shape is accepted.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+/// * `shape` - A pointer to the `ndim` dimensions of the planned input.
+/// * `ndim` - The number of dimensions in `shape`.
+///
+/// # Returns
+///
+/// * `0` if the shape is compatible with the model's input.
+/// * `-1` if it is not, or on failure (e.g., the model is not loaded). Call
+///   `infera_last_error()` to get a descriptive error message, which names the expected
+///   and given shapes.
+///
+/// # Safety
+///
+/// * `model_name` must not be null and must point to a valid, null-terminated C string.
+/// * `shape` must point to `ndim` `i64` values, and may only be null if `ndim` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn infera_check_input_shape(
+    model_name: *const c_char,
+    shape: *const i64,
+    ndim: usize,
+) -> i32 {
+    let result = (|| -> Result<(), error::InferaError> {
+        if model_name.is_null() || (shape.is_null() && ndim > 0) {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        let dims = if ndim == 0 {
+            &[][..]
+        } else {
+            std::slice::from_raw_parts(shape, ndim)
+        };
+        engine::check_input_shape_impl(&model::resolve_name(name_str), dims)
+    })();
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Returns a hash that identifies the contents of a loaded model, for keying artifacts
+/// derived from it.
+///
+/// The hash is the hex-encoded SHA-256 of the model file, computed when the model was
+/// loaded, so loading the same file again gives the same hash and any change to the file
+/// gives a different one. For remote models it is the hash of the downloaded file, the
+/// same one `infera_load_model_verified` checks.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated, null-terminated C string with 64 lowercase hexadecimal
+/// characters. The caller is responsible for freeing this string using `infera_free_string`.
+/// On error (e.g., model not found), the string is a JSON object with an "error" key.
+///
+/// # Safety
+///
+/// * The `model_name` pointer must not be null and must point to a valid C string.
+/// * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub unsafe extern "C" fn infera_get_model_signature(model_name: *const c_char) -> *mut c_char {
+    let result = (|| -> Result<String, error::InferaError> {
+        if model_name.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        engine::get_model_signature_impl(&model::resolve_name(name_str))
+    })();
+
+    match result {
+        Ok(hash) => CString::new(hash).unwrap_or_default().into_raw(),
+        Err(e) => {
+            error::set_last_error(&e);
+            let error_json = e.to_json().to_string();
+            CString::new(error_json).unwrap_or_default().into_raw()
+        }
+    }
+}
+
+/// Returns how long the most recent inference on a loaded model took.
+///
+/// The time is the wall-clock duration of running the model itself, measured separately
+/// for each model so concurrent callers of different models do not overwrite each other.
+///
+/// # Arguments
+///
+/// * `model_name` - A pointer to a null-terminated C string for the model's name.
+///
+/// # Returns
+///
+/// * The duration of the model's most recent run in nanoseconds.
+/// * `0` if the model has not run since it was loaded, or on failure (e.g., the model is
+///   not loaded). Call `infera_last_error()` to tell a failure apart.
+///
+/// # Safety
+///
+/// * The `model_name` pointer must not be null.
+/// * The memory pointed to by `model_name` must be a valid, null-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn infera_get_last_prediction_time_ns(model_name: *const c_char) -> u64 {
+    let result = (|| -> Result<u64, error::InferaError> {
+        if model_name.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(model_name).to_str()?;
+        engine::last_inference_ns_impl(&model::resolve_name(name_str))
+    })();
+
+    match result {
+        Ok(ns) => ns,
+        Err(e) => {
+            error::set_last_error(&e);
+            0
+        }
+    }
+}
+
+/// Checks whether a model is loaded under a name.
+///
+/// This only looks the name up in the model registry, so it is cheaper than listing the
+/// loaded models or attempting a prediction. Canary aliases are not models, so they are
+/// not reported as existing.
+///
+/// # Arguments
+///
+/// * `name` - A pointer to a null-terminated C string for the model's name.
+///
+/// # Returns
+///
+/// * `1` if a model is loaded under `name`.
+/// * `0` if not.
+/// * `-1` on failure (e.g., `name` is not valid UTF-8). Call `infera_last_error()` to get
+///   a descriptive error message.
+///
+/// # Safety
+///
+/// * The `name` pointer must not be null.
+/// * The memory pointed to by `name` must be a valid, null-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn infera_model_exists(name: *const c_char) -> i32 {
+    let result = (|| -> Result<bool, error::InferaError> {
+        if name.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let name_str = CStr::from_ptr(name).to_str()?;
+        Ok(model::is_loaded(&model::resolve_name(name_str)))
+    })();
+
+    match result {
+        Ok(exists) => i32::from(exists),
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Returns a JSON array of the names of all currently loaded models.
+///
+/// Only models in the current thread's default namespace are listed (see
+/// `infera_set_default_namespace`), or models loaded without a namespace if no default
+/// is set. Use `infera_get_loaded_models_ns` to list another namespace.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated, null-terminated C string containing a JSON array of strings.
+/// The caller is responsible for freeing this string using `infera_free_string`.
+///
+/// # Safety
+///
+/// The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub extern "C" fn infera_get_loaded_models() -> *mut c_char {
+    let list = model::names_in_namespace(model::default_namespace().as_deref());
+    let joined = serde_json::to_string(&list).unwrap_or_else(|_| "[]".to_string());
+    match CString::new(joined) {
+        Ok(cstr) => cstr.into_raw(),
+        Err(_) => {
+            // Fallback to empty JSON array if string contains null bytes
+            match CString::new("[]") {
+                Ok(cstr) => cstr.into_raw(),
+                Err(_) => std::ptr::null_mut(), // This should never happen with "[]"
+            }
+        }
+    }
+}
+
+/// Returns a JSON array describing the models loaded in the current thread's default
+/// namespace, or loaded without a namespace if no default is set.
+///
+/// Unlike `infera_get_loaded_models`, which only lists names, each entry is an object
+/// with the model's unqualified `name`, its registry key (`canonical_name`), its
+/// `namespace` (null for models loaded without one), the ID of the autoload group it
+/// belongs to (`group_id`, null if it was not autoloaded), the canary `aliases` currently
+/// routing to it, and the number of `previous_versions` kept for rollback. Entries are
+/// sorted by name and read from one consistent snapshot of the registries.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated, null-terminated C string containing a JSON array of objects.
+/// The caller is responsible for freeing this string using `infera_free_string`.
+///
+/// # Safety
+///
+/// The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub extern "C" fn infera_list_models_detailed() -> *mut c_char {
+    let list = model::list_models_detailed(model::default_namespace().as_deref());
+    let json_str = serde_json::to_string(&list).unwrap_or_else(|_| "[]".to_string());
+    CString::new(json_str).unwrap_or_default().into_raw()
+}
+
+/// Returns a JSON array of the names of the models loaded in a namespace.
+///
+/// The names are returned without the namespace prefix, sorted by name.
+///
+/// # Arguments
+///
+/// * `ns` - A pointer to a null-terminated C string naming the namespace.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated, null-terminated C string containing a JSON array of strings.
+/// The caller is responsible for freeing this string using `infera_free_string`.
+/// On error (e.g., an invalid namespace), the JSON will contain an "error" key.
+///
+/// # Safety
+///
+/// * The `ns` pointer must not be null and must point to a valid C string.
+/// * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub unsafe extern "C" fn infera_get_loaded_models_ns(ns: *const c_char) -> *mut c_char {
+    let result = (|| -> Result<Vec<String>, error::InferaError> {
+        if ns.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let ns_str = CStr::from_ptr(ns).to_str()?;
+        model::validate_namespace(ns_str)?;
+        Ok(model::names_in_namespace(Some(ns_str)))
+    })();
+
+    let final_json = match result {
+        Ok(names) => json!(names),
+        Err(e) => {
+            error::set_last_error(&e);
+            e.to_json()
+        }
+    };
+    let json_str = serde_json::to_string(&final_json).unwrap_or_default();
+    CString::new(json_str).unwrap_or_default().into_raw()
+}
+
+/// Unloads every model in a namespace.
+///
+/// The previous versions and concurrency limits of the models, and the canary aliases
+/// in the namespace, are removed as well.
+///
+/// # Arguments
+///
+/// * `ns` - A pointer to a null-terminated C string naming the namespace.
+///
+/// # Returns
+///
+/// * The number of models unloaded (`0` if the namespace was empty) on success.
+/// * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
+///
+/// # Safety
+///
+/// * The `ns` pointer must not be null.
+/// * The memory pointed to by `ns` must be a valid, null-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn infera_unload_namespace(ns: *const c_char) -> i32 {
+    let result = (|| -> Result<usize, error::InferaError> {
+        if ns.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let ns_str = CStr::from_ptr(ns).to_str()?;
+        model::remove_namespace(ns_str)
+    })();
+
+    match result {
+        Ok(count) => i32::try_from(count).unwrap_or(i32::MAX),
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Sets the namespace that unqualified model names resolve to on the calling thread.
+///
+/// After this call, functions such as `infera_load_model`, `infera_predict`,
+/// `infera_get_model_info`, `infera_unload_model`, and `infera_get_loaded_models` act on
+/// `ns/name` when given a bare `name`. Names that already contain a namespace
+/// (`other/name`) are not affected. The setting only applies to the calling thread.
+///
+/// A default namespace scopes names but does not restrict access: a qualified name still
+/// reaches a model in any namespace. Hosts serving several tenants should reject names
+/// containing `/` before passing them on.
+///
+/// # Arguments
+///
+/// * `ns` - A pointer to a null-terminated C string naming the namespace, or null
+///   (or an empty string) to go back to the root namespace.
+///
+/// # Returns
+///
+/// * `0` on success.
+/// * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
+///
+/// # Safety
+///
+/// * If not null, `ns` must point to a valid, null-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn infera_set_default_namespace(ns: *const c_char) -> i32 {
+    let result = (|| -> Result<(), error::InferaError> {
+        let ns_str = if ns.is_null() {
+            None
+        } else {
+            Some(CStr::from_ptr(ns).to_str()?).filter(|ns| !ns.is_empty())
+        };
+        model::set_default_namespace(ns_str)
+    })();
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Returns a JSON string with version and build information about the Infera library.
+///
+/// The JSON object includes the library version, the enabled ONNX backend (e.g., "tract"),
+/// and the directory used for caching remote models. To help reproduce differences in
+/// inference results between builds, it also includes the tract version the library was
+/// built with (`"tract_version"`, null without the "tract" feature), the target triple it
+/// was built for (`"target"`), and its enabled Cargo features (`"features"`). The versions
+/// of each backend library are listed under `"backend_versions"` by crate name (e.g.,
+/// `"tract-onnx"` and `"tract-core"`), which is empty without the "tract" feature.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated, null-terminated C string containing the version info.
+/// The caller is responsible for freeing this string using `infera_free_string`.
+///
+/// # Safety
+///
+/// The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub extern "C" fn infera_get_version() -> *mut c_char {
+    let cache_dir_str = config::CONFIG.cache_dir.to_string_lossy().to_string();
+    let info = json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "onnx_backend": if cfg!(feature = "tract") { "tract" } else { "disabled" },
+        "model_cache_dir": cache_dir_str,
+        "tract_version": tract_version(),
+        "target": env!("INFERA_BUILD_TARGET"),
+        "features": enabled_features(),
+        "backend_versions": backend_versions(),
+    });
+    let json_str = serde_json::to_string(&info).unwrap_or_default();
+    CString::new(json_str).unwrap_or_default().into_raw()
+}
+
+/// Returns the tract version the library was built with.
+#[cfg(feature = "tract")]
+fn tract_version() -> Option<&'static str> {
+    Some(engine::TRACT_VERSIONS.0)
+}
+
+/// Returns `None`, since the library was built without the "tract" feature.
+#[cfg(not(feature = "tract"))]
+fn tract_version() -> Option<&'static str> {
+    None
+}
+
+/// Returns the versions of the inference backend libraries the library was built with, by
+/// crate name.
+#[cfg(feature = "tract")]
+fn backend_versions() -> serde_json::Map<String, serde_json::Value> {
+    let (onnx, core) = engine::TRACT_VERSIONS;
+    let mut versions = serde_json::Map::new();
+    versions.insert("tract-onnx".to_string(), json!(onnx));
+    versions.insert("tract-core".to_string(), json!(core));
+    versions
+}
+
+/// Returns an empty map, since the library was built without an inference backend.
+#[cfg(not(feature = "tract"))]
+fn backend_versions() -> serde_json::Map<String, serde_json::Value> {
+    serde_json::Map::new()
+}
+
+/// Returns the Cargo features the library was built with.
+fn enabled_features() -> Vec<&'static str> {
+    [
+        ("tract", cfg!(feature = "tract")),
+        ("image", cfg!(feature = "image")),
+        ("duckdb_extension", cfg!(feature = "duckdb_extension")),
+    ]
+    .into_iter()
+    .filter_map(|(name, enabled)| enabled.then_some(name))
+    .collect()
+}
+
+/// Returns the configuration Infera resolved from its environment variables as a JSON string.
+///
+/// The JSON object has one key per configuration field (e.g., `"cache_dir"`,
+/// `"cache_size_limit"`, `"http_timeout_secs"`, `"http_retry_attempts"`,
+/// `"cache_eviction_strategy"`, and `"log_level"`), with the value in effect at startup.
+/// Use `infera_get_cache_info` for the current cache size limit, which can be changed
+/// at runtime.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated, null-terminated C string containing the configuration.
+/// The caller is responsible for freeing this string using `infera_free_string`.
+///
+/// # Safety
+///
+/// The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub extern "C" fn infera_get_config() -> *mut c_char {
+    let json_str = serde_json::to_string(&config::CONFIG.to_json()).unwrap_or_default();
+    CString::new(json_str).unwrap_or_default().into_raw()
+}
+
+/// Clears the entire model cache directory.
+///
+/// This removes all cached remote models, freeing up disk space. Loaded models keep
+/// running, but a warning naming the models whose cached files were deleted is logged,
+/// since reloading them will download them again.
+///
+/// # Returns
+///
+/// * `0` on success.
+/// * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
+///
+/// # Safety
+///
+/// This function is safe to call at any time.
+#[no_mangle]
+pub extern "C" fn infera_clear_cache() -> i32 {
+    let cached_models = model::models_cached_in(&http::cache_dir());
+    match http::clear_cache() {
+        Ok(()) => {
+            if !cached_models.is_empty() {
+                log!(
+                    LogLevel::Warn,
+                    "Cleared the cached files of loaded models {}; reloading them will \
+                     download them again",
+                    cached_models.join(", ")
+                );
+            }
+            0
+        }
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Sets the cache size limit in bytes for the rest of the process lifetime.
+///
+/// This overrides the limit read from `INFERA_CACHE_SIZE_LIMIT` at startup and
+/// immediately evicts least recently used cached models until the cache fits.
+///
+/// # Arguments
+///
+/// * `bytes` - The new cache size limit in bytes.
+///
+/// # Returns
+///
+/// * `0` on success.
+/// * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
+///
+/// # Safety
+///
+/// This function is safe to call at any time.
+#[no_mangle]
+pub extern "C" fn infera_set_cache_size_limit(bytes: u64) -> i32 {
+    match http::set_cache_size_limit(bytes) {
+        Ok(()) => 0,
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Turns offline mode on or off for the rest of the process lifetime.
+///
+/// This overrides `INFERA_OFFLINE` read at startup. While offline, loading a model from
+/// a URL never makes a network request: a cached copy is served without revalidating
+/// it, and a model that is not cached fails right away. `infera_check_model_updated`
+/// fails too, and signatures are not fetched.
+///
+/// # Arguments
+///
+/// * `offline` - `true` to go offline, or `false` to allow network requests again.
+///
+/// # Returns
+///
+/// * `0` on success.
+///
+/// # Safety
+///
+/// This function is safe to call at any time.
+#[no_mangle]
+pub extern "C" fn infera_set_offline(offline: bool) -> i32 {
+    config::set_offline(offline);
+    log!(
+        LogLevel::Info,
+        "Offline mode {}",
+        if offline { "enabled" } else { "disabled" }
+    );
+    0
+}
+
+/// Routes Infera's log records to a host callback instead of stderr.
+///
+/// The callback receives the level (`0` error, `1` warn, `2` info, `3` debug), the
+/// module the record comes from, and the message as separate arguments. While a callback
+/// is set it receives every record, regardless of `INFERA_LOG_LEVEL`, so the host decides
+/// what to keep. The callback may be called from any thread, including worker threads.
+///
+/// # Arguments
+///
+/// * `callback` - The function to call for each log record, or null to go back to
+///   printing records at or above `INFERA_LOG_LEVEL` to stderr.
+///
+/// # Returns
+///
+/// * `0` on success.
+///
+/// # Safety
+///
+/// This function is safe to call at any time. The strings passed to the callback are only
+/// valid for the duration of the call.
+#[no_mangle]
+pub extern "C" fn infera_set_log_callback(callback: Option<InferaLogCallback>) -> i32 {
+    config::set_log_callback(callback);
+    0
+}
+
+/// Adds a public key that is trusted to sign models.
+///
+/// Once a key is registered, every model load looks for a detached ed25519 signature
+/// next to the model file (`<path>.sig`, or `<url>.sig` for remote models) and reports
+/// the result in `infera_get_model_info`. With `INFERA_REQUIRE_SIGNED_MODELS=1`, models
+/// without a valid signature from a trusted key fail to load.
+///
+/// # Arguments
+///
+/// * `key` - A pointer to a null-terminated C string holding an ed25519 public key,
+///   either PEM-encoded (SubjectPublicKeyInfo) or as 64 hex characters.
+///
+/// # Returns
+///
+/// * `0` on success.
+/// * `-1` on failure. Call `infera_last_error()` to get a descriptive error message.
+///
+/// # Safety
+///
+/// * The `key` pointer must not be null.
+/// * The memory pointed to by `key` must be a valid, null-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn infera_add_trusted_key(key: *const c_char) -> i32 {
+    let result = (|| -> Result<(), error::InferaError> {
+        if key.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let key_str = CStr::from_ptr(key).to_str()?;
+        signature::add_trusted_key(key_str)
+    })();
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Returns cache statistics as a JSON string.
+///
+/// The JSON object includes:
+/// * `"cache_dir"`: The path to the cache directory.
+/// * `"temp_dir"`: The directory in-progress downloads are written to (see `INFERA_TEMP_DIR`).
+/// * `"total_size_bytes"`: Total size of cached models in bytes (same as `"logical_size_bytes"`).
+/// * `"logical_size_bytes"`: Total size of all cached models, counting a payload shared by
+///   several URLs once per URL.
+/// * `"physical_size_bytes"`: Size of the cached models on disk, counting each shared
+///   payload once (see `INFERA_CACHE_DEDUP`).
+/// * `"file_count"`: Number of cached model files.
+/// * `"size_limit_bytes"`: The configured cache size limit.
+/// * `"stats"`: Cache activity counters since the process started or since
+///   `infera_reset_cache_stats` was called: `hits` (loads served from a cached file),
+///   `misses` (loads that downloaded the model or failed), `revalidations` (304 responses),
+///   `downloads`, `bytes_downloaded`, `evictions` (entries removed to stay within the size
+///   limit), and `bytes_evicted`.
+/// * `"orphaned_parts"`: The `removed_files` count and `removed_bytes` of the orphaned
+///   `.part` files of unfinished downloads (for example, after a crash) that this call
+///   cleaned up. A `.part` file is orphaned once no download is writing it and it has not
+///   been modified for 10 minutes.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated, null-terminated C string containing the cache info.
+/// The caller is responsible for freeing this string using `infera_free_string`.
+///
+/// # Safety
+///
+/// The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub extern "C" fn infera_get_cache_info() -> *mut c_char {
+    let result = (|| -> Result<serde_json::Value, error::InferaError> {
+        let cache_dir = http::cache_dir();
+        let cache_dir_str = cache_dir.to_string_lossy().to_string();
+
+        let usage = http::cache_usage()?;
+        let size_limit = config::cache_size_limit();
+        let orphaned_parts = http::clean_orphaned_parts();
+
+        Ok(json!({
+            "cache_dir": cache_dir_str,
+            "temp_dir": http::temp_dir().to_string_lossy(),
+            "total_size_bytes": usage.logical_bytes,
+            "logical_size_bytes": usage.logical_bytes,
+            "physical_size_bytes": usage.physical_bytes,
+            "file_count": usage.file_count,
+            "size_limit_bytes": size_limit,
+            "stats": http::cache_stats(),
+            "orphaned_parts": {
+                "removed_files": orphaned_parts.removed_files,
+                "removed_bytes": orphaned_parts.removed_bytes,
+            },
+        }))
+    })();
+
+    let final_json = result.unwrap_or_else(|e| {
+        error::set_last_error(&e);
+        e.to_json()
+    });
+    let json_str = serde_json::to_string(&final_json).unwrap_or_default();
+    CString::new(json_str).unwrap_or_default().into_raw()
+}
+
+/// Resets the cache activity counters reported under `"stats"` by `infera_get_cache_info`.
+///
+/// # Returns
+///
+/// * `0` on success.
+#[no_mangle]
+pub extern "C" fn infera_reset_cache_stats() -> i32 {
+    http::reset_cache_stats();
+    0
+}
+
+/// Returns a JSON array describing each cached remote model.
+///
+/// Each entry contains the original `url` (or `null` for models cached before URLs
+/// were recorded), the `final_url` it was downloaded from after redirects (or `null`
+/// if not recorded), the `sha256` of the download, computed while it was streamed (or
+/// `null` if not recorded), the cached `file` path, `size_bytes`, and `last_access` as
+/// seconds since the Unix epoch. Entries are ordered by access time, oldest first.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated, null-terminated C string containing the JSON array.
+/// The caller is responsible for freeing this string using `infera_free_string`.
+/// On error, the JSON will be an object with an "error" key.
+///
+/// # Safety
+///
+/// The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub extern "C" fn infera_list_cache() -> *mut c_char {
+    let final_json = http::list_cache()
+        .map(serde_json::Value::from)
+        .unwrap_or_else(|e| {
+            error::set_last_error(&e);
+            e.to_json()
+        });
+    let json_str = serde_json::to_string(&final_json).unwrap_or_default();
+    CString::new(json_str).unwrap_or_default().into_raw()
+}
+
+/// Returns a JSON array of the cached models that would be evicted to make room for
+/// `required_bytes` more bytes, without deleting anything.
+///
+/// The selection uses the current cache size limit and eviction strategy
+/// (`INFERA_CACHE_EVICTION`), exactly as a download needing that much space would. Entries
+/// are in eviction order and contain the original `url`, the cached `file` path,
+/// `size_bytes`, `freed_bytes` (zero for a deduplicated payload still used by another
+/// entry or an entry in use), `last_access` and `downloaded_at` as seconds since the Unix
+/// epoch, `access_count`, and `in_use`. Entries in use back a loaded model and are
+/// skipped instead of evicted, so the next entries are evicted in their place. The
+/// array is empty if the space already fits.
+///
+/// # Arguments
+///
+/// * `required_bytes` - The number of bytes to make room for. Pass 0 to preview what
+///   fitting the cache within its current limit would evict.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated, null-terminated C string containing the JSON array.
+/// The caller is responsible for freeing this string using `infera_free_string`.
+/// On error, the JSON will be an object with an "error" key.
+///
+/// # Safety
+///
+/// The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub extern "C" fn infera_preview_eviction(required_bytes: u64) -> *mut c_char {
+    let final_json = http::preview_eviction(required_bytes)
+        .map(serde_json::Value::from)
+        .unwrap_or_else(|e| {
+            error::set_last_error(&e);
+            e.to_json()
+        });
+    let json_str = serde_json::to_string(&final_json).unwrap_or_default();
+    CString::new(json_str).unwrap_or_default().into_raw()
+}
+
+/// Scans a directory for `.onnx` files and loads them into Infera automatically.
+///
+/// The name for each model is derived from its filename (without the extension). The
+/// loaded models are recorded as a group, which `infera_unload_group` can unload later
+/// without affecting models loaded in other ways.
+///
+/// # Arguments
+///
+/// * `path` - A pointer to a null-terminated C string representing the directory path.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated C string containing a JSON object with these fields:
+/// * `"group_id"`: The ID of the group of models loaded from the directory.
+/// * `"loaded"`: A list of model names that were successfully loaded.
+/// * `"reloaded"`, `"unchanged"`, and `"removed"`: Empty lists, filled in when syncing a
+///   directory with `infera_set_autoload_dir_with_options`.
+/// * `"errors"`: A list of objects, each detailing a file that failed to load (or a
+///   directory entry that could not be read) and the reason. Files that failed to load
+///   also have the number of `"attempts"` made to load them (see `INFERA_LOAD_RETRY_ATTEMPTS`).
+///
+/// The caller is responsible for freeing this string using `infera_free_string`.
+///
+/// # Safety
+///
+/// * The `path` pointer must not be null and must point to a valid C string.
+/// * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub unsafe extern "C" fn infera_set_autoload_dir(path: *const c_char) -> *mut c_char {
+    infera_set_autoload_dir_with_options(path, std::ptr::null())
+}
+
+/// Autoloads a directory like `infera_set_autoload_dir`, with a JSON object of options.
+///
+/// With `{"sync": true}`, an earlier group of the same directory is reused instead of a
+/// new group being created. Models whose files did not change (by modification time and
+/// size) are kept and reported as `"unchanged"`, new files are loaded, changed files are
+/// reloaded and reported as `"reloaded"`, and models whose files were deleted are
+/// unloaded and reported as `"removed"`. A model whose reload fails stays loaded in its
+/// previous version.
+///
+/// # Arguments
+///
+/// * `path` - A pointer to a null-terminated C string representing the directory path.
+/// * `options_json` - An optional null-terminated C string with a JSON object of options.
+///   Pass null or an empty string for the defaults.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated C string containing the JSON report described for
+/// `infera_set_autoload_dir`. On error, the JSON will be an object with an "error" key.
+/// The caller is responsible for freeing this string using `infera_free_string`.
+///
+/// # Safety
+///
+/// * The `path` pointer must not be null and must point to a valid C string.
+/// * If not null, `options_json` must point to a valid C string.
+/// * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub unsafe extern "C" fn infera_set_autoload_dir_with_options(
+    path: *const c_char,
+    options_json: *const c_char,
+) -> *mut c_char {
+    let result = (|| -> Result<serde_json::Value, error::InferaError> {
+        if path.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let path_str = CStr::from_ptr(path).to_str()?;
+        let options = if options_json.is_null() {
+            autoload::AutoloadOptions::default()
+        } else {
+            autoload::AutoloadOptions::from_json(CStr::from_ptr(options_json).to_str()?)?
+        };
+        autoload::autoload_dir(path_str, options)
+    })();
+
+    let final_json = result.unwrap_or_else(|e| {
+        error::set_last_error(&e);
+        e.to_json()
+    });
+    let json_str = serde_json::to_string(&final_json).unwrap_or_default();
+    CString::new(json_str).unwrap_or_default().into_raw()
+}
+
+/// Unloads the models of an autoload group and forgets the group.
+///
+/// Only the models the group loaded are unloaded. Members that were already unloaded or
+/// renamed are skipped.
+///
+/// # Arguments
+///
+/// * `group_id` - The `group_id` returned by `infera_set_autoload_dir`.
+///
+/// # Returns
+///
+/// * The number of models unloaded on success.
+/// * `-1` if no group has this ID. Call `infera_last_error()` to get a descriptive error
+///   message.
+#[no_mangle]
+pub extern "C" fn infera_unload_group(group_id: u64) -> i32 {
+    match autoload::unload_group(group_id) {
+        Ok(count) => i32::try_from(count).unwrap_or(i32::MAX),
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Returns a JSON array describing each autoload group.
+///
+/// Each entry contains the `group_id`, the canonical `dir` the models were loaded from,
+/// and the names of its `models`. Groups are ordered by ID.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated, null-terminated C string containing the JSON array.
+/// The caller is responsible for freeing this string using `infera_free_string`.
+///
+/// # Safety
+///
+/// The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub extern "C" fn infera_list_groups() -> *mut c_char {
+    let json_str = serde_json::to_string(&autoload::list_groups()).unwrap_or_default();
+    CString::new(json_str).unwrap_or_default().into_raw()
+}
+
+/// Autoloads a directory and starts a background thread that keeps it in sync.
+///
+/// The directory is synced immediately, then every `poll_interval_secs` seconds, as if
+/// `infera_set_autoload_dir_with_options` were called with `{"sync": true}`: new files
+/// are loaded, changed files are hot-reloaded, and models whose files were deleted are
+/// unloaded. A changed file replaces the loaded model only once it compiled, so a file
+/// that fails to load keeps the previous version serving. Every action is logged, and
+/// the counts are reported under `autoload` by `infera_get_metrics`.
+///
+/// # Arguments
+///
+/// * `path` - A pointer to a null-terminated C string representing the directory path.
+/// * `poll_interval_secs` - The number of seconds between syncs. Must be at least 1.
+///
+/// # Returns
+///
+/// A pointer to a heap-allocated C string containing the JSON report of the first sync,
+/// as described for `infera_set_autoload_dir`. On error (for example, if the directory
+/// cannot be read or is already watched), the JSON will be an object with an "error" key.
+/// The caller is responsible for freeing this string using `infera_free_string`.
+///
+/// # Safety
+///
+/// * The `path` pointer must not be null and must point to a valid C string.
+/// * The returned pointer must be freed with `infera_free_string` to avoid memory leaks.
+#[no_mangle]
+pub unsafe extern "C" fn infera_watch_autoload_dir(
+    path: *const c_char,
+    poll_interval_secs: u64,
+) -> *mut c_char {
+    let result = (|| -> Result<serde_json::Value, error::InferaError> {
+        if path.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let path_str = CStr::from_ptr(path).to_str()?;
+        autoload::watch_dir(path_str, std::time::Duration::from_secs(poll_interval_secs))
+    })();
+
+    let final_json = result.unwrap_or_else(|e| {
+        error::set_last_error(&e);
+        e.to_json()
+    });
+    let json_str = serde_json::to_string(&final_json).unwrap_or_default();
+    CString::new(json_str).unwrap_or_default().into_raw()
+}
+
+/// Stops watching a directory started with `infera_watch_autoload_dir`.
+///
+/// Waits for the watcher thread to finish a sync in progress. The directory's models
+/// stay loaded.
+///
+/// # Arguments
+///
+/// * `path` - A pointer to a null-terminated C string representing the directory path.
+///
+/// # Returns
+///
+/// * `0` on success.
+/// * `-1` if the directory is not watched. Call `infera_last_error()` to get a
+///   descriptive error message.
+///
+/// # Safety
+///
+/// The `path` pointer must not be null and must point to a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn infera_unwatch_autoload_dir(path: *const c_char) -> i32 {
+    let result = (|| -> Result<(), error::InferaError> {
+        if path.is_null() {
+            return Err(error::InferaError::NullPointer);
+        }
+        let path_str = CStr::from_ptr(path).to_str()?;
+        autoload::unwatch_dir(path_str)
+    })();
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Initializes Infera's global state up front instead of on first use.
+///
+/// This reads the configuration from the environment, sets up the model registry, and
+/// creates the cache directory and the download temp directory. Other functions
+/// initialize what they need on first use, which is safe from several threads at once,
+/// so calling this is optional. Hosts can call it at startup to take that work, and any
+/// error creating the cache directory, out of their first model load. It can be called
+/// more than once.
+///
+/// # Returns
+///
+/// * `0` on success.
+/// * `-1` on failure (e.g., the cache directory cannot be created). Call
+///   `infera_last_error()` to get a descriptive error message.
+#[no_mangle]
+pub extern "C" fn infera_init() -> i32 {
+    once_cell::sync::Lazy::force(&config::CONFIG);
+    model::init_registry();
+    match http::ensure_cache_dirs() {
+        Ok(()) => 0,
+        Err(e) => {
+            error::set_last_error(&e);
+            -1
+        }
+    }
+}
+
+/// Stops Infera's background threads and waits for them to finish.
+///
+/// This stops every directory watcher started with `infera_watch_autoload_dir`. Loaded
+/// models stay loaded, and Infera can still be used afterwards. Call this before
+/// unloading the library so no watcher thread outlives it.
+///
+/// # Returns
+///
+/// The number of background threads stopped.
+#[no_mangle]
+pub extern "C" fn infera_shutdown() -> i32 {
+    let stopped = autoload::shutdown_watchers();
+    i32::try_from(stopped).unwrap_or(i32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_infera_get_version() {
+        let version_ptr = infera_get_version();
+        let version_json = unsafe { CStr::from_ptr(version_ptr).to_str().unwrap() };
+        let version_data: serde_json::Value = serde_json::from_str(version_json).unwrap();
+
+        assert!(version_data["version"].is_string());
+        assert!(version_data["onnx_backend"].is_string());
+        assert!(version_data["model_cache_dir"].is_string());
+
+        unsafe { infera_free_string(version_ptr) };
+    }
+
+    #[test]
+    fn test_infera_get_version_reports_build_info() {
+        let info = json_from_ptr(infera_get_version());
+        let non_empty = |key: &str| info[key].as_str().is_some_and(|value| !value.is_empty());
+
+        assert!(non_empty("target"), "{}", info);
+        let features = info["features"].as_array().unwrap();
+        let backends = info["backend_versions"].as_object().unwrap();
+        if cfg!(feature = "tract") {
+            assert!(non_empty("tract_version"), "{}", info);
+            assert!(features.contains(&json!("tract")), "{}", info);
+            assert_eq!(backends["tract-onnx"], info["tract_version"]);
+            assert!(backends["tract-core"].is_string(), "{}", info);
+        } else {
+            assert!(info["tract_version"].is_null());
+            assert!(backends.is_empty());
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_infera_set_autoload_dir_reports_unreadable_entries() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        fs::copy(
+            "../test/models/linear.onnx",
+            dir.path().join("autoload_ok.onnx"),
+        )
+        .unwrap();
+        let locked_path = dir.path().join("autoload_locked.onnx");
+        fs::copy("../test/models/linear.onnx", &locked_path).unwrap();
+        fs::set_permissions(&locked_path, fs::Permissions::from_mode(0o000)).unwrap();
+        // Privileged users (e.g. root in CI containers) can still read the file.
+        let locked_readable = fs::File::open(&locked_path).is_ok();
+
+        let path_cstr = CString::new(dir.path().to_str().unwrap()).unwrap();
+        let result_ptr = unsafe { infera_set_autoload_dir(path_cstr.as_ptr()) };
+        let result_json = unsafe { CStr::from_ptr(result_ptr).to_str().unwrap() };
+        let result_data: serde_json::Value = serde_json::from_str(result_json).unwrap();
+        unsafe { infera_free_string(result_ptr) };
+
+        let loaded = result_data["loaded"].as_array().unwrap();
+        assert!(loaded.iter().any(|name| name == "autoload_ok"));
+        if !locked_readable {
+            let errors = result_data["errors"].as_array().unwrap();
+            assert_eq!(errors.len(), 1, "unexpected errors: {}", result_json);
+            assert!(errors[0]["file"]
+                .as_str()
+                .unwrap()
+                .ends_with("autoload_locked.onnx"));
+            assert!(!loaded.iter().any(|name| name == "autoload_locked"));
+        }
+
+        for name in ["autoload_ok", "autoload_locked"] {
+            let name = CString::new(name).unwrap();
+            unsafe { infera_unload_model(name.as_ptr()) };
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "tract")]
+    fn test_infera_set_autoload_dir_reports_load_attempts() {
+        let dir = tempdir().unwrap();
+        let model_path = dir.path().join("autoload_flaky.onnx");
+        fs::copy("../test/models/linear.onnx", &model_path).unwrap();
+        engine::fail_reads(model_path.to_str().unwrap(), 1);
+
+        let path_cstr = CString::new(dir.path().to_str().unwrap()).unwrap();
+        let result_ptr = unsafe { infera_set_autoload_dir(path_cstr.as_ptr()) };
+        let result_data = json_from_ptr(result_ptr);
+
+        // INFERA_LOAD_RETRY_ATTEMPTS defaults to 1, so the one failed read fails the load
+        let errors = result_data["errors"].as_array().unwrap();
+        assert_eq!(errors.len(), 1, "unexpected errors: {}", result_data);
+        assert_eq!(errors[0]["attempts"], 1);
+        assert!(result_data["loaded"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_infera_set_autoload_dir() {
+        let dir = tempdir().unwrap();
+        let model_path = dir.path().join("linear.onnx");
+        fs::copy("../test/models/linear.onnx", &model_path).unwrap();
+
+        let path_cstr = CString::new(dir.path().to_str().unwrap()).unwrap();
+        let result_ptr = unsafe { infera_set_autoload_dir(path_cstr.as_ptr()) };
+        let result_json = unsafe { CStr::from_ptr(result_ptr).to_str().unwrap() };
+        let result_data: serde_json::Value = serde_json::from_str(result_json).unwrap();
+
+        assert_eq!(result_data["loaded"].as_array().unwrap().len(), 1);
+        assert_eq!(result_data["loaded"][0], "linear");
+        assert_eq!(result_data["errors"].as_array().unwrap().len(), 0);
+
+        unsafe { infera_free_string(result_ptr) };
+    }
+
+    #[test]
+    #[cfg(feature = "tract")]
+    fn test_infera_list_models_detailed() {
+        let dir = tempdir().unwrap();
+        fs::copy(
+            "../test/models/linear.onnx",
+            dir.path().join("detailed_member.onnx"),
+        )
+        .unwrap();
+        let path_cstr = CString::new(dir.path().to_str().unwrap()).unwrap();
+        let result_data = json_from_ptr(unsafe { infera_set_autoload_dir(path_cstr.as_ptr()) });
+        assert_eq!(result_data["loaded"][0], "detailed_member");
+        let group_id = result_data["group_id"].as_u64().unwrap();
+
+        let member = CString::new("detailed_member").unwrap();
+        let fallback = CString::new("detailed_fallback").unwrap();
+        let alias = CString::new("detailed_alias").unwrap();
+        let model_path = CString::new("../test/models/linear.onnx").unwrap();
+        unsafe {
+            assert_eq!(infera_load_model(fallback.as_ptr(), model_path.as_ptr()), 0);
+            assert_eq!(
+                infera_set_canary(alias.as_ptr(), member.as_ptr(), fallback.as_ptr(), 0.5, 4),
+                0
+            );
+        }
+
+        let listing = json_from_ptr(infera_list_models_detailed());
+        let entry = |name: &str| {
+            listing
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|entry| entry["name"] == name)
+                .cloned()
+                .unwrap_or_else(|| panic!("{} missing from {}", name, listing))
+        };
+        let member_entry = entry("detailed_member");
+        assert_eq!(member_entry["canonical_name"], "detailed_member");
+        assert!(member_entry["namespace"].is_null());
+        assert_eq!(member_entry["group_id"], group_id);
+        assert_eq!(member_entry["aliases"], json!(["detailed_alias"]));
+        assert_eq!(member_entry["previous_versions"], 0);
+        let fallback_entry = entry("detailed_fallback");
+        assert!(fallback_entry["group_id"].is_null());
+        assert_eq!(fallback_entry["aliases"], json!([]));
+
+        // Model info reports the same relationships
+        let info = json_from_ptr(unsafe { infera_get_model_info(member.as_ptr()) });
+        assert_eq!(info["group_id"], group_id);
+        assert_eq!(info["aliases"], json!(["detailed_alias"]));
+        assert!(info["namespace"].is_null());
+
+        // The flat listing keeps its format
+        let names = json_from_ptr(infera_get_loaded_models());
+        assert!(names
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|name| name.is_string()));
+
+        assert_eq!(infera_unload_group(group_id), 1);
+        unsafe { infera_unload_model(fallback.as_ptr()) };
+    }
+
+    #[test]
+    #[cfg(feature = "tract")]
+    fn test_infera_model_exists() {
+        let loaded = CString::new("exists_loaded").unwrap();
+        let fallback = CString::new("exists_fallback").unwrap();
+        let broken = CString::new("exists_broken").unwrap();
+        let alias = CString::new("exists_alias").unwrap();
+        let model_path = CString::new("../test/models/linear.onnx").unwrap();
+        let dir = tempdir().unwrap();
+        let broken_path = dir.path().join("broken.onnx");
+        fs::write(&broken_path, b"not an onnx model").unwrap();
+        let broken_path = CString::new(broken_path.to_str().unwrap()).unwrap();
+        unsafe {
+            assert_eq!(infera_load_model(loaded.as_ptr(), model_path.as_ptr()), 0);
+            assert_eq!(infera_load_model(fallback.as_ptr(), model_path.as_ptr()), 0);
+            assert_eq!(infera_load_model(broken.as_ptr(), broken_path.as_ptr()), -1);
+            assert_eq!(
+                infera_set_canary(alias.as_ptr(), loaded.as_ptr(), fallback.as_ptr(), 0.5, 4),
+                0
+            );
+
+            assert_eq!(infera_model_exists(loaded.as_ptr()), 1);
+            // A failed load leaves nothing in the registry
+            assert_eq!(infera_model_exists(broken.as_ptr()), 0);
+            // An alias is not a model
+            assert_eq!(infera_model_exists(alias.as_ptr()), 0);
+
+            assert_eq!(infera_unload_model(loaded.as_ptr()), 0);
+            assert_eq!(infera_model_exists(loaded.as_ptr()), 0);
+
+            assert_eq!(infera_model_exists(std::ptr::null()), -1);
+            infera_unload_model(fallback.as_ptr());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "tract")]
+    fn test_infera_watch_autoload_dir() {
+        let _guard = autoload::WATCH_TEST_LOCK.lock();
+        let dir = tempdir().unwrap();
+        fs::copy(
+            "../test/models/linear.onnx",
+            dir.path().join("watch_ffi.onnx"),
+        )
+        .unwrap();
+        let path = CString::new(dir.path().to_str().unwrap()).unwrap();
+
+        let zero = unsafe { infera_watch_autoload_dir(path.as_ptr(), 0) };
+        let zero_json = unsafe { CStr::from_ptr(zero).to_str().unwrap() };
+        assert!(zero_json.contains("Poll interval"));
+        unsafe { infera_free_string(zero) };
+
+        let report = unsafe { infera_watch_autoload_dir(path.as_ptr(), 60) };
+        let report_json: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(report).to_str().unwrap() }).unwrap();
+        assert_eq!(report_json["loaded"], json!(["watch_ffi"]));
+        unsafe { infera_free_string(report) };
+
+        // Shutdown stops the watcher (it is then no longer watched)
+        assert!(infera_shutdown() >= 1);
+        assert_eq!(unsafe { infera_unwatch_autoload_dir(path.as_ptr()) }, -1);
+        let err = unsafe { CStr::from_ptr(infera_last_error()) };
+        assert!(err.to_str().unwrap().contains("not watched"));
+        assert!(model::MODELS.read().contains_key("watch_ffi"));
+        model::MODELS.write().remove("watch_ffi");
+    }
+
+    #[test]
+    fn test_infera_unload_group_keeps_unrelated_models() {
+        let dir = tempdir().unwrap();
+        for name in ["ffi_group_a", "ffi_group_b"] {
+            fs::copy(
+                "../test/models/linear.onnx",
+                dir.path().join(format!("{}.onnx", name)),
+            )
+            .unwrap();
+        }
+        let unrelated = CString::new("ffi_group_unrelated").unwrap();
+        let model_path = CString::new("../test/models/linear.onnx").unwrap();
+        let path_cstr = CString::new(dir.path().to_str().unwrap()).unwrap();
+        unsafe {
+            let result_ptr = infera_set_autoload_dir(path_cstr.as_ptr());
+            let result: serde_json::Value =
+                serde_json::from_str(CStr::from_ptr(result_ptr).to_str().unwrap()).unwrap();
+            infera_free_string(result_ptr);
+            let group_id = result["group_id"].as_u64().unwrap();
+            assert_eq!(
+                infera_load_model(unrelated.as_ptr(), model_path.as_ptr()),
+                0
+            );
+
+            let groups_ptr = infera_list_groups();
+            let groups: serde_json::Value =
+                serde_json::from_str(CStr::from_ptr(groups_ptr).to_str().unwrap()).unwrap();
+            infera_free_string(groups_ptr);
+            let group = groups
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|g| g["group_id"] == group_id)
+                .unwrap();
+            assert_eq!(group["models"], json!(["ffi_group_a", "ffi_group_b"]));
+
+            assert_eq!(infera_unload_group(group_id), 2);
+            let models = model::MODELS.read();
+            assert!(!models.contains_key("ffi_group_a"));
+            assert!(!models.contains_key("ffi_group_b"));
+            assert!(models.contains_key("ffi_group_unrelated"));
+            drop(models);
+            assert_eq!(infera_unload_group(group_id), -1);
+            infera_unload_model(unrelated.as_ptr());
+        }
+    }
+
+    #[test]
+    fn test_infera_set_autoload_dir_non_existent() {
+        let dir = tempdir().unwrap();
+        let non_existent_path = dir.path().join("non_existent");
+        let path_cstr = CString::new(non_existent_path.to_str().unwrap()).unwrap();
+        let result_ptr = unsafe { infera_set_autoload_dir(path_cstr.as_ptr()) };
+        let result_json = unsafe { CStr::from_ptr(result_ptr).to_str().unwrap() };
+        let result_data: serde_json::Value = serde_json::from_str(result_json).unwrap();
+
+        assert!(result_data["error"].is_string());
+
+        unsafe { infera_free_string(result_ptr) };
+    }
+
+    #[test]
+    fn test_infera_set_autoload_dir_invalid_model() {
+        let dir = tempdir().unwrap();
+        let model_path = dir.path().join("invalid.onnx");
+        fs::write(&model_path, "invalid onnx data").unwrap();
+
+        let path_cstr = CString::new(dir.path().to_str().unwrap()).unwrap();
+        let result_ptr = unsafe { infera_set_autoload_dir(path_cstr.as_ptr()) };
+        let result_json = unsafe { CStr::from_ptr(result_ptr).to_str().unwrap() };
+        let result_data: serde_json::Value = serde_json::from_str(result_json).unwrap();
+
+        assert_eq!(result_data["loaded"].as_array().unwrap().len(), 0);
+        assert_eq!(result_data["errors"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            result_data["errors"][0]["file"],
+            model_path.to_str().unwrap()
+        );
+
+        unsafe { infera_free_string(result_ptr) };
+    }
+
+    #[test]
+    fn test_ffi_null_pointers() {
+        let model_name = CString::new("test").unwrap();
+        let path = CString::new("path").unwrap();
+        let null_ptr = std::ptr::null();
+
+        // Test infera_load_model
+        unsafe {
+            assert_eq!(infera_load_model(null_ptr, path.as_ptr()), -1);
+            let error = CStr::from_ptr(infera_last_error());
+            assert!(error.to_str().unwrap().contains("Null pointer passed"));
+
+            assert_eq!(infera_load_model(model_name.as_ptr(), null_ptr), -1);
+            let error = CStr::from_ptr(infera_last_error());
+            assert!(error.to_str().unwrap().contains("Null pointer passed"));
+        }
+
+        // Test infera_unload_model
+        unsafe {
+            assert_eq!(infera_unload_model(null_ptr), -1);
+            let error = CStr::from_ptr(infera_last_error());
+            assert!(error.to_str().unwrap().contains("Null pointer passed"));
+        }
+
+        // Test infera_predict
+        let data: [f32; 1] = [0.0];
+        unsafe {
+            let result = infera_predict(null_ptr, data.as_ptr(), 1, 1);
+            assert_eq!(result.status, -1);
+            let error = CStr::from_ptr(infera_last_error());
+            assert!(error.to_str().unwrap().contains("Null pointer passed"));
+
+            let result = infera_predict(model_name.as_ptr(), std::ptr::null(), 1, 1);
+            assert_eq!(result.status, -1);
+            let error = CStr::from_ptr(infera_last_error());
+            assert!(error.to_str().unwrap().contains("Null pointer passed"));
+        }
+
+        // Test infera_predict_from_blob
+        let blob: [u8; 4] = [0; 4];
+        unsafe {
+            let result = infera_predict_from_blob(null_ptr, blob.as_ptr(), 4);
+            assert_eq!(result.status, -1);
+            let error = CStr::from_ptr(infera_last_error());
+            assert!(error.to_str().unwrap().contains("Null pointer passed"));
+
+            let result = infera_predict_from_blob(model_name.as_ptr(), std::ptr::null(), 4);
+            assert_eq!(result.status, -1);
+            let error = CStr::from_ptr(infera_last_error());
+            assert!(error.to_str().unwrap().contains("Null pointer passed"));
+        }
+
+        // Test infera_get_model_info
+        unsafe {
+            let info_ptr = infera_get_model_info(null_ptr);
+            let info_json = CStr::from_ptr(info_ptr).to_str().unwrap();
+            let info_data: serde_json::Value = serde_json::from_str(info_json).unwrap();
+            assert!(info_data["error"].is_string());
+            assert!(info_data["error"]
+                .as_str()
+                .unwrap()
+                .contains("Null pointer passed"));
+            infera_free_string(info_ptr);
+        }
+
+        // Test infera_set_autoload_dir
+        unsafe {
+            let result_ptr = infera_set_autoload_dir(null_ptr);
+            let result_json = CStr::from_ptr(result_ptr).to_str().unwrap();
+            let result_data: serde_json::Value = serde_json::from_str(result_json).unwrap();
+            assert!(result_data["error"].is_string());
+            assert!(result_data["error"]
+                .as_str()
+                .unwrap()
+                .contains("Null pointer passed"));
+            infera_free_string(result_ptr);
+        }
+    }
+
+    #[test]
+    fn test_infera_predict_from_blob_invalid_size() {
+        let model_name = CString::new("test_model").unwrap();
+        let model_path = CString::new("../test/models/linear.onnx").unwrap();
+        unsafe {
+            infera_load_model(model_name.as_ptr(), model_path.as_ptr());
+        }
+
+        // Blob size is 5, which is not a multiple of 4 (size of f32)
+        let blob: [u8; 5] = [0; 5];
+        let result = unsafe { infera_predict_from_blob(model_name.as_ptr(), blob.as_ptr(), 5) };
+        assert_eq!(result.status, -3);
+
+        let error = unsafe { CStr::from_ptr(infera_last_error()) };
+        assert!(error
+            .to_str()
+            .unwrap()
+            .contains("Invalid BLOB size: length must be a multiple of 4"));
+
+        unsafe {
+            infera_unload_model(model_name.as_ptr());
+        }
+    }
+
+    #[test]
+    fn test_infera_predict_from_blob_batch_rejects_extra_samples() {
+        let name = CString::new("blob_batch_model").unwrap();
+        let path = CString::new("../test/models/dynamic_batch.onnx").unwrap();
+        unsafe {
+            assert_eq!(infera_load_model(name.as_ptr(), path.as_ptr()), 0);
+        }
+
+        // Two rows of three features: divisible into samples, but one more than declared.
+        let blob: Vec<u8> = [1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0]
+            .iter()
+            .flat_map(|x| x.to_ne_bytes())
+            .collect();
+        let res =
+            unsafe { infera_predict_from_blob_batch(name.as_ptr(), blob.as_ptr(), blob.len(), 1) };
+        assert_eq!(res.status, error::InferaStatus::InvalidShape as i32);
+        unsafe { infera_free_result(res) };
+
+        let res =
+            unsafe { infera_predict_from_blob_batch(name.as_ptr(), blob.as_ptr(), blob.len(), 2) };
+        assert_eq!(res.status, 0);
+        assert_eq!(res.rows, 2);
+        unsafe {
+            infera_free_result(res);
+            infera_unload_model(name.as_ptr());
+        }
+    }
+
+    #[test]
+    fn test_infera_predict_from_blob_timeout_matches_infera_predict_from_blob() {
+        let name = CString::new("blob_timeout_model").unwrap();
+        let path = CString::new("../test/models/dynamic_batch.onnx").unwrap();
+        unsafe {
+            assert_eq!(infera_load_model(name.as_ptr(), path.as_ptr()), 0);
+        }
+
+        let blob: Vec<u8> = [1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0]
+            .iter()
+            .flat_map(|x| x.to_ne_bytes())
+            .collect();
+        let expected =
+            unsafe { infera_predict_from_blob(name.as_ptr(), blob.as_ptr(), blob.len()) };
+        for timeout_ms in [0, 60_000] {
+            let res = unsafe {
+                infera_predict_from_blob_timeout(
+                    name.as_ptr(),
+                    blob.as_ptr(),
+                    blob.len(),
+                    timeout_ms,
+                )
+            };
+            assert_eq!(res.status, 0);
+            assert_eq!((res.rows, res.cols), (expected.rows, expected.cols));
+            unsafe {
+                assert_eq!(
+                    std::slice::from_raw_parts(res.data, res.len),
+                    std::slice::from_raw_parts(expected.data, expected.len)
+                );
+                infera_free_result(res);
+            }
+        }
+        unsafe {
+            infera_free_result(expected);
+            infera_unload_model(name.as_ptr());
+        }
+    }
+
+    #[test]
+    fn test_infera_predict_to_blob_matches_infera_predict() {
+        let name = CString::new("to_blob_model").unwrap();
+        let path = CString::new("../test/models/dynamic_batch.onnx").unwrap();
+        unsafe {
+            assert_eq!(infera_load_model(name.as_ptr(), path.as_ptr()), 0);
+        }
+        let input = [1.0f32, 2.0, 3.0, 0.0, 0.0, 0.0];
+        let expected = predict_values(&name, &input, 2, 3);
+        let to_blob = |native_endian: bool, out: &mut InferaBlob| unsafe {
+            infera_predict_to_blob(name.as_ptr(), input.as_ptr(), 2, 3, native_endian, out)
+        };
+
+        // The bytes are the flattened output as little-endian floats
+        let mut blob = InferaBlob::empty();
+        assert_eq!(to_blob(false, &mut blob), 0);
+        let bytes = unsafe { std::slice::from_raw_parts(blob.ptr, blob.len) };
+        let little: Vec<u8> = expected.iter().flat_map(|v| v.to_le_bytes()).collect();
+        assert_eq!(bytes, little.as_slice());
+        assert_eq!(&bytes[..4], &[0x00, 0x00, 0xe0, 0x3f]); // 1.75
+        unsafe { infera_free_blob(blob) };
+
+        let mut blob = InferaBlob::empty();
+        assert_eq!(to_blob(true, &mut blob), 0);
+        let bytes = unsafe { std::slice::from_raw_parts(blob.ptr, blob.len) };
+        let native: Vec<u8> = expected.iter().flat_map(|v| v.to_ne_bytes()).collect();
+        assert_eq!(bytes, native.as_slice());
+        unsafe { infera_free_blob(blob) };
+
+        // A failed prediction reports its status and leaves no blob to free
+        let mut blob = InferaBlob::empty();
+        let status = unsafe {
+            infera_predict_to_blob(name.as_ptr(), input.as_ptr(), 3, 2, false, &mut blob)
+        };
+        assert_eq!(status, error::InferaStatus::InvalidShape as i32);
+        assert!(blob.ptr.is_null());
+        assert_eq!(blob.len, 0);
+        unsafe {
+            infera_unload_model(name.as_ptr());
+        }
+    }
+
+    #[test]
+    fn test_infera_stream_matches_one_batched_call() {
+        let name = CString::new("stream_model").unwrap();
+        let path = CString::new("../test/models/dynamic_batch.onnx").unwrap();
+        unsafe {
+            assert_eq!(infera_load_model(name.as_ptr(), path.as_ptr()), 0);
+        }
+        let input: Vec<f32> = (0..21).map(|i| i as f32 * 0.5 - 3.0).collect();
+        let expected = predict_values(&name, &input, 7, 3);
+
+        let handle = unsafe { infera_stream_begin(name.as_ptr(), std::ptr::null()) };
+        assert!(handle > 0);
+        // The model stays in use by the session after it is unloaded
+        unsafe {
+            assert_eq!(infera_unload_model(name.as_ptr()), 0);
+        }
+
+        let mut streamed = Vec::new();
+        for chunk in [&input[0..6], &input[6..12], &input[12..18], &input[18..21]] {
+            let rows = chunk.len() / 3;
+            let res = unsafe { infera_stream_push(handle, chunk.as_ptr(), rows, 3) };
+            assert_eq!(res.status, 0);
+            assert_eq!(res.rows, rows);
+            streamed.extend_from_slice(unsafe { std::slice::from_raw_parts(res.data, res.len) });
+            unsafe { infera_free_result(res) };
+        }
+        assert_eq!(streamed, expected);
+
+        // A chunk with a different number of columns is rejected without ending the session
+        let res = unsafe { infera_stream_push(handle, input.as_ptr(), 3, 2) };
+        assert_eq!(res.status, error::InferaStatus::InvalidShape as i32);
+
+        let stats = json_from_ptr(infera_stream_end(handle));
+        assert_eq!(stats["model"], "stream_model");
+        assert_eq!(stats["chunks"], 4);
+        assert_eq!(stats["total_rows"], 7);
+        assert_eq!(stats["cols"], 3);
+        assert!(stats["elapsed_ns"].as_u64().unwrap() >= stats["inference_ns"].as_u64().unwrap());
+
+        // The session is gone once it has ended
+        let stats = json_from_ptr(infera_stream_end(handle));
+        assert!(stats["error"].as_str().unwrap().contains("is not open"));
+        let res = unsafe { infera_stream_push(handle, input.as_ptr(), 1, 3) };
+        assert_eq!(res.status, -1);
+    }
+
+    #[test]
+    fn test_infera_predict_invalid_shape() {
+        // Load a simple model that expects input shape [1,3]
+        let model_name = CString::new("shape_check").unwrap();
+        let model_path = CString::new("../test/models/linear.onnx").unwrap();
+        unsafe {
+            assert_eq!(
+                infera_load_model(model_name.as_ptr(), model_path.as_ptr()),
+                0
+            );
+        }
+
+        // Provide rows=1, cols=2 while model expects 3 features -> should error
+        let data: [f32; 2] = [0.0, 0.0];
+        let res = unsafe { infera_predict(model_name.as_ptr(), data.as_ptr(), 1, 2) };
+        assert_eq!(res.status, -3);
+        let err = unsafe { CStr::from_ptr(infera_last_error()) };
+        let msg = err.to_str().unwrap();
+        assert!(
+            msg.contains("Invalid input shape"),
+            "unexpected error: {}",
+            msg
+        );
+
+        unsafe {
+            infera_unload_model(model_name.as_ptr());
+        }
+    }
+
+    /// Runs `infera_predict` and returns the output values, freeing the result.
+    fn predict_values(name: &CString, data: &[f32], rows: usize, cols: usize) -> Vec<f32> {
+        let res = unsafe { infera_predict(name.as_ptr(), data.as_ptr(), rows, cols) };
+        assert_eq!(res.status, 0, "prediction failed: {:?}", unsafe {
+            CStr::from_ptr(infera_last_error())
+        });
+        assert_eq!(res.rows, rows);
+        let values = unsafe { std::slice::from_raw_parts(res.data, res.len) }.to_vec();
+        unsafe { infera_free_result(res) };
+        values
+    }
+
+    /// The linear test model: y = 2*x1 - 1*x2 + 0.5*x3 + 0.25
+    fn linear_expected(row: &[f32]) -> f32 {
+        2.0 * row[0] - row[1] + 0.5 * row[2] + 0.25
+    }
+
+    #[test]
+    fn test_infera_predict_profiled() {
+        let name = CString::new("linear_profiled").unwrap();
+        let path = CString::new("../test/models/dynamic_batch.onnx").unwrap();
+        assert_eq!(
+            unsafe { infera_load_model(name.as_ptr(), path.as_ptr()) },
+            0
+        );
+
+        let data = [1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let profiled = unsafe { infera_predict_profiled(name.as_ptr(), data.as_ptr(), 2, 3) };
+        assert_eq!(profiled.result.status, 0);
+        assert!(profiled.inference_ns > 0);
+        assert_eq!(profiled.input_elements, 6);
+        assert_eq!(profiled.output_elements, 2);
+        assert_eq!(profiled.output_elements, profiled.result.len);
+        let values =
+            unsafe { std::slice::from_raw_parts(profiled.result.data, profiled.result.len) };
+        assert_eq!(
+            values,
+            [linear_expected(&data[..3]), linear_expected(&data[3..])]
+        );
+        unsafe { infera_free_result(profiled.result) };
+
+        let missing = CString::new("linear_profiled_missing").unwrap();
+        let profiled = unsafe { infera_predict_profiled(missing.as_ptr(), data.as_ptr(), 2, 3) };
+        assert_eq!(profiled.result.status, InferaStatus::ModelNotFound as i32);
+        assert_eq!(profiled.inference_ns, 0);
+        assert_eq!(profiled.input_elements, 0);
+
+        unsafe { infera_unload_model(name.as_ptr()) };
+    }
+
+    #[test]
+    fn test_infera_predict_pad_batch_fixed_batch_model() {
+        let name = CString::new("fixed_batch_padded").unwrap();
+        let path = CString::new("../test/models/fixed_batch.onnx").unwrap();
+        let options = CString::new(r#"{"pad_batch": true}"#).unwrap();
+        unsafe {
+            assert_eq!(
+                infera_load_model_with_options(name.as_ptr(), path.as_ptr(), options.as_ptr()),
+                0
+            );
+        }
+
+        // Under-sized (3 rows), exact (4 rows), and over-sized (9 rows) inputs
+        for rows in [3usize, 4, 9] {
+            let data: Vec<f32> = (0..rows * 3).map(|i| i as f32 * 0.5).collect();
+            let values = predict_values(&name, &data, rows, 3);
+            assert_eq!(values.len(), rows);
+            for (row, value) in data.chunks(3).zip(values) {
+                assert!((value - linear_expected(row)).abs() < 1e-5);
+            }
+        }
+
+        unsafe { infera_unload_model(name.as_ptr()) };
+    }
+
+    #[test]
+    fn test_infera_predict_fixed_batch_without_padding_fails() {
+        let name = CString::new("fixed_batch_unpadded").unwrap();
+        let path = CString::new("../test/models/fixed_batch.onnx").unwrap();
+        unsafe {
+            assert_eq!(infera_load_model(name.as_ptr(), path.as_ptr()), 0);
+        }
+        let data = [1.0f32; 9];
+        let res = unsafe { infera_predict(name.as_ptr(), data.as_ptr(), 3, 3) };
+        assert_eq!(res.status, -4);
+        unsafe {
+            infera_free_result(res);
+            infera_unload_model(name.as_ptr());
+        }
+    }
+
+    #[test]
+    fn test_infera_load_model_with_invalid_options() {
+        let name = CString::new("bad_options").unwrap();
+        let path = CString::new("../test/models/linear.onnx").unwrap();
+        let options = CString::new(r#"{"pad_batch": 1}"#).unwrap();
+        let rc = unsafe {
+            infera_load_model_with_options(name.as_ptr(), path.as_ptr(), options.as_ptr())
+        };
+        assert_eq!(rc, -1);
+        let err = unsafe { CStr::from_ptr(infera_last_error()) };
+        assert!(err.to_str().unwrap().contains("pad_batch"));
+    }
+
+    #[test]
+    fn test_infera_model_versions_rollback() {
+        let input: [f32; 3] = [0.0, 0.0, 1.0];
+        let predict = |name: &CString| {
+            let res = unsafe { infera_predict(name.as_ptr(), input.as_ptr(), 1, 3) };
+            assert_eq!(res.status, 0);
+            let value = unsafe { *res.data };
+            unsafe { infera_free_result(res) };
+            value
+        };
+        let paths = [
+            "../test/models/linear.onnx",
+            "../test/models/dynamic_batch_perturbed.onnx",
+            "../test/models/hidden_layer.onnx",
+        ];
+
+        // The expected output of each version, from a model loaded on its own
+        let reference = CString::new("versions_reference").unwrap();
+        let expected: Vec<f32> = paths
+            .iter()
+            .map(|path| {
+                let path = CString::new(*path).unwrap();
+                assert_eq!(
+                    unsafe { infera_load_model(reference.as_ptr(), path.as_ptr()) },
+                    0
+                );
+                predict(&reference)
+            })
+            .collect();
+        unsafe { infera_unload_model(reference.as_ptr()) };
+        assert_ne!(expected[0], expected[1]);
+        assert_ne!(expected[1], expected[2]);
+
+        let name = CString::new("versioned_model").unwrap();
+        let options = CString::new(r#"{"versioning": true}"#).unwrap();
+        for path in paths {
+            let path = CString::new(path).unwrap();
+            let rc = unsafe {
+                infera_load_model_with_options(name.as_ptr(), path.as_ptr(), options.as_ptr())
+            };
+            assert_eq!(rc, 0);
+        }
+        assert_eq!(predict(&name), expected[2]);
+
+        let versions_ptr = unsafe { infera_list_model_versions(name.as_ptr()) };
+        let versions: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(versions_ptr).to_str().unwrap() })
+                .unwrap();
+        unsafe { infera_free_string(versions_ptr) };
+        let versions = versions.as_array().unwrap();
+        assert_eq!(versions.len(), 3);
+        assert_eq!(versions[0]["current"], true);
+        assert_eq!(versions[0]["source"], paths[2]);
+        assert_eq!(versions[2]["index"], 2);
+        assert_eq!(versions[2]["source"], paths[0]);
+        assert!(versions[2]["loaded_at"].as_u64().unwrap() > 0);
+
+        assert_eq!(unsafe { infera_rollback_model(name.as_ptr()) }, 0);
+        assert_eq!(predict(&name), expected[1]);
+        assert_eq!(unsafe { infera_rollback_model(name.as_ptr()) }, 0);
+        assert_eq!(predict(&name), expected[0]);
+        assert_eq!(unsafe { infera_rollback_model(name.as_ptr()) }, -1);
+        let err = unsafe { CStr::from_ptr(infera_last_error()) };
+        assert!(err.to_str().unwrap().contains("no previous version"));
+
+        // Pruning drops the oldest previous versions
+        for path in &paths[1..] {
+            let path = CString::new(*path).unwrap();
+            unsafe {
+                infera_load_model_with_options(name.as_ptr(), path.as_ptr(), options.as_ptr())
+            };
+        }
+        assert_eq!(unsafe { infera_prune_model_versions(name.as_ptr(), 1) }, 1);
+        assert_eq!(unsafe { infera_rollback_model(name.as_ptr()) }, 0);
+        assert_eq!(predict(&name), expected[1]);
+        assert_eq!(unsafe { infera_rollback_model(name.as_ptr()) }, -1);
+
+        unsafe { infera_unload_model(name.as_ptr()) };
+    }
+
+    #[test]
+    fn test_infera_predict_all_with_extra_outputs() {
+        let name = CString::new("hidden_layer_extra").unwrap();
+        let path = CString::new("../test/models/hidden_layer.onnx").unwrap();
+        let options = CString::new(r#"{"extra_outputs": ["hidden"]}"#).unwrap();
+        let rc = unsafe {
+            infera_load_model_with_options(name.as_ptr(), path.as_ptr(), options.as_ptr())
+        };
+        assert_eq!(rc, 0);
+
+        // hidden = X . W1 with W1 = [[1, -1], [2, 0.5], [-1, 1]], Y = relu(hidden) . [1, 2]
+        let input: [f32; 3] = [0.0, 0.0, 1.0];
+        let json_ptr = unsafe { infera_predict_all(name.as_ptr(), input.as_ptr(), 1, 3) };
+        let json_str = unsafe { CStr::from_ptr(json_ptr).to_str().unwrap() };
+        let outputs: serde_json::Value = serde_json::from_str(json_str).unwrap();
+        let outputs = outputs.as_array().unwrap();
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[0]["name"], "Y");
+        assert_eq!(outputs[0]["data"], json!([2.0]));
+        assert_eq!(outputs[1]["name"], "hidden");
+        assert_eq!(outputs[1]["shape"], json!([1, 2]));
+        assert_eq!(outputs[1]["data"], json!([-1.0, 1.0]));
+        unsafe { infera_free_string(json_ptr) };
+
+        // The primary output is unchanged for the regular predict path
+        let res = unsafe { infera_predict(name.as_ptr(), input.as_ptr(), 1, 3) };
+        assert_eq!(res.status, 0);
+        assert_eq!(unsafe { *res.data }, 2.0);
+        unsafe { infera_free_result(res) };
+
+        unsafe { infera_unload_model(name.as_ptr()) };
+    }
+
+    fn json_from_ptr(ptr: *mut c_char) -> serde_json::Value {
+        let value = serde_json::from_str(unsafe { CStr::from_ptr(ptr).to_str().unwrap() });
+        unsafe { infera_free_string(ptr) };
+        value.unwrap()
+    }
+
+    #[test]
+    fn test_infera_get_model_info_reports_tract_build() {
+        let name = CString::new("info_tract_build").unwrap();
+        let path = CString::new("../test/models/linear.onnx").unwrap();
+        assert_eq!(
+            unsafe { infera_load_model(name.as_ptr(), path.as_ptr()) },
+            0
+        );
+
+        let info = json_from_ptr(unsafe { infera_get_model_info(name.as_ptr()) });
+        let version = json_from_ptr(infera_get_version());
+        assert_eq!(
+            info["tract_version"],
+            version["backend_versions"]["tract-onnx"]
+        );
+        assert_eq!(
+            info["tract_core_version"],
+            version["backend_versions"]["tract-core"]
+        );
+        assert_eq!(info["optimized"], true);
+        assert!(info["load_duration_ns"].as_u64().unwrap() > 0);
+
+        unsafe { infera_unload_model(name.as_ptr()) };
+    }
+
+    #[test]
+    fn test_infera_predict_named_uses_graph_names() {
+        let name = CString::new("named_io").unwrap();
+        let path = CString::new("../test/models/named_io.onnx").unwrap();
+        assert_eq!(
+            unsafe { infera_load_model(name.as_ptr(), path.as_ptr()) },
+            0
+        );
+
+        let info = json_from_ptr(unsafe { infera_get_model_info(name.as_ptr()) });
+        assert_eq!(info["input_names"], json!(["float_input"]));
+        assert_eq!(info["output_names"], json!(["variable", "probabilities"]));
+
+        let inputs =
+            CString::new(r#"{"float_input": {"shape": [2, 3], "data": [1, 2, 3, 0, 0, 0]}}"#)
+                .unwrap();
+        let outputs =
+            json_from_ptr(unsafe { infera_predict_named(name.as_ptr(), inputs.as_ptr()) });
+        assert_eq!(outputs["variable"]["shape"], json!([2, 1]));
+        assert_eq!(outputs["variable"]["data"], json!([1.75, 0.25]));
+        let probabilities = outputs["probabilities"]["data"].as_array().unwrap();
+        let sigmoid = |x: f64| 1.0 / (1.0 + (-x).exp());
+        assert!((probabilities[0].as_f64().unwrap() - sigmoid(1.75)).abs() < 1e-5);
+        assert!((probabilities[1].as_f64().unwrap() - sigmoid(0.25)).abs() < 1e-5);
+
+        // Inputs are addressed by their graph names only.
+        let unknown = CString::new(r#"{"X": {"shape": [1, 3], "data": [1, 2, 3]}}"#).unwrap();
+        let err = json_from_ptr(unsafe { infera_predict_named(name.as_ptr(), unknown.as_ptr()) });
+        let message = err["error"].as_str().unwrap();
+        assert!(message.contains("Unknown input 'X'"), "{}", message);
+        assert!(message.contains("float_input"), "{}", message);
+        let missing = CString::new("{}").unwrap();
+        let err = json_from_ptr(unsafe { infera_predict_named(name.as_ptr(), missing.as_ptr()) });
+        assert!(err["error"]
+            .as_str()
+            .unwrap()
+            .contains("missing input 'float_input'"));
+
+        unsafe { infera_unload_model(name.as_ptr()) };
+    }
+
+    #[test]
+    fn test_infera_predict_output_selects_by_name_and_index() {
+        let name = CString::new("select_output").unwrap();
+        let path = CString::new("../test/models/hidden_layer.onnx").unwrap();
+        let options = CString::new(r#"{"extra_outputs": ["hidden"]}"#).unwrap();
+        let rc = unsafe {
+            infera_load_model_with_options(name.as_ptr(), path.as_ptr(), options.as_ptr())
+        };
+        assert_eq!(rc, 0);
+
+        // hidden = X . W1 = [-1, 1] and Y = relu(hidden) . [1, 2] = 2 for X = [0, 0, 1]
+        let input: [f32; 3] = [0.0, 0.0, 1.0];
+        let select = |selector: &str| {
+            let selector = CString::new(selector).unwrap();
+            let res = unsafe {
+                infera_predict_output(name.as_ptr(), input.as_ptr(), 1, 3, selector.as_ptr())
+            };
+            let output = (res.status == 0).then(|| {
+                let values = unsafe { std::slice::from_raw_parts(res.data, res.len) }.to_vec();
+                (values, res.rows, res.cols)
+            });
+            let status = res.status;
+            unsafe { infera_free_result(res) };
+            output.ok_or(status)
+        };
+        assert_eq!(select("Y"), Ok((vec![2.0], 1, 1)));
+        assert_eq!(select("0"), Ok((vec![2.0], 1, 1)));
+        assert_eq!(select("hidden"), Ok((vec![-1.0, 1.0], 1, 2)));
+        assert_eq!(select("1"), Ok((vec![-1.0, 1.0], 1, 2)));
+
+        for unknown in ["boxes", "2"] {
+            assert_eq!(select(unknown), Err(error::InferaStatus::Error as i32));
+            let msg = unsafe { CStr::from_ptr(infera_last_error()) }
+                .to_str()
+                .unwrap();
+            assert!(
+                msg.contains(&format!("Unknown output '{}'", unknown)),
+                "{}",
+                msg
+            );
+            assert!(msg.contains("Y (0), hidden (1)"), "{}", msg);
+        }
+
+        unsafe { infera_unload_model(name.as_ptr()) };
+    }
+
+    #[test]
+    fn test_infera_predict_output_index_selects_each_output() {
+        let name = CString::new("select_output_index").unwrap();
+        let path = CString::new("../test/models/hidden_layer.onnx").unwrap();
+        let options = CString::new(r#"{"extra_outputs": ["hidden"]}"#).unwrap();
+        let rc = unsafe {
+            infera_load_model_with_options(name.as_ptr(), path.as_ptr(), options.as_ptr())
+        };
+        assert_eq!(rc, 0);
+
+        let input: [f32; 3] = [0.0, 0.0, 1.0];
+        let select = |index: usize| {
+            let res =
+                unsafe { infera_predict_output_index(name.as_ptr(), input.as_ptr(), 1, 3, index) };
+            let output = (res.status == 0).then(|| {
+                let values = unsafe { std::slice::from_raw_parts(res.data, res.len) }.to_vec();
+                (values, res.rows, res.cols)
+            });
+            let status = res.status;
+            unsafe { infera_free_result(res) };
+            output.ok_or(status)
+        };
+        assert_eq!(select(0), Ok((vec![2.0], 1, 1)));
+        assert_eq!(select(1), Ok((vec![-1.0, 1.0], 1, 2)));
+
+        assert_eq!(select(2), Err(error::InferaStatus::Error as i32));
+        let msg = unsafe { CStr::from_ptr(infera_last_error()) }
+            .to_str()
+            .unwrap();
+        assert!(msg.contains("Unknown output '2'"), "{}", msg);
+        assert!(msg.contains("Y (0), hidden (1)"), "{}", msg);
+
+        unsafe { infera_unload_model(name.as_ptr()) };
+    }
+
+    #[test]
+    fn test_infera_profile_model_reports_node_times() {
+        let name = CString::new("profiled_linear").unwrap();
+        let path = CString::new("../test/models/linear.onnx").unwrap();
+        assert_eq!(
+            unsafe { infera_load_model(name.as_ptr(), path.as_ptr()) },
+            0
+        );
+
+        let report = json_from_ptr(unsafe { infera_profile_model(name.as_ptr(), 1, 20) });
+        assert_eq!(report["iterations"], 20);
+        let wall = report["wall_time_ns"].as_u64().unwrap();
+        let profiled = report["profiled_time_ns"].as_u64().unwrap();
+        assert!(profiled > 0 && profiled <= wall, "{}", report);
+
+        // The nodes account for the profiled time, as a share of the wall time.
+        let nodes = report["nodes"].as_array().unwrap();
+        assert_eq!(nodes.len() as u64, report["node_count"].as_u64().unwrap());
+        let node_total: u64 = nodes.iter().map(|n| n["total_ns"].as_u64().unwrap()).sum();
+        assert_eq!(node_total, profiled);
+        let percent: f64 = nodes.iter().map(|n| n["percent"].as_f64().unwrap()).sum();
+        let expected = profiled as f64 / wall as f64 * 100.0;
+        assert!((percent - expected).abs() < 1e-6, "{}", report);
+        let op_total: u64 = report["ops"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|op| op["total_ns"].as_u64().unwrap())
+            .sum();
+        assert_eq!(op_total, profiled);
+
+        // The MatMul node (named after its output "Z" in linear.onnx) is listed.
+        assert!(
+            nodes.iter().any(|n| {
+                n["name"].as_str().unwrap().starts_with('Z')
+                    || n["op"].as_str().unwrap().to_lowercase().contains("matmul")
+            }),
+            "{}",
+            report
+        );
+
+        let err = json_from_ptr(unsafe { infera_profile_model(name.as_ptr(), 1, 0) });
+        assert!(err["error"].as_str().unwrap().contains("iterations"));
+        unsafe { infera_unload_model(name.as_ptr()) };
+    }
+
+    #[test]
+    fn test_infera_get_input_and_output_shape() {
+        let name = CString::new("shape_accessors").unwrap();
+        let path = CString::new("../test/models/dynamic_batch.onnx").unwrap();
+        assert_eq!(
+            unsafe { infera_load_model(name.as_ptr(), path.as_ptr()) },
+            0
+        );
+
+        let mut shape = [0i64; 4];
+        let mut len = 0usize;
+        let status =
+            unsafe { infera_get_input_shape(name.as_ptr(), shape.as_mut_ptr(), 4, &mut len) };
+        assert_eq!(status, 0);
+        assert_eq!(&shape[..len], &[-1, 3]);
+        let status =
+            unsafe { infera_get_output_shape(name.as_ptr(), shape.as_mut_ptr(), 4, &mut len) };
+        assert_eq!(status, 0);
+        assert_eq!(&shape[..len], &[-1, 1]);
+
+        // A too-small buffer is an error that still reports the length needed, and is
+        // left untouched.
+        let mut small = [7i64; 1];
+        len = 0;
+        let status =
+            unsafe { infera_get_input_shape(name.as_ptr(), small.as_mut_ptr(), 1, &mut len) };
+        assert_eq!(status, error::InferaStatus::Error as i32);
+        assert_eq!(len, 2);
+        assert_eq!(small, [7]);
+        let msg = unsafe { CStr::from_ptr(infera_last_error()) }
+            .to_str()
+            .unwrap();
+        assert!(msg.contains("too small"), "{}", msg);
+        len = 0;
+        let status =
+            unsafe { infera_get_input_shape(name.as_ptr(), std::ptr::null_mut(), 0, &mut len) };
+        assert_eq!(status, error::InferaStatus::Error as i32);
+        assert_eq!(len, 2);
+
+        unsafe { infera_unload_model(name.as_ptr()) };
+        let status =
+            unsafe { infera_get_input_shape(name.as_ptr(), shape.as_mut_ptr(), 4, &mut len) };
+        assert_eq!(status, error::InferaStatus::ModelNotFound as i32);
+    }
+
+    #[test]
+    fn test_infera_get_output_size() {
+        let dynamic = CString::new("output_size_dynamic").unwrap();
+        let fixed = CString::new("output_size_fixed").unwrap();
+        let padded = CString::new("output_size_padded").unwrap();
+        let dynamic_path = CString::new("../test/models/dynamic_batch.onnx").unwrap();
+        let fixed_path = CString::new("../test/models/linear.onnx").unwrap();
+        let padded_path = CString::new("../test/models/fixed_batch.onnx").unwrap();
+        let options = CString::new(r#"{"pad_batch": true}"#).unwrap();
+        unsafe {
+            assert_eq!(
+                infera_load_model(dynamic.as_ptr(), dynamic_path.as_ptr()),
+                0
+            );
+            assert_eq!(infera_load_model(fixed.as_ptr(), fixed_path.as_ptr()), 0);
+            assert_eq!(
+                infera_load_model_with_options(
+                    padded.as_ptr(),
+                    padded_path.as_ptr(),
+                    options.as_ptr()
+                ),
+                0
+            );
+        }
+
+        // [N,3] -> [N,1]: the batch is substituted into the output's dynamic dimension
+        let info = json_from_ptr(unsafe { infera_get_model_info(dynamic.as_ptr()) });
+        assert_eq!(info["input_batch_dim"], 0);
+        assert_eq!(info["output_batch_dim"], 0);
+        assert_eq!(unsafe { infera_get_output_size(dynamic.as_ptr(), 5) }, 5);
+        assert_eq!(unsafe { infera_get_output_size(dynamic.as_ptr(), 0) }, 0);
+        let data = [1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0];
+        assert_eq!(predict_values(&dynamic, &data, 2, 3).len() as i64, unsafe {
+            infera_get_output_size(dynamic.as_ptr(), 2)
+        });
+
+        // [1,3] -> [1,1]: only the fixed batch size has a known output
+        let info = json_from_ptr(unsafe { infera_get_model_info(fixed.as_ptr()) });
+        assert_eq!(info["input_batch_dim"], -1);
+        assert_eq!(info["output_batch_dim"], -1);
+        assert_eq!(unsafe { infera_get_output_size(fixed.as_ptr(), 1) }, 1);
+        assert_eq!(unsafe { infera_get_output_size(fixed.as_ptr(), 4) }, -1);
+        let msg = unsafe { CStr::from_ptr(infera_last_error()) }
+            .to_str()
+            .unwrap();
+        assert!(msg.contains("does not scale"), "{}", msg);
+
+        // [4,3] -> [4,1] with pad_batch: the output is cut back to the caller's rows
+        assert_eq!(unsafe { infera_get_output_size(padded.as_ptr(), 10) }, 10);
+
+        unsafe {
+            infera_unload_model(dynamic.as_ptr());
+            infera_unload_model(fixed.as_ptr());
+            infera_unload_model(padded.as_ptr());
+        }
+        assert_eq!(unsafe { infera_get_output_size(dynamic.as_ptr(), 5) }, -1);
+    }
+
+    #[test]
+    fn test_infera_check_input_shape() {
+        let name = CString::new("check_input_shape").unwrap();
+        let path = CString::new("../test/models/dynamic_batch.onnx").unwrap();
+        assert_eq!(
+            unsafe { infera_load_model(name.as_ptr(), path.as_ptr()) },
+            0
+        );
+        let check = |shape: &[i64]| unsafe {
+            infera_check_input_shape(name.as_ptr(), shape.as_ptr(), shape.len())
+        };
+        let last_error = || {
+            unsafe { CStr::from_ptr(infera_last_error()) }
+                .to_string_lossy()
+                .into_owned()
+        };
+
+        // [N,3]: any batch size with 3 columns
+        assert_eq!(check(&[1, 3]), 0);
+        assert_eq!(check(&[4096, 3]), 0);
+
+        assert_eq!(check(&[4096]), -1);
+        assert!(
+            last_error().contains("expected [-1, 3], got [4096]"),
+            "{}",
+            last_error()
+        );
+        assert_eq!(check(&[2, 3, 1]), -1);
+        assert_eq!(check(&[2, 4]), -1);
+        assert!(
+            last_error().contains("expected [-1, 3], got [2, 4]"),
+            "{}",
+            last_error()
+        );
+        assert_eq!(check(&[-1, 3]), -1);
+        assert_eq!(
+            unsafe { infera_check_input_shape(name.as_ptr(), std::ptr::null(), 0) },
+            -1
+        );
+
+        unsafe { infera_unload_model(name.as_ptr()) };
+        assert_eq!(check(&[1, 3]), -1);
+    }
+
+    #[test]
+    fn test_infera_predict_model_without_inputs() {
+        let name = CString::new("no_input").unwrap();
+        let path = CString::new("../test/models/no_input.onnx").unwrap();
+        assert_eq!(
+            unsafe { infera_load_model(name.as_ptr(), path.as_ptr()) },
+            0
+        );
+
+        let info = json_from_ptr(unsafe { infera_get_model_info(name.as_ptr()) });
+        assert_eq!(info["input_names"], json!([]));
+        assert_eq!(info["input_shape"], json!([]));
+        assert_eq!(info["output_shape"], json!([1, 4]));
+
+        // The model runs with a null data pointer and rows = cols = 0.
+        let res = unsafe { infera_predict(name.as_ptr(), std::ptr::null(), 0, 0) };
+        assert_eq!(res.status, 0, "prediction failed: {:?}", unsafe {
+            CStr::from_ptr(infera_last_error())
+        });
+        assert_eq!((res.rows, res.cols), (1, 4));
+        let values = unsafe { std::slice::from_raw_parts(res.data, res.len) }.to_vec();
+        unsafe { infera_free_result(res) };
+        assert_eq!(values, [1.0, 0.5, 0.25, 0.125]);
+
+        // Passing input data to a model without inputs is a shape error.
+        let input = [1.0f32, 2.0, 3.0];
+        let res = unsafe { infera_predict(name.as_ptr(), input.as_ptr(), 1, 3) };
+        assert_eq!(res.status, error::InferaStatus::InvalidShape as i32);
+        unsafe { infera_free_result(res) };
+
+        unsafe { infera_unload_model(name.as_ptr()) };
+    }
+
+    #[test]
+    fn test_infera_get_output_names() {
+        let name = CString::new("output_names_hidden").unwrap();
+        let path = CString::new("../test/models/hidden_layer.onnx").unwrap();
+        let options = CString::new(r#"{"extra_outputs": ["hidden", "activation"]}"#).unwrap();
+        let rc = unsafe {
+            infera_load_model_with_options(name.as_ptr(), path.as_ptr(), options.as_ptr())
+        };
+        assert_eq!(rc, 0);
+
+        let names_ptr = unsafe { infera_get_output_names(name.as_ptr()) };
+        let names: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(names_ptr).to_str().unwrap() }).unwrap();
+        unsafe { infera_free_string(names_ptr) };
+        assert_eq!(names, json!(["Y", "hidden", "activation"]));
+
+        // The names label the entries of infera_predict_all in the same order
+        let input: [f32; 3] = [0.0, 0.0, 1.0];
+        let json_ptr = unsafe { infera_predict_all(name.as_ptr(), input.as_ptr(), 1, 3) };
+        let outputs: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(json_ptr).to_str().unwrap() }).unwrap();
+        unsafe { infera_free_string(json_ptr) };
+        let labels: Vec<&serde_json::Value> = outputs
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| &o["name"])
+            .collect();
+        assert_eq!(json!(labels), names);
+        unsafe { infera_unload_model(name.as_ptr()) };
+
+        let missing = CString::new("output_names_missing").unwrap();
+        let err_ptr = unsafe { infera_get_output_names(missing.as_ptr()) };
+        let err: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(err_ptr).to_str().unwrap() }).unwrap();
+        unsafe { infera_free_string(err_ptr) };
+        assert!(err["error"].is_string());
+    }
+
+    #[test]
+    fn test_infera_predict_many_json() {
+        let name = CString::new("many_json_dynamic").unwrap();
+        let path = CString::new("../test/models/dynamic_batch.onnx").unwrap();
+        assert_eq!(
+            unsafe { infera_load_model(name.as_ptr(), path.as_ptr()) },
+            0
+        );
+
+        let inputs = CString::new(
+            r#"[
+                {"shape": [1, 3], "data": [1, 2, 3]},
+                {"shape": [2, 3], "data": [1, 2, 3, 4, 5, 6]},
+                {"shape": [1, 4], "data": [1, 2, 3, 4]}
+            ]"#,
+        )
+        .unwrap();
+        let json_ptr = unsafe { infera_predict_many_json(name.as_ptr(), inputs.as_ptr()) };
+        let json_str = unsafe { CStr::from_ptr(json_ptr).to_str().unwrap() };
+        let outputs: serde_json::Value = serde_json::from_str(json_str).unwrap();
+        let outputs = outputs.as_array().unwrap();
+        assert_eq!(outputs.len(), 3);
+
+        // Each result matches a regular prediction on the same rows
+        let rows: [f32; 6] = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let res = unsafe { infera_predict(name.as_ptr(), rows.as_ptr(), 2, 3) };
+        assert_eq!(res.status, 0);
+        let expected = unsafe { std::slice::from_raw_parts(res.data, res.len) }.to_vec();
+        unsafe { infera_free_result(res) };
+        assert_eq!(outputs[0]["shape"], json!([1, 1]));
+        assert_eq!(outputs[0]["data"], json!([expected[0]]));
+        assert_eq!(outputs[1]["shape"], json!([2, 1]));
+        assert_eq!(outputs[1]["data"], json!(expected));
+
+        // A shape mismatch fails only its own item
+        let error = outputs[2]["error"].as_str().unwrap();
+        assert!(error.contains("[-1, 3]"), "{}", error);
+        assert!(outputs[2].get("data").is_none());
+        unsafe { infera_free_string(json_ptr) };
+
+        let not_array = CString::new(r#"{"shape": [1, 3]}"#).unwrap();
+        let json_ptr = unsafe { infera_predict_many_json(name.as_ptr(), not_array.as_ptr()) };
+        let json_str = unsafe { CStr::from_ptr(json_ptr).to_str().unwrap() };
+        assert!(json_str.contains("\"error\""));
+        unsafe { infera_free_string(json_ptr) };
+
+        unsafe { infera_unload_model(name.as_ptr()) };
+    }
+
+    #[test]
+    fn test_infera_load_model_with_unknown_extra_output() {
+        let name = CString::new("hidden_layer_unknown").unwrap();
+        let path = CString::new("../test/models/hidden_layer.onnx").unwrap();
+        let options = CString::new(r#"{"extra_outputs": ["missing"]}"#).unwrap();
+        let rc = unsafe {
+            infera_load_model_with_options(name.as_ptr(), path.as_ptr(), options.as_ptr())
+        };
+        assert_eq!(rc, -1);
+        let err = unsafe { CStr::from_ptr(infera_last_error()) }
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("Unknown node 'missing'"));
+        assert!(err.contains("hidden"));
+        assert!(err.contains("activation"));
+    }
+
+    fn compare_report(name_a: &CString, name_b: &CString, options: &str) -> serde_json::Value {
+        let options = CString::new(options).unwrap();
+        let ptr =
+            unsafe { infera_compare_models(name_a.as_ptr(), name_b.as_ptr(), options.as_ptr()) };
+        let report = unsafe { CStr::from_ptr(ptr).to_str().unwrap() };
+        let value = serde_json::from_str(report).unwrap();
+        unsafe { infera_free_string(ptr) };
+        value
+    }
+
+    #[test]
+    fn test_infera_compare_models() {
+        let base = CString::new("compare_base").unwrap();
+        let perturbed = CString::new("compare_perturbed").unwrap();
+        let linear = CString::new("compare_linear").unwrap();
+        let base_path = CString::new("../test/models/dynamic_batch.onnx").unwrap();
+        let perturbed_path = CString::new("../test/models/dynamic_batch_perturbed.onnx").unwrap();
+        let multi_path = CString::new("../test/models/multi_output.onnx").unwrap();
+        unsafe {
+            assert_eq!(infera_load_model(base.as_ptr(), base_path.as_ptr()), 0);
+            assert_eq!(
+                infera_load_model(perturbed.as_ptr(), perturbed_path.as_ptr()),
+                0
+            );
+            assert_eq!(infera_load_model(linear.as_ptr(), multi_path.as_ptr()), 0);
+        }
+
+        // A model compared against itself has no differences
+        let report = compare_report(&base, &base, r#"{"samples": 50, "seed": 1}"#);
+        assert_eq!(report["rows"], 50);
+        assert_eq!(report["max_abs_diff"], 0.0);
+        assert_eq!(report["mean_rel_diff"], 0.0);
+        assert_eq!(report["pass"], true);
+
+        // The perturbed copy adds 0.1 to the bias, so every output differs by 0.1
+        let report = compare_report(
+            &base,
+            &perturbed,
+            r#"{"inputs": [[1, 2, 3], [0, 0, 0]], "atol": 0.01}"#,
+        );
+        assert_eq!(report["rows"], 2);
+        let max_abs = report["max_abs_diff"].as_f64().unwrap();
+        let mean_abs = report["mean_abs_diff"].as_f64().unwrap();
+        assert!((max_abs - 0.1).abs() < 1e-6);
+        assert!((mean_abs - 0.1).abs() < 1e-6);
+        // Outputs are 1.75 vs 1.85 and 0.25 vs 0.35
+        let max_rel = report["max_rel_diff"].as_f64().unwrap();
+        assert!((max_rel - 0.1 / 0.35).abs() < 1e-5);
+        assert_eq!(report["argmax_mismatch_fraction"], 0.0);
+        assert_eq!(report["pass"], false);
+
+        let report = compare_report(&base, &perturbed, r#"{"atol": 0.2}"#);
+        assert_eq!(report["pass"], true);
+
+        // Models with different input shapes cannot be compared
+        let report = compare_report(&base, &linear, "");
+        assert!(report["error"]
+            .as_str()
+            .unwrap()
+            .contains("Models are not compatible"));
+
+        unsafe {
+            infera_unload_model(base.as_ptr());
+            infera_unload_model(perturbed.as_ptr());
+            infera_unload_model(linear.as_ptr());
+        }
+    }
+
+    #[test]
+    fn test_infera_predict_with_symbols() {
+        let name = CString::new("symbols_model").unwrap();
+        let path = CString::new("../test/models/symbolic_sequence.onnx").unwrap();
+        assert_eq!(
+            unsafe { infera_load_model(name.as_ptr(), path.as_ptr()) },
+            0
+        );
+
+        let info_ptr = unsafe { infera_get_model_info(name.as_ptr()) };
+        let info: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(info_ptr) }.to_str().unwrap()).unwrap();
+        unsafe { infera_free_string(info_ptr) };
+        assert_eq!(info["input_symbols"], json!(["N", "S", null]));
+
+        // One sequence of length 2 with 2 features; the model sums over the sequence
+        let input = [1.0f32, 2.0, 3.0, 4.0];
+        let symbols = CString::new(r#"{"S": 2}"#).unwrap();
+        let res = unsafe {
+            infera_predict_with_symbols(name.as_ptr(), input.as_ptr(), 1, 4, symbols.as_ptr())
+        };
+        assert_eq!(res.status, 0);
+        let output = unsafe { std::slice::from_raw_parts(res.data, res.len) }.to_vec();
+        unsafe { infera_free_result(res) };
+        assert_eq!(output, vec![4.0, 6.0]);
+
+        let res = unsafe {
+            infera_predict_with_symbols(name.as_ptr(), input.as_ptr(), 1, 4, std::ptr::null())
+        };
+        assert_eq!(res.status, -3);
+        unsafe { infera_free_result(res) };
+
+        let res = unsafe {
+            infera_predict_with_symbols(name.as_ptr(), std::ptr::null(), 1, 4, symbols.as_ptr())
+        };
+        assert_eq!(res.status, -1);
+        unsafe {
+            infera_free_result(res);
+            infera_unload_model(name.as_ptr());
+        }
+    }
+
+    #[test]
+    fn test_infera_predict_image_errors() {
+        let name = CString::new("image_errors_model").unwrap();
+        let layout = CString::new("chw").unwrap();
+        let bytes = b"not an image";
+        unsafe {
+            let res = infera_predict_image(
+                name.as_ptr(),
+                std::ptr::null(),
+                0,
+                8,
+                8,
+                layout.as_ptr(),
+                std::ptr::null(),
+            );
+            assert_eq!(res.status, -1);
+            assert!(res.data.is_null());
+
+            let res = infera_predict_image(
+                name.as_ptr(),
+                bytes.as_ptr(),
+                bytes.len(),
+                8,
+                8,
+                layout.as_ptr(),
+                std::ptr::null(),
+            );
+            assert_eq!(res.status, -1);
+            let error = CStr::from_ptr(infera_last_error()).to_str().unwrap();
+            if cfg!(feature = "image") {
+                assert!(error.contains("Failed to decode image"));
+            } else {
+                assert!(error.contains("Feature not enabled"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_infera_predict_image_with_format_raw_rgb() {
+        let name = CString::new("image_raw_rgb_model").unwrap();
+        let path = CString::new("../test/models/image_4d.onnx").unwrap();
+        let rgb = CString::new("rgb").unwrap();
+        let bmp = CString::new("bmp").unwrap();
+        let layout = CString::new("chw").unwrap();
+        // An 8x8 image filled with the color (255, 51, 0)
+        let pixels: Vec<u8> = [255u8, 51, 0].repeat(64);
+        unsafe {
+            let res = infera_predict_image_with_format(
+                name.as_ptr(),
+                pixels.as_ptr(),
+                pixels.len(),
+                bmp.as_ptr(),
+                8,
+                8,
+                8,
+                8,
+                layout.as_ptr(),
+                std::ptr::null(),
+            );
+            assert_eq!(res.status, -1);
+            let error = CStr::from_ptr(infera_last_error()).to_str().unwrap();
+            assert!(error.contains("unknown image format"));
+
+            assert_eq!(infera_load_model(name.as_ptr(), path.as_ptr()), 0);
+            let res = infera_predict_image_with_format(
+                name.as_ptr(),
+                pixels.as_ptr(),
+                pixels.len(),
+                rgb.as_ptr(),
+                8,
+                8,
+                8,
+                8,
+                layout.as_ptr(),
+                std::ptr::null(),
+            );
+            if cfg!(feature = "image") {
+                assert_eq!(res.status, 0);
+                let output = std::slice::from_raw_parts(res.data, res.len);
+                for (actual, expected) in output.iter().zip([1.0, 0.2, 0.0]) {
+                    assert!((actual - expected).abs() < 1e-6);
+                }
+            } else {
+                assert_eq!(res.status, -1);
+            }
+            infera_free_result(res);
+
+            // The raw pixels must match the given source size
+            let res = infera_predict_image_with_format(
+                name.as_ptr(),
+                pixels.as_ptr(),
+                pixels.len(),
+                rgb.as_ptr(),
+                4,
+                4,
+                8,
+                8,
+                layout.as_ptr(),
+                std::ptr::null(),
+            );
+            assert_eq!(res.status, -1);
+            infera_unload_model(name.as_ptr());
+        }
+    }
+
+    #[test]
+    fn test_infera_predict_ensemble() {
+        let base = CString::new("ensemble_base").unwrap();
+        let perturbed = CString::new("ensemble_perturbed").unwrap();
+        let base_path = CString::new("../test/models/dynamic_batch.onnx").unwrap();
+        let perturbed_path = CString::new("../test/models/dynamic_batch_perturbed.onnx").unwrap();
+        unsafe {
+            assert_eq!(infera_load_model(base.as_ptr(), base_path.as_ptr()), 0);
+            assert_eq!(
+                infera_load_model(perturbed.as_ptr(), perturbed_path.as_ptr()),
+                0
+            );
+        }
+        let input = [1.0f32, 2.0, 3.0, 0.0, 0.0, 0.0];
+        let single = predict_values(&base, &input, 2, 3);
+
+        let ensemble = |names: &str, weights: Option<&str>| {
+            let names = CString::new(names).unwrap();
+            let weights = weights.map(|w| CString::new(w).unwrap());
+            unsafe {
+                infera_predict_ensemble(
+                    names.as_ptr(),
+                    input.as_ptr(),
+                    2,
+                    3,
+                    weights.as_ref().map_or(std::ptr::null(), |w| w.as_ptr()),
+                )
+            }
+        };
+
+        // A model ensembled with itself gives the same output as a single run
+        let res = ensemble(r#"["ensemble_base", "ensemble_base"]"#, None);
+        assert_eq!(res.status, 0);
+        assert_eq!((res.rows, res.cols), (2, 1));
+        let values = unsafe { std::slice::from_raw_parts(res.data, res.len) }.to_vec();
+        unsafe { infera_free_result(res) };
+        assert_eq!(values.len(), single.len());
+        for (v, s) in values.iter().zip(&single) {
+            assert!((v - s).abs() < 1e-5);
+        }
+
+        // The perturbed copy adds 0.1 to every output, so a 1:3 weighting adds 0.075
+        let res = ensemble(r#"["ensemble_base", "ensemble_perturbed"]"#, Some("[1, 3]"));
+        assert_eq!(res.status, 0);
+        let values = unsafe { std::slice::from_raw_parts(res.data, res.len) }.to_vec();
+        unsafe { infera_free_result(res) };
+        for (v, s) in values.iter().zip(&single) {
+            assert!((v - (s + 0.075)).abs() < 1e-5);
+        }
+
+        let res = ensemble(r#"["ensemble_base", "ensemble_missing"]"#, None);
+        assert_eq!(res.status, -2);
+        unsafe { infera_free_result(res) };
+
+        let res = ensemble(r#"["ensemble_base"]"#, Some("[1, 2]"));
+        assert_eq!(res.status, -1);
+        unsafe { infera_free_result(res) };
+
+        unsafe {
+            infera_unload_model(base.as_ptr());
+            infera_unload_model(perturbed.as_ptr());
+        }
+    }
+
+    #[test]
+    fn test_infera_predict_u8_quantized_model() {
+        let name = CString::new("quantized_u8").unwrap();
+        let path = CString::new("../test/models/quantized_u8.onnx").unwrap();
+        assert_eq!(
+            unsafe { infera_load_model(name.as_ptr(), path.as_ptr()) },
+            0
+        );
+
+        // The model dequantizes with scale 0.5 and zero point 128, and its logits are the
+        // first three features, so each row's class is the index of its largest feature.
+        let input: Vec<u8> = vec![200, 10, 10, 0, 0, 0, 255, 0, 128, 250, 128, 255];
+        let argmax = |values: &[f32]| -> Vec<usize> {
+            values
+                .chunks(3)
+                .map(|row| {
+                    (0..row.len())
+                        .max_by(|&a, &b| row[a].total_cmp(&row[b]))
+                        .unwrap()
+                })
+                .collect()
+        };
+
+        let res = unsafe { infera_predict_u8(name.as_ptr(), input.as_ptr(), 3, 4) };
+        assert_eq!(res.status, 0, "prediction failed: {:?}", unsafe {
+            CStr::from_ptr(infera_last_error())
+        });
+        assert_eq!((res.rows, res.cols), (3, 3));
+        let values = unsafe { std::slice::from_raw_parts(res.data, res.len) }.to_vec();
+        unsafe { infera_free_result(res) };
+        assert_eq!(&values[..3], &[36.0, -59.0, -59.0]);
+        assert_eq!(argmax(&values), vec![0, 2, 1]);
+
+        // The blob entry point reads one byte per element for u8 models.
+        let res = unsafe { infera_predict_from_blob(name.as_ptr(), input.as_ptr(), input.len()) };
+        assert_eq!(res.status, 0);
+        let blob_values = unsafe { std::slice::from_raw_parts(res.data, res.len) }.to_vec();
+        unsafe { infera_free_result(res) };
+        assert_eq!(blob_values, values);
+
+        // The f32 entry point points callers at the byte entry points.
+        let float_input: Vec<f32> = input.iter().map(|&b| b as f32).collect();
+        let res = unsafe { infera_predict(name.as_ptr(), float_input.as_ptr(), 3, 4) };
+        assert_eq!(res.status, -1);
+        let err = unsafe { CStr::from_ptr(infera_last_error()) }
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert!(
+            err.contains("infera_predict_u8"),
+            "unexpected error: {}",
+            err
+        );
+
+        unsafe { infera_unload_model(name.as_ptr()) };
+    }
+
+    #[test]
+    fn test_infera_load_model_binds_initializers() {
+        let name = CString::new("weights_as_inputs").unwrap();
+        let path = CString::new("../test/models/weights_as_inputs.onnx").unwrap();
+        let load = |options: &str| {
+            let options = CString::new(options).unwrap();
+            unsafe {
+                infera_load_model_with_options(name.as_ptr(), path.as_ptr(), options.as_ptr())
+            }
+        };
+
+        // The weights W are a required input, and the bias B defaults to its initializer
+        assert_eq!(load("{}"), 0);
+        let info = json_from_ptr(unsafe { infera_get_model_info(name.as_ptr()) });
+        assert_eq!(info["input_names"], serde_json::json!(["X", "W"]));
+        assert_eq!(info["defaulted_inputs"], serde_json::json!(["B"]));
+
+        // Binding W leaves X as the only input, so the model runs like linear.onnx
+        assert_eq!(
+            load(r#"{"initializers": {"W": [2.0, -1.0, 0.5]}}"#),
+            0,
+            "{:?}",
+            unsafe { CStr::from_ptr(infera_last_error()) }
+        );
+        let info = json_from_ptr(unsafe { infera_get_model_info(name.as_ptr()) });
+        assert_eq!(info["input_names"], serde_json::json!(["X"]));
+        assert_eq!(info["defaulted_inputs"], serde_json::json!(["B", "W"]));
+        let input = [1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let values = predict_values(&name, &input, 2, 3);
+        assert_eq!(
+            values,
+            vec![linear_expected(&input[..3]), linear_expected(&input[3..])]
+        );
+        assert_eq!(
+            load(r#"{"initializers": {"W": {"shape": [3, 1], "values": [2.0, -1.0, 0.5]}}}"#),
+            0
+        );
+        assert_eq!(predict_values(&name, &input, 2, 3), values);
+
+        let last_error = || {
+            unsafe { CStr::from_ptr(infera_last_error()) }
+                .to_str()
+                .unwrap()
+                .to_string()
+        };
+        // Inputs that already have an initializer, or are not inputs, cannot be bound
+        assert_eq!(load(r#"{"initializers": {"B": [1.0]}}"#), -1);
+        assert!(last_error().contains("Unknown input 'B'. Model inputs: X, W"));
+        assert_eq!(load(r#"{"initializers": {"W": [2.0, -1.0]}}"#), -1);
+        assert!(
+            last_error().contains("Invalid initializer for input 'W': shape [3, 1] holds 3"),
+            "{}",
+            last_error()
+        );
+        assert_eq!(
+            load(r#"{"initializers": {"W": {"shape": [1, 3], "values": [2.0, -1.0, 0.5]}}}"#),
+            -1
+        );
+        assert!(last_error().contains("does not match the declared shape [3, 1]"));
+
+        unsafe { infera_unload_model(name.as_ptr()) };
+    }
+
+    #[test]
+    fn test_infera_predict_rejects_f32_for_int64_input() {
+        let name = CString::new("int64_input").unwrap();
+        let path = CString::new("../test/models/int64_input.onnx").unwrap();
+        assert_eq!(
+            unsafe { infera_load_model(name.as_ptr(), path.as_ptr()) },
+            0
+        );
+        let info = json_from_ptr(unsafe { infera_get_model_info(name.as_ptr()) });
+        assert_eq!(info["input_dtype"], "i64");
+        assert_eq!(info["input_dtypes"], serde_json::json!(["i64"]));
+
+        // The f32 and blob entry points fail before building a tensor, naming the input
+        // and what to do instead of reporting a backend error.
+        let input = [1.0f32, 2.0, 3.0, 4.0];
+        let res = unsafe { infera_predict(name.as_ptr(), input.as_ptr(), 1, 4) };
+        assert_eq!(res.status, -1);
+        let err = unsafe { CStr::from_ptr(infera_last_error()) }
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert!(
+            err.starts_with(
+                "Model input 'input_ids' expects i64 values but was given f32; use infera_predict_u8"
+            ),
+            "unexpected error: {}",
+            err
+        );
+        let blob: Vec<u8> = input.iter().flat_map(|v| v.to_ne_bytes()).collect();
+        let res = unsafe { infera_predict_from_blob(name.as_ptr(), blob.as_ptr(), blob.len()) };
+        assert_eq!(res.status, -1);
+        let err = unsafe { CStr::from_ptr(infera_last_error()) }
+            .to_str()
+            .unwrap();
+        assert!(
+            err.contains("expects i64 values but was given f32"),
+            "{}",
+            err
+        );
+        let symbols = CString::new("{}").unwrap();
+        let res = unsafe {
+            infera_predict_with_symbols(name.as_ptr(), input.as_ptr(), 1, 4, symbols.as_ptr())
+        };
+        assert_eq!(res.status, -1);
+        let err = unsafe { CStr::from_ptr(infera_last_error()) }
+            .to_str()
+            .unwrap();
+        assert!(
+            err.contains("expects i64 values but was given f32"),
+            "{}",
+            err
+        );
+
+        // Token ids below 256 can be fed as bytes, which are widened to i64.
+        let ids: Vec<u8> = vec![1, 2, 3, 250];
+        let res = unsafe { infera_predict_u8(name.as_ptr(), ids.as_ptr(), 1, 4) };
+        assert_eq!(res.status, 0);
+        let values = unsafe { std::slice::from_raw_parts(res.data, res.len) }.to_vec();
+        unsafe { infera_free_result(res) };
+        assert_eq!(values, vec![1.0, 2.0, 3.0, 250.0]);
+
+        unsafe { infera_unload_model(name.as_ptr()) };
+    }
+
+    #[cfg(feature = "tract")]
+    fn loaded_models_ns(namespace: &str) -> serde_json::Value {
+        let namespace = CString::new(namespace).unwrap();
+        let ptr = unsafe { infera_get_loaded_models_ns(namespace.as_ptr()) };
+        let value = serde_json::from_str(unsafe { CStr::from_ptr(ptr).to_str().unwrap() }).unwrap();
+        unsafe { infera_free_string(ptr) };
+        value
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_infera_namespaces_isolate_models() {
+        let ns_a = CString::new("ns_test_tenant_a").unwrap();
+        let ns_b = CString::new("ns_test_tenant_b").unwrap();
+        let name = CString::new("shared").unwrap();
+        let path = CString::new("../test/models/linear.onnx").unwrap();
+        for ns in [&ns_a, &ns_b] {
+            let rc = unsafe { infera_load_model_ns(ns.as_ptr(), name.as_ptr(), path.as_ptr()) };
+            assert_eq!(rc, 0);
+        }
+
+        // Listing: each namespace sees only its own model, and the root namespace sees neither.
+        assert_eq!(loaded_models_ns("ns_test_tenant_a"), json!(["shared"]));
+        assert_eq!(loaded_models_ns("ns_test_tenant_b"), json!(["shared"]));
+        let root_ptr = infera_get_loaded_models();
+        let root = unsafe { CStr::from_ptr(root_ptr).to_str().unwrap() }.to_string();
+        unsafe { infera_free_string(root_ptr) };
+        assert!(!root.contains("\"shared\""));
+        assert!(loaded_models_ns("a/b")["error"].is_string());
+
+        // Info and predict: a bare name resolves through the thread's default namespace.
+        assert_eq!(unsafe { infera_set_default_namespace(ns_a.as_ptr()) }, 0);
+        let info_ptr = unsafe { infera_get_model_info(name.as_ptr()) };
+        let info: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(info_ptr).to_str().unwrap() }).unwrap();
+        unsafe { infera_free_string(info_ptr) };
+        assert_eq!(info["name"], "ns_test_tenant_a/shared");
+        let row = [1.0f32, 2.0, 3.0];
+        let values = predict_values(&name, &row, 1, 3);
+        assert!((values[0] - linear_expected(&row)).abs() < 1e-5);
+        assert_eq!(unsafe { infera_set_default_namespace(std::ptr::null()) }, 0);
+
+        let missing = unsafe { infera_predict(name.as_ptr(), row.as_ptr(), 1, 3) };
+        assert_eq!(
+            missing.status, -2,
+            "the root namespace has no 'shared' model"
+        );
+        unsafe { infera_free_result(missing) };
+
+        // Unload: removing one namespace leaves the other untouched, and takes the
+        // previous versions, limits, and aliases of the namespace with it.
+        let qualified_a = CString::new("ns_test_tenant_a/shared").unwrap();
+        let qualified_b = CString::new("ns_test_tenant_b/shared").unwrap();
+        let alias_a = CString::new("ns_test_tenant_a/alias").unwrap();
+        let versioning = CString::new(r#"{"versioning": true}"#).unwrap();
+        unsafe {
+            assert_eq!(
+                infera_load_model_with_options(
+                    qualified_a.as_ptr(),
+                    path.as_ptr(),
+                    versioning.as_ptr()
+                ),
+                0
+            );
+            assert_eq!(infera_set_model_concurrency(qualified_a.as_ptr(), 2), 0);
+            assert_eq!(
+                infera_set_canary(
+                    alias_a.as_ptr(),
+                    qualified_a.as_ptr(),
+                    qualified_b.as_ptr(),
+                    0.5,
+                    4
+                ),
+                0
+            );
+        }
+        let versions = model::list_versions("ns_test_tenant_a/shared").unwrap();
+        assert_eq!(versions.as_array().unwrap().len(), 2);
+        assert_eq!(unsafe { infera_unload_namespace(ns_a.as_ptr()) }, 1);
+        assert!(model::list_versions("ns_test_tenant_a/shared").is_err());
+        assert_eq!(concurrency::limit("ns_test_tenant_a/shared"), 0);
+        assert!(canary_status(&alias_a)["error"].is_string());
+        assert_eq!(loaded_models_ns("ns_test_tenant_a"), json!([]));
+        let gone = unsafe { infera_predict(qualified_a.as_ptr(), row.as_ptr(), 1, 3) };
+        assert_eq!(gone.status, -2);
+        unsafe { infera_free_result(gone) };
+        let values = predict_values(&qualified_b, &row, 1, 3);
+        assert!((values[0] - linear_expected(&row)).abs() < 1e-5);
+
+        assert_eq!(unsafe { infera_unload_model(qualified_b.as_ptr()) }, 0);
+        assert_eq!(loaded_models_ns("ns_test_tenant_b"), json!([]));
+    }
+
+    fn model_info_name(name: &CString) -> serde_json::Value {
+        let info_ptr = unsafe { infera_get_model_info(name.as_ptr()) };
+        let info: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(info_ptr).to_str().unwrap() }).unwrap();
+        unsafe { infera_free_string(info_ptr) };
+        info["name"].clone()
+    }
+
+    fn canary_status(alias: &CString) -> serde_json::Value {
+        let ptr = unsafe { infera_get_canary_status(alias.as_ptr()) };
+        let status = serde_json::from_str(unsafe { CStr::from_ptr(ptr).to_str().unwrap() });
+        unsafe { infera_free_string(ptr) };
+        status.unwrap()
+    }
+
+    #[cfg(feature = "tract")]
+    #[test]
+    fn test_infera_set_model_concurrency() {
+        let name = CString::new("concurrency_model").unwrap();
+        let path = CString::new("../test/models/linear.onnx").unwrap();
+        let missing = CString::new("concurrency_missing").unwrap();
+        unsafe {
+            assert_eq!(infera_set_model_concurrency(std::ptr::null(), 1), -1);
+            assert_eq!(infera_set_model_concurrency(missing.as_ptr(), 1), -1);
+            assert_eq!(infera_load_model(name.as_ptr(), path.as_ptr()), 0);
+            assert_eq!(infera_set_model_concurrency(name.as_ptr(), 2), 0);
+        }
+        let info_ptr = unsafe { infera_get_model_info(name.as_ptr()) };
+        let info: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(info_ptr).to_str().unwrap() }).unwrap();
+        unsafe { infera_free_string(info_ptr) };
+        assert_eq!(info["max_concurrency"], 2);
+
+        // More threads than permits all get their predictions
+        let handles: Vec<_> = (0..6)
+            .map(|_| {
+                std::thread::spawn(|| {
+                    let name = CString::new("concurrency_model").unwrap();
+                    let input = [1.0f32, 2.0, 3.0];
+                    let res = unsafe { infera_predict(name.as_ptr(), input.as_ptr(), 1, 3) };
+                    let value = (res.status == 0).then(|| unsafe { *res.data });
+                    unsafe { infera_free_result(res) };
+                    value
+                })
+            })
+            .collect();
+        for handle in handles {
+            let value = handle.join().unwrap().unwrap();
+            assert!((value - 1.75).abs() < 1e-6);
+        }
+
+        // Predictions other than `infera_predict` wait for a permit too
+        let held = {
+            unsafe { assert_eq!(infera_set_model_concurrency(name.as_ptr(), 1), 0) };
+            concurrency::acquire("concurrency_model").unwrap()
+        };
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        let waiter = std::thread::spawn(move || {
+            let name = CString::new("concurrency_model").unwrap();
+            let input = [1.0f32, 2.0, 3.0];
+            let ptr = unsafe { infera_predict_all(name.as_ptr(), input.as_ptr(), 1, 3) };
+            done_tx.send(()).unwrap();
+            unsafe { infera_free_string(ptr) };
+        });
+        let timeout = std::time::Duration::from_millis(50);
+        assert!(done_rx.recv_timeout(timeout).is_err());
+        drop(held);
+        done_rx.recv().unwrap();
+        waiter.join().unwrap();
+
+        // Unloading the model removes its limit
+        unsafe { assert_eq!(infera_unload_model(name.as_ptr()), 0) };
+        assert_eq!(concurrency::limit("concurrency_model"), 0);
+    }
+
+    fn model_signature(name: &CString) -> String {
+        let ptr = unsafe { infera_get_model_signature(name.as_ptr()) };
+        let hash = unsafe { CStr::from_ptr(ptr).to_str().unwrap().to_string() };
+        unsafe { infera_free_string(ptr) };
+        hash
+    }
+
+    #[test]
+    fn test_infera_get_model_signature() {
+        use sha2::{Digest, Sha256};
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("signed.onnx");
+        let bytes = fs::read("../test/models/linear.onnx").unwrap();
+        fs::write(&path, &bytes).unwrap();
+        let path_c = CString::new(path.to_str().unwrap()).unwrap();
+        let first = CString::new("signature_hash_first").unwrap();
+        let second = CString::new("signature_hash_second").unwrap();
+        unsafe {
+            assert_eq!(infera_load_model(first.as_ptr(), path_c.as_ptr()), 0);
+            assert_eq!(infera_load_model(second.as_ptr(), path_c.as_ptr()), 0);
+        }
+
+        // Two loads of the same file have the same signature, the file's SHA-256
+        let signature = model_signature(&first);
+        assert_eq!(signature, hex::encode(Sha256::digest(&bytes)));
+        assert_eq!(model_signature(&second), signature);
+
+        // Reloading after the file changed gives a different signature
+        fs::copy("../test/models/dynamic_batch.onnx", &path).unwrap();
+        assert_eq!(
+            unsafe { infera_load_model(second.as_ptr(), path_c.as_ptr()) },
+            0
+        );
+        assert_ne!(model_signature(&second), signature);
+        assert_eq!(model_signature(&first), signature);
+
+        let missing = CString::new("signature_hash_missing").unwrap();
+        assert!(model_signature(&missing).starts_with("{\"error\""));
+        unsafe {
+            assert_eq!(infera_unload_model(first.as_ptr()), 0);
+            assert_eq!(infera_unload_model(second.as_ptr()), 0);
+        }
+    }
+
+    fn predict_features(name: &CString, features: &str) -> Result<Vec<f32>, i32> {
+        let features = CString::new(features).unwrap();
+        let res = unsafe { infera_predict_features(name.as_ptr(), features.as_ptr()) };
+        let values = (res.status == 0)
+            .then(|| unsafe { std::slice::from_raw_parts(res.data, res.len) }.to_vec())
+            .ok_or(res.status);
+        unsafe { infera_free_result(res) };
+        values
+    }
+
+    #[test]
+    fn test_infera_predict_features() {
+        let name = CString::new("features_model").unwrap();
+        let path = CString::new("../test/models/dynamic_batch.onnx").unwrap();
+        let load = |options: &str| {
+            let options = CString::new(options).unwrap();
+            unsafe {
+                infera_load_model_with_options(name.as_ptr(), path.as_ptr(), options.as_ptr())
+            }
+        };
+        assert_eq!(load(r#"{"feature_names": ["a", "b", "c"]}"#), 0);
+        let expected = predict_values(&name, &[1.0, 2.0, 3.0, 4.0, 0.0, 6.0], 2, 3);
+
+        // Keys in any order are put in the feature name order
+        assert_eq!(
+            predict_features(&name, r#"{"c": 3, "a": 1, "b": 2}"#),
+            Ok(expected[..1].to_vec())
+        );
+        assert_eq!(
+            predict_features(
+                &name,
+                r#"[{"b": 2, "c": 3, "a": 1}, {"c": 6, "b": 0, "a": 4}]"#
+            ),
+            Ok(expected.clone())
+        );
+
+        // Missing features fail without a default, as do unknown and non-numeric ones
+        let invalid = error::InferaStatus::Error as i32;
+        assert_eq!(predict_features(&name, r#"{"a": 4, "c": 6}"#), Err(invalid));
+        assert_eq!(
+            predict_features(&name, r#"{"a": 1, "b": 2, "c": 3, "d": 4}"#),
+            Err(invalid)
+        );
+        assert_eq!(
+            predict_features(&name, r#"{"a": 1, "b": "2", "c": 3}"#),
+            Err(invalid)
+        );
+        assert_eq!(predict_features(&name, "[]"), Err(invalid));
+        assert_eq!(predict_features(&name, "[1, 2, 3]"), Err(invalid));
+
+        // With a default, missing features take it, and unknown ones can be ignored
+        assert_eq!(
+            load(
+                r#"{"feature_names": ["a", "b", "c"], "feature_default": 0, "ignore_unknown": true}"#
+            ),
+            0
+        );
+        assert_eq!(
+            predict_features(&name, r#"{"a": 4, "c": 6, "d": 99}"#),
+            Ok(expected[1..].to_vec())
+        );
+
+        // Models without feature names cannot take feature maps
+        assert_eq!(load(""), 0);
+        assert_eq!(predict_features(&name, r#"{"a": 1}"#), Err(invalid));
+        assert_eq!(unsafe { infera_unload_model(name.as_ptr()) }, 0);
+    }
+
+    #[test]
+    fn test_feature_names_from_sidecar_file() {
+        let dir = tempdir().unwrap();
+        let model_path = dir.path().join("scored.onnx");
+        fs::copy("../test/models/dynamic_batch.onnx", &model_path).unwrap();
+        fs::write(
+            dir.path().join("scored.features.json"),
+            r#"["age", "income", "tenure"]"#,
+        )
+        .unwrap();
+        let name = CString::new("features_sidecar").unwrap();
+        let path = CString::new(model_path.to_str().unwrap()).unwrap();
+        assert_eq!(
+            unsafe { infera_load_model(name.as_ptr(), path.as_ptr()) },
+            0
+        );
+
+        let info_ptr = unsafe { infera_get_model_info(name.as_ptr()) };
+        let info: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(info_ptr).to_str().unwrap() }).unwrap();
+        unsafe { infera_free_string(info_ptr) };
+        assert_eq!(info["feature_names"], json!(["age", "income", "tenure"]));
+        assert_eq!(
+            predict_features(&name, r#"{"tenure": 3, "income": 2, "age": 1}"#),
+            Ok(predict_values(&name, &[1.0, 2.0, 3.0], 1, 3))
+        );
+        assert_eq!(unsafe { infera_unload_model(name.as_ptr()) }, 0);
+
+        // An invalid features file fails the load
+        fs::write(dir.path().join("scored.features.json"), r#"["age", "age"]"#).unwrap();
+        assert_eq!(
+            unsafe { infera_load_model(name.as_ptr(), path.as_ptr()) },
+            -1
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_infera_load_model_from_fd() {
+        use std::io::{Read, Write};
+        use std::os::fd::AsRawFd;
+        use std::os::unix::net::UnixStream;
+
+        // A file is read from its start, even if the caller already read part of it
+        let name = CString::new("fd_file_model").unwrap();
+        let mut file = fs::File::open("../test/models/linear.onnx").unwrap();
+        file.read_exact(&mut [0u8; 4]).unwrap();
+        assert_eq!(
+            unsafe { infera_load_model_from_fd(name.as_ptr(), file.as_raw_fd()) },
+            0
+        );
+        assert_eq!(predict_values(&name, &[1.0, 2.0, 3.0], 1, 3), [1.75]);
+        // The descriptor is still open for the caller
+        assert!(file.metadata().is_ok());
+
+        // A stream that cannot seek is read to its end
+        let stream_name = CString::new("fd_stream_model").unwrap();
+        let (mut writer, reader) = UnixStream::pair().unwrap();
+        let bytes = fs::read("../test/models/linear.onnx").unwrap();
+        let sender = std::thread::spawn(move || {
+            writer.write_all(&bytes).unwrap();
+        });
+        let status = unsafe { infera_load_model_from_fd(stream_name.as_ptr(), reader.as_raw_fd()) };
+        sender.join().unwrap();
+        assert_eq!(status, 0);
+        assert_eq!(predict_values(&stream_name, &[1.0, 2.0, 3.0], 1, 3), [1.75]);
+
+        let invalid = CString::new("fd_invalid_model").unwrap();
+        assert_eq!(
+            unsafe { infera_load_model_from_fd(invalid.as_ptr(), -1) },
+            -1
+        );
+        let error = unsafe { CStr::from_ptr(infera_last_error()) };
+        assert!(error.to_str().unwrap().contains("Invalid file descriptor"));
+
+        unsafe {
+            assert_eq!(infera_unload_model(name.as_ptr()), 0);
+            assert_eq!(infera_unload_model(stream_name.as_ptr()), 0);
+        }
+    }
+
+    #[test]
+    fn test_infera_set_postprocessor() {
+        let name = CString::new("postprocessed_model").unwrap();
+        let path = CString::new("../test/models/multi_output.onnx").unwrap();
+        let input = [-3.0f32, 0.25, 0.5, 4.0];
+        let set = |spec: &str| {
+            let spec = CString::new(spec).unwrap();
+            unsafe { infera_set_postprocessor(name.as_ptr(), spec.as_ptr()) }
+        };
+        assert_eq!(
+            unsafe { infera_load_model(name.as_ptr(), path.as_ptr()) },
+            0
+        );
+        let raw = predict_values(&name, &input, 1, 4);
+
+        // Each step matches the raw output postprocessed by hand
+        type Postprocess = fn(&[f32]) -> Vec<f32>;
+        let cases: [(&str, Postprocess); 4] = [
+            (r#"{"scale": 2, "offset": 1}"#, |raw| {
+                raw.iter().map(|v| v * 2.0 + 1.0).collect()
+            }),
+            (r#"{"activation": "sigmoid"}"#, |raw| {
+                raw.iter().map(|v| 1.0 / (1.0 + (-v).exp())).collect()
+            }),
+            (r#"{"clip_min": 0, "clip_max": 1}"#, |raw| {
+                raw.iter().map(|v| v.clamp(0.0, 1.0)).collect()
+            }),
+            (r#"{"activation": "softmax", "round": 4}"#, |raw| {
+                let sum: f32 = raw.iter().map(|v| v.exp()).sum();
+                raw.iter()
+                    .map(|v| (v.exp() / sum * 1e4).round() / 1e4)
+                    .collect()
+            }),
+        ];
+        for (spec, expected) in cases {
+            assert_eq!(set(spec), 0, "{}", spec);
+            let output = predict_values(&name, &input, 1, 4);
+            for (actual, expected) in output.iter().zip(expected(&raw)) {
+                assert!((actual - expected).abs() < 1e-5, "{}: {:?}", spec, output);
+            }
+        }
+        let info_ptr = unsafe { infera_get_model_info(name.as_ptr()) };
+        let info: serde_json::Value =
+            serde_json::from_str(unsafe { CStr::from_ptr(info_ptr).to_str().unwrap() }).unwrap();
+        unsafe { infera_free_string(info_ptr) };
+        assert_eq!(
+            info["postprocessor"],
+            json!({"activation": "softmax", "round": 4})
+        );
+
+        // Invalid specs are rejected and leave the current postprocessing in place
+        assert_eq!(set(r#"{"round": "4"}"#), -1);
+        assert_eq!(set(r#"{"clip": 1}"#), -1);
+        assert_eq!(
+            unsafe { infera_set_postprocessor(name.as_ptr(), std::ptr::null()) },
+            -1
+        );
+        assert_ne!(predict_values(&name, &input, 1, 4), raw);
+
+        // An empty spec detaches the postprocessing
+        assert_eq!(set(""), 0);
+        assert_eq!(predict_values(&name, &input, 1, 4), raw);
+        let missing = CString::new("postprocessed_missing").unwrap();
+        let spec = CString::new(r#"{"round": 2}"#).unwrap();
+        assert_eq!(
+            unsafe { infera_set_postprocessor(missing.as_ptr(), spec.as_ptr()) },
+            -1
+        );
+        assert_eq!(unsafe { infera_unload_model(name.as_ptr()) }, 0);
+    }
+
+    #[test]
+    fn test_infera_canary_falls_back_on_errors() {
+        let alias = CString::new("canary_alias").unwrap();
+        let candidate = CString::new("canary_candidate").unwrap();
+        let fallback = CString::new("canary_fallback").unwrap();
+        // The candidate expects 4 input columns, so every 3-column prediction fails
+        let candidate_path = CString::new("../test/models/multi_output.onnx").unwrap();
+        let fallback_path = CString::new("../test/models/linear.onnx").unwrap();
+        unsafe {
+            assert_eq!(
+                infera_load_model(candidate.as_ptr(), candidate_path.as_ptr()),
+                0
+            );
+            assert_eq!(
+                infera_load_model(fallback.as_ptr(), fallback_path.as_ptr()),
+                0
+            );
+        }
+
+        // Invalid policies and unknown models are rejected.
+        let set = |rate: f64, window: usize| unsafe {
+            infera_set_canary(
+                alias.as_ptr(),
+                candidate.as_ptr(),
+                fallback.as_ptr(),
+                rate,
+                window,
+            )
+        };
+        assert_eq!(set(1.5, 4), -1);
+        assert_eq!(set(0.5, 0), -1);
+        let missing = CString::new("canary_missing").unwrap();
+        assert_eq!(
+            unsafe {
+                infera_set_canary(alias.as_ptr(), missing.as_ptr(), fallback.as_ptr(), 0.5, 4)
+            },
+            -1
+        );
+        assert!(canary_status(&alias)["error"].is_string());
+
+        // Failing predictions through the alias go to the candidate until the window trips.
+        assert_eq!(set(0.5, 4), 0);
+        let row = [1.0f32, 2.0, 3.0];
+        for _ in 0..4 {
+            let res = unsafe { infera_predict(alias.as_ptr(), row.as_ptr(), 1, 3) };
+            assert_eq!(res.status, error::InferaStatus::InvalidShape as i32);
+            unsafe { infera_free_result(res) };
+        }
+        let status = canary_status(&alias);
+        assert_eq!(status["state"], "tripped");
+        assert_eq!(status["target"], "canary_fallback");
+        assert_eq!(status["errors"], 4);
+        assert_eq!(status["trip_error_rate"], 1.0);
+
+        // After the trip, the alias serves predictions from the fallback.
+        let values = predict_values(&alias, &row, 1, 3);
+        assert!((values[0] - linear_expected(&row)).abs() < 1e-5);
+        assert_eq!(canary_status(&alias)["requests"], 4);
+
+        // Promotion points the alias back at the candidate for good.
+        assert_eq!(unsafe { infera_promote_canary(alias.as_ptr()) }, 0);
+        let status = canary_status(&alias);
+        assert_eq!(status["state"], "promoted");
+        assert_eq!(status["target"], "canary_candidate");
+        let wide_row = [1.0f32, 2.0, 3.0, 4.0];
+        assert_eq!(predict_values(&alias, &wide_row, 1, 4), wide_row.to_vec());
+        assert_eq!(unsafe { infera_promote_canary(missing.as_ptr()) }, -1);
+
+        unsafe {
+            infera_unload_model(candidate.as_ptr());
+            infera_unload_model(fallback.as_ptr());
+        }
+    }
+
+    #[test]
+    fn test_infera_load_model_verified() {
+        use sha2::{Digest, Sha256};
+
+        let name = CString::new("verified_linear").unwrap();
+        let path = CString::new("../test/models/linear.onnx").unwrap();
+        let bytes = std::fs::read("../test/models/linear.onnx").unwrap();
+        let checksum = hex::encode(Sha256::digest(&bytes));
+
+        // A mismatching checksum refuses the load.
+        let wrong = CString::new("0".repeat(64)).unwrap();
+        assert_eq!(
+            unsafe { infera_load_model_verified(name.as_ptr(), path.as_ptr(), wrong.as_ptr()) },
+            -1
+        );
+        let err = unsafe { CStr::from_ptr(infera_last_error()) }
+            .to_str()
+            .unwrap();
+        assert!(err.contains("Checksum mismatch"), "{}", err);
+        assert!(err.contains(&checksum), "{}", err);
+        assert!(model_info_name(&name).is_null());
+
+        // A malformed checksum is rejected before the file is read.
+        let malformed = CString::new("abc123").unwrap();
+        assert_eq!(
+            unsafe { infera_load_model_verified(name.as_ptr(), path.as_ptr(), malformed.as_ptr()) },
+            -1
+        );
+        let err = unsafe { CStr::from_ptr(infera_last_error()) }
+            .to_str()
+            .unwrap();
+        assert!(err.contains("Invalid SHA-256 checksum"), "{}", err);
+
+        // The matching checksum loads the model, in either case.
+        let expected = CString::new(checksum.to_uppercase()).unwrap();
+        assert_eq!(
+            unsafe { infera_load_model_verified(name.as_ptr(), path.as_ptr(), expected.as_ptr()) },
+            0
+        );
+        let row = [1.0f32, 2.0, 3.0];
+        let values = predict_values(&name, &row, 1, 3);
+        assert!((values[0] - linear_expected(&row)).abs() < 1e-5);
+        unsafe { infera_unload_model(name.as_ptr()) };
+    }
+
+    #[test]
+    fn test_infera_rename_model() {
+        let old_name = CString::new("rename_old").unwrap();
+        let new_name = CString::new("rename_new").unwrap();
+        let other_name = CString::new("rename_other").unwrap();
+        let fallback = CString::new("rename_fallback").unwrap();
+        let alias = CString::new("rename_alias").unwrap();
+        let path = CString::new("../test/models/linear.onnx").unwrap();
+        let multi_path = CString::new("../test/models/multi_output.onnx").unwrap();
+        unsafe {
+            assert_eq!(infera_load_model(old_name.as_ptr(), path.as_ptr()), 0);
+            assert_eq!(
+                infera_load_model(other_name.as_ptr(), multi_path.as_ptr()),
+                0
+            );
+            assert_eq!(infera_load_model(fallback.as_ptr(), path.as_ptr()), 0);
+            assert_eq!(
+                infera_set_canary(alias.as_ptr(), old_name.as_ptr(), fallback.as_ptr(), 0.5, 4),
+                0
+            );
+        }
+
+        // Happy path: the compiled model moves to the new name and keeps working.
+        assert_eq!(
+            unsafe { infera_rename_model(old_name.as_ptr(), new_name.as_ptr(), false) },
+            0
+        );
+        assert!(model_info_name(&old_name).is_null());
+        assert_eq!(model_info_name(&new_name), "rename_new");
+        let row = [1.0f32, 2.0, 3.0];
+        let values = predict_values(&new_name, &row, 1, 3);
+        assert!((values[0] - linear_expected(&row)).abs() < 1e-5);
+
+        // Aliases follow the renamed model
+        assert_eq!(canary_status(&alias)["candidate"], "rename_new");
+        let values = predict_values(&alias, &row, 1, 3);
+        assert!((values[0] - linear_expected(&row)).abs() < 1e-5);
+        assert_eq!(canary_status(&alias)["errors"], 0);
+
+        // Collision: an existing name is kept unless replacing is requested.
+        assert_eq!(
+            unsafe { infera_rename_model(new_name.as_ptr(), other_name.as_ptr(), false) },
+            -1
+        );
+        let err = unsafe { CStr::from_ptr(infera_last_error()) }
+            .to_str()
+            .unwrap();
+        assert!(err.contains("already loaded"), "unexpected error: {}", err);
+        assert_eq!(model_info_name(&new_name), "rename_new");
+        assert_eq!(model_info_name(&other_name), "rename_other");
+
+        assert_eq!(
+            unsafe { infera_rename_model(new_name.as_ptr(), other_name.as_ptr(), true) },
+            0
+        );
+        assert!(model_info_name(&new_name).is_null());
+        let values = predict_values(&other_name, &row, 1, 3);
+        assert!((values[0] - linear_expected(&row)).abs() < 1e-5);
+        assert_eq!(canary_status(&alias)["candidate"], "rename_other");
+
+        let moved_fallback = CString::new("rename_fallback_moved").unwrap();
+        assert_eq!(
+            unsafe { infera_rename_model(fallback.as_ptr(), moved_fallback.as_ptr(), false) },
+            0
+        );
+        assert_eq!(canary_status(&alias)["fallback"], "rename_fallback_moved");
+
+        // Renaming a model that is not loaded fails.
+        assert_eq!(
+            unsafe { infera_rename_model(old_name.as_ptr(), new_name.as_ptr(), false) },
+            -1
+        );
+
+        unsafe {
+            infera_unload_model(other_name.as_ptr());
+            infera_unload_model(moved_fallback.as_ptr());
+        }
+    }
+
+    #[test]
+    fn test_inference_results_report_specific_status_codes() {
+        let data = [1.0f32, 2.0, 3.0, 4.0];
+
+        let missing = CString::new("__status_missing_model__").unwrap();
+        let res = unsafe { infera_predict(missing.as_ptr(), data.as_ptr(), 1, 3) };
+        assert_eq!(res.status, error::InferaStatus::ModelNotFound as i32);
+        assert_eq!(res.status, -2);
+        unsafe { infera_free_result(res) };
+
+        let name = CString::new("status_codes_linear").unwrap();
+        let path = CString::new("../test/models/linear.onnx").unwrap();
+        assert_eq!(
+            unsafe { infera_load_model(name.as_ptr(), path.as_ptr()) },
+            0
+        );
+
+        let res = unsafe { infera_predict(name.as_ptr(), data.as_ptr(), 1, 4) };
+        assert_eq!(res.status, -3, "wrong column count is a shape error");
+        unsafe { infera_free_result(res) };
 
-    #[test]
-    fn test_infera_get_version() {
-        let version_ptr = infera_get_version();
-        let version_json = unsafe { CStr::from_ptr(version_ptr).to_str().unwrap() };
-        let version_data: serde_json::Value = serde_json::from_str(version_json).unwrap();
+        let blob = [0u8; 5];
+        let res = unsafe { infera_predict_from_blob(name.as_ptr(), blob.as_ptr(), blob.len()) };
+        assert_eq!(res.status, -3, "a partial f32 is a shape error");
+        unsafe { infera_free_result(res) };
 
-        assert!(version_data["version"].is_string());
-        assert!(version_data["onnx_backend"].is_string());
-        assert!(version_data["model_cache_dir"].is_string());
+        let res = unsafe { infera_predict(std::ptr::null(), data.as_ptr(), 1, 3) };
+        assert_eq!(res.status, -1, "other errors keep the generic status");
+        unsafe { infera_free_result(res) };
 
-        unsafe { infera_free(version_ptr) };
+        // The detailed message is still available.
+        let res = unsafe { infera_predict(missing.as_ptr(), data.as_ptr(), 1, 3) };
+        let err = unsafe { CStr::from_ptr(infera_last_error()) }
+            .to_str()
+            .unwrap();
+        assert!(err.contains("__status_missing_model__"));
+        unsafe { infera_free_result(res) };
+
+        unsafe { infera_unload_model(name.as_ptr()) };
     }
 
     #[test]
-    fn test_infera_set_autoload_dir() {
-        let dir = tempdir().unwrap();
-        let model_path = dir.path().join("linear.onnx");
-        fs::copy("../test/models/linear.onnx", &model_path).unwrap();
+    fn test_infera_get_last_prediction_time_ns() {
+        let name = CString::new("timed_linear").unwrap();
+        let path = CString::new("../test/models/dynamic_batch.onnx").unwrap();
+        unsafe {
+            assert_eq!(infera_load_model(name.as_ptr(), path.as_ptr()), 0);
+            assert_eq!(infera_get_last_prediction_time_ns(name.as_ptr()), 0);
+        }
 
-        let path_cstr = CString::new(dir.path().to_str().unwrap()).unwrap();
-        let result_ptr = unsafe { infera_set_autoload_dir(path_cstr.as_ptr()) };
-        let result_json = unsafe { CStr::from_ptr(result_ptr).to_str().unwrap() };
-        let result_data: serde_json::Value = serde_json::from_str(result_json).unwrap();
+        let values = predict_values(&name, &[1.0, 2.0, 3.0], 1, 3);
+        assert_eq!(values.len(), 1);
+        assert!(unsafe { infera_get_last_prediction_time_ns(name.as_ptr()) } > 0);
 
-        assert_eq!(result_data["loaded"].as_array().unwrap().len(), 1);
-        assert_eq!(result_data["loaded"][0], "linear");
-        assert_eq!(result_data["errors"].as_array().unwrap().len(), 0);
+        let missing = CString::new("__timed_missing_model__").unwrap();
+        assert_eq!(
+            unsafe { infera_get_last_prediction_time_ns(missing.as_ptr()) },
+            0
+        );
+        let err = unsafe { CStr::from_ptr(infera_last_error()) }
+            .to_str()
+            .unwrap();
+        assert!(err.contains("__timed_missing_model__"));
 
-        unsafe { infera_free(result_ptr) };
+        unsafe { infera_unload_model(name.as_ptr()) };
     }
 
     #[test]
-    fn test_infera_set_autoload_dir_non_existent() {
+    fn test_infera_get_model_info_nonexistent_returns_error_json() {
+        let name = CString::new("__missing_model__").unwrap();
+        let info_ptr = unsafe { infera_get_model_info(name.as_ptr()) };
+        let info_json = unsafe { CStr::from_ptr(info_ptr).to_str().unwrap() };
+        let value: serde_json::Value = serde_json::from_str(info_json).unwrap();
+        assert!(
+            value.get("error").is_some(),
+            "expected error field in JSON: {}",
+            info_json
+        );
+        unsafe { infera_free_string(info_ptr) };
+    }
+
+    #[test]
+    fn test_infera_add_trusted_key_reports_signature_in_model_info() {
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let key = SigningKey::from_bytes(&[9u8; 32]);
         let dir = tempdir().unwrap();
-        let non_existent_path = dir.path().join("non_existent");
-        let path_cstr = CString::new(non_existent_path.to_str().unwrap()).unwrap();
-        let result_ptr = unsafe { infera_set_autoload_dir(path_cstr.as_ptr()) };
-        let result_json = unsafe { CStr::from_ptr(result_ptr).to_str().unwrap() };
-        let result_data: serde_json::Value = serde_json::from_str(result_json).unwrap();
+        let model_path = dir.path().join("signed.onnx");
+        let bytes = fs::read("../test/models/linear.onnx").unwrap();
+        fs::write(&model_path, &bytes).unwrap();
+        fs::write(
+            signature::signature_path(&model_path),
+            key.sign(&bytes).to_bytes(),
+        )
+        .unwrap();
 
-        assert!(result_data["error"].is_string());
+        let bad_key = CString::new("not a key").unwrap();
+        assert_eq!(unsafe { infera_add_trusted_key(bad_key.as_ptr()) }, -1);
+        let hex_key = CString::new(hex::encode(key.verifying_key().to_bytes())).unwrap();
+        assert_eq!(unsafe { infera_add_trusted_key(hex_key.as_ptr()) }, 0);
 
-        unsafe { infera_free(result_ptr) };
+        let name = CString::new("signed_linear").unwrap();
+        let path = CString::new(model_path.to_str().unwrap()).unwrap();
+        assert_eq!(
+            unsafe { infera_load_model(name.as_ptr(), path.as_ptr()) },
+            0
+        );
+        let info_ptr = unsafe { infera_get_model_info(name.as_ptr()) };
+        let info_json = unsafe { CStr::from_ptr(info_ptr).to_str().unwrap() };
+        let value: serde_json::Value = serde_json::from_str(info_json).unwrap();
+        assert_eq!(value["signature"], "verified");
+        unsafe { infera_free_string(info_ptr) };
+        unsafe { infera_unload_model(name.as_ptr()) };
     }
 
     #[test]
-    fn test_infera_set_autoload_dir_invalid_model() {
-        let dir = tempdir().unwrap();
-        let model_path = dir.path().join("invalid.onnx");
-        fs::write(&model_path, "invalid onnx data").unwrap();
+    fn test_infera_get_config_reports_every_field() {
+        let config_ptr = infera_get_config();
+        let config_json = unsafe { CStr::from_ptr(config_ptr).to_str().unwrap() };
+        let value: serde_json::Value = serde_json::from_str(config_json).unwrap();
+        unsafe { infera_free_string(config_ptr) };
 
-        let path_cstr = CString::new(dir.path().to_str().unwrap()).unwrap();
-        let result_ptr = unsafe { infera_set_autoload_dir(path_cstr.as_ptr()) };
-        let result_json = unsafe { CStr::from_ptr(result_ptr).to_str().unwrap() };
-        let result_data: serde_json::Value = serde_json::from_str(result_json).unwrap();
+        let config = &*config::CONFIG;
+        let expected_keys = [
+            "cache_dir",
+            "temp_dir",
+            "cache_size_limit",
+            "verbose_logging",
+            "http_timeout_secs",
+            "http_connect_timeout_secs",
+            "http_retry_attempts",
+            "http_retry_delay_ms",
+            "http_total_deadline_secs",
+            "require_octet_stream",
+            "keep_partial",
+            "cache_dedup",
+            "cache_naming",
+            "offline",
+            "cache_ttl_secs",
+            "min_free_disk_bytes",
+            "max_download_bytes",
+            "http_max_redirects",
+            "allow_insecure_redirects",
+            "strict_blob",
+            "strict_output_types",
+            "allowed_hosts",
+            "host_overrides",
+            "require_signed_models",
+            "model_versioning",
+            "model_history_depth",
+            "canary_count_non_finite",
+            "concurrency_block",
+            "validate_input",
+            "max_blob_bytes",
+            "batch_chunk_rows",
+            "intra_op_threads",
+            "worker_threads",
+            "result_pool_size",
+            "max_pending",
+            "queue_aging_ms",
+            "load_retry_attempts",
+            "load_retry_delay_ms",
+            "cache_eviction_strategy",
+            "log_level",
+        ];
+        let object = value.as_object().unwrap();
+        assert_eq!(object.len(), expected_keys.len());
+        for key in expected_keys {
+            assert!(object.contains_key(key), "missing key {}", key);
+        }
 
-        assert_eq!(result_data["loaded"].as_array().unwrap().len(), 0);
-        assert_eq!(result_data["errors"].as_array().unwrap().len(), 1);
         assert_eq!(
-            result_data["errors"][0]["file"],
-            model_path.to_str().unwrap()
+            value["cache_dir"],
+            config.cache_dir.to_string_lossy().as_ref()
+        );
+        assert_eq!(value["cache_size_limit"], config.cache_size_limit);
+        assert_eq!(value["http_timeout_secs"], config.http_timeout_secs);
+        assert_eq!(value["http_retry_attempts"], config.http_retry_attempts);
+        assert_eq!(value["http_retry_delay_ms"], config.http_retry_delay_ms);
+        assert_eq!(value["max_blob_bytes"], config.max_blob_bytes);
+        assert_eq!(value["worker_threads"], config.worker_threads);
+        assert_eq!(
+            value["cache_eviction_strategy"],
+            config.cache_eviction_strategy.as_str()
         );
+        assert_eq!(value["log_level"], config.log_level.as_str());
+        assert_eq!(value, config.to_json());
+    }
 
-        unsafe { infera_free(result_ptr) };
+    #[test]
+    fn test_infera_get_metrics_reports_queue_per_priority() {
+        let metrics_ptr = infera_get_metrics();
+        let metrics_json = unsafe { CStr::from_ptr(metrics_ptr).to_str().unwrap() };
+        let value: serde_json::Value = serde_json::from_str(metrics_json).unwrap();
+        for priority in ["high", "normal", "low"] {
+            assert!(value["queue"][priority]["depth"].is_u64());
+            assert!(value["queue"][priority]["wait_ms"]["buckets"].is_array());
+        }
+        assert!(value["cache"]["hits"].is_u64());
+        unsafe { infera_free_string(metrics_ptr) };
     }
 
     #[test]
-    fn test_ffi_null_pointers() {
-        let model_name = CString::new("test").unwrap();
-        let path = CString::new("path").unwrap();
-        let null_ptr = std::ptr::null();
+    fn test_infera_get_cache_info_includes_configured_limit() {
+        let cache_info_ptr = infera_get_cache_info();
+        let cache_info_json = unsafe { CStr::from_ptr(cache_info_ptr).to_str().unwrap() };
+        let value: serde_json::Value = serde_json::from_str(cache_info_json).unwrap();
+        let size_limit = value["size_limit_bytes"]
+            .as_u64()
+            .expect("size_limit_bytes should be u64");
+        assert_eq!(size_limit, crate::config::cache_size_limit());
+        for counter in ["hits", "misses", "revalidations", "downloads", "evictions"] {
+            assert!(value["stats"][counter].is_u64());
+        }
+        unsafe { infera_free_string(cache_info_ptr) };
+    }
 
-        // Test infera_load_model
-        unsafe {
-            assert_eq!(infera_load_model(null_ptr, path.as_ptr()), -1);
-            let error = CStr::from_ptr(infera_last_error());
-            assert!(error.to_str().unwrap().contains("Null pointer passed"));
+    #[test]
+    fn test_infera_load_model_ex_reports_download_and_cache_hits() {
+        let mut server = mockito::Server::new();
+        let body = fs::read("../test/models/linear.onnx").unwrap();
+        let url_str = format!("{}/load_ex_linear.onnx", server.url());
+        let unversioned_str = format!("{}/load_ex_unversioned.onnx", server.url());
+        let _ = http::evict_cached_model(&url_str);
+        let _ = http::evict_cached_model(&unversioned_str);
+        let download = server
+            .mock("GET", "/load_ex_linear.onnx")
+            .with_status(200)
+            .with_header("ETag", "v1")
+            .with_body(&body)
+            .create();
+        server
+            .mock("GET", "/load_ex_unversioned.onnx")
+            .with_status(200)
+            .with_body(&body)
+            .create();
+        let url = CString::new(url_str).unwrap();
+        let unversioned = CString::new(unversioned_str).unwrap();
+        let name = CString::new("load_ex_remote").unwrap();
+        let outcome_of = |name: &CString| {
+            json_from_ptr(unsafe { infera_get_model_info(name.as_ptr()) })["load_outcome"].clone()
+        };
 
-            assert_eq!(infera_load_model(model_name.as_ptr(), null_ptr), -1);
-            let error = CStr::from_ptr(infera_last_error());
-            assert!(error.to_str().unwrap().contains("Null pointer passed"));
-        }
+        // The first load downloads the model
+        assert_eq!(
+            unsafe { infera_load_model_ex(name.as_ptr(), url.as_ptr()) },
+            InferaLoadOutcome::Downloaded as i32
+        );
+        assert_eq!(outcome_of(&name), "downloaded");
+        download.assert();
+        download.remove();
 
-        // Test infera_unload_model
+        // The second load revalidates the cached copy with its ETag
+        let not_modified = server
+            .mock("GET", "/load_ex_linear.onnx")
+            .match_header("if-none-match", "v1")
+            .with_status(304)
+            .create();
+        assert_eq!(
+            unsafe { infera_load_model_ex(name.as_ptr(), url.as_ptr()) },
+            InferaLoadOutcome::Revalidated as i32
+        );
+        assert_eq!(outcome_of(&name), "revalidated");
+        not_modified.assert();
+
+        // A cached model without an ETag is reused without a request
         unsafe {
-            assert_eq!(infera_unload_model(null_ptr), -1);
-            let error = CStr::from_ptr(infera_last_error());
-            assert!(error.to_str().unwrap().contains("Null pointer passed"));
+            assert_eq!(
+                infera_load_model_ex(name.as_ptr(), unversioned.as_ptr()),
+                InferaLoadOutcome::Downloaded as i32
+            );
+            assert_eq!(
+                infera_load_model_ex(name.as_ptr(), unversioned.as_ptr()),
+                InferaLoadOutcome::CacheHit as i32
+            );
         }
+        assert_eq!(outcome_of(&name), "cache_hit");
 
-        // Test infera_predict
-        let data: [f32; 1] = [0.0];
-        unsafe {
-            let result = infera_predict(null_ptr, data.as_ptr(), 1, 1);
-            assert_eq!(result.status, -1);
-            let error = CStr::from_ptr(infera_last_error());
-            assert!(error.to_str().unwrap().contains("Null pointer passed"));
+        let path = CString::new("../test/models/linear.onnx").unwrap();
+        assert_eq!(
+            unsafe { infera_load_model_ex(name.as_ptr(), path.as_ptr()) },
+            InferaLoadOutcome::Local as i32
+        );
+        assert_eq!(outcome_of(&name), "local");
+        let missing = CString::new("../test/models/missing.onnx").unwrap();
+        assert_eq!(
+            unsafe { infera_load_model_ex(name.as_ptr(), missing.as_ptr()) },
+            -1
+        );
 
-            let result = infera_predict(model_name.as_ptr(), std::ptr::null(), 1, 1);
-            assert_eq!(result.status, -1);
-            let error = CStr::from_ptr(infera_last_error());
-            assert!(error.to_str().unwrap().contains("Null pointer passed"));
-        }
+        unsafe { infera_unload_model(name.as_ptr()) };
+        let _ = http::evict_cached_model(url.to_str().unwrap());
+        let _ = http::evict_cached_model(unversioned.to_str().unwrap());
+    }
 
-        // Test infera_predict_from_blob
-        let blob: [u8; 4] = [0; 4];
-        unsafe {
-            let result = infera_predict_from_blob(null_ptr, blob.as_ptr(), 4);
-            assert_eq!(result.status, -1);
-            let error = CStr::from_ptr(infera_last_error());
-            assert!(error.to_str().unwrap().contains("Null pointer passed"));
+    /// Calls the FFI from 16 threads at once for a few seconds: first loads of one URL and
+    /// of local files, predictions, unloads, and the read-only introspection functions.
+    ///
+    /// Under ThreadSanitizer (`make rust-tsan`), this also checks the calls for data races.
+    #[test]
+    fn test_ffi_concurrent_smoke() {
+        let mut server = mockito::Server::new();
+        let body = fs::read("../test/models/linear.onnx").unwrap();
+        server
+            .mock("GET", "/ffi_smoke_linear.onnx")
+            .with_status(200)
+            .with_header("ETag", "v1")
+            .with_body(&body)
+            .create();
+        let url_str = format!("{}/ffi_smoke_linear.onnx", server.url());
+        let _ = http::evict_cached_model(&url_str);
+        let url = CString::new(url_str.clone()).unwrap();
+        let path = CString::new("../test/models/linear.onnx").unwrap();
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(3);
 
-            let result = infera_predict_from_blob(model_name.as_ptr(), std::ptr::null(), 4);
-            assert_eq!(result.status, -1);
-            let error = CStr::from_ptr(infera_last_error());
-            assert!(error.to_str().unwrap().contains("Null pointer passed"));
-        }
+        std::thread::scope(|scope| {
+            for thread in 0..16 {
+                let (url, path) = (&url, &path);
+                scope.spawn(move || {
+                    // Pairs of threads share a name, so loads and unloads of one model race
+                    let name = CString::new(format!("ffi_smoke_{}", thread % 8)).unwrap();
+                    let row = [1.0f32, 2.0, 3.0];
+                    let read_only: [extern "C" fn() -> *mut c_char; 7] = [
+                        infera_get_loaded_models,
+                        infera_list_models_detailed,
+                        infera_get_cache_info,
+                        infera_list_cache,
+                        infera_get_metrics,
+                        infera_get_version,
+                        infera_get_config,
+                    ];
+                    while std::time::Instant::now() < deadline {
+                        assert_eq!(infera_init(), 0);
+                        unsafe {
+                            let source = if thread % 2 == 0 { url } else { path };
+                            assert!(infera_load_model_ex(name.as_ptr(), source.as_ptr()) >= 0);
+                            // The other thread with this name may have unloaded it since
+                            let res = infera_predict(name.as_ptr(), row.as_ptr(), 1, 3);
+                            if res.status == 0 {
+                                let value = *res.data;
+                                assert!((value - linear_expected(&row)).abs() < 1e-5);
+                            }
+                            infera_free_result(res);
+                            infera_free_string(infera_get_model_info(name.as_ptr()));
+                            let shape = [1i64, 3];
+                            infera_check_input_shape(name.as_ptr(), shape.as_ptr(), 2);
+                            infera_get_output_size(name.as_ptr(), 1);
+                            for call in read_only {
+                                infera_free_string(call());
+                            }
+                            infera_free_string(infera_preview_eviction(0));
+                            infera_last_error();
+                            infera_unload_model(name.as_ptr());
+                        }
+                    }
+                });
+            }
+        });
 
-        // Test infera_get_model_info
+        let _ = http::evict_cached_model(&url_str);
+    }
+
+    #[test]
+    fn test_infera_check_model_updated() {
+        let mut server = mockito::Server::new();
+        let body = fs::read("../test/models/linear.onnx").unwrap();
+        let download = server
+            .mock("GET", "/check_updated_linear.onnx")
+            .with_status(200)
+            .with_header("ETag", "v1")
+            .with_body(body)
+            .create();
+        let url = CString::new(format!("{}/check_updated_linear.onnx", server.url())).unwrap();
+        let name = CString::new("check_updated_remote").unwrap();
         unsafe {
-            let info_ptr = infera_get_model_info(null_ptr);
-            let info_json = CStr::from_ptr(info_ptr).to_str().unwrap();
-            let info_data: serde_json::Value = serde_json::from_str(info_json).unwrap();
-            assert!(info_data["error"].is_string());
-            assert!(info_data["error"]
-                .as_str()
-                .unwrap()
-                .contains("Null pointer passed"));
-            infera_free(info_ptr);
+            assert_eq!(infera_load_model(name.as_ptr(), url.as_ptr()), 0);
         }
+        download.assert();
 
-        // Test infera_set_autoload_dir
+        let unchanged = server
+            .mock("HEAD", "/check_updated_linear.onnx")
+            .match_header("if-none-match", "v1")
+            .with_status(304)
+            .create();
+        assert_eq!(unsafe { infera_check_model_updated(name.as_ptr()) }, 0);
+        unchanged.assert();
+        unchanged.remove();
+
+        // The remote changes its ETag, which is reported without reloading the model
+        server
+            .mock("HEAD", "/check_updated_linear.onnx")
+            .with_status(200)
+            .with_header("ETag", "v2")
+            .create();
+        assert_eq!(unsafe { infera_check_model_updated(name.as_ptr()) }, 1);
+        let row = [1.0f32, 2.0, 3.0];
+        let values = predict_values(&name, &row, 1, 3);
+        assert!((values[0] - linear_expected(&row)).abs() < 1e-5);
+
+        // Models loaded from a local file have no remote to check
+        let local = CString::new("check_updated_local").unwrap();
+        let path = CString::new("../test/models/linear.onnx").unwrap();
         unsafe {
-            let result_ptr = infera_set_autoload_dir(null_ptr);
-            let result_json = CStr::from_ptr(result_ptr).to_str().unwrap();
-            let result_data: serde_json::Value = serde_json::from_str(result_json).unwrap();
-            assert!(result_data["error"].is_string());
-            assert!(result_data["error"]
-                .as_str()
-                .unwrap()
-                .contains("Null pointer passed"));
-            infera_free(result_ptr);
+            assert_eq!(infera_load_model(local.as_ptr(), path.as_ptr()), 0);
+            assert_eq!(infera_check_model_updated(local.as_ptr()), -1);
+            let error = CStr::from_ptr(infera_last_error()).to_str().unwrap();
+            assert!(error.contains("was not loaded from a URL"), "{}", error);
+            infera_unload_model(local.as_ptr());
+            infera_unload_model(name.as_ptr());
         }
     }
 
+    #[cfg(feature = "tract")]
+    #[cfg(unix)]
     #[test]
-    fn test_infera_predict_from_blob_invalid_size() {
-        let model_name = CString::new("test_model").unwrap();
-        let model_path = CString::new("../test/models/linear.onnx").unwrap();
+    fn test_infera_get_model_info_reports_load_source() {
+        use std::os::fd::AsRawFd;
+
+        let mut server = mockito::Server::new();
+        let body = fs::read("../test/models/linear.onnx").unwrap();
+        server
+            .mock("GET", "/source_kind_linear.onnx")
+            .with_status(200)
+            .with_body(&body)
+            .create();
+        let url_str = format!("{}/source_kind_linear.onnx", server.url());
+        let url = CString::new(url_str.clone()).unwrap();
+        let remote = CString::new("source_kind_remote").unwrap();
+        let local = CString::new("source_kind_local").unwrap();
+        let memory = CString::new("source_kind_memory").unwrap();
+        let path = CString::new("../test/models/linear.onnx").unwrap();
+        let file = fs::File::open("../test/models/linear.onnx").unwrap();
+        let _ = http::evict_cached_model(&url_str);
         unsafe {
-            infera_load_model(model_name.as_ptr(), model_path.as_ptr());
+            assert_eq!(infera_load_model(remote.as_ptr(), url.as_ptr()), 0);
+            assert_eq!(infera_load_model(local.as_ptr(), path.as_ptr()), 0);
+            assert_eq!(
+                infera_load_model_from_fd(memory.as_ptr(), file.as_raw_fd()),
+                0
+            );
         }
+        let info_of =
+            |name: &CString| json_from_ptr(unsafe { infera_get_model_info(name.as_ptr()) });
 
-        // Blob size is 5, which is not a multiple of 4 (size of f32)
-        let blob: [u8; 5] = [0; 5];
-        let result = unsafe { infera_predict_from_blob(model_name.as_ptr(), blob.as_ptr(), 5) };
-        assert_eq!(result.status, -1);
+        let info = info_of(&remote);
+        let cached_path = http::cached_model_path(&url_str);
+        assert_eq!(info["source_kind"], "remote_cached");
+        assert_eq!(info["url"], url_str.as_str());
+        assert_eq!(info["cache_path"], cached_path.to_str().unwrap());
+        assert_eq!(info["content_hash"], http::bytes_sha256(&body).as_str());
 
-        let error = unsafe { CStr::from_ptr(infera_last_error()) };
-        assert!(error
-            .to_str()
-            .unwrap()
-            .contains("Invalid BLOB size: length must be a multiple of 4"));
+        let info = info_of(&local);
+        assert_eq!(info["source_kind"], "local");
+        assert_eq!(info["path"], "../test/models/linear.onnx");
+        assert!(info["url"].is_null());
+        assert!(info["cache_path"].is_null());
+        assert_eq!(info["content_hash"], http::bytes_sha256(&body).as_str());
+
+        let info = info_of(&memory);
+        assert_eq!(info["source_kind"], "memory");
+        assert!(info["url"].is_null());
+
+        // Only the remote model is backed by a file that clearing the cache deletes
+        let cached = model::models_cached_in(&http::cache_dir());
+        assert!(cached.contains(&"source_kind_remote".to_string()));
+        assert!(!cached.contains(&"source_kind_local".to_string()));
+        assert!(!cached.contains(&"source_kind_memory".to_string()));
 
         unsafe {
-            infera_unload_model(model_name.as_ptr());
+            infera_unload_model(remote.as_ptr());
+            infera_unload_model(local.as_ptr());
+            infera_unload_model(memory.as_ptr());
+        }
+        let _ = http::evict_cached_model(&url_str);
+    }
+
+    /// Writes a zip archive holding `files` (name and content) to `path`.
+    fn write_zip(path: &std::path::Path, files: &[(&str, &[u8])]) {
+        use std::io::Write;
+
+        let mut zip = zip::ZipWriter::new(fs::File::create(path).unwrap());
+        for (name, content) in files {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(content).unwrap();
         }
+        zip.finish().unwrap();
     }
 
     #[test]
-    fn test_infera_predict_invalid_shape() {
-        // Load a simple model that expects input shape [1,3]
-        let model_name = CString::new("shape_check").unwrap();
-        let model_path = CString::new("../test/models/linear.onnx").unwrap();
+    fn test_infera_load_model_from_bundle() {
+        let dir = tempdir().unwrap();
+        let model_bytes = fs::read("../test/models/multi_output.onnx").unwrap();
+        let bundle_path = dir.path().join("animals.infera.zip");
+        write_zip(
+            &bundle_path,
+            &[
+                ("model.onnx", model_bytes.as_slice()),
+                (
+                    "manifest.json",
+                    r#"{"name": "animals", "version": "1.0.0"}"#.as_bytes(),
+                ),
+                (
+                    "labels.json",
+                    r#"["cat", "dog", "bird", "fish"]"#.as_bytes(),
+                ),
+                ("preprocess.json", r#"{"scale": 0.5}"#.as_bytes()),
+                (
+                    "features.json",
+                    r#"["ears", "bark", "wings", "fins"]"#.as_bytes(),
+                ),
+            ],
+        );
+        let path = CString::new(bundle_path.to_str().unwrap()).unwrap();
+
+        // An empty name loads the model under the manifest's name
+        let empty = CString::new("").unwrap();
+        let name = CString::new("animals").unwrap();
+        assert_eq!(
+            unsafe { infera_load_model(empty.as_ptr(), path.as_ptr()) },
+            0
+        );
+        let info = json_from_ptr(unsafe { infera_get_model_info(name.as_ptr()) });
+        assert_eq!(info["bundle"]["name"], "animals");
+        assert_eq!(info["bundle"]["version"], "1.0.0");
+        assert_eq!(info["bundle"]["preprocess"], json!({"scale": 0.5}));
+        assert_eq!(
+            info["feature_names"],
+            json!(["ears", "bark", "wings", "fins"])
+        );
+
+        // Classify a row and name the top class with the bundle's labels
+        let scores = predict_values(&name, &[0.1, 0.7, 0.2, 0.0], 1, 4);
+        let top = (0..scores.len())
+            .max_by(|&a, &b| scores[a].total_cmp(&scores[b]))
+            .unwrap();
+        assert_eq!(info["bundle"]["labels"][top], "dog");
+
+        // The caller's name overrides the manifest's
+        let custom = CString::new("bundled_animals").unwrap();
+        assert_eq!(
+            unsafe { infera_load_model(custom.as_ptr(), path.as_ptr()) },
+            0
+        );
+        let info = json_from_ptr(unsafe { infera_get_model_info(custom.as_ptr()) });
+        assert_eq!(info["bundle"]["name"], "animals");
         unsafe {
+            infera_unload_model(name.as_ptr());
+            infera_unload_model(custom.as_ptr());
+        }
+
+        let manifest = r#"{"name": "broken"}"#.as_bytes();
+        type Entries<'a> = Vec<(&'a str, &'a [u8])>;
+        let cases: [(&str, Entries, &str); 3] = [
+            (
+                "no_model.infera.zip",
+                vec![("manifest.json", manifest)],
+                "has no model.onnx",
+            ),
+            (
+                "no_manifest.infera.zip",
+                vec![("model.onnx", model_bytes.as_slice())],
+                "has no manifest.json",
+            ),
+            (
+                "bad_manifest.infera.zip",
+                vec![
+                    ("model.onnx", model_bytes.as_slice()),
+                    ("manifest.json", r#"{"version": 1"#.as_bytes()),
+                ],
+                "Invalid model bundle",
+            ),
+        ];
+        for (file, files, message) in cases {
+            let bad_path = dir.path().join(file);
+            write_zip(&bad_path, &files);
+            let bad = CString::new(bad_path.to_str().unwrap()).unwrap();
             assert_eq!(
-                infera_load_model(model_name.as_ptr(), model_path.as_ptr()),
-                0
+                unsafe { infera_load_model(empty.as_ptr(), bad.as_ptr()) },
+                -1
             );
+            let err = unsafe { CStr::from_ptr(infera_last_error()) }
+                .to_string_lossy()
+                .into_owned();
+            assert!(err.contains(message), "{}: {}", file, err);
         }
+    }
 
-        // Provide rows=1, cols=2 while model expects 3 features -> should error
-        let data: [f32; 2] = [0.0, 0.0];
-        let res = unsafe { infera_predict(model_name.as_ptr(), data.as_ptr(), 1, 2) };
-        assert_eq!(res.status, -1);
-        let err = unsafe { CStr::from_ptr(infera_last_error()) };
-        let msg = err.to_str().unwrap();
-        assert!(
-            msg.contains("Invalid input shape"),
-            "unexpected error: {}",
-            msg
-        );
+    #[test]
+    fn test_infera_download_model_writes_to_dest_path() {
+        let mut server = mockito::Server::new();
+        let body = fs::read("../test/models/linear.onnx").unwrap();
+        let download = server
+            .mock("GET", "/download_only_linear.onnx")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_header("ETag", "d1")
+            .with_body(&body)
+            .expect(2)
+            .create();
+        let url_str = format!("{}/download_only_linear.onnx", server.url());
+        let url = CString::new(url_str.clone()).unwrap();
+        let dir = tempdir().unwrap();
+        let dest_path = dir.path().join("model.onnx");
+        let dest = CString::new(dest_path.to_str().unwrap()).unwrap();
+        let _ = http::evict_cached_model(&url_str);
 
         unsafe {
-            infera_unload_model(model_name.as_ptr());
+            assert_eq!(infera_download_model(url.as_ptr(), dest.as_ptr()), 0);
         }
-    }
+        assert_eq!(fs::read(&dest_path).unwrap(), body);
+        assert!(!http::cached_model_path(&url_str).exists());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
 
-    #[test]
-    fn test_infera_get_model_info_nonexistent_returns_error_json() {
-        let name = CString::new("__missing_model__").unwrap();
-        let info_ptr = unsafe { infera_get_model_info(name.as_ptr()) };
-        let info_json = unsafe { CStr::from_ptr(info_ptr).to_str().unwrap() };
-        let value: serde_json::Value = serde_json::from_str(info_json).unwrap();
-        assert!(
-            value.get("error").is_some(),
-            "expected error field in JSON: {}",
-            info_json
-        );
-        unsafe { infera_free(info_ptr) };
+        // An existing file is replaced, and no ETag is sent for it
+        fs::write(&dest_path, b"stale").unwrap();
+        unsafe {
+            assert_eq!(infera_download_model(url.as_ptr(), dest.as_ptr()), 0);
+        }
+        assert_eq!(fs::read(&dest_path).unwrap(), body);
+        download.assert();
+
+        unsafe {
+            assert_eq!(infera_download_model(url.as_ptr(), std::ptr::null()), -1);
+            let root = CString::new("/").unwrap();
+            assert_eq!(infera_download_model(url.as_ptr(), root.as_ptr()), -1);
+            let error = CStr::from_ptr(infera_last_error()).to_str().unwrap();
+            assert!(error.contains("no file name"), "{}", error);
+        }
     }
 
     #[test]
-    fn test_infera_get_cache_info_includes_configured_limit() {
-        let cache_info_ptr = infera_get_cache_info();
-        let cache_info_json = unsafe { CStr::from_ptr(cache_info_ptr).to_str().unwrap() };
-        let value: serde_json::Value = serde_json::from_str(cache_info_json).unwrap();
-        let size_limit = value["size_limit_bytes"]
-            .as_u64()
-            .expect("size_limit_bytes should be u64");
-        assert_eq!(size_limit, crate::config::CONFIG.cache_size_limit);
-        unsafe { infera_free(cache_info_ptr) };
+    fn test_infera_load_model_redownloads_corrupt_cached_file() {
+        let mut server = mockito::Server::new();
+        let body = fs::read("../test/models/linear.onnx").unwrap();
+        let download = server
+            .mock("GET", "/corrupt_cache_linear.onnx")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_header("ETag", "c1")
+            .with_body(&body)
+            .expect(3)
+            .create();
+        let revalidate = server
+            .mock("GET", "/corrupt_cache_linear.onnx")
+            .match_header("if-none-match", "c1")
+            .with_status(304)
+            .create();
+        let url_str = format!("{}/corrupt_cache_linear.onnx", server.url());
+        let url = CString::new(url_str.clone()).unwrap();
+        let name = CString::new("corrupt_cache_remote").unwrap();
+        let cached_path = http::cached_model_path(&url_str);
+        let row = [1.0f32, 2.0, 3.0];
+        let _ = http::evict_cached_model(&url_str);
+
+        unsafe {
+            assert_eq!(infera_load_model(name.as_ptr(), url.as_ptr()), 0);
+            infera_unload_model(name.as_ptr());
+        }
+
+        // A truncated file whose ETag the server still confirms is downloaded again
+        fs::write(&cached_path, &body[..body.len() / 2]).unwrap();
+        unsafe {
+            assert_eq!(infera_load_model(name.as_ptr(), url.as_ptr()), 0);
+        }
+        assert_eq!(fs::read(&cached_path).unwrap(), body);
+        let values = predict_values(&name, &row, 1, 3);
+        assert!((values[0] - linear_expected(&row)).abs() < 1e-5);
+        unsafe { infera_unload_model(name.as_ptr()) };
+        revalidate.assert();
+
+        // A corrupt file without metadata is a cache hit without any request, and is
+        // downloaded again as well
+        fs::write(&cached_path, b"not an onnx model").unwrap();
+        fs::remove_file(cached_path.with_extension("meta.json")).unwrap();
+        unsafe {
+            assert_eq!(infera_load_model(name.as_ptr(), url.as_ptr()), 0);
+        }
+        let values = predict_values(&name, &row, 1, 3);
+        assert!((values[0] - linear_expected(&row)).abs() < 1e-5);
+        unsafe { infera_unload_model(name.as_ptr()) };
+        download.assert();
     }
 }
@@ -1,8 +1,28 @@
 // Defines the internal representation of a model and the global model store.
 
+use crate::autoload;
+use crate::bundle::BundleInfo;
+use crate::canary;
+use crate::concurrency;
+use crate::error::InferaError;
+use crate::http::CachedModel;
+#[cfg(feature = "tract")]
+use crate::postprocess::Postprocessor;
+#[cfg(feature = "tract")]
+use crate::signature::SignatureStatus;
 use once_cell::sync::Lazy;
+#[cfg(feature = "tract")]
+use parking_lot::Mutex;
 use parking_lot::RwLock;
-use std::collections::HashMap;
+use serde_json::{json, Value};
+use std::cell::RefCell;
+#[cfg(feature = "tract")]
+use std::collections::VecDeque;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+#[cfg(feature = "tract")]
+use std::sync::atomic::AtomicU64;
+use std::sync::Arc;
 
 #[cfg(feature = "tract")]
 use tract_onnx::prelude::*;
@@ -13,30 +33,962 @@ use tract_onnx::prelude::*;
 pub(crate) type OnnxModelPlan =
     SimplePlan<TypedFact, Box<dyn TypedOp>, Graph<TypedFact, Box<dyn TypedOp>>>;
 
+/// The plans of a model recompiled for seeded predictions, kept so a seed is compiled
+/// only the first time it is used.
+#[cfg(feature = "tract")]
+#[derive(Default)]
+pub(crate) struct SeededPlans {
+    /// Whether the model has random ops, or `None` until a seeded prediction checks.
+    pub has_random_ops: Option<bool>,
+    /// The plans by seed, most recently used first.
+    pub plans: VecDeque<(u64, Arc<OnnxModelPlan>)>,
+}
+
+/// How the missing rows of a partial batch are filled when batch padding is enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum PadMode {
+    /// Fill missing rows with zeros.
+    #[default]
+    Zeros,
+    /// Repeat the last provided row.
+    RepeatLast,
+}
+
+/// Options that control how a model is loaded and run.
+///
+/// Options are passed as a JSON object to `infera_load_model_with_options`.
+/// Unknown keys are ignored so callers can pass options meant for newer versions.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LoadOptions {
+    /// Pad (and split) inputs to match a fixed batch dimension.
+    pub pad_batch: bool,
+    /// How padding rows are filled when `pad_batch` is enabled.
+    pub pad_mode: PadMode,
+    /// Names of internal nodes to expose as additional model outputs.
+    pub extra_outputs: Vec<String>,
+    /// Overrides `INFERA_CACHE_TTL_SECS` when the model is loaded from a URL.
+    pub cache_ttl_secs: Option<u64>,
+    /// Overrides `INFERA_MODEL_VERSIONING`, keeping the model this load replaces as a
+    /// previous version that can be rolled back to.
+    pub versioning: Option<bool>,
+    /// The SHA-256 the model file must have, in lowercase hex. Set by
+    /// `infera_load_model_verified`.
+    pub expected_sha256: Option<String>,
+    /// The names of the model's input columns in order, for `infera_predict_features`.
+    /// Overrides a `<model>.features.json` file and `feature_names` metadata.
+    pub feature_names: Option<Vec<String>>,
+    /// The value `infera_predict_features` uses for missing features. Missing features
+    /// are an error if it is not set.
+    pub feature_default: Option<f32>,
+    /// Whether `infera_predict_features` ignores features that are not in the model's
+    /// feature names instead of failing.
+    pub ignore_unknown: bool,
+    /// Constant values bound to model inputs that have no initializer in the graph, by
+    /// input name. Bound inputs are no longer inputs of the loaded model.
+    pub initializers: Vec<(String, InitializerValue)>,
+    /// The number of times loading the model file is retried, overriding
+    /// `INFERA_LOAD_RETRY_ATTEMPTS` (which counts the first attempt too).
+    pub load_retries: Option<u32>,
+}
+
+/// A constant value bound to a model input with the `"initializers"` load option.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct InitializerValue {
+    /// The shape of the value, or `None` to use the shape declared for the input.
+    pub shape: Option<Vec<usize>>,
+    /// The values in row-major order.
+    pub values: Vec<f64>,
+}
+
+impl LoadOptions {
+    /// Parses load options from a JSON object string.
+    ///
+    /// An empty string is treated as "no options".
+    pub(crate) fn from_json(json: &str) -> Result<Self, InferaError> {
+        let mut options = LoadOptions::default();
+        if json.trim().is_empty() {
+            return Ok(options);
+        }
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| InferaError::JsonError(e.to_string()))?;
+        let obj = value.as_object().ok_or_else(|| {
+            InferaError::JsonError("load options must be a JSON object".to_string())
+        })?;
+        if let Some(v) = obj.get("pad_batch") {
+            options.pad_batch = v.as_bool().ok_or_else(|| {
+                InferaError::JsonError("\"pad_batch\" must be a boolean".to_string())
+            })?;
+        }
+        if let Some(v) = obj.get("pad_mode") {
+            options.pad_mode = match v.as_str() {
+                Some("zeros") => PadMode::Zeros,
+                Some("repeat_last") => PadMode::RepeatLast,
+                _ => {
+                    return Err(InferaError::JsonError(
+                        "\"pad_mode\" must be \"zeros\" or \"repeat_last\"".to_string(),
+                    ))
+                }
+            };
+        }
+        if let Some(v) = obj.get("extra_outputs") {
+            let names = v.as_array().ok_or_else(|| {
+                InferaError::JsonError("\"extra_outputs\" must be an array of strings".to_string())
+            })?;
+            options.extra_outputs = names
+                .iter()
+                .map(|n| {
+                    n.as_str().map(String::from).ok_or_else(|| {
+                        InferaError::JsonError(
+                            "\"extra_outputs\" must be an array of strings".to_string(),
+                        )
+                    })
+                })
+                .collect::<Result<Vec<String>, InferaError>>()?;
+        }
+        if let Some(v) = obj.get("cache_ttl_secs") {
+            let ttl = match v {
+                Value::String(s) => crate::config::parse_ttl_secs(s),
+                _ => v.as_u64(),
+            };
+            options.cache_ttl_secs = Some(ttl.ok_or_else(|| {
+                InferaError::JsonError(
+                    "\"cache_ttl_secs\" must be a non-negative integer or \"infinite\"".to_string(),
+                )
+            })?);
+        }
+        if let Some(v) = obj.get("versioning") {
+            options.versioning = Some(v.as_bool().ok_or_else(|| {
+                InferaError::JsonError("\"versioning\" must be a boolean".to_string())
+            })?);
+        }
+        if let Some(v) = obj.get("feature_names") {
+            options.feature_names = Some(parse_feature_names(v)?);
+        }
+        if let Some(v) = obj.get("feature_default") {
+            options.feature_default = Some(
+                v.as_f64()
+                    .map(|v| v as f32)
+                    .filter(|v| v.is_finite())
+                    .ok_or_else(|| {
+                        InferaError::JsonError(
+                            "\"feature_default\" must be a finite number".to_string(),
+                        )
+                    })?,
+            );
+        }
+        if let Some(v) = obj.get("ignore_unknown") {
+            options.ignore_unknown = v.as_bool().ok_or_else(|| {
+                InferaError::JsonError("\"ignore_unknown\" must be a boolean".to_string())
+            })?;
+        }
+        if let Some(v) = obj.get("initializers") {
+            options.initializers = parse_initializers(v)?;
+        }
+        if let Some(v) = obj.get("load_retries") {
+            options.load_retries = Some(
+                v.as_u64()
+                    .and_then(|n| u32::try_from(n).ok())
+                    .ok_or_else(|| {
+                        InferaError::JsonError(
+                            "\"load_retries\" must be a non-negative integer".to_string(),
+                        )
+                    })?,
+            );
+        }
+        Ok(options)
+    }
+}
+
+/// Parses the `"initializers"` load option, a JSON object mapping input names to either
+/// an array of numbers or an object with a `"shape"` and `"values"`.
+fn parse_initializers(value: &Value) -> Result<Vec<(String, InitializerValue)>, InferaError> {
+    let invalid = |name: &str| {
+        InferaError::JsonError(format!(
+            "initializer '{}' must be an array of numbers or an object with a \"shape\" array of non-negative integers and a \"values\" array of numbers",
+            name
+        ))
+    };
+    let numbers = |value: &Value| -> Option<Vec<f64>> {
+        value.as_array()?.iter().map(Value::as_f64).collect()
+    };
+    let object = value.as_object().ok_or_else(|| {
+        InferaError::JsonError(
+            "\"initializers\" must be a JSON object keyed by input name".to_string(),
+        )
+    })?;
+    object
+        .iter()
+        .map(|(name, v)| {
+            let initializer = match v {
+                Value::Array(_) => InitializerValue {
+                    shape: None,
+                    values: numbers(v).ok_or_else(|| invalid(name))?,
+                },
+                Value::Object(fields) => InitializerValue {
+                    shape: Some(
+                        fields
+                            .get("shape")
+                            .and_then(Value::as_array)
+                            .and_then(|dims| {
+                                dims.iter()
+                                    .map(|d| d.as_u64().and_then(|d| usize::try_from(d).ok()))
+                                    .collect()
+                            })
+                            .ok_or_else(|| invalid(name))?,
+                    ),
+                    values: fields
+                        .get("values")
+                        .and_then(numbers)
+                        .ok_or_else(|| invalid(name))?,
+                },
+                _ => return Err(invalid(name)),
+            };
+            Ok((name.clone(), initializer))
+        })
+        .collect()
+}
+
+/// Parses a list of feature names, a JSON array of distinct, non-empty strings.
+pub(crate) fn parse_feature_names(value: &Value) -> Result<Vec<String>, InferaError> {
+    let invalid = || {
+        InferaError::JsonError(
+            "\"feature_names\" must be an array of distinct, non-empty strings".to_string(),
+        )
+    };
+    let names = value
+        .as_array()
+        .ok_or_else(invalid)?
+        .iter()
+        .map(|n| n.as_str().filter(|n| !n.is_empty()).map(String::from))
+        .collect::<Option<Vec<String>>>()
+        .ok_or_else(invalid)?;
+    let mut seen = std::collections::HashSet::new();
+    if !names.iter().all(|n| seen.insert(n.as_str())) {
+        return Err(invalid());
+    }
+    Ok(names)
+}
+
+/// The URL a model was downloaded from and the ETag of the downloaded copy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RemoteSource {
+    /// The HTTP/HTTPS URL the model was loaded from.
+    pub url: String,
+    /// The ETag the server sent for the downloaded copy, if any.
+    pub etag: Option<String>,
+    /// Whether the model was downloaded or served from the cache when it was loaded.
+    pub outcome: InferaLoadOutcome,
+}
+
+/// How a model was obtained when it was loaded, returned by `infera_load_model_ex`.
+///
+/// The values are part of the stable ABI.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InferaLoadOutcome {
+    /// The model was loaded from a local file.
+    Local = 0,
+    /// The model was loaded from the cache without downloading it, either within the
+    /// cache TTL, while offline, or because the server sent no ETag to revalidate with.
+    CacheHit = 1,
+    /// The model was downloaded from its URL.
+    Downloaded = 2,
+    /// The server confirmed that the cached copy is current (`304 Not Modified`), and it
+    /// was loaded from the cache.
+    Revalidated = 3,
+}
+
+impl InferaLoadOutcome {
+    /// Returns the outcome of resolving a remote model to its cached file.
+    pub(crate) fn of_cached(cached: &CachedModel) -> Self {
+        if !cached.from_cache {
+            InferaLoadOutcome::Downloaded
+        } else if cached.revalidated {
+            InferaLoadOutcome::Revalidated
+        } else {
+            InferaLoadOutcome::CacheHit
+        }
+    }
+
+    /// Returns the name of the outcome reported in model info.
+    #[cfg(feature = "tract")]
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            InferaLoadOutcome::Local => "local",
+            InferaLoadOutcome::CacheHit => "cache_hit",
+            InferaLoadOutcome::Downloaded => "downloaded",
+            InferaLoadOutcome::Revalidated => "revalidated",
+        }
+    }
+}
+
+/// Where the bytes of a loaded model came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SourceKind {
+    /// A model file on local disk.
+    #[cfg(feature = "tract")]
+    Local,
+    /// A model downloaded from a URL and loaded from its copy in the cache.
+    RemoteCached,
+    /// Bytes without a model file, such as those read from a file descriptor.
+    #[cfg(feature = "tract")]
+    Memory,
+}
+
+impl SourceKind {
+    /// Returns the name of the source kind reported in model info.
+    #[cfg(feature = "tract")]
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            SourceKind::Local => "local",
+            SourceKind::RemoteCached => "remote_cached",
+            SourceKind::Memory => "memory",
+        }
+    }
+}
+
 /// Represents a loaded ONNX model, holding its execution plan and metadata.
+///
+/// Cloning a model is cheap, since the plan is shared.
 #[cfg(feature = "tract")]
+#[derive(Clone)]
 pub(crate) struct OnnxModel {
-    /// The compiled, runnable model plan from the Tract engine.
-    pub model: OnnxModelPlan,
-    /// The shape of the model's input tensor. Dynamic dimensions are represented by -1.
-    pub input_shape: Vec<i64>,
-    /// The shape of the model's output tensor. Dynamic dimensions are represented by -1.
-    pub output_shape: Vec<i64>,
+    /// The compiled, runnable model plan from the Tract engine. Running the plan only
+    /// needs a shared reference, so it can serve many threads at once.
+    pub model: Arc<OnnxModelPlan>,
+    /// The shape of the model's input tensor, or `None` if it could not be determined.
+    /// Dynamic dimensions are represented by -1.
+    pub input_shape: Option<Vec<i64>>,
+    /// The symbol name of each input dimension (e.g., `S` for a sequence length), or `None`
+    /// for fixed dimensions and dimensions given by an expression.
+    pub input_symbols: Vec<Option<String>>,
+    /// The shape of the model's output tensor, or `None` if it could not be determined.
+    /// Dynamic dimensions are represented by -1.
+    pub output_shape: Option<Vec<i64>>,
+    /// The element type of the model's input tensor (f32 or f16 for supported models).
+    /// Defaults to f32 when the input shape is unknown.
+    pub input_dtype: DatumType,
+    /// The element type of each model input, in input order. Inputs whose type could not
+    /// be determined are recorded as f32.
+    pub input_dtypes: Vec<DatumType>,
+    /// The element type of the model's first output tensor.
+    pub output_dtype: DatumType,
+    /// The names of the model's inputs in the original ONNX graph, in input order.
+    pub input_names: Vec<String>,
+    /// The names of the graph inputs that have a constant value, either from an
+    /// initializer in the model file or from the `"initializers"` load option. They are
+    /// not inputs of the loaded model.
+    pub defaulted_inputs: Vec<String>,
+    /// The names of all model outputs, including any extra outputs, in output order.
+    /// Original outputs are named as in the ONNX graph.
+    pub output_names: Vec<String>,
+    /// Key-value pairs from the ONNX `metadata_props` (e.g., author, version, description).
+    pub model_metadata: HashMap<String, String>,
+    /// The result of checking the model's detached signature when it was loaded.
+    pub signature: SignatureStatus,
     /// The user-defined name for the model.
     pub name: String,
+    /// The options the model was loaded with.
+    pub options: LoadOptions,
+    /// Where the model was downloaded from, or `None` for models loaded from a local file.
+    pub source: Option<RemoteSource>,
+    /// Whether the model was loaded from a local file, the cache, or memory.
+    pub source_kind: SourceKind,
+    /// The local file the model was loaded from (the cached copy for remote models).
+    pub path: String,
+    /// The manifest, labels, and preprocessing of the bundle the model was loaded from, or
+    /// `None` for models not loaded from a bundle.
+    pub bundle: Option<Arc<BundleInfo>>,
+    /// When the model was loaded, in seconds since the Unix epoch.
+    pub loaded_at: u64,
+    /// The wall-clock duration in nanoseconds of the model's most recent run, or 0 if it
+    /// has not run yet. Clones of the model share the same counter.
+    pub last_inference_ns: Arc<AtomicU64>,
+    /// The postprocessing applied to the model's output, set with `infera_set_postprocessor`.
+    pub postprocessor: Option<Arc<Postprocessor>>,
+    /// The hex-encoded SHA-256 of the model file, computed when it was loaded.
+    pub content_sha256: String,
+    /// The names of the model's input columns in order, or empty if they are not known.
+    pub feature_names: Vec<String>,
+    /// The plans of the model recompiled with seeded random ops. Clones of the model share
+    /// them.
+    pub seeded_plans: Arc<Mutex<SeededPlans>>,
+    /// The versions of `tract-onnx` and `tract-core` that compiled the model's plan.
+    pub tract_versions: (&'static str, &'static str),
+    /// Whether tract's optimization passes were applied to the model's plan.
+    pub optimized: bool,
+    /// How long the model took to parse, compile, and optimize, in nanoseconds.
+    pub load_duration_ns: u64,
 }
 
 /// A placeholder struct for when the "tract" feature is not enabled.
 #[cfg(not(feature = "tract"))]
+#[derive(Clone)]
 pub(crate) struct OnnxModel {
     /// The user-defined name for the model.
     pub name: String,
+    /// Where the model was downloaded from, or `None` for models loaded from a local file.
+    pub source: Option<RemoteSource>,
+    /// Whether the model was loaded from a local file, the cache, or memory.
+    pub source_kind: SourceKind,
+    /// The local file the model was loaded from (the cached copy for remote models).
+    pub path: String,
+    /// The manifest, labels, and preprocessing of the bundle the model was loaded from, or
+    /// `None` for models not loaded from a bundle.
+    pub bundle: Option<Arc<BundleInfo>>,
+    /// When the model was loaded, in seconds since the Unix epoch.
+    pub loaded_at: u64,
 }
 
 /// A global, thread-safe store for all loaded ONNX models.
 ///
 /// This is a `Lazy` static, meaning it is initialized on first access.
 /// It uses a `RwLock` to allow multiple concurrent reads and exclusive writes,
-/// mapping model names (strings) to their `OnnxModel` representations.
-pub(crate) static MODELS: Lazy<RwLock<HashMap<String, OnnxModel>>> =
+/// mapping model names (strings) to shared handles of their `OnnxModel` representations.
+pub(crate) static MODELS: Lazy<RwLock<HashMap<String, Arc<OnnxModel>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// The previous versions of each model name, oldest first, kept when a load replaces a
+/// model with versioning enabled.
+///
+/// When both locks are needed, `MODELS` is always locked first.
+static HISTORY: Lazy<RwLock<HashMap<String, Vec<Arc<OnnxModel>>>>> =
     Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Initializes the model registry, which otherwise happens on first use.
+pub(crate) fn init_registry() {
+    Lazy::force(&MODELS);
+    Lazy::force(&HISTORY);
+}
+
+/// Stores a loaded model under `name`.
+///
+/// If a model is already loaded under `name` and `history_depth` is not zero, the replaced
+/// model is kept as the newest previous version, and the oldest versions beyond
+/// `history_depth` are dropped. Otherwise the replaced model is dropped.
+#[cfg(feature = "tract")]
+pub(crate) fn insert_model(name: &str, model: OnnxModel, history_depth: usize) {
+    let mut models = MODELS.write();
+    let Some(previous) = models.insert(name.to_string(), Arc::new(model)) else {
+        return;
+    };
+    if history_depth == 0 {
+        return;
+    }
+    let mut history = HISTORY.write();
+    let versions = history.entry(name.to_string()).or_default();
+    versions.push(previous);
+    let excess = versions.len().saturating_sub(history_depth);
+    versions.drain(..excess);
+}
+
+/// Unloads the model loaded under `name`, drops its previous versions, and removes its
+/// concurrency limit.
+pub(crate) fn unload_model(name: &str) -> Result<(), InferaError> {
+    let mut models = MODELS.write();
+    let removed = models.remove(name);
+    let history = HISTORY.write().remove(name);
+    if removed.is_none() && history.is_none() {
+        return Err(InferaError::ModelNotFound(name.to_string()));
+    }
+    drop(models);
+    concurrency::set_limit(name, 0);
+    Ok(())
+}
+
+/// Returns the files that loaded models, including previous versions, were loaded from,
+/// canonicalized where possible.
+///
+/// Cache eviction skips these files, since refreshing a loaded model revalidates the
+/// cached copy it was loaded from.
+pub(crate) fn loaded_model_paths() -> HashSet<PathBuf> {
+    let models = MODELS.read();
+    let history = HISTORY.read();
+    models
+        .values()
+        .chain(history.values().flatten())
+        .map(|model| {
+            let path = PathBuf::from(&model.path);
+            std::fs::canonicalize(&path).unwrap_or(path)
+        })
+        .collect()
+}
+
+/// Lists the current and previous versions of a model, newest first.
+///
+/// Each entry has its `index` (0 for the current version, 1 for the version a rollback
+/// would restore, and so on), whether it is `current`, the `source` it was loaded from
+/// (the URL for remote models, otherwise the file path), and when it was loaded
+/// (`loaded_at`, in seconds since the Unix epoch).
+pub(crate) fn list_versions(name: &str) -> Result<Value, InferaError> {
+    let models = MODELS.read();
+    let history = HISTORY.read();
+    let current = models.get(name);
+    let previous = history.get(name).map(Vec::as_slice).unwrap_or_default();
+    if current.is_none() && previous.is_empty() {
+        return Err(InferaError::ModelNotFound(name.to_string()));
+    }
+    let versions = current
+        .into_iter()
+        .map(|model| (true, model))
+        .chain(previous.iter().rev().map(|model| (false, model)))
+        .enumerate()
+        .map(|(index, (current, model))| {
+            json!({
+                "index": index,
+                "current": current,
+                "source": model
+                    .source
+                    .as_ref()
+                    .map_or(model.path.as_str(), |source| source.url.as_str()),
+                "loaded_at": model.loaded_at,
+            })
+        })
+        .collect::<Vec<_>>();
+    Ok(Value::from(versions))
+}
+
+/// Makes the newest previous version of a model current again and drops the current
+/// version.
+///
+/// The swap happens under the registry write lock, so every prediction runs against
+/// either the old or the restored version.
+pub(crate) fn rollback_model(name: &str) -> Result<(), InferaError> {
+    let mut models = MODELS.write();
+    let mut history = HISTORY.write();
+    let previous = history
+        .get_mut(name)
+        .and_then(Vec::pop)
+        .ok_or_else(|| InferaError::NoPreviousVersion(name.to_string()))?;
+    if history.get(name).is_some_and(Vec::is_empty) {
+        history.remove(name);
+    }
+    models.insert(name.to_string(), previous);
+    Ok(())
+}
+
+/// Drops all but the `keep` newest previous versions of a model and returns how many
+/// versions were dropped. The current version is never dropped.
+pub(crate) fn prune_versions(name: &str, keep: usize) -> usize {
+    let mut history = HISTORY.write();
+    let Some(versions) = history.get_mut(name) else {
+        return 0;
+    };
+    let excess = versions.len().saturating_sub(keep);
+    versions.drain(..excess);
+    if versions.is_empty() {
+        history.remove(name);
+    }
+    excess
+}
+
+/// Looks up a loaded model and returns a shared handle to it.
+///
+/// The registry lock is only held for the lookup, so inference on the returned model
+/// runs outside the lock and never waits on loads, unloads, or other predictions. A
+/// model unloaded while in use stays alive until its last handle is dropped.
+pub(crate) fn get_model(name: &str) -> Result<Arc<OnnxModel>, InferaError> {
+    MODELS
+        .read()
+        .get(name)
+        .cloned()
+        .ok_or_else(|| InferaError::ModelNotFound(name.to_string()))
+}
+
+/// Returns whether a model is loaded under `name`.
+pub(crate) fn is_loaded(name: &str) -> bool {
+    MODELS.read().contains_key(name)
+}
+
+/// Moves a loaded model from `old_name` to `new_name` without recompiling it.
+///
+/// The move happens under a single write lock, so the model is never missing from the
+/// store and predictions that already hold the read lock finish against the model first.
+/// If `new_name` is taken, the call fails unless `replace` is set, in which case the model
+/// loaded under `new_name` is unloaded. Canaries that route to the model, its concurrency
+/// limit, and its autoload group membership follow it.
+pub(crate) fn rename_model(
+    old_name: &str,
+    new_name: &str,
+    replace: bool,
+) -> Result<(), InferaError> {
+    if new_name.is_empty() {
+        return Err(InferaError::InvalidModelName(new_name.to_string()));
+    }
+    let mut models = MODELS.write();
+    if !models.contains_key(old_name) {
+        return Err(InferaError::ModelNotFound(old_name.to_string()));
+    }
+    if old_name == new_name {
+        return Ok(());
+    }
+    if !replace && models.contains_key(new_name) {
+        return Err(InferaError::ModelAlreadyExists(new_name.to_string()));
+    }
+    if let Some(model) = models.remove(old_name) {
+        let mut model = Arc::unwrap_or_clone(model);
+        model.name = new_name.to_string();
+        models.insert(new_name.to_string(), Arc::new(model));
+    }
+    canary::rename_target(old_name, new_name);
+    concurrency::rename_limit(old_name, new_name);
+    autoload::rename_member(old_name, new_name);
+    // Previous versions follow the model, and those of a replaced model are dropped
+    let mut history = HISTORY.write();
+    match history.remove(old_name) {
+        Some(versions) => history.insert(new_name.to_string(), versions),
+        None => history.remove(new_name),
+    };
+    Ok(())
+}
+
+/// Records where a loaded model was downloaded from.
+pub(crate) fn set_remote_source(name: &str, source: RemoteSource) {
+    if let Some(model) = MODELS.write().get_mut(name) {
+        let model = Arc::make_mut(model);
+        model.source = Some(source);
+        model.source_kind = SourceKind::RemoteCached;
+    }
+}
+
+/// Records the bundle a loaded model was loaded from.
+pub(crate) fn set_bundle(name: &str, bundle: BundleInfo) {
+    if let Some(model) = MODELS.write().get_mut(name) {
+        Arc::make_mut(model).bundle = Some(Arc::new(bundle));
+    }
+}
+
+/// Returns the names of the loaded models whose cached files are in `dir`, sorted by name.
+pub(crate) fn models_cached_in(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = MODELS
+        .read()
+        .iter()
+        .filter(|(_, model)| {
+            model.source_kind == SourceKind::RemoteCached && Path::new(&model.path).starts_with(dir)
+        })
+        .map(|(name, _)| name.clone())
+        .collect();
+    names.sort();
+    names
+}
+
+/// Sets or removes the postprocessing applied to a loaded model's output.
+///
+/// The model is replaced under the registry write lock, so every prediction runs with
+/// either the old or the new postprocessing.
+#[cfg(feature = "tract")]
+pub(crate) fn set_postprocessor(
+    name: &str,
+    postprocessor: Option<Postprocessor>,
+) -> Result<(), InferaError> {
+    let mut models = MODELS.write();
+    let model = models
+        .get_mut(name)
+        .ok_or_else(|| InferaError::ModelNotFound(name.to_string()))?;
+    Arc::make_mut(model).postprocessor = postprocessor.map(Arc::new);
+    Ok(())
+}
+
+/// Returns where a loaded model was downloaded from.
+///
+/// Fails with `NotRemoteModel` if the model was loaded from a local file.
+pub(crate) fn remote_source(name: &str) -> Result<RemoteSource, InferaError> {
+    get_model(name)?
+        .source
+        .clone()
+        .ok_or_else(|| InferaError::NotRemoteModel(name.to_string()))
+}
+
+/// Separates the namespace from the model name in registry keys (`namespace/name`).
+const NAMESPACE_SEPARATOR: char = '/';
+
+thread_local! {
+    /// The namespace that unqualified model names resolve to on the current thread.
+    static DEFAULT_NAMESPACE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Checks that a namespace is non-empty and does not contain the separator.
+pub(crate) fn validate_namespace(namespace: &str) -> Result<(), InferaError> {
+    if namespace.is_empty() || namespace.contains(NAMESPACE_SEPARATOR) {
+        return Err(InferaError::InvalidNamespace(namespace.to_string()));
+    }
+    Ok(())
+}
+
+/// Returns the registry key of `name` in `namespace`.
+pub(crate) fn qualified_name(namespace: &str, name: &str) -> Result<String, InferaError> {
+    validate_namespace(namespace)?;
+    if name.is_empty() || name.contains(NAMESPACE_SEPARATOR) {
+        return Err(InferaError::InvalidModelName(name.to_string()));
+    }
+    Ok(format!("{}{}{}", namespace, NAMESPACE_SEPARATOR, name))
+}
+
+/// Returns the registry key `name`, or `default` in the namespace of `name` if the model
+/// name part of `name` is empty (e.g., `""` or `"ns/"`).
+pub(crate) fn name_or_default(name: &str, default: &str) -> String {
+    match name.split_once(NAMESPACE_SEPARATOR) {
+        Some((namespace, "")) => format!("{}{}{}", namespace, NAMESPACE_SEPARATOR, default),
+        None if name.is_empty() => default.to_string(),
+        _ => name.to_string(),
+    }
+}
+
+/// Sets the default namespace of the current thread, or clears it with `None`.
+pub(crate) fn set_default_namespace(namespace: Option<&str>) -> Result<(), InferaError> {
+    if let Some(ns) = namespace {
+        validate_namespace(ns)?;
+    }
+    DEFAULT_NAMESPACE.with(|cell| *cell.borrow_mut() = namespace.map(str::to_string));
+    Ok(())
+}
+
+/// Returns the default namespace of the current thread, if one is set.
+pub(crate) fn default_namespace() -> Option<String> {
+    DEFAULT_NAMESPACE.with(|cell| cell.borrow().clone())
+}
+
+/// Resolves a model name passed to an unqualified API to its registry key.
+///
+/// Names that already contain a namespace (`namespace/name`) are used as-is. Other
+/// names map to the current thread's default namespace, or to the root namespace
+/// (the bare name) when no default is set.
+///
+/// A qualified name reaches its namespace whatever the thread's default is, so namespaces
+/// keep tenants' names apart but do not control access. A host that serves several
+/// tenants must not pass names containing `/` from a tenant to the API.
+pub(crate) fn resolve_name(name: &str) -> String {
+    if name.contains(NAMESPACE_SEPARATOR) {
+        return name.to_string();
+    }
+    match default_namespace() {
+        Some(ns) => format!("{}{}{}", ns, NAMESPACE_SEPARATOR, name),
+        None => name.to_string(),
+    }
+}
+
+/// Returns the namespace part of a registry key, or `None` for the root namespace.
+pub(crate) fn namespace_of(key: &str) -> Option<&str> {
+    key.split_once(NAMESPACE_SEPARATOR).map(|(ns, _)| ns)
+}
+
+/// Returns the unqualified names of the models loaded in `namespace`, sorted by name.
+///
+/// `None` selects the root namespace, which holds models loaded without a namespace.
+pub(crate) fn names_in_namespace(namespace: Option<&str>) -> Vec<String> {
+    let models = MODELS.read();
+    let mut names: Vec<String> = models
+        .keys()
+        .filter_map(
+            |key| match (namespace, key.split_once(NAMESPACE_SEPARATOR)) {
+                (Some(ns), Some((key_ns, name))) if key_ns == ns => Some(name.to_string()),
+                (None, None) => Some(key.clone()),
+                _ => None,
+            },
+        )
+        .collect();
+    names.sort();
+    names
+}
+
+/// Lists the models loaded in `namespace` with how they relate to other registries,
+/// sorted by name.
+///
+/// Each entry has the model's unqualified `name`, its registry key (`canonical_name`),
+/// its `namespace` (null for the root namespace), the ID of the autoload group it belongs
+/// to (`group_id`, null if it was not autoloaded), the canary `aliases` currently routing
+/// to it, and the number of `previous_versions` kept for rollback.
+///
+/// The groups, canaries, and models are read under their read locks held together, so
+/// the listing is a consistent snapshot.
+pub(crate) fn list_models_detailed(namespace: Option<&str>) -> Value {
+    crate::autoload::with_group_ids(|group_ids| {
+        crate::canary::with_alias_targets(|alias_targets| {
+            let models = MODELS.read();
+            let history = HISTORY.read();
+            let mut entries: Vec<(&str, Value)> = models
+                .keys()
+                .filter_map(|key| {
+                    let (key_ns, name) = match key.split_once(NAMESPACE_SEPARATOR) {
+                        Some((key_ns, name)) => (Some(key_ns), name),
+                        None => (None, key.as_str()),
+                    };
+                    (key_ns == namespace).then(|| {
+                        let entry = json!({
+                            "name": name,
+                            "canonical_name": key,
+                            "namespace": key_ns,
+                            "group_id": group_ids.get(key.as_str()),
+                            "aliases": alias_targets.get(key).cloned().unwrap_or_default(),
+                            "previous_versions": history.get(key).map_or(0, Vec::len),
+                        });
+                        (name, entry)
+                    })
+                })
+                .collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            Value::from(
+                entries
+                    .into_iter()
+                    .map(|(_, entry)| entry)
+                    .collect::<Vec<_>>(),
+            )
+        })
+    })
+}
+
+/// Unloads every model in `namespace` and returns how many were removed.
+///
+/// The previous versions and concurrency limits of the models, and the canary aliases
+/// in the namespace, are removed with them.
+pub(crate) fn remove_namespace(namespace: &str) -> Result<usize, InferaError> {
+    validate_namespace(namespace)?;
+    let in_namespace = |key: &str| namespace_of(key) == Some(namespace);
+    let mut models = MODELS.write();
+    let mut removed: Vec<String> = models
+        .keys()
+        .filter(|key| in_namespace(key))
+        .cloned()
+        .collect();
+    for key in &removed {
+        models.remove(key);
+    }
+    let count = removed.len();
+    let mut history = HISTORY.write();
+    removed.extend(history.keys().filter(|key| in_namespace(key)).cloned());
+    history.retain(|key, _| !in_namespace(key));
+    drop(history);
+    canary::remove_aliases(in_namespace);
+    drop(models);
+    for key in &removed {
+        concurrency::set_limit(key, 0);
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_options_from_json() {
+        let options = LoadOptions::from_json("").unwrap();
+        assert!(!options.pad_batch);
+        assert_eq!(options.pad_mode, PadMode::Zeros);
+
+        let options =
+            LoadOptions::from_json(r#"{"pad_batch": true, "pad_mode": "repeat_last"}"#).unwrap();
+        assert!(options.pad_batch);
+        assert_eq!(options.pad_mode, PadMode::RepeatLast);
+
+        assert!(LoadOptions::from_json("[]").is_err());
+        assert!(LoadOptions::from_json(r#"{"pad_batch": "yes"}"#).is_err());
+        assert!(LoadOptions::from_json(r#"{"pad_mode": "edge"}"#).is_err());
+
+        let options = LoadOptions::from_json(r#"{"extra_outputs": ["a", "b"]}"#).unwrap();
+        assert_eq!(options.extra_outputs, vec!["a", "b"]);
+        assert!(LoadOptions::from_json(r#"{"extra_outputs": "a"}"#).is_err());
+        assert!(LoadOptions::from_json(r#"{"extra_outputs": [1]}"#).is_err());
+
+        assert_eq!(LoadOptions::from_json("{}").unwrap().cache_ttl_secs, None);
+        let options = LoadOptions::from_json(r#"{"cache_ttl_secs": 60}"#).unwrap();
+        assert_eq!(options.cache_ttl_secs, Some(60));
+        let options = LoadOptions::from_json(r#"{"cache_ttl_secs": "infinite"}"#).unwrap();
+        assert_eq!(options.cache_ttl_secs, Some(u64::MAX));
+        assert!(LoadOptions::from_json(r#"{"cache_ttl_secs": -1}"#).is_err());
+        assert!(LoadOptions::from_json(r#"{"cache_ttl_secs": "later"}"#).is_err());
+
+        let options = LoadOptions::from_json(
+            r#"{"feature_names": ["age", "income"], "feature_default": 0, "ignore_unknown": true}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            options.feature_names,
+            Some(vec!["age".to_string(), "income".to_string()])
+        );
+        assert_eq!(options.feature_default, Some(0.0));
+        assert!(options.ignore_unknown);
+        assert!(LoadOptions::from_json(r#"{"feature_names": ["age", "age"]}"#).is_err());
+        assert!(LoadOptions::from_json(r#"{"feature_names": ["", "b"]}"#).is_err());
+        assert!(LoadOptions::from_json(r#"{"feature_names": "age"}"#).is_err());
+        assert!(LoadOptions::from_json(r#"{"feature_default": "0"}"#).is_err());
+        assert!(LoadOptions::from_json(r#"{"ignore_unknown": 1}"#).is_err());
+
+        let options = LoadOptions::from_json(
+            r#"{"initializers": {"W": [1, 2.5], "mask": {"shape": [1, 2], "values": [0, 1]}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            options.initializers,
+            vec![
+                (
+                    "W".to_string(),
+                    InitializerValue {
+                        shape: None,
+                        values: vec![1.0, 2.5],
+                    }
+                ),
+                (
+                    "mask".to_string(),
+                    InitializerValue {
+                        shape: Some(vec![1, 2]),
+                        values: vec![0.0, 1.0],
+                    }
+                ),
+            ]
+        );
+        for initializers in [
+            r#"[1.0]"#,
+            r#"{"W": 1.0}"#,
+            r#"{"W": ["1"]}"#,
+            r#"{"W": {"values": [1.0]}}"#,
+            r#"{"W": {"shape": [-1], "values": [1.0]}}"#,
+        ] {
+            let json = format!(r#"{{"initializers": {}}}"#, initializers);
+            assert!(LoadOptions::from_json(&json).is_err(), "{}", json);
+        }
+
+        assert_eq!(LoadOptions::from_json("{}").unwrap().load_retries, None);
+        let options = LoadOptions::from_json(r#"{"load_retries": 3}"#).unwrap();
+        assert_eq!(options.load_retries, Some(3));
+        assert!(LoadOptions::from_json(r#"{"load_retries": -1}"#).is_err());
+        assert!(LoadOptions::from_json(r#"{"load_retries": "3"}"#).is_err());
+    }
+
+    #[test]
+    fn test_namespace_resolution() {
+        assert_eq!(qualified_name("tenant_a", "m").unwrap(), "tenant_a/m");
+        assert!(matches!(
+            qualified_name("", "m"),
+            Err(InferaError::InvalidNamespace(_))
+        ));
+        assert!(matches!(
+            qualified_name("a/b", "m"),
+            Err(InferaError::InvalidNamespace(_))
+        ));
+        assert!(matches!(
+            qualified_name("a", "x/m"),
+            Err(InferaError::InvalidModelName(_))
+        ));
+
+        // Unqualified names resolve to the root namespace until a default is set.
+        assert_eq!(resolve_name("m"), "m");
+        set_default_namespace(Some("tenant_a")).unwrap();
+        assert_eq!(resolve_name("m"), "tenant_a/m");
+        assert_eq!(resolve_name("tenant_b/m"), "tenant_b/m");
+
+        // The default is per thread.
+        let other = std::thread::spawn(|| resolve_name("m")).join().unwrap();
+        assert_eq!(other, "m");
+
+        assert!(set_default_namespace(Some("")).is_err());
+        assert_eq!(default_namespace().as_deref(), Some("tenant_a"));
+        set_default_namespace(None).unwrap();
+        assert_eq!(resolve_name("m"), "m");
+
+        // An empty model name falls back to the default in the same namespace.
+        assert_eq!(name_or_default("", "churn"), "churn");
+        assert_eq!(name_or_default("tenant_a/", "churn"), "tenant_a/churn");
+        assert_eq!(name_or_default("tenant_a/m", "churn"), "tenant_a/m");
+    }
+}
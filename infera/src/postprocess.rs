@@ -0,0 +1,299 @@
+// Postprocessing steps applied to a model's output values before they are returned.
+
+use crate::error::InferaError;
+use serde_json::{json, Map, Value};
+
+/// The largest number of decimals outputs can be rounded to. `f32` values carry about
+/// seven significant digits, so rounding to more decimals has no effect.
+const MAX_ROUND_DECIMALS: u64 = 10;
+
+/// An activation function applied to the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Activation {
+    /// The logistic function, applied to each value.
+    Sigmoid,
+    /// Softmax over the values of each output row.
+    Softmax,
+}
+
+impl Activation {
+    fn as_str(self) -> &'static str {
+        match self {
+            Activation::Sigmoid => "sigmoid",
+            Activation::Softmax => "softmax",
+        }
+    }
+}
+
+/// Postprocessing steps for a model's output, parsed from the JSON passed to
+/// `infera_set_postprocessor`.
+///
+/// The steps that are set run in a fixed order: `scale`, `offset`, `activation`, `clip`
+/// (`clip_min` and `clip_max`), and `round`. Scaling before the activation lets the
+/// affine step calibrate logits, and rounding last makes the stored values exact.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Postprocessor {
+    /// The factor every value is multiplied by.
+    pub scale: Option<f32>,
+    /// The amount added to every value after scaling.
+    pub offset: Option<f32>,
+    /// The activation applied after the affine step.
+    pub activation: Option<Activation>,
+    /// The smallest value kept; lower values are raised to it.
+    pub clip_min: Option<f32>,
+    /// The largest value kept; higher values are lowered to it.
+    pub clip_max: Option<f32>,
+    /// The number of decimals values are rounded to.
+    pub round: Option<u32>,
+}
+
+impl Postprocessor {
+    /// Parses postprocessing steps from a JSON object string, such as
+    /// `{"activation": "sigmoid", "clip_min": 0, "clip_max": 1, "round": 4}`.
+    ///
+    /// An empty string or object has no steps.
+    pub(crate) fn from_json(json: &str) -> Result<Self, InferaError> {
+        let mut postprocessor = Postprocessor::default();
+        if json.trim().is_empty() {
+            return Ok(postprocessor);
+        }
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| InferaError::JsonError(e.to_string()))?;
+        let obj = value.as_object().ok_or_else(|| {
+            InferaError::InvalidPostprocessor("the spec must be a JSON object".to_string())
+        })?;
+        for (key, v) in obj {
+            match key.as_str() {
+                "scale" => postprocessor.scale = Some(finite_number(key, v)?),
+                "offset" => postprocessor.offset = Some(finite_number(key, v)?),
+                "clip_min" => postprocessor.clip_min = Some(finite_number(key, v)?),
+                "clip_max" => postprocessor.clip_max = Some(finite_number(key, v)?),
+                "activation" => {
+                    postprocessor.activation = Some(match v.as_str() {
+                        Some("sigmoid") => Activation::Sigmoid,
+                        Some("softmax") => Activation::Softmax,
+                        _ => {
+                            return Err(InferaError::InvalidPostprocessor(
+                                "\"activation\" must be \"sigmoid\" or \"softmax\"".to_string(),
+                            ))
+                        }
+                    })
+                }
+                "round" => {
+                    let decimals = v
+                        .as_u64()
+                        .filter(|&d| d <= MAX_ROUND_DECIMALS)
+                        .and_then(|d| u32::try_from(d).ok())
+                        .ok_or_else(|| {
+                            InferaError::InvalidPostprocessor(format!(
+                                "\"round\" must be an integer from 0 to {}",
+                                MAX_ROUND_DECIMALS
+                            ))
+                        })?;
+                    postprocessor.round = Some(decimals);
+                }
+                _ => {
+                    return Err(InferaError::InvalidPostprocessor(format!(
+                        "unknown step \"{}\"",
+                        key
+                    )))
+                }
+            }
+        }
+        if let (Some(min), Some(max)) = (postprocessor.clip_min, postprocessor.clip_max) {
+            if min > max {
+                return Err(InferaError::InvalidPostprocessor(format!(
+                    "\"clip_min\" ({}) must not be greater than \"clip_max\" ({})",
+                    min, max
+                )));
+            }
+        }
+        Ok(postprocessor)
+    }
+
+    /// Returns whether no steps are set, so applying it leaves outputs unchanged.
+    pub(crate) fn is_empty(&self) -> bool {
+        *self == Postprocessor::default()
+    }
+
+    /// Returns the steps that are set as a JSON object, in the format `from_json` accepts.
+    pub(crate) fn to_json(&self) -> Value {
+        let mut obj = Map::new();
+        for (key, value) in [
+            ("scale", self.scale),
+            ("offset", self.offset),
+            ("clip_min", self.clip_min),
+            ("clip_max", self.clip_max),
+        ] {
+            if let Some(value) = value {
+                obj.insert(key.to_string(), json!(value));
+            }
+        }
+        if let Some(activation) = self.activation {
+            obj.insert("activation".to_string(), json!(activation.as_str()));
+        }
+        if let Some(round) = self.round {
+            obj.insert("round".to_string(), json!(round));
+        }
+        Value::Object(obj)
+    }
+
+    /// Applies the steps in place to row-major output values with `cols` values per row.
+    pub(crate) fn apply(&self, values: &mut [f32], cols: usize) {
+        let (scale, offset) = (self.scale.unwrap_or(1.0), self.offset.unwrap_or(0.0));
+        if self.scale.is_some() || self.offset.is_some() {
+            values.iter_mut().for_each(|v| *v = *v * scale + offset);
+        }
+        match self.activation {
+            Some(Activation::Sigmoid) => values.iter_mut().for_each(|v| *v = sigmoid(*v)),
+            Some(Activation::Softmax) => values.chunks_mut(cols.max(1)).for_each(softmax),
+            None => {}
+        }
+        if let Some(min) = self.clip_min {
+            values
+                .iter_mut()
+                .filter(|v| **v < min)
+                .for_each(|v| *v = min);
+        }
+        if let Some(max) = self.clip_max {
+            values
+                .iter_mut()
+                .filter(|v| **v > max)
+                .for_each(|v| *v = max);
+        }
+        if let Some(decimals) = self.round {
+            let factor = 10f64.powi(decimals as i32);
+            values
+                .iter_mut()
+                .for_each(|v| *v = ((f64::from(*v) * factor).round() / factor) as f32);
+        }
+    }
+}
+
+/// Reads a finite number for the step `key`.
+fn finite_number(key: &str, value: &Value) -> Result<f32, InferaError> {
+    value
+        .as_f64()
+        .map(|v| v as f32)
+        .filter(|v| v.is_finite())
+        .ok_or_else(|| {
+            InferaError::InvalidPostprocessor(format!("\"{}\" must be a finite number", key))
+        })
+}
+
+fn sigmoid(v: f32) -> f32 {
+    1.0 / (1.0 + (-v).exp())
+}
+
+/// Replaces a row of values with their softmax, shifted by the row maximum so large
+/// values do not overflow.
+fn softmax(row: &mut [f32]) {
+    let max = row.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    row.iter_mut().for_each(|v| *v = (*v - max).exp());
+    let sum: f32 = row.iter().sum();
+    row.iter_mut().for_each(|v| *v /= sum);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-6, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    fn applied(spec: &str, values: &[f32], cols: usize) -> Vec<f32> {
+        let mut values = values.to_vec();
+        Postprocessor::from_json(spec)
+            .unwrap()
+            .apply(&mut values, cols);
+        values
+    }
+
+    #[test]
+    fn test_postprocessor_from_json() {
+        assert!(Postprocessor::from_json("").unwrap().is_empty());
+        assert!(Postprocessor::from_json("{}").unwrap().is_empty());
+        let spec = r#"{"scale": 2, "offset": -1, "activation": "softmax", "clip_min": 0, "clip_max": 1, "round": 4}"#;
+        let postprocessor = Postprocessor::from_json(spec).unwrap();
+        assert_eq!(postprocessor.activation, Some(Activation::Softmax));
+        assert_eq!(postprocessor.round, Some(4));
+        assert_eq!(
+            Postprocessor::from_json(&postprocessor.to_json().to_string()).unwrap(),
+            postprocessor
+        );
+
+        for invalid in [
+            "[]",
+            r#"{"scale": "2"}"#,
+            r#"{"activation": "relu"}"#,
+            r#"{"round": -1}"#,
+            r#"{"round": 11}"#,
+            r#"{"clip_min": 1, "clip_max": 0}"#,
+            r#"{"clamp": 1}"#,
+        ] {
+            assert!(
+                matches!(
+                    Postprocessor::from_json(invalid),
+                    Err(InferaError::InvalidPostprocessor(_))
+                ),
+                "{}",
+                invalid
+            );
+        }
+        assert!(matches!(
+            Postprocessor::from_json("{"),
+            Err(InferaError::JsonError(_))
+        ));
+    }
+
+    #[test]
+    fn test_postprocessor_steps() {
+        let values = [-2.0, 0.0, 0.5, 3.0];
+        assert_close(
+            &applied(r#"{"scale": 2, "offset": 1}"#, &values, 4),
+            &[-3.0, 1.0, 2.0, 7.0],
+        );
+        assert_close(
+            &applied(r#"{"activation": "sigmoid"}"#, &values, 4),
+            &values.map(|v| 1.0 / (1.0 + (-v).exp())),
+        );
+        assert_close(
+            &applied(r#"{"clip_min": 0, "clip_max": 1}"#, &values, 4),
+            &[0.0, 0.0, 0.5, 1.0],
+        );
+        assert_close(
+            &applied(r#"{"round": 1}"#, &[0.14, 0.15, -0.26, 2.0], 4),
+            &[0.1, 0.2, -0.3, 2.0],
+        );
+    }
+
+    #[test]
+    fn test_softmax_is_per_row() {
+        let out = applied(
+            r#"{"activation": "softmax"}"#,
+            &[1.0, 2.0, 1000.0, 1000.0],
+            2,
+        );
+        let e = 1f32.exp();
+        assert_close(&out, &[1.0 / (1.0 + e), e / (1.0 + e), 0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_postprocessor_composes_in_order() {
+        let values = [-4.0f32, -0.1, 0.3, 5.0];
+        let out = applied(
+            r#"{"round": 2, "clip_max": 0.9, "activation": "sigmoid", "offset": 0.5, "scale": 0.5, "clip_min": 0.2}"#,
+            &values,
+            1,
+        );
+        let expected = values.map(|v| {
+            let v = 1.0 / (1.0 + (-(v * 0.5 + 0.5)).exp());
+            (v.clamp(0.2, 0.9) * 100.0).round() / 100.0
+        });
+        assert_close(&out, &expected);
+    }
+}
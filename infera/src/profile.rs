@@ -0,0 +1,215 @@
+// Profiles the time a loaded model spends in each of its nodes.
+
+#[cfg(feature = "tract")]
+use crate::error::context;
+use crate::error::InferaError;
+#[cfg(feature = "tract")]
+use crate::model::get_model;
+#[cfg(feature = "tract")]
+use serde_json::json;
+#[cfg(feature = "tract")]
+use std::collections::HashMap;
+#[cfg(feature = "tract")]
+use std::time::{Duration, Instant};
+#[cfg(feature = "tract")]
+use tract_onnx::prelude::*;
+#[cfg(feature = "tract")]
+use tract_onnx::tract_core::plan::eval;
+
+/// Number of nodes listed in a profiling report, slowest first.
+#[cfg(feature = "tract")]
+const TOP_NODES: usize = 20;
+
+/// Seed for the random inputs a model is profiled on.
+#[cfg(feature = "tract")]
+const PROFILE_SEED: u64 = 42;
+
+/// The accumulated evaluation time of one node over all profiled runs.
+#[cfg(feature = "tract")]
+struct NodeTime {
+    id: usize,
+    total: Duration,
+}
+
+/// Returns a duration in nanoseconds, saturating at `u64::MAX`.
+#[cfg(feature = "tract")]
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// Returns `part` as a percentage of `whole`, or 0 if `whole` is zero.
+#[cfg(feature = "tract")]
+fn percent(part: Duration, whole: Duration) -> f64 {
+    if whole.is_zero() {
+        0.0
+    } else {
+        part.as_secs_f64() / whole.as_secs_f64() * 100.0
+    }
+}
+
+/// Profiles a loaded model on `rows` rows of random input over `iterations` runs and
+/// returns a JSON report.
+///
+/// Each node is timed with an evaluation hook on a separate run state, so the model's
+/// plan and normal predictions are not affected. The report has the total wall time of
+/// the runs, the part of it spent evaluating nodes, the `TOP_NODES` slowest nodes (with
+/// their op types, output shapes, and share of the wall time), and the time per op type.
+///
+/// # Returns
+///
+/// * `Ok(String)` containing the JSON report.
+/// * `Err(InferaError)` if the model is not found, the arguments are invalid, the input
+///   width cannot be determined from the model, or inference fails.
+#[cfg(feature = "tract")]
+pub(crate) fn profile_model_impl(
+    model_name: &str,
+    rows: usize,
+    iterations: usize,
+) -> Result<String, InferaError> {
+    if iterations == 0 {
+        return Err(InferaError::InvalidProfile(
+            "iterations must be greater than zero".to_string(),
+        ));
+    }
+    let model = get_model(model_name)?;
+    let input = if model.input_names.is_empty() {
+        None
+    } else {
+        if rows == 0 {
+            return Err(InferaError::InvalidProfile(
+                "rows must be greater than zero".to_string(),
+            ));
+        }
+        let inner_dims = model
+            .input_shape
+            .as_deref()
+            .and_then(|shape| shape.get(1..))
+            .filter(|dims| dims.iter().all(|&d| d > 0))
+            .ok_or_else(|| {
+                InferaError::InvalidProfile(format!(
+                    "the input width of model '{}' is not known",
+                    model_name
+                ))
+            })?;
+        let cols = inner_dims.iter().map(|&d| d as usize).product::<usize>();
+        crate::engine::check_input_size(rows, cols, std::mem::size_of::<f32>())?;
+        let data = crate::compare::random_inputs(PROFILE_SEED, rows, cols);
+        let tensor = Tensor::from_shape(&[rows, cols], &data).map_err(context(
+            InferaError::OnnxError,
+            "input_tensor",
+            model_name,
+        ))?;
+        Some(crate::engine::to_model_input(&model, tensor)?)
+    };
+
+    let mut state = SimpleState::new(&*model.model).map_err(context(
+        InferaError::OnnxError,
+        "model_run",
+        model_name,
+    ))?;
+    let mut node_times: HashMap<usize, Duration> = HashMap::new();
+    let mut wall_time = Duration::ZERO;
+    for _ in 0..iterations {
+        let inputs: TVec<TValue> = input.iter().cloned().collect();
+        let start = Instant::now();
+        state
+            .run_plan_with_eval(inputs, |session, op_state, node, node_input| {
+                let node_start = Instant::now();
+                let outputs = eval(session, op_state, node, node_input);
+                *node_times.entry(node.id).or_default() += node_start.elapsed();
+                outputs
+            })
+            .map_err(context(InferaError::OnnxError, "model_run", model_name))?;
+        wall_time += start.elapsed();
+    }
+
+    let graph = model.model.model();
+    let mut nodes: Vec<NodeTime> = node_times
+        .into_iter()
+        .map(|(id, total)| NodeTime { id, total })
+        .collect();
+    nodes.sort_by(|a, b| b.total.cmp(&a.total).then(a.id.cmp(&b.id)));
+    let profiled_time: Duration = nodes.iter().map(|n| n.total).sum();
+
+    let mut op_times: HashMap<String, (usize, Duration)> = HashMap::new();
+    for node in &nodes {
+        let op = graph.node(node.id).op().name().to_string();
+        let entry = op_times.entry(op).or_default();
+        entry.0 += 1;
+        entry.1 += node.total;
+    }
+    let mut ops: Vec<(String, usize, Duration)> = op_times
+        .into_iter()
+        .map(|(op, (count, total))| (op, count, total))
+        .collect();
+    ops.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+
+    let top_nodes: Vec<serde_json::Value> = nodes
+        .iter()
+        .take(TOP_NODES)
+        .map(|n| {
+            let node = graph.node(n.id);
+            let output_shapes: Vec<Vec<i64>> = node
+                .outputs
+                .iter()
+                .map(|o| {
+                    o.fact
+                        .shape
+                        .iter()
+                        .map(|d| d.to_i64().unwrap_or(-1))
+                        .collect()
+                })
+                .collect();
+            json!({
+                "id": n.id,
+                "name": node.name,
+                "op": node.op().name(),
+                "output_shapes": output_shapes,
+                "total_ns": nanos(n.total),
+                "mean_ns": nanos(n.total) / iterations as u64,
+                "percent": percent(n.total, wall_time),
+            })
+        })
+        .collect();
+    let op_report: Vec<serde_json::Value> = ops
+        .iter()
+        .map(|(op, count, total)| {
+            json!({
+                "op": op,
+                "nodes": count,
+                "total_ns": nanos(*total),
+                "percent": percent(*total, wall_time),
+            })
+        })
+        .collect();
+
+    let report = json!({
+        "model": model_name,
+        "rows": rows,
+        "iterations": iterations,
+        "wall_time_ns": nanos(wall_time),
+        "profiled_time_ns": nanos(profiled_time),
+        "node_count": nodes.len(),
+        "nodes": top_nodes,
+        "ops": op_report,
+    });
+    serde_json::to_string(&report).map_err(context(
+        InferaError::JsonError,
+        "json_write",
+        model_name,
+    ))
+}
+
+/// A stub for `profile_model_impl` when the "tract" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "tract"))]
+pub(crate) fn profile_model_impl(
+    _model_name: &str,
+    _rows: usize,
+    _iterations: usize,
+) -> Result<String, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "ONNX inference requires 'tract' feature to be enabled".to_string(),
+    ))
+}
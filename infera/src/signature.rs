@@ -0,0 +1,262 @@
+// Verification of detached ed25519 model signatures against trusted public keys.
+
+use crate::config::CONFIG;
+use crate::error::InferaError;
+use ed25519_dalek::pkcs8::DecodePublicKey;
+#[cfg(feature = "tract")]
+use ed25519_dalek::{Signature, SIGNATURE_LENGTH};
+use ed25519_dalek::{VerifyingKey, PUBLIC_KEY_LENGTH};
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+#[cfg(feature = "tract")]
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Public keys that model signatures are verified against.
+static TRUSTED_KEYS: Lazy<RwLock<Vec<VerifyingKey>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// The outcome of checking a model's detached signature.
+#[cfg(feature = "tract")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SignatureStatus {
+    /// The signature was made by one of the trusted keys.
+    Verified,
+    /// A signature exists but does not match the model bytes and any trusted key.
+    Invalid,
+    /// No signature file was found next to the model.
+    Missing,
+    /// Signatures are not checked (no trusted keys and signing is not required).
+    Unchecked,
+}
+
+#[cfg(feature = "tract")]
+impl SignatureStatus {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            SignatureStatus::Verified => "verified",
+            SignatureStatus::Invalid => "invalid",
+            SignatureStatus::Missing => "missing",
+            SignatureStatus::Unchecked => "unchecked",
+        }
+    }
+}
+
+/// Parses an ed25519 public key from PEM (SubjectPublicKeyInfo) or 64 hex characters.
+fn parse_public_key(key: &str) -> Result<VerifyingKey, InferaError> {
+    let key = key.trim();
+    if key.starts_with("-----BEGIN") {
+        return VerifyingKey::from_public_key_pem(key)
+            .map_err(|e| InferaError::InvalidKey(e.to_string()));
+    }
+    let bytes = hex::decode(key).map_err(|e| InferaError::InvalidKey(e.to_string()))?;
+    let bytes: [u8; PUBLIC_KEY_LENGTH] = bytes.try_into().map_err(|_| {
+        InferaError::InvalidKey(format!(
+            "expected {} bytes of hex-encoded key data",
+            PUBLIC_KEY_LENGTH
+        ))
+    })?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| InferaError::InvalidKey(e.to_string()))
+}
+
+/// Adds a public key (PEM or hex) to the set of keys trusted to sign models.
+pub(crate) fn add_trusted_key(key: &str) -> Result<(), InferaError> {
+    let key = parse_public_key(key)?;
+    let mut keys = TRUSTED_KEYS.write();
+    if !keys.contains(&key) {
+        keys.push(key);
+    }
+    Ok(())
+}
+
+/// Returns true if model signatures should be looked up and verified.
+pub(crate) fn checking_enabled() -> bool {
+    CONFIG.require_signed_models || !TRUSTED_KEYS.read().is_empty()
+}
+
+/// Returns the path of the detached signature for a model file (`<path>.sig`).
+pub(crate) fn signature_path(model_path: &Path) -> PathBuf {
+    let mut name = model_path.as_os_str().to_owned();
+    name.push(".sig");
+    PathBuf::from(name)
+}
+
+/// Parses a detached signature stored as 64 raw bytes or as 128 hex characters.
+#[cfg(feature = "tract")]
+fn parse_signature(bytes: &[u8]) -> Option<Signature> {
+    if bytes.len() == SIGNATURE_LENGTH {
+        return Signature::from_slice(bytes).ok();
+    }
+    let text = std::str::from_utf8(bytes).ok()?;
+    let decoded = hex::decode(text.trim()).ok()?;
+    Signature::from_slice(&decoded).ok()
+}
+
+/// Checks the detached signature of a model against the trusted keys and the
+/// `INFERA_REQUIRE_SIGNED_MODELS` setting.
+///
+/// `model_bytes` must be the exact bytes that will be compiled, so the verified model is
+/// the one that runs.
+#[cfg(feature = "tract")]
+pub(crate) fn verify_model(
+    model_path: &Path,
+    model_bytes: &[u8],
+) -> Result<SignatureStatus, InferaError> {
+    if !checking_enabled() {
+        return Ok(SignatureStatus::Unchecked);
+    }
+    let keys = TRUSTED_KEYS.read().clone();
+    check_signature(model_path, model_bytes, &keys, CONFIG.require_signed_models)
+}
+
+/// Checks a model that has no file a detached signature could be stored next to, such as
+/// one read from a file descriptor.
+///
+/// The model is reported as unsigned, which is an error when `INFERA_REQUIRE_SIGNED_MODELS`
+/// is set.
+#[cfg(feature = "tract")]
+pub(crate) fn verify_unsigned_model(source: &str) -> Result<SignatureStatus, InferaError> {
+    if !checking_enabled() {
+        return Ok(SignatureStatus::Unchecked);
+    }
+    if CONFIG.require_signed_models {
+        return Err(InferaError::SignatureMissing(source.to_string()));
+    }
+    Ok(SignatureStatus::Missing)
+}
+
+/// Checks a model's detached signature against `keys`.
+///
+/// When `require` is set, a missing or invalid signature is an error; otherwise the
+/// status is only reported.
+#[cfg(feature = "tract")]
+fn check_signature(
+    model_path: &Path,
+    model_bytes: &[u8],
+    keys: &[VerifyingKey],
+    require: bool,
+) -> Result<SignatureStatus, InferaError> {
+    let sig_path = signature_path(model_path);
+    let status = match fs::read(&sig_path) {
+        Err(_) => SignatureStatus::Missing,
+        Ok(sig_bytes) => match parse_signature(&sig_bytes) {
+            Some(signature)
+                if keys
+                    .iter()
+                    .any(|key| key.verify_strict(model_bytes, &signature).is_ok()) =>
+            {
+                SignatureStatus::Verified
+            }
+            _ => SignatureStatus::Invalid,
+        },
+    };
+    if require {
+        let path = model_path.display().to_string();
+        match status {
+            SignatureStatus::Missing => return Err(InferaError::SignatureMissing(path)),
+            SignatureStatus::Invalid => return Err(InferaError::SignatureInvalid(path)),
+            _ => {}
+        }
+    }
+    Ok(status)
+}
+
+#[cfg(all(test, feature = "tract"))]
+mod tests {
+    use super::*;
+    use ed25519_dalek::pkcs8::spki::der::pem::LineEnding;
+    use ed25519_dalek::pkcs8::EncodePublicKey;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn signed_model(dir: &Path, key: &SigningKey) -> (PathBuf, Vec<u8>) {
+        let model_path = dir.join("model.onnx");
+        let bytes = fs::read("../test/models/linear.onnx").unwrap();
+        fs::write(&model_path, &bytes).unwrap();
+        let signature = key.sign(&bytes);
+        fs::write(signature_path(&model_path), signature.to_bytes()).unwrap();
+        (model_path, bytes)
+    }
+
+    #[test]
+    fn test_parse_public_key_hex_and_pem() {
+        let key = SigningKey::from_bytes(&[7u8; 32]).verifying_key();
+        let hex_key = hex::encode(key.to_bytes());
+        assert_eq!(parse_public_key(&hex_key).unwrap(), key);
+
+        let pem = key.to_public_key_pem(LineEnding::LF).unwrap();
+        assert_eq!(parse_public_key(&pem).unwrap(), key);
+
+        assert!(matches!(
+            parse_public_key("abcd"),
+            Err(InferaError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            parse_public_key("not hex"),
+            Err(InferaError::InvalidKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_check_signature_valid() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = SigningKey::from_bytes(&[1u8; 32]);
+        let (model_path, bytes) = signed_model(dir.path(), &key);
+
+        let status = check_signature(&model_path, &bytes, &[key.verifying_key()], true).unwrap();
+        assert_eq!(status, SignatureStatus::Verified);
+
+        // Hex-encoded signatures are accepted too.
+        let signature = key.sign(&bytes);
+        fs::write(
+            signature_path(&model_path),
+            format!("{}\n", hex::encode(signature.to_bytes())),
+        )
+        .unwrap();
+        let status = check_signature(&model_path, &bytes, &[key.verifying_key()], true).unwrap();
+        assert_eq!(status, SignatureStatus::Verified);
+    }
+
+    #[test]
+    fn test_check_signature_tampered_or_untrusted() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = SigningKey::from_bytes(&[2u8; 32]);
+        let (model_path, mut bytes) = signed_model(dir.path(), &key);
+        let trusted = [key.verifying_key()];
+
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+        assert!(matches!(
+            check_signature(&model_path, &bytes, &trusted, true),
+            Err(InferaError::SignatureInvalid(_))
+        ));
+        assert_eq!(
+            check_signature(&model_path, &bytes, &trusted, false).unwrap(),
+            SignatureStatus::Invalid
+        );
+
+        // A valid signature from a key that is not trusted is rejected as well.
+        bytes[last] ^= 0xFF;
+        let other = SigningKey::from_bytes(&[3u8; 32]).verifying_key();
+        assert!(matches!(
+            check_signature(&model_path, &bytes, &[other], true),
+            Err(InferaError::SignatureInvalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_check_signature_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = SigningKey::from_bytes(&[4u8; 32]);
+        let (model_path, bytes) = signed_model(dir.path(), &key);
+        fs::remove_file(signature_path(&model_path)).unwrap();
+        let trusted = [key.verifying_key()];
+
+        assert!(matches!(
+            check_signature(&model_path, &bytes, &trusted, true),
+            Err(InferaError::SignatureMissing(_))
+        ));
+        assert_eq!(
+            check_signature(&model_path, &bytes, &trusted, false).unwrap(),
+            SignatureStatus::Missing
+        );
+    }
+}
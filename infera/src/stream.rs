@@ -0,0 +1,195 @@
+// Streaming prediction sessions, which run inference on a dataset one chunk at a time.
+
+use crate::config::LogLevel;
+use crate::engine::{self, InputScratch};
+use crate::error::InferaError;
+use crate::ffi_utils::InferaInferenceResult;
+use crate::log;
+use crate::model::{self, OnnxModel};
+use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Options for a streaming session, parsed from the JSON passed to `infera_stream_begin`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct StreamOptions {
+    /// The number of columns every chunk must have. `None` takes it from the first chunk.
+    pub cols: Option<usize>,
+}
+
+impl StreamOptions {
+    /// Parses stream options from a JSON object string.
+    ///
+    /// An empty string is treated as "no options".
+    pub(crate) fn from_json(json: &str) -> Result<Self, InferaError> {
+        let mut options = StreamOptions::default();
+        if json.trim().is_empty() {
+            return Ok(options);
+        }
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| InferaError::JsonError(e.to_string()))?;
+        let obj = value.as_object().ok_or_else(|| {
+            InferaError::JsonError("stream options must be a JSON object".to_string())
+        })?;
+        if let Some(v) = obj.get("cols") {
+            let cols = v
+                .as_u64()
+                .filter(|&cols| cols > 0)
+                .and_then(|cols| usize::try_from(cols).ok())
+                .ok_or_else(|| {
+                    InferaError::JsonError("\"cols\" must be a positive integer".to_string())
+                })?;
+            options.cols = Some(cols);
+        }
+        if let Some(key) = obj.keys().find(|k| k.as_str() != "cols") {
+            return Err(InferaError::JsonError(format!(
+                "unknown stream option \"{}\"",
+                key
+            )));
+        }
+        Ok(options)
+    }
+}
+
+/// A streaming session over one model.
+struct Stream {
+    /// The name the model was loaded under when the session began.
+    model_name: String,
+    /// The model the session runs. Holding it keeps it alive, so unloading or replacing
+    /// the model does not affect a session that has already begun.
+    model: Arc<OnnxModel>,
+    /// The number of columns every chunk must have, once known.
+    cols: Option<usize>,
+    /// The input tensor reused between chunks.
+    scratch: InputScratch,
+    /// Chunks run successfully.
+    chunks: u64,
+    /// Input rows run successfully.
+    rows: u64,
+    /// Time spent running chunks, in nanoseconds.
+    inference_ns: u64,
+    started: Instant,
+}
+
+impl Stream {
+    fn stats(&self) -> Value {
+        json!({
+            "model": self.model_name,
+            "cols": self.cols,
+            "chunks": self.chunks,
+            "total_rows": self.rows,
+            "inference_ns": self.inference_ns,
+            "elapsed_ns": u64::try_from(self.started.elapsed().as_nanos()).unwrap_or(u64::MAX),
+        })
+    }
+}
+
+/// Open streaming sessions by handle. Each session has its own lock, so pushes to
+/// different sessions do not contend.
+static STREAMS: Lazy<RwLock<HashMap<i64, Mutex<Stream>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// The handle of the next session. Handles start at 1 and are never reused.
+static NEXT_HANDLE: AtomicI64 = AtomicI64::new(1);
+
+/// Begins a streaming session over the model loaded as `model_name` and returns its handle.
+pub(crate) fn begin(model_name: &str, options: StreamOptions) -> Result<i64, InferaError> {
+    let model = model::get_model(model_name)?;
+    let handle = NEXT_HANDLE.fetch_add(1, Ordering::Relaxed);
+    STREAMS.write().insert(
+        handle,
+        Mutex::new(Stream {
+            model_name: model_name.to_string(),
+            model,
+            cols: options.cols,
+            scratch: InputScratch::default(),
+            chunks: 0,
+            rows: 0,
+            inference_ns: 0,
+            started: Instant::now(),
+        }),
+    );
+    log!(
+        LogLevel::Debug,
+        "Stream {} began on model '{}'",
+        handle,
+        model_name
+    );
+    Ok(handle)
+}
+
+/// Runs inference on the next chunk of a session and returns the chunk's outputs.
+///
+/// Every chunk must have the same number of columns as the first one (or as the `cols`
+/// option). A chunk that fails is not counted, and the session stays open.
+///
+/// # Safety
+///
+/// `data` must point to at least `rows * cols` contiguous `f32` values.
+pub(crate) unsafe fn push(
+    handle: i64,
+    data: *const f32,
+    rows: usize,
+    cols: usize,
+) -> Result<InferaInferenceResult, InferaError> {
+    let streams = STREAMS.read();
+    let mut guard = streams
+        .get(&handle)
+        .ok_or(InferaError::StreamNotFound(handle))?
+        .lock();
+    let stream = &mut *guard;
+    if let Some(expected) = stream.cols.filter(|&expected| expected != cols) {
+        return Err(InferaError::InvalidInputShape {
+            expected: format!("{} columns, as in the stream's other chunks", expected),
+            actual: format!("{} x {}", rows, cols),
+        });
+    }
+
+    let start = Instant::now();
+    let result =
+        engine::run_inference_reusing_impl(&stream.model, data, rows, cols, &mut stream.scratch)?;
+    stream.inference_ns = stream
+        .inference_ns
+        .saturating_add(u64::try_from(start.elapsed().as_nanos()).unwrap_or(u64::MAX));
+    stream.cols = Some(cols);
+    stream.chunks += 1;
+    stream.rows += rows as u64;
+    Ok(result)
+}
+
+/// Ends a session, releasing its model, and returns its statistics as a JSON object.
+pub(crate) fn end(handle: i64) -> Result<Value, InferaError> {
+    let stream = STREAMS
+        .write()
+        .remove(&handle)
+        .ok_or(InferaError::StreamNotFound(handle))?
+        .into_inner();
+    let stats = stream.stats();
+    log!(LogLevel::Debug, "Stream {} ended: {}", handle, stats);
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_options_from_json() {
+        assert_eq!(
+            StreamOptions::from_json("").unwrap(),
+            StreamOptions::default()
+        );
+        assert_eq!(
+            StreamOptions::from_json(r#"{"cols": 3}"#).unwrap().cols,
+            Some(3)
+        );
+        assert!(StreamOptions::from_json(r#"{"cols": 0}"#).is_err());
+        assert!(StreamOptions::from_json(r#"{"cols": "3"}"#).is_err());
+        assert!(StreamOptions::from_json(r#"{"rows": 3}"#).is_err());
+        assert!(StreamOptions::from_json("[]").is_err());
+    }
+}
@@ -0,0 +1,509 @@
+// Decodes and resizes images into input tensors for vision models.
+
+use crate::error::InferaError;
+use crate::ffi_utils::InferaInferenceResult;
+#[cfg(feature = "image")]
+use image::imageops::FilterType;
+#[cfg(feature = "image")]
+use image::DynamicImage;
+#[cfg(feature = "image")]
+use serde_json::Value;
+
+/// The order of the dimensions of an image input tensor.
+#[cfg(feature = "image")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ImageLayout {
+    /// Channels first, as a `[1, 3, height, width]` tensor.
+    Chw,
+    /// Channels last, as a `[1, height, width, 3]` tensor.
+    Hwc,
+}
+
+#[cfg(feature = "image")]
+impl ImageLayout {
+    /// Parses a layout name (`"chw"` or `"hwc"`, case-insensitive).
+    pub(crate) fn parse(layout: &str) -> Result<Self, InferaError> {
+        match layout.trim().to_ascii_lowercase().as_str() {
+            "chw" => Ok(ImageLayout::Chw),
+            "hwc" => Ok(ImageLayout::Hwc),
+            other => Err(InferaError::InvalidImageOption(format!(
+                "unknown layout '{}': expected \"chw\" or \"hwc\"",
+                other
+            ))),
+        }
+    }
+
+    /// Returns the input tensor shape for a single image of `width x height` pixels.
+    fn shape(self, width: u32, height: u32) -> Vec<usize> {
+        let (w, h) = (width as usize, height as usize);
+        match self {
+            ImageLayout::Chw => vec![1, 3, h, w],
+            ImageLayout::Hwc => vec![1, h, w, 3],
+        }
+    }
+}
+
+/// The format of the bytes passed as an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ImageFormat {
+    /// A PNG-encoded image.
+    Png,
+    /// A JPEG-encoded image.
+    Jpeg,
+    /// A WebP-encoded image.
+    Webp,
+    /// Already decoded pixels, 3 bytes (R, G, B) per pixel in row-major order.
+    RawRgb { width: u32, height: u32 },
+    /// Already decoded pixels, 4 bytes (R, G, B, A) per pixel in row-major order.
+    RawRgba { width: u32, height: u32 },
+}
+
+impl ImageFormat {
+    /// Parses a format name (`"png"`, `"jpeg"` or `"jpg"`, `"webp"`, `"rgb"`, or `"rgba"`,
+    /// case-insensitive).
+    ///
+    /// `width` and `height` give the size of raw `"rgb"` and `"rgba"` pixels and are
+    /// ignored for encoded formats, which store their own size.
+    pub(crate) fn parse(format: &str, width: u32, height: u32) -> Result<Self, InferaError> {
+        match format.trim().to_ascii_lowercase().as_str() {
+            "png" => Ok(ImageFormat::Png),
+            "jpeg" | "jpg" => Ok(ImageFormat::Jpeg),
+            "webp" => Ok(ImageFormat::Webp),
+            "rgb" => Ok(ImageFormat::RawRgb { width, height }),
+            "rgba" => Ok(ImageFormat::RawRgba { width, height }),
+            other => Err(InferaError::InvalidImageOption(format!(
+                "unknown image format '{}': expected \"png\", \"jpeg\", \"webp\", \"rgb\", or \"rgba\"",
+                other
+            ))),
+        }
+    }
+}
+
+/// Decodes image bytes in the given format, or detects the encoding when `format` is
+/// `None`.
+///
+/// Raw pixels are used as they are, so callers that already decoded an image skip a
+/// second decode. Their length must match the given size exactly.
+#[cfg(feature = "image")]
+fn decode_image(bytes: &[u8], format: Option<ImageFormat>) -> Result<DynamicImage, InferaError> {
+    let encoded = |format| {
+        image::load_from_memory_with_format(bytes, format)
+            .map_err(|e| InferaError::ImageDecodeError(e.to_string()))
+    };
+    let check_raw_size = |width: u32, height: u32, channels: usize| {
+        let expected = (width as usize)
+            .saturating_mul(height as usize)
+            .saturating_mul(channels);
+        if bytes.len() == expected {
+            Ok(bytes.to_vec())
+        } else {
+            Err(InferaError::ImageDecodeError(format!(
+                "raw pixels of {} x {} with {} channels must be {} bytes, got {}",
+                width,
+                height,
+                channels,
+                expected,
+                bytes.len()
+            )))
+        }
+    };
+    let raw_size_error = || InferaError::ImageDecodeError("invalid raw image size".to_string());
+    match format {
+        None => {
+            image::load_from_memory(bytes).map_err(|e| InferaError::ImageDecodeError(e.to_string()))
+        }
+        Some(ImageFormat::Png) => encoded(image::ImageFormat::Png),
+        Some(ImageFormat::Jpeg) => encoded(image::ImageFormat::Jpeg),
+        Some(ImageFormat::Webp) => encoded(image::ImageFormat::WebP),
+        Some(ImageFormat::RawRgb { width, height }) => {
+            image::RgbImage::from_raw(width, height, check_raw_size(width, height, 3)?)
+                .map(DynamicImage::ImageRgb8)
+                .ok_or_else(raw_size_error)
+        }
+        Some(ImageFormat::RawRgba { width, height }) => {
+            image::RgbaImage::from_raw(width, height, check_raw_size(width, height, 4)?)
+                .map(DynamicImage::ImageRgba8)
+                .ok_or_else(raw_size_error)
+        }
+    }
+}
+
+/// Per-channel (RGB) normalization applied after pixel values are scaled to `[0, 1]`.
+#[cfg(feature = "image")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Normalization {
+    /// The value subtracted from each channel.
+    pub(crate) mean: [f32; 3],
+    /// The value each channel is divided by after the mean is subtracted.
+    pub(crate) std: [f32; 3],
+}
+
+#[cfg(feature = "image")]
+impl Default for Normalization {
+    fn default() -> Self {
+        Normalization {
+            mean: [0.0; 3],
+            std: [1.0; 3],
+        }
+    }
+}
+
+/// Parses normalization parameters from a JSON object like
+/// `{"mean": [0.485, 0.456, 0.406], "std": [0.229, 0.224, 0.225]}`.
+///
+/// Both keys are optional, and an empty string leaves the scaled pixel values unchanged.
+#[cfg(feature = "image")]
+pub(crate) fn parse_normalization(json: &str) -> Result<Normalization, InferaError> {
+    let mut normalization = Normalization::default();
+    if json.trim().is_empty() {
+        return Ok(normalization);
+    }
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| InferaError::JsonError(e.to_string()))?;
+    let object = value.as_object().ok_or_else(|| {
+        InferaError::InvalidImageOption("normalization must be a JSON object".to_string())
+    })?;
+    for (key, value) in object {
+        let channels = parse_channels(key, value)?;
+        match key.as_str() {
+            "mean" => normalization.mean = channels,
+            "std" if channels.iter().all(|&s| s > 0.0) => normalization.std = channels,
+            "std" => {
+                return Err(InferaError::InvalidImageOption(
+                    "std values must be positive".to_string(),
+                ))
+            }
+            _ => {
+                return Err(InferaError::InvalidImageOption(format!(
+                    "unknown normalization key '{}': expected \"mean\" or \"std\"",
+                    key
+                )))
+            }
+        }
+    }
+    Ok(normalization)
+}
+
+/// Parses a JSON array of three finite numbers, one per RGB channel.
+#[cfg(feature = "image")]
+fn parse_channels(key: &str, value: &Value) -> Result<[f32; 3], InferaError> {
+    let invalid = || {
+        InferaError::InvalidImageOption(format!(
+            "'{}' must be an array of 3 numbers (one per RGB channel)",
+            key
+        ))
+    };
+    let items = value
+        .as_array()
+        .filter(|a| a.len() == 3)
+        .ok_or_else(invalid)?;
+    let mut channels = [0.0f32; 3];
+    for (channel, item) in channels.iter_mut().zip(items) {
+        *channel = item
+            .as_f64()
+            .map(|v| v as f32)
+            .filter(|v| v.is_finite())
+            .ok_or_else(invalid)?;
+    }
+    Ok(channels)
+}
+
+/// Decodes an image (see `decode_image`), resizes it to `width x height`, and returns its
+/// RGB pixels as normalized `f32` values in the given layout.
+///
+/// Images with an alpha channel or a single gray channel are converted to RGB first.
+/// Pixel values are scaled to `[0, 1]` and then normalized as `(value - mean) / std`.
+#[cfg(feature = "image")]
+pub(crate) fn preprocess_image(
+    bytes: &[u8],
+    format: Option<ImageFormat>,
+    width: u32,
+    height: u32,
+    layout: ImageLayout,
+    normalization: &Normalization,
+) -> Result<Vec<f32>, InferaError> {
+    if width == 0 || height == 0 {
+        return Err(InferaError::InvalidImageOption(format!(
+            "target size must be positive, got {} x {}",
+            width, height
+        )));
+    }
+    let plane = (width as usize).saturating_mul(height as usize);
+    crate::engine::check_input_size(plane, 3, std::mem::size_of::<f32>())?;
+
+    let image = decode_image(bytes, format)?;
+    let image = if (image.width(), image.height()) == (width, height) {
+        image
+    } else {
+        image.resize_exact(width, height, FilterType::Triangle)
+    };
+    let rgb = image.to_rgb8();
+
+    let mut data = vec![0.0f32; plane * 3];
+    for (i, pixel) in rgb.pixels().enumerate() {
+        for (c, &value) in pixel.0.iter().enumerate() {
+            let index = match layout {
+                ImageLayout::Chw => c * plane + i,
+                ImageLayout::Hwc => i * 3 + c,
+            };
+            data[index] = (value as f32 / 255.0 - normalization.mean[c]) / normalization.std[c];
+        }
+    }
+    Ok(data)
+}
+
+/// Decodes and resizes an image, then runs a model on it as a batch of one.
+///
+/// # Arguments
+///
+/// * `model_name` - The name of the loaded model.
+/// * `bytes` - The image bytes, encoded or raw pixels as given by `format`.
+/// * `format` - The format of `bytes`, or `None` to detect an encoded image's format.
+/// * `width` - The width the image is resized to.
+/// * `height` - The height the image is resized to.
+/// * `layout` - `"chw"` or `"hwc"`, the dimension order the model expects.
+/// * `normalization` - Optional normalization parameters as JSON (see `parse_normalization`).
+///
+/// # Returns
+///
+/// * `Ok(InferaInferenceResult)` containing the model output.
+/// * `Err(InferaError)` if the image or an option is invalid, the model is not found, or
+///   inference fails.
+#[cfg(feature = "image")]
+pub(crate) fn predict_image_impl(
+    model_name: &str,
+    bytes: &[u8],
+    format: Option<ImageFormat>,
+    width: u32,
+    height: u32,
+    layout: &str,
+    normalization: &str,
+) -> Result<InferaInferenceResult, InferaError> {
+    crate::engine::check_input_bytes(bytes.len())?;
+    let layout = ImageLayout::parse(layout)?;
+    let normalization = parse_normalization(normalization)?;
+    let data = preprocess_image(bytes, format, width, height, layout, &normalization)?;
+    crate::engine::run_shaped_impl(model_name, &layout.shape(width, height), &data)
+}
+
+/// A stub for `predict_image_impl` when the "image" feature is disabled.
+///
+/// Always returns an `InferaError::FeatureNotEnabled` error.
+#[cfg(not(feature = "image"))]
+pub(crate) fn predict_image_impl(
+    _model_name: &str,
+    _bytes: &[u8],
+    _format: Option<ImageFormat>,
+    _width: u32,
+    _height: u32,
+    _layout: &str,
+    _normalization: &str,
+) -> Result<InferaInferenceResult, InferaError> {
+    Err(InferaError::FeatureNotEnabled(
+        "image inputs require 'image' feature to be enabled".to_string(),
+    ))
+}
+
+#[cfg(all(test, feature = "image"))]
+mod tests {
+    use super::*;
+
+    /// A 2x2 RGB PNG with red, green, blue, and white pixels (in row-major order).
+    const QUAD_PNG: [u8; 75] = [
+        0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44,
+        0x52, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x02, 0x08, 0x02, 0x00, 0x00, 0x00, 0xfd,
+        0xd4, 0x9a, 0x73, 0x00, 0x00, 0x00, 0x12, 0x49, 0x44, 0x41, 0x54, 0x78, 0xda, 0x63, 0xf8,
+        0xcf, 0xc0, 0xc0, 0x00, 0xc2, 0x0c, 0xff, 0x81, 0x00, 0x00, 0x1f, 0xee, 0x05, 0xfb, 0xf1,
+        0xab, 0xba, 0x77, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+    ];
+
+    /// A 2x2 RGB PNG filled with the color (255, 51, 0).
+    const SOLID_PNG: [u8; 73] = [
+        0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44,
+        0x52, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x02, 0x08, 0x02, 0x00, 0x00, 0x00, 0xfd,
+        0xd4, 0x9a, 0x73, 0x00, 0x00, 0x00, 0x10, 0x49, 0x44, 0x41, 0x54, 0x78, 0xda, 0x63, 0xf8,
+        0x6f, 0xcc, 0x00, 0x44, 0x0c, 0x10, 0x0a, 0x00, 0x25, 0x82, 0x04, 0xc9, 0xa1, 0x1c, 0xd5,
+        0x77, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+    ];
+
+    #[test]
+    fn test_parse_layout_and_normalization() {
+        assert_eq!(ImageLayout::parse("CHW").unwrap(), ImageLayout::Chw);
+        assert_eq!(ImageLayout::parse(" hwc ").unwrap(), ImageLayout::Hwc);
+        assert!(ImageLayout::parse("nchw").is_err());
+
+        assert_eq!(parse_normalization("").unwrap(), Normalization::default());
+        let normalization = parse_normalization(r#"{"mean": [0.5, 0.5, 0.5]}"#).unwrap();
+        assert_eq!(normalization.mean, [0.5; 3]);
+        assert_eq!(normalization.std, [1.0; 3]);
+        assert!(parse_normalization(r#"{"std": [1, 0, 1]}"#).is_err());
+        assert!(parse_normalization(r#"{"mean": [0.5]}"#).is_err());
+        assert!(parse_normalization(r#"{"scale": [1, 1, 1]}"#).is_err());
+    }
+
+    #[test]
+    fn test_preprocess_image_layouts() {
+        let none = Normalization::default();
+        let chw = preprocess_image(&QUAD_PNG, None, 2, 2, ImageLayout::Chw, &none).unwrap();
+        assert_eq!(
+            chw,
+            vec![
+                1.0, 0.0, 0.0, 1.0, // red plane
+                0.0, 1.0, 0.0, 1.0, // green plane
+                0.0, 0.0, 1.0, 1.0, // blue plane
+            ]
+        );
+        let hwc = preprocess_image(&QUAD_PNG, None, 2, 2, ImageLayout::Hwc, &none).unwrap();
+        assert_eq!(
+            hwc,
+            vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0]
+        );
+
+        let normalization = Normalization {
+            mean: [0.5; 3],
+            std: [0.5; 3],
+        };
+        let normalized =
+            preprocess_image(&QUAD_PNG, None, 2, 2, ImageLayout::Hwc, &normalization).unwrap();
+        assert_eq!(&normalized[..3], &[1.0, -1.0, -1.0]);
+    }
+
+    #[test]
+    fn test_preprocess_image_resizes_and_rejects_bad_input() {
+        let none = Normalization::default();
+        let resized = preprocess_image(&SOLID_PNG, None, 5, 3, ImageLayout::Hwc, &none).unwrap();
+        assert_eq!(resized.len(), 5 * 3 * 3);
+        for pixel in resized.chunks_exact(3) {
+            assert_eq!(pixel, &[1.0, 0.2, 0.0]);
+        }
+
+        assert!(matches!(
+            preprocess_image(b"not an image", None, 2, 2, ImageLayout::Chw, &none),
+            Err(InferaError::ImageDecodeError(_))
+        ));
+        assert!(matches!(
+            preprocess_image(&SOLID_PNG, None, 0, 2, ImageLayout::Chw, &none),
+            Err(InferaError::InvalidImageOption(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_image_format() {
+        assert_eq!(ImageFormat::parse("PNG", 0, 0).unwrap(), ImageFormat::Png);
+        assert_eq!(ImageFormat::parse("jpg", 0, 0).unwrap(), ImageFormat::Jpeg);
+        assert_eq!(
+            ImageFormat::parse(" webp ", 0, 0).unwrap(),
+            ImageFormat::Webp
+        );
+        assert_eq!(
+            ImageFormat::parse("rgb", 4, 2).unwrap(),
+            ImageFormat::RawRgb {
+                width: 4,
+                height: 2
+            }
+        );
+        assert_eq!(
+            ImageFormat::parse("RGBA", 4, 2).unwrap(),
+            ImageFormat::RawRgba {
+                width: 4,
+                height: 2
+            }
+        );
+        assert!(matches!(
+            ImageFormat::parse("bmp", 0, 0),
+            Err(InferaError::InvalidImageOption(_))
+        ));
+    }
+
+    #[test]
+    fn test_raw_pixels_match_decoded_image() {
+        let none = Normalization::default();
+        // The pixels of QUAD_PNG: red, green, blue, and white
+        let rgb = [255, 0, 0, 0, 255, 0, 0, 0, 255, 255, 255, 255];
+        let rgba = [
+            255, 0, 0, 255, 0, 255, 0, 255, 0, 0, 255, 255, 255, 255, 255, 255,
+        ];
+        let raw_rgb = Some(ImageFormat::RawRgb {
+            width: 2,
+            height: 2,
+        });
+        let raw_rgba = Some(ImageFormat::RawRgba {
+            width: 2,
+            height: 2,
+        });
+        for layout in [ImageLayout::Chw, ImageLayout::Hwc] {
+            let decoded = preprocess_image(&QUAD_PNG, None, 2, 2, layout, &none).unwrap();
+            let png = preprocess_image(&QUAD_PNG, Some(ImageFormat::Png), 2, 2, layout, &none);
+            assert_eq!(png.unwrap(), decoded);
+            let from_rgb = preprocess_image(&rgb, raw_rgb, 2, 2, layout, &none).unwrap();
+            assert_eq!(from_rgb, decoded);
+            let from_rgba = preprocess_image(&rgba, raw_rgba, 2, 2, layout, &none).unwrap();
+            assert_eq!(from_rgba, decoded);
+        }
+
+        // Raw pixels are resized like decoded ones
+        let resized = preprocess_image(&rgb, raw_rgb, 4, 4, ImageLayout::Hwc, &none).unwrap();
+        let decoded = preprocess_image(&QUAD_PNG, None, 4, 4, ImageLayout::Hwc, &none).unwrap();
+        assert_eq!(resized, decoded);
+    }
+
+    #[test]
+    fn test_decode_image_rejects_mismatched_input() {
+        let rgb = [0u8; 11];
+        let error = decode_image(
+            &rgb,
+            Some(ImageFormat::RawRgb {
+                width: 2,
+                height: 2,
+            }),
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("must be 12 bytes, got 11"));
+        assert!(matches!(
+            decode_image(&QUAD_PNG, Some(ImageFormat::Jpeg)),
+            Err(InferaError::ImageDecodeError(_))
+        ));
+        assert!(matches!(
+            decode_image(&QUAD_PNG, Some(ImageFormat::Webp)),
+            Err(InferaError::ImageDecodeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_predict_image_runs_model() {
+        let name = "vision_image_4d";
+        crate::engine::load_model_impl(name, "../test/models/image_4d.onnx").unwrap();
+
+        // The model returns the mean of each channel of an [N, 3, 8, 8] input.
+        let result = predict_image_impl(name, &SOLID_PNG, None, 8, 8, "chw", "").unwrap();
+        let output = unsafe { std::slice::from_raw_parts(result.data, result.len) };
+        assert_eq!((result.rows, result.cols), (1, 3));
+        for (actual, expected) in output.iter().zip([1.0, 0.2, 0.0]) {
+            assert!((actual - expected).abs() < 1e-6);
+        }
+        unsafe { crate::ffi_utils::infera_free_result(result) };
+
+        let normalized = predict_image_impl(
+            name,
+            &SOLID_PNG,
+            None,
+            8,
+            8,
+            "chw",
+            r#"{"mean": [1, 0, 0]}"#,
+        )
+        .unwrap();
+        assert!(unsafe { *normalized.data }.abs() < 1e-6);
+        unsafe { crate::ffi_utils::infera_free_result(normalized) };
+
+        assert!(matches!(
+            predict_image_impl(name, &SOLID_PNG, None, 8, 8, "hwc", ""),
+            Err(InferaError::InvalidInputShape { .. })
+        ));
+        assert!(matches!(
+            predict_image_impl(name, &SOLID_PNG, None, 4, 4, "chw", ""),
+            Err(InferaError::InvalidInputShape { .. })
+        ));
+        crate::model::MODELS.write().remove(name);
+    }
+}
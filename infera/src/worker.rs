@@ -0,0 +1,548 @@
+// A bounded, priority-ordered worker pool for asynchronous predictions submitted with
+// `infera_submit`.
+
+use crate::canary;
+use crate::config::{LogLevel, CONFIG};
+use crate::engine;
+use crate::error::{self, InferaError};
+use crate::ffi_utils::InferaInferenceResult;
+use crate::log;
+use once_cell::sync::Lazy;
+use parking_lot::{Condvar, Mutex};
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The callback invoked on a worker thread when a submitted prediction finishes.
+///
+/// The callback owns `result` and must free it with `infera_free_result`. On failure,
+/// `result.status` is a negative `InferaStatus` code and `infera_last_error()` (called
+/// from within the callback) describes the error.
+pub type InferaPredictCallback =
+    extern "C" fn(request_id: u64, result: InferaInferenceResult, user_data: *mut c_void);
+
+/// Upper bounds (in milliseconds) of the queue wait-time histogram buckets.
+const WAIT_BUCKETS_MS: [u64; 8] = [1, 5, 10, 50, 100, 500, 1000, 5000];
+
+/// Priority classes for queued predictions, highest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Priority {
+    High = 0,
+    Normal = 1,
+    Low = 2,
+}
+
+impl Priority {
+    const ALL: [Priority; 3] = [Priority::High, Priority::Normal, Priority::Low];
+
+    /// Parses the C API priority value: `0` (high), `1` (normal), or `2` (low).
+    pub(crate) fn from_i32(value: i32) -> Result<Self, InferaError> {
+        match value {
+            0 => Ok(Priority::High),
+            1 => Ok(Priority::Normal),
+            2 => Ok(Priority::Low),
+            _ => Err(InferaError::InvalidPriority(value)),
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Priority::High => "high",
+            Priority::Normal => "normal",
+            Priority::Low => "low",
+        }
+    }
+}
+
+/// A prediction request as submitted by the caller.
+pub(crate) struct Request {
+    pub model_name: String,
+    pub data: Vec<f32>,
+    pub rows: usize,
+    pub cols: usize,
+    pub callback: InferaPredictCallback,
+    /// The caller's opaque pointer, passed back untouched to the callback.
+    pub user_data: *mut c_void,
+}
+
+// SAFETY: Infera never dereferences `user_data`; the caller is responsible for making
+// whatever it points to safe to use from the worker thread that runs the callback.
+unsafe impl Send for Request {}
+
+impl Request {
+    /// Runs the prediction and hands the result to the callback.
+    fn run(self, id: u64) {
+        let result = match canary::predict(&self.model_name, |name| {
+            engine::run_inference_impl(name, self.data.as_ptr(), self.rows, self.cols)
+        }) {
+            Ok(res) => res,
+            Err(e) => {
+                error::set_last_error(&e);
+                InferaInferenceResult::from_error(&e)
+            }
+        };
+        (self.callback)(id, result, self.user_data);
+    }
+}
+
+/// A request waiting in one of the priority queues.
+struct Job {
+    id: u64,
+    enqueued: Instant,
+    request: Request,
+}
+
+/// A histogram of how long jobs waited in a queue before running.
+#[derive(Default)]
+struct WaitHistogram {
+    /// Counts per bucket in `WAIT_BUCKETS_MS`, plus a final overflow bucket.
+    counts: [u64; WAIT_BUCKETS_MS.len() + 1],
+    sum_ms: f64,
+}
+
+impl WaitHistogram {
+    fn record(&mut self, wait: Duration) {
+        let ms = wait.as_secs_f64() * 1000.0;
+        let bucket = WAIT_BUCKETS_MS
+            .iter()
+            .position(|&le| ms <= le as f64)
+            .unwrap_or(WAIT_BUCKETS_MS.len());
+        self.counts[bucket] += 1;
+        self.sum_ms += ms;
+    }
+
+    /// Returns the histogram as JSON with cumulative bucket counts.
+    fn to_json(&self) -> Value {
+        let mut cumulative = 0;
+        let buckets: Vec<Value> = WAIT_BUCKETS_MS
+            .iter()
+            .map(|le| le.to_string())
+            .chain(std::iter::once("+Inf".to_string()))
+            .zip(self.counts.iter())
+            .map(|(le, &count)| {
+                cumulative += count;
+                json!({"le": le, "count": cumulative})
+            })
+            .collect();
+        json!({
+            "buckets": buckets,
+            "count": cumulative,
+            "sum_ms": self.sum_ms,
+        })
+    }
+}
+
+/// The queued jobs and wait-time statistics for each priority class.
+#[derive(Default)]
+struct QueueState {
+    queues: [VecDeque<Job>; 3],
+    waits: [WaitHistogram; 3],
+    closed: bool,
+}
+
+impl QueueState {
+    fn pending(&self) -> usize {
+        self.queues.iter().map(VecDeque::len).sum()
+    }
+
+    /// Takes the next job to run.
+    ///
+    /// Normally this is the oldest job of the highest non-empty priority. To keep lower
+    /// priorities from starving, a normal or low priority job that has waited at least
+    /// `max_wait` goes first (the oldest such job if there are several).
+    fn next(&mut self, max_wait: Duration) -> Option<Job> {
+        let now = Instant::now();
+        let starved = (Priority::Normal as usize..self.queues.len())
+            .filter_map(|i| self.queues[i].front().map(|job| (i, job.enqueued)))
+            .filter(|(_, enqueued)| now.duration_since(*enqueued) >= max_wait)
+            .min_by_key(|(_, enqueued)| *enqueued)
+            .map(|(i, _)| i);
+        let index = starved.or_else(|| self.queues.iter().position(|q| !q.is_empty()))?;
+        let job = self.queues[index].pop_front()?;
+        self.waits[index].record(now.duration_since(job.enqueued));
+        Some(job)
+    }
+
+    /// Removes a queued job by ID, returning it if it has not started running yet.
+    fn remove(&mut self, id: u64) -> Option<Job> {
+        self.queues.iter_mut().find_map(|queue| {
+            let position = queue.iter().position(|job| job.id == id)?;
+            queue.remove(position)
+        })
+    }
+
+    /// Adds a job to a priority queue, keeping each queue ordered by submission time.
+    fn insert(&mut self, priority: Priority, job: Job) {
+        let queue = &mut self.queues[priority as usize];
+        let position = queue.partition_point(|queued| queued.enqueued <= job.enqueued);
+        queue.insert(position, job);
+    }
+}
+
+/// State shared between a pool and its worker threads.
+#[derive(Default)]
+struct Shared {
+    state: Mutex<QueueState>,
+    available: Condvar,
+}
+
+/// A fixed set of worker threads fed by bounded, priority-ordered queues.
+pub(crate) struct WorkerPool {
+    shared: Arc<Shared>,
+    max_pending: usize,
+    next_id: AtomicU64,
+}
+
+impl WorkerPool {
+    /// Starts `threads` workers that share queues holding at most `max_pending` requests
+    /// in total. Normal and low priority requests that wait at least `max_wait` are run
+    /// ahead of higher priorities.
+    pub(crate) fn new(threads: usize, max_pending: usize, max_wait: Duration) -> Self {
+        let shared = Arc::new(Shared::default());
+        for i in 0..threads.max(1) {
+            let shared = Arc::clone(&shared);
+            let spawned = thread::Builder::new()
+                .name(format!("infera-worker-{}", i))
+                .spawn(move || worker_loop(&shared, max_wait));
+            if let Err(e) = spawned {
+                log!(LogLevel::Error, "Failed to spawn worker thread: {}", e);
+            }
+        }
+        Self {
+            shared,
+            max_pending: max_pending.max(1),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Queues a prediction and returns its request ID.
+    ///
+    /// Fails immediately with `InferaError::QueueFull` if the queues are saturated.
+    pub(crate) fn submit(&self, priority: Priority, request: Request) -> Result<u64, InferaError> {
+        let mut state = self.shared.state.lock();
+        if state.pending() >= self.max_pending {
+            return Err(InferaError::QueueFull(self.max_pending));
+        }
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        state.queues[priority as usize].push_back(Job {
+            id,
+            enqueued: Instant::now(),
+            request,
+        });
+        drop(state);
+        self.shared.available.notify_one();
+        Ok(id)
+    }
+
+    /// Removes a queued request so that it never runs (and its callback is never called).
+    pub(crate) fn cancel(&self, id: u64) -> Result<(), InferaError> {
+        self.shared
+            .state
+            .lock()
+            .remove(id)
+            .map(|_| ())
+            .ok_or(InferaError::RequestNotFound(id))
+    }
+
+    /// Moves a queued request to another priority class, keeping its submission time.
+    pub(crate) fn set_priority(&self, id: u64, priority: Priority) -> Result<(), InferaError> {
+        let mut state = self.shared.state.lock();
+        let job = state.remove(id).ok_or(InferaError::RequestNotFound(id))?;
+        state.insert(priority, job);
+        Ok(())
+    }
+
+    /// Returns the queue depth and wait-time histogram for each priority class.
+    pub(crate) fn metrics(&self) -> Value {
+        let state = self.shared.state.lock();
+        let mut metrics = serde_json::Map::new();
+        for priority in Priority::ALL {
+            let i = priority as usize;
+            metrics.insert(
+                priority.as_str().to_string(),
+                json!({
+                    "depth": state.queues[i].len(),
+                    "wait_ms": state.waits[i].to_json(),
+                }),
+            );
+        }
+        Value::Object(metrics)
+    }
+}
+
+impl Drop for WorkerPool {
+    /// Lets the workers finish the queued jobs and then exit.
+    fn drop(&mut self) {
+        self.shared.state.lock().closed = true;
+        self.shared.available.notify_all();
+    }
+}
+
+/// Takes jobs off the shared queues and runs them until the pool is dropped.
+fn worker_loop(shared: &Shared, max_wait: Duration) {
+    loop {
+        let job = {
+            let mut state = shared.state.lock();
+            loop {
+                if let Some(job) = state.next(max_wait) {
+                    break job;
+                }
+                if state.closed {
+                    return;
+                }
+                shared.available.wait(&mut state);
+            }
+        };
+        job.request.run(job.id);
+    }
+}
+
+/// The global pool behind `infera_submit`, sized by `INFERA_WORKER_THREADS`, bounded by
+/// `INFERA_MAX_PENDING`, and aging waiting requests after `INFERA_QUEUE_AGING_MS`.
+static POOL: Lazy<WorkerPool> = Lazy::new(|| {
+    WorkerPool::new(
+        CONFIG.worker_threads,
+        CONFIG.max_pending,
+        Duration::from_millis(CONFIG.queue_aging_ms),
+    )
+});
+
+/// Queues a prediction on the global worker pool and returns its request ID.
+pub(crate) fn submit(priority: Priority, request: Request) -> Result<u64, InferaError> {
+    POOL.submit(priority, request)
+}
+
+/// Cancels a request queued on the global worker pool.
+pub(crate) fn cancel(id: u64) -> Result<(), InferaError> {
+    POOL.cancel(id)
+}
+
+/// Changes the priority of a request queued on the global worker pool.
+pub(crate) fn set_priority(id: u64, priority: Priority) -> Result<(), InferaError> {
+    POOL.set_priority(id, priority)
+}
+
+/// Returns the per-priority queue metrics of the global worker pool.
+pub(crate) fn metrics() -> Value {
+    POOL.metrics()
+}
+
+#[cfg(all(test, feature = "tract"))]
+mod tests {
+    use super::*;
+    use crate::ffi_utils::infera_free_result;
+
+    const MODEL_NAME: &str = "worker_dynamic_batch";
+
+    /// Shared state between a test and its callbacks, passed through `user_data`.
+    ///
+    /// Each request holds a reference to the state, so it outlives callbacks that are still
+    /// returning when the test ends. Dropping a pool does not wait for its workers.
+    #[derive(Default)]
+    struct TestState {
+        /// The request ID, status, and output of each finished prediction, in order.
+        done: Mutex<Vec<(u64, i32, Vec<f32>)>>,
+        done_changed: Condvar,
+        /// Whether callbacks may return; until then they block their worker thread.
+        released: Mutex<bool>,
+        released_changed: Condvar,
+    }
+
+    impl TestState {
+        fn release(&self) {
+            *self.released.lock() = true;
+            self.released_changed.notify_all();
+        }
+
+        fn wait_for_done(&self, count: usize) {
+            let mut done = self.done.lock();
+            while done.len() < count {
+                let timed_out = self
+                    .done_changed
+                    .wait_for(&mut done, Duration::from_secs(10))
+                    .timed_out();
+                assert!(
+                    !timed_out,
+                    "only {} of {} callbacks fired",
+                    done.len(),
+                    count
+                );
+            }
+        }
+
+        fn done_ids(&self) -> Vec<u64> {
+            self.done.lock().iter().map(|(id, _, _)| *id).collect()
+        }
+    }
+
+    extern "C" fn record_result(
+        request_id: u64,
+        result: InferaInferenceResult,
+        user_data: *mut c_void,
+    ) {
+        let state = unsafe { Arc::from_raw(user_data as *const TestState) };
+        let values = if result.status == 0 {
+            unsafe { std::slice::from_raw_parts(result.data, result.len) }.to_vec()
+        } else {
+            Vec::new()
+        };
+        let status = result.status;
+        unsafe { infera_free_result(result) };
+
+        state.done.lock().push((request_id, status, values));
+        state.done_changed.notify_all();
+        let mut released = state.released.lock();
+        while !*released {
+            state.released_changed.wait(&mut released);
+        }
+    }
+
+    fn load_model() {
+        engine::load_model_impl(MODEL_NAME, "../test/models/dynamic_batch.onnx").unwrap();
+    }
+
+    fn submit(
+        pool: &WorkerPool,
+        state: &Arc<TestState>,
+        priority: Priority,
+        x: f32,
+    ) -> Result<u64, InferaError> {
+        let request = Request {
+            model_name: MODEL_NAME.to_string(),
+            data: vec![x, 0.0, 0.0],
+            rows: 1,
+            cols: 3,
+            callback: record_result,
+            user_data: Arc::into_raw(Arc::clone(state)) as *mut c_void,
+        };
+        // A request that never runs keeps its reference, which only leaks the state
+        pool.submit(priority, request)
+    }
+
+    /// Submits a request that occupies the pool's single worker until `state` is released.
+    fn block_worker(pool: &WorkerPool, state: &Arc<TestState>) -> u64 {
+        let id = submit(pool, state, Priority::Normal, 0.0).unwrap();
+        state.wait_for_done(1);
+        id
+    }
+
+    #[test]
+    fn test_submit_applies_backpressure_and_runs_accepted_requests() {
+        load_model();
+        let state = Arc::new(TestState::default());
+        let pool = WorkerPool::new(1, 2, Duration::from_secs(60));
+
+        let mut accepted = vec![(block_worker(&pool, &state), 0.0)];
+
+        // Two more requests fill the queue, and the next one is rejected.
+        for x in [2.0, 3.0] {
+            accepted.push((submit(&pool, &state, Priority::Normal, x).unwrap(), x));
+        }
+        assert!(matches!(
+            submit(&pool, &state, Priority::High, 4.0),
+            Err(InferaError::QueueFull(2))
+        ));
+
+        state.release();
+        state.wait_for_done(accepted.len());
+
+        // The dynamic batch model computes y = 2*x1 - x2 + 0.5*x3 + 0.25
+        let done = state.done.lock();
+        for (id, x) in accepted {
+            let (_, status, values) = done.iter().find(|(done_id, _, _)| *done_id == id).unwrap();
+            assert_eq!(*status, 0);
+            assert_eq!(values, &vec![2.0 * x + 0.25]);
+        }
+    }
+
+    #[test]
+    fn test_high_priority_requests_run_first() {
+        load_model();
+        let state = Arc::new(TestState::default());
+        let pool = WorkerPool::new(1, 16, Duration::from_secs(60));
+        let blocker = block_worker(&pool, &state);
+
+        let low_a = submit(&pool, &state, Priority::Low, 1.0).unwrap();
+        let high_a = submit(&pool, &state, Priority::High, 2.0).unwrap();
+        let normal = submit(&pool, &state, Priority::Normal, 3.0).unwrap();
+        let high_b = submit(&pool, &state, Priority::High, 4.0).unwrap();
+        let low_b = submit(&pool, &state, Priority::Low, 5.0).unwrap();
+
+        let metrics = pool.metrics();
+        assert_eq!(metrics["high"]["depth"], 2);
+        assert_eq!(metrics["normal"]["depth"], 1);
+        assert_eq!(metrics["low"]["depth"], 2);
+
+        state.release();
+        state.wait_for_done(6);
+        assert_eq!(
+            state.done_ids(),
+            vec![blocker, high_a, high_b, normal, low_a, low_b]
+        );
+
+        let metrics = pool.metrics();
+        assert_eq!(metrics["low"]["depth"], 0);
+        assert_eq!(metrics["low"]["wait_ms"]["count"], 2);
+        assert_eq!(metrics["high"]["wait_ms"]["count"], 2);
+        assert_eq!(metrics["normal"]["wait_ms"]["count"], 2);
+    }
+
+    #[test]
+    fn test_starved_low_priority_requests_age_ahead() {
+        load_model();
+        let state = Arc::new(TestState::default());
+        let pool = WorkerPool::new(1, 16, Duration::from_millis(50));
+        let blocker = block_worker(&pool, &state);
+
+        let low = submit(&pool, &state, Priority::Low, 1.0).unwrap();
+        thread::sleep(Duration::from_millis(100));
+        let high = submit(&pool, &state, Priority::High, 2.0).unwrap();
+
+        state.release();
+        state.wait_for_done(3);
+        assert_eq!(state.done_ids(), vec![blocker, low, high]);
+    }
+
+    #[test]
+    fn test_cancel_and_reprioritize_queued_requests() {
+        load_model();
+        let state = Arc::new(TestState::default());
+        let pool = WorkerPool::new(1, 16, Duration::from_secs(60));
+        let blocker = block_worker(&pool, &state);
+
+        let cancelled = submit(&pool, &state, Priority::Normal, 1.0).unwrap();
+        let normal = submit(&pool, &state, Priority::Normal, 2.0).unwrap();
+        let promoted = submit(&pool, &state, Priority::Low, 3.0).unwrap();
+
+        pool.cancel(cancelled).unwrap();
+        pool.set_priority(promoted, Priority::High).unwrap();
+        assert!(matches!(
+            pool.cancel(cancelled),
+            Err(InferaError::RequestNotFound(id)) if id == cancelled
+        ));
+        // A running request can no longer be changed.
+        assert!(matches!(
+            pool.set_priority(blocker, Priority::High),
+            Err(InferaError::RequestNotFound(_))
+        ));
+
+        state.release();
+        state.wait_for_done(3);
+        assert_eq!(state.done_ids(), vec![blocker, promoted, normal]);
+    }
+
+    #[test]
+    fn test_priority_from_i32() {
+        assert_eq!(Priority::from_i32(0).unwrap(), Priority::High);
+        assert_eq!(Priority::from_i32(1).unwrap(), Priority::Normal);
+        assert_eq!(Priority::from_i32(2).unwrap(), Priority::Low);
+        assert!(matches!(
+            Priority::from_i32(3),
+            Err(InferaError::InvalidPriority(3))
+        ));
+    }
+}